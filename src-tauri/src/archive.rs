@@ -0,0 +1,226 @@
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use tokio::time::{interval, Duration};
+use rusqlite::Connection;
+use tauri::{AppHandle, Manager, State};
+use crate::i18n;
+use crate::player::AudioPlayer;
+
+// 归档策略保存在 app_settings 中
+const POLICY_SETTING_KEY: &str = "recording_archive_policy";
+// 录音导入音频库时的来源类型
+pub const RECORDING_SOURCE_TYPE: &str = "recording";
+// 归档目录（音频目录下的子目录）
+const ARCHIVE_DIR: &str = "archive";
+// 自动归档的检查间隔（秒）
+const ARCHIVE_CHECK_SECS: u64 = 6 * 60 * 60;
+
+/// 录音归档策略：超过 months 个月的录音移到归档目录，compress 为 true 时 WAV 转为 MP3（需要 FFmpeg）
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct ArchivePolicy {
+    pub enabled: bool,
+    pub months: u32,
+    pub compress: bool,
+}
+
+impl Default for ArchivePolicy {
+    fn default() -> Self {
+        Self { enabled: false, months: 6, compress: true }
+    }
+}
+
+/// 归档结果
+#[derive(Debug, Default, Serialize)]
+pub struct ArchiveResult {
+    pub archived: usize,
+    pub compressed: usize,
+    /// 正在播放、文件丢失或移动失败而跳过的录音数
+    pub skipped: usize,
+    /// 本次节省的磁盘空间（字节）
+    pub saved_bytes: i64,
+}
+
+pub fn load_policy(conn: &Connection) -> ArchivePolicy {
+    conn.query_row(
+        "SELECT value FROM app_settings WHERE key = ?1",
+        [POLICY_SETTING_KEY],
+        |row| row.get::<_, String>(0),
+    )
+    .ok()
+    .and_then(|value| serde_json::from_str(&value).ok())
+    .unwrap_or_default()
+}
+
+struct ArchiveCandidate {
+    id: i64,
+    filename: String,
+    file_path: String,
+    file_size: i64,
+    format: String,
+}
+
+/// 查询超过保留期、尚未归档的录音
+fn load_candidates(conn: &Connection, months: u32) -> rusqlite::Result<Vec<ArchiveCandidate>> {
+    conn.prepare(
+        "SELECT id, filename, file_path, file_size, format FROM audio_files
+         WHERE source_type = ?1 AND archived_at IS NULL AND is_managed = 1
+           AND upload_date < datetime('now', ?2)
+         ORDER BY id",
+    )?
+    .query_map((RECORDING_SOURCE_TYPE, format!("-{} months", months)), |row| {
+        Ok(ArchiveCandidate {
+            id: row.get(0)?,
+            filename: row.get(1)?,
+            file_path: row.get(2)?,
+            file_size: row.get(3)?,
+            format: row.get(4)?,
+        })
+    })?
+    .collect()
+}
+
+/// 把一个录音移到归档目录（可选转为 MP3），返回新的文件名、路径和格式
+fn archive_file(
+    candidate: &ArchiveCandidate,
+    archive_dir: &Path,
+    ffmpeg_path: Option<&PathBuf>,
+) -> Result<(String, PathBuf, String), String> {
+    let src = Path::new(&candidate.file_path);
+    match ffmpeg_path.filter(|_| candidate.format.eq_ignore_ascii_case("wav")) {
+        Some(ffmpeg_path) => {
+            let filename = Path::new(&candidate.filename).with_extension("mp3").to_string_lossy().to_string();
+            let dest = archive_dir.join(&filename);
+            crate::audio::run_ffmpeg_extract(ffmpeg_path, &candidate.file_path, &dest, false)?;
+            std::fs::remove_file(src).map_err(|e| e.to_string())?;
+            Ok((filename, dest, "mp3".to_string()))
+        }
+        None => {
+            let dest = archive_dir.join(&candidate.filename);
+            std::fs::rename(src, &dest).map_err(|e| e.to_string())?;
+            Ok((candidate.filename.clone(), dest, candidate.format.clone()))
+        }
+    }
+}
+
+/// 按策略归档旧录音。数据库记录保留（路径指向归档目录，仍可播放），并标记归档时间
+pub async fn archive_old_recordings(
+    app: &AppHandle,
+    db: &Arc<Mutex<Connection>>,
+    player: &Arc<Mutex<AudioPlayer>>,
+    audio_dir: &Path,
+    policy: ArchivePolicy,
+) -> Result<ArchiveResult, String> {
+    let candidates = load_candidates(&*db.lock().await, policy.months).map_err(|e| e.to_string())?;
+    let mut result = ArchiveResult::default();
+    if candidates.is_empty() {
+        return Ok(result);
+    }
+
+    let archive_dir = audio_dir.join(ARCHIVE_DIR);
+    std::fs::create_dir_all(&archive_dir).map_err(|e| i18n::trf("创建归档目录失败: {}", &[&e]))?;
+    let ffmpeg_path = match policy.compress {
+        true => crate::audio::get_ffmpeg_executable_path(Some(app)).await,
+        false => None,
+    };
+
+    for candidate in candidates {
+        let in_use = {
+            let player = player.lock().await;
+            player.current_audio_id() == Some(candidate.id) || player.is_queued(candidate.id)
+        };
+        if in_use || !Path::new(&candidate.file_path).exists() {
+            result.skipped += 1;
+            continue;
+        }
+
+        let (filename, dest, format) = match archive_file(&candidate, &archive_dir, ffmpeg_path.as_ref()) {
+            Ok(archived) => archived,
+            Err(e) => {
+                eprintln!("[Archive] 归档录音失败 {}: {}", candidate.id, e);
+                result.skipped += 1;
+                continue;
+            }
+        };
+        let file_size = std::fs::metadata(&dest).map(|m| m.len() as i64).unwrap_or(candidate.file_size);
+
+        db.lock()
+            .await
+            .execute(
+                "UPDATE audio_files
+                 SET filename = ?1, file_path = ?2, file_size = ?3, format = ?4, archived_at = datetime('now')
+                 WHERE id = ?5",
+                (&filename, dest.to_string_lossy().to_string(), file_size, &format, candidate.id),
+            )
+            .map_err(|e| e.to_string())?;
+
+        result.archived += 1;
+        if format != candidate.format {
+            result.compressed += 1;
+        }
+        result.saved_bytes += (candidate.file_size - file_size).max(0);
+    }
+
+    Ok(result)
+}
+
+/// 启动自动归档：开启策略时定期归档超过保留期的录音
+pub fn start_archive_monitor(app: AppHandle, db: Arc<Mutex<Connection>>, player: Arc<Mutex<AudioPlayer>>, audio_dir: PathBuf) {
+    tauri::async_runtime::spawn(async move {
+        let mut ticker = interval(Duration::from_secs(ARCHIVE_CHECK_SECS));
+
+        loop {
+            ticker.tick().await;
+
+            let policy = load_policy(&*db.lock().await);
+            if !policy.enabled {
+                continue;
+            }
+            match archive_old_recordings(&app, &db, &player, &audio_dir, policy).await {
+                Ok(result) if result.archived > 0 => {
+                    println!("[Archive] 已归档 {} 个录音，节省 {} 字节", result.archived, result.saved_bytes);
+                }
+                Ok(_) => {}
+                Err(e) => eprintln!("[Archive] 自动归档失败: {}", e),
+            }
+        }
+    });
+}
+
+#[tauri::command]
+pub async fn get_archive_policy(
+    conn: State<'_, Arc<Mutex<Connection>>>,
+) -> Result<ArchivePolicy, String> {
+    Ok(load_policy(&*conn.lock().await))
+}
+
+#[tauri::command]
+pub async fn set_archive_policy(
+    policy: ArchivePolicy,
+    conn: State<'_, Arc<Mutex<Connection>>>,
+) -> Result<(), String> {
+    if policy.months == 0 {
+        return Err(i18n::tr("保留月数必须大于0"));
+    }
+
+    let value = serde_json::to_string(&policy).map_err(|e| e.to_string())?;
+    conn.lock()
+        .await
+        .execute(
+            "INSERT OR REPLACE INTO app_settings (key, value) VALUES (?1, ?2)",
+            (POLICY_SETTING_KEY, &value),
+        )
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// 立即按当前策略的保留期归档旧录音（不要求开启自动归档）
+#[tauri::command]
+pub async fn archive_recordings_now(app: AppHandle) -> Result<ArchiveResult, String> {
+    let db = app.state::<Arc<Mutex<Connection>>>().inner().clone();
+    let player = app.state::<Arc<Mutex<AudioPlayer>>>().inner().clone();
+    let audio_dir = app.state::<PathBuf>().inner().clone();
+    let policy = load_policy(&*db.lock().await);
+    archive_old_recordings(&app, &db, &player, &audio_dir, policy).await
+}
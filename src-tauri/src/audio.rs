@@ -1,7 +1,9 @@
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::future::Future;
 use std::path::PathBuf;
 use std::sync::Arc;
-use tokio::sync::Mutex;
+use tokio::sync::{watch, Mutex};
 use rusqlite::Connection;
 use tauri::{State, AppHandle, Manager};
 use anyhow::Result;
@@ -9,7 +11,6 @@ use std::fs;
 use std::io::BufReader;
 use std::process::Command;
 use std::fs::File;
-use std::io::Write;
 use zip::ZipArchive;
 use dirs::home_dir;
 use rodio::{Decoder, Source};
@@ -18,14 +19,40 @@ use symphonia::core::probe::Hint;
 use symphonia::core::meta::MetadataOptions;
 use symphonia::core::formats::FormatOptions;
 use encoding_rs;
+use crate::probe::DurationProbeQueue;
+use crate::library_events::LibraryEvents;
+use crate::formats;
+use crate::player::AudioPlayer;
 
 #[cfg(target_os = "windows")]
 use std::os::windows::process::CommandExt;
+use crate::i18n;
+use crate::storage;
 
 // Windows平台的CREATE_NO_WINDOW标志
 #[cfg(target_os = "windows")]
 const CREATE_NO_WINDOW: u32 = 0x08000000;
 
+// yt-dlp 官方发布地址
+const YTDLP_DOWNLOAD_BASE: &str = "https://github.com/yt-dlp/yt-dlp/releases/latest/download";
+const YTDLP_CHECKSUM_URL: &str = "https://github.com/yt-dlp/yt-dlp/releases/latest/download/SHA2-256SUMS";
+
+#[cfg(target_os = "windows")]
+const YTDLP_RELEASE_ASSET: &str = "yt-dlp.exe";
+#[cfg(target_os = "macos")]
+const YTDLP_RELEASE_ASSET: &str = "yt-dlp_macos";
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+const YTDLP_RELEASE_ASSET: &str = "yt-dlp_linux";
+
+// 一键安装后yt-dlp在tools目录中的文件名
+#[cfg(target_os = "windows")]
+const YTDLP_FILE_NAME: &str = "yt-dlp.exe";
+#[cfg(not(target_os = "windows"))]
+const YTDLP_FILE_NAME: &str = "yt-dlp";
+
+// 提取时响度归一化使用的 FFmpeg 滤镜（EBU R128，目标 -16 LUFS，适合人声素材）
+const LOUDNORM_FILTER: &str = "loudnorm=I=-16:TP=-1.5:LRA=11";
+
 /// 创建一个隐藏窗口的Command
 fn create_command(program: &str) -> Command {
     let mut cmd = Command::new(program);
@@ -39,7 +66,7 @@ fn create_command(program: &str) -> Command {
 }
 
 /// 创建一个隐藏窗口的Command (PathBuf版本)
-fn create_command_from_path(program: &PathBuf) -> Command {
+pub(crate) fn create_command_from_path(program: &PathBuf) -> Command {
     let mut cmd = Command::new(program);
 
     #[cfg(target_os = "windows")]
@@ -57,6 +84,9 @@ pub struct FFmpegStatus {
     pub path: Option<String>,
 }
 
+// 无法读取时长时使用的默认值（秒）
+pub(crate) const FALLBACK_DURATION_SECS: i64 = 180;
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct AudioFile {
     pub id: i64,
@@ -64,15 +94,33 @@ pub struct AudioFile {
     pub original_name: String,
     pub file_path: String,
     pub file_size: i64,
-    pub duration: i64,
+    /// 时长（秒），为空表示尚未检测或无法读取
+    pub duration: Option<i64>,
     pub format: String,
     pub upload_date: String,
     pub play_count: i64,
     pub last_played: Option<String>,
+    /// 是否为复制到应用目录的文件（false 表示引用外部文件）
+    pub is_managed: bool,
+    /// 来源类型：video（本地视频提取）、online（在线视频提取）、transcoded（导入时转码）、recording（录音），其他为空
+    pub source_type: Option<String>,
+    /// 来源地址：本地视频路径或在线视频URL
+    pub source_url: Option<String>,
+    /// 归档时间，不为空表示已移到归档目录
+    pub archived_at: Option<String>,
+    /// 记住的播放倍速，为空表示使用手动选择的倍速
+    pub preferred_speed: Option<f32>,
+    /// 标记为已学会的时间，为空表示未学会
+    pub learned_at: Option<String>,
+}
+
+/// 获取音频文件的真实时长（秒），读取失败时返回默认值
+pub(crate) fn get_audio_duration(file_path: &std::path::Path) -> i64 {
+    probe_audio_duration(file_path).unwrap_or(FALLBACK_DURATION_SECS)
 }
 
-/// 获取音频文件的真实时长（秒）
-fn get_audio_duration(file_path: &std::path::Path) -> i64 {
+/// 使用 symphonia / rodio 读取音频时长（秒），无法读取时返回 None
+pub(crate) fn probe_audio_duration(file_path: &std::path::Path) -> Option<i64> {
     // 使用 symphonia 获取准确的音频时长
     match fs::File::open(file_path) {
         Ok(file) => {
@@ -99,7 +147,7 @@ fn get_audio_duration(file_path: &std::path::Path) -> i64 {
                                 // 使用时间基数和帧数计算准确时长
                                 let duration_secs = (n_frames as f64 * timebase.numer as f64)
                                     / timebase.denom as f64;
-                                return duration_secs.ceil() as i64;
+                                return Some(duration_secs.ceil() as i64);
                             }
                         }
 
@@ -107,7 +155,7 @@ fn get_audio_duration(file_path: &std::path::Path) -> i64 {
                         if let Some(sample_rate) = track.codec_params.sample_rate {
                             if let Some(n_frames) = track.codec_params.n_frames {
                                 let duration_secs = n_frames as f64 / sample_rate as f64;
-                                return duration_secs.ceil() as i64;
+                                return Some(duration_secs.ceil() as i64);
                             }
                         }
                     }
@@ -116,123 +164,212 @@ fn get_audio_duration(file_path: &std::path::Path) -> i64 {
                     if let Ok(file) = fs::File::open(file_path) {
                         if let Ok(source) = Decoder::new(BufReader::new(file)) {
                             if let Some(duration) = source.total_duration() {
-                                return duration.as_secs() as i64;
+                                return Some(duration.as_secs() as i64);
                             }
                         }
                     }
 
-                    180 // 默认值
+                    None
                 }
                 Err(_) => {
                     // symphonia 失败，尝试使用 rodio 作为备选
                     if let Ok(file) = fs::File::open(file_path) {
                         if let Ok(source) = Decoder::new(BufReader::new(file)) {
                             if let Some(duration) = source.total_duration() {
-                                return duration.as_secs() as i64;
+                                return Some(duration.as_secs() as i64);
                             }
                         }
                     }
-                    180 // 默认值
+                    None
                 }
             }
         }
-        Err(_) => 180 // 文件打开失败，返回默认值
+        Err(_) => None // 文件打开失败
     }
 }
 
 #[tauri::command]
 pub async fn upload_audio_file(
     file_path: String,
+    app: AppHandle,
     conn: State<'_, Arc<Mutex<Connection>>>,
     audio_dir: State<'_, PathBuf>,
+    probe_queue: State<'_, DurationProbeQueue>,
 ) -> Result<i64, String> {
     let src_path = PathBuf::from(&file_path);
 
     if !src_path.exists() {
-        return Err("文件不存在".to_string());
+        return Err(i18n::tr("文件不存在"));
     }
 
     let original_name = src_path
         .file_name()
         .and_then(|n| n.to_str())
-        .ok_or("无效的文件名")?
+        .ok_or_else(|| i18n::tr("无效的文件名"))?
         .to_string();
 
     let extension = src_path
         .extension()
         .and_then(|e| e.to_str())
-        .ok_or("无法获取文件扩展名")?
+        .ok_or_else(|| i18n::tr("无法获取文件扩展名"))?
         .to_lowercase();
 
-    // 验证音频格式
-    if !["mp3", "wav", "ogg", "flac", "m4a"].contains(&extension.as_str()) {
-        return Err("不支持的音频格式".to_string());
+    // 验证音频格式，播放器无法直接解码的文件导入时转码为 MP3
+    let (auto_transcode, layout) = {
+        let conn = conn.lock().await;
+        (crate::settings::auto_transcode_enabled(&conn), storage::load_layout(&conn))
+    };
+    let transcode = formats::check_import(&src_path, auto_transcode)?;
+    let stored_extension = if transcode { "mp3" } else { extension.as_str() };
+
+    // 按存储布局生成文件名和路径
+    let (filename, dest_path) = storage::managed_file_path(
+        &layout,
+        &audio_dir,
+        &original_name,
+        stored_extension,
+        transcode.then_some("transcoded"),
+    )?;
+
+    if transcode {
+        let ffmpeg_path = get_ffmpeg_executable_path(Some(&app)).await
+            .ok_or_else(|| i18n::tr("播放器无法直接解码该文件，需要FFmpeg转码，请先安装FFmpeg"))?;
+        run_ffmpeg_extract(&ffmpeg_path, &file_path, &dest_path, false)?;
+    } else {
+        // 复制文件
+        std::fs::copy(&src_path, &dest_path).map_err(|e| e.to_string())?;
     }
 
     // 获取文件大小
-    let metadata = std::fs::metadata(&src_path).map_err(|e| e.to_string())?;
+    let metadata = std::fs::metadata(&dest_path).map_err(|e| e.to_string())?;
     let file_size = metadata.len() as i64;
 
-    // 生成唯一文件名
-    let filename = format!(
-        "{}_{}.{}",
-        chrono::Local::now().format("%Y%m%d_%H%M%S"),
-        uuid::Uuid::new_v4().to_string().split('-').next().unwrap(),
-        extension
-    );
-
-    let dest_path = audio_dir.join(&filename);
-
-    // 复制文件
-    std::fs::copy(&src_path, &dest_path).map_err(|e| e.to_string())?;
-
-    // 获取音频真实时长
-    let duration = get_audio_duration(&dest_path);
-
-    // 保存到数据库
+    // 保存到数据库（时长由后台检测队列补全），转码的文件记录原文件作为来源
+    let (source_type, source_url) = if transcode {
+        (Some("transcoded"), Some(file_path.as_str()))
+    } else {
+        (None, None)
+    };
     let conn = conn.lock().await;
     conn.execute(
-        "INSERT INTO audio_files (filename, original_name, file_path, file_size, duration, format)
-         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        "INSERT INTO audio_files (filename, original_name, file_path, file_size, duration, format, source_type, source_url)
+         VALUES (?1, ?2, ?3, ?4, NULL, ?5, ?6, ?7)",
         (
             &filename,
             &original_name,
             dest_path.to_str().unwrap(),
             file_size,
-            duration,
-            &extension,
+            stored_extension,
+            source_type,
+            source_url,
         ),
     )
     .map_err(|e| e.to_string())?;
 
     let id = conn.last_insert_rowid();
+    probe_queue.wake();
+    app.state::<LibraryEvents>().added([id]);
     Ok(id)
 }
 
+/// 音频库查询过滤条件（均为可选，组合时取交集）
+#[derive(Debug, Default, Deserialize)]
+pub struct AudioFileFilter {
+    pub min_duration: Option<i64>,
+    pub max_duration: Option<i64>,
+    pub min_play_count: Option<i64>,
+    pub max_play_count: Option<i64>,
+    /// 最后播放时间早于该时间（从未播放过的也包含在内）
+    pub last_played_before: Option<String>,
+    /// 最后播放时间晚于该时间
+    pub last_played_after: Option<String>,
+    pub formats: Option<Vec<String>>,
+}
+
+const AUDIO_FILE_COLUMNS: &str =
+    "id, filename, original_name, file_path, file_size, duration, format, upload_date, play_count, last_played, is_managed, source_type, source_url, archived_at, preferred_speed, learned_at";
+
+fn audio_file_from_row(row: &rusqlite::Row) -> rusqlite::Result<AudioFile> {
+    Ok(AudioFile {
+        id: row.get(0)?,
+        filename: row.get(1)?,
+        original_name: row.get(2)?,
+        file_path: row.get(3)?,
+        file_size: row.get(4)?,
+        duration: row.get(5)?,
+        format: row.get(6)?,
+        upload_date: row.get(7)?,
+        play_count: row.get(8)?,
+        last_played: row.get(9)?,
+        is_managed: row.get(10)?,
+        source_type: row.get(11)?,
+        source_url: row.get(12)?,
+        archived_at: row.get(13)?,
+        preferred_speed: row.get(14)?,
+        learned_at: row.get(15)?,
+    })
+}
+
 #[tauri::command]
 pub async fn get_audio_files(
+    filter: Option<AudioFileFilter>,
     conn: State<'_, Arc<Mutex<Connection>>>,
 ) -> Result<Vec<AudioFile>, String> {
+    let filter = filter.unwrap_or_default();
+    let mut conditions: Vec<String> = Vec::new();
+    let mut params: Vec<rusqlite::types::Value> = Vec::new();
+
+    if let Some(min) = filter.min_duration {
+        params.push(min.into());
+        conditions.push(format!("duration >= ?{}", params.len()));
+    }
+    if let Some(max) = filter.max_duration {
+        params.push(max.into());
+        conditions.push(format!("duration <= ?{}", params.len()));
+    }
+    if let Some(min) = filter.min_play_count {
+        params.push(min.into());
+        conditions.push(format!("play_count >= ?{}", params.len()));
+    }
+    if let Some(max) = filter.max_play_count {
+        params.push(max.into());
+        conditions.push(format!("play_count <= ?{}", params.len()));
+    }
+    if let Some(before) = filter.last_played_before {
+        params.push(before.into());
+        conditions.push(format!("(last_played IS NULL OR last_played < ?{})", params.len()));
+    }
+    if let Some(after) = filter.last_played_after {
+        params.push(after.into());
+        conditions.push(format!("last_played > ?{}", params.len()));
+    }
+    if let Some(formats) = filter.formats.filter(|f| !f.is_empty()) {
+        let placeholders: Vec<String> = formats
+            .into_iter()
+            .map(|format| {
+                params.push(format.trim_start_matches('.').to_lowercase().into());
+                format!("?{}", params.len())
+            })
+            .collect();
+        conditions.push(format!("LOWER(format) IN ({})", placeholders.join(", ")));
+    }
+
+    let where_clause = if conditions.is_empty() {
+        String::new()
+    } else {
+        format!(" WHERE {}", conditions.join(" AND "))
+    };
+
     let conn = conn.lock().await;
     let mut stmt = conn
-        .prepare("SELECT id, filename, original_name, file_path, file_size, duration, format, upload_date, play_count, last_played FROM audio_files ORDER BY id DESC")
+        .prepare(&format!(
+            "SELECT {} FROM audio_files{} ORDER BY id DESC",
+            AUDIO_FILE_COLUMNS, where_clause
+        ))
         .map_err(|e| e.to_string())?;
 
     let files = stmt
-        .query_map([], |row| {
-            Ok(AudioFile {
-                id: row.get(0)?,
-                filename: row.get(1)?,
-                original_name: row.get(2)?,
-                file_path: row.get(3)?,
-                file_size: row.get(4)?,
-                duration: row.get(5)?,
-                format: row.get(6)?,
-                upload_date: row.get(7)?,
-                play_count: row.get(8)?,
-                last_played: row.get(9)?,
-            })
-        })
+        .query_map(rusqlite::params_from_iter(params), audio_file_from_row)
         .map_err(|e| e.to_string())?
         .collect::<std::result::Result<Vec<_>, _>>()
         .map_err(|e| e.to_string())?;
@@ -240,50 +377,360 @@ pub async fn get_audio_files(
     Ok(files)
 }
 
+/// 音频的使用情况：正在播放、在播放队列中、被启用的定时任务引用
+#[derive(Debug, Serialize)]
+pub struct AudioUsage {
+    pub playing: bool,
+    pub queued: bool,
+    /// 引用该音频（通过播放列表或星期映射）的已启用任务名称
+    pub tasks: Vec<String>,
+}
+
+impl AudioUsage {
+    fn in_use(&self) -> bool {
+        self.playing || self.queued || !self.tasks.is_empty()
+    }
+
+    fn describe(&self) -> String {
+        let mut reasons = Vec::new();
+        if self.playing {
+            reasons.push(i18n::tr("正在播放"));
+        }
+        if self.queued {
+            reasons.push(i18n::tr("在播放队列中"));
+        }
+        if !self.tasks.is_empty() {
+            reasons.push(i18n::trf("被定时任务引用：{}", &[&self.tasks.join("、")]));
+        }
+        reasons.join("；")
+    }
+}
+
+/// 查询通过播放列表引用该音频的已启用任务（包括星期映射中的播放列表）
+fn find_referencing_tasks(conn: &Connection, audio_id: i64) -> rusqlite::Result<Vec<String>> {
+    let playlist_ids: Vec<i64> = conn
+        .prepare(
+            "SELECT DISTINCT pi.playlist_id
+             FROM playlist_items pi
+             JOIN playlists p ON pi.playlist_id = p.id
+             WHERE pi.audio_id = ?1 AND p.deleted_at IS NULL",
+        )?
+        .query_map([audio_id], |row| row.get(0))?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    if playlist_ids.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let tasks: Vec<(String, i64, Option<String>)> = conn
+        .prepare(
+            "SELECT name, playlist_id, weekday_playlists FROM scheduled_tasks
+             WHERE is_enabled = 1 AND deleted_at IS NULL
+             ORDER BY hour, minute",
+        )?
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    Ok(tasks
+        .into_iter()
+        .filter(|(_, playlist_id, weekday_playlists)| {
+            playlist_ids.contains(playlist_id)
+                || weekday_playlists
+                    .as_deref()
+                    .and_then(crate::weekday::parse_weekday_playlists)
+                    .is_some_and(|mapping| mapping.values().any(|id| playlist_ids.contains(id)))
+        })
+        .map(|(name, _, _)| name)
+        .collect())
+}
+
+fn audio_usage(player: &AudioPlayer, conn: &Connection, audio_id: i64) -> Result<AudioUsage, String> {
+    Ok(AudioUsage {
+        playing: player.current_audio_id() == Some(audio_id),
+        queued: player.is_queued(audio_id),
+        tasks: find_referencing_tasks(conn, audio_id).map_err(|e| e.to_string())?,
+    })
+}
+
+/// 查询音频是否正在使用，删除前用于提示
+#[tauri::command]
+pub async fn get_audio_usage(
+    id: i64,
+    player: State<'_, Arc<Mutex<AudioPlayer>>>,
+    conn: State<'_, Arc<Mutex<Connection>>>,
+) -> Result<AudioUsage, String> {
+    let player = player.lock().await;
+    let conn = conn.lock().await;
+    audio_usage(&player, &conn, id)
+}
+
+// 详情中返回的最近播放记录条数
+const DETAIL_HISTORY_LIMIT: i64 = 20;
+
+/// 包含该音频的播放列表
+#[derive(Debug, Serialize)]
+pub struct AudioPlaylistRef {
+    pub id: i64,
+    pub name: String,
+}
+
+/// 音频的一条播放记录
+#[derive(Debug, Serialize)]
+pub struct AudioPlayRecord {
+    pub play_time: String,
+    pub playlist_name: Option<String>,
+}
+
+/// 单个音频的详情：文件信息以及播放列表、标记、最近播放等关联数据
+#[derive(Debug, Serialize)]
+pub struct AudioDetail {
+    pub file: AudioFile,
+    pub file_exists: bool,
+    pub playlists: Vec<AudioPlaylistRef>,
+    pub markers: Vec<crate::recorder::AudioMarker>,
+    /// 最近的播放记录（最新的在前）
+    pub recent_history: Vec<AudioPlayRecord>,
+    pub has_lyrics: bool,
+    /// 已预渲染的倍速版本
+    pub speed_variants: Vec<f64>,
+    pub usage: AudioUsage,
+}
+
+fn load_audio_detail(player: &AudioPlayer, conn: &Connection, id: i64) -> Result<AudioDetail, String> {
+    let file = conn
+        .query_row(
+            &format!("SELECT {} FROM audio_files WHERE id = ?1", AUDIO_FILE_COLUMNS),
+            [id],
+            audio_file_from_row,
+        )
+        .map_err(|_| i18n::tr("音频不存在"))?;
+
+    let playlists = conn
+        .prepare(
+            "SELECT DISTINCT p.id, p.name FROM playlist_items pi
+             JOIN playlists p ON pi.playlist_id = p.id
+             WHERE pi.audio_id = ?1 AND p.deleted_at IS NULL
+             ORDER BY p.id",
+        )
+        .and_then(|mut stmt| {
+            stmt.query_map([id], |row| Ok(AudioPlaylistRef { id: row.get(0)?, name: row.get(1)? }))?
+                .collect::<rusqlite::Result<Vec<_>>>()
+        })
+        .map_err(|e| e.to_string())?;
+
+    let recent_history = conn
+        .prepare(
+            "SELECT datetime(play_time, 'localtime'), playlist_name FROM playback_history
+             WHERE audio_id = ?1 ORDER BY play_time DESC, id DESC LIMIT ?2",
+        )
+        .and_then(|mut stmt| {
+            stmt.query_map((id, DETAIL_HISTORY_LIMIT), |row| {
+                Ok(AudioPlayRecord { play_time: row.get(0)?, playlist_name: row.get(1)? })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()
+        })
+        .map_err(|e| e.to_string())?;
+
+    let speed_variants = conn
+        .prepare("SELECT speed FROM audio_speed_variants WHERE audio_id = ?1 ORDER BY speed")
+        .and_then(|mut stmt| stmt.query_map([id], |row| row.get(0))?.collect::<rusqlite::Result<Vec<f64>>>())
+        .map_err(|e| e.to_string())?;
+
+    let has_lyrics: bool = conn
+        .query_row("SELECT EXISTS(SELECT 1 FROM audio_lyrics WHERE audio_id = ?1)", [id], |row| row.get(0))
+        .map_err(|e| e.to_string())?;
+
+    Ok(AudioDetail {
+        file_exists: std::path::Path::new(&file.file_path).exists(),
+        markers: crate::recorder::load_audio_markers(conn, id).map_err(|e| e.to_string())?,
+        usage: audio_usage(player, conn, id)?,
+        file,
+        playlists,
+        recent_history,
+        has_lyrics,
+        speed_variants,
+    })
+}
+
+/// 一次性获取单个音频的详情，供详情页使用
+#[tauri::command]
+pub async fn get_audio_detail(
+    id: i64,
+    player: State<'_, Arc<Mutex<AudioPlayer>>>,
+    conn: State<'_, Arc<Mutex<Connection>>>,
+) -> Result<AudioDetail, String> {
+    let player = player.lock().await;
+    let conn = conn.lock().await;
+    load_audio_detail(&player, &conn, id)
+}
+
+/// 删除音频。音频正在播放、在队列中或被已启用的任务引用时拒绝删除，
+/// force 为 true 时强制删除：停止播放、移出队列并从播放列表中移除
 #[tauri::command]
 pub async fn delete_audio_file(
     id: i64,
     delete_physical_file: bool,
+    delete_external_file: Option<bool>,
+    force: Option<bool>,
+    player: State<'_, Arc<Mutex<AudioPlayer>>>,
     conn: State<'_, Arc<Mutex<Connection>>>,
+    library: State<'_, LibraryEvents>,
 ) -> Result<(), String> {
+    let mut player = player.lock().await;
     let conn = conn.lock().await;
 
+    let usage = audio_usage(&player, &conn, id)?;
+    if usage.in_use() {
+        if !force.unwrap_or(false) {
+            return Err(i18n::trf("音频正在使用中（{}），如需删除请确认强制删除", &[&usage.describe()]));
+        }
+        if usage.playing {
+            player.stop();
+        } else if usage.queued {
+            player.remove_from_queue(id);
+        }
+    }
+    drop(player);
+
     // 获取文件路径
-    let file_path: String = conn
+    let (file_path, is_managed): (String, bool) = conn
         .query_row(
-            "SELECT file_path FROM audio_files WHERE id = ?1",
+            "SELECT file_path, is_managed FROM audio_files WHERE id = ?1",
             [id],
-            |row| row.get(0),
+            |row| Ok((row.get(0)?, row.get(1)?)),
         )
         .map_err(|e| e.to_string())?;
 
-    // 根据用户选择决定是否删除物理文件
-    if delete_physical_file {
+    // 根据用户选择决定是否删除物理文件；引用的外部文件需要显式确认才会删除
+    if delete_physical_file && !is_managed && !delete_external_file.unwrap_or(false) {
+        println!("保留外部文件，仅从音频库移除: {}", file_path);
+    } else if delete_physical_file {
         if let Err(e) = std::fs::remove_file(&file_path) {
             eprintln!("删除物理文件失败: {}", e);
             // 注意：即使物理删除失败，仍然从数据库中删除记录
         }
     }
 
+    // 删除预渲染的倍速版本
+    crate::variant::remove_variants(&conn, id)?;
+
+    // 删除关联的歌词
+    conn.execute("DELETE FROM audio_lyrics WHERE audio_id = ?1", [id])
+        .map_err(|e| e.to_string())?;
+
+    // 删除关联的跟读评分
+    conn.execute(
+        "DELETE FROM pronunciation_scores WHERE reference_id = ?1 OR recording_id = ?1",
+        [id],
+    )
+    .map_err(|e| e.to_string())?;
+
+    // 从播放列表中移除，删除录音标记
+    conn.execute("DELETE FROM playlist_items WHERE audio_id = ?1", [id])
+        .map_err(|e| e.to_string())?;
+    conn.execute("DELETE FROM audio_markers WHERE audio_id = ?1", [id])
+        .map_err(|e| e.to_string())?;
+    conn.execute("DELETE FROM playback_positions WHERE audio_id = ?1", [id])
+        .map_err(|e| e.to_string())?;
+
     // 从数据库删除
     conn.execute("DELETE FROM audio_files WHERE id = ?1", [id])
         .map_err(|e| e.to_string())?;
 
+    library.removed([id]);
     Ok(())
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Default, Serialize)]
 pub struct ScanResult {
     pub found_files: i32,
     pub added_files: i32,
     pub skipped_files: i32,
     pub error_files: i32,
+    /// 按文件夹新建的播放列表数
+    pub playlists_created: i32,
+}
+
+/// 收集目录中的文件，recursive 时包含子目录（跳过隐藏目录）
+fn collect_scan_files(dir: &std::path::Path, recursive: bool, files: &mut Vec<PathBuf>, error_files: &mut i32) -> std::io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = match entry {
+            Ok(entry) => entry.path(),
+            Err(_) => {
+                *error_files += 1;
+                continue;
+            }
+        };
+
+        if path.is_file() {
+            files.push(path);
+        } else if recursive && path.is_dir() {
+            let hidden = path.file_name().and_then(|n| n.to_str()).is_some_and(|n| n.starts_with('.'));
+            if !hidden && collect_scan_files(&path, true, files, error_files).is_err() {
+                *error_files += 1;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// 为每个包含音频的子文件夹创建（或复用同名的）播放列表，按文件名顺序加入尚未在其中的音频，返回新建的播放列表数
+fn create_folder_playlists(conn: &Connection, root: &std::path::Path, scanned: &[(i64, PathBuf)]) -> rusqlite::Result<i32> {
+    let mut folders: std::collections::BTreeMap<String, Vec<(i64, &PathBuf)>> = std::collections::BTreeMap::new();
+    for (audio_id, path) in scanned {
+        let Some(relative) = path.parent().and_then(|parent| parent.strip_prefix(root).ok()) else { continue };
+        let name = relative
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+        if !name.is_empty() {
+            folders.entry(name).or_default().push((*audio_id, path));
+        }
+    }
+
+    let mut created = 0;
+    for (name, mut items) in folders {
+        items.sort_by(|a, b| a.1.cmp(b.1));
+
+        let existing: Option<i64> = conn
+            .query_row(
+                "SELECT id FROM playlists WHERE name = ?1 AND deleted_at IS NULL ORDER BY id LIMIT 1",
+                [&name],
+                |row| row.get(0),
+            )
+            .ok();
+        let playlist_id = match existing {
+            Some(id) => id,
+            None => {
+                conn.execute("INSERT INTO playlists (name) VALUES (?1)", [&name])?;
+                created += 1;
+                conn.last_insert_rowid()
+            }
+        };
+
+        for (audio_id, _) in items {
+            let present: bool = conn.query_row(
+                "SELECT EXISTS(SELECT 1 FROM playlist_items WHERE playlist_id = ?1 AND audio_id = ?2)",
+                (playlist_id, audio_id),
+                |row| row.get(0),
+            )?;
+            if !present {
+                crate::playlist::append_playlist_item(conn, playlist_id, audio_id)?;
+            }
+        }
+    }
+    Ok(created)
 }
 
 #[tauri::command]
 pub async fn scan_audio_directory(
+    copy_into_library: Option<bool>,
+    recursive: Option<bool>,
+    folder_playlists: Option<bool>,
+    app: AppHandle,
     conn: State<'_, Arc<Mutex<Connection>>>,
     audio_dir: State<'_, PathBuf>,
+    probe_queue: State<'_, DurationProbeQueue>,
 ) -> Result<ScanResult, String> {
     // 从数据库读取用户配置的音频路径
     let scan_path = {
@@ -306,46 +753,75 @@ pub async fn scan_audio_directory(
         }
     };
 
+    import_directory(
+        &scan_path,
+        &scan_path,
+        ScanOptions {
+            copy_into_library: copy_into_library.unwrap_or(false),
+            recursive: recursive.unwrap_or(false),
+            folder_playlists: folder_playlists.unwrap_or(false),
+        },
+        &app,
+        &conn,
+        &audio_dir,
+        &probe_queue,
+    )
+    .await
+}
+
+/// 导入目录的选项
+pub(crate) struct ScanOptions {
+    /// 复制进应用的音频目录（否则原地引用）
+    pub copy_into_library: bool,
+    /// 包含子文件夹
+    pub recursive: bool,
+    /// 按文件夹层级（相对 playlist_root）生成播放列表
+    pub folder_playlists: bool,
+}
+
+/// 把目录中支持的音频导入音频库（扫描音频目录和导入U盘共用）
+pub(crate) async fn import_directory(
+    scan_path: &std::path::Path,
+    playlist_root: &std::path::Path,
+    options: ScanOptions,
+    app: &AppHandle,
+    conn: &Mutex<Connection>,
+    audio_dir: &std::path::Path,
+    probe_queue: &DurationProbeQueue,
+) -> Result<ScanResult, String> {
     if !scan_path.exists() {
-        return Err(format!("音频目录不存在: {}", scan_path.display()));
+        return Err(i18n::trf("音频目录不存在: {}", &[&scan_path.display()]));
     }
 
+    // 扫描应用自身的音频目录时，文件本身就在库中；否则按选项复制进库或原地引用
+    let scanning_library_dir = scan_path.canonicalize().ok() == audio_dir.canonicalize().ok();
+    let copy_into_library = options.copy_into_library && !scanning_library_dir;
+
+    // 需要转码的格式用到FFmpeg，未安装时这些文件计为失败
+    let ffmpeg_path = get_ffmpeg_executable_path(Some(app)).await;
+
     let mut found_files = 0;
     let mut added_files = 0;
     let mut skipped_files = 0;
     let mut error_files = 0;
 
-    // 支持的音频格式
-    let supported_formats = ["mp3", "wav", "ogg", "flac", "m4a"];
-
-    // 读取目录中的所有文件
-    let entries = match fs::read_dir(&scan_path) {
-        Ok(entries) => entries,
-        Err(e) => return Err(format!("读取目录失败: {}", e)),
-    };
+    // 读取目录中的所有文件（应用自身的音频目录由应用管理，不递归）
+    let recursive = options.recursive && !scanning_library_dir;
+    let mut files = Vec::new();
+    collect_scan_files(scan_path, recursive, &mut files, &mut error_files)
+        .map_err(|e| i18n::trf("读取目录失败: {}", &[&e]))?;
 
     let conn_guard = conn.lock().await;
+    let auto_transcode = crate::settings::auto_transcode_enabled(&conn_guard);
+    let layout = storage::load_layout(&conn_guard);
+    // 扫描到的音频（含已在库中的），用于按文件夹生成播放列表
+    let mut scanned: Vec<(i64, PathBuf)> = Vec::new();
 
-    for entry in entries {
-        let entry = match entry {
-            Ok(entry) => entry,
-            Err(_) => {
-                error_files += 1;
-                continue;
-            }
-        };
-
-        let path = entry.path();
-
-        // 只处理文件，跳过目录
-        if !path.is_file() {
-            continue;
-        }
-
+    for path in files {
         // 检查文件扩展名
         if let Some(extension) = path.extension() {
             if let Some(ext_str) = extension.to_str() {
-                if supported_formats.contains(&ext_str.to_lowercase().as_str()) {
+                if formats::is_supported(ext_str) {
                     found_files += 1;
 
                     // 获取文件信息
@@ -365,62 +841,145 @@ pub async fn scan_audio_directory(
 
                     let file_path_str = path.to_string_lossy().to_string();
 
-                    // 检查文件是否已存在于数据库中
-                    let existing_count: i64 = conn_guard
+                    // 检查文件是否已存在于数据库中（复制导入的按原文件名和大小判断，转码导入的按来源判断）
+                    let existing_id: Option<i64> = conn_guard
                         .query_row(
-                            "SELECT COUNT(*) FROM audio_files WHERE file_path = ?1",
-                            [&file_path_str],
+                            "SELECT id FROM audio_files
+                             WHERE file_path = ?1
+                                OR (?2 AND is_managed = 1 AND original_name = ?3 AND file_size = ?4)
+                                OR (source_type = 'transcoded' AND source_url = ?1)
+                             LIMIT 1",
+                            (&file_path_str, copy_into_library, &original_name, file_size),
                             |row| row.get(0),
                         )
-                        .unwrap_or(0);
+                        .ok();
 
-                    if existing_count > 0 {
+                    if let Some(existing_id) = existing_id {
                         skipped_files += 1;
+                        scanned.push((existing_id, path));
                         continue;
                     }
 
+                    let transcode = match formats::check_import(&path, auto_transcode) {
+                        Ok(transcode) => transcode,
+                        Err(e) => {
+                            eprintln!("跳过文件 {}: {}", file_path_str, e);
+                            error_files += 1;
+                            continue;
+                        }
+                    };
+
                     // 添加到数据库
-                    let filename = format!(
-                        "{}_{}.{}",
-                        chrono::Local::now().format("%Y%m%d_%H%M%S"),
-                        uuid::Uuid::new_v4().to_string().split('-').next().unwrap(),
-                        ext_str.to_lowercase()
-                    );
+                    let stored_extension = if transcode { "mp3".to_string() } else { ext_str.to_lowercase() };
+                    let managed_path = match (transcode || copy_into_library)
+                        .then(|| storage::managed_file_path(&layout, audio_dir, &original_name, &stored_extension, transcode.then_some("transcoded")))
+                        .transpose()
+                    {
+                        Ok(managed_path) => managed_path,
+                        Err(e) => {
+                            eprintln!("跳过文件 {}: {}", file_path_str, e);
+                            error_files += 1;
+                            continue;
+                        }
+                    };
+                    let filename = match &managed_path {
+                        Some((filename, _)) => filename.clone(),
+                        None => format!(
+                            "{}_{}.{}",
+                            chrono::Local::now().format("%Y%m%d_%H%M%S"),
+                            uuid::Uuid::new_v4().to_string().split('-').next().unwrap(),
+                            stored_extension
+                        ),
+                    };
 
-                    // 获取音频真实时长
-                    let duration = get_audio_duration(&path);
+                    // 转码或复制进库时按存储布局存放到应用目录
+                    let (stored_path, is_managed) = match managed_path {
+                        Some((_, dest_path)) if transcode => {
+                            let result = match &ffmpeg_path {
+                                Some(ffmpeg_path) => run_ffmpeg_extract(ffmpeg_path, &file_path_str, &dest_path, false),
+                                None => Err(i18n::tr("FFmpeg未安装")),
+                            };
+                            if let Err(e) = result {
+                                eprintln!("转码文件失败 {}: {}", file_path_str, e);
+                                let _ = fs::remove_file(&dest_path);
+                                error_files += 1;
+                                continue;
+                            }
+                            (dest_path, true)
+                        }
+                        Some((_, dest_path)) => {
+                            if let Err(e) = fs::copy(&path, &dest_path) {
+                                eprintln!("复制文件失败 {}: {}", file_path_str, e);
+                                error_files += 1;
+                                continue;
+                            }
+                            (dest_path, true)
+                        }
+                        None => (path.clone(), scanning_library_dir),
+                    };
+
+                    // 时长由后台检测队列补全，导入时不再逐个读取
+                    let stored_size = if transcode {
+                        fs::metadata(&stored_path).map(|m| m.len() as i64).unwrap_or(file_size)
+                    } else {
+                        file_size
+                    };
+                    let source_url = transcode.then_some(&file_path_str);
 
                     match conn_guard.execute(
-                        "INSERT INTO audio_files (filename, original_name, file_path, file_size, duration, format, upload_date)
-                         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                        "INSERT INTO audio_files (filename, original_name, file_path, file_size, duration, format, upload_date, is_managed, source_type, source_url)
+                         VALUES (?1, ?2, ?3, ?4, NULL, ?5, ?6, ?7, ?8, ?9)",
                         (
                             &filename,
                             &original_name,
-                            &file_path_str,
-                            file_size,
-                            duration,
-                            &ext_str.to_lowercase(),
+                            stored_path.to_string_lossy().to_string(),
+                            stored_size,
+                            &stored_extension,
                             chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+                            is_managed,
+                            transcode.then_some("transcoded"),
+                            source_url,
                         ),
                     ) {
-                        Ok(_) => added_files += 1,
-                        Err(_) => error_files += 1,
+                        Ok(_) => {
+                            added_files += 1;
+                            scanned.push((conn_guard.last_insert_rowid(), path));
+                        }
+                        Err(_) => {
+                            if copy_into_library || transcode {
+                                let _ = fs::remove_file(&stored_path);
+                            }
+                            error_files += 1;
+                        }
                     }
                 }
             }
         }
     }
 
+    // 按文件夹层级生成播放列表（如“英语/一年级/Unit 3”），根目录下的文件不归入播放列表
+    let playlists_created = if options.folder_playlists {
+        create_folder_playlists(&conn_guard, playlist_root, &scanned).map_err(|e| i18n::trf("生成文件夹播放列表失败: {}", &[&e]))?
+    } else {
+        0
+    };
+
+    if added_files > 0 {
+        probe_queue.wake();
+        app.state::<LibraryEvents>().added(scanned.iter().map(|(id, _)| *id));
+    }
+
     Ok(ScanResult {
         found_files,
         added_files,
         skipped_files,
         error_files,
+        playlists_created,
     })
 }
 
 /// 获取FFmpeg可执行文件路径
-async fn get_ffmpeg_executable_path(app: Option<&AppHandle>) -> Option<PathBuf> {
+pub(crate) async fn get_ffmpeg_executable_path(app: Option<&AppHandle>) -> Option<PathBuf> {
     // 首先尝试使用tools目录中的ffmpeg（优先级最高）
     if let Some(app_handle) = app {
         // 开发环境：使用项目根目录下的tools
@@ -519,7 +1078,153 @@ async fn get_ffmpeg_executable_path(app: Option<&AppHandle>) -> Option<PathBuf>
     None
 }
 
-/// 从视频文件提取音频（使用FFmpeg命令行）
+/// 使用FFmpeg从视频（或播放器无法解码的音频）文件中提取MP3音频，loudnorm 为 true 时同时做响度归一化
+pub(crate) fn run_ffmpeg_extract(
+    ffmpeg_path: &std::path::Path,
+    video_path: &str,
+    output_path: &std::path::Path,
+    loudnorm: bool,
+) -> Result<(), String> {
+    let mut cmd = create_command_from_path(&ffmpeg_path.to_path_buf());
+    cmd
+        .arg("-i") // 输入文件
+        .arg(video_path)
+        .arg("-vn"); // 不要视频
+    if loudnorm {
+        cmd.arg("-af").arg(LOUDNORM_FILTER);
+    }
+    cmd
+        .arg("-acodec") // 音频编码器
+        .arg("libmp3lame") // MP3编码器
+        .arg("-ab") // 音频比特率
+        .arg("128k") // 128kbps
+        .arg("-ar") // 音频采样率
+        .arg("44100") // 44.1kHz
+        .arg("-ac") // 音频声道数
+        .arg("2") // 立体声
+        .arg("-y") // 覆盖输出文件
+        .arg(output_path);
+
+    let output = cmd.output().map_err(|e| i18n::trf("执行FFmpeg命令失败: {}", &[&e]))?;
+
+    if !output.status.success() {
+        let error_msg = String::from_utf8_lossy(&output.stderr);
+        return Err(i18n::trf("FFmpeg执行失败: {}", &[&error_msg]));
+    }
+
+    Ok(())
+}
+
+/// 使用yt-dlp下载在线视频的音频并转换为MP3，loudnorm 为 true 时转换时同时做响度归一化
+fn run_ytdlp_download(
+    ytdlp_path: &std::path::Path,
+    ffmpeg_path: &std::path::Path,
+    video_url: &str,
+    output_path: &std::path::Path,
+    loudnorm: bool,
+) -> Result<(), String> {
+    let mut cmd = create_command_from_path(&ytdlp_path.to_path_buf());
+    cmd
+        .arg("-x") // 提取音频
+        .arg("--audio-format").arg("mp3") // 转换为mp3
+        .arg("--audio-quality").arg("0") // 最佳音质
+        .arg("--ffmpeg-location").arg(ffmpeg_path); // 指定ffmpeg位置
+    if loudnorm {
+        // 传给转换MP3时的 FFmpeg 输出参数
+        cmd.arg("--postprocessor-args").arg(format!("ExtractAudio+ffmpeg_o:-af {}", LOUDNORM_FILTER));
+    }
+    cmd
+        .arg("-o").arg(output_path) // 输出路径
+        .arg("--no-playlist") // 不下载播放列表
+        .arg("--no-warnings") // 不显示警告
+        .arg(video_url);
+
+    let output = cmd.output().map_err(|e| i18n::trf("执行yt-dlp命令失败: {}. 请确保已安装 yt-dlp", &[&e]))?;
+
+    if !output.status.success() {
+        let error_msg = String::from_utf8_lossy(&output.stderr);
+        return Err(i18n::trf("yt-dlp执行失败: {}. 请检查视频URL是否正确", &[&error_msg]));
+    }
+
+    Ok(())
+}
+
+/// 提取任务的结果，完成前为 None
+type ExtractionResult = Option<Result<String, String>>;
+
+/// 正在进行的提取任务，按规范化后的来源（文件路径或URL）去重，
+/// 避免重复点击时同时启动两个 FFmpeg/yt-dlp 进程并导入两份
+#[derive(Default)]
+pub struct ExtractionJobs(std::sync::Mutex<HashMap<String, watch::Receiver<ExtractionResult>>>);
+
+/// 任务结束（包括出错提前返回）时从正在进行的任务中移除
+struct ExtractionJobGuard<'a> {
+    jobs: &'a ExtractionJobs,
+    key: String,
+}
+
+impl Drop for ExtractionJobGuard<'_> {
+    fn drop(&mut self) {
+        if let Ok(mut jobs) = self.jobs.0.lock() {
+            jobs.remove(&self.key);
+        }
+    }
+}
+
+impl ExtractionJobs {
+    /// 同一来源已有提取任务在进行时等待它完成并返回它的结果，否则执行 job
+    async fn run_once(&self, key: String, job: impl Future<Output = Result<String, String>>) -> Result<String, String> {
+        let existing = {
+            let mut jobs = self.0.lock().map_err(|e| e.to_string())?;
+            match jobs.get(&key) {
+                Some(receiver) => Ok(receiver.clone()),
+                None => {
+                    let (sender, receiver) = watch::channel(None);
+                    jobs.insert(key.clone(), receiver);
+                    Err(sender)
+                }
+            }
+        };
+
+        match existing {
+            Ok(mut receiver) => {
+                println!("[Extract] 该来源正在提取中，等待已有任务完成: {}", key);
+                let result = receiver
+                    .wait_for(Option::is_some)
+                    .await
+                    .map_err(|_| i18n::tr("提取任务意外中断"))?;
+                result.clone().unwrap_or_else(|| Err(i18n::tr("提取任务意外中断")))
+            }
+            Err(sender) => {
+                let _guard = ExtractionJobGuard { jobs: self, key };
+                let result = job.await;
+                sender.send_replace(Some(result.clone()));
+                result
+            }
+        }
+    }
+}
+
+/// 提取来源的去重键：URL 的协议和域名不区分大小写并去掉末尾的斜杠，本地文件使用规范化的绝对路径
+fn extraction_key(source: &str) -> String {
+    let source = source.trim();
+    if let Some((scheme, rest)) = source.split_once("://") {
+        let (host, path) = rest.split_once('/').unwrap_or((rest, ""));
+        let path = path.trim_end_matches('/');
+        return format!("{}://{}/{}", scheme.to_lowercase(), host.to_lowercase(), path);
+    }
+
+    let path = fs::canonicalize(source).unwrap_or_else(|_| PathBuf::from(source));
+    let key = path.to_string_lossy().to_string();
+    // Windows 的路径不区分大小写
+    if cfg!(target_os = "windows") {
+        key.to_lowercase()
+    } else {
+        key
+    }
+}
+
+/// 从视频文件提取音频（使用FFmpeg命令行），同一文件正在提取时返回该任务的结果
 #[tauri::command]
 pub async fn extract_audio_from_video(
     video_path: String,
@@ -527,21 +1232,33 @@ pub async fn extract_audio_from_video(
     app: AppHandle,
     conn: State<'_, Arc<Mutex<Connection>>>,
     audio_dir: State<'_, PathBuf>,
+    jobs: State<'_, ExtractionJobs>,
+) -> Result<String, String> {
+    let key = extraction_key(&video_path);
+    jobs.run_once(key, run_video_extraction(video_path, output_filename, app, conn, audio_dir)).await
+}
+
+async fn run_video_extraction(
+    video_path: String,
+    output_filename: String,
+    app: AppHandle,
+    conn: State<'_, Arc<Mutex<Connection>>>,
+    audio_dir: State<'_, PathBuf>,
 ) -> Result<String, String> {
     // 获取FFmpeg可执行文件路径
     let ffmpeg_path = get_ffmpeg_executable_path(Some(&app)).await
-        .ok_or("FFmpeg未安装。请将ffmpeg.exe放入tools目录，或点击\"一键安装FFmpeg\"按钮进行安装".to_string())?;
+        .ok_or_else(|| i18n::tr("FFmpeg未安装。请将ffmpeg.exe放入tools目录，或点击\"一键安装FFmpeg\"按钮进行安装"))?;
 
     let input_path = PathBuf::from(&video_path);
     if !input_path.exists() {
-        return Err("视频文件不存在".to_string());
+        return Err(i18n::tr("视频文件不存在"));
     }
 
     // 获取视频文件的原始名称（不含扩展名）
     let video_original_name = input_path
         .file_stem()
         .and_then(|n| n.to_str())
-        .ok_or("无法获取视频文件名")?
+        .ok_or_else(|| i18n::tr("无法获取视频文件名"))?
         .to_string();
 
     // 决定使用的 original_name：用户指定的名称 或 视频原始名称
@@ -551,52 +1268,26 @@ pub async fn extract_audio_from_video(
         output_filename.clone()
     };
 
-    // 生成唯一的文件名（用于实际存储）
-    let filename = format!(
-        "{}_{}.mp3",
-        chrono::Local::now().format("%Y%m%d_%H%M%S"),
-        uuid::Uuid::new_v4().to_string().split('-').next().unwrap()
-    );
-
-    let output_path = audio_dir.join(&filename);
+    // 按存储布局生成文件名和路径（用于实际存储）
+    let layout = storage::load_layout(&*conn.lock().await);
+    let (filename, output_path) = storage::managed_file_path(&layout, &audio_dir, &original_name, "mp3", Some("video"))?;
 
     // 发送进度开始事件
     app.emit_all("extract-progress", 0u8).map_err(|e| e.to_string())?;
 
-    // 构建FFmpeg命令
-    let mut cmd = create_command_from_path(&ffmpeg_path);
-    cmd
-        .arg("-i") // 输入文件
-        .arg(&video_path)
-        .arg("-vn") // 不要视频
-        .arg("-acodec") // 音频编码器
-        .arg("libmp3lame") // MP3编码器
-        .arg("-ab") // 音频比特率
-        .arg("128k") // 128kbps
-        .arg("-ar") // 音频采样率
-        .arg("44100") // 44.1kHz
-        .arg("-ac") // 音频声道数
-        .arg("2") // 立体声
-        .arg("-y") // 覆盖输出文件
-        .arg(output_path.to_str().unwrap());
-
     // 发送进度 10%
     app.emit_all("extract-progress", 10u8).map_err(|e| e.to_string())?;
 
     // 执行FFmpeg命令
-    let output = cmd.output().map_err(|e| format!("执行FFmpeg命令失败: {}", e))?;
+    let loudnorm = crate::settings::extract_loudnorm_enabled(&*conn.lock().await);
+    run_ffmpeg_extract(&ffmpeg_path, &video_path, &output_path, loudnorm)?;
 
     // 发送进度 90%
     app.emit_all("extract-progress", 90u8).map_err(|e| e.to_string())?;
 
-    if !output.status.success() {
-        let error_msg = String::from_utf8_lossy(&output.stderr);
-        return Err(format!("FFmpeg执行失败: {}", error_msg));
-    }
-
     // 检查输出文件是否存在
     if !output_path.exists() {
-        return Err("音频提取失败：输出文件不存在".to_string());
+        return Err(i18n::tr("音频提取失败：输出文件不存在"));
     }
 
     // 发送完成进度
@@ -604,7 +1295,7 @@ pub async fn extract_audio_from_video(
 
     // 获取输出文件信息
     let metadata = std::fs::metadata(&output_path)
-        .map_err(|e| format!("无法获取输出文件信息: {}", e))?;
+        .map_err(|e| i18n::trf("无法获取输出文件信息: {}", &[&e]))?;
     let file_size = metadata.len() as i64;
 
     // 获取音频时长
@@ -613,8 +1304,8 @@ pub async fn extract_audio_from_video(
     // 保存到数据库
     let conn = conn.lock().await;
     conn.execute(
-        "INSERT INTO audio_files (filename, original_name, file_path, file_size, duration, format, upload_date)
-         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        "INSERT INTO audio_files (filename, original_name, file_path, file_size, duration, format, upload_date, source_type, source_url)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, 'video', ?8)",
         (
             &filename,
             &original_name,  // 使用视频文件的原始名称或用户指定的名称
@@ -623,14 +1314,16 @@ pub async fn extract_audio_from_video(
             duration,
             "mp3",
             chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+            &video_path,
         ),
     )
-    .map_err(|e| format!("保存到数据库失败: {}", e))?;
+    .map_err(|e| i18n::trf("保存到数据库失败: {}", &[&e]))?;
+    app.state::<LibraryEvents>().added([conn.last_insert_rowid()]);
 
     Ok(original_name)  // 返回 original_name 而不是 filename
 }
 
-/// 从在线视频提取音频（使用yt-dlp + FFmpeg）
+/// 从在线视频提取音频（使用yt-dlp + FFmpeg），同一URL正在提取时返回该任务的结果
 #[tauri::command]
 pub async fn extract_audio_from_online_video(
     video_url: String,
@@ -638,14 +1331,26 @@ pub async fn extract_audio_from_online_video(
     app: AppHandle,
     conn: State<'_, Arc<Mutex<Connection>>>,
     audio_dir: State<'_, PathBuf>,
+    jobs: State<'_, ExtractionJobs>,
+) -> Result<String, String> {
+    let key = extraction_key(&video_url);
+    jobs.run_once(key, run_online_extraction(video_url, output_filename, app, conn, audio_dir)).await
+}
+
+async fn run_online_extraction(
+    video_url: String,
+    output_filename: String,
+    app: AppHandle,
+    conn: State<'_, Arc<Mutex<Connection>>>,
+    audio_dir: State<'_, PathBuf>,
 ) -> Result<String, String> {
     // 获取FFmpeg可执行文件路径
     let ffmpeg_path = get_ffmpeg_executable_path(Some(&app)).await
-        .ok_or("FFmpeg未安装。请将ffmpeg.exe放入tools目录，或点击\"一键安装FFmpeg\"按钮进行安装".to_string())?;
+        .ok_or_else(|| i18n::tr("FFmpeg未安装。请将ffmpeg.exe放入tools目录，或点击\"一键安装FFmpeg\"按钮进行安装"))?;
 
     // 获取yt-dlp可执行文件路径
     let ytdlp_path = get_ytdlp_executable_path(Some(&app)).await
-        .ok_or("yt-dlp未安装。请将yt-dlp.exe放入tools目录".to_string())?;
+        .ok_or_else(|| i18n::tr("yt-dlp未安装。请将yt-dlp.exe放入tools目录"))?;
 
     // 决定使用的 original_name：用户指定的名称 或 视频标题
     let original_name = if output_filename.is_empty() {
@@ -701,47 +1406,26 @@ pub async fn extract_audio_from_online_video(
         output_filename.clone()
     };
 
-    // 生成唯一的文件名（用于实际存储）
-    let filename = format!(
-        "{}_{}.mp3",
-        chrono::Local::now().format("%Y%m%d_%H%M%S"),
-        uuid::Uuid::new_v4().to_string().split('-').next().unwrap()
-    );
-
-    let output_path = audio_dir.join(&filename);
+    // 按存储布局生成文件名和路径（用于实际存储）
+    let layout = storage::load_layout(&*conn.lock().await);
+    let (filename, output_path) = storage::managed_file_path(&layout, &audio_dir, &original_name, "mp3", Some("online"))?;
 
     // 发送进度开始事件
     app.emit_all("extract-progress", 0u8).map_err(|e| e.to_string())?;
 
-    // 使用yt-dlp下载音频（直接提取最佳音频）
-    let mut cmd = create_command_from_path(&ytdlp_path);
-    cmd
-        .arg("-x") // 提取音频
-        .arg("--audio-format").arg("mp3") // 转换为mp3
-        .arg("--audio-quality").arg("0") // 最佳音质
-        .arg("--ffmpeg-location").arg(ffmpeg_path.to_str().unwrap()) // 指定ffmpeg位置
-        .arg("-o").arg(output_path.to_str().unwrap()) // 输出路径
-        .arg("--no-playlist") // 不下载播放列表
-        .arg("--no-warnings") // 不显示警告
-        .arg(&video_url);
-
     // 发送进度 20%
     app.emit_all("extract-progress", 20u8).map_err(|e| e.to_string())?;
 
-    // 执行yt-dlp命令
-    let output = cmd.output().map_err(|e| format!("执行yt-dlp命令失败: {}. 请确保已安装 yt-dlp", e))?;
+    // 使用yt-dlp下载音频（直接提取最佳音频）
+    let loudnorm = crate::settings::extract_loudnorm_enabled(&*conn.lock().await);
+    run_ytdlp_download(&ytdlp_path, &ffmpeg_path, &video_url, &output_path, loudnorm)?;
 
     // 发送进度 90%
     app.emit_all("extract-progress", 90u8).map_err(|e| e.to_string())?;
 
-    if !output.status.success() {
-        let error_msg = String::from_utf8_lossy(&output.stderr);
-        return Err(format!("yt-dlp执行失败: {}. 请检查视频URL是否正确", error_msg));
-    }
-
     // 检查输出文件是否存在
     if !output_path.exists() {
-        return Err("音频提取失败：输出文件不存在".to_string());
+        return Err(i18n::tr("音频提取失败：输出文件不存在"));
     }
 
     // 发送完成进度
@@ -749,7 +1433,7 @@ pub async fn extract_audio_from_online_video(
 
     // 获取输出文件信息
     let metadata = std::fs::metadata(&output_path)
-        .map_err(|e| format!("无法获取输出文件信息: {}", e))?;
+        .map_err(|e| i18n::trf("无法获取输出文件信息: {}", &[&e]))?;
     let file_size = metadata.len() as i64;
 
     // 获取音频时长
@@ -758,8 +1442,8 @@ pub async fn extract_audio_from_online_video(
     // 保存到数据库
     let conn = conn.lock().await;
     conn.execute(
-        "INSERT INTO audio_files (filename, original_name, file_path, file_size, duration, format, upload_date)
-         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        "INSERT INTO audio_files (filename, original_name, file_path, file_size, duration, format, upload_date, source_type, source_url)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, 'online', ?8)",
         (
             &filename,
             &original_name,  // 使用已获取的视频标题或用户指定名称
@@ -768,20 +1452,101 @@ pub async fn extract_audio_from_online_video(
             duration,
             "mp3",
             chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+            &video_url,
         ),
     )
-    .map_err(|e| format!("保存到数据库失败: {}", e))?;
+    .map_err(|e| i18n::trf("保存到数据库失败: {}", &[&e]))?;
+    app.state::<LibraryEvents>().added([conn.last_insert_rowid()]);
 
     Ok(original_name)
 }
 
+/// 从记录的来源重新提取音频，覆盖库中的文件
+#[tauri::command]
+pub async fn refresh_audio_from_source(
+    id: i64,
+    app: AppHandle,
+    conn: State<'_, Arc<Mutex<Connection>>>,
+) -> Result<(), String> {
+    let (file_path, source_type, source_url): (String, Option<String>, Option<String>) = {
+        let conn = conn.lock().await;
+        conn.query_row(
+            "SELECT file_path, source_type, source_url FROM audio_files WHERE id = ?1",
+            [id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )
+        .map_err(|e| i18n::trf("音频不存在: {}", &[&e]))?
+    };
+
+    let source_url = source_url.ok_or_else(|| i18n::tr("该音频没有记录来源，无法刷新"))?;
+
+    let ffmpeg_path = get_ffmpeg_executable_path(Some(&app)).await
+        .ok_or_else(|| i18n::tr("FFmpeg未安装。请将ffmpeg.exe放入tools目录，或点击\"一键安装FFmpeg\"按钮进行安装"))?;
+
+    // 先输出到临时文件，成功后再替换原文件
+    let target_path = PathBuf::from(&file_path);
+    let temp_path = target_path.with_extension("refresh.mp3");
+
+    app.emit_all("extract-progress", 0u8).map_err(|e| e.to_string())?;
+
+    // 重新提取的视频和在线音频按当前设置做响度归一化，导入时转码的音频保持原样
+    let loudnorm = crate::settings::extract_loudnorm_enabled(&*conn.lock().await);
+    match source_type.as_deref() {
+        Some("video") | Some("transcoded") => {
+            if !std::path::Path::new(&source_url).exists() {
+                return Err(i18n::trf("来源文件不存在: {}", &[&source_url]));
+            }
+            let loudnorm = loudnorm && source_type.as_deref() == Some("video");
+            run_ffmpeg_extract(&ffmpeg_path, &source_url, &temp_path, loudnorm)?;
+        }
+        Some("online") => {
+            let ytdlp_path = get_ytdlp_executable_path(Some(&app)).await
+                .ok_or_else(|| i18n::tr("yt-dlp未安装。请将yt-dlp.exe放入tools目录"))?;
+            run_ytdlp_download(&ytdlp_path, &ffmpeg_path, &source_url, &temp_path, loudnorm)?;
+        }
+        _ => return Err(i18n::tr("不支持从该来源刷新")),
+    }
+
+    app.emit_all("extract-progress", 90u8).map_err(|e| e.to_string())?;
+
+    if !temp_path.exists() {
+        return Err(i18n::tr("音频提取失败：输出文件不存在"));
+    }
+
+    std::fs::rename(&temp_path, &target_path)
+        .map_err(|e| i18n::trf("替换音频文件失败: {}", &[&e]))?;
+
+    let file_size = std::fs::metadata(&target_path)
+        .map_err(|e| i18n::trf("无法获取输出文件信息: {}", &[&e]))?
+        .len() as i64;
+    let duration = get_audio_duration(&target_path);
+
+    let conn = conn.lock().await;
+    // 文件内容已变化，清空响度和首尾静音的分析结果，由后台重新分析
+    conn.execute(
+        "UPDATE audio_files SET file_size = ?1, duration = ?2,
+             loudness_db = NULL, peak_db = NULL, silence_lead_secs = NULL, silence_end_secs = NULL
+         WHERE id = ?3",
+        (file_size, duration, id),
+    )
+    .map_err(|e| i18n::trf("更新音频信息失败: {}", &[&e]))?;
+
+    // 原文件已替换，旧的倍速版本不再对应
+    crate::variant::remove_variants(&conn, id)?;
+    app.state::<LibraryEvents>().updated([id]);
+
+    app.emit_all("extract-progress", 100u8).map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
 /// 检查yt-dlp是否可用
 async fn check_ytdlp_available(app: Option<&AppHandle>) -> bool {
     get_ytdlp_executable_path(app).await.is_some()
 }
 
 /// 获取yt-dlp可执行文件路径
-async fn get_ytdlp_executable_path(app: Option<&AppHandle>) -> Option<PathBuf> {
+pub(crate) async fn get_ytdlp_executable_path(app: Option<&AppHandle>) -> Option<PathBuf> {
     // 首先尝试使用tools目录中的yt-dlp（优先级最高）
     if let Some(app_handle) = app {
         // 开发环境：使用项目根目录下的tools
@@ -855,6 +1620,20 @@ async fn get_ytdlp_executable_path(app: Option<&AppHandle>) -> Option<PathBuf> {
         }
     }
 
+    // 尝试一键安装到应用数据目录的yt-dlp
+    if let Some(app_handle) = app {
+        if let Some(app_dir) = app_handle.path_resolver().app_data_dir() {
+            let installed_ytdlp = app_dir.join("tools").join(YTDLP_FILE_NAME);
+            if installed_ytdlp.exists() {
+                if let Ok(output) = create_command_from_path(&installed_ytdlp).arg("--version").output() {
+                    if output.status.success() {
+                        return Some(installed_ytdlp);
+                    }
+                }
+            }
+        }
+    }
+
     // 其次尝试使用PATH中的yt-dlp
     if let Ok(output) = create_command("yt-dlp").arg("--version").output() {
         if output.status.success() {
@@ -865,6 +1644,55 @@ async fn get_ytdlp_executable_path(app: Option<&AppHandle>) -> Option<PathBuf> {
     None
 }
 
+/// 一键下载安装yt-dlp（安装到应用数据目录的tools子目录）
+#[tauri::command]
+pub async fn install_ytdlp(app: AppHandle) -> Result<String, String> {
+    let tools_dir = app.path_resolver()
+        .app_data_dir()
+        .ok_or_else(|| i18n::tr("无法获取应用数据目录"))?
+        .join("tools");
+    let ytdlp_path = tools_dir.join(YTDLP_FILE_NAME);
+
+    app.emit_all("ytdlp-install-progress", 0u8).map_err(|e| e.to_string())?;
+
+    // 读取官方发布的SHA-256校验值，获取失败时只校验文件长度
+    let expected_sha256 = match reqwest::get(YTDLP_CHECKSUM_URL).await {
+        Ok(response) => response.text().await.ok().and_then(|sums| {
+            sums.lines()
+                .filter_map(|line| line.split_once(char::is_whitespace))
+                .find(|(_, name)| name.trim() == YTDLP_RELEASE_ASSET)
+                .map(|(hash, _)| hash.to_string())
+        }),
+        Err(e) => {
+            eprintln!("获取yt-dlp校验值失败: {}", e);
+            None
+        }
+    };
+
+    app.emit_all("ytdlp-install-progress", 5u8).map_err(|e| e.to_string())?;
+
+    crate::download::download_file(&app, &crate::download::DownloadRequest {
+        url: &format!("{}/{}", YTDLP_DOWNLOAD_BASE, YTDLP_RELEASE_ASSET),
+        dest: &ytdlp_path,
+        progress_event: "ytdlp-install-progress",
+        progress_range: (5, 95),
+        expected_sha256: expected_sha256.as_deref(),
+    })
+    .await
+    .map_err(|e| i18n::trf("下载yt-dlp失败: {}", &[&e]))?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&ytdlp_path, fs::Permissions::from_mode(0o755))
+            .map_err(|e| i18n::trf("设置执行权限失败: {}", &[&e]))?;
+    }
+
+    app.emit_all("ytdlp-install-progress", 100u8).map_err(|e| e.to_string())?;
+
+    Ok(i18n::tr("yt-dlp安装完成"))
+}
+
 /// 检查FFmpeg状态
 #[tauri::command]
 pub async fn check_ffmpeg_status(app: AppHandle) -> Result<FFmpegStatus, String> {
@@ -1030,13 +1858,13 @@ pub async fn install_ffmpeg(app: AppHandle) -> Result<String, String> {
 
     #[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
     {
-        Err("不支持的操作系统".to_string())
+        Err(i18n::tr("不支持的操作系统"))
     }
 }
 
 #[cfg(target_os = "windows")]
 async fn install_ffmpeg_windows(app: AppHandle) -> Result<String, String> {
-    let home_dir = home_dir().ok_or("无法获取用户目录")?;
+    let home_dir = home_dir().ok_or_else(|| i18n::tr("无法获取用户目录"))?;
     let ffmpeg_dir = home_dir.join("ffmpeg");
     let ffmpeg_exe = ffmpeg_dir.join("bin").join("ffmpeg.exe");
 
@@ -1045,13 +1873,13 @@ async fn install_ffmpeg_windows(app: AppHandle) -> Result<String, String> {
         // 检查PATH环境变量
         if let Ok(output) = create_command("ffmpeg").arg("-version").output() {
             if output.status.success() {
-                return Ok("FFmpeg已安装并配置完成".to_string());
+                return Ok(i18n::tr("FFmpeg已安装并配置完成"));
             }
         }
 
         // 添加到PATH环境变量
         add_to_path_windows(ffmpeg_dir.join("bin").to_str().unwrap())?;
-        return Ok("FFmpeg已安装，已配置环境变量".to_string());
+        return Ok(i18n::tr("FFmpeg已安装，已配置环境变量"));
     }
 
     // 发送进度开始事件
@@ -1059,83 +1887,59 @@ async fn install_ffmpeg_windows(app: AppHandle) -> Result<String, String> {
 
     // 创建安装目录
     fs::create_dir_all(&ffmpeg_dir)
-        .map_err(|e| format!("创建安装目录失败: {}", e))?;
+        .map_err(|e| i18n::trf("创建安装目录失败: {}", &[&e]))?;
 
     // 发送进度 10%
     app.emit_all("ffmpeg-install-progress", 10u8).map_err(|e| e.to_string())?;
 
-    // 下载FFmpeg
+    // 下载FFmpeg（支持断点续传和失败重试）
     let download_url = "https://github.com/BtbN/FFmpeg-Builds/releases/download/latest/ffmpeg-master-latest-win64-gpl.zip";
-    let client = reqwest::Client::new();
-
-    let response = client.get(download_url)
-        .send()
-        .await
-        .map_err(|e| format!("下载FFmpeg失败: {}", e))?;
-
-    let total_size = response.content_length().unwrap_or(0);
-    let mut downloaded = 0u64;
-
-    // 发送进度 20%
-    app.emit_all("ffmpeg-install-progress", 20u8).map_err(|e| e.to_string())?;
-
-    // 下载文件
     let temp_zip_path = ffmpeg_dir.join("ffmpeg.zip");
-    let mut file = File::create(&temp_zip_path)
-        .map_err(|e| format!("创建临时文件失败: {}", e))?;
-
-    let mut stream = response.bytes_stream();
-    use futures_util::StreamExt;
-
-    while let Some(chunk) = stream.next().await {
-        let chunk = chunk.map_err(|e| format!("下载中断: {}", e))?;
-        file.write_all(&chunk)
-            .map_err(|e| format!("写入文件失败: {}", e))?;
-        downloaded += chunk.len() as u64;
-
-        // 更新进度 (20% - 80%)
-        if total_size > 0 {
-            let progress = 20 + (downloaded * 60 / total_size) as u8;
-            app.emit_all("ffmpeg-install-progress", progress).map_err(|e| e.to_string())?;
-        }
-    }
 
-    drop(file);
+    crate::download::download_file(&app, &crate::download::DownloadRequest {
+        url: download_url,
+        dest: &temp_zip_path,
+        progress_event: "ffmpeg-install-progress",
+        progress_range: (20, 80),
+        expected_sha256: None,
+    })
+    .await
+    .map_err(|e| i18n::trf("下载FFmpeg失败: {}", &[&e]))?;
 
     // 发送进度 80%
     app.emit_all("ffmpeg-install-progress", 80u8).map_err(|e| e.to_string())?;
 
     // 解压文件
     let zip_file = File::open(&temp_zip_path)
-        .map_err(|e| format!("打开压缩文件失败: {}", e))?;
+        .map_err(|e| i18n::trf("打开压缩文件失败: {}", &[&e]))?;
     let mut archive = ZipArchive::new(zip_file)
-        .map_err(|e| format!("读取压缩文件失败: {}", e))?;
+        .map_err(|e| i18n::trf("读取压缩文件失败: {}", &[&e]))?;
 
     for i in 0..archive.len() {
         let mut file = archive.by_index(i)
-            .map_err(|e| format!("解压失败: {}", e))?;
+            .map_err(|e| i18n::trf("解压失败: {}", &[&e]))?;
         let outpath = ffmpeg_dir.join(file.mangled_name());
 
         if (*file.name()).ends_with('/') {
             fs::create_dir_all(&outpath)
-                .map_err(|e| format!("创建目录失败: {}", e))?;
+                .map_err(|e| i18n::trf("创建目录失败: {}", &[&e]))?;
         } else {
             if let Some(p) = outpath.parent() {
                 if !p.exists() {
                     fs::create_dir_all(p)
-                        .map_err(|e| format!("创建父目录失败: {}", e))?;
+                        .map_err(|e| i18n::trf("创建父目录失败: {}", &[&e]))?;
                 }
             }
             let mut outfile = File::create(&outpath)
-                .map_err(|e| format!("创建文件失败: {}", e))?;
+                .map_err(|e| i18n::trf("创建文件失败: {}", &[&e]))?;
             std::io::copy(&mut file, &mut outfile)
-                .map_err(|e| format!("写入文件失败: {}", e))?;
+                .map_err(|e| i18n::trf("写入文件失败: {}", &[&e]))?;
         }
     }
 
     // 删除压缩文件
     fs::remove_file(&temp_zip_path)
-        .map_err(|e| format!("删除临时文件失败: {}", e))?;
+        .map_err(|e| i18n::trf("删除临时文件失败: {}", &[&e]))?;
 
     // 发送进度 90%
     app.emit_all("ffmpeg-install-progress", 90u8).map_err(|e| e.to_string())?;
@@ -1146,7 +1950,7 @@ async fn install_ffmpeg_windows(app: AppHandle) -> Result<String, String> {
     // 发送完成进度
     app.emit_all("ffmpeg-install-progress", 100u8).map_err(|e| e.to_string())?;
 
-    Ok("FFmpeg安装完成！应用即将重启以使更改生效".to_string())
+    Ok(i18n::tr("FFmpeg安装完成！应用即将重启以使更改生效"))
 }
 
 #[cfg(target_os = "windows")]
@@ -1156,7 +1960,7 @@ fn add_to_path_windows(ffmpeg_path: &str) -> Result<(), String> {
 
     let hkcu = RegKey::predef(HKEY_CURRENT_USER);
     let environment = hkcu.open_subkey_with_flags("Environment", KEY_READ | KEY_WRITE)
-        .map_err(|e| format!("打开注册表失败: {}", e))?;
+        .map_err(|e| i18n::trf("打开注册表失败: {}", &[&e]))?;
 
     let path_value: String = environment.get_value("Path")
         .unwrap_or_default();
@@ -1169,7 +1973,7 @@ fn add_to_path_windows(ffmpeg_path: &str) -> Result<(), String> {
         };
 
         environment.set_value("Path", &new_path)
-            .map_err(|e| format!("设置PATH失败: {}", e))?;
+            .map_err(|e| i18n::trf("设置PATH失败: {}", &[&e]))?;
 
         // 通知系统环境变量已更改
         unsafe {
@@ -1192,13 +1996,13 @@ async fn install_ffmpeg_macos() -> Result<String, String> {
         .args(&["install", "ffmpeg"])
         .output()
         .await
-        .map_err(|e| format!("执行brew命令失败: {}", e))?;
+        .map_err(|e| i18n::trf("执行brew命令失败: {}", &[&e]))?;
 
     if output.status.success() {
-        Ok("FFmpeg通过Homebrew安装完成".to_string())
+        Ok(i18n::tr("FFmpeg通过Homebrew安装完成"))
     } else {
         let error = String::from_utf8_lossy(&output.stderr);
-        Err(format!("Homebrew安装FFmpeg失败: {}", error))
+        Err(i18n::trf("Homebrew安装FFmpeg失败: {}", &[&error]))
     }
 }
 
@@ -1207,19 +2011,17 @@ async fn install_ffmpeg_linux() -> Result<String, String> {
     // 尝试apt
     let output = Command::new("apt")
         .args(&["update"])
-        .output()
-        .await;
+        .output();
 
     if let Ok(result) = output {
         if result.status.success() {
             let output = Command::new("apt")
                 .args(&["install", "-y", "ffmpeg"])
                 .output()
-                .await
-                .map_err(|e| format!("执行apt命令失败: {}", e))?;
+                .map_err(|e| i18n::trf("执行apt命令失败: {}", &[&e]))?;
 
             if output.status.success() {
-                return Ok("FFmpeg通过apt安装完成".to_string());
+                return Ok(i18n::tr("FFmpeg通过apt安装完成"));
             }
         }
     }
@@ -1228,12 +2030,11 @@ async fn install_ffmpeg_linux() -> Result<String, String> {
     let output = Command::new("yum")
         .args(&["install", "-y", "ffmpeg"])
         .output()
-        .await
-        .map_err(|e| format!("执行yum命令失败: {}", e))?;
+        .map_err(|e| i18n::trf("执行yum命令失败: {}", &[&e]))?;
 
     if output.status.success() {
-        Ok("FFmpeg通过yum安装完成".to_string())
+        Ok(i18n::tr("FFmpeg通过yum安装完成"))
     } else {
-        Err("无法安装FFmpeg，请手动安装".to_string())
+        Err(i18n::tr("无法安装FFmpeg，请手动安装"))
     }
 }
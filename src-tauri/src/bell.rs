@@ -0,0 +1,139 @@
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use rusqlite::Connection;
+use chrono::{NaiveTime, Timelike};
+use tauri::State;
+use crate::weekday;
+use crate::scheduler::SchedulerWake;
+use crate::i18n;
+
+// 铃声任务的默认音量
+const DEFAULT_BELL_VOLUME: i64 = 80;
+
+/// 作息时间表：从 start_time 开始，依次为 periods 节课，每节 lesson_minutes 分钟，课间 break_minutes 分钟
+#[derive(Debug, Deserialize)]
+pub struct BellTimetable {
+    /// 第一节课的上课时间（HH:MM）
+    pub start_time: String,
+    pub lesson_minutes: i64,
+    pub break_minutes: i64,
+    pub periods: i64,
+    /// 上课铃播放列表
+    pub start_playlist_id: i64,
+    /// 下课铃播放列表，为空时与上课铃相同
+    pub end_playlist_id: Option<i64>,
+    /// 午休：在第几节课之后，以及午休时长（分钟）
+    pub lunch_after_period: Option<i64>,
+    pub lunch_minutes: Option<i64>,
+    /// 重复模式，默认工作日
+    pub repeat_mode: Option<String>,
+    pub custom_days: Option<String>,
+    pub volume: Option<i64>,
+}
+
+/// 生成的一个铃声任务
+#[derive(Debug, Serialize)]
+pub struct BellTask {
+    pub id: i64,
+    pub name: String,
+    pub hour: i64,
+    pub minute: i64,
+}
+
+/// 按作息表计算各节课的上课、下课时间（当天的分钟数）
+fn plan_bells(timetable: &BellTimetable, start: i64) -> Vec<(i64, i64)> {
+    let mut bells = Vec::new();
+    let mut lesson_start = start;
+    for period in 1..=timetable.periods {
+        let lesson_end = lesson_start + timetable.lesson_minutes;
+        bells.push((lesson_start, lesson_end));
+
+        lesson_start = lesson_end + timetable.break_minutes;
+        if timetable.lunch_after_period == Some(period) {
+            lesson_start += timetable.lunch_minutes.unwrap_or(0) - timetable.break_minutes;
+        }
+    }
+    bells
+}
+
+/// 根据作息时间表一次性生成全部上课铃、下课铃定时任务
+#[tauri::command]
+pub async fn generate_bell_schedule(
+    timetable: BellTimetable,
+    conn: State<'_, Arc<Mutex<Connection>>>,
+    wake: State<'_, SchedulerWake>,
+) -> Result<Vec<BellTask>, String> {
+    let start = NaiveTime::parse_from_str(&timetable.start_time, "%H:%M")
+        .map_err(|_| i18n::trf("无效的上课时间: {}", &[&timetable.start_time]))?;
+
+    if !(1..=20).contains(&timetable.periods) {
+        return Err(i18n::tr("节数需在1-20之间"));
+    }
+    if timetable.lesson_minutes <= 0 || timetable.break_minutes < 0 {
+        return Err(i18n::tr("每节课时长必须大于0，课间时长不能为负"));
+    }
+    if timetable.lunch_minutes.is_some_and(|m| m < 0) {
+        return Err(i18n::tr("午休时长不能为负"));
+    }
+
+    let repeat_mode = timetable.repeat_mode.clone().unwrap_or_else(|| "weekday".to_string());
+    let custom_days = timetable
+        .custom_days
+        .as_deref()
+        .map(|days| weekday::normalize_custom_days(days).ok_or_else(|| i18n::trf("无效的自定义星期: {}", &[&days])))
+        .transpose()?;
+    let volume = timetable.volume.unwrap_or(DEFAULT_BELL_VOLUME);
+    if !(0..=100).contains(&volume) {
+        return Err(i18n::tr("音量必须在0-100之间"));
+    }
+    let end_playlist_id = timetable.end_playlist_id.unwrap_or(timetable.start_playlist_id);
+
+    let bells = plan_bells(&timetable, (start.hour() * 60 + start.minute()) as i64);
+    if bells.last().is_some_and(|(_, end)| *end >= 24 * 60) {
+        return Err(i18n::tr("作息时间超过了当天午夜，请调整节数或时长"));
+    }
+
+    let mut conn = conn.lock().await;
+    for playlist_id in [timetable.start_playlist_id, end_playlist_id] {
+        let exists: bool = conn
+            .query_row(
+                "SELECT EXISTS(SELECT 1 FROM playlists WHERE id = ?1 AND deleted_at IS NULL)",
+                [playlist_id],
+                |row| row.get(0),
+            )
+            .map_err(|e| e.to_string())?;
+        if !exists {
+            return Err(i18n::tr("铃声播放列表不存在"));
+        }
+    }
+
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    let mut tasks = Vec::new();
+
+    for (index, (lesson_start, lesson_end)) in bells.into_iter().enumerate() {
+        let period = index + 1;
+        for (minutes, playlist_id, name) in [
+            (lesson_start, timetable.start_playlist_id, format!("第{}节 上课铃", period)),
+            (lesson_end, end_playlist_id, format!("第{}节 下课铃", period)),
+        ] {
+            let (hour, minute) = (minutes / 60, minutes % 60);
+            tx.execute(
+                "INSERT INTO scheduled_tasks (name, hour, minute, repeat_mode, custom_days, playlist_id, volume, fade_in_duration)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, 0)",
+                (&name, hour, minute, &repeat_mode, &custom_days, playlist_id, volume),
+            )
+            .map_err(|e| e.to_string())?;
+            tasks.push(BellTask {
+                id: tx.last_insert_rowid(),
+                name,
+                hour,
+                minute,
+            });
+        }
+    }
+
+    tx.commit().map_err(|e| e.to_string())?;
+    wake.notify();
+    Ok(tasks)
+}
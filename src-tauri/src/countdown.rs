@@ -0,0 +1,176 @@
+use serde::Serialize;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use tokio::time::{sleep, Duration};
+use rusqlite::Connection;
+use chrono::Local;
+use tauri::{AppHandle, Manager, State};
+use crate::i18n;
+use crate::player::AudioPlayer;
+
+// 最近一次使用的提示音保存在 app_settings 中，托盘倒计时沿用
+const CHIME_SETTING_KEY: &str = "countdown_chime_audio_id";
+
+/// 托盘菜单中快速倒计时的菜单项ID及时长（分钟）
+pub const TRAY_ITEM_ID: &str = "start_countdown";
+pub const TRAY_COUNTDOWN_MINUTES: f64 = 25.0;
+
+/// 托盘菜单中快速倒计时的文字（按当前语言）
+pub fn tray_title() -> String {
+    i18n::trf("开始 {} 分钟倒计时", &[&TRAY_COUNTDOWN_MINUTES])
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CountdownInfo {
+    pub minutes: f64,
+    /// 结束时间（本地时间 YYYY-MM-DD HH:MM:SS）
+    pub ends_at: String,
+    pub remaining_secs: i64,
+    pub chime_audio_id: Option<i64>,
+}
+
+struct ActiveCountdown {
+    minutes: f64,
+    ends_at: chrono::DateTime<Local>,
+    chime_audio_id: Option<i64>,
+    handle: tauri::async_runtime::JoinHandle<()>,
+}
+
+impl ActiveCountdown {
+    fn info(&self) -> CountdownInfo {
+        CountdownInfo {
+            minutes: self.minutes,
+            ends_at: self.ends_at.format("%Y-%m-%d %H:%M:%S").to_string(),
+            remaining_secs: (self.ends_at - Local::now()).num_seconds().max(0),
+            chime_audio_id: self.chime_audio_id,
+        }
+    }
+}
+
+/// 独立于定时任务的倒计时（同一时间只有一个）
+#[derive(Clone, Default)]
+pub struct CountdownTimer {
+    active: Arc<Mutex<Option<ActiveCountdown>>>,
+}
+
+/// 播放提示音
+async fn play_chime(app: &AppHandle, audio_id: i64) -> Result<(), String> {
+    let (file_path, audio_name): (String, String) = {
+        let conn = app.state::<Arc<Mutex<Connection>>>();
+        let conn = conn.lock().await;
+        conn.query_row(
+            "SELECT file_path, original_name FROM audio_files WHERE id = ?1",
+            [audio_id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .map_err(|e| i18n::trf("提示音不存在: {}", &[&e]))?
+    };
+
+    let player = app.state::<Arc<Mutex<AudioPlayer>>>();
+    let mut player = player.lock().await;
+    player.play_with_info(&file_path, audio_id, audio_name)
+}
+
+impl CountdownTimer {
+    async fn start(&self, app: AppHandle, minutes: f64, chime_audio_id: Option<i64>) -> CountdownInfo {
+        let mut active = self.active.lock().await;
+        if let Some(previous) = active.take() {
+            previous.handle.abort();
+        }
+
+        let duration = Duration::from_secs_f64(minutes * 60.0);
+        let ends_at = Local::now() + chrono::Duration::milliseconds(duration.as_millis() as i64);
+        let timer = self.clone();
+        let handle = tauri::async_runtime::spawn(async move {
+            sleep(duration).await;
+            timer.active.lock().await.take();
+
+            if let Some(audio_id) = chime_audio_id {
+                if let Err(e) = play_chime(&app, audio_id).await {
+                    eprintln!("[Countdown] 播放提示音失败: {}", e);
+                }
+            }
+            let _ = app.emit_all("countdown-finished", minutes);
+            let _ = app.emit_all("countdown-changed", None::<CountdownInfo>);
+        });
+
+        let countdown = ActiveCountdown { minutes, ends_at, chime_audio_id, handle };
+        let info = countdown.info();
+        *active = Some(countdown);
+        info
+    }
+}
+
+fn load_saved_chime(conn: &Connection) -> Option<i64> {
+    conn.query_row(
+        "SELECT value FROM app_settings WHERE key = ?1",
+        [CHIME_SETTING_KEY],
+        |row| row.get::<_, String>(0),
+    )
+    .ok()
+    .and_then(|value| value.parse().ok())
+}
+
+/// 托盘菜单：使用上次的提示音开始快速倒计时
+pub async fn start_from_tray(app: &AppHandle) {
+    let chime_audio_id = {
+        let conn = app.state::<Arc<Mutex<Connection>>>();
+        let conn = conn.lock().await;
+        load_saved_chime(&conn)
+    };
+    let timer = app.state::<CountdownTimer>().inner().clone();
+    let info = timer.start(app.clone(), TRAY_COUNTDOWN_MINUTES, chime_audio_id).await;
+    let _ = app.emit_all("countdown-changed", Some(info));
+}
+
+/// 开始倒计时，结束时播放提示音（chime_audio_id 为空时沿用上次的提示音）
+#[tauri::command]
+pub async fn start_countdown(
+    minutes: f64,
+    chime_audio_id: Option<i64>,
+    app: AppHandle,
+    timer: State<'_, CountdownTimer>,
+    conn: State<'_, Arc<Mutex<Connection>>>,
+) -> Result<CountdownInfo, String> {
+    if !(minutes > 0.0 && minutes <= 24.0 * 60.0) {
+        return Err(i18n::tr("倒计时需在0-1440分钟之间"));
+    }
+
+    let chime_audio_id = {
+        let conn = conn.lock().await;
+        match chime_audio_id {
+            Some(audio_id) => {
+                conn.execute(
+                    "INSERT OR REPLACE INTO app_settings (key, value) VALUES (?1, ?2)",
+                    (CHIME_SETTING_KEY, audio_id.to_string()),
+                )
+                .map_err(|e| e.to_string())?;
+                Some(audio_id)
+            }
+            None => load_saved_chime(&conn),
+        }
+    };
+
+    let info = timer.start(app.clone(), minutes, chime_audio_id).await;
+    let _ = app.emit_all("countdown-changed", Some(info.clone()));
+    Ok(info)
+}
+
+#[tauri::command]
+pub async fn cancel_countdown(
+    app: AppHandle,
+    timer: State<'_, CountdownTimer>,
+) -> Result<(), String> {
+    if let Some(active) = timer.active.lock().await.take() {
+        active.handle.abort();
+    }
+    let _ = app.emit_all("countdown-changed", None::<CountdownInfo>);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_countdown(
+    timer: State<'_, CountdownTimer>,
+) -> Result<Option<CountdownInfo>, String> {
+    Ok(timer.active.lock().await.as_ref().map(ActiveCountdown::info))
+}
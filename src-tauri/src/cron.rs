@@ -0,0 +1,169 @@
+//! cron 表达式（repeat_mode = "cron"，表达式保存在 custom_days 中）
+//!
+//! 标准的五个字段：分 时 日 月 星期。每个字段支持 `*`、数字、范围 `a-b`、步长 `*/n`、`a-b/n`、`a/n`
+//! 和逗号分隔的列表；星期 0 和 7 都表示周日。与常见的 cron 实现一致，日和星期都有限制时满足其一即可，
+//! 以 `*` 开头的字段（如 `*/2`）不算限制，与另一个字段同时满足才执行。
+//! 例如 `0 9-18/2 * * *` 表示每天 9:00 到 18:00 每两小时一次。
+
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime, NaiveTime, Timelike};
+use crate::i18n;
+use crate::weekday;
+
+// 查找下一次触发时最多向后查找的天数
+const MAX_SCAN_DAYS: i64 = 366;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CronSchedule {
+    // bit n 表示第 n 分钟（0-59）
+    minutes: u64,
+    // bit n 表示 n 点（0-23）
+    hours: u32,
+    // bit n 表示 n 号（1-31）
+    days: u32,
+    // bit n 表示 n 月（1-12）
+    months: u16,
+    // bit n 表示星期 n（0=周日 … 6=周六）
+    weekdays: u8,
+    // 日、星期字段是否以 * 开头（不限制，与另一个字段同时满足）
+    any_day: bool,
+    any_weekday: bool,
+}
+
+/// 解析一个字段，返回取值的掩码；超出 min-max 或格式错误时为 None
+fn parse_field(field: &str, min: u32, max: u32) -> Option<u64> {
+    let mut mask = 0u64;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => (range, step.parse::<u32>().ok().filter(|step| *step > 0)?),
+            None => (part, 1),
+        };
+        let (start, end) = match (range, range.split_once('-')) {
+            ("*", _) => (min, max),
+            (_, Some((start, end))) => (start.parse().ok()?, end.parse().ok()?),
+            // a/n 表示从 a 开始到最大值每隔 n
+            (value, None) if part.contains('/') => (value.parse().ok()?, max),
+            (value, None) => {
+                let value = value.parse().ok()?;
+                (value, value)
+            }
+        };
+        if start < min || end > max || start > end {
+            return None;
+        }
+        mask |= (start..=end).step_by(step as usize).fold(0u64, |mask, value| mask | (1 << value));
+    }
+    Some(mask)
+}
+
+impl CronSchedule {
+    pub fn parse(expression: &str) -> Result<Self, String> {
+        let invalid = || i18n::trf("无效的 cron 表达式: {}", &[&expression]);
+        let fields: Vec<&str> = expression.split_whitespace().collect();
+        let [minute, hour, day, month, weekday] = fields[..] else {
+            return Err(invalid());
+        };
+        let weekdays = parse_field(weekday, 0, 7).ok_or_else(invalid)?;
+        Ok(Self {
+            minutes: parse_field(minute, 0, 59).ok_or_else(invalid)?,
+            hours: parse_field(hour, 0, 23).ok_or_else(invalid)? as u32,
+            days: parse_field(day, 1, 31).ok_or_else(invalid)? as u32,
+            months: parse_field(month, 1, 12).ok_or_else(invalid)? as u16,
+            // 7 也表示周日
+            weekdays: ((weekdays | (weekdays >> 7)) & 0b111_1111) as u8,
+            any_day: day.starts_with('*'),
+            any_weekday: weekday.starts_with('*'),
+        })
+    }
+
+    /// 指定日期是否执行（按日、月、星期）
+    pub fn runs_on(&self, date: NaiveDate) -> bool {
+        let day_matches = self.days & (1 << date.day()) != 0;
+        let weekday_matches = self.weekdays & (1 << weekday::weekday_index(&date)) != 0;
+        let day_rule = match (self.any_day, self.any_weekday) {
+            (false, false) => day_matches || weekday_matches,
+            _ => day_matches && weekday_matches,
+        };
+        self.months & (1 << date.month()) != 0 && day_rule
+    }
+
+    /// 一天中的触发时刻，按时间先后
+    pub fn times(&self) -> impl Iterator<Item = NaiveTime> + '_ {
+        (0..24u32)
+            .filter(|hour| self.hours & (1 << hour) != 0)
+            .flat_map(move |hour| {
+                (0..60u32)
+                    .filter(|minute| self.minutes & (1 << minute) != 0)
+                    .filter_map(move |minute| NaiveTime::from_hms_opt(hour, minute, 0))
+            })
+    }
+
+    /// 该分钟是否触发
+    pub fn matches(&self, time: NaiveDateTime) -> bool {
+        self.hours & (1 << time.hour()) != 0 && self.minutes & (1 << time.minute()) != 0 && self.runs_on(time.date())
+    }
+
+    /// 晚于 after 的下一次触发时刻，只考虑 accept_date 接受的日子（有效日期范围、节假日等）；
+    /// 一年内不会触发时为 None
+    pub fn next_after(&self, after: NaiveDateTime, accept_date: impl Fn(NaiveDate) -> bool) -> Option<NaiveDateTime> {
+        (0..=MAX_SCAN_DAYS)
+            .map(|offset| after.date() + Duration::days(offset))
+            .filter(|date| self.runs_on(*date) && accept_date(*date))
+            .find_map(|date| self.times().map(|time| date.and_time(time)).find(|time| *time > after))
+    }
+
+    /// 可能执行的星期掩码（bit n 表示星期 n）：限制了日期时任何一天都可能执行
+    pub fn weekday_mask(&self) -> u8 {
+        match self.any_day {
+            true => self.weekdays,
+            false => 0b111_1111,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn time(value: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    #[test]
+    fn every_two_hours_during_the_day() {
+        let cron = CronSchedule::parse("0 9-18/2 * * *").unwrap();
+        let times: Vec<String> = cron.times().map(|time| time.format("%H:%M").to_string()).collect();
+        assert_eq!(times, ["09:00", "11:00", "13:00", "15:00", "17:00"]);
+        assert!(cron.matches(time("2024-06-03 11:00")));
+        assert!(!cron.matches(time("2024-06-03 12:00")));
+        assert_eq!(cron.next_after(time("2024-06-03 17:00"), |_| true), Some(time("2024-06-04 09:00")));
+    }
+
+    #[test]
+    fn day_and_weekday_fields() {
+        // 2024-06-01 是周六，2024-06-03 是周一
+        let weekdays = CronSchedule::parse("30 7 * * 1-5").unwrap();
+        assert!(!weekdays.runs_on(NaiveDate::from_ymd_opt(2024, 6, 1).unwrap()));
+        assert!(weekdays.runs_on(NaiveDate::from_ymd_opt(2024, 6, 3).unwrap()));
+
+        // 日和星期都有限制时满足其一即可；7 表示周日
+        let either = CronSchedule::parse("0 8 1 * 7").unwrap();
+        assert!(either.runs_on(NaiveDate::from_ymd_opt(2024, 6, 1).unwrap()));
+        assert!(either.runs_on(NaiveDate::from_ymd_opt(2024, 6, 2).unwrap()));
+        assert!(!either.runs_on(NaiveDate::from_ymd_opt(2024, 6, 3).unwrap()));
+
+        // 以 * 开头的日字段不算限制：单数日且是周一时执行（6 月 3 日、17 日是单数日周一，10 日是双数日）
+        let stepped = CronSchedule::parse("0 8 */2 * 1").unwrap();
+        assert!(stepped.runs_on(NaiveDate::from_ymd_opt(2024, 6, 3).unwrap()));
+        assert!(!stepped.runs_on(NaiveDate::from_ymd_opt(2024, 6, 5).unwrap()));
+        assert!(!stepped.runs_on(NaiveDate::from_ymd_opt(2024, 6, 10).unwrap()));
+        assert!(stepped.runs_on(NaiveDate::from_ymd_opt(2024, 6, 17).unwrap()));
+        assert_eq!(stepped.weekday_mask(), 0b000_0010);
+    }
+
+    #[test]
+    fn rejects_invalid_expressions() {
+        for expression in ["", "0 9 * *", "60 9 * * *", "0 9-7 * * *", "0 */0 * * *", "0 9 * * 8", "a b c d e"] {
+            assert!(CronSchedule::parse(expression).is_err(), "{}", expression);
+        }
+    }
+}
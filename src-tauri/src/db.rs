@@ -1,6 +1,99 @@
 use rusqlite::{Connection, Result};
 use std::path::Path;
 
+// 已删除的任务和播放列表保留的天数，期间可以恢复
+const DELETED_RETENTION_DAYS: i64 = 30;
+
+/// 数据库迁移：检查字段是否存在，如果不存在则添加（返回是否新增了字段）
+fn add_column_if_missing(conn: &Connection, table: &str, column: &str, definition: &str) -> Result<bool> {
+    let column_exists: Result<i64> = conn.query_row(
+        &format!("SELECT COUNT(*) FROM pragma_table_info('{}') WHERE name = ?1", table),
+        [column],
+        |row| row.get(0),
+    );
+
+    if let Ok(count) = column_exists {
+        if count == 0 {
+            conn.execute(
+                &format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition),
+                [],
+            )?;
+            return Ok(true);
+        }
+    }
+
+    Ok(false)
+}
+
+/// 重建 audio_files 表以去掉 duration 的 NOT NULL 约束（SQLite 不支持直接修改约束）
+fn allow_null_audio_duration(conn: &Connection) -> Result<()> {
+    let create_sql: String = conn.query_row(
+        "SELECT sql FROM sqlite_master WHERE type = 'table' AND name = 'audio_files'",
+        [],
+        |row| row.get(0),
+    )?;
+
+    if !create_sql.contains("duration INTEGER NOT NULL") {
+        return Ok(());
+    }
+
+    let migrated_sql = create_sql
+        .replacen("CREATE TABLE audio_files", "CREATE TABLE audio_files_migrated", 1)
+        .replacen("duration INTEGER NOT NULL", "duration INTEGER", 1);
+
+    conn.execute_batch(&format!(
+        "BEGIN;
+         {};
+         INSERT INTO audio_files_migrated SELECT * FROM audio_files;
+         DROP TABLE audio_files;
+         ALTER TABLE audio_files_migrated RENAME TO audio_files;
+         COMMIT;",
+        migrated_sql
+    ))
+}
+
+/// 按 weekday 模块的星期约定改写已保存的 custom_days
+fn normalize_stored_custom_days(conn: &Connection) -> Result<()> {
+    let rows: Vec<(i64, String)> = conn
+        .prepare("SELECT id, custom_days FROM scheduled_tasks WHERE custom_days IS NOT NULL")?
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<Result<Vec<_>>>()?;
+
+    for (id, custom_days) in rows {
+        if let Some(normalized) = crate::weekday::normalize_custom_days(&custom_days) {
+            if normalized != custom_days {
+                conn.execute(
+                    "UPDATE scheduled_tasks SET custom_days = ?1 WHERE id = ?2",
+                    (&normalized, id),
+                )?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// 彻底清除超过保留期的已删除任务和播放列表；停止、降低音量任务仍引用的播放列表保留
+/// （任务的 playlist_id 外键为级联删除，清除播放列表会连带删除这些任务）
+pub(crate) fn purge_deleted_items(conn: &Connection) -> Result<()> {
+    let cutoff = format!("-{} days", DELETED_RETENTION_DAYS);
+    conn.execute(
+        "DELETE FROM scheduled_tasks WHERE deleted_at IS NOT NULL AND deleted_at < datetime('now', ?1)",
+        [&cutoff],
+    )?;
+    let expired_playlists = "SELECT id FROM playlists WHERE deleted_at IS NOT NULL AND deleted_at < datetime('now', ?1)
+                             AND id NOT IN (SELECT playlist_id FROM scheduled_tasks WHERE action != 'play' AND deleted_at IS NULL)";
+    conn.execute(
+        &format!("DELETE FROM playlist_items WHERE playlist_id IN ({})", expired_playlists),
+        [&cutoff],
+    )?;
+    conn.execute(&format!("DELETE FROM playlists WHERE id IN ({})", expired_playlists), [&cutoff])?;
+    Ok(())
+}
+
+/// 数据库结构版本（保存在 PRAGMA user_version 中，用于问题诊断），新增迁移时加一
+pub const SCHEMA_VERSION: i64 = 4;
+
 pub fn init_database(db_path: &Path) -> Result<Connection> {
     let conn = Connection::open(db_path)?;
 
@@ -12,7 +105,7 @@ pub fn init_database(db_path: &Path) -> Result<Connection> {
             original_name TEXT NOT NULL,
             file_path TEXT NOT NULL,
             file_size INTEGER NOT NULL,
-            duration INTEGER NOT NULL,
+            duration INTEGER,
             format TEXT NOT NULL,
             upload_date DATETIME DEFAULT CURRENT_TIMESTAMP,
             play_count INTEGER DEFAULT 0,
@@ -88,22 +181,68 @@ pub fn init_database(db_path: &Path) -> Result<Connection> {
         [],
     )?;
 
+    // 数据库迁移：为 audio_files 添加 is_managed 字段（1=已复制到应用目录，0=引用外部文件）
+    if add_column_if_missing(&conn, "audio_files", "is_managed", "INTEGER NOT NULL DEFAULT 1")? {
+        // 旧数据中，扫描导入的文件保留原文件名，而复制进应用目录的文件以生成的 filename 命名
+        conn.execute(
+            "UPDATE audio_files SET is_managed = 0
+             WHERE REPLACE(file_path, '\\', '/') NOT LIKE '%/' || filename",
+            [],
+        )?;
+    }
+
+    // 数据库迁移：audio_files.duration 允许为 NULL（表示尚未检测时长）
+    allow_null_audio_duration(&conn)?;
+
+    // 数据库迁移：为 audio_files 记录提取来源（video/online 及对应路径或URL）
+    add_column_if_missing(&conn, "audio_files", "source_type", "TEXT")?;
+    add_column_if_missing(&conn, "audio_files", "source_url", "TEXT")?;
+
+    // 数据库迁移：规范化 custom_days（统一为 0=周日 … 6=周六，去重排序）
+    normalize_stored_custom_days(&conn)?;
+
     // 数据库迁移：为 scheduled_tasks 添加 duration_minutes 字段
-    // 检查字段是否存在，如果不存在则添加
-    let column_exists: Result<i64, _> = conn.query_row(
-        "SELECT COUNT(*) FROM pragma_table_info('scheduled_tasks') WHERE name='duration_minutes'",
-        [],
-        |row| row.get(0),
-    );
+    add_column_if_missing(&conn, "scheduled_tasks", "duration_minutes", "INTEGER")?;
 
-    if let Ok(count) = column_exists {
-        if count == 0 {
-            conn.execute(
-                "ALTER TABLE scheduled_tasks ADD COLUMN duration_minutes INTEGER",
-                [],
-            )?;
-        }
-    }
+    // 数据库迁移：为 scheduled_tasks 添加 volume_curve 字段（任务期间的音量曲线JSON）
+    add_column_if_missing(&conn, "scheduled_tasks", "volume_curve", "TEXT")?;
+
+    // 数据库迁移：为 scheduled_tasks 添加 min_plays_per_track 字段（每首音频当天至少播放的次数）
+    add_column_if_missing(&conn, "scheduled_tasks", "min_plays_per_track", "INTEGER")?;
+
+    // 数据库迁移：为 scheduled_tasks 添加 play_order 字段（覆盖播放列表的播放顺序，NULL 表示沿用播放列表）
+    add_column_if_missing(&conn, "scheduled_tasks", "play_order", "TEXT")?;
+
+    // 数据库迁移：为 scheduled_tasks 添加 weekday_playlists 字段（按星期指定播放列表的JSON映射）
+    add_column_if_missing(&conn, "scheduled_tasks", "weekday_playlists", "TEXT")?;
+
+    // 数据库迁移：为 execution_history 记录执行时的播放列表
+    add_column_if_missing(&conn, "execution_history", "playlist_id", "INTEGER")?;
+    add_column_if_missing(&conn, "execution_history", "playlist_name", "TEXT")?;
+
+    // 数据库迁移：为 playlist_items 添加片段起止位置（秒，NULL 表示从头/到结尾）
+    add_column_if_missing(&conn, "playlist_items", "start_offset", "REAL")?;
+    add_column_if_missing(&conn, "playlist_items", "end_offset", "REAL")?;
+
+    // 数据库迁移：播放列表项的音量增益（dB，NULL 表示不调整）
+    add_column_if_missing(&conn, "playlist_items", "gain_db", "REAL")?;
+
+    // 数据库迁移：任务和播放列表改为软删除（deleted_at 不为空表示在“最近删除”中）
+    add_column_if_missing(&conn, "scheduled_tasks", "deleted_at", "DATETIME")?;
+    add_column_if_missing(&conn, "playlists", "deleted_at", "DATETIME")?;
+
+    // 数据库迁移：播放列表的无缝播放开关（连续曲目追加到同一个 Sink，适合分段的有声书）
+    add_column_if_missing(&conn, "playlists", "gapless", "INTEGER NOT NULL DEFAULT 0")?;
+
+    // 数据库迁移：音频的响度分析结果（dBFS，NULL 表示尚未分析），用于播放时的响度归一化
+    add_column_if_missing(&conn, "audio_files", "loudness_db", "REAL")?;
+    add_column_if_missing(&conn, "audio_files", "peak_db", "REAL")?;
+
+    // 数据库迁移：记录音频由哪个录音模板录制
+    add_column_if_missing(&conn, "audio_files", "recording_template_id", "INTEGER")?;
+
+    // 数据库迁移：录音归档时间（不为空表示已移到归档目录）
+    add_column_if_missing(&conn, "audio_files", "archived_at", "DATETIME")?;
 
     // 创建播放历史记录表（用于统计和日历展示）
     conn.execute(
@@ -120,5 +259,166 @@ pub fn init_database(db_path: &Path) -> Result<Connection> {
         [],
     )?;
 
+    // 创建倍速预渲染版本表（保存 FFmpeg atempo 生成的变调不变速副本）
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS audio_speed_variants (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            audio_id INTEGER NOT NULL,
+            speed REAL NOT NULL,
+            file_path TEXT NOT NULL,
+            file_size INTEGER NOT NULL,
+            created_date DATETIME DEFAULT CURRENT_TIMESTAMP,
+            FOREIGN KEY (audio_id) REFERENCES audio_files(id) ON DELETE CASCADE
+        )",
+        [],
+    )?;
+
+    // 创建歌词表（LRC格式，用于跟读显示）
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS audio_lyrics (
+            audio_id INTEGER PRIMARY KEY,
+            lrc TEXT NOT NULL,
+            updated_date DATETIME DEFAULT CURRENT_TIMESTAMP,
+            FOREIGN KEY (audio_id) REFERENCES audio_files(id) ON DELETE CASCADE
+        )",
+        [],
+    )?;
+
+    // 创建音频标记表（录音时打的时间点标记，用作章节/书签）
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS audio_markers (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            audio_id INTEGER NOT NULL,
+            position REAL NOT NULL,
+            label TEXT NOT NULL,
+            created_date DATETIME DEFAULT CURRENT_TIMESTAMP,
+            FOREIGN KEY (audio_id) REFERENCES audio_files(id) ON DELETE CASCADE
+        )",
+        [],
+    )?;
+
+    // 创建跟读评分记录表
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS pronunciation_scores (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            reference_id INTEGER NOT NULL,
+            recording_id INTEGER NOT NULL,
+            score INTEGER NOT NULL,
+            duration_score INTEGER NOT NULL,
+            rhythm_score INTEGER NOT NULL,
+            pitch_score INTEGER,
+            created_date DATETIME DEFAULT CURRENT_TIMESTAMP,
+            FOREIGN KEY (reference_id) REFERENCES audio_files(id) ON DELETE CASCADE,
+            FOREIGN KEY (recording_id) REFERENCES audio_files(id) ON DELETE CASCADE
+        )",
+        [],
+    )?;
+
+    // 创建录音模板表（固定命名、目录、格式和播放列表的录音预设）
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS recording_templates (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL,
+            filename_pattern TEXT NOT NULL,
+            folder TEXT,
+            format TEXT NOT NULL DEFAULT 'wav',
+            playlist_id INTEGER,
+            created_date DATETIME DEFAULT CURRENT_TIMESTAMP,
+            FOREIGN KEY (playlist_id) REFERENCES playlists(id) ON DELETE SET NULL
+        )",
+        [],
+    )?;
+
+    // 创建播放位置表（每个音频上次播放到的位置，用于继续播放）
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS playback_positions (
+            audio_id INTEGER PRIMARY KEY,
+            position_secs REAL NOT NULL,
+            updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            FOREIGN KEY (audio_id) REFERENCES audio_files(id) ON DELETE CASCADE
+        )",
+        [],
+    )?;
+
+    // 数据库迁移：工作日错过的任务周六上午补播（execution_history.makeup 标记补播的执行记录）
+    add_column_if_missing(&conn, "scheduled_tasks", "weekend_makeup", "INTEGER NOT NULL DEFAULT 0")?;
+    add_column_if_missing(&conn, "execution_history", "makeup", "INTEGER NOT NULL DEFAULT 0")?;
+
+    // 数据库迁移：播放列表项的备注（学习要点，如“注意过去时”）
+    add_column_if_missing(&conn, "playlist_items", "note", "TEXT")?;
+
+    // 数据库迁移：音频记住的播放倍速（为空时使用手动选择的倍速）
+    add_column_if_missing(&conn, "audio_files", "preferred_speed", "REAL")?;
+
+    // 数据库迁移：重要任务（暂停定时任务期间照常执行）
+    add_column_if_missing(&conn, "scheduled_tasks", "critical", "INTEGER NOT NULL DEFAULT 0")?;
+
+    // 创建调度日志表（调度器对每个任务的判断和原因，用于排查任务为什么没有执行）
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS scheduler_trace (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            trace_time DATETIME DEFAULT CURRENT_TIMESTAMP,
+            task_id INTEGER NOT NULL,
+            task_name TEXT NOT NULL,
+            decision TEXT NOT NULL,
+            reason TEXT NOT NULL
+        )",
+        [],
+    )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_scheduler_trace_time ON scheduler_trace(trace_time)",
+        [],
+    )?;
+
+    // 数据库迁移：音频首尾静音的分析结果（秒，NULL 表示尚未分析），用于播放时跳过静音
+    add_column_if_missing(&conn, "audio_files", "silence_lead_secs", "REAL")?;
+    add_column_if_missing(&conn, "audio_files", "silence_end_secs", "REAL")?;
+
+    // 创建每日收听时长表（本地日期 YYYY-MM-DD，实际播放的秒数），用于家长设置的每日收听限制
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS listening_time (
+            day TEXT PRIMARY KEY,
+            seconds REAL NOT NULL DEFAULT 0
+        )",
+        [],
+    )?;
+
+    // 数据库迁移：音频标记为已学会的时间，播放列表可设置播放时跳过已学会的音频
+    add_column_if_missing(&conn, "audio_files", "learned_at", "DATETIME")?;
+    add_column_if_missing(&conn, "playlists", "exclude_learned", "INTEGER NOT NULL DEFAULT 0")?;
+
+    // 数据库迁移：任务达到时长限制时的渐弱时长（秒），渐弱在结束时刻完成
+    add_column_if_missing(&conn, "scheduled_tasks", "fade_out_duration", "INTEGER NOT NULL DEFAULT 0")?;
+
+    // 数据库迁移：播放列表每周完成的目标遍数（NULL 表示没有目标）
+    add_column_if_missing(&conn, "playlists", "weekly_target", "INTEGER")?;
+
+    // 数据库迁移：任务类型，play 播放播放列表，stop 停止播放，lower_volume 把音量降到任务音量
+    add_column_if_missing(&conn, "scheduled_tasks", "action", "TEXT NOT NULL DEFAULT 'play'")?;
+
+    // 数据库迁移：任务的有效日期范围（YYYY-MM-DD，含两端，NULL 表示不限），repeat_mode = 'date' 时为执行的那一天
+    add_column_if_missing(&conn, "scheduled_tasks", "start_date", "TEXT")?;
+    add_column_if_missing(&conn, "scheduled_tasks", "end_date", "TEXT")?;
+
+    // 节假日日历（手动添加或从公共假日列表导入），设置了 skip_holidays 的任务在这些日子不执行
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS holidays (
+            date TEXT PRIMARY KEY,
+            name TEXT NOT NULL DEFAULT '',
+            source TEXT NOT NULL DEFAULT 'manual',
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+        )",
+        [],
+    )?;
+    add_column_if_missing(&conn, "scheduled_tasks", "skip_holidays", "INTEGER NOT NULL DEFAULT 0")?;
+
+    // 数据库迁移：调度器改为在任务的触发时刻检查，不再有轮询间隔和精确定时设置
+    conn.execute("DELETE FROM app_settings WHERE key = 'scheduler_timing'", [])?;
+
+    // 清除超过保留期的已删除项（用到任务类型等字段，放在所有迁移之后）
+    purge_deleted_items(&conn)?;
+
+    conn.pragma_update(None, "user_version", SCHEMA_VERSION)?;
+
     Ok(conn)
 }
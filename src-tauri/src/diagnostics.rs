@@ -0,0 +1,151 @@
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use rusqlite::Connection;
+use cpal::traits::{DeviceTrait, HostTrait};
+use tauri::{AppHandle, State};
+use crate::audio;
+
+/// 外部工具（FFmpeg、yt-dlp）的位置和版本，找不到时均为 None
+#[derive(Debug, Serialize)]
+pub struct ToolInfo {
+    pub path: Option<String>,
+    /// 版本输出的第一行
+    pub version: Option<String>,
+}
+
+/// 音频目录所在磁盘的空间（字节）
+#[derive(Debug, Serialize)]
+pub struct DiskSpace {
+    pub available_bytes: u64,
+    pub total_bytes: u64,
+}
+
+/// 系统中的音频设备名称
+#[derive(Debug, Default, Serialize)]
+pub struct AudioDevices {
+    pub outputs: Vec<String>,
+    pub default_output: Option<String>,
+    pub inputs: Vec<String>,
+    pub default_input: Option<String>,
+}
+
+/// 问题反馈用的诊断信息，前端整体复制为一段文本
+#[derive(Debug, Serialize)]
+pub struct Diagnostics {
+    pub app_version: String,
+    pub tauri_version: String,
+    /// 操作系统和架构，如 windows x86_64
+    pub os: String,
+    pub debug_build: bool,
+    pub schema_version: i64,
+    pub sqlite_version: String,
+    pub app_data_dir: Option<String>,
+    pub audio_dir: String,
+    pub audio_file_count: i64,
+    pub ffmpeg: ToolInfo,
+    pub ytdlp: ToolInfo,
+    pub audio_devices: AudioDevices,
+    pub disk_space: Option<DiskSpace>,
+}
+
+/// 运行工具的版本命令，返回输出的第一行
+fn tool_info(path: Option<PathBuf>, version_arg: &str) -> ToolInfo {
+    let version = path.as_ref().and_then(|path| {
+        let output = audio::create_command_from_path(path).arg(version_arg).output().ok()?;
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        stdout.lines().next().map(|line| line.trim().to_string())
+    });
+    ToolInfo { path: path.map(|path| path.to_string_lossy().to_string()), version }
+}
+
+fn audio_devices() -> AudioDevices {
+    let host = cpal::default_host();
+    AudioDevices {
+        outputs: host
+            .output_devices()
+            .map(|devices| devices.filter_map(|device| device.name().ok()).collect())
+            .unwrap_or_default(),
+        default_output: host.default_output_device().and_then(|device| device.name().ok()),
+        inputs: host
+            .input_devices()
+            .map(|devices| devices.filter_map(|device| device.name().ok()).collect())
+            .unwrap_or_default(),
+        default_input: host.default_input_device().and_then(|device| device.name().ok()),
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn disk_space(path: &Path) -> Option<DiskSpace> {
+    use std::os::windows::ffi::OsStrExt;
+    use winapi::um::fileapi::GetDiskFreeSpaceExW;
+    use winapi::um::winnt::ULARGE_INTEGER;
+
+    let wide: Vec<u16> = path.as_os_str().encode_wide().chain(Some(0)).collect();
+    let (mut available, mut total, mut free): (ULARGE_INTEGER, ULARGE_INTEGER, ULARGE_INTEGER) = unsafe { std::mem::zeroed() };
+    let ok = unsafe { GetDiskFreeSpaceExW(wide.as_ptr(), &mut available, &mut total, &mut free) };
+    (ok != 0).then(|| unsafe {
+        DiskSpace { available_bytes: *available.QuadPart(), total_bytes: *total.QuadPart() }
+    })
+}
+
+/// 使用 df 查询（POSIX 输出格式：文件系统 总块数 已用 可用 …，块大小为 1024 字节）
+#[cfg(not(target_os = "windows"))]
+fn disk_space(path: &Path) -> Option<DiskSpace> {
+    let output = std::process::Command::new("df").arg("-Pk").arg(path).output().ok()?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let columns: Vec<&str> = stdout.lines().nth(1)?.split_whitespace().collect();
+    let total_kb: u64 = columns.get(1)?.parse().ok()?;
+    let available_kb: u64 = columns.get(3)?.parse().ok()?;
+    Some(DiskSpace { available_bytes: available_kb * 1024, total_bytes: total_kb * 1024 })
+}
+
+/// 收集版本、数据库、数据目录、外部工具、音频设备和磁盘空间等诊断信息，方便附在问题反馈中
+#[tauri::command]
+pub async fn get_diagnostics(
+    app: AppHandle,
+    conn: State<'_, Arc<Mutex<Connection>>>,
+    audio_dir: State<'_, PathBuf>,
+) -> Result<Diagnostics, String> {
+    let (schema_version, audio_file_count) = {
+        let conn = conn.lock().await;
+        let schema_version = conn
+            .query_row("PRAGMA user_version", [], |row| row.get(0))
+            .unwrap_or(0);
+        let audio_file_count = conn
+            .query_row("SELECT COUNT(*) FROM audio_files", [], |row| row.get(0))
+            .unwrap_or(0);
+        (schema_version, audio_file_count)
+    };
+
+    let ffmpeg_path = audio::get_ffmpeg_executable_path(Some(&app)).await;
+    let ytdlp_path = audio::get_ytdlp_executable_path(Some(&app)).await;
+    let dir = audio_dir.inner().clone();
+    let (ffmpeg, ytdlp, audio_devices, disk_space) = tauri::async_runtime::spawn_blocking(move || {
+        (
+            tool_info(ffmpeg_path, "-version"),
+            tool_info(ytdlp_path, "--version"),
+            audio_devices(),
+            disk_space(&dir),
+        )
+    })
+    .await
+    .map_err(|e| e.to_string())?;
+
+    Ok(Diagnostics {
+        app_version: app.package_info().version.to_string(),
+        tauri_version: tauri::VERSION.to_string(),
+        os: format!("{} {}", std::env::consts::OS, std::env::consts::ARCH),
+        debug_build: cfg!(debug_assertions),
+        schema_version,
+        sqlite_version: rusqlite::version().to_string(),
+        app_data_dir: app.path_resolver().app_data_dir().map(|dir| dir.to_string_lossy().to_string()),
+        audio_dir: audio_dir.to_string_lossy().to_string(),
+        audio_file_count,
+        ffmpeg,
+        ytdlp,
+        audio_devices,
+        disk_space,
+    })
+}
@@ -0,0 +1,192 @@
+use std::fs::{self, OpenOptions};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use futures_util::StreamExt;
+use reqwest::header::RANGE;
+use reqwest::StatusCode;
+use sha2::{Digest, Sha256};
+use tauri::{AppHandle, Manager};
+use tokio::time::{sleep, Duration};
+use crate::i18n;
+
+// 最大重试次数
+const MAX_RETRIES: u32 = 5;
+// 首次重试前的等待时间（秒），之后每次翻倍
+const INITIAL_BACKOFF_SECS: u64 = 1;
+
+/// 一次下载任务的参数
+pub struct DownloadRequest<'a> {
+    pub url: &'a str,
+    pub dest: &'a Path,
+    // 进度事件名称，例如 ffmpeg-install-progress
+    pub progress_event: &'a str,
+    // 下载进度映射到的百分比区间
+    pub progress_range: (u8, u8),
+    // 期望的SHA-256（十六进制），为None时只校验长度
+    pub expected_sha256: Option<&'a str>,
+}
+
+/// 未完成下载的临时文件路径
+fn part_path(dest: &Path) -> PathBuf {
+    let mut name = dest.file_name().map(|n| n.to_os_string()).unwrap_or_default();
+    name.push(".part");
+    dest.with_file_name(name)
+}
+
+/// 单次下载尝试的错误，区分是否值得重试
+enum AttemptError {
+    Retryable(String),
+    Fatal(String),
+}
+
+/// 下载文件：支持断点续传、失败重试（指数退避）、进度事件和完整性校验
+pub async fn download_file(app: &AppHandle, request: &DownloadRequest<'_>) -> Result<PathBuf, String> {
+    if let Some(parent) = request.dest.parent() {
+        fs::create_dir_all(parent).map_err(|e| i18n::trf("创建下载目录失败: {}", &[&e]))?;
+    }
+
+    let part = part_path(request.dest);
+    let client = reqwest::Client::new();
+    let mut backoff = INITIAL_BACKOFF_SECS;
+    let mut last_error = String::new();
+
+    for attempt in 0..=MAX_RETRIES {
+        if attempt > 0 {
+            eprintln!("下载失败，{}秒后第{}次重试: {}", backoff, attempt, last_error);
+            sleep(Duration::from_secs(backoff)).await;
+            backoff *= 2;
+        }
+
+        match download_attempt(app, &client, request, &part).await {
+            Ok(total_size) => {
+                if let Err(e) = verify_file(&part, total_size, request.expected_sha256) {
+                    // 校验失败说明临时文件已损坏，删除后从头下载
+                    let _ = fs::remove_file(&part);
+                    last_error = e;
+                    continue;
+                }
+
+                if request.dest.exists() {
+                    let _ = fs::remove_file(request.dest);
+                }
+                fs::rename(&part, request.dest).map_err(|e| i18n::trf("保存下载文件失败: {}", &[&e]))?;
+
+                let _ = app.emit_all(request.progress_event, request.progress_range.1);
+                return Ok(request.dest.to_path_buf());
+            }
+            Err(AttemptError::Retryable(e)) => last_error = e,
+            Err(AttemptError::Fatal(e)) => {
+                let _ = fs::remove_file(&part);
+                return Err(e);
+            }
+        }
+    }
+
+    // 保留临时文件，下次下载时可以继续
+    Err(i18n::trf("下载失败（已重试{}次）: {}", &[&MAX_RETRIES, &last_error]))
+}
+
+/// 执行一次下载，返回文件总大小（未知时为None）
+async fn download_attempt(
+    app: &AppHandle,
+    client: &reqwest::Client,
+    request: &DownloadRequest<'_>,
+    part: &Path,
+) -> Result<Option<u64>, AttemptError> {
+    let existing = fs::metadata(part).map(|m| m.len()).unwrap_or(0);
+
+    let mut builder = client.get(request.url);
+    if existing > 0 {
+        builder = builder.header(RANGE, format!("bytes={}-", existing));
+    }
+
+    let response = builder
+        .send()
+        .await
+        .map_err(|e| AttemptError::Retryable(i18n::trf("连接失败: {}", &[&e])))?;
+
+    let status = response.status();
+
+    // 临时文件已完整（服务器认为请求范围超出文件长度）
+    if status == StatusCode::RANGE_NOT_SATISFIABLE && existing > 0 {
+        return Ok(None);
+    }
+
+    if status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS {
+        return Err(AttemptError::Retryable(i18n::trf("服务器错误: {}", &[&status])));
+    }
+    if !status.is_success() {
+        return Err(AttemptError::Fatal(i18n::trf("下载失败: HTTP {}", &[&status])));
+    }
+
+    // 服务器支持续传时返回206，否则从头开始
+    let resuming = status == StatusCode::PARTIAL_CONTENT;
+    let mut downloaded = if resuming { existing } else { 0 };
+    let total_size = response.content_length().map(|len| len + downloaded);
+
+    let mut file = OpenOptions::new()
+        .create(true)
+        .write(true)
+        .append(resuming)
+        .truncate(!resuming)
+        .open(part)
+        .map_err(|e| AttemptError::Fatal(i18n::trf("创建临时文件失败: {}", &[&e])))?;
+
+    let (start, end) = request.progress_range;
+    let mut last_progress = start;
+    let mut stream = response.bytes_stream();
+
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk.map_err(|e| AttemptError::Retryable(i18n::trf("下载中断: {}", &[&e])))?;
+        file.write_all(&chunk)
+            .map_err(|e| AttemptError::Fatal(i18n::trf("写入文件失败: {}", &[&e])))?;
+        downloaded += chunk.len() as u64;
+
+        if let Some(total) = total_size.filter(|t| *t > 0) {
+            let progress = start + (downloaded.min(total) * (end - start) as u64 / total) as u8;
+            if progress != last_progress {
+                last_progress = progress;
+                let _ = app.emit_all(request.progress_event, progress);
+            }
+        }
+    }
+
+    file.flush()
+        .map_err(|e| AttemptError::Fatal(i18n::trf("写入文件失败: {}", &[&e])))?;
+
+    Ok(total_size)
+}
+
+/// 校验下载文件的长度和SHA-256
+fn verify_file(path: &Path, total_size: Option<u64>, expected_sha256: Option<&str>) -> Result<(), String> {
+    let actual_size = fs::metadata(path).map_err(|e| e.to_string())?.len();
+    if let Some(total) = total_size {
+        if actual_size != total {
+            return Err(i18n::trf("文件大小不符: 期望{}字节，实际{}字节", &[&total, &actual_size]));
+        }
+    }
+
+    if let Some(expected) = expected_sha256 {
+        let mut file = fs::File::open(path).map_err(|e| e.to_string())?;
+        let mut hasher = Sha256::new();
+        let mut buffer = [0u8; 64 * 1024];
+        loop {
+            let read = file.read(&mut buffer).map_err(|e| e.to_string())?;
+            if read == 0 {
+                break;
+            }
+            hasher.update(&buffer[..read]);
+        }
+
+        let actual: String = hasher
+            .finalize()
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect();
+        if !actual.eq_ignore_ascii_case(expected.trim()) {
+            return Err(i18n::tr("文件校验失败: SHA-256不匹配"));
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,360 @@
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use rusqlite::Connection;
+use tauri::{State, AppHandle, Manager};
+use crate::audio::{create_command_from_path, get_audio_duration, get_ffmpeg_executable_path};
+use crate::i18n;
+use crate::library_events::LibraryEvents;
+
+// 静音检测的默认参数
+const DEFAULT_SILENCE_THRESHOLD_DB: f64 = -35.0;
+const DEFAULT_MIN_TRACK_SECS: f64 = 30.0;
+// 至少持续这么久的静音才视为分段点（秒）
+const MIN_SILENCE_SECS: f64 = 1.5;
+
+/// 合并后统一的采样参数（格式不一致时重新编码）
+const MERGE_FILTER_FORMAT: &str = "aresample=44100,aformat=sample_fmts=fltp:channel_layouts=stereo";
+
+#[derive(Debug, Serialize)]
+pub struct SplitResult {
+    pub audio_ids: Vec<i64>,
+    pub playlist_id: Option<i64>,
+}
+
+/// 解析 FFmpeg 输出中的 "Duration: HH:MM:SS.ss"
+fn parse_ffmpeg_duration(stderr: &str) -> Option<f64> {
+    let rest = &stderr[stderr.find("Duration: ")? + "Duration: ".len()..];
+    let value = rest.split(',').next()?.trim();
+    let mut parts = value.split(':');
+    let hours: f64 = parts.next()?.parse().ok()?;
+    let minutes: f64 = parts.next()?.parse().ok()?;
+    let seconds: f64 = parts.next()?.parse().ok()?;
+    Some(hours * 3600.0 + minutes * 60.0 + seconds)
+}
+
+/// 解析 silencedetect 输出的静音区间
+fn parse_silences(stderr: &str) -> Vec<(f64, f64)> {
+    let mut silences = Vec::new();
+    let mut start: Option<f64> = None;
+
+    for line in stderr.lines() {
+        if let Some(pos) = line.find("silence_start: ") {
+            start = line[pos + "silence_start: ".len()..]
+                .split_whitespace()
+                .next()
+                .and_then(|v| v.parse().ok());
+        } else if let Some(pos) = line.find("silence_end: ") {
+            let end = line[pos + "silence_end: ".len()..]
+                .split_whitespace()
+                .next()
+                .and_then(|v| v.parse::<f64>().ok());
+            if let (Some(s), Some(e)) = (start.take(), end) {
+                silences.push((s.max(0.0), e));
+            }
+        }
+    }
+
+    silences
+}
+
+/// 以静音中点为分割点规划各段，短于 min_len 的段并入相邻段
+fn plan_segments(silences: &[(f64, f64)], total: f64, min_len: f64) -> Vec<(f64, f64)> {
+    let cuts: Vec<f64> = silences
+        .iter()
+        .filter(|(start, end)| *start > 0.05 && *end < total - 0.05)
+        .map(|(start, end)| (start + end) / 2.0)
+        .collect();
+
+    let mut segments: Vec<(f64, f64)> = Vec::new();
+    let mut segment_start = 0.0;
+    for cut in cuts {
+        if cut - segment_start >= min_len {
+            segments.push((segment_start, cut));
+            segment_start = cut;
+        }
+    }
+
+    // 最后一段过短时并入前一段
+    match segments.last_mut() {
+        Some(last) if total - segment_start < min_len => last.1 = total,
+        _ => segments.push((segment_start, total)),
+    }
+
+    segments
+}
+
+/// 按静音将一段长录音切分为多个音频，可选地创建包含各段的播放列表
+#[tauri::command]
+pub async fn split_audio_by_silence(
+    id: i64,
+    threshold: Option<f64>,
+    min_len: Option<f64>,
+    create_playlist: Option<bool>,
+    app: AppHandle,
+    conn: State<'_, Arc<Mutex<Connection>>>,
+    audio_dir: State<'_, PathBuf>,
+) -> Result<SplitResult, String> {
+    let ffmpeg_path = get_ffmpeg_executable_path(Some(&app)).await
+        .ok_or_else(|| i18n::tr("FFmpeg未安装。请将ffmpeg.exe放入tools目录，或点击\"一键安装FFmpeg\"按钮进行安装"))?;
+
+    let threshold = threshold.unwrap_or(DEFAULT_SILENCE_THRESHOLD_DB);
+    let min_len = min_len.unwrap_or(DEFAULT_MIN_TRACK_SECS).max(1.0);
+
+    let (file_path, original_name): (String, String) = {
+        let conn = conn.lock().await;
+        conn.query_row(
+            "SELECT file_path, original_name FROM audio_files WHERE id = ?1",
+            [id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .map_err(|e| i18n::trf("音频不存在: {}", &[&e]))?
+    };
+
+    app.emit_all("split-progress", 0u8).map_err(|e| e.to_string())?;
+
+    // 静音检测
+    let output = create_command_from_path(&ffmpeg_path)
+        .arg("-i")
+        .arg(&file_path)
+        .arg("-af")
+        .arg(format!("silencedetect=noise={}dB:d={}", threshold, MIN_SILENCE_SECS))
+        .arg("-f")
+        .arg("null")
+        .arg("-")
+        .output()
+        .map_err(|e| i18n::trf("执行FFmpeg命令失败: {}", &[&e]))?;
+
+    if !output.status.success() {
+        return Err(i18n::trf("静音检测失败: {}", &[&String::from_utf8_lossy(&output.stderr)]));
+    }
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let total = parse_ffmpeg_duration(&stderr).ok_or_else(|| i18n::tr("无法获取音频时长"))?;
+    let segments = plan_segments(&parse_silences(&stderr), total, min_len);
+
+    if segments.len() < 2 {
+        return Err(i18n::tr("未检测到可用于分割的静音段，请调整静音阈值或最短时长"));
+    }
+
+    app.emit_all("split-progress", 10u8).map_err(|e| e.to_string())?;
+
+    let base_name = Path::new(&original_name)
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or(&original_name)
+        .to_string();
+
+    let mut audio_ids = Vec::new();
+    let total_segments = segments.len();
+    let layout = crate::storage::load_layout(&*conn.lock().await);
+
+    for (index, (start, end)) in segments.into_iter().enumerate() {
+        let part_name = format!("{}_{:02}", base_name, index + 1);
+        let (filename, output_path) = crate::storage::managed_file_path(&layout, &audio_dir, &part_name, "mp3", None)?;
+
+        let output = create_command_from_path(&ffmpeg_path)
+            .arg("-i")
+            .arg(&file_path)
+            .arg("-ss")
+            .arg(format!("{:.3}", start))
+            .arg("-to")
+            .arg(format!("{:.3}", end))
+            .arg("-vn")
+            .arg("-acodec")
+            .arg("libmp3lame")
+            .arg("-ab")
+            .arg("128k")
+            .arg("-y")
+            .arg(&output_path)
+            .output()
+            .map_err(|e| i18n::trf("执行FFmpeg命令失败: {}", &[&e]))?;
+
+        if !output.status.success() || !output_path.exists() {
+            let _ = std::fs::remove_file(&output_path);
+            return Err(i18n::trf(
+                "切分第 {} 段失败: {}",
+                &[&(index + 1), &String::from_utf8_lossy(&output.stderr)],
+            ));
+        }
+
+        let file_size = std::fs::metadata(&output_path)
+            .map(|m| m.len() as i64)
+            .unwrap_or(0);
+        let duration = get_audio_duration(&output_path);
+
+        let conn = conn.lock().await;
+        conn.execute(
+            "INSERT INTO audio_files (filename, original_name, file_path, file_size, duration, format, upload_date)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            (
+                &filename,
+                &part_name,
+                output_path.to_str().unwrap(),
+                file_size,
+                duration,
+                "mp3",
+                chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+            ),
+        )
+        .map_err(|e| i18n::trf("保存到数据库失败: {}", &[&e]))?;
+        audio_ids.push(conn.last_insert_rowid());
+
+        let progress = 10 + ((index + 1) * 90 / total_segments) as u8;
+        app.emit_all("split-progress", progress).map_err(|e| e.to_string())?;
+    }
+
+    app.state::<LibraryEvents>().added(audio_ids.iter().copied());
+
+    // 创建包含各段的播放列表
+    let playlist_id = if create_playlist.unwrap_or(false) {
+        let conn = conn.lock().await;
+        conn.execute("INSERT INTO playlists (name) VALUES (?1)", [&base_name])
+            .map_err(|e| e.to_string())?;
+        let playlist_id = conn.last_insert_rowid();
+        for (sort_order, audio_id) in audio_ids.iter().enumerate() {
+            conn.execute(
+                "INSERT INTO playlist_items (playlist_id, audio_id, sort_order) VALUES (?1, ?2, ?3)",
+                (playlist_id, audio_id, sort_order as i64),
+            )
+            .map_err(|e| e.to_string())?;
+        }
+        Some(playlist_id)
+    } else {
+        None
+    };
+
+    Ok(SplitResult { audio_ids, playlist_id })
+}
+
+/// 将多个音频按顺序合并为一个新文件（格式相同时直接拼接，不同时重新编码为 MP3）
+#[tauri::command]
+pub async fn merge_audios(
+    ids: Vec<i64>,
+    name: String,
+    app: AppHandle,
+    conn: State<'_, Arc<Mutex<Connection>>>,
+    audio_dir: State<'_, PathBuf>,
+) -> Result<i64, String> {
+    if ids.len() < 2 {
+        return Err(i18n::tr("请至少选择两个音频"));
+    }
+    if name.trim().is_empty() {
+        return Err(i18n::tr("请输入合并后的名称"));
+    }
+
+    let ffmpeg_path = get_ffmpeg_executable_path(Some(&app)).await
+        .ok_or_else(|| i18n::tr("FFmpeg未安装。请将ffmpeg.exe放入tools目录，或点击\"一键安装FFmpeg\"按钮进行安装"))?;
+
+    let sources: Vec<(String, String)> = {
+        let conn = conn.lock().await;
+        ids.iter()
+            .map(|id| {
+                conn.query_row(
+                    "SELECT file_path, format FROM audio_files WHERE id = ?1",
+                    [id],
+                    |row| Ok((row.get(0)?, row.get::<_, String>(1)?.to_lowercase())),
+                )
+                .map_err(|_| i18n::trf("音频不存在: {}", &[&id]))
+            })
+            .collect::<Result<Vec<_>, _>>()?
+    };
+
+    if let Some((missing, _)) = sources.iter().find(|(path, _)| !Path::new(path).exists()) {
+        return Err(i18n::trf("文件不存在: {}", &[&missing]));
+    }
+
+    let same_format = sources.iter().all(|(_, format)| *format == sources[0].1);
+    let format = if same_format { sources[0].1.clone() } else { "mp3".to_string() };
+
+    let layout = crate::storage::load_layout(&*conn.lock().await);
+    let (filename, output_path) = crate::storage::managed_file_path(&layout, &audio_dir, name.trim(), &format, None)?;
+
+    app.emit_all("merge-progress", 0u8).map_err(|e| e.to_string())?;
+
+    let mut cmd = create_command_from_path(&ffmpeg_path);
+    let list_path = audio_dir.join(format!("{}.txt", filename));
+
+    if same_format {
+        // 格式一致：使用 concat demuxer 直接拼接，不重新编码
+        let list = sources
+            .iter()
+            .map(|(path, _)| format!("file '{}'", path.replace('\'', "'\\''")))
+            .collect::<Vec<_>>()
+            .join("\n");
+        std::fs::write(&list_path, list).map_err(|e| i18n::trf("写入合并列表失败: {}", &[&e]))?;
+
+        cmd.arg("-f")
+            .arg("concat")
+            .arg("-safe")
+            .arg("0")
+            .arg("-i")
+            .arg(&list_path)
+            .arg("-vn")
+            .arg("-c")
+            .arg("copy");
+    } else {
+        // 格式不同：统一采样参数后用 concat 滤镜合并并重新编码
+        let mut filter = String::new();
+        for (index, (path, _)) in sources.iter().enumerate() {
+            cmd.arg("-i").arg(path);
+            filter.push_str(&format!("[{}:a]{}[a{}];", index, MERGE_FILTER_FORMAT, index));
+        }
+        for index in 0..sources.len() {
+            filter.push_str(&format!("[a{}]", index));
+        }
+        filter.push_str(&format!("concat=n={}:v=0:a=1[out]", sources.len()));
+
+        cmd.arg("-filter_complex")
+            .arg(filter)
+            .arg("-map")
+            .arg("[out]")
+            .arg("-acodec")
+            .arg("libmp3lame")
+            .arg("-ab")
+            .arg("128k");
+    }
+
+    let output = cmd
+        .arg("-y")
+        .arg(&output_path)
+        .output()
+        .map_err(|e| i18n::trf("执行FFmpeg命令失败: {}", &[&e]));
+    let _ = std::fs::remove_file(&list_path);
+    let output = output?;
+
+    app.emit_all("merge-progress", 90u8).map_err(|e| e.to_string())?;
+
+    if !output.status.success() || !output_path.exists() {
+        let _ = std::fs::remove_file(&output_path);
+        return Err(i18n::trf("合并失败: {}", &[&String::from_utf8_lossy(&output.stderr)]));
+    }
+
+    let file_size = std::fs::metadata(&output_path)
+        .map(|m| m.len() as i64)
+        .unwrap_or(0);
+    let duration = get_audio_duration(&output_path);
+
+    let conn = conn.lock().await;
+    conn.execute(
+        "INSERT INTO audio_files (filename, original_name, file_path, file_size, duration, format, upload_date)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        (
+            &filename,
+            name.trim(),
+            output_path.to_str().unwrap(),
+            file_size,
+            duration,
+            &format,
+            chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+        ),
+    )
+    .map_err(|e| i18n::trf("保存到数据库失败: {}", &[&e]))?;
+
+    let id = conn.last_insert_rowid();
+    app.state::<LibraryEvents>().added([id]);
+    app.emit_all("merge-progress", 100u8).map_err(|e| e.to_string())?;
+
+    Ok(id)
+}
@@ -0,0 +1,371 @@
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use rusqlite::Connection;
+use tauri::State;
+use chrono::Duration;
+use crate::stats::{self, ExecutionRecord, ListeningSession};
+use crate::xlsx::{Cell, Sheet};
+use crate::{i18n, weekday, xlsx};
+
+// 打印用的星期顺序（周一在前），值为 weekday 模块的星期编号
+const GRID_DAYS: [(u32, &str); 7] = [
+    (1, "周一"),
+    (2, "周二"),
+    (3, "周三"),
+    (4, "周四"),
+    (5, "周五"),
+    (6, "周六"),
+    (0, "周日"),
+];
+
+// 一次最多导出多少天的历史记录
+const MAX_HISTORY_EXPORT_DAYS: i64 = 366;
+
+struct ScheduleEntry {
+    time: String,
+    name: String,
+    repeat_mode: String,
+    custom_days: Option<String>,
+    start_date: Option<String>,
+    end_date: Option<String>,
+    playlist_id: i64,
+    playlist_name: String,
+    weekday_playlists: Option<String>,
+}
+
+/// 周课表的一行：某个时间点在每一天要执行的任务（“任务名（播放列表）”）
+struct GridRow {
+    time: String,
+    cells: Vec<Vec<String>>,
+}
+
+fn load_entries(conn: &Connection) -> rusqlite::Result<Vec<ScheduleEntry>> {
+    let mut stmt = conn.prepare(
+        "SELECT printf('%02d:%02d', st.hour, st.minute), st.name, st.repeat_mode, st.custom_days,
+                st.playlist_id, p.name, st.weekday_playlists, st.start_date, st.end_date
+         FROM scheduled_tasks st
+         JOIN playlists p ON st.playlist_id = p.id
+         WHERE st.is_enabled = 1 AND st.deleted_at IS NULL AND p.deleted_at IS NULL AND st.action = 'play'
+           AND NOT (st.repeat_mode = 'once' AND EXISTS(SELECT 1 FROM execution_history eh WHERE eh.task_id = st.id))
+           AND (st.end_date IS NULL OR st.end_date >= DATE('now', 'localtime'))
+         ORDER BY st.hour, st.minute, st.priority DESC",
+    )?;
+    let entries = stmt
+        .query_map([], |row| {
+            Ok(ScheduleEntry {
+                time: row.get(0)?,
+                name: row.get(1)?,
+                repeat_mode: row.get(2)?,
+                custom_days: row.get(3)?,
+                playlist_id: row.get(4)?,
+                playlist_name: row.get(5)?,
+                weekday_playlists: row.get(6)?,
+                start_date: row.get(7)?,
+                end_date: row.get(8)?,
+            })
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    Ok(entries)
+}
+
+/// 所有播放列表的名称（星期映射中可能引用任务默认播放列表以外的列表）
+fn load_playlist_names(conn: &Connection) -> rusqlite::Result<Vec<(i64, String)>> {
+    let mut stmt = conn.prepare("SELECT id, name FROM playlists")?;
+    let playlists = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    Ok(playlists)
+}
+
+/// 有效日期范围的说明，如“2024-07-01 至 2024-08-31”，不限日期时为 None
+fn date_range_label(start_date: Option<&str>, end_date: Option<&str>) -> Option<String> {
+    match (start_date, end_date) {
+        (Some(start), Some(end)) => Some(format!("{} 至 {}", start, end)),
+        (Some(start), None) => Some(format!("{} 起", start)),
+        (None, Some(end)) => Some(format!("至 {}", end)),
+        (None, None) => None,
+    }
+}
+
+/// 按时间汇总为周课表，单次任务、指定日期的任务和 cron 任务单独列出
+fn build_grid(entries: &[ScheduleEntry], playlists: &[(i64, String)]) -> (Vec<GridRow>, Vec<String>) {
+    let mut rows: Vec<GridRow> = Vec::new();
+    let mut once = Vec::new();
+
+    for entry in entries {
+        if entry.repeat_mode == "once" {
+            once.push(format!("{} {}（{}）", entry.time, entry.name, entry.playlist_name));
+            continue;
+        }
+        if entry.repeat_mode == "date" {
+            let date = entry.start_date.as_deref().unwrap_or_default();
+            once.push(format!("{} {} {}（{}）", date, entry.time, entry.name, entry.playlist_name));
+            continue;
+        }
+        if entry.repeat_mode == "cron" {
+            let expression = entry.custom_days.as_deref().unwrap_or_default();
+            once.push(format!("cron {} {}（{}）", expression, entry.name, entry.playlist_name));
+            continue;
+        }
+        let range = date_range_label(entry.start_date.as_deref(), entry.end_date.as_deref());
+
+        if rows.last().map(|row| row.time != entry.time).unwrap_or(true) {
+            rows.push(GridRow {
+                time: entry.time.clone(),
+                cells: vec![Vec::new(); GRID_DAYS.len()],
+            });
+        }
+        let row = rows.last_mut().unwrap();
+
+        for (cell, (day, _)) in row.cells.iter_mut().zip(GRID_DAYS) {
+            if !weekday::runs_on_weekday(&entry.repeat_mode, entry.custom_days.as_deref(), day) {
+                continue;
+            }
+            let playlist_id = weekday::playlist_for_weekday(entry.weekday_playlists.as_deref(), day, entry.playlist_id);
+            let playlist_name = playlists
+                .iter()
+                .find(|(id, _)| *id == playlist_id)
+                .map(|(_, name)| name.as_str())
+                .unwrap_or(&entry.playlist_name);
+            match &range {
+                Some(range) => cell.push(format!("{}（{}，{}）", entry.name, playlist_name, range)),
+                None => cell.push(format!("{}（{}）", entry.name, playlist_name)),
+            }
+        }
+    }
+
+    (rows, once)
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn render_csv(rows: &[GridRow], once: &[String]) -> String {
+    // 带 BOM，Excel 打开中文不乱码
+    let mut csv = String::from("\u{feff}时间");
+    for (_, name) in GRID_DAYS {
+        csv.push(',');
+        csv.push_str(name);
+    }
+    csv.push_str("\r\n");
+
+    for row in rows {
+        csv.push_str(&row.time);
+        for cell in &row.cells {
+            csv.push(',');
+            csv.push_str(&csv_field(&cell.join(" / ")));
+        }
+        csv.push_str("\r\n");
+    }
+
+    if !once.is_empty() {
+        csv.push_str("\r\n其他任务\r\n");
+        for item in once {
+            csv.push_str(&csv_field(item));
+            csv.push_str("\r\n");
+        }
+    }
+
+    csv
+}
+
+fn html_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn render_html(rows: &[GridRow], once: &[String]) -> String {
+    let mut html = String::from(
+        "<!DOCTYPE html>\n<html lang=\"zh-CN\">\n<head>\n<meta charset=\"utf-8\">\n<title>磨耳朵播放时间表</title>\n\
+         <style>\n\
+         body { font-family: sans-serif; margin: 24px; }\n\
+         table { border-collapse: collapse; width: 100%; }\n\
+         th, td { border: 1px solid #999; padding: 6px 8px; font-size: 14px; vertical-align: top; }\n\
+         th { background: #eef3fb; }\n\
+         td.time { font-weight: bold; white-space: nowrap; }\n\
+         @media print { body { margin: 0; } }\n\
+         </style>\n</head>\n<body>\n<h1>磨耳朵播放时间表</h1>\n<table>\n<tr><th>时间</th>",
+    );
+    for (_, name) in GRID_DAYS {
+        html.push_str(&format!("<th>{}</th>", name));
+    }
+    html.push_str("</tr>\n");
+
+    for row in rows {
+        html.push_str(&format!("<tr><td class=\"time\">{}</td>", row.time));
+        for cell in &row.cells {
+            let items: Vec<String> = cell.iter().map(|item| html_escape(item)).collect();
+            html.push_str(&format!("<td>{}</td>", items.join("<br>")));
+        }
+        html.push_str("</tr>\n");
+    }
+    html.push_str("</table>\n");
+
+    if !once.is_empty() {
+        html.push_str("<h2>其他任务</h2>\n<ul>\n");
+        for item in once {
+            html.push_str(&format!("<li>{}</li>\n", html_escape(item)));
+        }
+        html.push_str("</ul>\n");
+    }
+
+    html.push_str(&format!(
+        "<p style=\"color:#888;font-size:12px\">导出时间：{}</p>\n</body>\n</html>\n",
+        chrono::Local::now().format("%Y-%m-%d %H:%M")
+    ));
+    html
+}
+
+/// 导出所有启用任务的周时间表（format: csv / html），写入 path 并返回该路径
+#[tauri::command]
+pub async fn export_schedule(
+    format: String,
+    path: String,
+    conn: State<'_, Arc<Mutex<Connection>>>,
+) -> Result<String, String> {
+    let (entries, playlists) = {
+        let conn = conn.lock().await;
+        let entries = load_entries(&conn).map_err(|e| e.to_string())?;
+        let playlists = load_playlist_names(&conn).map_err(|e| e.to_string())?;
+        (entries, playlists)
+    };
+
+    let (rows, once) = build_grid(&entries, &playlists);
+    let content = match format.as_str() {
+        "csv" => render_csv(&rows, &once),
+        "html" => render_html(&rows, &once),
+        _ => return Err(i18n::trf("不支持的导出格式: {}", &[&format])),
+    };
+
+    std::fs::write(&path, content).map_err(|e| i18n::trf("写入文件失败: {}", &[&e]))?;
+    Ok(path)
+}
+
+fn execution_status_name(status: &str) -> &str {
+    match status {
+        "completed" => "完成",
+        "failed" => "失败",
+        "started" => "进行中",
+        other => other,
+    }
+}
+
+fn minutes(secs: i64) -> f64 {
+    (secs as f64 / 6.0).round() / 10.0
+}
+
+/// 生成历史记录工作簿：汇总表（每天一行及合计），以及每个有记录的日期一个工作表
+fn build_history_sheets(
+    start: chrono::NaiveDate,
+    end: chrono::NaiveDate,
+    executions: &[ExecutionRecord],
+    sessions: &[ListeningSession],
+) -> Vec<Sheet> {
+    let tracks: Vec<_> = sessions.iter().flat_map(|session| &session.tracks).collect();
+    let mut summary = Sheet::new("汇总").widths(&[14.0, 14.0, 12.0, 12.0, 18.0]);
+    summary.bold_row(vec![
+        format!("磨耳朵收听记录 {} 至 {}", start.format("%Y-%m-%d"), end.format("%Y-%m-%d")).into(),
+    ]);
+    summary.blank_row();
+    summary.bold_row(vec!["日期".into(), "定时任务执行".into(), "执行成功".into(), "播放曲目数".into(), "收听时长（分钟）".into()]);
+
+    let mut day_sheets = Vec::new();
+    let (mut total_runs, mut total_completed, mut total_tracks, mut total_secs) = (0i64, 0i64, 0i64, 0i64);
+    let mut day = start;
+    while day <= end {
+        let date = day.format("%Y-%m-%d").to_string();
+        let day_executions: Vec<&ExecutionRecord> =
+            executions.iter().filter(|e| e.execution_time.starts_with(&date)).collect();
+        let day_tracks: Vec<_> = tracks.iter().filter(|t| t.play_time.starts_with(&date)).collect();
+        let completed = day_executions.iter().filter(|e| e.status == "completed").count() as i64;
+        let listened_secs: i64 = day_tracks.iter().map(|t| t.listened_secs).sum();
+
+        summary.row(vec![
+            date.clone().into(),
+            (day_executions.len() as i64).into(),
+            completed.into(),
+            (day_tracks.len() as i64).into(),
+            minutes(listened_secs).into(),
+        ]);
+        total_runs += day_executions.len() as i64;
+        total_completed += completed;
+        total_tracks += day_tracks.len() as i64;
+        total_secs += listened_secs;
+
+        if !day_executions.is_empty() || !day_tracks.is_empty() {
+            let mut sheet = Sheet::new(&date).widths(&[12.0, 30.0, 20.0, 18.0]);
+            sheet.bold_row(vec![format!("{} 收听记录", date).into()]);
+            sheet.blank_row();
+            sheet.bold_row(vec!["定时任务".into()]);
+            sheet.bold_row(vec!["时间".into(), "任务".into(), "播放列表".into(), "状态".into()]);
+            for execution in &day_executions {
+                sheet.row(vec![
+                    execution.execution_time[11..].to_string().into(),
+                    execution.task_name.clone().unwrap_or_else(|| "已删除的任务".to_string()).into(),
+                    execution.playlist_name.clone().into(),
+                    execution_status_name(&execution.status).into(),
+                ]);
+            }
+            sheet.blank_row();
+            sheet.bold_row(vec!["播放记录".into()]);
+            sheet.bold_row(vec!["时间".into(), "音频".into(), "播放列表".into(), "收听时长（分钟）".into()]);
+            for track in &day_tracks {
+                sheet.row(vec![
+                    track.play_time[11..].to_string().into(),
+                    track.audio_name.clone().into(),
+                    track.playlist_name.clone().unwrap_or_else(|| "单独播放".to_string()).into(),
+                    minutes(track.listened_secs).into(),
+                ]);
+            }
+            sheet.bold_row(vec!["合计".into(), format!("{} 首", day_tracks.len()).into(), Cell::Empty, minutes(listened_secs).into()]);
+            day_sheets.push(sheet);
+        }
+        day += Duration::days(1);
+    }
+
+    summary.bold_row(vec![
+        "合计".into(),
+        total_runs.into(),
+        total_completed.into(),
+        total_tracks.into(),
+        minutes(total_secs).into(),
+    ]);
+    summary.blank_row();
+    summary.row(vec![format!("导出时间：{}", chrono::Local::now().format("%Y-%m-%d %H:%M")).into()]);
+
+    std::iter::once(summary).chain(day_sheets).collect()
+}
+
+/// 导出本地日期范围内的任务执行记录和播放记录为 Excel（汇总表 + 每天一个工作表），写入 path 并返回该路径
+#[tauri::command]
+pub async fn export_history_xlsx(
+    start_date: Option<String>,
+    end_date: Option<String>,
+    path: String,
+    conn: State<'_, Arc<Mutex<Connection>>>,
+) -> Result<String, String> {
+    let (start, end) = stats::parse_date_range(start_date.as_deref(), end_date.as_deref())?;
+    if (end - start).num_days() >= MAX_HISTORY_EXPORT_DAYS {
+        return Err(i18n::trf("导出范围不能超过{}天", &[&MAX_HISTORY_EXPORT_DAYS]));
+    }
+
+    let (executions, sessions) = {
+        let conn = conn.lock().await;
+        let (from, to) = (start.format("%Y-%m-%d").to_string(), end.format("%Y-%m-%d").to_string());
+        let executions = stats::load_executions(&conn, &from, &to).map_err(|e| e.to_string())?;
+        let sessions = stats::load_sessions(&conn, &from, &to).map_err(|e| e.to_string())?;
+        (executions, sessions)
+    };
+
+    let sheets = build_history_sheets(start, end, &executions, &sessions);
+    xlsx::write_workbook(&path, &sheets)?;
+    Ok(path)
+}
@@ -0,0 +1,100 @@
+use serde::Serialize;
+use std::fs;
+use std::io::BufReader;
+use std::path::Path;
+use rodio::Decoder;
+use crate::i18n;
+
+/// 音频库支持的一种格式
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct AudioFormat {
+    /// 小写扩展名（不含点）
+    pub extension: &'static str,
+    pub label: &'static str,
+    /// 播放器（rodio / symphonia）能否直接解码；不能时导入时用 FFmpeg 转码为 MP3
+    pub native: bool,
+    pub mime_type: &'static str,
+}
+
+/// 支持导入的全部音频格式
+pub const AUDIO_FORMATS: [AudioFormat; 9] = [
+    AudioFormat { extension: "mp3", label: "MP3", native: true, mime_type: "audio/mpeg" },
+    AudioFormat { extension: "wav", label: "WAV", native: true, mime_type: "audio/wav" },
+    AudioFormat { extension: "ogg", label: "OGG Vorbis", native: true, mime_type: "audio/ogg" },
+    AudioFormat { extension: "flac", label: "FLAC", native: true, mime_type: "audio/flac" },
+    AudioFormat { extension: "m4a", label: "M4A (AAC)", native: true, mime_type: "audio/mp4" },
+    AudioFormat { extension: "aac", label: "AAC", native: true, mime_type: "audio/aac" },
+    AudioFormat { extension: "opus", label: "Opus", native: false, mime_type: "audio/ogg" },
+    AudioFormat { extension: "wma", label: "WMA", native: false, mime_type: "audio/x-ms-wma" },
+    AudioFormat { extension: "ape", label: "APE", native: false, mime_type: "audio/x-ape" },
+];
+
+/// 查找扩展名对应的格式（忽略大小写和前导点）
+pub fn find_format(extension: &str) -> Option<&'static AudioFormat> {
+    let extension = extension.trim_start_matches('.').to_lowercase();
+    AUDIO_FORMATS.iter().find(|format| format.extension == extension)
+}
+
+/// 是否为支持导入的格式
+pub fn is_supported(extension: &str) -> bool {
+    find_format(extension).is_some()
+}
+
+/// 播放器能否直接解码该格式
+pub fn is_native(extension: &str) -> bool {
+    find_format(extension).is_some_and(|format| format.native)
+}
+
+/// 是否需要在导入时转码为 MP3
+pub fn needs_transcode(extension: &str) -> bool {
+    find_format(extension).is_some_and(|format| !format.native)
+}
+
+/// 文件路径是否为支持的音频格式
+pub fn is_supported_path(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(is_supported)
+}
+
+/// 文件路径对应的 MIME 类型，未知格式返回 application/octet-stream
+pub fn mime_type_for_path(path: &Path) -> &'static str {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .and_then(find_format)
+        .map_or("application/octet-stream", |format| format.mime_type)
+}
+
+/// 检查文件能否被播放器解码
+pub fn is_decodable(path: &Path) -> bool {
+    match fs::File::open(path) {
+        Ok(file) => Decoder::new(BufReader::new(file)).is_ok(),
+        Err(_) => false,
+    }
+}
+
+/// 判断导入的文件是否需要转码为 MP3（扩展名不被直接支持，或文件实际无法解码，如 ALAC 编码的 M4A）。
+/// 需要转码但未开启自动转码时返回错误
+pub fn check_import(path: &Path, auto_transcode: bool) -> Result<bool, String> {
+    let extension = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .ok_or_else(|| i18n::tr("无法获取文件扩展名"))?;
+    if !is_supported(extension) {
+        return Err(i18n::tr("不支持的音频格式"));
+    }
+
+    if !needs_transcode(extension) && is_decodable(path) {
+        return Ok(false);
+    }
+    if !auto_transcode {
+        return Err(i18n::tr("播放器无法直接解码该文件，请在设置中开启“导入时自动转码”"));
+    }
+    Ok(true)
+}
+
+/// 供前端查询支持的格式（文件选择器过滤、提示文字等）
+#[tauri::command]
+pub async fn get_supported_formats() -> Result<Vec<AudioFormat>, String> {
+    Ok(AUDIO_FORMATS.to_vec())
+}
@@ -0,0 +1,108 @@
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use rusqlite::{Connection, OptionalExtension};
+use chrono::NaiveDateTime;
+use tauri::State;
+use crate::i18n;
+use crate::player::TrackLevel;
+use crate::scheduler;
+
+// 任务交接的设置保存在 app_settings 中，后台服务进程也按此交接
+const SETTING_KEY: &str = "task_handoff";
+// 下一个任务在上一个任务结束前后多少秒内开始算作紧接着
+const BACK_TO_BACK_SECS: i64 = 60;
+// 交接后下一个任务迟迟没有开始（如播放列表为空）时，多少秒后渐弱停止交接的音频
+pub const FALLBACK_SECS: u64 = 180;
+// 交叉淡入淡出时长的范围（秒）
+const MIN_CROSSFADE_SECS: u32 = 1;
+const MAX_CROSSFADE_SECS: u32 = 30;
+// 响度匹配最多调整的分贝数，避免分析结果异常时音量突变
+const MAX_MATCH_DB: f64 = 12.0;
+
+/// 任务交接：一个任务到点结束、紧接着另一个播放任务开始时，不再先停止再开始，
+/// 而是让上一个任务的音频与下一个任务的第一首交叉淡入淡出
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HandoffSettings {
+    pub enabled: bool,
+    /// 交叉淡入淡出的时长（秒），代替下一个任务的渐强
+    pub crossfade_secs: u32,
+}
+
+impl Default for HandoffSettings {
+    fn default() -> Self {
+        Self { enabled: false, crossfade_secs: 8 }
+    }
+}
+
+pub fn load_settings(conn: &Connection) -> HandoffSettings {
+    conn.query_row(
+        "SELECT value FROM app_settings WHERE key = ?1",
+        [SETTING_KEY],
+        |row| row.get::<_, String>(0),
+    )
+    .ok()
+    .and_then(|value| serde_json::from_str(&value).ok())
+    .unwrap_or_default()
+}
+
+/// 开启交接时，是否有另一个播放任务紧接在 task_id 的结束时刻 end 开始（且不会因暂停而跳过）；
+/// 其间触发的停止、降低音量任务或暂停中的任务不影响判断
+pub fn is_back_to_back(conn: &Connection, task_id: i64, end: NaiveDateTime) -> bool {
+    load_settings(conn).enabled
+        && scheduler::has_play_task_near(conn, task_id, end, BACK_TO_BACK_SECS).unwrap_or_else(|e| {
+            eprintln!("[Handoff] 查询下一个任务失败: {}", e);
+            false
+        })
+}
+
+/// 交叉淡入淡出时上一个任务的音量向下一个任务靠拢的倍数：两者的响度（分析得到的响度、
+/// 播放时的增益和任务音量）之差；音频还没有分析响度时只按任务音量匹配
+pub fn loudness_match_factor(conn: &Connection, from: TrackLevel, to: TrackLevel) -> f32 {
+    let loudness = |audio_id: Option<i64>| -> Option<f64> {
+        conn.query_row("SELECT loudness_db FROM audio_files WHERE id = ?1", [audio_id?], |row| row.get(0))
+            .optional()
+            .ok()
+            .flatten()
+            .flatten()
+    };
+    let track_db = match (loudness(from.audio_id), loudness(to.audio_id)) {
+        (Some(from_db), Some(to_db)) => (to_db + to.gain_db) - (from_db + from.gain_db),
+        _ => 0.0,
+    };
+    let volume_db = match from.volume > 0.0 && to.volume > 0.0 {
+        true => 20.0 * (to.volume as f64 / from.volume as f64).log10(),
+        false => 0.0,
+    };
+    let match_db = (track_db + volume_db).clamp(-MAX_MATCH_DB, MAX_MATCH_DB);
+    10f32.powf(match_db as f32 / 20.0)
+}
+
+#[tauri::command]
+pub async fn get_task_handoff(
+    conn: State<'_, Arc<Mutex<Connection>>>,
+) -> Result<HandoffSettings, String> {
+    Ok(load_settings(&*conn.lock().await))
+}
+
+#[tauri::command]
+pub async fn set_task_handoff(
+    settings: HandoffSettings,
+    conn: State<'_, Arc<Mutex<Connection>>>,
+) -> Result<(), String> {
+    if !(MIN_CROSSFADE_SECS..=MAX_CROSSFADE_SECS).contains(&settings.crossfade_secs) {
+        return Err(i18n::trf(
+            "交叉淡入淡出时长必须在{}-{}秒之间",
+            &[&MIN_CROSSFADE_SECS, &MAX_CROSSFADE_SECS],
+        ));
+    }
+    let value = serde_json::to_string(&settings).map_err(|e| e.to_string())?;
+    conn.lock()
+        .await
+        .execute(
+            "INSERT OR REPLACE INTO app_settings (key, value) VALUES (?1, ?2)",
+            (SETTING_KEY, &value),
+        )
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
@@ -0,0 +1,242 @@
+//! 节假日日历：手动维护的日期，或从公共假日列表（.ics 日历，或每行“YYYY-MM-DD,名称”的文本/CSV）导入。
+//! 设置了“节假日不执行”的任务（如工作日的起床播放）在这些日子跳过。
+
+use serde::Serialize;
+use std::collections::HashSet;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use rusqlite::Connection;
+use chrono::{Duration, NaiveDate};
+use tauri::State;
+use crate::scheduler::SchedulerWake;
+use crate::{i18n, ical};
+
+const DATE_FORMAT: &str = "%Y-%m-%d";
+// 导入的一个事件最多展开的天数，避免日历中的长期事件把几个月都算作假日
+const MAX_EVENT_DAYS: i64 = 31;
+
+#[derive(Debug, Serialize)]
+pub struct Holiday {
+    pub date: String,
+    pub name: String,
+    /// manual（手动添加）/ import（从假日列表导入）
+    pub source: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct HolidayImportResult {
+    pub imported: usize,
+    /// 无法识别的行或事件
+    pub skipped: usize,
+}
+
+/// 所有节假日
+pub fn load_holidays(conn: &Connection) -> HashSet<NaiveDate> {
+    let dates = conn
+        .prepare("SELECT date FROM holidays")
+        .and_then(|mut stmt| stmt.query_map([], |row| row.get::<_, String>(0))?.collect::<rusqlite::Result<Vec<_>>>())
+        .unwrap_or_else(|e| {
+            eprintln!("[Holiday] 读取节假日失败: {}", e);
+            Vec::new()
+        });
+    dates
+        .iter()
+        .filter_map(|date| NaiveDate::parse_from_str(date, DATE_FORMAT).ok())
+        .collect()
+}
+
+/// 指定日期是否在节假日日历中
+pub fn is_holiday(conn: &Connection, date: NaiveDate) -> bool {
+    conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM holidays WHERE date = ?1)",
+        [date.format(DATE_FORMAT).to_string()],
+        |row| row.get(0),
+    )
+    .unwrap_or(false)
+}
+
+/// 文本/CSV 格式的假日列表：每行一个日期，可在逗号、制表符或空格后跟名称；# 开头的行和表头忽略
+fn parse_text_list(content: &str) -> (Vec<(Option<String>, NaiveDate, NaiveDate)>, usize) {
+    let mut holidays = Vec::new();
+    let mut skipped = 0;
+    for line in content.lines().map(|line| line.trim_start_matches('\u{feff}').trim()) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (date, name) = line.split_once([',', '\t', ' ']).unwrap_or((line, ""));
+        let name = name.trim().trim_matches('"').trim();
+        match NaiveDate::parse_from_str(date.trim().trim_matches('"'), DATE_FORMAT) {
+            Ok(date) => holidays.push(((!name.is_empty()).then(|| name.to_string()), date, date)),
+            // 表头（如 date,name）不算跳过
+            Err(_) if !date.chars().any(|c| c.is_ascii_digit()) => {}
+            Err(_) => skipped += 1,
+        }
+    }
+    (holidays, skipped)
+}
+
+/// 把导入的假日（名称, 第一天, 最后一天）展开为每一天，超过 MAX_EVENT_DAYS 天的事件跳过；返回 (日期, 名称) 和跳过的个数
+fn expand_days(holidays: Vec<(Option<String>, NaiveDate, NaiveDate)>) -> (Vec<(NaiveDate, String)>, usize) {
+    let mut days = Vec::new();
+    let mut skipped = 0;
+    for (name, first, last) in holidays {
+        if (last - first).num_days() >= MAX_EVENT_DAYS {
+            skipped += 1;
+            continue;
+        }
+        let name = name.as_deref().unwrap_or("").trim().to_string();
+        let mut date = first;
+        while date <= last {
+            days.push((date, name.clone()));
+            date += Duration::days(1);
+        }
+    }
+    (days, skipped)
+}
+
+#[tauri::command]
+pub async fn get_holidays(
+    conn: State<'_, Arc<Mutex<Connection>>>,
+) -> Result<Vec<Holiday>, String> {
+    let conn = conn.lock().await;
+    let mut stmt = conn
+        .prepare("SELECT date, name, source FROM holidays ORDER BY date")
+        .map_err(|e| e.to_string())?;
+    let holidays = stmt
+        .query_map([], |row| Ok(Holiday { date: row.get(0)?, name: row.get(1)?, source: row.get(2)? }))
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+    Ok(holidays)
+}
+
+/// 添加节假日（同一天已有时更新名称）
+#[tauri::command]
+pub async fn add_holiday(
+    date: String,
+    name: String,
+    conn: State<'_, Arc<Mutex<Connection>>>,
+    wake: State<'_, SchedulerWake>,
+) -> Result<(), String> {
+    let date = NaiveDate::parse_from_str(date.trim(), DATE_FORMAT).map_err(|_| i18n::trf("无效的日期: {}", &[&date]))?;
+    conn.lock()
+        .await
+        .execute(
+            "INSERT OR REPLACE INTO holidays (date, name, source) VALUES (?1, ?2, 'manual')",
+            (date.format(DATE_FORMAT).to_string(), name.trim()),
+        )
+        .map_err(|e| e.to_string())?;
+    wake.notify();
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn delete_holiday(
+    date: String,
+    conn: State<'_, Arc<Mutex<Connection>>>,
+    wake: State<'_, SchedulerWake>,
+) -> Result<(), String> {
+    conn.lock()
+        .await
+        .execute("DELETE FROM holidays WHERE date = ?1", [&date])
+        .map_err(|e| e.to_string())?;
+    wake.notify();
+    Ok(())
+}
+
+/// 导入公共假日列表（.ics 日历中的全天事件，或文本/CSV 列表）；已有的日期保留手动添加的名称
+#[tauri::command]
+pub async fn import_holidays(
+    path: String,
+    conn: State<'_, Arc<Mutex<Connection>>>,
+    wake: State<'_, SchedulerWake>,
+) -> Result<HolidayImportResult, String> {
+    let content = std::fs::read_to_string(&path).map_err(|e| i18n::trf("读取文件失败: {}", &[&e]))?;
+    let (holidays, mut skipped) = match content.trim_start_matches('\u{feff}').trim_start().starts_with("BEGIN:VCALENDAR") {
+        true => (ical::parse_all_day_events(&content), 0),
+        false => parse_text_list(&content),
+    };
+    if holidays.is_empty() {
+        return Err(i18n::tr("文件中没有可导入的节假日"));
+    }
+
+    let (days, too_long) = expand_days(holidays);
+    skipped += too_long;
+
+    let mut conn = conn.lock().await;
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    let mut imported = 0;
+    for (date, name) in days {
+        imported += tx
+            .execute(
+                "INSERT OR IGNORE INTO holidays (date, name, source) VALUES (?1, ?2, 'import')",
+                (date.format(DATE_FORMAT).to_string(), name),
+            )
+            .map_err(|e| e.to_string())?;
+    }
+    tx.commit().map_err(|e| e.to_string())?;
+    wake.notify();
+
+    Ok(HolidayImportResult { imported, skipped })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(value: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(value, DATE_FORMAT).unwrap()
+    }
+
+    #[test]
+    fn parses_text_lists() {
+        let content = "\u{feff}date,name\n# 2024 年\n2024-01-01,元旦\n\"2024-05-01\",\"劳动节\"\n2024-06-10\t端午节\n2024-09-17 中秋节\n2024-10-01\n";
+        let (holidays, skipped) = parse_text_list(content);
+        assert_eq!(skipped, 0);
+        let parsed: Vec<(Option<&str>, NaiveDate)> = holidays.iter().map(|(name, first, _)| (name.as_deref(), *first)).collect();
+        assert_eq!(
+            parsed,
+            [
+                (Some("元旦"), date("2024-01-01")),
+                (Some("劳动节"), date("2024-05-01")),
+                (Some("端午节"), date("2024-06-10")),
+                (Some("中秋节"), date("2024-09-17")),
+                (None, date("2024-10-01")),
+            ]
+        );
+        assert!(holidays.iter().all(|(_, first, last)| first == last));
+    }
+
+    #[test]
+    fn counts_invalid_lines() {
+        let (holidays, skipped) = parse_text_list("2024-02-30,不存在\n2024/05/01,斜杠\n20240501\n2024-05-02,调休\n节日,没有日期\n");
+        assert_eq!(holidays.len(), 1);
+        assert_eq!(skipped, 3);
+    }
+
+    #[test]
+    fn expands_ranges_and_skips_long_events() {
+        let (days, skipped) = expand_days(vec![
+            (Some(" 国庆节 ".to_string()), date("2024-10-01"), date("2024-10-07")),
+            (None, date("2024-12-31"), date("2025-01-01")),
+            (Some("暑假".to_string()), date("2024-07-01"), date("2024-08-31")),
+        ]);
+        assert_eq!(skipped, 1);
+        assert_eq!(days.len(), 9);
+        assert_eq!(days[0], (date("2024-10-01"), "国庆节".to_string()));
+        assert_eq!(days[6], (date("2024-10-07"), "国庆节".to_string()));
+        assert_eq!(days[8], (date("2025-01-01"), String::new()));
+    }
+
+    #[test]
+    fn reads_all_day_ranges_from_calendars() {
+        // DTEND 为不含的结束日期，带时间的事件不是假日
+        let ics = "BEGIN:VCALENDAR\nBEGIN:VEVENT\nSUMMARY:春节\nDTSTART;VALUE=DATE:20250128\nDTEND;VALUE=DATE:20250205\nEND:VEVENT\n\
+                   BEGIN:VEVENT\nSUMMARY:会议\nDTSTART:20250110T090000\nEND:VEVENT\nEND:VCALENDAR\n";
+        let (days, skipped) = expand_days(ical::parse_all_day_events(ics));
+        assert_eq!(skipped, 0);
+        assert_eq!(days.first().map(|(day, _)| *day), Some(date("2025-01-28")));
+        assert_eq!(days.last().map(|(day, _)| *day), Some(date("2025-02-04")));
+        assert_eq!(days.len(), 8);
+    }
+}
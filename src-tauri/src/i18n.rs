@@ -0,0 +1,315 @@
+use std::sync::atomic::{AtomicU8, Ordering};
+use rusqlite::Connection;
+use tauri::AppHandle;
+use crate::{countdown, pause, player};
+
+// 界面语言保存在 app_settings 中（"zh" / "en"）
+pub const LANGUAGE_KEY: &str = "language";
+
+const LANG_ZH: u8 = 0;
+const LANG_EN: u8 = 1;
+
+// 当前语言缓存在进程内，返回给前端的错误不必每次都查数据库
+static CURRENT_LANGUAGE: AtomicU8 = AtomicU8::new(LANG_ZH);
+
+/// 翻译表：以中文原文为键（源码里的字符串保持中文），{} 为占位符
+const CATALOG: &[(&str, &str)] = &[
+    // 托盘菜单
+    ("显示主窗口", "Show Window"),
+    ("隐藏窗口", "Hide Window"),
+    ("播放/暂停", "Play/Pause"),
+    ("静音/取消静音", "Mute/Unmute"),
+    ("暂停所有定时任务", "Pause All Scheduled Tasks"),
+    ("恢复定时任务", "Resume Scheduled Tasks"),
+    ("开始 {} 分钟倒计时", "Start {}-Minute Countdown"),
+    ("退出应用", "Quit"),
+    ("下一个任务：{} {}", "Next task: {} {}"),
+    // 通知
+    ("定时任务预检发现问题", "Scheduled task pre-flight check found problems"),
+    ("{} 将在 {} 执行：{}", "{} will run at {}: {}"),
+    ("播放列表不存在或已删除", "Playlist does not exist or has been deleted"),
+    ("播放列表“{}”为空", "Playlist \"{}\" is empty"),
+    ("播放列表“{}”中有 {} 个音频文件丢失：{}", "{1} audio file(s) missing from playlist \"{0}\": {2}"),
+    ("未检测到音频输出设备", "No audio output device detected"),
+    // 错误
+    ("FFmpeg未安装", "FFmpeg is not installed"),
+    ("下载“{}”失败: {}", "Failed to download \"{}\": {}"),
+    ("不支持从该来源刷新", "Refreshing from this source is not supported"),
+    ("不支持的播放模式", "Unsupported play mode"),
+    ("不支持的操作系统", "Unsupported operating system"),
+    ("不支持的音频格式", "Unsupported audio format"),
+    ("不支持的语言", "Unsupported language"),
+    ("不能改为使用正在删除的播放列表", "Cannot retarget tasks to the playlist being deleted"),
+    ("保存目录只能是一层子目录名", "The save folder must be a single sub-folder name"),
+    ("不是有效的日历文件", "Not a valid calendar file"),
+    ("任务不存在", "Task not found"),
+    ("交叉淡入淡出时长必须在{}-{}秒之间", "The crossfade length must be between {} and {} seconds"),
+    ("任务使用的播放列表已被删除，请先恢复播放列表", "The task's playlist has been deleted; restore the playlist first"),
+    ("今天不在任务的重复日期内", "Today is not one of the task's repeat days"),
+    ("今天已经执行过", "Already ran today"),
+    ("今天的收听时长已用完（非重要任务）", "Today's listening time is used up (not a critical task)"),
+    ("今天是节假日", "Today is a holiday"),
+    ("这次触发已经执行过", "Already ran for this trigger"),
+    ("作息时间超过了当天午夜，请调整节数或时长", "The timetable runs past midnight; adjust the number or length of periods"),
+    ("保留月数必须大于0", "The number of months to keep must be greater than 0"),
+    ("儿童模式下不能使用该功能", "This feature is not available in kid mode"),
+    ("儿童模式下只能播放指定的播放列表", "Only the selected playlists can be played in kid mode"),
+    ("读取文件失败: {}", "Failed to read the file: {}"),
+    ("写入文件失败: {}", "Failed to write the file: {}"),
+    ("倍速必须在0.5到2.0之间", "Speed must be between 0.5 and 2.0"),
+    ("倍速必须在0.5到3.0之间", "Speed must be between 0.5 and 3.0"),
+    ("倍速步长需在0.05-1.0之间", "The speed step must be between 0.05 and 1.0"),
+    ("倒计时需在0-1440分钟之间", "Countdown must be between 0 and 1440 minutes"),
+    ("创建归档目录失败: {}", "Failed to create the archive folder: {}"),
+    ("创建录音目录失败: {}", "Failed to create the recordings folder: {}"),
+    ("创建音频目录失败: {}", "Failed to create the audio folder: {}"),
+    ("功能暂未实现", "Not implemented yet"),
+    ("到达触发时间", "Trigger time reached"),
+    ("到达触发时间（重要任务，忽略暂停）", "Trigger time reached (critical task, runs while paused)"),
+    ("午休时长不能为负", "Lunch break length cannot be negative"),
+    ("单次任务已经执行过", "One-time task has already run"),
+    ("周末补播工作日错过的执行", "Weekend makeup for a missed weekday run"),
+    ("只支持 HTTP/HTTPS 地址", "Only HTTP/HTTPS addresses are supported"),
+    ("定时停止需在0-1440分钟之间", "The sleep timer must be between 0 and 1440 minutes"),
+    ("定时任务已暂停（非重要任务）", "Scheduled tasks are paused (not a critical task)"),
+    ("家长密码必须是4到8位数字", "The parental PIN must be 4 to 8 digits"),
+    ("家长密码错误", "Incorrect parental PIN"),
+    ("将播放时长缩短为 {} 分钟", "Shorten the playback to {} minutes"),
+    ("导出范围不能超过{}天", "The export range cannot exceed {} days"),
+    ("已将音量降至{}%", "Volume lowered to {}%"),
+    ("已停止播放", "Playback stopped"),
+    ("已经在录音中", "Already recording"),
+    ("录音模板不存在", "Recording template not found"),
+    ("当前不在对比播放中", "Not in comparison playback"),
+    ("当前没有可重新打乱的播放队列", "There is no playback queue to reshuffle"),
+    ("当前没有正在播放的音频", "Nothing is playing"),
+    ("声道平衡需在-1到1之间", "Balance must be between -1 and 1"),
+    ("备注不能超过{}个字符", "Notes cannot exceed {} characters"),
+    ("开始位置无效", "Invalid start position"),
+    ("开始位置超出音频时长", "Start position is beyond the audio duration"),
+    ("手动执行", "Run manually"),
+    ("提前到 {} 开始", "Start earlier at {}"),
+    ("推迟到 {} 开始", "Start later at {}"),
+    ("播放列表不存在", "Playlist not found"),
+    ("播放列表为空", "Playlist is empty"),
+    ("播放列表项不存在", "Playlist item not found"),
+    ("被重要任务中断", "Interrupted by a critical task"),
+    ("播放完成", "Playback finished"),
+    ("播放失败", "Playback failed"),
+    ("播放器无法直接解码该文件，请在设置中开启“导入时自动转码”", "The player cannot decode this file; enable \"Transcode on import\" in Settings"),
+    ("文件不存在", "File not found"),
+    ("文件校验失败: SHA-256不匹配", "File verification failed: SHA-256 mismatch"),
+    ("文件中没有可导入的节假日", "No holidays to import were found in the file"),
+    ("无法安装FFmpeg，请手动安装", "Unable to install FFmpeg; please install it manually"),
+    ("无法读取音频信息", "Unable to read audio information"),
+    ("提取任务意外中断", "The extraction job ended unexpectedly"),
+    ("无效的任务类型: {}", "Invalid task type: {}"),
+    ("无效的网络地址", "Invalid web address"),
+    ("无效的日期: {}", "Invalid date: {}"),
+    ("无效的时间: {}", "Invalid time: {}"),
+    ("无效的 cron 表达式: {}", "Invalid cron expression: {}"),
+    ("无法解码网络音频: {}", "Unable to decode the web audio: {}"),
+    ("暂停时长必须大于0", "Pause duration must be greater than 0"),
+    ("最大音量需在1-100之间", "Maximum volume must be between 1 and 100"),
+    ("未在录音中", "Not recording"),
+    ("未检测到可用于分割的静音段，请调整静音阈值或最短时长", "No silence found to split on; adjust the silence threshold or minimum length"),
+    ("未解析到带时间标签的歌词", "No timestamped lyric lines found"),
+    ("每周目标需在1-{}遍之间", "The weekly target must be between 1 and {} times"),
+    ("每日收听时长需在0-1440分钟之间", "The daily listening limit must be between 0 and 1440 minutes"),
+    ("每节课时长必须大于0，课间时长不能为负", "Period length must be greater than 0 and breaks cannot be negative"),
+    ("每首最少播放次数需在1-20之间", "Minimum plays per track must be between 1 and 20"),
+    ("生成文件夹播放列表失败: {}", "Failed to create folder playlists: {}"),
+    ("结束位置必须大于开始位置", "End position must be after the start position"),
+    ("结束日期不能早于开始日期", "End date cannot be earlier than the start date"),
+    ("网络音频下载超时", "Web audio download timed out"),
+    ("网络音频请求失败: HTTP {}", "Web audio request failed: HTTP {}"),
+    ("网络音频过大，请先导入音频库再播放", "The web audio is too large; import it into the library first"),
+    ("示例内容包不存在: {}", "Sample content pack not found: {}"),
+    ("获取文件信息失败: {}", "Failed to read the file information: {}"),
+    ("节数需在1-20之间", "Number of periods must be between 1 and 20"),
+    ("视频文件不存在", "Video file not found"),
+    ("触发时间 {}:{}，当前 {}:{}", "Trigger time {}:{}, now {}:{}"),
+    ("cron 表达式 {}，当前 {}", "Cron expression {}, now {}"),
+    ("计数比例需在0-100之间，最短时长不能为负", "Count percentage must be between 0 and 100 and the minimum time cannot be negative"),
+    ("请先设置家长密码", "Set a parental PIN first"),
+    ("请输入模板名称和文件名规则", "Enter a template name and file name pattern"),
+    ("请输入重命名规则", "Enter a rename pattern"),
+    ("请输入 cron 表达式", "Enter a cron expression"),
+    ("请选择两个不同的音频", "Select two different audio files"),
+    ("请选择任务执行的日期", "Choose the date the task runs on"),
+    ("请至少选择一个播放列表", "Select at least one playlist"),
+    ("请至少选择两个音频", "Select at least two audio files"),
+    ("请输入合并后的名称", "Enter a name for the merged audio"),
+    ("连接失败: {}", "Connection failed: {}"),
+    ("重命名文件失败: {}", "Failed to rename the file: {}"),
+    ("铃声播放列表不存在", "Bell playlist not found"),
+    ("音量必须在0-100之间", "Volume must be between 0 and 100"),
+    ("音量曲线格式错误: {}", "Invalid volume curve: {}"),
+    ("音量曲线的时间不能为负，音量必须在0-100之间", "Volume curve times cannot be negative and volumes must be between 0 and 100"),
+    ("音量曲线的时间点必须递增", "Volume curve time points must be increasing"),
+    ("音量曲线至少需要一个点", "The volume curve needs at least one point"),
+    ("音频不存在", "Audio file not found"),
+    ("音频提取失败：输出文件不存在", "Audio extraction failed: output file not found"),
+    ("音频没有可分析的声音", "The audio has no sound to analyze"),
+    ("无效的文件名", "Invalid file name"),
+    ("无法获取文件扩展名", "Unable to get the file extension"),
+    ("播放器无法直接解码该文件，需要FFmpeg转码，请先安装FFmpeg", "The player cannot decode this file directly and needs FFmpeg to transcode it; install FFmpeg first"),
+    ("正在播放", "Now playing"),
+    ("在播放队列中", "In the playback queue"),
+    ("被定时任务引用：{}", "Used by scheduled tasks: {}"),
+    ("音频正在使用中（{}），如需删除请确认强制删除", "The audio is in use ({}); confirm a forced delete to remove it"),
+    ("音频目录不存在: {}", "Audio folder not found: {}"),
+    ("读取目录失败: {}", "Failed to read the folder: {}"),
+    ("执行FFmpeg命令失败: {}", "Failed to run FFmpeg: {}"),
+    ("FFmpeg执行失败: {}", "FFmpeg failed: {}"),
+    ("执行yt-dlp命令失败: {}. 请确保已安装 yt-dlp", "Failed to run yt-dlp: {}. Make sure yt-dlp is installed"),
+    ("yt-dlp执行失败: {}. 请检查视频URL是否正确", "yt-dlp failed: {}. Check that the video URL is correct"),
+    ("FFmpeg未安装。请将ffmpeg.exe放入tools目录，或点击\"一键安装FFmpeg\"按钮进行安装", "FFmpeg is not installed. Put ffmpeg.exe in the tools folder, or click \"Install FFmpeg\" to install it"),
+    ("无法获取视频文件名", "Unable to get the video file name"),
+    ("无法获取输出文件信息: {}", "Unable to read the output file information: {}"),
+    ("保存到数据库失败: {}", "Failed to save to the database: {}"),
+    ("yt-dlp未安装。请将yt-dlp.exe放入tools目录", "yt-dlp is not installed. Put yt-dlp.exe in the tools folder"),
+    ("音频不存在: {}", "Audio file not found: {}"),
+    ("该音频没有记录来源，无法刷新", "This audio has no recorded source and cannot be refreshed"),
+    ("来源文件不存在: {}", "Source file not found: {}"),
+    ("替换音频文件失败: {}", "Failed to replace the audio file: {}"),
+    ("更新音频信息失败: {}", "Failed to update the audio information: {}"),
+    ("无法获取应用数据目录", "Unable to get the app data folder"),
+    ("下载yt-dlp失败: {}", "Failed to download yt-dlp: {}"),
+    ("设置执行权限失败: {}", "Failed to set execute permission: {}"),
+    ("yt-dlp安装完成", "yt-dlp installed"),
+    ("无法获取用户目录", "Unable to get the user folder"),
+    ("FFmpeg已安装并配置完成", "FFmpeg is installed and configured"),
+    ("FFmpeg已安装，已配置环境变量", "FFmpeg is installed and the PATH has been configured"),
+    ("创建安装目录失败: {}", "Failed to create the install folder: {}"),
+    ("下载FFmpeg失败: {}", "Failed to download FFmpeg: {}"),
+    ("打开压缩文件失败: {}", "Failed to open the archive: {}"),
+    ("读取压缩文件失败: {}", "Failed to read the archive: {}"),
+    ("解压失败: {}", "Failed to extract: {}"),
+    ("创建目录失败: {}", "Failed to create the folder: {}"),
+    ("创建父目录失败: {}", "Failed to create the parent folder: {}"),
+    ("创建文件失败: {}", "Failed to create the file: {}"),
+    ("删除临时文件失败: {}", "Failed to delete the temporary file: {}"),
+    ("FFmpeg安装完成！应用即将重启以使更改生效", "FFmpeg installed! The app will restart to apply the change"),
+    ("打开注册表失败: {}", "Failed to open the registry: {}"),
+    ("设置PATH失败: {}", "Failed to set PATH: {}"),
+    ("执行brew命令失败: {}", "Failed to run brew: {}"),
+    ("FFmpeg通过Homebrew安装完成", "FFmpeg installed with Homebrew"),
+    ("Homebrew安装FFmpeg失败: {}", "Failed to install FFmpeg with Homebrew: {}"),
+    ("执行apt命令失败: {}", "Failed to run apt: {}"),
+    ("FFmpeg通过apt安装完成", "FFmpeg installed with apt"),
+    ("执行yum命令失败: {}", "Failed to run yum: {}"),
+    ("FFmpeg通过yum安装完成", "FFmpeg installed with yum"),
+    ("无效的上课时间: {}", "Invalid class start time: {}"),
+    ("无效的自定义星期: {}", "Invalid custom weekdays: {}"),
+    ("提示音不存在: {}", "Chime sound not found: {}"),
+    ("创建下载目录失败: {}", "Failed to create the download folder: {}"),
+    ("保存下载文件失败: {}", "Failed to save the downloaded file: {}"),
+    ("下载失败（已重试{}次）: {}", "Download failed (retried {} times): {}"),
+    ("服务器错误: {}", "Server error: {}"),
+    ("下载失败: HTTP {}", "Download failed: HTTP {}"),
+    ("创建临时文件失败: {}", "Failed to create a temporary file: {}"),
+    ("下载中断: {}", "Download interrupted: {}"),
+    ("文件大小不符: 期望{}字节，实际{}字节", "File size mismatch: expected {} bytes, got {} bytes"),
+    ("静音检测失败: {}", "Silence detection failed: {}"),
+    ("无法获取音频时长", "Unable to get the audio duration"),
+    ("文件不存在: {}", "File not found: {}"),
+    ("写入合并列表失败: {}", "Failed to write the merge list: {}"),
+    ("合并失败: {}", "Merge failed: {}"),
+    ("不支持的导出格式: {}", "Unsupported export format: {}"),
+    ("生成健康报告失败: {}", "Failed to generate the health report: {}"),
+    ("无效的恢复时间: {}", "Invalid resume time: {}"),
+    ("音频流未初始化", "Audio stream is not initialized"),
+    ("目标播放列表不存在", "Target playlist not found"),
+    ("增益需在 -{0} 到 +{0} dB 之间", "Gain must be between -{0} and +{0} dB"),
+    ("打开音频失败: {}", "Failed to open the audio: {}"),
+    ("解码音频失败: {}", "Failed to decode the audio: {}"),
+    ("录音文件路径丢失", "The recording file path is missing"),
+    ("获取当前可执行文件路径失败: {}", "Failed to get the current executable path: {}"),
+    ("获取当前工作目录失败: {}", "Failed to get the current working folder: {}"),
+    ("重启失败: {} 和 {}", "Restart failed: {} and {}"),
+    ("启动新进程失败: {}", "Failed to start the new process: {}"),
+    ("启动后台服务失败: {}", "Failed to start the background service: {}"),
+    ("模拟范围不能超过{}天", "The simulation range cannot exceed {} days"),
+    ("无效的播放顺序: {}", "Invalid play order: {}"),
+    ("无效的星期播放列表映射: {}", "Invalid weekday playlist mapping: {}"),
+    ("星期映射中的播放列表不存在: {}", "Playlist in the weekday mapping not found: {}"),
+    ("任务或其播放列表不存在", "Task or its playlist not found"),
+    ("创建倍速目录失败: {}", "Failed to create the speed variant folder: {}"),
+    ("播放列表“{}”正被 {} 个任务使用（{}），请确认后再删除", "Playlist \"{}\" is used by {} task(s) ({}); confirm before deleting"),
+    ("切分第 {} 段失败: {}", "Failed to split segment {}: {}"),
+    ("批处理文件执行失败", "Failed to run the batch file"),
+    ("创建批处理文件失败", "Failed to create the batch file"),
+];
+
+/// 校验语言代码，返回规范化后的值
+pub fn normalize_language(language: &str) -> Result<&'static str, String> {
+    match language {
+        "zh" => Ok("zh"),
+        "en" => Ok("en"),
+        _ => Err(tr("不支持的语言")),
+    }
+}
+
+pub fn current_language() -> &'static str {
+    match CURRENT_LANGUAGE.load(Ordering::Relaxed) {
+        LANG_EN => "en",
+        _ => "zh",
+    }
+}
+
+pub fn set_language(language: &str) {
+    let value = if language == "en" { LANG_EN } else { LANG_ZH };
+    CURRENT_LANGUAGE.store(value, Ordering::Relaxed);
+}
+
+/// 从 app_settings 读取语言设置并设为当前语言（默认中文）
+pub fn load_language(conn: &Connection) {
+    let language: String = conn
+        .query_row(
+            "SELECT value FROM app_settings WHERE key = ?1",
+            [LANGUAGE_KEY],
+            |row| row.get(0),
+        )
+        .unwrap_or_else(|_| "zh".to_string());
+    set_language(&language);
+}
+
+/// 按当前语言翻译一条中文原文，表中没有的原样返回
+pub fn tr(zh: &str) -> String {
+    if current_language() == "zh" {
+        return zh.to_string();
+    }
+    CATALOG
+        .iter()
+        .find(|(source, _)| *source == zh)
+        .map(|(_, en)| en.to_string())
+        .unwrap_or_else(|| zh.to_string())
+}
+
+/// 翻译带 {} 占位符的模板并依次填入参数，译文中可用 {0}、{1} 调整参数顺序
+pub fn trf(zh: &str, args: &[&dyn std::fmt::Display]) -> String {
+    let mut text = tr(zh);
+    for (index, arg) in args.iter().enumerate() {
+        let value = arg.to_string();
+        let positional = format!("{{{}}}", index);
+        if text.contains(&positional) {
+            text = text.replace(&positional, &value);
+        } else {
+            text = text.replacen("{}", &value, 1);
+        }
+    }
+    text
+}
+
+/// 按当前语言刷新托盘菜单文字
+pub fn refresh_tray(app: &AppHandle, paused: bool) {
+    let tray = app.tray_handle();
+    let _ = tray.get_item("show").set_title(tr("显示主窗口"));
+    let _ = tray.get_item("hide").set_title(tr("隐藏窗口"));
+    let _ = tray.get_item(player::TRAY_ITEM_ID).set_title(tr("播放/暂停"));
+    let _ = tray.get_item(player::TRAY_MUTE_ITEM_ID).set_title(tr("静音/取消静音"));
+    let _ = tray.get_item(pause::TRAY_ITEM_ID).set_title(pause::tray_title(paused));
+    let _ = tray.get_item(countdown::TRAY_ITEM_ID).set_title(countdown::tray_title());
+    let _ = tray.get_item("quit").set_title(tr("退出应用"));
+}
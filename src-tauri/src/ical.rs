@@ -0,0 +1,541 @@
+//! 定时任务与 iCalendar（.ics）文件的互相转换
+//!
+//! 导出：每个启用的任务一个 VEVENT，重复规则写成 RRULE（有效日期的结束日写成 UNTIL），
+//! 时间为不带时区的本地时间，家庭日历应用订阅或导入后即可显示收听安排（cron 任务无法用 RRULE 表示，不导出）。
+//! 导入：只支持简单的日历（每天 / 每周按星期重复，或将来的单次事件），其余事件跳过。
+
+use serde::Serialize;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use rusqlite::{Connection, OptionalExtension};
+use chrono::{Duration, Local, NaiveDate, NaiveDateTime, TimeZone, Timelike, Utc};
+use tauri::State;
+use crate::scheduler::SchedulerWake;
+use crate::{i18n, task, weekday};
+
+// RRULE 中的星期缩写，下标为 weekday 模块的星期编号（0=周日）
+const ICS_DAYS: [&str; 7] = ["SU", "MO", "TU", "WE", "TH", "FR", "SA"];
+// 描述中的星期名称，下标同上
+const DAY_NAMES: [&str; 7] = ["周日", "周一", "周二", "周三", "周四", "周五", "周六"];
+const ICS_TIME_FORMAT: &str = "%Y%m%dT%H%M%S";
+// 一行最多的字节数，超出时折行（RFC 5545）
+const MAX_LINE_OCTETS: usize = 75;
+// 导出时记录播放列表和任务类型的扩展属性，导入本应用导出的文件时还原
+const PLAYLIST_PROPERTY: &str = "X-MOERDUO-PLAYLIST";
+const ACTION_PROPERTY: &str = "X-MOERDUO-ACTION";
+// 没有时长的停止、降低音量任务在日历中显示的时长（分钟）
+const SILENCE_EVENT_MINUTES: i64 = 1;
+
+struct IcsTask {
+    id: i64,
+    name: String,
+    hour: u32,
+    minute: u32,
+    repeat_mode: String,
+    custom_days: Option<String>,
+    start_date: Option<String>,
+    end_date: Option<String>,
+    playlist_name: String,
+    weekday_playlists: Option<String>,
+    /// 任务的播放时长，未设置时为播放列表的总时长
+    duration_minutes: i64,
+    action: String,
+    volume: i64,
+}
+
+/// 从日历导入的结果
+#[derive(Debug, Serialize)]
+pub struct IcsImportResult {
+    pub imported: usize,
+    /// 无法转换为定时任务而跳过的事件名称
+    pub skipped: Vec<String>,
+}
+
+fn load_tasks(conn: &Connection) -> rusqlite::Result<Vec<IcsTask>> {
+    conn.prepare(
+        "SELECT st.id, st.name, st.hour, st.minute, st.repeat_mode, st.custom_days, st.playlist_id, COALESCE(p.name, ''),
+                st.weekday_playlists, st.duration_minutes, st.action, st.volume, st.start_date, st.end_date
+         FROM scheduled_tasks st
+         LEFT JOIN playlists p ON st.playlist_id = p.id
+         WHERE st.is_enabled = 1 AND st.deleted_at IS NULL
+           AND (st.action != 'play' OR (p.id IS NOT NULL AND p.deleted_at IS NULL))
+           AND NOT (st.repeat_mode = 'once' AND EXISTS(SELECT 1 FROM execution_history eh WHERE eh.task_id = st.id))
+           AND st.repeat_mode != 'cron'
+         ORDER BY st.hour, st.minute, st.priority DESC",
+    )?
+    .query_map([], |row| {
+        Ok(IcsTask {
+            id: row.get(0)?,
+            name: row.get(1)?,
+            hour: row.get(2)?,
+            minute: row.get(3)?,
+            repeat_mode: row.get(4)?,
+            custom_days: row.get(5)?,
+            playlist_name: row.get(7)?,
+            weekday_playlists: row.get(8)?,
+            duration_minutes: task::estimated_minutes(conn, row.get(9)?, row.get(6)?),
+            action: row.get(10)?,
+            volume: row.get(11)?,
+            start_date: row.get(12)?,
+            end_date: row.get(13)?,
+        })
+    })?
+    .collect()
+}
+
+/// 转义 TEXT 类型的属性值
+fn escape_text(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+fn unescape_text(value: &str) -> String {
+    let mut text = String::new();
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            text.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n' | 'N') => text.push('\n'),
+            Some(escaped) => text.push(escaped),
+            None => {}
+        }
+    }
+    text
+}
+
+/// 写入一行，超过 75 字节时折行（续行以空格开头，不拆开多字节字符）
+fn push_line(ics: &mut String, line: &str) {
+    let mut octets = 0;
+    for c in line.chars() {
+        if octets + c.len_utf8() > MAX_LINE_OCTETS {
+            ics.push_str("\r\n ");
+            octets = 1;
+        }
+        ics.push(c);
+        octets += c.len_utf8();
+    }
+    ics.push_str("\r\n");
+}
+
+/// 重复规则对应的 RRULE（有效日期有结束日时加上 UNTIL），单次任务和指定日期的任务为 None
+fn rrule(task: &IcsTask) -> Option<String> {
+    let rule = match task.repeat_mode.as_str() {
+        "once" | "date" => return None,
+        "daily" => "FREQ=DAILY".to_string(),
+        _ => {
+            let days: Vec<&str> = (0..7)
+                .filter(|day| weekday::runs_on_weekday(&task.repeat_mode, task.custom_days.as_deref(), *day))
+                .map(|day| ICS_DAYS[day as usize])
+                .collect();
+            format!("FREQ=WEEKLY;BYDAY={}", days.join(","))
+        }
+    };
+    let until = task.end_date.as_deref().and_then(|end| NaiveDate::parse_from_str(end, "%Y-%m-%d").ok());
+    Some(match until {
+        Some(until) => format!("{};UNTIL={}T235959", rule, until.format("%Y%m%d")),
+        None => rule,
+    })
+}
+
+/// 第一次执行的时刻：重复任务为今天（有效日期尚未开始时为开始日期）起第一个执行的日子，
+/// 单次任务和指定日期的任务为下一次到达该时刻时
+fn first_occurrence(task: &IcsTask, now: NaiveDateTime) -> Option<NaiveDateTime> {
+    let first_date = task
+        .start_date
+        .as_deref()
+        .and_then(|start| NaiveDate::parse_from_str(start, "%Y-%m-%d").ok())
+        .map_or(now.date(), |start| start.max(now.date()));
+    (0..7).find_map(|offset| {
+        let date = first_date + Duration::days(offset);
+        let time = date.and_hms_opt(task.hour, task.minute, 0)?;
+        let runs = weekday::runs_on_date(
+            &task.repeat_mode,
+            task.custom_days.as_deref(),
+            task.start_date.as_deref(),
+            task.end_date.as_deref(),
+            date,
+        );
+        let upcoming = !matches!(task.repeat_mode.as_str(), "once" | "date") || time > now;
+        (runs && upcoming).then_some(time)
+    })
+}
+
+fn description(task: &IcsTask, playlists: &[(i64, String)]) -> String {
+    match task.action.as_str() {
+        "stop" => "停止播放".to_string(),
+        "lower_volume" => format!("音量降至 {}%", task.volume),
+        _ => {
+            let mut lines = vec![format!("播放列表：{}", task.playlist_name)];
+            let mapping = task.weekday_playlists.as_deref().and_then(weekday::parse_weekday_playlists);
+            for (day, playlist_id) in mapping.into_iter().flatten() {
+                if let Some((_, name)) = playlists.iter().find(|(id, _)| *id == playlist_id) {
+                    lines.push(format!("{}：{}", DAY_NAMES[day as usize], name));
+                }
+            }
+            lines.join("\n")
+        }
+    }
+}
+
+fn render_ics(tasks: &[IcsTask], playlists: &[(i64, String)]) -> String {
+    let now = Local::now().naive_local();
+    let stamp = Utc::now().format("%Y%m%dT%H%M%SZ").to_string();
+    let mut ics = String::new();
+    for line in ["BEGIN:VCALENDAR", "VERSION:2.0", "PRODID:-//moerduo//schedule//ZH", "CALSCALE:GREGORIAN", "X-WR-CALNAME:磨耳朵"] {
+        push_line(&mut ics, line);
+    }
+
+    for task in tasks {
+        let Some(start) = first_occurrence(task, now) else { continue };
+        let minutes = match task.action.as_str() {
+            "play" => task.duration_minutes.max(1),
+            _ => SILENCE_EVENT_MINUTES,
+        };
+        push_line(&mut ics, "BEGIN:VEVENT");
+        push_line(&mut ics, &format!("UID:moerduo-task-{}", task.id));
+        push_line(&mut ics, &format!("DTSTAMP:{}", stamp));
+        push_line(&mut ics, &format!("DTSTART:{}", start.format(ICS_TIME_FORMAT)));
+        push_line(&mut ics, &format!("DURATION:PT{}M", minutes));
+        if let Some(rule) = rrule(task) {
+            push_line(&mut ics, &format!("RRULE:{}", rule));
+        }
+        push_line(&mut ics, &format!("SUMMARY:{}", escape_text(&task.name)));
+        push_line(&mut ics, &format!("DESCRIPTION:{}", escape_text(&description(task, playlists))));
+        if task.action == "play" {
+            push_line(&mut ics, &format!("{}:{}", PLAYLIST_PROPERTY, escape_text(&task.playlist_name)));
+        }
+        push_line(&mut ics, &format!("{}:{}", ACTION_PROPERTY, task.action));
+        push_line(&mut ics, "END:VEVENT");
+    }
+
+    push_line(&mut ics, "END:VCALENDAR");
+    ics
+}
+
+/// 导出所有启用的任务为 iCalendar 文件，写入 path 并返回该路径
+#[tauri::command]
+pub async fn export_schedule_ics(
+    path: String,
+    conn: State<'_, Arc<Mutex<Connection>>>,
+) -> Result<String, String> {
+    let (tasks, playlists) = {
+        let conn = conn.lock().await;
+        let tasks = load_tasks(&conn).map_err(|e| e.to_string())?;
+        let playlists = conn
+            .prepare("SELECT id, name FROM playlists WHERE deleted_at IS NULL")
+            .and_then(|mut stmt| {
+                stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+                    .collect::<rusqlite::Result<Vec<(i64, String)>>>()
+            })
+            .map_err(|e| e.to_string())?;
+        (tasks, playlists)
+    };
+
+    std::fs::write(&path, render_ics(&tasks, &playlists)).map_err(|e| i18n::trf("写入文件失败: {}", &[&e]))?;
+    Ok(path)
+}
+
+/// 日历中的一个事件（只保留导入需要的属性）
+#[derive(Default)]
+struct IcsEvent {
+    summary: Option<String>,
+    /// DTSTART 的参数和值，如 ("TZID=Asia/Shanghai", "20240101T070000")
+    start: Option<(String, String)>,
+    end: Option<(String, String)>,
+    duration: Option<String>,
+    rrule: Option<String>,
+    playlist: Option<String>,
+    action: Option<String>,
+}
+
+/// 展开折行后按 BEGIN:VEVENT / END:VEVENT 拆分事件
+fn parse_events(content: &str) -> Vec<IcsEvent> {
+    let mut lines: Vec<String> = Vec::new();
+    for line in content.lines() {
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(rest), Some(last)) => last.push_str(rest),
+            _ => lines.push(line.to_string()),
+        }
+    }
+
+    let mut events = Vec::new();
+    let mut current: Option<IcsEvent> = None;
+    for line in lines {
+        let Some((key, value)) = line.split_once(':') else { continue };
+        let (name, params) = key.split_once(';').unwrap_or((key, ""));
+        let value = value.trim().to_string();
+        let name = name.to_ascii_uppercase();
+        if name == "BEGIN" && value.eq_ignore_ascii_case("VEVENT") {
+            current = Some(IcsEvent::default());
+            continue;
+        }
+        if name == "END" && value.eq_ignore_ascii_case("VEVENT") {
+            events.extend(current.take());
+            continue;
+        }
+        match (name.as_str(), current.as_mut()) {
+            ("SUMMARY", Some(event)) => event.summary = Some(unescape_text(&value)),
+            ("DTSTART", Some(event)) => event.start = Some((params.to_ascii_uppercase(), value)),
+            ("DTEND", Some(event)) => event.end = Some((params.to_ascii_uppercase(), value)),
+            ("DURATION", Some(event)) => event.duration = Some(value),
+            ("RRULE", Some(event)) => event.rrule = Some(value.to_ascii_uppercase()),
+            (PLAYLIST_PROPERTY, Some(event)) => event.playlist = Some(unescape_text(&value)),
+            (ACTION_PROPERTY, Some(event)) => event.action = Some(value),
+            _ => {}
+        }
+    }
+    events
+}
+
+/// 日历中的全天事件（公共假日列表）：(名称, 第一天, 最后一天)，DTEND 为不含的结束日期
+pub fn parse_all_day_events(content: &str) -> Vec<(Option<String>, NaiveDate, NaiveDate)> {
+    let date = |(_, value): &(String, String)| NaiveDate::parse_from_str(value.get(..8)?, "%Y%m%d").ok();
+    parse_events(content)
+        .into_iter()
+        .filter_map(|event| {
+            let start = event.start.as_ref().filter(|start| parse_time(start).is_none())?;
+            let first = date(start)?;
+            let last = event.end.as_ref().and_then(date).map_or(first, |end| (end - Duration::days(1)).max(first));
+            Some((event.summary, first, last))
+        })
+        .collect()
+}
+
+/// 解析日期时间：UTC 时间（以 Z 结尾）换算为本地时间，带 TZID 或不带时区的按本地时间处理；
+/// 全天事件（只有日期）返回 None
+fn parse_time((params, value): &(String, String)) -> Option<NaiveDateTime> {
+    if params.contains("VALUE=DATE") && !params.contains("VALUE=DATE-TIME") {
+        return None;
+    }
+    match value.strip_suffix(['Z', 'z']) {
+        Some(utc) => {
+            let time = NaiveDateTime::parse_from_str(utc, ICS_TIME_FORMAT).ok()?;
+            Some(Utc.from_utc_datetime(&time).with_timezone(&Local).naive_local())
+        }
+        None => NaiveDateTime::parse_from_str(value, ICS_TIME_FORMAT).ok(),
+    }
+}
+
+/// 解析 DURATION（如 PT30M、PT1H30M、P1D），返回分钟数
+fn parse_duration_minutes(value: &str) -> Option<i64> {
+    let rest = value.strip_prefix(['P', 'p'])?;
+    let mut minutes = 0;
+    let mut number = String::new();
+    for c in rest.chars() {
+        match c.to_ascii_uppercase() {
+            'T' => {}
+            digit if digit.is_ascii_digit() => number.push(digit),
+            unit => {
+                let n: i64 = number.parse().ok()?;
+                number.clear();
+                minutes += match unit {
+                    'W' => n * 7 * 24 * 60,
+                    'D' => n * 24 * 60,
+                    'H' => n * 60,
+                    'M' => n,
+                    'S' => n / 60,
+                    _ => return None,
+                };
+            }
+        }
+    }
+    Some(minutes)
+}
+
+/// RRULE 中 UNTIL 的日期（只取日期部分），没有 UNTIL 时为 None
+fn rule_until(rule: &str) -> Option<NaiveDate> {
+    let until = rule.split(';').find_map(|part| part.strip_prefix("UNTIL="))?;
+    NaiveDate::parse_from_str(until.get(..8)?, "%Y%m%d").ok()
+}
+
+/// RRULE 转换为重复模式和自定义星期，不支持的规则（每月、隔周、按第几个星期几等）返回 None
+fn repeat_rule(rule: &str, start_weekday: u32) -> Option<(String, Option<String>)> {
+    let parts: Vec<(&str, &str)> = rule.split(';').filter_map(|part| part.split_once('=')).collect();
+    let get = |key: &str| parts.iter().find(|(k, _)| *k == key).map(|(_, v)| *v);
+    if get("INTERVAL").is_some_and(|interval| interval != "1") {
+        return None;
+    }
+
+    let days: Vec<u32> = match (get("FREQ")?, get("BYDAY")) {
+        ("DAILY", None) => (0..7).collect(),
+        ("DAILY" | "WEEKLY", Some(by_day)) => by_day
+            .split(',')
+            .map(|day| ICS_DAYS.iter().position(|d| *d == day).map(|d| d as u32))
+            .collect::<Option<Vec<_>>>()?,
+        ("WEEKLY", None) => vec![start_weekday],
+        _ => return None,
+    };
+
+    let runs_on = |mode: &str| (0..7).all(|day| weekday::runs_on_weekday(mode, None, day) == days.contains(&day));
+    let repeat = ["daily", "weekday", "weekend"].into_iter().find(|mode| runs_on(mode));
+    Some(match repeat {
+        Some(mode) => (mode.to_string(), None),
+        None => {
+            let mut days = days;
+            days.sort_unstable();
+            days.dedup();
+            ("custom".to_string(), serde_json::to_string(&days).ok())
+        }
+    })
+}
+
+/// 从 iCalendar 文件导入定时任务：每天或每周按星期重复的事件导入为重复任务
+/// （尚未开始的事件从开始日期起生效，UNTIL 作为结束日期），将来的单次事件导入为指定日期的任务，
+/// 已结束的事件跳过；本应用导出的文件按记录的播放列表名称和任务类型还原，其余使用 playlist_id
+#[tauri::command]
+pub async fn import_schedule_ics(
+    path: String,
+    playlist_id: i64,
+    conn: State<'_, Arc<Mutex<Connection>>>,
+    wake: State<'_, SchedulerWake>,
+) -> Result<IcsImportResult, String> {
+    let content = std::fs::read_to_string(&path).map_err(|e| i18n::trf("读取文件失败: {}", &[&e]))?;
+    if !content.trim_start_matches('\u{feff}').trim_start().starts_with("BEGIN:VCALENDAR") {
+        return Err(i18n::tr("不是有效的日历文件"));
+    }
+
+    let mut conn = conn.lock().await;
+    let find_playlist = |conn: &Connection, sql: &str, value: &dyn rusqlite::ToSql| -> Result<Option<i64>, String> {
+        conn.query_row(sql, [value], |row| row.get(0)).optional().map_err(|e| e.to_string())
+    };
+    if find_playlist(&conn, "SELECT id FROM playlists WHERE id = ?1 AND deleted_at IS NULL", &playlist_id)?.is_none() {
+        return Err(i18n::tr("播放列表不存在"));
+    }
+
+    let now = Local::now().naive_local();
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    let mut result = IcsImportResult { imported: 0, skipped: Vec::new() };
+
+    for event in parse_events(&content) {
+        let name = event.summary.clone().filter(|name| !name.trim().is_empty()).unwrap_or_else(|| "日历任务".to_string());
+        let Some(start) = event.start.as_ref().and_then(parse_time) else {
+            result.skipped.push(name);
+            continue;
+        };
+        let date = start.date().format("%Y-%m-%d").to_string();
+        let repeat = match &event.rrule {
+            Some(rule) => {
+                let until = rule_until(rule);
+                let start_date = (start.date() > now.date()).then(|| date.clone());
+                let end_date = until.map(|until| until.format("%Y-%m-%d").to_string());
+                repeat_rule(rule, weekday::weekday_index(&start))
+                    .filter(|_| until.is_none_or(|until| until >= now.date()))
+                    .map(|(mode, days)| (mode, days, start_date, end_date))
+            }
+            None => (start > now).then(|| ("date".to_string(), None, Some(date.clone()), Some(date.clone()))),
+        };
+        let Some((repeat_mode, custom_days, start_date, end_date)) = repeat else {
+            result.skipped.push(name);
+            continue;
+        };
+
+        let action = event.action.as_deref().filter(|action| task::TASK_ACTIONS.contains(action)).unwrap_or("play");
+        let duration_minutes = match (&event.duration, event.end.as_ref().and_then(parse_time)) {
+            (Some(duration), _) => parse_duration_minutes(duration),
+            (None, Some(end)) => Some((end - start).num_minutes()),
+            (None, None) => None,
+        }
+        .filter(|minutes| action == "play" && *minutes > 0);
+        let task_playlist = match &event.playlist {
+            Some(playlist) => find_playlist(
+                &tx,
+                "SELECT id FROM playlists WHERE name = ?1 AND deleted_at IS NULL ORDER BY id LIMIT 1",
+                playlist,
+            )?
+            .unwrap_or(playlist_id),
+            None => playlist_id,
+        };
+
+        tx.execute(
+            "INSERT INTO scheduled_tasks (name, hour, minute, repeat_mode, custom_days, playlist_id, duration_minutes, action,
+                                          start_date, end_date)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+            (
+                &name,
+                start.hour(),
+                start.minute(),
+                &repeat_mode,
+                &custom_days,
+                task_playlist,
+                duration_minutes,
+                action,
+                &start_date,
+                &end_date,
+            ),
+        )
+        .map_err(|e| e.to_string())?;
+        result.imported += 1;
+    }
+
+    tx.commit().map_err(|e| e.to_string())?;
+    wake.notify();
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unfolds_continuation_lines() {
+        let content = "BEGIN:VCALENDAR\r\nBEGIN:VEVENT\r\nSUMMARY:早上\r\n  听儿歌\\, 英语\r\nDTSTART:2024010\r\n\t1T073000\r\n\
+                       RRULE:FREQ=WEEKLY;\r\n BYDAY=MO,WE\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n";
+        let events = parse_events(content);
+        assert_eq!(events.len(), 1);
+        let event = &events[0];
+        assert_eq!(event.summary.as_deref(), Some("早上 听儿歌, 英语"));
+        assert_eq!(event.start, Some((String::new(), "20240101T073000".to_string())));
+        assert_eq!(event.rrule.as_deref(), Some("FREQ=WEEKLY;BYDAY=MO,WE"));
+    }
+
+    #[test]
+    fn exported_lines_round_trip() {
+        let mut ics = String::new();
+        for line in ["BEGIN:VEVENT", &format!("SUMMARY:{}", escape_text(&"磨耳朵；晨读, 晚听\n".repeat(8))), "END:VEVENT"] {
+            push_line(&mut ics, line);
+        }
+        assert!(ics.lines().all(|line| line.len() <= MAX_LINE_OCTETS));
+        let events = parse_events(&ics);
+        assert_eq!(events[0].summary.as_deref(), Some("磨耳朵；晨读, 晚听\n".repeat(8).as_str()));
+    }
+
+    #[test]
+    fn maps_byday_to_weekday_numbers() {
+        // 0=周日：SU,SA 为周末，MO-FR 为工作日
+        assert_eq!(repeat_rule("FREQ=WEEKLY;BYDAY=SA,SU", 1), Some(("weekend".to_string(), None)));
+        assert_eq!(repeat_rule("FREQ=WEEKLY;BYDAY=MO,TU,WE,TH,FR", 1), Some(("weekday".to_string(), None)));
+        assert_eq!(repeat_rule("FREQ=DAILY", 3), Some(("daily".to_string(), None)));
+        assert_eq!(repeat_rule("FREQ=WEEKLY;BYDAY=SU,WE,SU", 1), Some(("custom".to_string(), Some("[0,3]".to_string()))));
+        // 没有 BYDAY 时按开始日期的星期
+        assert_eq!(repeat_rule("FREQ=WEEKLY", 0), Some(("custom".to_string(), Some("[0]".to_string()))));
+    }
+
+    #[test]
+    fn parses_start_times_and_durations() {
+        let local = |value: &str| parse_time(&(String::new(), value.to_string()));
+        assert_eq!(local("20240301T183000"), NaiveDate::from_ymd_opt(2024, 3, 1).and_then(|d| d.and_hms_opt(18, 30, 0)));
+        assert_eq!(parse_time(&("VALUE=DATE".to_string(), "20240301".to_string())), None);
+        assert_eq!(parse_duration_minutes("PT1H30M"), Some(90));
+        assert_eq!(parse_duration_minutes("P1DT2H"), Some(26 * 60));
+        assert_eq!(rule_until("FREQ=DAILY;UNTIL=20240630T235959Z"), NaiveDate::from_ymd_opt(2024, 6, 30));
+    }
+
+    #[test]
+    fn rejects_bad_input() {
+        // 不在 VEVENT 中的属性、没有冒号的行被忽略，没有结束的事件不返回
+        assert!(parse_events("SUMMARY:孤立的属性\nBEGIN:VEVENT\nSUMMARY:未结束").is_empty());
+        assert_eq!(parse_time(&(String::new(), "2024-03-01 18:30".to_string())), None);
+        assert_eq!(parse_duration_minutes("30M"), None);
+        assert_eq!(parse_duration_minutes("PT1X"), None);
+        assert_eq!(repeat_rule("FREQ=MONTHLY", 1), None);
+        assert_eq!(repeat_rule("FREQ=WEEKLY;INTERVAL=2;BYDAY=MO", 1), None);
+        assert_eq!(repeat_rule("FREQ=WEEKLY;BYDAY=1MO", 1), None);
+        assert_eq!(rule_until("FREQ=DAILY;UNTIL=bad"), None);
+    }
+}
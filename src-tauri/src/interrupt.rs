@@ -0,0 +1,165 @@
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use tokio::time::{interval, Duration};
+use rusqlite::Connection;
+use tauri::{AppHandle, Manager, State};
+use crate::player::AudioPlayer;
+
+// 通话/会议时自动暂停的设置保存在 app_settings 中，后台服务进程也按此暂停
+const POLICY_SETTING_KEY: &str = "capture_auto_pause";
+// 检查其他应用是否在使用麦克风的间隔（秒）
+const CAPTURE_CHECK_SECS: u64 = 2;
+// 自动暂停、自动恢复时发给前端的事件
+const AUTO_PAUSED_EVENT: &str = "capture-auto-paused";
+const AUTO_RESUMED_EVENT: &str = "capture-auto-resumed";
+
+/// 其他应用占用麦克风（通话、网课、会议）时的处理方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CapturePausePolicy {
+    /// 其他应用开始使用麦克风时暂停播放
+    pub enabled: bool,
+    /// 麦克风释放后继续播放（期间手动操作过播放器时不恢复）
+    pub resume: bool,
+}
+
+pub fn load_policy(conn: &Connection) -> CapturePausePolicy {
+    conn.query_row(
+        "SELECT value FROM app_settings WHERE key = ?1",
+        [POLICY_SETTING_KEY],
+        |row| row.get::<_, String>(0),
+    )
+    .ok()
+    .and_then(|value| serde_json::from_str(&value).ok())
+    .unwrap_or_default()
+}
+
+/// 是否有其他应用正在使用麦克风：读取系统隐私设置中记录的麦克风使用情况，
+/// 正在使用的应用 LastUsedTimeStop 为 0（本应用自己录音时不算）
+#[cfg(target_os = "windows")]
+fn other_app_capturing() -> bool {
+    use winreg::enums::HKEY_CURRENT_USER;
+    use winreg::RegKey;
+
+    const CONSENT_STORE: &str = r"Software\Microsoft\Windows\CurrentVersion\CapabilityAccessManager\ConsentStore\microphone";
+
+    // 非商店应用以可执行文件路径（\ 替换为 #）为键名
+    let own_key = std::env::current_exe()
+        .map(|exe| exe.to_string_lossy().replace('\\', "#").to_lowercase())
+        .unwrap_or_default();
+    let in_use = |key: &RegKey| {
+        let start: u64 = key.get_value("LastUsedTimeStart").unwrap_or(0);
+        let stop: u64 = key.get_value("LastUsedTimeStop").unwrap_or(1);
+        start != 0 && stop == 0
+    };
+    let any_in_use = |parent: &RegKey| {
+        parent.enum_keys().flatten().any(|name| {
+            name.to_lowercase() != own_key && parent.open_subkey(&name).is_ok_and(|key| in_use(&key))
+        })
+    };
+
+    let Ok(store) = RegKey::predef(HKEY_CURRENT_USER).open_subkey(CONSENT_STORE) else {
+        return false;
+    };
+    any_in_use(&store) || store.open_subkey("NonPackaged").is_ok_and(|key| any_in_use(&key))
+}
+
+/// 是否有其他应用正在使用麦克风：PulseAudio/PipeWire 中有其他进程的录音流（本应用自己录音时不算）
+#[cfg(target_os = "linux")]
+fn other_app_capturing() -> bool {
+    let Ok(output) = std::process::Command::new("pactl").args(["list", "source-outputs"]).output() else {
+        return false;
+    };
+    if !output.status.success() {
+        return false;
+    }
+    let own_pid = format!("\"{}\"", std::process::id());
+    String::from_utf8_lossy(&output.stdout)
+        .split("Source Output #")
+        .skip(1)
+        .any(|block| {
+            !block.lines().any(|line| {
+                let line = line.trim();
+                line.starts_with("application.process.id") && line.ends_with(&own_pid)
+            })
+        })
+}
+
+/// macOS 没有不需要额外权限的查询方式，不自动暂停
+#[cfg(target_os = "macos")]
+fn other_app_capturing() -> bool {
+    false
+}
+
+/// 启动麦克风占用监视器：其他应用开始使用麦克风时暂停正在进行的播放并发出 capture-auto-paused 事件，
+/// 麦克风释放后若设置了自动恢复、且播放器仍暂停在同一曲目上，则继续播放并发出 capture-auto-resumed 事件
+pub fn start_capture_monitor(app: Option<AppHandle>, db: Arc<Mutex<Connection>>, player: Arc<Mutex<AudioPlayer>>) {
+    tauri::async_runtime::spawn(async move {
+        let mut ticker = interval(Duration::from_secs(CAPTURE_CHECK_SECS));
+        let mut was_capturing = false;
+        // 自动暂停时的曲目，恢复时只继续这一曲目
+        let mut paused_audio: Option<i64> = None;
+
+        loop {
+            ticker.tick().await;
+            let policy = load_policy(&*db.lock().await);
+            if !policy.enabled {
+                was_capturing = false;
+                paused_audio = None;
+                continue;
+            }
+
+            let capturing = tauri::async_runtime::spawn_blocking(other_app_capturing)
+                .await
+                .unwrap_or(false);
+            if capturing == was_capturing {
+                continue;
+            }
+            was_capturing = capturing;
+
+            // 只在开始占用时暂停一次，占用期间手动继续播放不会再被暂停
+            let mut player = player.lock().await;
+            if capturing {
+                if player.is_playing() {
+                    println!("⏸️ [Interrupt] 其他应用正在使用麦克风，暂停播放");
+                    player.pause();
+                    paused_audio = player.current_audio_id();
+                    if let Some(app) = &app {
+                        let _ = app.emit_all(AUTO_PAUSED_EVENT, ());
+                    }
+                }
+            } else if let Some(audio_id) = paused_audio.take() {
+                if policy.resume && !player.is_playing() && player.current_audio_id() == Some(audio_id) {
+                    println!("▶️ [Interrupt] 麦克风已释放，继续播放");
+                    player.resume();
+                    if let Some(app) = &app {
+                        let _ = app.emit_all(AUTO_RESUMED_EVENT, ());
+                    }
+                }
+            }
+        }
+    });
+}
+
+#[tauri::command]
+pub async fn get_capture_pause_policy(
+    conn: State<'_, Arc<Mutex<Connection>>>,
+) -> Result<CapturePausePolicy, String> {
+    Ok(load_policy(&*conn.lock().await))
+}
+
+#[tauri::command]
+pub async fn set_capture_pause_policy(
+    policy: CapturePausePolicy,
+    conn: State<'_, Arc<Mutex<Connection>>>,
+) -> Result<(), String> {
+    let value = serde_json::to_string(&policy).map_err(|e| e.to_string())?;
+    conn.lock()
+        .await
+        .execute(
+            "INSERT OR REPLACE INTO app_settings (key, value) VALUES (?1, ?2)",
+            (POLICY_SETTING_KEY, &value),
+        )
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
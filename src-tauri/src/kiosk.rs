@@ -0,0 +1,313 @@
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::sync::{Arc, RwLock};
+use tokio::sync::Mutex;
+use rusqlite::Connection;
+use tauri::{AppHandle, Invoke, Manager, Runtime, State};
+use crate::i18n;
+use crate::player::AudioPlayer;
+
+// 儿童模式设置保存在 app_settings 中
+const SETTING_KEY: &str = "kiosk_mode";
+// 家长密码（保存 SHA-256 摘要）
+const PIN_SETTING_KEY: &str = "parental_pin";
+// 最大音量（百分比，保护听力），默认不限制
+const MAX_VOLUME_KEY: &str = "max_volume";
+const DEFAULT_MAX_VOLUME: i64 = 100;
+const PIN_MIN_LEN: usize = 4;
+const PIN_MAX_LEN: usize = 8;
+// Tauri 内置 API（事件监听、窗口等）使用的命令名，不受儿童模式限制
+const TAURI_INTERNAL_COMMAND: &str = "tauri";
+// 儿童模式下允许调用的命令，其余命令一律拒绝
+const ALLOWED_COMMANDS: [&str; 13] = [
+    "get_kiosk_status",
+    "get_listening_quota",
+    "exit_kiosk_mode",
+    "get_settings",
+    "get_playback_state",
+    "play_playlist",
+    "pause_audio",
+    "resume_audio",
+    "toggle_play_pause",
+    "stop_audio",
+    "play_next",
+    "play_previous",
+    "set_volume",
+];
+
+/// 儿童模式设置：开启时窗口锁定全屏，只能播放白名单中的播放列表
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct KioskSettings {
+    pub enabled: bool,
+    pub playlist_ids: Vec<i64>,
+}
+
+/// 儿童模式的运行状态（开启时为允许播放的播放列表），命令拦截时同步读取
+#[derive(Default)]
+pub struct KioskState(RwLock<Option<Vec<i64>>>);
+
+impl KioskState {
+    pub fn is_active(&self) -> bool {
+        self.0.read().map(|state| state.is_some()).unwrap_or(false)
+    }
+
+    fn set(&self, playlist_ids: Option<Vec<i64>>) {
+        if let Ok(mut state) = self.0.write() {
+            *state = playlist_ids;
+        }
+    }
+
+    /// 儿童模式下检查命令是否允许调用，播放列表只能播放白名单中的
+    fn check(&self, command: &str, payload: &serde_json::Value) -> Result<(), String> {
+        let Ok(state) = self.0.read() else { return Ok(()) };
+        let Some(playlist_ids) = state.as_ref() else { return Ok(()) };
+
+        if !ALLOWED_COMMANDS.contains(&command) {
+            return Err(i18n::tr("儿童模式下不能使用该功能"));
+        }
+        if command == "play_playlist" {
+            let playlist_id = payload.get("playlistId").and_then(|id| id.as_i64());
+            if !playlist_id.is_some_and(|id| playlist_ids.contains(&id)) {
+                return Err(i18n::tr("儿童模式下只能播放指定的播放列表"));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 包装命令处理函数：儿童模式开启时拒绝白名单以外的命令
+pub fn guard<R: Runtime>(
+    handler: impl Fn(Invoke<R>) + Send + Sync + 'static,
+) -> impl Fn(Invoke<R>) + Send + Sync + 'static {
+    move |invoke: Invoke<R>| {
+        let command = invoke.message.command();
+        if command != TAURI_INTERNAL_COMMAND {
+            let window = invoke.message.window();
+            let checked = window
+                .try_state::<KioskState>()
+                .map_or(Ok(()), |state| state.check(command, invoke.message.payload()));
+            if let Err(e) = checked {
+                invoke.resolver.reject(e);
+                return;
+            }
+        }
+        handler(invoke)
+    }
+}
+
+/// 儿童模式是否开启（托盘菜单和关闭窗口时检查）
+pub fn is_active<R: Runtime>(app: &AppHandle<R>) -> bool {
+    app.try_state::<KioskState>().is_some_and(|state| state.is_active())
+}
+
+fn load_settings(conn: &Connection) -> KioskSettings {
+    conn.query_row(
+        "SELECT value FROM app_settings WHERE key = ?1",
+        [SETTING_KEY],
+        |row| row.get::<_, String>(0),
+    )
+    .ok()
+    .and_then(|value| serde_json::from_str(&value).ok())
+    .unwrap_or_default()
+}
+
+fn save_settings(conn: &Connection, settings: &KioskSettings) -> Result<(), String> {
+    let value = serde_json::to_string(settings).map_err(|e| e.to_string())?;
+    conn.execute(
+        "INSERT OR REPLACE INTO app_settings (key, value) VALUES (?1, ?2)",
+        (SETTING_KEY, &value),
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// 家长设置的最大音量（百分比，1-100）
+pub fn load_max_volume(conn: &Connection) -> i64 {
+    conn.query_row(
+        "SELECT value FROM app_settings WHERE key = ?1",
+        [MAX_VOLUME_KEY],
+        |row| row.get::<_, String>(0),
+    )
+    .ok()
+    .and_then(|value| value.parse().ok())
+    .unwrap_or(DEFAULT_MAX_VOLUME)
+    .clamp(1, 100)
+}
+
+fn hash_pin(pin: &str) -> String {
+    Sha256::digest(pin.as_bytes()).iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn load_pin_hash(conn: &Connection) -> Option<String> {
+    conn.query_row(
+        "SELECT value FROM app_settings WHERE key = ?1",
+        [PIN_SETTING_KEY],
+        |row| row.get(0),
+    )
+    .ok()
+}
+
+fn verify_pin(conn: &Connection, pin: &str) -> Result<(), String> {
+    match load_pin_hash(conn) {
+        Some(hash) if hash == hash_pin(pin) => Ok(()),
+        _ => Err(i18n::tr("家长密码错误")),
+    }
+}
+
+/// 修改家长控制设置前检查密码，未设置家长密码时不需要
+pub(crate) fn check_parental_pin(conn: &Connection, pin: Option<&str>) -> Result<(), String> {
+    match load_pin_hash(conn) {
+        Some(_) => verify_pin(conn, pin.unwrap_or_default().trim()),
+        None => Ok(()),
+    }
+}
+
+/// 锁定或解除主窗口全屏
+fn lock_window<R: Runtime>(app: &AppHandle<R>, locked: bool) {
+    let Some(window) = app.get_window("main") else { return };
+    if locked {
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
+    if let Err(e) = window.set_fullscreen(locked) {
+        eprintln!("[Kiosk] 设置全屏失败: {}", e);
+    }
+}
+
+/// 启动时恢复儿童模式（专用设备重启后仍保持锁定）
+pub fn restore(app: &AppHandle, conn: &Connection, state: &KioskState) {
+    let settings = load_settings(conn);
+    if settings.enabled {
+        state.set(Some(settings.playlist_ids));
+        lock_window(app, true);
+    }
+}
+
+/// 儿童模式中可播放的播放列表
+#[derive(Debug, Serialize)]
+pub struct KioskPlaylist {
+    pub id: i64,
+    pub name: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct KioskStatus {
+    pub enabled: bool,
+    pub has_pin: bool,
+    pub playlists: Vec<KioskPlaylist>,
+    /// 最大音量（百分比）
+    pub max_volume: i64,
+}
+
+#[tauri::command]
+pub async fn get_kiosk_status(
+    conn: State<'_, Arc<Mutex<Connection>>>,
+    kiosk: State<'_, KioskState>,
+) -> Result<KioskStatus, String> {
+    let conn = conn.lock().await;
+    let settings = load_settings(&conn);
+    let mut stmt = conn
+        .prepare("SELECT id, name FROM playlists WHERE id = ?1 AND deleted_at IS NULL")
+        .map_err(|e| e.to_string())?;
+    let playlists = settings
+        .playlist_ids
+        .iter()
+        .filter_map(|id| stmt.query_row([id], |row| Ok(KioskPlaylist { id: row.get(0)?, name: row.get(1)? })).ok())
+        .collect();
+
+    Ok(KioskStatus {
+        enabled: kiosk.is_active(),
+        has_pin: load_pin_hash(&conn).is_some(),
+        playlists,
+        max_volume: load_max_volume(&conn),
+    })
+}
+
+/// 设置或修改家长密码（4-8 位数字），已设置时需要提供原密码
+#[tauri::command]
+pub async fn set_parental_pin(
+    old_pin: Option<String>,
+    new_pin: String,
+    conn: State<'_, Arc<Mutex<Connection>>>,
+) -> Result<(), String> {
+    let new_pin = new_pin.trim();
+    if !(PIN_MIN_LEN..=PIN_MAX_LEN).contains(&new_pin.len()) || !new_pin.chars().all(|c| c.is_ascii_digit()) {
+        return Err(i18n::tr("家长密码必须是4到8位数字"));
+    }
+
+    let conn = conn.lock().await;
+    if load_pin_hash(&conn).is_some() {
+        verify_pin(&conn, old_pin.as_deref().unwrap_or_default().trim())?;
+    }
+    conn.execute(
+        "INSERT OR REPLACE INTO app_settings (key, value) VALUES (?1, ?2)",
+        (PIN_SETTING_KEY, hash_pin(new_pin)),
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// 设置最大音量（百分比），界面和定时任务都不能超过；已设置家长密码时需要提供密码
+#[tauri::command]
+pub async fn set_max_volume(
+    max_volume: i64,
+    pin: Option<String>,
+    conn: State<'_, Arc<Mutex<Connection>>>,
+    player: State<'_, Arc<Mutex<AudioPlayer>>>,
+) -> Result<(), String> {
+    if !(1..=100).contains(&max_volume) {
+        return Err(i18n::tr("最大音量需在1-100之间"));
+    }
+
+    {
+        let conn = conn.lock().await;
+        check_parental_pin(&conn, pin.as_deref())?;
+        conn.execute(
+            "INSERT OR REPLACE INTO app_settings (key, value) VALUES (?1, ?2)",
+            (MAX_VOLUME_KEY, max_volume.to_string()),
+        )
+        .map_err(|e| e.to_string())?;
+    }
+    player.lock().await.set_max_volume(max_volume as f32 / 100.0);
+    Ok(())
+}
+
+/// 进入儿童模式：窗口锁定全屏，只能播放选定的播放列表
+#[tauri::command]
+pub async fn enter_kiosk_mode(
+    playlist_ids: Vec<i64>,
+    app: AppHandle,
+    conn: State<'_, Arc<Mutex<Connection>>>,
+    kiosk: State<'_, KioskState>,
+) -> Result<(), String> {
+    if playlist_ids.is_empty() {
+        return Err(i18n::tr("请至少选择一个播放列表"));
+    }
+
+    let conn = conn.lock().await;
+    if load_pin_hash(&conn).is_none() {
+        return Err(i18n::tr("请先设置家长密码"));
+    }
+    save_settings(&conn, &KioskSettings { enabled: true, playlist_ids: playlist_ids.clone() })?;
+    kiosk.set(Some(playlist_ids));
+    lock_window(&app, true);
+    Ok(())
+}
+
+/// 输入家长密码退出儿童模式
+#[tauri::command]
+pub async fn exit_kiosk_mode(
+    pin: String,
+    app: AppHandle,
+    conn: State<'_, Arc<Mutex<Connection>>>,
+    kiosk: State<'_, KioskState>,
+) -> Result<(), String> {
+    let conn = conn.lock().await;
+    verify_pin(&conn, pin.trim())?;
+
+    let settings = load_settings(&conn);
+    save_settings(&conn, &KioskSettings { enabled: false, ..settings })?;
+    kiosk.set(None);
+    lock_window(&app, false);
+    Ok(())
+}
@@ -0,0 +1,193 @@
+use serde::Serialize;
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use rusqlite::Connection;
+use tauri::State;
+use crate::audio::FALLBACK_DURATION_SECS;
+use crate::formats;
+use crate::i18n;
+
+#[derive(Debug, Serialize)]
+pub struct HealthItem {
+    pub audio_id: Option<i64>,
+    pub name: String,
+    pub file_path: String,
+}
+
+/// 一类问题：数量、涉及的文件以及建议的修复操作
+#[derive(Debug, Serialize)]
+pub struct HealthIssue {
+    pub count: usize,
+    pub items: Vec<HealthItem>,
+    pub fix_action: String,
+    pub fix_description: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LibraryHealthReport {
+    pub total_files: usize,
+    pub missing_files: HealthIssue,
+    pub fallback_duration: HealthIssue,
+    pub unreadable_files: HealthIssue,
+    pub untagged_files: HealthIssue,
+    pub orphaned_files: HealthIssue,
+}
+
+impl HealthIssue {
+    fn new(items: Vec<HealthItem>, fix_action: &str, fix_description: &str) -> Self {
+        HealthIssue {
+            count: items.len(),
+            items,
+            fix_action: fix_action.to_string(),
+            fix_description: fix_description.to_string(),
+        }
+    }
+}
+
+struct LibraryRow {
+    id: i64,
+    name: String,
+    file_path: String,
+    duration: Option<i64>,
+    format: String,
+    in_playlist: bool,
+}
+
+fn build_report(rows: Vec<LibraryRow>, known_paths: HashSet<PathBuf>, scan_dirs: Vec<PathBuf>) -> LibraryHealthReport {
+    let total_files = rows.len();
+    let mut missing = Vec::new();
+    let mut fallback = Vec::new();
+    let mut unreadable = Vec::new();
+    let mut untagged = Vec::new();
+
+    for row in rows {
+        let path = Path::new(&row.file_path);
+        let item = || HealthItem {
+            audio_id: Some(row.id),
+            name: row.name.clone(),
+            file_path: row.file_path.clone(),
+        };
+
+        if !row.in_playlist {
+            untagged.push(item());
+        }
+
+        if !path.exists() {
+            missing.push(item());
+            continue;
+        }
+
+        if !matches!(row.duration, Some(d) if d > 0 && d != FALLBACK_DURATION_SECS) {
+            fallback.push(item());
+        }
+
+        if !formats::is_native(&row.format) || !formats::is_decodable(path) {
+            unreadable.push(item());
+        }
+    }
+
+    // 音频目录中存在、但音频库中没有记录的文件
+    let mut orphaned = Vec::new();
+    for dir in scan_dirs {
+        let Ok(entries) = fs::read_dir(&dir) else { continue };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if !path.is_file() || !formats::is_supported_path(&path) {
+                continue;
+            }
+            let canonical = path.canonicalize().unwrap_or_else(|_| path.clone());
+            if known_paths.contains(&canonical) {
+                continue;
+            }
+            orphaned.push(HealthItem {
+                audio_id: None,
+                name: entry.file_name().to_string_lossy().to_string(),
+                file_path: path.to_string_lossy().to_string(),
+            });
+        }
+    }
+
+    LibraryHealthReport {
+        total_files,
+        missing_files: HealthIssue::new(missing, "remove_records", "文件已不存在，可从音频库中移除这些记录"),
+        fallback_duration: HealthIssue::new(fallback, "reprobe_duration", "时长未知或读取失败（使用了默认时长），可重新检测时长"),
+        unreadable_files: HealthIssue::new(unreadable, "convert_format", "播放器无法解码，建议用 FFmpeg 转换为 MP3 后重新导入"),
+        untagged_files: HealthIssue::new(untagged, "add_to_playlist", "未加入任何播放列表，可整理到播放列表中"),
+        orphaned_files: HealthIssue::new(orphaned, "import_or_delete", "音频目录中未导入的文件，可扫描导入或删除"),
+    }
+}
+
+/// 汇总音频库的健康状况
+#[tauri::command]
+pub async fn get_library_health(
+    conn: State<'_, Arc<Mutex<Connection>>>,
+    audio_dir: State<'_, PathBuf>,
+) -> Result<LibraryHealthReport, String> {
+    let (rows, known_paths, custom_path) = {
+        let conn = conn.lock().await;
+        let mut stmt = conn
+            .prepare(
+                "SELECT af.id, af.original_name, af.file_path, af.duration, af.format,
+                        EXISTS(SELECT 1 FROM playlist_items pi
+                               JOIN playlists p ON pi.playlist_id = p.id
+                               WHERE pi.audio_id = af.id AND p.deleted_at IS NULL)
+                 FROM audio_files af
+                 ORDER BY af.id",
+            )
+            .map_err(|e| e.to_string())?;
+
+        let rows = stmt
+            .query_map([], |row| {
+                Ok(LibraryRow {
+                    id: row.get(0)?,
+                    name: row.get(1)?,
+                    file_path: row.get(2)?,
+                    duration: row.get(3)?,
+                    format: row.get(4)?,
+                    in_playlist: row.get(5)?,
+                })
+            })
+            .map_err(|e| e.to_string())?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| e.to_string())?;
+
+        // 倍速版本等派生文件也视为已登记
+        let mut stmt = conn
+            .prepare("SELECT file_path FROM audio_files UNION SELECT file_path FROM audio_speed_variants")
+            .map_err(|e| e.to_string())?;
+        let known_paths: HashSet<PathBuf> = stmt
+            .query_map([], |row| row.get::<_, String>(0))
+            .map_err(|e| e.to_string())?
+            .filter_map(|path| path.ok())
+            .map(|path| {
+                let path = PathBuf::from(path);
+                path.canonicalize().unwrap_or(path)
+            })
+            .collect();
+
+        let custom_path: Option<String> = conn
+            .query_row(
+                "SELECT value FROM app_settings WHERE key = 'audio_path'",
+                [],
+                |row| row.get(0),
+            )
+            .ok();
+
+        (rows, known_paths, custom_path)
+    };
+
+    let mut scan_dirs = vec![audio_dir.as_path().to_path_buf()];
+    if let Some(path) = custom_path {
+        let path = PathBuf::from(path.trim_matches('"'));
+        if !scan_dirs.contains(&path) {
+            scan_dirs.push(path);
+        }
+    }
+
+    tokio::task::spawn_blocking(move || build_report(rows, known_paths, scan_dirs))
+        .await
+        .map_err(|e| i18n::trf("生成健康报告失败: {}", &[&e]))
+}
@@ -0,0 +1,134 @@
+use serde::Serialize;
+use std::collections::BTreeSet;
+use tokio::sync::mpsc;
+use tokio::time::{timeout_at, Duration, Instant};
+use tauri::{AppHandle, Manager};
+
+// 发给前端的音库变化事件
+const LIBRARY_CHANGED_EVENT: &str = "library-changed";
+// 最后一次变化后等待多久没有新变化才发出事件（毫秒）
+const QUIET_MILLIS: u64 = 300;
+// 持续变化（批量导入、后台检测时长）时最多间隔多久发出一次事件（毫秒）
+const MAX_DELAY_MILLIS: u64 = 2000;
+// 一次事件最多附带的音频ID数，超过时只给数量并要求重新获取整个音库
+const MAX_SUMMARY_IDS: usize = 500;
+
+#[derive(Debug, Clone, Copy)]
+enum ChangeKind {
+    Added,
+    Updated,
+    Removed,
+}
+
+/// 音库变化通知：导入、删除、修改音频时调用，合并后以 library-changed 事件发给前端
+#[derive(Clone)]
+pub struct LibraryEvents {
+    sender: mpsc::UnboundedSender<(ChangeKind, i64)>,
+}
+
+impl LibraryEvents {
+    fn send(&self, kind: ChangeKind, ids: impl IntoIterator<Item = i64>) {
+        for id in ids {
+            let _ = self.sender.send((kind, id));
+        }
+    }
+
+    pub fn added(&self, ids: impl IntoIterator<Item = i64>) {
+        self.send(ChangeKind::Added, ids);
+    }
+
+    pub fn updated(&self, ids: impl IntoIterator<Item = i64>) {
+        self.send(ChangeKind::Updated, ids);
+    }
+
+    pub fn removed(&self, ids: impl IntoIterator<Item = i64>) {
+        self.send(ChangeKind::Removed, ids);
+    }
+}
+
+/// library-changed 事件的内容：一段时间内合并的变化
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct LibraryChangeSummary {
+    pub added: usize,
+    pub updated: usize,
+    pub removed: usize,
+    pub added_ids: Vec<i64>,
+    pub updated_ids: Vec<i64>,
+    pub removed_ids: Vec<i64>,
+    /// 变化太多，ID 列表为空，需要重新获取整个音库
+    pub full_refresh: bool,
+}
+
+/// 合并中的变化：同一音频只算一次，新增后修改仍算新增，删除时不再算新增或修改
+#[derive(Default)]
+struct PendingChanges {
+    added: BTreeSet<i64>,
+    updated: BTreeSet<i64>,
+    removed: BTreeSet<i64>,
+}
+
+impl PendingChanges {
+    fn record(&mut self, kind: ChangeKind, id: i64) {
+        match kind {
+            ChangeKind::Added => {
+                self.added.insert(id);
+            }
+            ChangeKind::Updated if !self.added.contains(&id) => {
+                self.updated.insert(id);
+            }
+            ChangeKind::Updated => {}
+            ChangeKind::Removed => {
+                // 合并期间新增又删除的音频对前端来说没有变化
+                if !self.added.remove(&id) {
+                    self.removed.insert(id);
+                }
+                self.updated.remove(&id);
+            }
+        }
+    }
+
+    fn into_summary(self) -> LibraryChangeSummary {
+        let (added, updated, removed) = (self.added.len(), self.updated.len(), self.removed.len());
+        let full_refresh = added + updated + removed > MAX_SUMMARY_IDS;
+        let ids = |set: BTreeSet<i64>| if full_refresh { Vec::new() } else { set.into_iter().collect() };
+        LibraryChangeSummary {
+            added,
+            updated,
+            removed,
+            added_ids: ids(self.added),
+            updated_ids: ids(self.updated),
+            removed_ids: ids(self.removed),
+            full_refresh,
+        }
+    }
+}
+
+/// 启动音库变化事件：收到变化后等到 QUIET_MILLIS 内没有新变化（最长 MAX_DELAY_MILLIS）再合并发出，
+/// 批量导入几百个文件时前端只会收到几次事件
+pub fn start_library_events(app: AppHandle) -> LibraryEvents {
+    let (sender, mut receiver) = mpsc::unbounded_channel();
+
+    tauri::async_runtime::spawn(async move {
+        while let Some((kind, id)) = receiver.recv().await {
+            let mut pending = PendingChanges::default();
+            pending.record(kind, id);
+
+            let flush_at = Instant::now() + Duration::from_millis(MAX_DELAY_MILLIS);
+            loop {
+                let deadline = (Instant::now() + Duration::from_millis(QUIET_MILLIS)).min(flush_at);
+                match timeout_at(deadline, receiver.recv()).await {
+                    Ok(Some((kind, id))) => pending.record(kind, id),
+                    // 超时或通道关闭
+                    _ => break,
+                }
+            }
+
+            let summary = pending.into_summary();
+            if summary.added + summary.updated + summary.removed > 0 {
+                let _ = app.emit_all(LIBRARY_CHANGED_EVENT, summary);
+            }
+        }
+    });
+
+    LibraryEvents { sender }
+}
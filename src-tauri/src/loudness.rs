@@ -0,0 +1,292 @@
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::BufReader;
+use std::sync::Arc;
+use tokio::sync::{Mutex, Notify};
+use tokio::time::Duration;
+use rodio::{Decoder, Source};
+use rusqlite::Connection;
+use tauri::State;
+use crate::player::AudioPlayer;
+
+// 是否开启响度归一化，保存在 app_settings 中（默认开启）
+const SETTING_KEY: &str = "loudness_normalization";
+// 是否跳过开头和结尾的静音，保存在 app_settings 中（默认关闭）
+const SKIP_SILENCE_KEY: &str = "skip_silence";
+// 归一化的目标响度（dBFS，按 50ms 块 RMS 的 95 百分位计算，与 ReplayGain 的做法相同）
+const TARGET_LOUDNESS_DB: f64 = -18.0;
+// 自动增益的最大调整幅度（dB）
+const MAX_GAIN_DB: f64 = 12.0;
+// 计算 RMS 的块长度（毫秒）
+const BLOCK_MILLIS: u64 = 50;
+const LOUDNESS_PERCENTILE: f64 = 0.95;
+// 块 RMS 低于此值（dBFS）视为静音
+const SILENCE_THRESHOLD_DB: f64 = -50.0;
+// 开头或结尾的静音至少这么长才跳过（秒）
+const MIN_SKIPPED_SILENCE_SECS: f64 = 0.5;
+// 跳过静音时在声音前后保留的余量（秒），避免切掉渐入渐出
+const SILENCE_MARGIN_SECS: f64 = 0.2;
+// 没有新导入通知时，隔一段时间再检查一次（也用于同步其他进程修改的开关）
+const IDLE_RECHECK_SECS: u64 = 60;
+
+/// 后台响度分析队列：loudness_db 为 NULL 的音频在后台逐个分析
+#[derive(Clone)]
+pub struct LoudnessQueue {
+    notify: Arc<Notify>,
+}
+
+impl LoudnessQueue {
+    /// 通知后台重新检查待分析的音频并刷新播放器的增益
+    pub fn wake(&self) {
+        self.notify.notify_one();
+    }
+}
+
+/// 一次解码得到的分析结果
+struct Analysis {
+    loudness_db: f64,
+    peak_db: f64,
+    /// 开头静音结束的位置（秒），开头没有明显静音时为0
+    silence_lead_secs: f64,
+    /// 结尾静音开始的位置（秒），结尾没有明显静音时为文件时长
+    silence_end_secs: f64,
+}
+
+/// 按块 RMS 找出开头和结尾的静音，返回声音开始和结束的位置（秒，已留出余量）
+fn silence_bounds(block_rms: &[f64]) -> (f64, f64) {
+    let block_secs = BLOCK_MILLIS as f64 / 1000.0;
+    let total = block_rms.len() as f64 * block_secs;
+    let threshold = 10f64.powf(SILENCE_THRESHOLD_DB / 20.0);
+    let first = block_rms.iter().position(|rms| *rms >= threshold).unwrap_or(0);
+    let last = block_rms.iter().rposition(|rms| *rms >= threshold).unwrap_or(block_rms.len() - 1);
+
+    let sound_start = first as f64 * block_secs;
+    let sound_end = (last + 1) as f64 * block_secs;
+    let lead = match sound_start >= MIN_SKIPPED_SILENCE_SECS {
+        true => sound_start - SILENCE_MARGIN_SECS,
+        false => 0.0,
+    };
+    let end = match total - sound_end >= MIN_SKIPPED_SILENCE_SECS {
+        true => sound_end + SILENCE_MARGIN_SECS,
+        false => total,
+    };
+    (lead, end)
+}
+
+/// 解码整个文件，返回响度、峰值（dBFS）和首尾静音的位置，无法解码或全是静音时返回 None
+fn measure(file_path: &str) -> Option<Analysis> {
+    let file = File::open(file_path).ok()?;
+    let source = Decoder::new(BufReader::new(file)).ok()?;
+    let block_len = (source.sample_rate() as u64 * source.channels() as u64 * BLOCK_MILLIS / 1000).max(1) as usize;
+
+    let mut block_rms = Vec::new();
+    let mut sum_squares = 0.0f64;
+    let mut count = 0usize;
+    let mut peak = 0i32;
+    for sample in source {
+        let value = sample as f64 / 32768.0;
+        sum_squares += value * value;
+        count += 1;
+        peak = peak.max((sample as i32).abs());
+        if count == block_len {
+            block_rms.push((sum_squares / count as f64).sqrt());
+            sum_squares = 0.0;
+            count = 0;
+        }
+    }
+    if count > 0 {
+        block_rms.push((sum_squares / count as f64).sqrt());
+    }
+    if peak == 0 || block_rms.is_empty() {
+        return None;
+    }
+    let (silence_lead_secs, silence_end_secs) = silence_bounds(&block_rms);
+
+    block_rms.sort_by(|a, b| a.total_cmp(b));
+    let index = ((block_rms.len() - 1) as f64 * LOUDNESS_PERCENTILE).round() as usize;
+    let loudness = block_rms[index].max(f64::MIN_POSITIVE);
+    Some(Analysis {
+        loudness_db: 20.0 * loudness.log10(),
+        peak_db: 20.0 * (peak as f64 / 32768.0).log10(),
+        silence_lead_secs,
+        silence_end_secs,
+    })
+}
+
+/// 达到目标响度所需的增益（dB），提升时不超过峰值余量，避免削波
+pub fn normalization_gain_db(loudness_db: f64, peak_db: f64) -> f64 {
+    (TARGET_LOUDNESS_DB - loudness_db)
+        .min(-peak_db)
+        .clamp(-MAX_GAIN_DB, MAX_GAIN_DB)
+}
+
+fn is_enabled(conn: &Connection) -> bool {
+    conn.query_row(
+        "SELECT value FROM app_settings WHERE key = ?1",
+        [SETTING_KEY],
+        |row| row.get::<_, String>(0),
+    )
+    .map(|value| value != "false")
+    .unwrap_or(true)
+}
+
+fn skip_silence_enabled(conn: &Connection) -> bool {
+    conn.query_row(
+        "SELECT value FROM app_settings WHERE key = ?1",
+        [SKIP_SILENCE_KEY],
+        |row| row.get::<_, String>(0),
+    )
+    .map(|value| value == "true")
+    .unwrap_or(false)
+}
+
+/// 已分析音频的归一化增益（dB），关闭归一化时为空
+fn load_gains(conn: &Connection) -> rusqlite::Result<HashMap<i64, f64>> {
+    if !is_enabled(conn) {
+        return Ok(HashMap::new());
+    }
+    conn.prepare("SELECT id, loudness_db, peak_db FROM audio_files WHERE loudness_db IS NOT NULL")?
+        .query_map([], |row| {
+            let (loudness_db, peak_db): (f64, Option<f64>) = (row.get(1)?, row.get(2)?);
+            Ok((row.get(0)?, normalization_gain_db(loudness_db, peak_db.unwrap_or(0.0))))
+        })?
+        .collect()
+}
+
+/// 已分析音频去掉首尾静音后的播放范围（秒），关闭跳过静音时为空
+fn load_silence_trims(conn: &Connection) -> rusqlite::Result<HashMap<i64, (f64, f64)>> {
+    if !skip_silence_enabled(conn) {
+        return Ok(HashMap::new());
+    }
+    conn.prepare(
+        "SELECT id, silence_lead_secs, silence_end_secs FROM audio_files
+         WHERE silence_lead_secs IS NOT NULL AND silence_end_secs IS NOT NULL",
+    )?
+    .query_map([], |row| Ok((row.get(0)?, (row.get(1)?, row.get(2)?))))?
+    .collect()
+}
+
+async fn refresh_player_gains(db: &Arc<Mutex<Connection>>, player: &Arc<Mutex<AudioPlayer>>) {
+    let (gains, trims) = {
+        let conn = db.lock().await;
+        (load_gains(&conn), load_silence_trims(&conn))
+    };
+    match (gains, trims) {
+        (Ok(gains), Ok(trims)) => {
+            let mut player = player.lock().await;
+            player.set_loudness_gains(gains);
+            player.set_silence_trims(trims);
+        }
+        (Err(e), _) | (_, Err(e)) => eprintln!("[Loudness] 读取响度数据失败: {}", e),
+    }
+}
+
+/// 查询尚未分析响度（或首尾静音）的音频
+fn load_pending(conn: &Connection) -> rusqlite::Result<Vec<(i64, String)>> {
+    conn.prepare(
+        "SELECT id, file_path FROM audio_files
+         WHERE loudness_db IS NULL OR silence_lead_secs IS NULL ORDER BY id",
+    )?
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect()
+}
+
+/// 启动后台响度分析队列，分析结果用于播放时的自动增益
+pub fn start_loudness_analyzer(db: Arc<Mutex<Connection>>, player: Arc<Mutex<AudioPlayer>>) -> LoudnessQueue {
+    let notify = Arc::new(Notify::new());
+    let queue = LoudnessQueue { notify: notify.clone() };
+
+    tauri::async_runtime::spawn(async move {
+        // 本次运行中无法分析的文件不再重复分析
+        let mut failed: HashSet<i64> = HashSet::new();
+
+        loop {
+            refresh_player_gains(&db, &player).await;
+
+            let pending = load_pending(&*db.lock().await).unwrap_or_else(|e| {
+                eprintln!("[Loudness] 查询待分析音频失败: {}", e);
+                Vec::new()
+            });
+            let pending: Vec<(i64, String)> = pending
+                .into_iter()
+                .filter(|(id, _)| !failed.contains(id))
+                .collect();
+
+            if pending.is_empty() {
+                let _ = tokio::time::timeout(Duration::from_secs(IDLE_RECHECK_SECS), notify.notified()).await;
+                continue;
+            }
+
+            for (audio_id, file_path) in pending {
+                let measured = tokio::task::spawn_blocking(move || measure(&file_path)).await.ok().flatten();
+                let Some(analysis) = measured else {
+                    eprintln!("[Loudness] 无法分析音频响度: {}", audio_id);
+                    failed.insert(audio_id);
+                    continue;
+                };
+
+                let conn = db.lock().await;
+                if let Err(e) = conn.execute(
+                    "UPDATE audio_files SET loudness_db = ?1, peak_db = ?2, silence_lead_secs = ?3, silence_end_secs = ?4
+                     WHERE id = ?5",
+                    (analysis.loudness_db, analysis.peak_db, analysis.silence_lead_secs, analysis.silence_end_secs, audio_id),
+                ) {
+                    eprintln!("[Loudness] 保存响度失败: {}", e);
+                    failed.insert(audio_id);
+                }
+            }
+        }
+    });
+
+    queue
+}
+
+#[tauri::command]
+pub async fn get_loudness_normalization(
+    conn: State<'_, Arc<Mutex<Connection>>>,
+) -> Result<bool, String> {
+    Ok(is_enabled(&*conn.lock().await))
+}
+
+/// 开启或关闭响度归一化，从下一首曲目开始生效
+#[tauri::command]
+pub async fn set_loudness_normalization(
+    enabled: bool,
+    conn: State<'_, Arc<Mutex<Connection>>>,
+    queue: State<'_, LoudnessQueue>,
+) -> Result<(), String> {
+    conn.lock()
+        .await
+        .execute(
+            "INSERT OR REPLACE INTO app_settings (key, value) VALUES (?1, ?2)",
+            (SETTING_KEY, enabled.to_string()),
+        )
+        .map_err(|e| e.to_string())?;
+    queue.wake();
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_skip_silence(
+    conn: State<'_, Arc<Mutex<Connection>>>,
+) -> Result<bool, String> {
+    Ok(skip_silence_enabled(&*conn.lock().await))
+}
+
+/// 开启或关闭跳过首尾静音，从下一首曲目开始生效
+#[tauri::command]
+pub async fn set_skip_silence(
+    enabled: bool,
+    conn: State<'_, Arc<Mutex<Connection>>>,
+    queue: State<'_, LoudnessQueue>,
+) -> Result<(), String> {
+    conn.lock()
+        .await
+        .execute(
+            "INSERT OR REPLACE INTO app_settings (key, value) VALUES (?1, ?2)",
+            (SKIP_SILENCE_KEY, enabled.to_string()),
+        )
+        .map_err(|e| e.to_string())?;
+    queue.wake();
+    Ok(())
+}
@@ -0,0 +1,206 @@
+use serde::Serialize;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use tokio::time::{interval, Duration};
+use rusqlite::Connection;
+use tauri::{State, AppHandle, Manager};
+use crate::player::AudioPlayer;
+use crate::i18n;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LyricLine {
+    pub time: f64,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CurrentLyric {
+    pub audio_id: i64,
+    pub index: usize,
+    pub time: f64,
+    pub text: String,
+    pub next_time: Option<f64>,
+    pub position: f64,
+}
+
+/// 解析LRC歌词，支持一行多个时间标签和 [offset:±毫秒] 标签
+pub fn parse_lrc(lrc: &str) -> Vec<LyricLine> {
+    let mut offset_secs = 0.0;
+    let mut lines = Vec::new();
+
+    for raw_line in lrc.lines() {
+        let mut rest = raw_line.trim();
+        let mut times = Vec::new();
+
+        while rest.starts_with('[') {
+            let end = match rest.find(']') {
+                Some(end) => end,
+                None => break,
+            };
+            let tag = &rest[1..end];
+            rest = &rest[end + 1..];
+
+            if let Some(value) = tag.strip_prefix("offset:") {
+                if let Ok(ms) = value.trim().parse::<f64>() {
+                    offset_secs = ms / 1000.0;
+                }
+            } else if let Some(time) = parse_lrc_time(tag) {
+                times.push(time);
+            }
+        }
+
+        for time in times {
+            lines.push(LyricLine {
+                time,
+                text: rest.trim().to_string(),
+            });
+        }
+    }
+
+    // LRC中offset为正表示歌词提前显示
+    for line in lines.iter_mut() {
+        line.time = (line.time - offset_secs).max(0.0);
+    }
+
+    lines.sort_by(|a, b| a.time.partial_cmp(&b.time).unwrap_or(std::cmp::Ordering::Equal));
+    lines
+}
+
+/// 解析 mm:ss 或 mm:ss.xx 格式的时间标签
+fn parse_lrc_time(tag: &str) -> Option<f64> {
+    let (minutes, seconds) = tag.split_once(':')?;
+    let minutes = minutes.trim().parse::<u32>().ok()?;
+    let seconds = seconds.trim().replace(':', ".").parse::<f64>().ok()?;
+    Some(minutes as f64 * 60.0 + seconds)
+}
+
+/// 根据播放位置查找当前歌词行
+pub fn find_line_index(lines: &[LyricLine], position: f64) -> Option<usize> {
+    lines.iter().rposition(|line| line.time <= position)
+}
+
+fn load_lyrics(conn: &Connection, audio_id: i64) -> Vec<LyricLine> {
+    conn.query_row(
+        "SELECT lrc FROM audio_lyrics WHERE audio_id = ?1",
+        [audio_id],
+        |row| row.get::<_, String>(0),
+    )
+    .map(|lrc| parse_lrc(&lrc))
+    .unwrap_or_default()
+}
+
+fn build_current_lyric(lines: &[LyricLine], audio_id: i64, position: f64) -> Option<CurrentLyric> {
+    let index = find_line_index(lines, position)?;
+    Some(CurrentLyric {
+        audio_id,
+        index,
+        time: lines[index].time,
+        text: lines[index].text.clone(),
+        next_time: lines.get(index + 1).map(|line| line.time),
+        position,
+    })
+}
+
+/// 启动歌词跟随监视器，歌词行变化时发送 lyric-line-changed 事件
+pub fn start_lyric_monitor(
+    app: AppHandle,
+    db: Arc<Mutex<Connection>>,
+    player: Arc<Mutex<AudioPlayer>>,
+) {
+    tauri::async_runtime::spawn(async move {
+        let mut ticker = interval(Duration::from_millis(200));
+        let mut cached: Option<(i64, Vec<LyricLine>)> = None;
+        let mut last_line: Option<(i64, usize)> = None;
+
+        loop {
+            ticker.tick().await;
+
+            let (audio_id, position) = {
+                let player = player.lock().await;
+                match player.current_audio_id() {
+                    Some(audio_id) => (audio_id, player.position_secs()),
+                    None => {
+                        last_line = None;
+                        continue;
+                    }
+                }
+            };
+
+            if cached.as_ref().map(|(id, _)| *id) != Some(audio_id) {
+                let conn = db.lock().await;
+                cached = Some((audio_id, load_lyrics(&conn, audio_id)));
+            }
+
+            let lines = match &cached {
+                Some((_, lines)) if !lines.is_empty() => lines,
+                _ => continue,
+            };
+
+            if let Some(current) = build_current_lyric(lines, audio_id, position) {
+                if last_line != Some((audio_id, current.index)) {
+                    last_line = Some((audio_id, current.index));
+                    let _ = app.emit_all("lyric-line-changed", current);
+                }
+            }
+        }
+    });
+}
+
+#[tauri::command]
+pub async fn set_audio_lyrics(
+    audio_id: i64,
+    lrc: String,
+    conn: State<'_, Arc<Mutex<Connection>>>,
+) -> Result<usize, String> {
+    let lines = parse_lrc(&lrc);
+    if lines.is_empty() {
+        return Err(i18n::tr("未解析到带时间标签的歌词"));
+    }
+
+    let conn = conn.lock().await;
+    conn.execute(
+        "INSERT OR REPLACE INTO audio_lyrics (audio_id, lrc, updated_date) VALUES (?1, ?2, datetime('now'))",
+        (audio_id, &lrc),
+    )
+    .map_err(|e| e.to_string())?;
+
+    Ok(lines.len())
+}
+
+#[tauri::command]
+pub async fn get_audio_lyrics(
+    audio_id: i64,
+    conn: State<'_, Arc<Mutex<Connection>>>,
+) -> Result<Vec<LyricLine>, String> {
+    let conn = conn.lock().await;
+    Ok(load_lyrics(&conn, audio_id))
+}
+
+#[tauri::command]
+pub async fn delete_audio_lyrics(
+    audio_id: i64,
+    conn: State<'_, Arc<Mutex<Connection>>>,
+) -> Result<(), String> {
+    let conn = conn.lock().await;
+    conn.execute("DELETE FROM audio_lyrics WHERE audio_id = ?1", [audio_id])
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_current_lyric_line(
+    player: State<'_, Arc<Mutex<AudioPlayer>>>,
+    conn: State<'_, Arc<Mutex<Connection>>>,
+) -> Result<Option<CurrentLyric>, String> {
+    let (audio_id, position) = {
+        let player = player.lock().await;
+        match player.current_audio_id() {
+            Some(audio_id) => (audio_id, player.position_secs()),
+            None => return Ok(None),
+        }
+    };
+
+    let conn = conn.lock().await;
+    let lines = load_lyrics(&conn, audio_id);
+    Ok(build_current_lyric(&lines, audio_id, position))
+}
@@ -12,21 +12,80 @@ mod settings;
 mod recorder;
 mod autostart;
 mod restart;
+mod variant;
+mod lyrics;
+mod pronunciation;
+mod download;
+mod service;
+mod library;
+mod weekday;
+mod probe;
+mod pause;
+mod editor;
+mod bell;
+mod countdown;
+mod export;
+mod formats;
+mod preflight;
+mod simulate;
+mod i18n;
+mod samples;
+mod playcount;
+mod loudness;
+mod stream;
+mod presets;
+mod archive;
+mod kiosk;
+mod xlsx;
+mod resume;
+mod sleep_timer;
+mod removable;
+mod mediainfo;
+mod rename;
+mod remote;
+mod trace;
+mod storage;
+mod quota;
+mod speed;
+mod interrupt;
+mod tray;
+mod diagnostics;
+mod ical;
+mod library_events;
+mod handoff;
+mod holiday;
+mod cron;
 
 use tauri::{Manager, SystemTray, SystemTrayEvent, SystemTrayMenu, SystemTrayMenuItem, CustomMenuItem};
 use std::sync::Arc;
 use tokio::sync::Mutex;
 
 fn main() {
-    // 创建系统托盘菜单
+    // 后台服务模式：只运行调度器和播放器，不创建窗口
+    if service::is_service_mode() {
+        service::run_service();
+        return;
+    }
+
+    // 创建系统托盘菜单（数据库就绪后按语言设置刷新文字）
     let show = CustomMenuItem::new("show".to_string(), "显示主窗口");
     let hide = CustomMenuItem::new("hide".to_string(), "隐藏窗口");
+    let play_pause = CustomMenuItem::new(player::TRAY_ITEM_ID.to_string(), "播放/暂停");
+    let mute = CustomMenuItem::new(player::TRAY_MUTE_ITEM_ID.to_string(), "静音/取消静音");
+    let toggle_pause = CustomMenuItem::new(pause::TRAY_ITEM_ID.to_string(), pause::tray_title(false));
+    let countdown = CustomMenuItem::new(countdown::TRAY_ITEM_ID.to_string(), countdown::tray_title());
     let quit = CustomMenuItem::new("quit".to_string(), "退出应用");
 
     let tray_menu = SystemTrayMenu::new()
         .add_item(show)
         .add_item(hide)
         .add_native_item(SystemTrayMenuItem::Separator)
+        .add_item(play_pause)
+        .add_item(mute)
+        .add_native_item(SystemTrayMenuItem::Separator)
+        .add_item(toggle_pause)
+        .add_item(countdown)
+        .add_native_item(SystemTrayMenuItem::Separator)
         .add_item(quit);
 
     let system_tray = SystemTray::new().with_menu(tray_menu);
@@ -34,10 +93,13 @@ fn main() {
     tauri::Builder::default()
         .system_tray(system_tray)
         .on_system_tray_event(|app, event| match event {
-            SystemTrayEvent::LeftClick { .. } => {
-                let window = app.get_window("main").unwrap();
-                window.show().unwrap();
-                window.set_focus().unwrap();
+            // 单击/双击托盘图标的动作可在设置中修改（显示窗口、播放/暂停、执行默认任务）
+            SystemTrayEvent::LeftClick { .. } | SystemTrayEvent::DoubleClick { .. } => {
+                let double_click = matches!(event, SystemTrayEvent::DoubleClick { .. });
+                let app = app.clone();
+                tauri::async_runtime::spawn(async move {
+                    tray::handle_click(&app, double_click).await;
+                });
             }
             SystemTrayEvent::MenuItemClick { id, .. } => {
                 match id.as_str() {
@@ -46,10 +108,36 @@ fn main() {
                         window.show().unwrap();
                         window.set_focus().unwrap();
                     }
+                    // 儿童模式下不能从托盘隐藏窗口或退出
+                    "hide" | "quit" if kiosk::is_active(app) => {}
                     "hide" => {
                         let window = app.get_window("main").unwrap();
                         window.hide().unwrap();
                     }
+                    player::TRAY_ITEM_ID => {
+                        let app = app.clone();
+                        tauri::async_runtime::spawn(async move {
+                            player::toggle_from_tray(&app).await;
+                        });
+                    }
+                    player::TRAY_MUTE_ITEM_ID => {
+                        let app = app.clone();
+                        tauri::async_runtime::spawn(async move {
+                            player::toggle_mute_from_tray(&app).await;
+                        });
+                    }
+                    pause::TRAY_ITEM_ID => {
+                        let app = app.clone();
+                        tauri::async_runtime::spawn(async move {
+                            pause::toggle_from_tray(&app).await;
+                        });
+                    }
+                    countdown::TRAY_ITEM_ID => {
+                        let app = app.clone();
+                        tauri::async_runtime::spawn(async move {
+                            countdown::start_from_tray(&app).await;
+                        });
+                    }
                     "quit" => {
                         std::process::exit(0);
                     }
@@ -58,9 +146,13 @@ fn main() {
             }
             _ => {}
         })
+        .register_uri_scheme_protocol(stream::STREAM_SCHEME, stream::handle_stream_request)
         .on_window_event(|event| match event.event() {
             tauri::WindowEvent::CloseRequested { api, .. } => {
-                event.window().hide().unwrap();
+                // 儿童模式下窗口保持全屏显示
+                if !kiosk::is_active(&event.window().app_handle()) {
+                    event.window().hide().unwrap();
+                }
                 api.prevent_close();
             }
             _ => {}
@@ -81,35 +173,134 @@ fn main() {
             let audio_dir = app_dir.join("audio");
             std::fs::create_dir_all(&audio_dir).expect("Failed to create audio dir");
 
+            // 播放器使用设置中的默认音量和声道设置
+            let mut audio_player = player::AudioPlayer::new();
+            audio_player.set_max_volume(kiosk::load_max_volume(&conn) as f32 / 100.0);
+            let default_volume = settings::load_settings(&conn)
+                .map(|app_settings| player::default_volume(&app_settings))
+                .ok();
+            if let Some(volume) = default_volume {
+                audio_player.set_volume(volume);
+            }
+            audio_player.set_channel_mix(player::load_channel_mix(&conn));
+
             // 创建共享状态
             let db_conn = Arc::new(Mutex::new(conn));
-            let audio_player = Arc::new(Mutex::new(player::AudioPlayer::new()));
+            let audio_player = Arc::new(Mutex::new(audio_player));
             let audio_recorder = Arc::new(Mutex::new(recorder::AudioRecorder::new()));
 
             // 启动定时任务调度器
-            let scheduler = scheduler::Scheduler::new(db_conn.clone(), audio_player.clone());
+            let scheduler = scheduler::Scheduler::new(db_conn.clone(), audio_player.clone())
+                .defer_to_service(app_dir.clone());
+            app.manage(scheduler.waker());
             tauri::async_runtime::spawn(async move {
                 scheduler.start().await;
             });
 
+            // 启动歌词跟随监视器
+            lyrics::start_lyric_monitor(app_handle.clone(), db_conn.clone(), audio_player.clone());
+
+            // 启动播放队列监视器（曲目播完时通知前端，手动播放播放列表时按播放模式接着播放）
+            player::start_queue_monitor(app_handle.clone(), db_conn.clone(), audio_player.clone());
+
+            // 播放状态变化时通知前端和托盘，无需轮询
+            player::start_state_events(app_handle.clone(), audio_player.clone());
+
+            // 设置变更广播：保存设置后立即应用，无需重启
+            let settings_bus = settings::SettingsBus::default();
+            player::start_settings_listener(audio_player.clone(), default_volume, settings_bus.subscribe());
+
+            // 启动定时停止监视器（倒计时结束前渐弱并停止播放）
+            sleep_timer::start_sleep_monitor(audio_player.clone());
+
+            // 记住长音频的播放位置，并恢复上次未播完的曲目（暂停状态）
+            resume::start_position_saver(db_conn.clone(), audio_player.clone());
+            {
+                let (db, player) = (db_conn.clone(), audio_player.clone());
+                tauri::async_runtime::spawn(async move {
+                    resume::restore_last_track(&db, &player).await;
+                });
+            }
+
+            // 启动播放计数监视器
+            playcount::start_play_count_monitor(db_conn.clone(), audio_player.clone());
+
+            // 启动每日收听时长监视器（用完时停止播放）
+            quota::start_quota_monitor(Some(app_handle.clone()), db_conn.clone(), audio_player.clone());
+
+            // 空闲时在托盘提示中显示下一个任务
+            tray::start_tooltip_refresher(app_handle.clone(), audio_player.clone());
+
+            // 其他应用占用麦克风（通话、网课）时自动暂停
+            interrupt::start_capture_monitor(Some(app_handle.clone()), db_conn.clone(), audio_player.clone());
+
+            // 启动任务预检监视器
+            preflight::start_preflight_monitor(app_handle.clone(), db_conn.clone());
+
+            // 插入含音频的U盘时提示导入
+            removable::start_drive_monitor(app_handle.clone());
+
+            // 启动旧录音自动归档
+            archive::start_archive_monitor(app_handle.clone(), db_conn.clone(), audio_player.clone(), audio_dir.clone());
+
+            // 启动后台响度分析队列
+            let loudness_queue = loudness::start_loudness_analyzer(db_conn.clone(), audio_player.clone());
+
+            // 音库变化合并后通知前端（批量导入时不逐个文件发出）
+            let library_events = library_events::start_library_events(app_handle.clone());
+
+            // 启动后台时长检测队列
+            let probe_queue = probe::start_duration_prober(app_handle.clone(), db_conn.clone(), library_events.clone());
+
+            // 按语言设置刷新托盘菜单，并显示持久化的暂停状态；恢复儿童模式
+            let kiosk_state = kiosk::KioskState::default();
+            let paused = tauri::async_runtime::block_on(async {
+                let conn = db_conn.lock().await;
+                i18n::load_language(&conn);
+                kiosk::restore(&app_handle, &conn, &kiosk_state);
+                pause::load_pause_state(&conn).paused
+            });
+            i18n::refresh_tray(&app_handle, paused);
+
             // 将状态放入管理
             app.manage(db_conn);
             app.manage(audio_dir.clone());
             app.manage(audio_player);
             app.manage(audio_recorder);
+            app.manage(probe_queue);
+            app.manage(library_events);
+            app.manage(loudness_queue);
+            app.manage(countdown::CountdownTimer::default());
+            app.manage(audio::ExtractionJobs::default());
+            app.manage(settings_bus);
+            app.manage(kiosk_state);
 
             Ok(())
         })
-        .invoke_handler(tauri::generate_handler![
+        .invoke_handler(kiosk::guard(tauri::generate_handler![
             audio::upload_audio_file,
             audio::get_audio_files,
             audio::delete_audio_file,
+            audio::get_audio_usage,
+            audio::get_audio_detail,
             audio::scan_audio_directory,
             player::play_audio,
             player::pause_audio,
+            player::resume_audio,
+            player::toggle_play_pause,
+            player::set_ab_loop,
+            player::clear_ab_loop,
+            player::play_ab_compare,
+            player::switch_ab_compare,
+            player::reshuffle_queue,
             player::stop_audio,
             player::set_volume,
+            player::mute_audio,
+            player::unmute_audio,
+            player::get_channel_mix,
+            player::set_channel_mix,
             player::set_speed,
+            player::set_audio_preferred_speed,
             player::get_playback_state,
             player::play_next,
             player::play_previous,
@@ -117,21 +308,33 @@ fn main() {
             playlist::get_playlists,
             playlist::create_playlist,
             playlist::delete_playlist,
+            playlist::get_playlist_delete_impact,
+            playlist::get_deleted_playlists,
+            playlist::restore_playlist,
             playlist::set_playlist_mode,
+            playlist::set_playlist_gapless,
             playlist::get_playlist_items,
             playlist::add_to_playlist,
+            playlist::set_playlist_item_clip,
+            playlist::set_playlist_item_gain,
+            playlist::set_playlist_item_note,
             playlist::remove_from_playlist,
             playlist::check_playlist_tasks,
             task::get_scheduled_tasks,
             task::create_scheduled_task,
             task::update_scheduled_task,
+            task::bulk_update_tasks,
             task::delete_scheduled_task,
+            task::get_deleted_tasks,
+            task::restore_task,
             task::toggle_scheduled_task,
             task::check_task_conflicts,
             stats::get_statistics,
             stats::get_top_audios,
             stats::get_daily_activity,
             stats::get_monthly_playback,
+            stats::get_sessions,
+            stats::get_day_schedule,
             settings::get_settings,
             settings::save_settings,
             settings::get_data_usage,
@@ -140,14 +343,108 @@ fn main() {
             recorder::start_recording,
             recorder::stop_recording,
             recorder::get_recording_state,
+            recorder::add_recording_marker,
+            recorder::get_audio_markers,
+            recorder::delete_audio_marker,
             audio::extract_audio_from_video,
             audio::extract_audio_from_online_video,
+            audio::refresh_audio_from_source,
             audio::check_ffmpeg_status,
             audio::install_ffmpeg,
+            audio::install_ytdlp,
+            service::get_background_service_status,
+            service::set_background_service,
             restart::restart_app,
             autostart::get_auto_launch_status,
             autostart::set_auto_launch,
-        ])
+            variant::render_speed_variants,
+            variant::get_speed_variants,
+            variant::delete_speed_variants,
+            lyrics::set_audio_lyrics,
+            lyrics::get_audio_lyrics,
+            lyrics::delete_audio_lyrics,
+            lyrics::get_current_lyric_line,
+            pronunciation::compare_pronunciation,
+            pronunciation::get_pronunciation_history,
+            library::get_library_health,
+            pause::get_scheduler_pause,
+            pause::pause_scheduler,
+            pause::resume_scheduler,
+            editor::split_audio_by_silence,
+            editor::merge_audios,
+            bell::generate_bell_schedule,
+            countdown::start_countdown,
+            countdown::cancel_countdown,
+            countdown::get_countdown,
+            export::export_schedule,
+            export::export_history_xlsx,
+            formats::get_supported_formats,
+            preflight::preflight_check_task,
+            simulate::simulate_schedule,
+            samples::get_sample_packs,
+            samples::install_sample_pack,
+            playcount::get_play_count_rule,
+            playcount::set_play_count_rule,
+            loudness::get_loudness_normalization,
+            loudness::set_loudness_normalization,
+            presets::get_recording_templates,
+            presets::create_recording_template,
+            presets::update_recording_template,
+            presets::delete_recording_template,
+            archive::get_archive_policy,
+            archive::set_archive_policy,
+            archive::archive_recordings_now,
+            kiosk::get_kiosk_status,
+            kiosk::set_parental_pin,
+            kiosk::enter_kiosk_mode,
+            kiosk::exit_kiosk_mode,
+            resume::get_resume_positions,
+            resume::clear_resume_position,
+            sleep_timer::set_sleep_timer,
+            sleep_timer::cancel_sleep_timer,
+            removable::get_removable_drives,
+            removable::import_drive_folders,
+            mediainfo::get_media_info,
+            rename::batch_rename,
+            remote::play_stream,
+            trace::get_scheduler_trace,
+            trace::get_scheduler_trace_level,
+            trace::set_scheduler_trace_level,
+            loudness::get_skip_silence,
+            loudness::set_skip_silence,
+            kiosk::set_max_volume,
+            storage::get_storage_layout,
+            storage::set_storage_layout,
+            storage::reorganize_library,
+            quota::get_listening_quota,
+            quota::set_listening_quota,
+            playlist::set_playlist_exclude_learned,
+            player::set_audio_learned,
+            task::clone_task,
+            speed::speed_up,
+            speed::speed_down,
+            speed::set_speed_preset,
+            speed::get_speed_step,
+            speed::set_speed_step,
+            interrupt::get_capture_pause_policy,
+            interrupt::set_capture_pause_policy,
+            playlist::set_playlist_weekly_target,
+            storage::find_orphaned_files,
+            storage::delete_orphaned_files,
+            storage::import_orphaned_files,
+            tray::get_tray_actions,
+            tray::set_tray_actions,
+            diagnostics::get_diagnostics,
+            task::get_next_task,
+            ical::export_schedule_ics,
+            ical::import_schedule_ics,
+            handoff::get_task_handoff,
+            handoff::set_task_handoff,
+            holiday::get_holidays,
+            holiday::add_holiday,
+            holiday::delete_holiday,
+            holiday::import_holidays,
+        ]))
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
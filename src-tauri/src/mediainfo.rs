@@ -0,0 +1,222 @@
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use rusqlite::Connection;
+use tauri::{AppHandle, State};
+use symphonia::core::formats::FormatOptions;
+use symphonia::core::io::MediaSourceStream;
+use symphonia::core::meta::{MetadataOptions, StandardTagKey, Tag};
+use symphonia::core::probe::{Hint, ProbeResult};
+use crate::audio::{create_command_from_path, get_ffmpeg_executable_path};
+use crate::i18n;
+use crate::probe::ffprobe_path_for;
+
+/// 音频文件的编码信息和内嵌标签
+#[derive(Debug, Default, Serialize)]
+pub struct MediaInfo {
+    /// 读取方式：ffprobe 或 symphonia（没有 FFmpeg 时）
+    pub source: String,
+    /// 容器格式，如 mp3、mov,mp4,m4a
+    pub container: Option<String>,
+    /// 编码，如 mp3、aac
+    pub codec: Option<String>,
+    /// 编码全称，仅 ffprobe
+    pub codec_long_name: Option<String>,
+    /// 码率（bps），symphonia 读取时按文件大小和时长估算
+    pub bitrate: Option<i64>,
+    pub sample_rate: Option<u32>,
+    pub channels: Option<u32>,
+    /// 声道布局，如 stereo，仅 ffprobe
+    pub channel_layout: Option<String>,
+    pub bits_per_sample: Option<u32>,
+    pub duration_secs: Option<f64>,
+    pub file_size: Option<u64>,
+    /// 内嵌标签（标题、艺术家、专辑等），键为文件中的原始名称
+    pub tags: BTreeMap<String, String>,
+}
+
+/// JSON 中数字或数字字符串（ffprobe 的采样率、码率等输出为字符串）
+fn json_number(value: &serde_json::Value) -> Option<f64> {
+    match value {
+        serde_json::Value::Number(number) => number.as_f64(),
+        serde_json::Value::String(text) => text.parse().ok(),
+        _ => None,
+    }
+}
+
+fn json_string(value: &serde_json::Value) -> Option<String> {
+    value.as_str().filter(|text| !text.is_empty() && *text != "unknown").map(str::to_string)
+}
+
+fn collect_json_tags(tags: &serde_json::Value, into: &mut BTreeMap<String, String>) {
+    if let Some(tags) = tags.as_object() {
+        for (key, value) in tags {
+            if let Some(value) = value.as_str() {
+                into.entry(key.clone()).or_insert_with(|| value.to_string());
+            }
+        }
+    }
+}
+
+/// 使用 ffprobe 读取第一条音轨和容器的信息
+fn probe_with_ffprobe(ffprobe_path: &PathBuf, file_path: &Path) -> Option<MediaInfo> {
+    let output = create_command_from_path(ffprobe_path)
+        .arg("-v")
+        .arg("error")
+        .arg("-print_format")
+        .arg("json")
+        .arg("-show_format")
+        .arg("-show_streams")
+        .arg("-select_streams")
+        .arg("a:0")
+        .arg(file_path)
+        .output()
+        .ok()?;
+
+    if !output.status.success() {
+        return None;
+    }
+
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).ok()?;
+    let format = &json["format"];
+    let stream = &json["streams"][0];
+    if stream.is_null() {
+        return None;
+    }
+
+    let mut tags = BTreeMap::new();
+    collect_json_tags(&format["tags"], &mut tags);
+    collect_json_tags(&stream["tags"], &mut tags);
+
+    Some(MediaInfo {
+        source: "ffprobe".to_string(),
+        container: json_string(&format["format_name"]),
+        codec: json_string(&stream["codec_name"]),
+        codec_long_name: json_string(&stream["codec_long_name"]),
+        bitrate: json_number(&stream["bit_rate"]).or_else(|| json_number(&format["bit_rate"])).map(|bps| bps as i64),
+        sample_rate: json_number(&stream["sample_rate"]).map(|rate| rate as u32),
+        channels: json_number(&stream["channels"]).map(|channels| channels as u32),
+        channel_layout: json_string(&stream["channel_layout"]),
+        bits_per_sample: json_number(&stream["bits_per_raw_sample"])
+            .or_else(|| json_number(&stream["bits_per_sample"]))
+            .filter(|bits| *bits > 0.0)
+            .map(|bits| bits as u32),
+        duration_secs: json_number(&stream["duration"]).or_else(|| json_number(&format["duration"])),
+        file_size: json_number(&format["size"]).map(|size| size as u64),
+        tags,
+    })
+}
+
+fn symphonia_probe(file_path: &Path) -> Option<ProbeResult> {
+    let file = fs::File::open(file_path).ok()?;
+    let mss = MediaSourceStream::new(Box::new(file), Default::default());
+
+    let mut hint = Hint::new();
+    if let Some(extension) = file_path.extension().and_then(|ext| ext.to_str()) {
+        hint.with_extension(extension);
+    }
+
+    symphonia::default::get_probe()
+        .format(&hint, mss, &FormatOptions::default(), &MetadataOptions::default())
+        .ok()
+}
+
+/// 容器中和探测阶段读到的全部标签（ID3v2 等在探测阶段读取）
+fn probed_tags(probed: &mut ProbeResult) -> Vec<Tag> {
+    let mut tags: Vec<Tag> = probed.format.metadata().current().map(|revision| revision.tags().to_vec()).unwrap_or_default();
+    if let Some(metadata) = probed.metadata.get() {
+        tags.extend(metadata.current().map(|revision| revision.tags().to_vec()).unwrap_or_default());
+    }
+    tags
+}
+
+/// 读取常用的内嵌标签，键统一为 title、artist、album、track、date
+pub(crate) fn read_standard_tags(file_path: &Path) -> BTreeMap<String, String> {
+    let mut tags = BTreeMap::new();
+    let Some(mut probed) = symphonia_probe(file_path) else { return tags };
+    for tag in probed_tags(&mut probed) {
+        let key = match tag.std_key {
+            Some(StandardTagKey::TrackTitle) => "title",
+            Some(StandardTagKey::Artist) => "artist",
+            Some(StandardTagKey::Album) => "album",
+            Some(StandardTagKey::TrackNumber) => "track",
+            Some(StandardTagKey::Date) => "date",
+            _ => continue,
+        };
+        let value = tag.value.to_string();
+        if !value.trim().is_empty() {
+            tags.entry(key.to_string()).or_insert_with(|| value.trim().to_string());
+        }
+    }
+    tags
+}
+
+/// 使用 symphonia 读取默认音轨的信息（没有 FFmpeg 时）
+fn probe_with_symphonia(file_path: &Path) -> Option<MediaInfo> {
+    let file_size = fs::metadata(file_path).ok().map(|metadata| metadata.len());
+    let mut probed = symphonia_probe(file_path)?;
+    let params = probed.format.default_track()?.codec_params.clone();
+
+    let duration_secs = match (params.time_base, params.n_frames) {
+        (Some(time_base), Some(n_frames)) => Some(n_frames as f64 * time_base.numer as f64 / time_base.denom as f64),
+        (None, Some(n_frames)) => params.sample_rate.map(|rate| n_frames as f64 / rate as f64),
+        _ => None,
+    };
+
+    let mut tags = BTreeMap::new();
+    for tag in probed_tags(&mut probed) {
+        tags.entry(tag.key.clone()).or_insert_with(|| tag.value.to_string());
+    }
+
+    Some(MediaInfo {
+        source: "symphonia".to_string(),
+        container: file_path.extension().and_then(|ext| ext.to_str()).map(str::to_lowercase),
+        codec: symphonia::default::get_codecs().get_codec(params.codec).map(|codec| codec.short_name.to_string()),
+        codec_long_name: None,
+        bitrate: match (file_size, duration_secs) {
+            (Some(size), Some(secs)) if secs > 0.0 => Some((size as f64 * 8.0 / secs) as i64),
+            _ => None,
+        },
+        sample_rate: params.sample_rate,
+        channels: params.channels.map(|channels| channels.count() as u32),
+        channel_layout: None,
+        bits_per_sample: params.bits_per_sample,
+        duration_secs,
+        file_size,
+        tags,
+    })
+}
+
+/// 音频文件的编码、码率、采样率、声道和内嵌标签；有 FFmpeg 时用 ffprobe，否则用 symphonia
+#[tauri::command]
+pub async fn get_media_info(
+    id: i64,
+    app: AppHandle,
+    conn: State<'_, Arc<Mutex<Connection>>>,
+) -> Result<MediaInfo, String> {
+    let file_path: String = {
+        let conn = conn.lock().await;
+        conn.query_row("SELECT file_path FROM audio_files WHERE id = ?1", [id], |row| row.get(0))
+            .map_err(|e| e.to_string())?
+    };
+    if !Path::new(&file_path).exists() {
+        return Err(i18n::tr("文件不存在"));
+    }
+
+    let ffprobe_path = get_ffmpeg_executable_path(Some(&app))
+        .await
+        .map(|ffmpeg| ffprobe_path_for(&ffmpeg));
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let path = Path::new(&file_path);
+        ffprobe_path
+            .and_then(|ffprobe| probe_with_ffprobe(&ffprobe, path))
+            .or_else(|| probe_with_symphonia(path))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .ok_or_else(|| i18n::tr("无法读取音频信息"))
+}
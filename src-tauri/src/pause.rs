@@ -0,0 +1,148 @@
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use rusqlite::Connection;
+use chrono::{Duration, Local, NaiveTime};
+use tauri::{AppHandle, Manager, State};
+use crate::i18n;
+
+// 暂停状态保存在 app_settings 中，后台服务进程也能读取
+const PAUSE_SETTING_KEY: &str = "scheduler_pause";
+const TIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+/// 托盘菜单中暂停开关的菜单项ID
+pub const TRAY_ITEM_ID: &str = "toggle_scheduler_pause";
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SchedulerPauseState {
+    pub paused: bool,
+    /// 自动恢复时间（本地时间 YYYY-MM-DD HH:MM:SS），为空表示需要手动恢复
+    pub resume_at: Option<String>,
+}
+
+/// 读取暂停状态，已过自动恢复时间时清除暂停
+pub fn load_pause_state(conn: &Connection) -> SchedulerPauseState {
+    let state: SchedulerPauseState = conn
+        .query_row(
+            "SELECT value FROM app_settings WHERE key = ?1",
+            [PAUSE_SETTING_KEY],
+            |row| row.get::<_, String>(0),
+        )
+        .ok()
+        .and_then(|value| serde_json::from_str(&value).ok())
+        .unwrap_or_default();
+
+    if let (true, Some(resume_at)) = (state.paused, &state.resume_at) {
+        if Local::now().format(TIME_FORMAT).to_string() >= *resume_at {
+            println!("[Scheduler] 已到自动恢复时间 {}，恢复定时任务", resume_at);
+            let resumed = SchedulerPauseState::default();
+            let _ = save_pause_state(conn, &resumed);
+            return resumed;
+        }
+    }
+
+    state
+}
+
+fn save_pause_state(conn: &Connection, state: &SchedulerPauseState) -> Result<(), String> {
+    let value = serde_json::to_string(state).map_err(|e| e.to_string())?;
+    conn.execute(
+        "INSERT OR REPLACE INTO app_settings (key, value) VALUES (?1, ?2)",
+        (PAUSE_SETTING_KEY, &value),
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// 计算自动恢复时间：hours 小时后，或下一个 until（HH:MM）时刻
+fn resolve_resume_at(hours: Option<f64>, until: Option<&str>) -> Result<Option<String>, String> {
+    let now = Local::now();
+
+    if let Some(until) = until {
+        let time = NaiveTime::parse_from_str(until, "%H:%M")
+            .map_err(|_| i18n::trf("无效的恢复时间: {}", &[&until]))?;
+        let mut resume = now.date_naive().and_time(time);
+        if resume <= now.naive_local() {
+            resume += Duration::days(1);
+        }
+        return Ok(Some(resume.format(TIME_FORMAT).to_string()));
+    }
+
+    match hours {
+        Some(hours) if hours > 0.0 => {
+            let resume = now + Duration::seconds((hours * 3600.0) as i64);
+            Ok(Some(resume.format(TIME_FORMAT).to_string()))
+        }
+        Some(_) => Err(i18n::tr("暂停时长必须大于0")),
+        None => Ok(None),
+    }
+}
+
+/// 托盘菜单中暂停开关的文字（按当前语言）
+pub fn tray_title(paused: bool) -> String {
+    i18n::tr(if paused { "恢复定时任务" } else { "暂停所有定时任务" })
+}
+
+/// 同步托盘菜单文字并通知前端
+fn notify_pause_changed(app: &AppHandle, state: &SchedulerPauseState) {
+    let _ = app.tray_handle().get_item(TRAY_ITEM_ID).set_title(tray_title(state.paused));
+    let _ = app.emit_all("scheduler-pause-changed", state.clone());
+}
+
+/// 托盘菜单：在暂停与恢复之间切换（托盘暂停不自动恢复）
+pub async fn toggle_from_tray(app: &AppHandle) {
+    let conn = app.state::<Arc<Mutex<Connection>>>();
+    let conn = conn.lock().await;
+    let state = if load_pause_state(&conn).paused {
+        SchedulerPauseState::default()
+    } else {
+        SchedulerPauseState { paused: true, resume_at: None }
+    };
+
+    match save_pause_state(&conn, &state) {
+        Ok(()) => notify_pause_changed(app, &state),
+        Err(e) => eprintln!("切换定时任务暂停状态失败: {}", e),
+    }
+}
+
+#[tauri::command]
+pub async fn get_scheduler_pause(
+    app: AppHandle,
+    conn: State<'_, Arc<Mutex<Connection>>>,
+) -> Result<SchedulerPauseState, String> {
+    let conn = conn.lock().await;
+    let state = load_pause_state(&conn);
+    notify_pause_changed(&app, &state);
+    Ok(state)
+}
+
+/// 暂停所有定时任务，可指定 hours 小时后或 until（HH:MM）自动恢复
+#[tauri::command]
+pub async fn pause_scheduler(
+    hours: Option<f64>,
+    until: Option<String>,
+    app: AppHandle,
+    conn: State<'_, Arc<Mutex<Connection>>>,
+) -> Result<SchedulerPauseState, String> {
+    let state = SchedulerPauseState {
+        paused: true,
+        resume_at: resolve_resume_at(hours, until.as_deref())?,
+    };
+
+    let conn = conn.lock().await;
+    save_pause_state(&conn, &state)?;
+    notify_pause_changed(&app, &state);
+    Ok(state)
+}
+
+#[tauri::command]
+pub async fn resume_scheduler(
+    app: AppHandle,
+    conn: State<'_, Arc<Mutex<Connection>>>,
+) -> Result<(), String> {
+    let state = SchedulerPauseState::default();
+    let conn = conn.lock().await;
+    save_pause_state(&conn, &state)?;
+    notify_pause_changed(&app, &state);
+    Ok(())
+}
@@ -0,0 +1,108 @@
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use tokio::time::{interval, Duration};
+use rusqlite::Connection;
+use tauri::State;
+use crate::i18n;
+use crate::player::AudioPlayer;
+
+// 计数规则保存在 app_settings 中，后台服务进程也能读取
+const RULE_SETTING_KEY: &str = "play_count_rule";
+// 检查实际播放时长的间隔（毫秒）
+const CHECK_INTERVAL_MILLIS: u64 = 500;
+
+/// 播放计数规则：实际播放达到 min_secs 秒或曲目时长的 min_percent% 时计一次（任一满足即可），
+/// 两者都为 0 时开始播放即计数
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct PlayCountRule {
+    pub min_percent: f64,
+    pub min_secs: f64,
+}
+
+impl Default for PlayCountRule {
+    fn default() -> Self {
+        Self { min_percent: 50.0, min_secs: 30.0 }
+    }
+}
+
+impl PlayCountRule {
+    /// 实际播放了 listened_secs 秒（曲目时间）是否足以计数，track_secs 为曲目（片段）长度
+    pub fn is_satisfied(&self, listened_secs: f64, track_secs: Option<f64>) -> bool {
+        if self.min_percent <= 0.0 && self.min_secs <= 0.0 {
+            return true;
+        }
+        if self.min_secs > 0.0 && listened_secs >= self.min_secs {
+            return true;
+        }
+        match track_secs.filter(|secs| *secs > 0.0) {
+            Some(track_secs) => self.min_percent > 0.0 && listened_secs * 100.0 >= track_secs * self.min_percent,
+            None => false,
+        }
+    }
+}
+
+pub fn load_rule(conn: &Connection) -> PlayCountRule {
+    conn.query_row(
+        "SELECT value FROM app_settings WHERE key = ?1",
+        [RULE_SETTING_KEY],
+        |row| row.get::<_, String>(0),
+    )
+    .ok()
+    .and_then(|value| serde_json::from_str(&value).ok())
+    .unwrap_or_default()
+}
+
+/// 启动播放计数监视器：当前曲目的实际播放时长满足规则时，播放次数加一（每次播放只计一次）
+pub fn start_play_count_monitor(db: Arc<Mutex<Connection>>, player: Arc<Mutex<AudioPlayer>>) {
+    tauri::async_runtime::spawn(async move {
+        let mut ticker = interval(Duration::from_millis(CHECK_INTERVAL_MILLIS));
+
+        loop {
+            ticker.tick().await;
+
+            if player.lock().await.current_audio_id().is_none() {
+                continue;
+            }
+
+            let rule = load_rule(&*db.lock().await);
+            let counted = player.lock().await.take_countable_play(&rule);
+            if let Some(audio_id) = counted {
+                let conn = db.lock().await;
+                if let Err(e) = conn.execute(
+                    "UPDATE audio_files SET play_count = play_count + 1, last_played = datetime('now') WHERE id = ?1",
+                    [audio_id],
+                ) {
+                    eprintln!("[PlayCount] 更新播放次数失败: {}", e);
+                }
+            }
+        }
+    });
+}
+
+#[tauri::command]
+pub async fn get_play_count_rule(
+    conn: State<'_, Arc<Mutex<Connection>>>,
+) -> Result<PlayCountRule, String> {
+    Ok(load_rule(&*conn.lock().await))
+}
+
+#[tauri::command]
+pub async fn set_play_count_rule(
+    rule: PlayCountRule,
+    conn: State<'_, Arc<Mutex<Connection>>>,
+) -> Result<(), String> {
+    if !(0.0..=100.0).contains(&rule.min_percent) || !rule.min_secs.is_finite() || rule.min_secs < 0.0 {
+        return Err(i18n::tr("计数比例需在0-100之间，最短时长不能为负"));
+    }
+
+    let value = serde_json::to_string(&rule).map_err(|e| e.to_string())?;
+    conn.lock()
+        .await
+        .execute(
+            "INSERT OR REPLACE INTO app_settings (key, value) VALUES (?1, ?2)",
+            (RULE_SETTING_KEY, &value),
+        )
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
@@ -1,11 +1,22 @@
+use std::collections::{HashMap, VecDeque};
 use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
 use std::fs::File;
 use std::io::BufReader;
-use tauri::State;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager, State};
 use rusqlite::Connection;
-use tokio::sync::Mutex;
+use tokio::sync::{broadcast, Mutex, Notify};
 use rodio::{Sink, OutputStream, OutputStreamHandle, Decoder, Source};
 use serde::{Serialize, Deserialize};
+use crate::playcount::PlayCountRule;
+use crate::settings::AppSettings;
+use crate::variant;
+use crate::i18n;
+use crate::resume;
+use crate::sleep_timer::{SleepMode, SleepTimer, SleepTimerState};
+use crate::speed::SpeedPreset;
+use rand::seq::SliceRandom;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PlaybackState {
@@ -13,10 +24,57 @@ pub struct PlaybackState {
     pub current_audio_id: Option<i64>,
     pub current_audio_name: Option<String>,
     pub volume: f32,
+    /// 是否静音（静音时 volume 为静音前的音量）
+    pub is_muted: bool,
+    /// 家长设置的最大音量
+    pub max_volume: f32,
     pub speed: f32,
+    /// 当前倍速对应的常用倍速，不是常用倍速时为 None
+    pub speed_preset: Option<SpeedPreset>,
     pub playlist_queue: Vec<i64>,
     pub current_index: usize,
     pub is_auto_play: bool,
+    /// 当前曲目的播放位置（秒，原文件时间）
+    pub position_secs: f64,
+    /// 当前曲目的结束位置（秒，原文件时间，设置了片段结束时为片段结束位置），未知时为 None
+    pub duration_secs: Option<f64>,
+    /// 正在循环的 A-B 段（开始秒数，结束秒数）
+    pub ab_loop: Option<(f64, f64)>,
+    /// 正在对比播放的两个音频ID，当前播放的是 current_audio_id
+    pub compare_pair: Option<(i64, i64)>,
+    /// 当前播放列表的播放模式
+    pub play_mode: Option<PlayMode>,
+    /// 定时停止，未设置时为 None
+    pub sleep_timer: Option<SleepTimerState>,
+}
+
+/// 播放列表的播放模式（与 playlists.play_mode 的取值一致）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PlayMode {
+    Sequential,
+    /// 随机顺序播放一遍
+    Random,
+    /// 单曲循环
+    Single,
+    /// 列表循环
+    Loop,
+}
+
+impl PlayMode {
+    /// 解析数据库中保存的模式，未知值按顺序播放处理
+    pub fn parse(mode: Option<&str>) -> Self {
+        match mode {
+            Some("random") => PlayMode::Random,
+            Some("single") => PlayMode::Single,
+            Some("loop") => PlayMode::Loop,
+            _ => PlayMode::Sequential,
+        }
+    }
+
+    pub fn is_repeating(self) -> bool {
+        matches!(self, PlayMode::Single | PlayMode::Loop)
+    }
 }
 
 pub struct AudioPlayer {
@@ -28,10 +86,375 @@ pub struct AudioPlayer {
     playlist_queue: Vec<i64>,
     current_index: usize,
     volume: f32,
+    // 静音时 Sink 音量为0，volume 保留静音前的音量，取消静音后恢复
+    muted: bool,
+    // 家长设置的最大音量（0.0-1.0），任何途径设置的音量都不超过它
+    max_volume: f32,
+    // 播放中改了设置里的默认音量时，等到停止或下一次开始播放时再使用
+    pending_default_volume: Option<f32>,
     speed: f32,
+    // 手动选择的倍速；没有记住倍速的音频按此倍速播放
+    manual_speed: f32,
+    // 下一次播放的文件本身的倍速（预渲染版本为其渲染倍速，原文件为1.0），仅作用于下一次 play
+    rendered_speed: f32,
+    // 下一次播放的片段范围，仅作用于下一次 play
+    clip: ClipRange,
+    // 与播放队列一一对应的片段范围
+    queue_clips: Vec<ClipRange>,
+    // 已分析音频的响度归一化增益（dB），关闭归一化时为空
+    loudness_gains: HashMap<i64, f64>,
+    // 下一次播放的响度归一化增益（dB），仅作用于下一次 play
+    loudness_db: Option<f64>,
+    // 已分析音频去掉首尾静音后的播放范围（秒），关闭跳过静音时为空
+    silence_trims: HashMap<i64, (f64, f64)>,
+    // 下一次播放去掉首尾静音后的范围，未设置片段时生效，仅作用于下一次 play
+    silence_trim: Option<(f64, f64)>,
     is_auto_play: bool,
+    // 当前曲目开始（或恢复）播放的时刻，暂停时为None
+    play_started_at: Option<Instant>,
+    // 最近一次恢复播放之前已播放的位置（秒）
+    position_offset: f64,
+    // 当前曲目的结束位置（秒，原文件时间），未知时为None
+    current_duration: Option<f64>,
+    // 本次会话实际播放过的曲目（音频ID，队列索引），用于“上一首”回溯
+    play_history: Vec<(i64, usize)>,
+    // 已追加到当前 Sink、排在当前曲目之后的曲目（无缝播放）
+    appended: VecDeque<AppendedTrack>,
+    // 每开始一首曲目（包括无缝切换）加一，用于判断当前曲目是否已播完
+    track_serial: u64,
+    // 当前曲目的来源文件，用于 A-B 循环时重新解码
+    current_origin: Option<TrackOrigin>,
+    // 正在循环的 A-B 段（秒，原文件时间）
+    ab_loop: Option<(f64, f64)>,
+    // 当前曲目在最近一次恢复播放之前实际播放的时长（秒，曲目时间），用于播放计数
+    listened_before: f64,
+    // 当前曲目本次播放是否已计入播放次数
+    play_counted: bool,
+    // 对比播放的两个音频及当前播放的一方，播放其他曲目后失效
+    compare: Option<([CompareTrack; 2], usize)>,
+    // 当前播放列表的播放模式
+    play_mode: Option<PlayMode>,
+    // 曲目播完后是否由队列监视器按播放模式接着播放（定时任务由调度器自己推进）
+    auto_advance: bool,
+    // 队列监视器推进时是否把下一首追加到同一个 Sink（播放列表的无缝播放）
+    gapless: bool,
+    // 停止前最后播放的音频，新一轮随机播放时避免紧接着重复
+    last_audio_id: Option<i64>,
+    // 下一次播放时加载后保持暂停（启动时恢复上次未播完的曲目）
+    start_paused: bool,
+    // 声道平衡和强制单声道，所有音源共享
+    channel_mix: Arc<SharedChannelMix>,
+    // 定时停止（倒计时结束后保留到下一次开始播放列表，让正在推进的播放也能停下）
+    sleep_timer: Option<SleepTimer>,
+    // 已播完、尚未发出 track-ended 事件的曲目
+    ended_tracks: Vec<i64>,
+    // 最近一次记为播完的曲目序号，避免 Sink 播空后重复记录
+    ended_serial: u64,
+    // 预加载的下一首，播放同一文件时直接使用
+    preloaded: Option<PreloadedFile>,
+    // 播放状态（曲目、播放/暂停/停止、音量）变化时通知状态事件任务
+    state_changed: Arc<Notify>,
+    // 上一个任务交接出来的曲目：下一次开始播放时保留当前 Sink，与新曲目交叉淡入淡出
+    handoff: Option<TrackLevel>,
+    // 下一次开始播放的是紧接着的任务（手动播放时不交叉淡入淡出，直接停止交接过来的音频）
+    handoff_accepted: bool,
+    // 交叉淡入淡出中的上一个任务的音频
+    crossfade: Option<Crossfade>,
+    // 正在执行重要任务：收听时长用完时也不停止播放
+    critical_playback: bool,
+}
+
+/// 曲目播放时的响度相关信息，用于任务交接时匹配响度
+#[derive(Debug, Clone, Copy)]
+pub struct TrackLevel {
+    pub audio_id: Option<i64>,
+    /// 播放列表项增益与响度归一化增益之和（dB）
+    pub gain_db: f64,
+    /// 播放器音量（0.0-1.0）
+    pub volume: f32,
+}
+
+/// 交叉淡入淡出中淡出的 Sink
+struct Crossfade {
+    sink: Sink,
+    from: TrackLevel,
+    // 淡出过程中音量向新曲目靠拢的倍数
+    match_factor: f32,
+    // 0.0-1.0
+    progress: f32,
+}
+
+impl Crossfade {
+    /// 新曲目的音量倍数（等功率曲线）
+    fn incoming_factor(&self) -> f32 {
+        (self.progress * std::f32::consts::FRAC_PI_2).sin()
+    }
+
+    /// 淡出一方的音量：先向新曲目的响度靠拢，再按等功率曲线减小
+    fn outgoing_volume(&self) -> f32 {
+        self.from.volume * self.match_factor.powf(self.progress) * (self.progress * std::f32::consts::FRAC_PI_2).cos()
+    }
+}
+
+/// 对比播放中的一个音频
+#[derive(Clone)]
+pub struct CompareTrack {
+    pub audio_id: i64,
+    pub audio_name: String,
+    pub file_path: String,
+    pub rendered_speed: f32,
+}
+
+/// 曲目的来源文件及播放时应用的倍速、片段和增益
+#[derive(Clone)]
+struct TrackOrigin {
+    file_path: String,
+    rendered_speed: f32,
+    clip: ClipRange,
+    loudness_db: Option<f64>,
+}
+
+impl TrackOrigin {
+    /// 播放列表项增益与响度归一化增益合并后的振幅倍数
+    fn gain_factor(&self) -> f32 {
+        let loudness_factor = self.loudness_db.map(|db| 10f32.powf(db as f32 / 20.0)).unwrap_or(1.0);
+        self.clip.gain_factor() * loudness_factor
+    }
+
+    fn gain_db(&self) -> f64 {
+        20.0 * (self.gain_factor().max(f32::MIN_POSITIVE) as f64).log10()
+    }
+}
+
+/// 解码后的曲目
+struct OpenedTrack {
+    source: DecodedSource,
+    start: f64,
+    duration: Option<f64>,
+    origin: TrackOrigin,
+}
+
+/// 追加到 Sink 中等待无缝播放的曲目
+struct AppendedTrack {
+    audio_id: i64,
+    audio_name: String,
+    // 在播放队列中的位置，切换到这一首时成为当前位置
+    queue_index: Option<usize>,
+    start: f64,
+    duration: Option<f64>,
+    origin: TrackOrigin,
+}
+
+/// 播放列表项的片段范围（基于原文件时间的秒数，None 表示从头/到结尾）及该项的音量增益
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ClipRange {
+    pub start: Option<f64>,
+    pub end: Option<f64>,
+    /// 音量增益（dB），只作用于该项，不改变播放器音量
+    pub gain_db: Option<f64>,
+}
+
+impl ClipRange {
+    /// 片段的实际时长（秒），完整时长未知且未设置结束位置时返回 None
+    pub fn effective_secs(&self, full_duration: Option<i64>) -> Option<i64> {
+        let start = self.start.unwrap_or(0.0);
+        let end = self.end.or(full_duration.map(|d| d as f64))?;
+        Some((end - start).max(0.0).ceil() as i64)
+    }
+
+    /// 增益对应的振幅倍数
+    pub fn gain_factor(&self) -> f32 {
+        self.gain_db.map(|db| 10f32.powf(db as f32 / 20.0)).unwrap_or(1.0)
+    }
+}
+
+// 声道设置保存在 app_settings 中
+const CHANNEL_MIX_KEY: &str = "channel_mix";
+
+/// 声道设置：平衡（-1.0 只有左声道 … 0 居中 … 1.0 只有右声道）及强制单声道
+/// （各声道取平均后从两侧同时播放，适合只录到一个声道的录音）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct ChannelMix {
+    pub balance: f32,
+    pub force_mono: bool,
+}
+
+impl ChannelMix {
+    /// 处理一帧（每个声道一个采样）；单声道音源不做处理
+    fn apply(&self, frame: &mut [i16]) {
+        if frame.len() < 2 {
+            return;
+        }
+        if self.force_mono {
+            let mean = frame.iter().map(|sample| *sample as i32).sum::<i32>() / frame.len() as i32;
+            frame.fill(mean as i16);
+        }
+        if self.balance != 0.0 {
+            let balance = self.balance.clamp(-1.0, 1.0);
+            frame[0] = (frame[0] as f32 * (1.0 - balance).min(1.0)) as i16;
+            frame[1] = (frame[1] as f32 * (1.0 + balance).min(1.0)) as i16;
+        }
+    }
+}
+
+/// 播放中的音源共享的声道设置，修改后立即作用于正在播放的曲目
+#[derive(Default)]
+struct SharedChannelMix {
+    balance_bits: AtomicU32,
+    force_mono: AtomicBool,
+}
+
+impl SharedChannelMix {
+    fn load(&self) -> ChannelMix {
+        ChannelMix {
+            balance: f32::from_bits(self.balance_bits.load(Ordering::Relaxed)),
+            force_mono: self.force_mono.load(Ordering::Relaxed),
+        }
+    }
+
+    fn store(&self, mix: ChannelMix) {
+        self.balance_bits.store(mix.balance.to_bits(), Ordering::Relaxed);
+        self.force_mono.store(mix.force_mono, Ordering::Relaxed);
+    }
+}
+
+/// 按声道设置逐帧处理的音源
+struct ChannelMixSource<S> {
+    inner: S,
+    mix: Arc<SharedChannelMix>,
+    frame: Vec<i16>,
+    index: usize,
+}
+
+impl<S: Source<Item = i16>> ChannelMixSource<S> {
+    fn new(inner: S, mix: Arc<SharedChannelMix>) -> Self {
+        Self { inner, mix, frame: Vec::new(), index: 0 }
+    }
+}
+
+impl<S: Source<Item = i16>> Iterator for ChannelMixSource<S> {
+    type Item = i16;
+
+    fn next(&mut self) -> Option<i16> {
+        if self.index >= self.frame.len() {
+            let channels = self.inner.channels().max(1) as usize;
+            self.frame.clear();
+            for _ in 0..channels {
+                self.frame.push(self.inner.next()?);
+            }
+            self.index = 0;
+            self.mix.load().apply(&mut self.frame);
+        }
+        let sample = self.frame[self.index];
+        self.index += 1;
+        Some(sample)
+    }
+}
+
+impl<S: Source<Item = i16>> Source for ChannelMixSource<S> {
+    fn current_frame_len(&self) -> Option<usize> {
+        self.inner.current_frame_len().map(|len| len + self.frame.len() - self.index)
+    }
+
+    fn channels(&self) -> u16 {
+        self.inner.channels()
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.inner.sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.inner.total_duration()
+    }
+}
+
+/// 读取保存的声道设置
+pub fn load_channel_mix(conn: &Connection) -> ChannelMix {
+    conn.query_row(
+        "SELECT value FROM app_settings WHERE key = ?1",
+        [CHANNEL_MIX_KEY],
+        |row| row.get::<_, String>(0),
+    )
+    .ok()
+    .and_then(|value| serde_json::from_str(&value).ok())
+    .unwrap_or_default()
+}
+
+// 预加载下一首时预先解码的时长（秒）
+const PRELOAD_HEAD_SECS: usize = 2;
+
+/// 预先打开并解码了开头的下一首，开始播放时省去打开文件、探测格式、读取时长和开头解码的延迟
+pub struct PreloadedFile {
+    file_path: String,
+    source: PrebufferedSource,
+    duration_secs: Option<i64>,
+}
+
+impl PreloadedFile {
+    /// 打开文件并解码开头（耗时，应在播放器锁外执行）
+    pub fn open(file_path: &str) -> Result<Self, String> {
+        let file = File::open(file_path).map_err(|e| e.to_string())?;
+        let mut decoder = Decoder::new(BufReader::new(file)).map_err(|e| e.to_string())?;
+        let channels = decoder.channels();
+        let sample_rate = decoder.sample_rate();
+        let head_len = sample_rate as usize * channels as usize * PRELOAD_HEAD_SECS;
+        let head: Vec<i16> = decoder.by_ref().take(head_len).collect();
+
+        Ok(Self {
+            file_path: file_path.to_string(),
+            source: PrebufferedSource { head: head.into_iter(), rest: decoder, channels, sample_rate },
+            duration_secs: crate::audio::probe_audio_duration(std::path::Path::new(file_path)),
+        })
+    }
+}
+
+/// 先播放预先解码的开头，再接着解码剩余部分的音源
+struct PrebufferedSource {
+    head: std::vec::IntoIter<i16>,
+    rest: Decoder<BufReader<File>>,
+    channels: u16,
+    sample_rate: u32,
+}
+
+impl Iterator for PrebufferedSource {
+    type Item = i16;
+
+    fn next(&mut self) -> Option<i16> {
+        self.head.next().or_else(|| self.rest.next())
+    }
 }
 
+impl Source for PrebufferedSource {
+    fn current_frame_len(&self) -> Option<usize> {
+        match self.head.len() {
+            0 => self.rest.current_frame_len(),
+            len => Some(len),
+        }
+    }
+
+    fn channels(&self) -> u16 {
+        if self.head.len() > 0 { self.channels } else { self.rest.channels() }
+    }
+
+    fn sample_rate(&self) -> u32 {
+        if self.head.len() > 0 { self.sample_rate } else { self.rest.sample_rate() }
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.rest.total_duration()
+    }
+}
+
+/// 解码后、已应用片段范围和增益的音源
+pub type DecodedSource = Box<dyn Source<Item = i16> + Send>;
+
+// 播放历史最多保留的条目数
+const MAX_PLAY_HISTORY: usize = 200;
+// 播放倍速范围
+const MIN_SPEED: f32 = 0.5;
+const MAX_SPEED: f32 = 3.0;
+
 // 手动实现Send，因为我们确保只在单线程中访问
 unsafe impl Send for AudioPlayer {}
 unsafe impl Sync for AudioPlayer {}
@@ -47,8 +470,45 @@ impl AudioPlayer {
             playlist_queue: Vec::new(),
             current_index: 0,
             volume: 0.5,
+            muted: false,
+            max_volume: 1.0,
+            pending_default_volume: None,
             speed: 1.0,
+            manual_speed: 1.0,
+            rendered_speed: 1.0,
+            clip: ClipRange::default(),
+            queue_clips: Vec::new(),
+            loudness_gains: HashMap::new(),
+            loudness_db: None,
+            silence_trims: HashMap::new(),
+            silence_trim: None,
             is_auto_play: false,
+            play_started_at: None,
+            position_offset: 0.0,
+            current_duration: None,
+            play_history: Vec::new(),
+            appended: VecDeque::new(),
+            track_serial: 0,
+            current_origin: None,
+            ab_loop: None,
+            listened_before: 0.0,
+            play_counted: false,
+            compare: None,
+            play_mode: None,
+            auto_advance: false,
+            gapless: false,
+            last_audio_id: None,
+            start_paused: false,
+            channel_mix: Arc::default(),
+            sleep_timer: None,
+            ended_tracks: Vec::new(),
+            ended_serial: 0,
+            preloaded: None,
+            state_changed: Arc::default(),
+            handoff: None,
+            handoff_accepted: false,
+            crossfade: None,
+            critical_playback: false,
         }
     }
 
@@ -62,45 +522,439 @@ impl AudioPlayer {
     }
 
     pub fn play(&mut self, file_path: &str) -> Result<(), String> {
+        let track = self.open_source(file_path)?;
+        self.start_track(track.source, track.start, track.duration, Some(track.origin))
+    }
+
+    /// 播放网络音频流：不在音频库中，没有音频ID，也不支持 A-B 循环等需要重新打开文件的操作
+    pub fn play_stream(&mut self, source: DecodedSource, name: String, duration: Option<f64>) -> Result<(), String> {
+        self.current_audio_id = None;
+        self.current_audio_name = Some(name);
+        self.clip = ClipRange::default();
+        self.loudness_db = None;
+        self.silence_trim = None;
+        let source = ChannelMixSource::new(source.speed(self.speed), self.channel_mix.clone());
+        self.start_track(Box::new(source), 0.0, duration, None)
+    }
+
+    /// 停止当前播放，用新的 Sink 开始播放音源
+    fn start_track(&mut self, source: DecodedSource, start: f64, duration: Option<f64>, origin: Option<TrackOrigin>) -> Result<(), String> {
         self.init_stream();
 
         let stream_handle = self.stream_handle.as_ref()
-            .ok_or("音频流未初始化")?;
+            .ok_or_else(|| i18n::tr("音频流未初始化"))?;
 
-        // 停止当前播放
-        if let Some(sink) = &self.sink {
-            sink.stop();
+        // 停止当前播放（上一个任务交接过来时保留，与新曲目交叉淡入淡出）
+        if let Some(crossfade) = self.crossfade.take() {
+            crossfade.sink.stop();
+        }
+        let accepted = std::mem::take(&mut self.handoff_accepted);
+        match (self.sink.take(), self.handoff.take().filter(|_| accepted)) {
+            (Some(sink), Some(from)) if !sink.empty() => {
+                self.crossfade = Some(Crossfade { sink, from, match_factor: 1.0, progress: 0.0 });
+            }
+            (Some(sink), _) => sink.stop(),
+            (None, _) => {}
         }
+        self.appended.clear();
 
         // 创建新的Sink
         let sink = Sink::try_new(stream_handle).map_err(|e| e.to_string())?;
 
-        // 打开音频文件
-        let file = File::open(file_path).map_err(|e| e.to_string())?;
-        let source = Decoder::new(BufReader::new(file)).map_err(|e| e.to_string())?;
+        let start_paused = std::mem::take(&mut self.start_paused);
+        if start_paused {
+            sink.pause();
+        }
+        sink.append(source);
+        sink.set_volume(self.sink_volume());
+        if !start_paused {
+            sink.play();
+        }
+
+        self.sink = Some(sink);
+        self.play_started_at = (!start_paused).then(Instant::now);
+        self.position_offset = start;
+        self.current_duration = duration;
+        self.current_origin = origin;
+        self.ab_loop = None;
+        self.listened_before = 0.0;
+        self.play_counted = false;
+        self.track_serial += 1;
+        self.state_changed.notify_one();
 
-        // 应用倍速
-        let source = source.speed(self.speed);
+        Ok(())
+    }
+
+    /// 解码文件并应用下一次播放的片段范围、增益、响度归一化和倍速，返回音源、开始位置和结束位置
+    fn open_source(&mut self, file_path: &str) -> Result<OpenedTrack, String> {
+        // 打开音频文件（已预加载时直接使用预加载的音源和时长）
+        let (source, preloaded_secs): (DecodedSource, _) =
+            match self.preloaded.take().filter(|preloaded| preloaded.file_path == file_path) {
+                Some(preloaded) => (Box::new(preloaded.source), Some(preloaded.duration_secs)),
+                None => {
+                    let file = File::open(file_path).map_err(|e| e.to_string())?;
+                    (Box::new(Decoder::new(BufReader::new(file)).map_err(|e| e.to_string())?), None)
+                }
+            };
 
+        // 截取片段（预渲染版本的时间轴按其倍速缩短），没有设置的开始/结束位置跳过首尾静音
+        let mut clip = std::mem::take(&mut self.clip);
+        if let Some((lead, end)) = self.silence_trim.take() {
+            clip.start = clip.start.or(Some(lead).filter(|lead| *lead > 0.0));
+            clip.end = clip.end.or(Some(end).filter(|end| *end > lead));
+        }
+        let start = clip.start.unwrap_or(0.0).max(0.0);
+        let rendered_speed = self.rendered_speed as f64;
+        let duration = match clip.end {
+            Some(end) if end > start => Some(end),
+            _ => preloaded_secs
+                .unwrap_or_else(|| crate::audio::probe_audio_duration(std::path::Path::new(file_path)))
+                .map(|secs| secs as f64 * rendered_speed),
+        };
+        let source = source.skip_duration(Duration::from_secs_f64(start / rendered_speed));
+        let source: DecodedSource = match clip.end {
+            Some(end) if end > start => {
+                Box::new(source.take_duration(Duration::from_secs_f64((end - start) / rendered_speed)))
+            }
+            _ => Box::new(source),
+        };
+
+        // 应用该项的增益、响度归一化和倍速（预渲染版本只需补足剩余的倍率）
+        let origin = TrackOrigin {
+            file_path: file_path.to_string(),
+            rendered_speed: self.rendered_speed,
+            clip,
+            loudness_db: self.loudness_db.take(),
+        };
+        let source = source
+            .amplify(origin.gain_factor())
+            .speed(self.speed / self.rendered_speed);
+        self.rendered_speed = 1.0;
+
+        let source = ChannelMixSource::new(source, self.channel_mix.clone());
+        Ok(OpenedTrack { source: Box::new(source), start, duration, origin })
+    }
+
+    /// 把曲目追加到当前 Sink 末尾，当前曲目播完后无缝接上；没有正在播放的曲目时直接播放。
+    /// queue_index 为曲目在播放队列中的位置，开始播放这一首时成为当前位置
+    pub fn append_with_info(&mut self, file_path: &str, audio_id: i64, audio_name: String, queue_index: Option<usize>) -> Result<(), String> {
+        self.sync_gapless();
+        if self.sink.as_ref().is_none_or(|sink| sink.empty()) {
+            self.play_with_info(file_path, audio_id, audio_name)?;
+            if let Some(index) = queue_index {
+                self.current_index = index;
+            }
+            return Ok(());
+        }
+
+        self.loudness_db = self.loudness_gains.get(&audio_id).copied();
+        self.silence_trim = self.silence_trims.get(&audio_id).copied();
+        let track = self.open_source(file_path)?;
+        if let Some(sink) = &self.sink {
+            sink.append(track.source);
+        }
+        self.appended.push_back(AppendedTrack {
+            audio_id,
+            audio_name,
+            queue_index,
+            start: track.start,
+            duration: track.duration,
+            origin: track.origin,
+        });
+        Ok(())
+    }
+
+    /// Sink 已切换到追加的曲目时，更新当前曲目信息和播放位置
+    pub fn sync_gapless(&mut self) {
+        let Some(sink) = &self.sink else { return };
+        let remaining = sink.len();
+
+        while !self.appended.is_empty() && remaining <= self.appended.len() {
+            let Some(track) = self.appended.pop_front() else { break };
+
+            // 按上一首的结束位置推算切换时刻，避免把轮询延迟算进播放位置
+            let switched_at = match (self.play_started_at, self.current_duration) {
+                (Some(started_at), Some(duration)) => {
+                    let played = ((duration - self.position_offset) / self.speed as f64).max(0.0);
+                    Some((started_at + Duration::from_secs_f64(played)).min(Instant::now()))
+                }
+                (Some(_), None) => Some(Instant::now()),
+                (None, _) => None,
+            };
+
+            if let Some(current_id) = self.current_audio_id {
+                self.ended_tracks.push(current_id);
+                self.play_history.push((current_id, self.current_index));
+                if self.play_history.len() > MAX_PLAY_HISTORY {
+                    self.play_history.remove(0);
+                }
+            }
+            if let Some(index) = track.queue_index {
+                self.current_index = index;
+            }
+            self.current_audio_id = Some(track.audio_id);
+            self.current_audio_name = Some(track.audio_name);
+            self.play_started_at = switched_at;
+            self.position_offset = track.start;
+            self.current_duration = track.duration;
+            self.current_origin = Some(track.origin);
+            self.listened_before = 0.0;
+            self.play_counted = false;
+            self.track_serial += 1;
+            self.state_changed.notify_one();
+        }
+    }
+
+    /// 反复播放当前曲目的 start_secs 到 end_secs 一段（原文件时间），直到清除
+    pub fn set_ab_loop(&mut self, start_secs: f64, end_secs: f64) -> Result<(), String> {
+        let origin = self.current_origin.clone().ok_or_else(|| i18n::tr("当前没有正在播放的音频"))?;
+        if !(start_secs.is_finite() && end_secs.is_finite()) || start_secs < 0.0 || end_secs <= start_secs {
+            return Err(i18n::tr("结束位置必须大于开始位置"));
+        }
+        if self.current_duration.is_some_and(|duration| start_secs >= duration) {
+            return Err(i18n::tr("开始位置超出音频时长"));
+        }
+        let end_secs = self.current_duration.map_or(end_secs, |duration| end_secs.min(duration));
+
+        let stream_handle = self.stream_handle.as_ref().ok_or_else(|| i18n::tr("音频流未初始化"))?;
+        let file = File::open(&origin.file_path).map_err(|e| e.to_string())?;
+        let rendered_speed = origin.rendered_speed as f64;
+        let source = Decoder::new(BufReader::new(file))
+            .map_err(|e| e.to_string())?
+            .skip_duration(Duration::from_secs_f64(start_secs / rendered_speed))
+            .take_duration(Duration::from_secs_f64((end_secs - start_secs) / rendered_speed))
+            .repeat_infinite()
+            .amplify(origin.gain_factor())
+            .speed(self.speed / origin.rendered_speed);
+        let source = ChannelMixSource::new(source, self.channel_mix.clone());
+
+        // 循环段会一直播放，排在后面的无缝曲目不再有机会播放
+        if let Some(crossfade) = self.crossfade.take() {
+            crossfade.sink.stop();
+        }
+        if let Some(sink) = &self.sink {
+            sink.stop();
+        }
+        self.appended.clear();
+
+        let sink = Sink::try_new(stream_handle).map_err(|e| e.to_string())?;
         sink.append(source);
-        sink.set_volume(self.volume);
+        sink.set_volume(self.sink_volume());
         sink.play();
 
+        self.listened_before = self.listened_secs();
         self.sink = Some(sink);
+        self.play_started_at = Some(Instant::now());
+        self.position_offset = start_secs;
+        self.ab_loop = Some((start_secs, end_secs));
+        self.state_changed.notify_one();
+        Ok(())
+    }
+
+    /// 结束 A-B 循环，从当前位置继续往后播放
+    pub fn clear_ab_loop(&mut self) -> Result<(), String> {
+        if self.ab_loop.is_none() {
+            return Ok(());
+        }
+        let Some(origin) = self.current_origin.clone() else {
+            self.ab_loop = None;
+            return Ok(());
+        };
+
+        let position = self.position_secs();
+        let paused = self.play_started_at.is_none();
+        let (listened, counted) = (self.listened_secs(), self.play_counted);
+        self.clip = ClipRange { start: Some(position), ..origin.clip };
+        self.rendered_speed = origin.rendered_speed;
+        self.loudness_db = origin.loudness_db;
+        self.play(&origin.file_path)?;
+        // 仍是同一次播放，保留已播放时长和计数状态
+        self.listened_before = listened;
+        self.play_counted = counted;
+        if paused {
+            self.pause();
+        }
+        Ok(())
+    }
+
+    /// 开始对比播放：从头播放第一个音频，之后可用 switch_compare 在两者之间切换
+    pub fn start_compare(&mut self, tracks: [CompareTrack; 2]) -> Result<(), String> {
+        let first = tracks[0].clone();
+        self.rendered_speed = first.rendered_speed;
+        self.play_with_info(&first.file_path, first.audio_id, first.audio_name)?;
+        self.compare = Some((tracks, 0));
+        Ok(())
+    }
 
+    /// 仍在对比播放中时返回两个音频和当前一方
+    fn active_compare(&self) -> Option<&([CompareTrack; 2], usize)> {
+        self.compare
+            .as_ref()
+            .filter(|(tracks, active)| self.current_audio_id == Some(tracks[*active].audio_id))
+    }
+
+    /// 切换到对比的另一个音频，从相同的相对位置（按时长比例）继续，保持播放/暂停状态
+    pub fn switch_compare(&mut self) -> Result<(), String> {
+        let (tracks, active) = self.active_compare().cloned().ok_or_else(|| i18n::tr("当前不在对比播放中"))?;
+        let other = tracks[1 - active].clone();
+
+        let position = self.position_secs();
+        let other_duration = crate::audio::probe_audio_duration(std::path::Path::new(&other.file_path))
+            .map(|secs| secs as f64 * other.rendered_speed as f64);
+        let start = match (self.current_duration.filter(|d| *d > 0.0), other_duration) {
+            (Some(duration), Some(other_duration)) => (position / duration).clamp(0.0, 1.0) * other_duration,
+            _ => position,
+        };
+
+        let paused = self.play_started_at.is_none();
+        self.clip = ClipRange { start: Some(start), ..ClipRange::default() };
+        self.rendered_speed = other.rendered_speed;
+        self.play_from_history(&other.file_path, other.audio_id, other.audio_name)?;
+        if paused {
+            self.pause();
+        }
+        self.compare = Some((tracks, 1 - active));
         Ok(())
     }
 
+    /// Sink 中是否还有未播完的音频（暂停时也算）
+    pub fn has_pending_audio(&self) -> bool {
+        self.sink.as_ref().is_some_and(|sink| !sink.empty())
+    }
+
+    /// 当前曲目的序号，每开始一首曲目加一
+    pub fn track_serial(&self) -> u64 {
+        self.track_serial
+    }
+
+    /// 取出自上次调用以来自然播完的曲目（无缝切换或 Sink 播空），停止和切歌不算播完
+    pub fn take_ended_tracks(&mut self) -> Vec<i64> {
+        self.sync_gapless();
+        if self.ended_serial != self.track_serial && !self.has_pending_audio() {
+            if let Some(audio_id) = self.current_audio_id {
+                self.ended_tracks.push(audio_id);
+                self.ended_serial = self.track_serial;
+                self.state_changed.notify_one();
+            }
+        }
+        std::mem::take(&mut self.ended_tracks)
+    }
+
     pub fn play_with_info(&mut self, file_path: &str, audio_id: i64, audio_name: String) -> Result<(), String> {
+        // 切换到其他曲目时，把当前曲目压入历史
+        if let Some(current_id) = self.current_audio_id {
+            if current_id != audio_id {
+                self.play_history.push((current_id, self.current_index));
+                if self.play_history.len() > MAX_PLAY_HISTORY {
+                    self.play_history.remove(0);
+                }
+            }
+        }
+        self.play_from_history(file_path, audio_id, audio_name)
+    }
+
+    /// 加载曲目但保持暂停，之后 resume 即可从片段开始位置继续播放
+    pub fn load_paused(&mut self, file_path: &str, audio_id: i64, audio_name: String) -> Result<(), String> {
+        self.start_paused = true;
+        let result = self.play_with_info(file_path, audio_id, audio_name);
+        self.start_paused = false;
+        result
+    }
+
+    /// 播放从历史中回退得到的曲目（不再记入历史）
+    pub fn play_from_history(&mut self, file_path: &str, audio_id: i64, audio_name: String) -> Result<(), String> {
         self.current_audio_id = Some(audio_id);
         self.current_audio_name = Some(audio_name);
+        self.loudness_db = self.loudness_gains.get(&audio_id).copied();
+        self.silence_trim = self.silence_trims.get(&audio_id).copied();
         self.play(file_path)
     }
 
+    /// 设置各音频的响度归一化增益（dB），从下一首曲目开始生效
+    pub fn set_loudness_gains(&mut self, gains: HashMap<i64, f64>) {
+        self.loudness_gains = gains;
+    }
+
+    /// 设置各音频去掉首尾静音后的播放范围（秒），从下一首曲目开始生效
+    pub fn set_silence_trims(&mut self, trims: HashMap<i64, (f64, f64)>) {
+        self.silence_trims = trims;
+    }
+
     pub fn set_playlist_queue(&mut self, queue: Vec<i64>, is_auto_play: bool) {
         self.playlist_queue = queue;
+        self.queue_clips.clear();
         self.current_index = 0;
         self.is_auto_play = is_auto_play;
+        self.play_mode = None;
+        self.auto_advance = false;
+        self.gapless = false;
+        // 播完当前曲目/播放列表的定时停止只针对之前的播放，倒计时继续有效
+        self.sleep_timer = self.sleep_timer.filter(|timer| timer.mode == SleepMode::Countdown && !timer.is_expired());
+    }
+
+    /// 设置队列的播放模式（需在 set_playlist_queue 之后调用），auto_advance 为 true 时曲目播完由队列监视器接着播放
+    pub fn set_play_mode(&mut self, mode: PlayMode, auto_advance: bool) {
+        self.play_mode = Some(mode);
+        self.auto_advance = auto_advance;
+    }
+
+    /// 设置队列监视器推进时是否无缝播放（需在 set_playlist_queue 之后调用）
+    pub fn set_gapless(&mut self, gapless: bool) {
+        self.gapless = gapless;
+    }
+
+    /// 最近播放的音频（正在播放的，或停止前最后播放的）
+    pub fn last_played_audio(&self) -> Option<i64> {
+        self.current_audio_id.or(self.last_audio_id)
+    }
+
+    /// 重新打乱队列中尚未播放的曲目，当前曲目继续播放（只用于队列监视器推进的手动播放）
+    pub fn reshuffle_queue(&mut self) -> Result<(), String> {
+        self.sync_gapless();
+        if self.playlist_queue.is_empty() || !self.auto_advance {
+            return Err(i18n::tr("当前没有可重新打乱的播放队列"));
+        }
+
+        let start = (self.current_index + 1).min(self.playlist_queue.len());
+        let mut upcoming: Vec<(i64, ClipRange)> = self.playlist_queue[start..]
+            .iter()
+            .enumerate()
+            .map(|(offset, id)| (*id, self.queue_clips.get(start + offset).copied().unwrap_or_default()))
+            .collect();
+        shuffle_tracks(&mut upcoming, |(id, _)| *id, self.current_audio_id);
+
+        self.queue_clips.resize(self.playlist_queue.len(), ClipRange::default());
+        for (offset, (id, clip)) in upcoming.into_iter().enumerate() {
+            self.playlist_queue[start + offset] = id;
+            self.queue_clips[start + offset] = clip;
+        }
+        Ok(())
+    }
+
+    /// 设置与播放队列对应的片段范围（需在 set_playlist_queue 之后调用）
+    pub fn set_queue_clips(&mut self, clips: Vec<ClipRange>) {
+        self.queue_clips = clips;
+    }
+
+    /// 队列当前位置为该音频时，返回其片段范围
+    pub fn queue_clip(&self, audio_id: i64) -> ClipRange {
+        match self.playlist_queue.get(self.current_index) {
+            Some(id) if *id == audio_id => self.queue_clips.get(self.current_index).copied().unwrap_or_default(),
+            _ => ClipRange::default(),
+        }
+    }
+
+    pub fn set_clip(&mut self, clip: ClipRange) {
+        self.clip = clip;
+    }
+
+    /// 是否已预加载该文件
+    pub fn is_preloaded(&self, file_path: &str) -> bool {
+        self.preloaded.as_ref().is_some_and(|preloaded| preloaded.file_path == file_path)
+    }
+
+    pub fn set_preloaded(&mut self, preloaded: PreloadedFile) {
+        self.preloaded = Some(preloaded);
     }
 
     pub fn play_next(&mut self) -> Option<i64> {
@@ -111,12 +965,119 @@ impl AudioPlayer {
         if self.current_index + 1 < self.playlist_queue.len() {
             self.current_index += 1;
             Some(self.playlist_queue[self.current_index])
+        } else if self.play_mode.is_some_and(PlayMode::is_repeating) {
+            // 循环模式下从队列开头继续
+            self.current_index = 0;
+            Some(self.playlist_queue[0])
         } else {
             None
         }
     }
 
+    /// 队列监视器推进时当前曲目播完后将要播放的下一首（不改变队列位置），用于预加载
+    fn peek_next_queue_track(&self) -> Option<i64> {
+        self.peek_next_queue_index().and_then(|index| self.playlist_queue.get(index).copied())
+    }
+
+    /// 队列监视器推进时当前曲目播完后将要播放的队列位置（不改变队列位置）
+    fn peek_next_queue_index(&self) -> Option<usize> {
+        if !self.auto_advance || self.playlist_queue.get(self.current_index).copied() != self.current_audio_id {
+            return None;
+        }
+        match self.play_mode {
+            Some(PlayMode::Single) => Some(self.current_index),
+            _ if self.current_index + 1 < self.playlist_queue.len() => Some(self.current_index + 1),
+            Some(mode) if mode.is_repeating() => Some(0),
+            _ => None,
+        }
+    }
+
+    /// 无缝播放时，当前曲目即将播完、应把下一首追加到 Sink 的队列位置：
+    /// 尚未追加、不在 A-B 循环中，且播完当前曲目时不会因定时停止而停下
+    fn gapless_append_due(&self) -> Option<usize> {
+        if !self.gapless || !self.appended.is_empty() || self.ab_loop.is_some() || !self.has_pending_audio() {
+            return None;
+        }
+        if self.track_remaining_secs().is_none_or(|secs| secs > GAPLESS_APPEND_SECS) {
+            return None;
+        }
+        let index = self.peek_next_queue_index()?;
+        let wraps = self.play_mode == Some(PlayMode::Single) || index <= self.current_index;
+        match self.sleep_timer.is_some_and(|timer| timer.stops_at_track_end(wraps)) {
+            true => None,
+            false => Some(index),
+        }
+    }
+
+    /// 把队列中 index 位置的曲目（按其片段范围）追加到当前 Sink
+    fn append_queue_item(&mut self, index: usize, file_path: &str, audio_name: String, rendered_speed: f32) -> Result<(), String> {
+        let audio_id = self.playlist_queue.get(index).copied().ok_or_else(|| i18n::tr("播放列表项不存在"))?;
+        self.clip = self.queue_clips.get(index).copied().unwrap_or_default();
+        self.rendered_speed = rendered_speed;
+        self.append_with_info(file_path, audio_id, audio_name, Some(index))
+    }
+
+    /// 当前曲目自然播完后按播放模式选出下一首：单曲循环重播当前曲目，其他模式同 play_next
+    fn next_after_finish(&mut self) -> Option<i64> {
+        match self.play_mode {
+            Some(PlayMode::Single) => self.playlist_queue.get(self.current_index).copied(),
+            _ => self.play_next(),
+        }
+    }
+
+    /// 由队列监视器推进时，当前曲目已播完且应接着播放的下一首
+    fn take_finished_queue_track(&mut self) -> Option<i64> {
+        self.sync_gapless();
+        if !self.auto_advance || !self.sink.as_ref().is_some_and(|sink| sink.empty()) {
+            return None;
+        }
+        // 期间单独播放了其他音频时不再接着播放队列
+        if self.current_audio_id.is_none() || self.playlist_queue.get(self.current_index).copied() != self.current_audio_id {
+            self.auto_advance = false;
+            return None;
+        }
+        let wraps = self.play_mode == Some(PlayMode::Single) || self.current_index + 1 >= self.playlist_queue.len();
+        if self.take_sleep_stop(wraps) {
+            self.auto_advance = false;
+            return None;
+        }
+        let next = self.next_after_finish();
+        if next.is_none() {
+            self.auto_advance = false;
+        }
+        next
+    }
+
+    pub fn sleep_timer(&self) -> Option<SleepTimer> {
+        self.sleep_timer
+    }
+
+    pub fn set_sleep_timer(&mut self, timer: Option<SleepTimer>) {
+        self.sleep_timer = timer;
+    }
+
+    /// 曲目播完、即将播放下一首时检查定时停止，需要停止时清除定时并返回 true
+    /// （wraps 表示下一首要从播放列表开头重新播放）
+    pub fn take_sleep_stop(&mut self, wraps: bool) -> bool {
+        let stops = self.sleep_timer.is_some_and(|timer| timer.stops_at_track_end(wraps));
+        if stops {
+            self.sleep_timer = None;
+        }
+        stops
+    }
+
     pub fn play_previous(&mut self) -> Option<i64> {
+        // 优先按实际播放历史回退（随机模式下也能回到真正的上一首）
+        while let Some((audio_id, index)) = self.play_history.pop() {
+            if Some(audio_id) == self.current_audio_id {
+                continue;
+            }
+            if self.playlist_queue.get(index) == Some(&audio_id) {
+                self.current_index = index;
+            }
+            return Some(audio_id);
+        }
+
         if self.playlist_queue.is_empty() {
             return None;
         }
@@ -129,93 +1090,651 @@ impl AudioPlayer {
         }
     }
 
-    pub fn pause(&self) {
-        if let Some(sink) = &self.sink {
-            sink.pause();
-        }
+    pub fn pause(&mut self) {
+        self.end_crossfade();
+        if let Some(sink) = &self.sink {
+            sink.pause();
+        }
+        if let Some(started_at) = self.play_started_at.take() {
+            let played = started_at.elapsed().as_secs_f64() * self.speed as f64;
+            self.position_offset += played;
+            self.listened_before += played;
+        }
+        self.state_changed.notify_one();
+    }
+
+    pub fn resume(&mut self) {
+        if let Some(sink) = &self.sink {
+            sink.play();
+            if self.play_started_at.is_none() {
+                self.play_started_at = Some(Instant::now());
+            }
+        }
+        self.state_changed.notify_one();
+    }
+
+    /// 在暂停与播放之间切换，没有已加载的曲目时不做任何事
+    pub fn toggle_play_pause(&mut self) {
+        if self.is_playing() {
+            self.pause();
+        } else {
+            self.resume();
+        }
+    }
+
+    /// 当前曲目本次实际播放的时长（秒，曲目时间，不含暂停）
+    pub fn listened_secs(&self) -> f64 {
+        let running = self.play_started_at
+            .map(|started_at| started_at.elapsed().as_secs_f64() * self.speed as f64)
+            .unwrap_or(0.0);
+        self.listened_before + running
+    }
+
+    /// 当前曲目的实际播放时长满足计数规则且尚未计数时，标记为已计数并返回音频ID
+    pub fn take_countable_play(&mut self, rule: &PlayCountRule) -> Option<i64> {
+        self.sync_gapless();
+        let audio_id = self.current_audio_id?;
+        if self.play_counted || self.sink.is_none() {
+            return None;
+        }
+
+        let track_secs = self.current_duration.map(|duration| match self.ab_loop {
+            Some((start, end)) => end - start,
+            None => duration - self.current_origin.as_ref().and_then(|origin| origin.clip.start).unwrap_or(0.0),
+        });
+        if !rule.is_satisfied(self.listened_secs(), track_secs) {
+            return None;
+        }
+        self.play_counted = true;
+        Some(audio_id)
+    }
+
+    /// 当前曲目的播放位置（秒）
+    pub fn position_secs(&self) -> f64 {
+        if self.sink.is_none() {
+            return 0.0;
+        }
+        let running = self.play_started_at
+            .map(|started_at| started_at.elapsed().as_secs_f64() * self.speed as f64)
+            .unwrap_or(0.0);
+        let position = self.position_offset + running;
+        if let Some((start, end)) = self.ab_loop {
+            return start + (position - start).rem_euclid(end - start);
+        }
+        match self.current_duration {
+            Some(duration) => position.min(duration),
+            None => position,
+        }
+    }
+
+    pub fn current_audio_id(&self) -> Option<i64> {
+        self.current_audio_id
+    }
+
+    /// 当前曲目的时长（秒，原文件时间），未知时为 None
+    pub fn current_duration(&self) -> Option<f64> {
+        self.current_duration
+    }
+
+    /// 当前曲目按当前倍速还需播放的秒数，时长未知时为 None
+    pub fn track_remaining_secs(&self) -> Option<f64> {
+        self.current_duration
+            .map(|duration| ((duration - self.position_secs()) / self.speed as f64).max(0.0))
+    }
+
+    /// 音频是否在播放队列中等待播放（当前曲目之后）
+    pub fn is_queued(&self, audio_id: i64) -> bool {
+        self.playlist_queue
+            .iter()
+            .skip(self.current_index + 1)
+            .any(|id| *id == audio_id)
+    }
+
+    /// 从播放队列和播放历史中移除指定音频，当前曲目保持不变
+    pub fn remove_from_queue(&mut self, audio_id: i64) {
+        let mut queue = Vec::with_capacity(self.playlist_queue.len());
+        let mut clips = Vec::with_capacity(self.queue_clips.len());
+        let mut current_index = 0;
+        for (index, id) in self.playlist_queue.iter().enumerate() {
+            if index == self.current_index {
+                current_index = queue.len();
+            } else if *id == audio_id {
+                continue;
+            }
+            queue.push(*id);
+            if let Some(clip) = self.queue_clips.get(index) {
+                clips.push(*clip);
+            }
+        }
+        self.playlist_queue = queue;
+        self.queue_clips = clips;
+        self.current_index = current_index;
+        self.play_history.retain(|(id, _)| *id != audio_id);
+    }
+
+    pub fn stop(&mut self) {
+        self.end_crossfade();
+        self.handoff = None;
+        if let Some(sink) = &self.sink {
+            sink.stop();
+        }
+        self.sink = None;
+        self.apply_pending_default_volume();
+        self.appended.clear();
+        self.preloaded = None;
+        self.current_origin = None;
+        self.ab_loop = None;
+        self.listened_before = 0.0;
+        self.play_counted = false;
+        self.play_started_at = None;
+        self.position_offset = 0.0;
+        self.current_duration = None;
+        self.last_audio_id = self.current_audio_id.take().or(self.last_audio_id);
+        self.current_audio_name = None;
+        self.playlist_queue.clear();
+        self.queue_clips.clear();
+        self.current_index = 0;
+        self.is_auto_play = false;
+        self.compare = None;
+        self.play_mode = None;
+        self.auto_advance = false;
+        self.gapless = false;
+        self.sleep_timer = self.sleep_timer.filter(|timer| timer.mode == SleepMode::Countdown);
+        self.state_changed.notify_one();
+    }
+
+    pub fn volume(&self) -> f32 {
+        self.volume
+    }
+
+    /// 设置音量（不超过最大音量）；静音时只记下新音量，取消静音后生效
+    pub fn set_volume(&mut self, volume: f32) {
+        self.volume = volume.clamp(0.0, self.max_volume);
+        if let Some(sink) = &self.sink {
+            sink.set_volume(self.sink_volume());
+        }
+        self.state_changed.notify_one();
+    }
+
+    /// 设置里的默认音量改变：空闲时立即使用；正在播放（或暂停）时不改变当前播放的音量，
+    /// 停止或下一次开始播放时再使用
+    pub fn set_default_volume(&mut self, volume: f32) {
+        if self.has_pending_audio() {
+            self.pending_default_volume = Some(volume);
+        } else {
+            self.pending_default_volume = None;
+            self.set_volume(volume);
+        }
+    }
+
+    /// 使用播放中改过的默认音量（没有时不做任何事）
+    pub fn apply_pending_default_volume(&mut self) {
+        if let Some(volume) = self.pending_default_volume.take() {
+            self.set_volume(volume);
+        }
+    }
+
+    /// 淡出结束后停止并恢复淡出前的音量；停止时用上了改过的默认音量就不再恢复
+    pub fn stop_after_fade(&mut self, volume: f32) {
+        let default_pending = self.pending_default_volume.is_some();
+        self.stop();
+        if !default_pending {
+            self.set_volume(volume);
+        }
+    }
+
+    /// 设置最大音量，当前音量超过时立即降低
+    pub fn set_max_volume(&mut self, max_volume: f32) {
+        self.max_volume = max_volume.clamp(0.0, 1.0);
+        self.set_volume(self.volume);
+    }
+
+    /// Sink 实际使用的音量（交叉淡入淡出中按进度淡入）
+    fn sink_volume(&self) -> f32 {
+        let crossfade_factor = self.crossfade.as_ref().map_or(1.0, Crossfade::incoming_factor);
+        if self.muted { 0.0 } else { self.volume * crossfade_factor }
+    }
+
+    /// 任务到点结束但紧接着有下一个任务时调用：保留正在播放的音频，
+    /// 下一次开始播放时与新曲目交叉淡入淡出（没有正在播放的音频时不做任何事）
+    pub fn begin_handoff(&mut self) {
+        self.sync_gapless();
+        if !self.is_playing() {
+            return;
+        }
+        self.handoff = Some(TrackLevel {
+            audio_id: self.current_audio_id,
+            gain_db: self.current_origin.as_ref().map_or(0.0, TrackOrigin::gain_db),
+            volume: self.volume,
+        });
+    }
+
+    /// 标记正在（或不再）执行重要任务，重要任务的播放不受每日收听时长限制
+    pub fn set_critical_playback(&mut self, critical: bool) {
+        self.critical_playback = critical;
+    }
+
+    pub fn is_critical_playback(&self) -> bool {
+        self.critical_playback
+    }
+
+    /// 是否有上一个任务交接过来、等待交叉淡入淡出的音频
+    pub fn handoff_pending(&self) -> bool {
+        self.handoff.is_some()
+    }
+
+    /// 由紧接着的任务接手交接过来的音频：下一次开始播放时与其交叉淡入淡出
+    pub fn accept_handoff(&mut self) {
+        self.handoff_accepted = self.handoff.is_some();
+    }
+
+    /// 交叉淡入淡出中淡出和淡入两方的响度信息
+    pub fn crossfade_levels(&self) -> Option<(TrackLevel, TrackLevel)> {
+        let crossfade = self.crossfade.as_ref()?;
+        let to = TrackLevel {
+            audio_id: self.current_audio_id,
+            gain_db: self.current_origin.as_ref().map_or(0.0, TrackOrigin::gain_db),
+            volume: self.volume,
+        };
+        Some((crossfade.from, to))
+    }
+
+    /// 设置交叉淡入淡出的响度匹配倍数
+    pub fn set_crossfade_match(&mut self, match_factor: f32) {
+        if let Some(crossfade) = &mut self.crossfade {
+            crossfade.match_factor = match_factor;
+        }
+    }
+
+    /// 更新交叉淡入淡出的进度（0.0-1.0），到 1.0 时停止淡出的一方；没有进行中的交叉淡入淡出时返回 false。
+    /// 响度匹配可能调高淡出一方的音量，同样不超过最大音量
+    pub fn set_crossfade_progress(&mut self, progress: f32) -> bool {
+        let (muted, max_volume) = (self.muted, self.max_volume);
+        let Some(crossfade) = &mut self.crossfade else { return false };
+        crossfade.progress = progress.clamp(0.0, 1.0);
+        crossfade.sink.set_volume(if muted { 0.0 } else { crossfade.outgoing_volume().clamp(0.0, max_volume) });
+        if crossfade.progress >= 1.0 {
+            self.end_crossfade();
+        } else if let Some(sink) = &self.sink {
+            sink.set_volume(self.sink_volume());
+        }
+        true
+    }
+
+    /// 立即结束交叉淡入淡出：停止淡出的一方，新曲目恢复正常音量
+    fn end_crossfade(&mut self) {
+        if let Some(crossfade) = self.crossfade.take() {
+            crossfade.sink.stop();
+            if let Some(sink) = &self.sink {
+                sink.set_volume(self.sink_volume());
+            }
+        }
+    }
+
+    pub fn channel_mix(&self) -> ChannelMix {
+        self.channel_mix.load()
+    }
+
+    /// 修改声道设置，正在播放的曲目立即生效
+    pub fn set_channel_mix(&mut self, mix: ChannelMix) {
+        self.channel_mix.store(mix);
+    }
+
+    pub fn is_muted(&self) -> bool {
+        self.muted
     }
 
-    #[allow(dead_code)]
-    pub fn resume(&self) {
+    /// 静音或取消静音，音量保持不变
+    pub fn set_muted(&mut self, muted: bool) {
+        self.muted = muted;
+        if muted {
+            self.end_crossfade();
+        }
         if let Some(sink) = &self.sink {
-            sink.play();
+            sink.set_volume(self.sink_volume());
         }
+        self.state_changed.notify_one();
     }
 
-    pub fn stop(&mut self) {
-        if let Some(sink) = &self.sink {
-            sink.stop();
-        }
-        self.sink = None;
-        self.current_audio_id = None;
-        self.current_audio_name = None;
-        self.playlist_queue.clear();
-        self.current_index = 0;
-        self.is_auto_play = false;
+    pub fn speed(&self) -> f32 {
+        self.speed
     }
 
-    pub fn set_volume(&mut self, volume: f32) {
-        self.volume = volume.max(0.0).min(1.0);
-        if let Some(sink) = &self.sink {
-            sink.set_volume(self.volume);
-        }
+    pub fn set_rendered_speed(&mut self, rendered_speed: f32) {
+        self.rendered_speed = rendered_speed;
     }
 
     pub fn set_speed(&mut self, speed: f32) {
-        self.speed = speed.max(0.5).min(3.0);
+        self.speed = speed.clamp(MIN_SPEED, MAX_SPEED);
+        self.manual_speed = self.speed;
         // 需要重新播放才能应用新的倍速
         // 调用者需要重新调用 play
     }
 
-    #[allow(dead_code)]
+    /// 按音频记住的倍速播放，没有记住的恢复为手动选择的倍速（同样需要重新调用 play）
+    pub fn apply_preferred_speed(&mut self, preferred_speed: Option<f32>) {
+        self.speed = preferred_speed.unwrap_or(self.manual_speed).clamp(MIN_SPEED, MAX_SPEED);
+    }
+
     pub fn is_playing(&self) -> bool {
         self.sink.as_ref().map_or(false, |s| !s.is_paused() && !s.empty())
     }
 
+    /// 用于比较播放状态变化的快照
+    fn state_snapshot(&self) -> StateSnapshot {
+        StateSnapshot {
+            status: match (self.has_pending_audio(), self.is_playing()) {
+                (false, _) => PlaybackStatus::Stopped,
+                (true, false) => PlaybackStatus::Paused,
+                (true, true) => PlaybackStatus::Playing,
+            },
+            track_serial: self.track_serial,
+            volume: self.volume,
+            muted: self.muted,
+        }
+    }
+
     pub fn get_state(&self) -> PlaybackState {
         PlaybackState {
             is_playing: self.is_playing(),
             current_audio_id: self.current_audio_id,
             current_audio_name: self.current_audio_name.clone(),
             volume: self.volume,
+            is_muted: self.muted,
+            max_volume: self.max_volume,
             speed: self.speed,
+            speed_preset: SpeedPreset::matching(self.speed),
             playlist_queue: self.playlist_queue.clone(),
             current_index: self.current_index,
             is_auto_play: self.is_auto_play,
+            position_secs: self.position_secs(),
+            duration_secs: self.current_duration,
+            ab_loop: self.ab_loop,
+            compare_pair: self
+                .active_compare()
+                .map(|(tracks, _)| (tracks[0].audio_id, tracks[1].audio_id)),
+            play_mode: self.play_mode,
+            sleep_timer: self.sleep_timer.filter(|timer| !timer.is_expired()).map(|timer| timer.state()),
+        }
+    }
+}
+
+/// 随机打乱曲目顺序（每轮播放打乱一次）。avoid_first 为上一轮最后播放的音频时，
+/// 把它从第一首换走，避免跨轮紧接着重复播放
+pub fn shuffle_tracks<T>(items: &mut [T], audio_id: impl Fn(&T) -> i64, avoid_first: Option<i64>) {
+    let mut rng = rand::thread_rng();
+    items.shuffle(&mut rng);
+
+    let Some(avoid) = avoid_first else { return };
+    if items.first().map(&audio_id) != Some(avoid) {
+        return;
+    }
+    let others: Vec<usize> = (1..items.len()).filter(|&i| audio_id(&items[i]) != avoid).collect();
+    if let Some(&index) = others.choose(&mut rng) {
+        items.swap(0, index);
+    }
+}
+
+/// 托盘菜单中播放/暂停切换的菜单项ID
+pub const TRAY_ITEM_ID: &str = "toggle_play_pause";
+/// 托盘菜单中静音切换的菜单项ID
+pub const TRAY_MUTE_ITEM_ID: &str = "toggle_mute";
+
+/// 托盘菜单：播放/暂停切换
+pub async fn toggle_from_tray(app: &AppHandle) {
+    let player = app.state::<Arc<Mutex<AudioPlayer>>>();
+    let mut player = player.lock().await;
+    player.toggle_play_pause();
+}
+
+/// 托盘菜单：静音/取消静音切换（接电话时一键静音）
+pub async fn toggle_mute_from_tray(app: &AppHandle) {
+    let player = app.state::<Arc<Mutex<AudioPlayer>>>();
+    let mut player = player.lock().await;
+    let muted = !player.is_muted();
+    player.set_muted(muted);
+}
+
+/// 默认音量（设置中的百分比）对应的播放器音量
+pub fn default_volume(settings: &AppSettings) -> f32 {
+    settings.default_volume as f32 / 100.0
+}
+
+/// 订阅设置变更：只有默认音量变化时才应用到播放器，空闲时立即生效，正在播放时（包括定时任务）
+/// 从停止或下一次开始播放起生效；initial_volume 为启动时的默认音量
+pub fn start_settings_listener(
+    player: Arc<Mutex<AudioPlayer>>,
+    initial_volume: Option<f32>,
+    mut changes: broadcast::Receiver<AppSettings>,
+) {
+    tauri::async_runtime::spawn(async move {
+        let mut last_volume = initial_volume;
+        loop {
+            let settings = match changes.recv().await {
+                Ok(settings) => settings,
+                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => break,
+            };
+            let volume = default_volume(&settings);
+            if last_volume == Some(volume) {
+                continue;
+            }
+            last_volume = Some(volume);
+            player.lock().await.set_default_volume(volume);
+        }
+    });
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum PlaybackStatus {
+    Playing,
+    Paused,
+    Stopped,
+}
+
+/// 播放状态事件比较的内容
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct StateSnapshot {
+    status: PlaybackStatus,
+    track_serial: u64,
+    volume: f32,
+    muted: bool,
+}
+
+/// 启动播放状态事件：状态变化时向前端发出 playback-started / playback-paused / playback-stopped /
+/// track-changed / volume-changed 事件（内容为 PlaybackState），并更新托盘提示；
+/// 一次操作中的多次变化合并为最终状态，定时任务发起的播放同样会发出
+pub fn start_state_events(app: AppHandle, player: Arc<Mutex<AudioPlayer>>) {
+    tauri::async_runtime::spawn(async move {
+        let (changed, mut last) = {
+            let player = player.lock().await;
+            (player.state_changed.clone(), player.state_snapshot())
+        };
+
+        loop {
+            changed.notified().await;
+
+            let (state, snapshot) = {
+                let player = player.lock().await;
+                (player.get_state(), player.state_snapshot())
+            };
+            if snapshot == last {
+                continue;
+            }
+
+            if snapshot.track_serial != last.track_serial && state.current_audio_id.is_some() {
+                let _ = app.emit_all("track-changed", state.clone());
+            }
+            if snapshot.status != last.status {
+                let event = match snapshot.status {
+                    PlaybackStatus::Playing => "playback-started",
+                    PlaybackStatus::Paused => "playback-paused",
+                    PlaybackStatus::Stopped => "playback-stopped",
+                };
+                let _ = app.emit_all(event, state.clone());
+            }
+            if (snapshot.volume, snapshot.muted) != (last.volume, last.muted) {
+                let _ = app.emit_all("volume-changed", state.clone());
+            }
+
+            let tooltip = match (&state.current_audio_name, snapshot.status) {
+                (Some(name), PlaybackStatus::Playing) => format!("▶ {}", name),
+                (Some(name), PlaybackStatus::Paused) => format!("⏸ {}", name),
+                _ => crate::tray::idle_tooltip(&app).await,
+            };
+            let _ = app.tray_handle().set_tooltip(&tooltip);
+
+            last = snapshot;
+        }
+    });
+}
+
+// 队列监视器的检查间隔（毫秒）
+const QUEUE_CHECK_MILLIS: u64 = 500;
+// 无缝播放时当前曲目还剩多少秒时追加下一首（下一首已在曲目开始时预加载，追加很快）
+const GAPLESS_APPEND_SECS: f64 = 3.0;
+// 曲目自然播完时发给前端的事件
+const TRACK_ENDED_EVENT: &str = "track-ended";
+
+/// track-ended 事件的内容
+#[derive(Debug, Clone, Serialize)]
+struct TrackEnded {
+    audio_id: i64,
+}
+
+/// 启动队列监视器：曲目播完时发出 track-ended 事件（手动播放和定时任务都会发出），
+/// 手动播放播放列表时再按播放模式接着播放下一首（无缝播放的播放列表在当前曲目播完前把下一首追加到同一个 Sink）
+pub fn start_queue_monitor(app: AppHandle, db: Arc<Mutex<Connection>>, player: Arc<Mutex<AudioPlayer>>) {
+    tauri::async_runtime::spawn(async move {
+        let mut ticker = tokio::time::interval(Duration::from_millis(QUEUE_CHECK_MILLIS));
+        // 已为其预加载下一首的曲目序号
+        let mut preloaded_serial = 0;
+
+        loop {
+            ticker.tick().await;
+
+            let next_file = {
+                let mut player = player.lock().await;
+                for audio_id in player.take_ended_tracks() {
+                    let _ = app.emit_all(TRACK_ENDED_EVENT, TrackEnded { audio_id });
+                }
+                if let Some(audio_id) = player.take_finished_queue_track() {
+                    let loaded = load_audio(&*db.lock().await, audio_id, player.speed());
+                    let result = loaded.and_then(|(file_path, audio_name, rendered_speed)| {
+                        let clip = player.queue_clip(audio_id);
+                        player.set_clip(clip);
+                        player.set_rendered_speed(rendered_speed);
+                        player.play_with_info(&file_path, audio_id, audio_name)
+                    });
+                    if let Err(e) = result {
+                        eprintln!("[Queue] 播放下一首失败: {}", e);
+                        player.stop();
+                    }
+                }
+                if let Some(index) = player.gapless_append_due() {
+                    let audio_id = player.playlist_queue[index];
+                    let loaded = load_audio(&*db.lock().await, audio_id, player.speed());
+                    let result = loaded.and_then(|(file_path, audio_name, rendered_speed)| {
+                        player.append_queue_item(index, &file_path, audio_name, rendered_speed)
+                    });
+                    // 追加失败时当前曲目播完后按普通方式播放下一首
+                    if let Err(e) = result {
+                        eprintln!("[Queue] 追加下一首失败: {}", e);
+                    }
+                }
+
+                // 每首曲目开始后预加载一次下一首
+                if player.track_serial() == preloaded_serial {
+                    continue;
+                }
+                preloaded_serial = player.track_serial();
+                let Some(next_id) = player.peek_next_queue_track() else { continue };
+                match load_audio(&*db.lock().await, next_id, player.speed()) {
+                    Ok((file_path, _, _)) => file_path,
+                    Err(_) => continue,
+                }
+            };
+            preload_file(&player, next_file).await;
+        }
+    });
+}
+
+/// 在播放器锁外预加载文件，下一次播放该文件时立即开始（已预加载时跳过）
+pub async fn preload_file(player: &Arc<Mutex<AudioPlayer>>, file_path: String) {
+    if player.lock().await.is_preloaded(&file_path) {
+        return;
+    }
+    let path = file_path.clone();
+    match tauri::async_runtime::spawn_blocking(move || PreloadedFile::open(&path)).await {
+        Ok(Ok(preloaded)) => player.lock().await.set_preloaded(preloaded),
+        Ok(Err(e)) => eprintln!("[Preload] 预加载 {} 失败: {}", file_path, e),
+        Err(e) => eprintln!("[Preload] 预加载 {} 失败: {}", file_path, e),
+    }
+}
+
+/// 获取音频的播放路径和名称，优先使用与当前倍速匹配的预渲染版本
+pub(crate) fn load_audio(conn: &Connection, audio_id: i64, speed: f32) -> Result<(String, String, f32), String> {
+    let (file_path, audio_name): (String, String) = conn
+        .query_row(
+            "SELECT file_path, original_name FROM audio_files WHERE id = ?1",
+            [audio_id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .map_err(|e| e.to_string())?;
+
+    if (speed - 1.0).abs() > f32::EPSILON {
+        if let Some(variant_path) = variant::find_variant_path(conn, audio_id, speed) {
+            return Ok((variant_path, audio_name, speed));
         }
     }
+
+    Ok((file_path, audio_name, 1.0))
+}
+
+/// 音频记住的倍速，没有记住时为 None
+fn load_preferred_speed(conn: &Connection, audio_id: i64) -> Option<f32> {
+    conn.query_row("SELECT preferred_speed FROM audio_files WHERE id = ?1", [audio_id], |row| row.get(0))
+        .ok()
+        .flatten()
 }
 
+/// 播放音频：speed 指定时按该倍速播放，否则使用音频记住的倍速
 #[tauri::command]
 pub async fn play_audio(
     id: i64,
+    start_at: Option<f64>,
+    resume: Option<bool>,
+    speed: Option<f32>,
     player: State<'_, Arc<Mutex<AudioPlayer>>>,
     conn: State<'_, Arc<Mutex<Connection>>>,
 ) -> Result<(), String> {
-    // 从数据库获取文件路径和名称
-    let (file_path, audio_name): (String, String) = {
+    let mut player = player.lock().await;
+
+    // 从数据库获取文件路径和名称；resume 为 true 且未指定开始位置时从上次播放到的位置继续
+    let (file_path, audio_name, rendered_speed, start_at) = {
         let conn = conn.lock().await;
-        conn.query_row(
-            "SELECT file_path, original_name FROM audio_files WHERE id = ?1",
-            [id],
-            |row| Ok((row.get(0)?, row.get(1)?)),
-        )
-        .map_err(|e| e.to_string())?
+        match speed {
+            Some(speed) => player.set_speed(speed),
+            None => player.apply_preferred_speed(load_preferred_speed(&conn, id)),
+        }
+        let (file_path, audio_name, rendered_speed) = load_audio(&conn, id, player.speed())?;
+        let start_at = match (start_at, resume.unwrap_or(false)) {
+            (None, true) => resume::load_position(&conn, id),
+            (start_at, _) => start_at,
+        };
+        (file_path, audio_name, rendered_speed, start_at)
     };
 
-    // 播放音频
-    let mut player = player.lock().await;
+    // 播放音频（start_at 为开始位置的秒数，用于从标记处跳转播放）
+    player.apply_pending_default_volume();
+    player.set_rendered_speed(rendered_speed);
+    if let Some(start) = start_at.filter(|start| start.is_finite() && *start > 0.0) {
+        player.set_clip(ClipRange { start: Some(start), ..ClipRange::default() });
+    }
     player.play_with_info(&file_path, id, audio_name.clone())?;
 
-    // 更新播放计数和记录播放历史
+    // 记录到播放历史（播放次数由计数监视器按实际播放时长累加）
     let conn = conn.lock().await;
-    conn.execute(
-        "UPDATE audio_files SET play_count = play_count + 1, last_played = datetime('now') WHERE id = ?1",
-        [id],
-    )
-    .map_err(|e| e.to_string())?;
-
-    // 记录到播放历史
     conn.execute(
         "INSERT INTO playback_history (audio_id, audio_name) VALUES (?1, ?2)",
         (id, &audio_name),
@@ -229,11 +1748,101 @@ pub async fn play_audio(
 pub async fn pause_audio(
     player: State<'_, Arc<Mutex<AudioPlayer>>>,
 ) -> Result<(), String> {
-    let player = player.lock().await;
+    let mut player = player.lock().await;
     player.pause();
     Ok(())
 }
 
+/// 从暂停处继续播放当前曲目
+#[tauri::command]
+pub async fn resume_audio(
+    player: State<'_, Arc<Mutex<AudioPlayer>>>,
+) -> Result<(), String> {
+    let mut player = player.lock().await;
+    player.resume();
+    Ok(())
+}
+
+/// 播放/暂停切换，返回切换后的播放状态
+#[tauri::command]
+pub async fn toggle_play_pause(
+    player: State<'_, Arc<Mutex<AudioPlayer>>>,
+) -> Result<PlaybackState, String> {
+    let mut player = player.lock().await;
+    player.toggle_play_pause();
+    Ok(player.get_state())
+}
+
+/// 循环播放当前曲目的一段（秒），用于反复听同一句
+#[tauri::command]
+pub async fn set_ab_loop(
+    start_secs: f64,
+    end_secs: f64,
+    player: State<'_, Arc<Mutex<AudioPlayer>>>,
+) -> Result<PlaybackState, String> {
+    let mut player = player.lock().await;
+    player.set_ab_loop(start_secs, end_secs)?;
+    Ok(player.get_state())
+}
+
+#[tauri::command]
+pub async fn clear_ab_loop(
+    player: State<'_, Arc<Mutex<AudioPlayer>>>,
+) -> Result<PlaybackState, String> {
+    let mut player = player.lock().await;
+    player.clear_ab_loop()?;
+    Ok(player.get_state())
+}
+
+/// 对比播放两个音频（如示范音频和自己的录音），从头播放第一个
+#[tauri::command]
+pub async fn play_ab_compare(
+    id_a: i64,
+    id_b: i64,
+    player: State<'_, Arc<Mutex<AudioPlayer>>>,
+    conn: State<'_, Arc<Mutex<Connection>>>,
+) -> Result<PlaybackState, String> {
+    if id_a == id_b {
+        return Err(i18n::tr("请选择两个不同的音频"));
+    }
+    let mut player = player.lock().await;
+    let tracks = {
+        let conn = conn.lock().await;
+        let load = |audio_id: i64| {
+            load_audio(&conn, audio_id, player.speed()).map(|(file_path, audio_name, rendered_speed)| CompareTrack {
+                audio_id,
+                audio_name,
+                file_path,
+                rendered_speed,
+            })
+        };
+        [load(id_a)?, load(id_b)?]
+    };
+
+    player.start_compare(tracks)?;
+    Ok(player.get_state())
+}
+
+/// 对比播放时切换到另一个音频的相同相对位置
+#[tauri::command]
+pub async fn switch_ab_compare(
+    player: State<'_, Arc<Mutex<AudioPlayer>>>,
+) -> Result<PlaybackState, String> {
+    let mut player = player.lock().await;
+    player.switch_compare()?;
+    Ok(player.get_state())
+}
+
+/// 重新打乱当前播放队列中尚未播放的曲目，不中断当前曲目
+#[tauri::command]
+pub async fn reshuffle_queue(
+    player: State<'_, Arc<Mutex<AudioPlayer>>>,
+) -> Result<PlaybackState, String> {
+    let mut player = player.lock().await;
+    player.reshuffle_queue()?;
+    Ok(player.get_state())
+}
+
 #[tauri::command]
 pub async fn stop_audio(
     player: State<'_, Arc<Mutex<AudioPlayer>>>,
@@ -253,6 +1862,57 @@ pub async fn set_volume(
     Ok(())
 }
 
+/// 静音，保留当前音量
+#[tauri::command]
+pub async fn mute_audio(
+    player: State<'_, Arc<Mutex<AudioPlayer>>>,
+) -> Result<PlaybackState, String> {
+    let mut player = player.lock().await;
+    player.set_muted(true);
+    Ok(player.get_state())
+}
+
+/// 取消静音，恢复静音前的音量
+#[tauri::command]
+pub async fn unmute_audio(
+    player: State<'_, Arc<Mutex<AudioPlayer>>>,
+) -> Result<PlaybackState, String> {
+    let mut player = player.lock().await;
+    player.set_muted(false);
+    Ok(player.get_state())
+}
+
+#[tauri::command]
+pub async fn get_channel_mix(
+    player: State<'_, Arc<Mutex<AudioPlayer>>>,
+) -> Result<ChannelMix, String> {
+    Ok(player.lock().await.channel_mix())
+}
+
+/// 保存声道平衡和强制单声道设置，正在播放的曲目立即生效
+#[tauri::command]
+pub async fn set_channel_mix(
+    mix: ChannelMix,
+    player: State<'_, Arc<Mutex<AudioPlayer>>>,
+    conn: State<'_, Arc<Mutex<Connection>>>,
+) -> Result<(), String> {
+    if !(-1.0..=1.0).contains(&mix.balance) {
+        return Err(i18n::tr("声道平衡需在-1到1之间"));
+    }
+
+    let mut player = player.lock().await;
+    let value = serde_json::to_string(&mix).map_err(|e| e.to_string())?;
+    conn.lock()
+        .await
+        .execute(
+            "INSERT OR REPLACE INTO app_settings (key, value) VALUES (?1, ?2)",
+            (CHANNEL_MIX_KEY, &value),
+        )
+        .map_err(|e| e.to_string())?;
+    player.set_channel_mix(mix);
+    Ok(())
+}
+
 #[tauri::command]
 pub async fn set_speed(
     speed: f32,
@@ -260,31 +1920,72 @@ pub async fn set_speed(
     conn: State<'_, Arc<Mutex<Connection>>>,
 ) -> Result<(), String> {
     let mut player = player.lock().await;
+    apply_speed(&mut player, &conn, speed).await
+}
+
+/// 设置手动倍速，正在播放时重新播放当前音频以应用
+pub(crate) async fn apply_speed(player: &mut AudioPlayer, conn: &Arc<Mutex<Connection>>, speed: f32) -> Result<(), String> {
     player.set_speed(speed);
 
     // 如果正在播放，需要重新播放当前音频以应用新倍速
     if let Some(audio_id) = player.current_audio_id {
-        let (file_path, audio_name): (String, String) = {
+        let (file_path, audio_name, rendered_speed) = {
             let conn = conn.lock().await;
-            conn.query_row(
-                "SELECT file_path, original_name FROM audio_files WHERE id = ?1",
-                [audio_id],
-                |row| Ok((row.get(0)?, row.get(1)?)),
-            )
-            .map_err(|e| e.to_string())?
+            load_audio(&conn, audio_id, player.speed())?
         };
 
+        let clip = player.queue_clip(audio_id);
+        player.set_clip(clip);
+        player.set_rendered_speed(rendered_speed);
         player.play_with_info(&file_path, audio_id, audio_name)?;
     }
 
     Ok(())
 }
 
+/// 记住音频的倍速，之后播放该音频时自动使用；speed 为空时清除
+#[tauri::command]
+pub async fn set_audio_preferred_speed(
+    id: i64,
+    speed: Option<f32>,
+    conn: State<'_, Arc<Mutex<Connection>>>,
+) -> Result<(), String> {
+    if speed.is_some_and(|speed| !(MIN_SPEED..=MAX_SPEED).contains(&speed)) {
+        return Err(i18n::tr("倍速必须在0.5到3.0之间"));
+    }
+
+    let conn = conn.lock().await;
+    conn.execute("UPDATE audio_files SET preferred_speed = ?1 WHERE id = ?2", (speed, id))
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// 标记音频为已学会或取消标记，开启了“排除已学会”的播放列表播放时会跳过已学会的音频
+#[tauri::command]
+pub async fn set_audio_learned(
+    ids: Vec<i64>,
+    learned: bool,
+    conn: State<'_, Arc<Mutex<Connection>>>,
+) -> Result<(), String> {
+    let mut conn = conn.lock().await;
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    for id in ids {
+        tx.execute(
+            "UPDATE audio_files SET learned_at = CASE WHEN ?1 THEN COALESCE(learned_at, datetime('now')) END WHERE id = ?2",
+            (learned, id),
+        )
+        .map_err(|e| e.to_string())?;
+    }
+    tx.commit().map_err(|e| e.to_string())?;
+    Ok(())
+}
+
 #[tauri::command]
 pub async fn get_playback_state(
     player: State<'_, Arc<Mutex<AudioPlayer>>>,
 ) -> Result<PlaybackState, String> {
-    let player = player.lock().await;
+    let mut player = player.lock().await;
+    player.sync_gapless();
     Ok(player.get_state())
 }
 
@@ -296,25 +1997,15 @@ pub async fn play_next(
     let mut player = player.lock().await;
 
     if let Some(next_audio_id) = player.play_next() {
-        let (file_path, audio_name): (String, String) = {
+        let (file_path, audio_name, rendered_speed) = {
             let conn = conn.lock().await;
-            conn.query_row(
-                "SELECT file_path, original_name FROM audio_files WHERE id = ?1",
-                [next_audio_id],
-                |row| Ok((row.get(0)?, row.get(1)?)),
-            )
-            .map_err(|e| e.to_string())?
+            load_audio(&conn, next_audio_id, player.speed())?
         };
 
+        let clip = player.queue_clip(next_audio_id);
+        player.set_clip(clip);
+        player.set_rendered_speed(rendered_speed);
         player.play_with_info(&file_path, next_audio_id, audio_name)?;
-
-        // 更新播放计数
-        let conn = conn.lock().await;
-        conn.execute(
-            "UPDATE audio_files SET play_count = play_count + 1, last_played = datetime('now') WHERE id = ?1",
-            [next_audio_id],
-        )
-        .map_err(|e| e.to_string())?;
     }
 
     Ok(())
@@ -328,25 +2019,15 @@ pub async fn play_previous(
     let mut player = player.lock().await;
 
     if let Some(prev_audio_id) = player.play_previous() {
-        let (file_path, audio_name): (String, String) = {
+        let (file_path, audio_name, rendered_speed) = {
             let conn = conn.lock().await;
-            conn.query_row(
-                "SELECT file_path, original_name FROM audio_files WHERE id = ?1",
-                [prev_audio_id],
-                |row| Ok((row.get(0)?, row.get(1)?)),
-            )
-            .map_err(|e| e.to_string())?
+            load_audio(&conn, prev_audio_id, player.speed())?
         };
 
-        player.play_with_info(&file_path, prev_audio_id, audio_name)?;
-
-        // 更新播放计数
-        let conn = conn.lock().await;
-        conn.execute(
-            "UPDATE audio_files SET play_count = play_count + 1, last_played = datetime('now') WHERE id = ?1",
-            [prev_audio_id],
-        )
-        .map_err(|e| e.to_string())?;
+        let clip = player.queue_clip(prev_audio_id);
+        player.set_clip(clip);
+        player.set_rendered_speed(rendered_speed);
+        player.play_from_history(&file_path, prev_audio_id, audio_name)?;
     }
 
     Ok(())
@@ -359,54 +2040,185 @@ pub async fn play_playlist(
     player: State<'_, Arc<Mutex<AudioPlayer>>>,
     conn: State<'_, Arc<Mutex<Connection>>>,
 ) -> Result<(), String> {
-    // 获取播放列表中的所有音频 ID
-    let audio_ids: Vec<i64> = {
+    // 获取播放列表的播放模式和所有音频 ID 及片段范围、增益
+    let (play_mode, gapless, mut items): (PlayMode, bool, Vec<(i64, ClipRange)>) = {
         let conn = conn.lock().await;
+        let (play_mode, gapless): (Option<String>, bool) = conn
+            .query_row("SELECT play_mode, gapless FROM playlists WHERE id = ?1", [playlist_id], |row| {
+                Ok((row.get(0)?, row.get(1)?))
+            })
+            .map_err(|_| i18n::tr("播放列表不存在"))?;
         let mut stmt = conn
-            .prepare(
-                "SELECT audio_id FROM playlist_items
-                 WHERE playlist_id = ?1
-                 ORDER BY sort_order"
-            )
+            .prepare(&format!(
+                "SELECT pi.audio_id, pi.start_offset, pi.end_offset, pi.gain_db
+                 FROM playlist_items pi
+                 JOIN audio_files af ON pi.audio_id = af.id
+                 WHERE pi.playlist_id = ?1 AND {}
+                 ORDER BY pi.sort_order",
+                crate::playlist::PLAYABLE_ITEM_CONDITION
+            ))
             .map_err(|e| e.to_string())?;
 
-        let ids: Vec<i64> = stmt
-            .query_map([playlist_id], |row| row.get(0))
+        let items = stmt
+            .query_map([playlist_id], |row| {
+                Ok((row.get(0)?, ClipRange { start: row.get(1)?, end: row.get(2)?, gain_db: row.get(3)? }))
+            })
             .map_err(|e| e.to_string())?
             .collect::<Result<Vec<_>, _>>()
             .map_err(|e| e.to_string())?;
 
-        ids
+        (PlayMode::parse(play_mode.as_deref()), gapless, items)
     };
 
-    if audio_ids.is_empty() {
-        return Err("播放列表为空".to_string());
+    if items.is_empty() {
+        return Err(i18n::tr("播放列表为空"));
     }
-
     let mut player = player.lock().await;
+    if play_mode == PlayMode::Random {
+        shuffle_tracks(&mut items, |(id, _)| *id, player.last_played_audio());
+    }
+
+    let (audio_ids, clips): (Vec<i64>, Vec<ClipRange>) = items.into_iter().unzip();
+
     player.set_playlist_queue(audio_ids.clone(), is_auto_play);
+    player.set_queue_clips(clips);
+    player.set_play_mode(play_mode, is_auto_play);
+    player.set_gapless(gapless);
+    player.apply_pending_default_volume();
 
     // 播放第一首
     let first_audio_id = audio_ids[0];
-    let (file_path, audio_name): (String, String) = {
+    let (file_path, audio_name, rendered_speed) = {
         let conn = conn.lock().await;
-        conn.query_row(
-            "SELECT file_path, original_name FROM audio_files WHERE id = ?1",
-            [first_audio_id],
-            |row| Ok((row.get(0)?, row.get(1)?)),
-        )
-        .map_err(|e| e.to_string())?
+        load_audio(&conn, first_audio_id, player.speed())?
     };
 
+    let clip = player.queue_clip(first_audio_id);
+    player.set_clip(clip);
+    player.set_rendered_speed(rendered_speed);
     player.play_with_info(&file_path, first_audio_id, audio_name)?;
 
-    // 更新播放计数
-    let conn = conn.lock().await;
-    conn.execute(
-        "UPDATE audio_files SET play_count = play_count + 1, last_played = datetime('now') WHERE id = ?1",
-        [first_audio_id],
-    )
-    .map_err(|e| e.to_string())?;
-
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn appended(audio_id: i64, queue_index: usize) -> AppendedTrack {
+        let clip = ClipRange { start: Some(5.0), ..ClipRange::default() };
+        AppendedTrack {
+            audio_id,
+            audio_name: format!("曲目{}", audio_id),
+            queue_index: Some(queue_index),
+            start: 5.0,
+            duration: Some(60.0),
+            origin: TrackOrigin { file_path: String::new(), rendered_speed: 1.0, clip, loudness_db: None },
+        }
+    }
+
+    /// 正在无缝播放队列第一首的播放器；Sink 中只有 pending 个音源（用不连接输出设备的 Sink 模拟播放进度）
+    fn gapless_player(pending: usize) -> (AudioPlayer, rodio::queue::SourcesQueueOutput<f32>) {
+        let mut player = AudioPlayer::new();
+        let (sink, output) = Sink::new_idle();
+        for _ in 0..pending {
+            sink.append(rodio::source::Zero::<i16>::new(1, 44_100));
+        }
+        player.sink = Some(sink);
+        player.set_playlist_queue(vec![10, 20, 30], false);
+        player.set_queue_clips(vec![ClipRange::default(), ClipRange { start: Some(5.0), ..ClipRange::default() }, ClipRange::default()]);
+        player.set_play_mode(PlayMode::Sequential, true);
+        player.set_gapless(true);
+        player.current_audio_id = Some(10);
+        (player, output)
+    }
+
+    #[test]
+    fn crossfade_match_stays_under_max_volume() {
+        let mut player = AudioPlayer::new();
+        player.set_max_volume(0.7);
+        let (sink, _output) = Sink::new_idle();
+        // 响度匹配 +12 dB：不限制时淡出一方在 30% 处约为 0.81
+        player.crossfade = Some(Crossfade {
+            sink,
+            from: TrackLevel { audio_id: None, gain_db: 0.0, volume: 0.6 },
+            match_factor: 10f32.powf(12.0 / 20.0),
+            progress: 0.0,
+        });
+        assert!(player.set_crossfade_progress(0.3));
+        let volume = player.crossfade.as_ref().map(|crossfade| crossfade.sink.volume());
+        assert_eq!(volume, Some(0.7));
+    }
+
+    #[test]
+    fn gapless_switch_advances_queue_position() {
+        // Sink 中只剩追加的第二首，说明第一首已播完
+        let (mut player, _output) = gapless_player(1);
+        player.appended.push_back(appended(20, 1));
+        player.sync_gapless();
+
+        assert_eq!(player.current_audio_id(), Some(20));
+        assert_eq!(player.get_state().current_index, 1);
+        assert_eq!(player.queue_clip(20).start, Some(5.0));
+        assert_eq!(player.play_history, [(10, 0)]);
+        assert_eq!(player.peek_next_queue_track(), Some(30));
+    }
+
+    #[test]
+    fn gapless_switch_waits_for_current_track() {
+        let (mut player, _output) = gapless_player(2);
+        player.appended.push_back(appended(20, 1));
+        player.sync_gapless();
+
+        assert_eq!(player.current_audio_id(), Some(10));
+        assert_eq!(player.get_state().current_index, 0);
+    }
+
+    #[test]
+    fn default_volume_change_waits_for_current_playback() {
+        let (mut player, _output) = gapless_player(1);
+        player.set_volume(0.8);
+        player.set_default_volume(0.3);
+        assert_eq!(player.volume(), 0.8);
+
+        // 停止后使用新的默认音量
+        player.stop();
+        assert_eq!(player.volume(), 0.3);
+        player.set_default_volume(0.6);
+        assert_eq!(player.volume(), 0.6);
+    }
+
+    #[test]
+    fn fade_out_keeps_changed_default_volume() {
+        // 淡出到 0.1 后停止：没有改过默认音量时恢复淡出前的 0.8
+        let (mut player, _output) = gapless_player(1);
+        player.set_volume(0.1);
+        player.stop_after_fade(0.8);
+        assert_eq!(player.volume(), 0.8);
+
+        // 淡出中改了默认音量，停止后使用新的默认音量
+        let (mut player, _output) = gapless_player(1);
+        player.set_default_volume(0.3);
+        player.set_volume(0.1);
+        player.stop_after_fade(0.8);
+        assert_eq!(player.volume(), 0.3);
+    }
+
+    #[test]
+    fn gapless_append_near_track_end() {
+        let (mut player, _output) = gapless_player(1);
+        player.current_duration = Some(60.0);
+        assert_eq!(player.gapless_append_due(), None);
+
+        player.position_offset = 58.0;
+        assert_eq!(player.gapless_append_due(), Some(1));
+
+        // 已追加过，或最后一首且不循环时不再追加
+        player.appended.push_back(appended(20, 1));
+        assert_eq!(player.gapless_append_due(), None);
+        player.appended.clear();
+        player.current_index = 2;
+        player.current_audio_id = Some(30);
+        assert_eq!(player.gapless_append_due(), None);
+    }
+}
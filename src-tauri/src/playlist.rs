@@ -3,12 +3,23 @@ use std::sync::Arc;
 use tokio::sync::Mutex;
 use rusqlite::Connection;
 use tauri::State;
+use crate::task::DeletedEntry;
+use crate::weekday;
+use crate::i18n;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Playlist {
     pub id: i64,
     pub name: String,
     pub play_mode: String,
+    /// 无缝播放：手动播放和定时任务播放时连续曲目之间没有间隙
+    pub gapless: bool,
+    /// 播放时跳过已学会的音频（手动播放和定时任务）
+    pub exclude_learned: bool,
+    /// 每周完成的目标遍数，没有目标时为 None
+    pub weekly_target: Option<i64>,
+    /// 本周已完成的遍数
+    pub week_completions: i64,
     pub created_date: String,
     pub updated_date: String,
 }
@@ -20,7 +31,47 @@ pub struct PlaylistItem {
     pub audio_id: i64,
     pub sort_order: i64,
     pub audio_name: String,
-    pub duration: i64,
+    pub duration: Option<i64>,
+    pub start_offset: Option<f64>,
+    pub end_offset: Option<f64>,
+    /// 音量增益（dB）
+    pub gain_db: Option<f64>,
+    /// 备注（学习要点），随播放顺序一起展示
+    pub note: Option<String>,
+}
+
+/// 播放列表总时长（秒），按各项的片段范围计算
+pub(crate) const PLAYLIST_DURATION_SQL: &str =
+    "SELECT CAST(COALESCE(SUM(MAX(COALESCE(pi.end_offset, af.duration, 0) - COALESCE(pi.start_offset, 0), 0)), 0) AS INTEGER)
+     FROM playlist_items pi
+     JOIN audio_files af ON pi.audio_id = af.id
+     WHERE pi.playlist_id = ?1";
+
+/// 播放时要跳过的项：播放列表开启了“排除已学会”且音频已标记为学会（用于 playlist_items pi JOIN audio_files af 的查询）
+pub(crate) const PLAYABLE_ITEM_CONDITION: &str =
+    "(af.learned_at IS NULL OR NOT EXISTS(SELECT 1 FROM playlists p WHERE p.id = pi.playlist_id AND p.exclude_learned = 1))";
+
+// 每周目标遍数的上限
+const MAX_WEEKLY_TARGET: i64 = 50;
+
+/// 本周（周一起，本地时间）完整播放的遍数：每一项可播放的音频本周都至少播放了 n 次即为完成 n 遍
+/// （手动播放和定时任务都计入），播放列表为空时为 0
+pub(crate) fn week_completions(conn: &Connection, playlist_id: i64) -> rusqlite::Result<i64> {
+    let week_start = weekday::week_start(chrono::Local::now().date_naive());
+    conn.query_row(
+        &format!(
+            "SELECT COALESCE(MIN(plays), 0) FROM (
+                SELECT (SELECT COUNT(*) FROM playback_history ph
+                        WHERE ph.audio_id = pi.audio_id AND DATE(ph.play_time, 'localtime') >= ?2) AS plays
+                FROM playlist_items pi
+                JOIN audio_files af ON pi.audio_id = af.id
+                WHERE pi.playlist_id = ?1 AND {}
+            )",
+            PLAYABLE_ITEM_CONDITION
+        ),
+        (playlist_id, week_start.format("%Y-%m-%d").to_string()),
+        |row| row.get(0),
+    )
 }
 
 #[tauri::command]
@@ -29,7 +80,7 @@ pub async fn get_playlists(
 ) -> Result<Vec<Playlist>, String> {
     let conn = conn.lock().await;
     let mut stmt = conn
-        .prepare("SELECT id, name, play_mode, created_date, updated_date FROM playlists ORDER BY created_date DESC")
+        .prepare("SELECT id, name, play_mode, gapless, exclude_learned, weekly_target, created_date, updated_date FROM playlists WHERE deleted_at IS NULL ORDER BY created_date DESC")
         .map_err(|e| e.to_string())?;
 
     let playlists = stmt
@@ -38,8 +89,12 @@ pub async fn get_playlists(
                 id: row.get(0)?,
                 name: row.get(1)?,
                 play_mode: row.get(2)?,
-                created_date: row.get(3)?,
-                updated_date: row.get(4)?,
+                gapless: row.get(3)?,
+                exclude_learned: row.get(4)?,
+                weekly_target: row.get(5)?,
+                week_completions: week_completions(&conn, row.get(0)?)?,
+                created_date: row.get(6)?,
+                updated_date: row.get(7)?,
             })
         })
         .map_err(|e| e.to_string())?
@@ -65,14 +120,214 @@ pub async fn create_playlist(
     Ok(id)
 }
 
+/// 受播放列表删除影响的播放任务（停止、降低音量任务不播放播放列表，不受影响）
+#[derive(Debug, Serialize)]
+pub struct AffectedTask {
+    pub id: i64,
+    pub name: String,
+    pub is_enabled: bool,
+    /// 仅通过星期映射使用该播放列表
+    pub via_weekday: bool,
+}
+
+/// 删除播放列表前的影响报告
+#[derive(Debug, Serialize)]
+pub struct PlaylistDeleteImpact {
+    pub playlist_id: i64,
+    pub playlist_name: String,
+    /// 使用该播放列表的任务（包括已禁用的任务）
+    pub tasks: Vec<AffectedTask>,
+    /// 引用该播放列表的任务执行记录数
+    pub execution_count: i64,
+    /// 引用该播放列表的播放记录数
+    pub playback_count: i64,
+}
+
+fn load_delete_impact(conn: &Connection, playlist_id: i64) -> Result<PlaylistDeleteImpact, String> {
+    let playlist_name: String = conn
+        .query_row(
+            "SELECT name FROM playlists WHERE id = ?1 AND deleted_at IS NULL",
+            [playlist_id],
+            |row| row.get(0),
+        )
+        .map_err(|_| i18n::tr("播放列表不存在"))?;
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, name, is_enabled, playlist_id, weekday_playlists FROM scheduled_tasks
+             WHERE deleted_at IS NULL AND action = 'play'
+             ORDER BY hour, minute",
+        )
+        .map_err(|e| e.to_string())?;
+    let tasks = stmt
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, bool>(2)?,
+                row.get::<_, i64>(3)?,
+                row.get::<_, Option<String>>(4)?,
+            ))
+        })
+        .map_err(|e| e.to_string())?
+        .collect::<std::result::Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .filter_map(|(id, name, is_enabled, default_playlist, weekday_playlists)| {
+            let in_mapping = weekday_playlists
+                .as_deref()
+                .and_then(weekday::parse_weekday_playlists)
+                .is_some_and(|mapping| mapping.values().any(|p| *p == playlist_id));
+            (default_playlist == playlist_id || in_mapping).then_some(AffectedTask {
+                id,
+                name,
+                is_enabled,
+                via_weekday: default_playlist != playlist_id,
+            })
+        })
+        .collect();
+
+    let count = |sql: &str| -> Result<i64, String> {
+        conn.query_row(sql, [playlist_id], |row| row.get(0))
+            .map_err(|e| e.to_string())
+    };
+
+    Ok(PlaylistDeleteImpact {
+        playlist_id,
+        playlist_name,
+        tasks,
+        execution_count: count("SELECT COUNT(*) FROM execution_history WHERE playlist_id = ?1")?,
+        playback_count: count("SELECT COUNT(*) FROM playback_history WHERE playlist_id = ?1")?,
+    })
+}
+
+/// 删除播放列表前查询受影响的任务和历史记录
+#[tauri::command]
+pub async fn get_playlist_delete_impact(
+    id: i64,
+    conn: State<'_, Arc<Mutex<Connection>>>,
+) -> Result<PlaylistDeleteImpact, String> {
+    let conn = conn.lock().await;
+    load_delete_impact(&conn, id)
+}
+
+/// 删除播放列表（移入“最近删除”）。有任务使用该播放列表时需要 confirm 确认，
+/// 指定 retarget_playlist_id 时先把这些任务（包括星期映射）改为使用另一个播放列表
 #[tauri::command]
 pub async fn delete_playlist(
     id: i64,
+    confirm: Option<bool>,
+    retarget_playlist_id: Option<i64>,
     conn: State<'_, Arc<Mutex<Connection>>>,
 ) -> Result<(), String> {
+    let mut conn = conn.lock().await;
+
+    let impact = load_delete_impact(&conn, id)?;
+    if !impact.tasks.is_empty() && !confirm.unwrap_or(false) {
+        let names: Vec<&str> = impact.tasks.iter().map(|task| task.name.as_str()).collect();
+        return Err(i18n::trf(
+            "播放列表“{}”正被 {} 个任务使用（{}），请确认后再删除",
+            &[&impact.playlist_name, &names.len(), &names.join("、")],
+        ));
+    }
+
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+
+    if let Some(target) = retarget_playlist_id {
+        if target == id {
+            return Err(i18n::tr("不能改为使用正在删除的播放列表"));
+        }
+        tx.query_row(
+            "SELECT 1 FROM playlists WHERE id = ?1 AND deleted_at IS NULL",
+            [target],
+            |_| Ok(()),
+        )
+        .map_err(|_| i18n::tr("目标播放列表不存在"))?;
+
+        tx.execute(
+            "UPDATE scheduled_tasks SET playlist_id = ?1 WHERE playlist_id = ?2 AND deleted_at IS NULL",
+            [target, id],
+        )
+        .map_err(|e| e.to_string())?;
+
+        // 星期映射中的该播放列表同样替换
+        for task in &impact.tasks {
+            let mapping: Option<String> = tx
+                .query_row(
+                    "SELECT weekday_playlists FROM scheduled_tasks WHERE id = ?1",
+                    [task.id],
+                    |row| row.get(0),
+                )
+                .map_err(|e| e.to_string())?;
+            let Some(mut mapping) = mapping.as_deref().and_then(weekday::parse_weekday_playlists) else {
+                continue;
+            };
+            if !mapping.values().any(|p| *p == id) {
+                continue;
+            }
+            for playlist_id in mapping.values_mut() {
+                if *playlist_id == id {
+                    *playlist_id = target;
+                }
+            }
+            let mapping = serde_json::to_string(&mapping).map_err(|e| e.to_string())?;
+            tx.execute(
+                "UPDATE scheduled_tasks SET weekday_playlists = ?1 WHERE id = ?2",
+                (&mapping, task.id),
+            )
+            .map_err(|e| e.to_string())?;
+        }
+    }
+
+    tx.execute(
+        "UPDATE playlists SET deleted_at = datetime('now') WHERE id = ?1",
+        [id],
+    )
+    .map_err(|e| e.to_string())?;
+
+    tx.commit().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_deleted_playlists(
+    conn: State<'_, Arc<Mutex<Connection>>>,
+) -> Result<Vec<DeletedEntry>, String> {
     let conn = conn.lock().await;
-    conn.execute("DELETE FROM playlists WHERE id = ?1", [id])
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, name, deleted_at FROM playlists
+             WHERE deleted_at IS NOT NULL
+             ORDER BY deleted_at DESC"
+        )
         .map_err(|e| e.to_string())?;
+
+    let playlists = stmt
+        .query_map([], |row| {
+            Ok(DeletedEntry {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                deleted_at: row.get(2)?,
+            })
+        })
+        .map_err(|e| e.to_string())?
+        .collect::<std::result::Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    Ok(playlists)
+}
+
+/// 从“最近删除”中恢复播放列表（使用它的任务随之恢复显示和执行）
+#[tauri::command]
+pub async fn restore_playlist(
+    id: i64,
+    conn: State<'_, Arc<Mutex<Connection>>>,
+) -> Result<(), String> {
+    let conn = conn.lock().await;
+    conn.execute(
+        "UPDATE playlists SET deleted_at = NULL WHERE id = ?1",
+        [id],
+    )
+    .map_err(|e| e.to_string())?;
     Ok(())
 }
 
@@ -82,6 +337,9 @@ pub async fn set_playlist_mode(
     mode: String,
     conn: State<'_, Arc<Mutex<Connection>>>,
 ) -> Result<(), String> {
+    if !["sequential", "random", "single", "loop"].contains(&mode.as_str()) {
+        return Err(i18n::tr("不支持的播放模式"));
+    }
     let conn = conn.lock().await;
     conn.execute(
         "UPDATE playlists SET play_mode = ?1, updated_date = datetime('now') WHERE id = ?2",
@@ -91,6 +349,56 @@ pub async fn set_playlist_mode(
     Ok(())
 }
 
+#[tauri::command]
+pub async fn set_playlist_gapless(
+    playlist_id: i64,
+    gapless: bool,
+    conn: State<'_, Arc<Mutex<Connection>>>,
+) -> Result<(), String> {
+    let conn = conn.lock().await;
+    conn.execute(
+        "UPDATE playlists SET gapless = ?1, updated_date = datetime('now') WHERE id = ?2",
+        (gapless, playlist_id),
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// 设置播放列表播放时是否跳过已学会的音频
+#[tauri::command]
+pub async fn set_playlist_exclude_learned(
+    playlist_id: i64,
+    exclude_learned: bool,
+    conn: State<'_, Arc<Mutex<Connection>>>,
+) -> Result<(), String> {
+    let conn = conn.lock().await;
+    conn.execute(
+        "UPDATE playlists SET exclude_learned = ?1, updated_date = datetime('now') WHERE id = ?2",
+        (exclude_learned, playlist_id),
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// 设置播放列表每周完成的目标遍数，None 为取消目标
+#[tauri::command]
+pub async fn set_playlist_weekly_target(
+    playlist_id: i64,
+    target: Option<i64>,
+    conn: State<'_, Arc<Mutex<Connection>>>,
+) -> Result<(), String> {
+    if target.is_some_and(|target| !(1..=MAX_WEEKLY_TARGET).contains(&target)) {
+        return Err(i18n::trf("每周目标需在1-{}遍之间", &[&MAX_WEEKLY_TARGET]));
+    }
+    let conn = conn.lock().await;
+    conn.execute(
+        "UPDATE playlists SET weekly_target = ?1, updated_date = datetime('now') WHERE id = ?2",
+        (target, playlist_id),
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
 #[tauri::command]
 pub async fn get_playlist_items(
     playlist_id: i64,
@@ -99,7 +407,8 @@ pub async fn get_playlist_items(
     let conn = conn.lock().await;
     let mut stmt = conn
         .prepare(
-            "SELECT pi.id, pi.playlist_id, pi.audio_id, pi.sort_order, af.original_name, af.duration
+            "SELECT pi.id, pi.playlist_id, pi.audio_id, pi.sort_order, af.original_name, af.duration,
+                    pi.start_offset, pi.end_offset, pi.gain_db, pi.note
              FROM playlist_items pi
              JOIN audio_files af ON pi.audio_id = af.id
              WHERE pi.playlist_id = ?1
@@ -116,6 +425,10 @@ pub async fn get_playlist_items(
                 sort_order: row.get(3)?,
                 audio_name: row.get(4)?,
                 duration: row.get(5)?,
+                start_offset: row.get(6)?,
+                end_offset: row.get(7)?,
+                gain_db: row.get(8)?,
+                note: row.get(9)?,
             })
         })
         .map_err(|e| e.to_string())?
@@ -132,22 +445,126 @@ pub async fn add_to_playlist(
     conn: State<'_, Arc<Mutex<Connection>>>,
 ) -> Result<(), String> {
     let conn = conn.lock().await;
+    append_playlist_item(&conn, playlist_id, audio_id).map_err(|e| e.to_string())
+}
 
+/// 把音频追加到播放列表末尾
+pub(crate) fn append_playlist_item(conn: &Connection, playlist_id: i64, audio_id: i64) -> rusqlite::Result<()> {
     // 获取当前最大排序值
-    let max_order: i64 = conn
+    let max_order: i64 = conn.query_row(
+        "SELECT COALESCE(MAX(sort_order), -1) FROM playlist_items WHERE playlist_id = ?1",
+        [playlist_id],
+        |row| row.get(0),
+    )?;
+
+    conn.execute(
+        "INSERT INTO playlist_items (playlist_id, audio_id, sort_order) VALUES (?1, ?2, ?3)",
+        (playlist_id, audio_id, max_order + 1),
+    )?;
+
+    Ok(())
+}
+
+/// 设置播放列表项的片段范围（秒），只播放音频中的一段
+#[tauri::command]
+pub async fn set_playlist_item_clip(
+    id: i64,
+    start_offset: Option<f64>,
+    end_offset: Option<f64>,
+    conn: State<'_, Arc<Mutex<Connection>>>,
+) -> Result<(), String> {
+    let start_offset = start_offset.filter(|start| *start > 0.0);
+    if let Some(start) = start_offset {
+        if !start.is_finite() {
+            return Err(i18n::tr("开始位置无效"));
+        }
+    }
+    if let Some(end) = end_offset {
+        if !end.is_finite() || end <= start_offset.unwrap_or(0.0) {
+            return Err(i18n::tr("结束位置必须大于开始位置"));
+        }
+    }
+
+    let conn = conn.lock().await;
+    let duration: Option<i64> = conn
         .query_row(
-            "SELECT COALESCE(MAX(sort_order), -1) FROM playlist_items WHERE playlist_id = ?1",
-            [playlist_id],
+            "SELECT af.duration FROM playlist_items pi
+             JOIN audio_files af ON pi.audio_id = af.id
+             WHERE pi.id = ?1",
+            [id],
             |row| row.get(0),
         )
-        .map_err(|e| e.to_string())?;
+        .map_err(|_| i18n::tr("播放列表项不存在"))?;
+
+    if let Some(duration) = duration.filter(|d| *d > 0) {
+        if start_offset.unwrap_or(0.0) >= duration as f64 {
+            return Err(i18n::tr("开始位置超出音频时长"));
+        }
+    }
 
     conn.execute(
-        "INSERT INTO playlist_items (playlist_id, audio_id, sort_order) VALUES (?1, ?2, ?3)",
-        (playlist_id, audio_id, max_order + 1),
+        "UPDATE playlist_items SET start_offset = ?1, end_offset = ?2 WHERE id = ?3",
+        (start_offset, end_offset, id),
     )
     .map_err(|e| e.to_string())?;
+    Ok(())
+}
 
+// 播放列表项允许的增益范围（dB）
+const MAX_ITEM_GAIN_DB: f64 = 20.0;
+
+/// 设置播放列表项的音量增益（dB），只在播放该项时生效；为空或 0 表示不调整
+#[tauri::command]
+pub async fn set_playlist_item_gain(
+    id: i64,
+    gain_db: Option<f64>,
+    conn: State<'_, Arc<Mutex<Connection>>>,
+) -> Result<(), String> {
+    let gain_db = gain_db.filter(|gain| *gain != 0.0);
+    if let Some(gain) = gain_db {
+        if !gain.is_finite() || gain.abs() > MAX_ITEM_GAIN_DB {
+            return Err(i18n::trf("增益需在 -{0} 到 +{0} dB 之间", &[&MAX_ITEM_GAIN_DB]));
+        }
+    }
+
+    let conn = conn.lock().await;
+    let updated = conn
+        .execute(
+            "UPDATE playlist_items SET gain_db = ?1 WHERE id = ?2",
+            (gain_db, id),
+        )
+        .map_err(|e| e.to_string())?;
+    if updated == 0 {
+        return Err(i18n::tr("播放列表项不存在"));
+    }
+    Ok(())
+}
+
+// 播放列表项备注的最大长度（字符）
+const MAX_ITEM_NOTE_CHARS: usize = 500;
+
+/// 设置播放列表项的备注，为空表示清除
+#[tauri::command]
+pub async fn set_playlist_item_note(
+    id: i64,
+    note: Option<String>,
+    conn: State<'_, Arc<Mutex<Connection>>>,
+) -> Result<(), String> {
+    let note = note.map(|note| note.trim().to_string()).filter(|note| !note.is_empty());
+    if note.as_ref().is_some_and(|note| note.chars().count() > MAX_ITEM_NOTE_CHARS) {
+        return Err(i18n::trf("备注不能超过{}个字符", &[&MAX_ITEM_NOTE_CHARS]));
+    }
+
+    let conn = conn.lock().await;
+    let updated = conn
+        .execute(
+            "UPDATE playlist_items SET note = ?1 WHERE id = ?2",
+            (&note, id),
+        )
+        .map_err(|e| e.to_string())?;
+    if updated == 0 {
+        return Err(i18n::tr("播放列表项不存在"));
+    }
     Ok(())
 }
 
@@ -170,7 +587,7 @@ pub async fn check_playlist_tasks(
     let conn = conn.lock().await;
     let mut stmt = conn
         .prepare(
-            "SELECT name FROM scheduled_tasks WHERE playlist_id = ?1 AND is_enabled = 1"
+            "SELECT name FROM scheduled_tasks WHERE playlist_id = ?1 AND is_enabled = 1 AND deleted_at IS NULL AND action = 'play'"
         )
         .map_err(|e| e.to_string())?;
 
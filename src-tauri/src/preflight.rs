@@ -0,0 +1,264 @@
+use serde::Serialize;
+use std::collections::HashSet;
+use std::path::Path;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use tokio::time::{interval, Duration};
+use rusqlite::Connection;
+use chrono::{Local, NaiveDateTime, NaiveTime};
+use cpal::traits::HostTrait;
+use tauri::{AppHandle, Manager, State};
+use crate::holiday;
+use crate::i18n;
+use crate::pause;
+use crate::playlist;
+use crate::weekday;
+
+// 在任务触发前多少分钟进行预检
+const PREFLIGHT_LEAD_MINUTES: i64 = 5;
+// 预检监视器的检查间隔（秒）
+const PREFLIGHT_CHECK_SECS: u64 = 30;
+// 问题报告中最多列出的缺失文件数
+const MAX_LISTED_MISSING: usize = 5;
+
+/// 任务预检结果，problems 为空表示一切正常
+#[derive(Debug, Clone, Serialize)]
+pub struct PreflightReport {
+    pub task_id: i64,
+    pub task_name: String,
+    /// 预计触发时间（YYYY-MM-DD HH:MM）
+    pub trigger_time: String,
+    pub playlist_id: i64,
+    pub problems: Vec<String>,
+}
+
+struct UpcomingTask {
+    id: i64,
+    name: String,
+    trigger: NaiveDateTime,
+    playlist_id: i64,
+    critical: bool,
+}
+
+/// 默认音频输出设备是否存在
+fn output_device_available() -> bool {
+    cpal::default_host().default_output_device().is_some()
+}
+
+/// 检查播放列表能否正常播放（存在、非空、文件齐全）以及输出设备是否可用
+fn check_playlist(conn: &Connection, playlist_id: i64) -> Vec<String> {
+    let mut problems = Vec::new();
+
+    let playlist_name: Option<String> = conn
+        .query_row(
+            "SELECT name FROM playlists WHERE id = ?1 AND deleted_at IS NULL",
+            [playlist_id],
+            |row| row.get(0),
+        )
+        .ok();
+
+    match playlist_name {
+        None => problems.push(i18n::tr("播放列表不存在或已删除")),
+        Some(playlist_name) => {
+            let files: Vec<(String, String)> = conn
+                .prepare(&format!(
+                    "SELECT af.original_name, af.file_path
+                     FROM playlist_items pi
+                     JOIN audio_files af ON pi.audio_id = af.id
+                     WHERE pi.playlist_id = ?1 AND {}
+                     ORDER BY pi.sort_order",
+                    playlist::PLAYABLE_ITEM_CONDITION
+                ))
+                .and_then(|mut stmt| {
+                    stmt.query_map([playlist_id], |row| Ok((row.get(0)?, row.get(1)?)))?
+                        .collect()
+                })
+                .unwrap_or_default();
+
+            if files.is_empty() {
+                problems.push(i18n::trf("播放列表“{}”为空", &[&playlist_name]));
+            } else {
+                let missing: Vec<&str> = files
+                    .iter()
+                    .filter(|(_, file_path)| !Path::new(file_path).exists())
+                    .map(|(name, _)| name.as_str())
+                    .collect();
+                if !missing.is_empty() {
+                    let mut listed = missing.iter().take(MAX_LISTED_MISSING).copied().collect::<Vec<_>>().join("、");
+                    if missing.len() > MAX_LISTED_MISSING {
+                        listed.push_str(" 等");
+                    }
+                    problems.push(i18n::trf(
+                        "播放列表“{}”中有 {} 个音频文件丢失：{}",
+                        &[&playlist_name, &missing.len(), &listed],
+                    ));
+                }
+            }
+        }
+    }
+
+    if !output_device_available() {
+        problems.push(i18n::tr("未检测到音频输出设备"));
+    }
+
+    problems
+}
+
+/// 查询将在 (from, to] 之间触发的任务（按星期映射解析当天的播放列表）
+fn load_upcoming_tasks(
+    conn: &Connection,
+    from: NaiveDateTime,
+    to: NaiveDateTime,
+) -> rusqlite::Result<Vec<UpcomingTask>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, name, hour, minute, repeat_mode, custom_days, playlist_id, weekday_playlists,
+                EXISTS(SELECT 1 FROM execution_history eh WHERE eh.task_id = st.id), critical,
+                start_date, end_date, skip_holidays
+         FROM scheduled_tasks st
+         WHERE is_enabled = 1 AND deleted_at IS NULL AND action = 'play'",
+    )?;
+    let rows = stmt
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, u32>(2)?,
+                row.get::<_, u32>(3)?,
+                row.get::<_, String>(4)?,
+                row.get::<_, Option<String>>(5)?,
+                row.get::<_, i64>(6)?,
+                row.get::<_, Option<String>>(7)?,
+                row.get::<_, bool>(8)?,
+                row.get::<_, bool>(9)?,
+                (row.get::<_, Option<String>>(10)?, row.get::<_, Option<String>>(11)?, row.get::<_, bool>(12)?),
+            ))
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    let mut upcoming = Vec::new();
+    for (id, name, hour, minute, repeat_mode, custom_days, playlist_id, weekday_playlists, executed, critical, (start, end, skip_holidays)) in rows {
+        if repeat_mode == "once" && executed {
+            continue;
+        }
+        let Some(time) = NaiveTime::from_hms_opt(hour, minute, 0) else { continue };
+        // cron 任务一天可能触发多次，取窗口内的第一次
+        let times = weekday::trigger_times(&repeat_mode, custom_days.as_deref(), time);
+
+        // 窗口可能跨过午夜，今天和明天都要看
+        'dates: for date in [from.date(), to.date()] {
+            let day = weekday::weekday_index(&date);
+            if !weekday::runs_on_date(&repeat_mode, custom_days.as_deref(), start.as_deref(), end.as_deref(), date)
+                || (skip_holidays && holiday::is_holiday(conn, date))
+            {
+                continue;
+            }
+            for trigger in times.iter().map(|time| date.and_time(*time)) {
+                if trigger <= from || trigger > to {
+                    continue;
+                }
+                upcoming.push(UpcomingTask {
+                    id,
+                    name: name.clone(),
+                    trigger,
+                    playlist_id: weekday::playlist_for_weekday(weekday_playlists.as_deref(), day, playlist_id),
+                    critical,
+                });
+                break 'dates;
+            }
+        }
+    }
+    Ok(upcoming)
+}
+
+fn notify(app: &AppHandle, report: &PreflightReport) {
+    let separator = if i18n::current_language() == "en" { "; " } else { "；" };
+    let body = i18n::trf(
+        "{} 将在 {} 执行：{}",
+        &[&report.task_name, &report.trigger_time, &report.problems.join(separator)],
+    );
+    if let Err(e) = tauri::api::notification::Notification::new(&app.config().tauri.bundle.identifier)
+        .title(i18n::tr("定时任务预检发现问题"))
+        .body(body)
+        .show()
+    {
+        eprintln!("[Preflight] 发送系统通知失败: {}", e);
+    }
+}
+
+/// 启动预检监视器：任务触发前几分钟检查播放列表、音频文件和输出设备，有问题时提前提醒
+pub fn start_preflight_monitor(app: AppHandle, db: Arc<Mutex<Connection>>) {
+    tauri::async_runtime::spawn(async move {
+        let mut ticker = interval(Duration::from_secs(PREFLIGHT_CHECK_SECS));
+        // 已预检过的 (任务ID, 触发时间)，每次触发只提醒一次
+        let mut checked: HashSet<(i64, NaiveDateTime)> = HashSet::new();
+
+        loop {
+            ticker.tick().await;
+
+            let now = Local::now().naive_local();
+            checked.retain(|(_, trigger)| *trigger > now);
+
+            let reports = {
+                let conn = db.lock().await;
+                // 暂停期间只有重要任务会执行
+                let paused = pause::load_pause_state(&conn).paused;
+
+                let upcoming = match load_upcoming_tasks(&conn, now, now + chrono::Duration::minutes(PREFLIGHT_LEAD_MINUTES)) {
+                    Ok(upcoming) => upcoming,
+                    Err(e) => {
+                        eprintln!("[Preflight] 查询任务失败: {}", e);
+                        continue;
+                    }
+                };
+
+                upcoming
+                    .into_iter()
+                    .filter(|task| !paused || task.critical)
+                    .filter(|task| checked.insert((task.id, task.trigger)))
+                    .map(|task| PreflightReport {
+                        task_id: task.id,
+                        problems: check_playlist(&conn, task.playlist_id),
+                        task_name: task.name,
+                        trigger_time: task.trigger.format("%Y-%m-%d %H:%M").to_string(),
+                        playlist_id: task.playlist_id,
+                    })
+                    .filter(|report| !report.problems.is_empty())
+                    .collect::<Vec<_>>()
+            };
+
+            for report in reports {
+                eprintln!("[Preflight] 任务 {} 预检发现问题: {:?}", report.task_name, report.problems);
+                notify(&app, &report);
+                let _ = app.emit_all("preflight-warning", &report);
+            }
+        }
+    });
+}
+
+/// 立即对指定任务做一次预检（按今天的星期解析播放列表）
+#[tauri::command]
+pub async fn preflight_check_task(
+    task_id: i64,
+    conn: State<'_, Arc<Mutex<Connection>>>,
+) -> Result<PreflightReport, String> {
+    let conn = conn.lock().await;
+    let (task_name, hour, minute, playlist_id, weekday_playlists): (String, u32, u32, i64, Option<String>) = conn
+        .query_row(
+            "SELECT name, hour, minute, playlist_id, weekday_playlists
+             FROM scheduled_tasks WHERE id = ?1 AND deleted_at IS NULL",
+            [task_id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?)),
+        )
+        .map_err(|_| i18n::tr("任务不存在"))?;
+
+    let today = Local::now().date_naive();
+    let playlist_id = weekday::playlist_for_weekday(weekday_playlists.as_deref(), weekday::weekday_index(&today), playlist_id);
+
+    Ok(PreflightReport {
+        task_id,
+        task_name,
+        trigger_time: format!("{} {:02}:{:02}", today.format("%Y-%m-%d"), hour, minute),
+        playlist_id,
+        problems: check_playlist(&conn, playlist_id),
+    })
+}
@@ -0,0 +1,153 @@
+use serde::Serialize;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use rusqlite::Connection;
+use tauri::State;
+use crate::i18n;
+
+// 录音模板支持的保存格式（mp3 需要 FFmpeg，未安装时保留为 wav）
+const TEMPLATE_FORMATS: [&str; 2] = ["wav", "mp3"];
+
+/// 录音模板：固定的命名规则、保存目录、格式和自动加入的播放列表，用于每天重复的跟读打卡
+#[derive(Debug, Clone, Serialize)]
+pub struct RecordingTemplate {
+    pub id: i64,
+    pub name: String,
+    /// 文件名规则，可用 {name}（模板名）、{date}（20240101）、{time}（083000）、{n}（该模板的第几次录音）
+    pub filename_pattern: String,
+    /// 音频目录下的子目录，为空时保存在音频目录
+    pub folder: Option<String>,
+    pub format: String,
+    /// 录音结束后自动加入的播放列表
+    pub playlist_id: Option<i64>,
+    pub playlist_name: Option<String>,
+    /// 使用该模板录制的音频数
+    pub recording_count: i64,
+}
+
+fn template_from_row(row: &rusqlite::Row) -> rusqlite::Result<RecordingTemplate> {
+    Ok(RecordingTemplate {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        filename_pattern: row.get(2)?,
+        folder: row.get(3)?,
+        format: row.get(4)?,
+        playlist_id: row.get(5)?,
+        playlist_name: row.get(6)?,
+        recording_count: row.get(7)?,
+    })
+}
+
+const TEMPLATE_QUERY: &str =
+    "SELECT rt.id, rt.name, rt.filename_pattern, rt.folder, rt.format, rt.playlist_id, p.name,
+            (SELECT COUNT(*) FROM audio_files af WHERE af.recording_template_id = rt.id)
+     FROM recording_templates rt
+     LEFT JOIN playlists p ON rt.playlist_id = p.id AND p.deleted_at IS NULL";
+
+pub fn load_template(conn: &Connection, id: i64) -> Result<RecordingTemplate, String> {
+    conn.query_row(&format!("{} WHERE rt.id = ?1", TEMPLATE_QUERY), [id], template_from_row)
+        .map_err(|_| i18n::tr("录音模板不存在"))
+}
+
+/// 按模板的文件名规则生成本次录音的文件名（不含扩展名）
+pub fn render_filename(template: &RecordingTemplate) -> String {
+    let now = chrono::Local::now();
+    let name = template
+        .filename_pattern
+        .replace("{name}", &template.name)
+        .replace("{date}", &now.format("%Y%m%d").to_string())
+        .replace("{time}", &now.format("%H%M%S").to_string())
+        .replace("{n}", &(template.recording_count + 1).to_string());
+    name.replace(['/', '\\'], "_").trim().to_string()
+}
+
+/// 校验模板字段，返回规范化后的子目录
+fn validate(name: &str, filename_pattern: &str, folder: Option<String>, format: &str) -> Result<Option<String>, String> {
+    if name.trim().is_empty() || filename_pattern.trim().is_empty() {
+        return Err(i18n::tr("请输入模板名称和文件名规则"));
+    }
+    if !TEMPLATE_FORMATS.contains(&format) {
+        return Err(i18n::tr("不支持的音频格式"));
+    }
+    let folder = folder.map(|f| f.trim().to_string()).filter(|f| !f.is_empty());
+    if folder.as_deref().is_some_and(|f| f.contains(['/', '\\']) || f == "." || f == "..") {
+        return Err(i18n::tr("保存目录只能是一层子目录名"));
+    }
+    Ok(folder)
+}
+
+#[tauri::command]
+pub async fn get_recording_templates(
+    conn: State<'_, Arc<Mutex<Connection>>>,
+) -> Result<Vec<RecordingTemplate>, String> {
+    let conn = conn.lock().await;
+    let mut stmt = conn
+        .prepare(&format!("{} ORDER BY rt.id", TEMPLATE_QUERY))
+        .map_err(|e| e.to_string())?;
+    let templates = stmt
+        .query_map([], template_from_row)
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+    Ok(templates)
+}
+
+#[tauri::command]
+pub async fn create_recording_template(
+    name: String,
+    filename_pattern: String,
+    folder: Option<String>,
+    format: String,
+    playlist_id: Option<i64>,
+    conn: State<'_, Arc<Mutex<Connection>>>,
+) -> Result<i64, String> {
+    let folder = validate(&name, &filename_pattern, folder, &format)?;
+    let conn = conn.lock().await;
+    conn.execute(
+        "INSERT INTO recording_templates (name, filename_pattern, folder, format, playlist_id)
+         VALUES (?1, ?2, ?3, ?4, ?5)",
+        (name.trim(), filename_pattern.trim(), folder, &format, playlist_id),
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(conn.last_insert_rowid())
+}
+
+#[tauri::command]
+pub async fn update_recording_template(
+    id: i64,
+    name: String,
+    filename_pattern: String,
+    folder: Option<String>,
+    format: String,
+    playlist_id: Option<i64>,
+    conn: State<'_, Arc<Mutex<Connection>>>,
+) -> Result<(), String> {
+    let folder = validate(&name, &filename_pattern, folder, &format)?;
+    let conn = conn.lock().await;
+    let updated = conn
+        .execute(
+            "UPDATE recording_templates
+             SET name = ?1, filename_pattern = ?2, folder = ?3, format = ?4, playlist_id = ?5
+             WHERE id = ?6",
+            (name.trim(), filename_pattern.trim(), folder, &format, playlist_id, id),
+        )
+        .map_err(|e| e.to_string())?;
+    if updated == 0 {
+        return Err(i18n::tr("录音模板不存在"));
+    }
+    Ok(())
+}
+
+/// 删除录音模板，已录制的音频保留
+#[tauri::command]
+pub async fn delete_recording_template(
+    id: i64,
+    conn: State<'_, Arc<Mutex<Connection>>>,
+) -> Result<(), String> {
+    let conn = conn.lock().await;
+    conn.execute("UPDATE audio_files SET recording_template_id = NULL WHERE recording_template_id = ?1", [id])
+        .map_err(|e| e.to_string())?;
+    conn.execute("DELETE FROM recording_templates WHERE id = ?1", [id])
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
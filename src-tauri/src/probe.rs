@@ -0,0 +1,131 @@
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::sync::{Mutex, Notify};
+use rusqlite::Connection;
+use tauri::AppHandle;
+use crate::audio::{create_command_from_path, get_ffmpeg_executable_path, probe_audio_duration};
+use crate::library_events::LibraryEvents;
+
+/// 后台时长检测队列：导入时写入 duration=NULL，唤醒队列后在后台补全
+#[derive(Clone)]
+pub struct DurationProbeQueue {
+    notify: Arc<Notify>,
+}
+
+impl DurationProbeQueue {
+    /// 通知后台有新的待检测音频
+    pub fn wake(&self) {
+        self.notify.notify_one();
+    }
+}
+
+/// 根据 FFmpeg 路径推断同目录下的 ffprobe
+pub(crate) fn ffprobe_path_for(ffmpeg_path: &Path) -> PathBuf {
+    let is_exe = ffmpeg_path
+        .extension()
+        .map(|ext| ext.eq_ignore_ascii_case("exe"))
+        .unwrap_or(false);
+    ffmpeg_path.with_file_name(if is_exe { "ffprobe.exe" } else { "ffprobe" })
+}
+
+/// 使用 ffprobe 读取时长（秒）
+fn probe_with_ffprobe(ffprobe_path: &PathBuf, file_path: &Path) -> Option<i64> {
+    let output = create_command_from_path(ffprobe_path)
+        .arg("-v")
+        .arg("error")
+        .arg("-show_entries")
+        .arg("format=duration")
+        .arg("-of")
+        .arg("default=noprint_wrappers=1:nokey=1")
+        .arg(file_path)
+        .output()
+        .ok()?;
+
+    if !output.status.success() {
+        return None;
+    }
+
+    String::from_utf8_lossy(&output.stdout)
+        .trim()
+        .parse::<f64>()
+        .ok()
+        .filter(|secs| secs.is_finite() && *secs > 0.0)
+        .map(|secs| secs.ceil() as i64)
+}
+
+/// 查询尚未检测时长的音频
+fn load_pending(conn: &Connection) -> rusqlite::Result<Vec<(i64, String)>> {
+    let mut stmt = conn.prepare("SELECT id, file_path FROM audio_files WHERE duration IS NULL ORDER BY id")?;
+    let rows = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    Ok(rows)
+}
+
+/// 启动后台时长检测队列，检测到的时长通过音库变化事件通知前端
+pub fn start_duration_prober(app: AppHandle, db: Arc<Mutex<Connection>>, library: LibraryEvents) -> DurationProbeQueue {
+    let notify = Arc::new(Notify::new());
+    let queue = DurationProbeQueue { notify: notify.clone() };
+
+    tauri::async_runtime::spawn(async move {
+        // 本次运行中无法读取的文件不再重复检测
+        let mut failed: HashSet<i64> = HashSet::new();
+
+        loop {
+            let pending = {
+                let conn = db.lock().await;
+                load_pending(&conn).unwrap_or_else(|e| {
+                    eprintln!("[DurationProbe] 查询待检测音频失败: {}", e);
+                    Vec::new()
+                })
+            };
+            let pending: Vec<(i64, String)> = pending
+                .into_iter()
+                .filter(|(id, _)| !failed.contains(id))
+                .collect();
+
+            if pending.is_empty() {
+                notify.notified().await;
+                continue;
+            }
+
+            let ffprobe_path = get_ffmpeg_executable_path(Some(&app))
+                .await
+                .map(|ffmpeg| ffprobe_path_for(&ffmpeg));
+
+            for (audio_id, file_path) in pending {
+                let ffprobe_path = ffprobe_path.clone();
+                let duration = tokio::task::spawn_blocking(move || {
+                    let path = Path::new(&file_path);
+                    probe_audio_duration(path)
+                        .or_else(|| ffprobe_path.and_then(|ffprobe| probe_with_ffprobe(&ffprobe, path)))
+                })
+                .await
+                .ok()
+                .flatten();
+
+                match duration {
+                    Some(duration) => {
+                        let conn = db.lock().await;
+                        if let Err(e) = conn.execute(
+                            "UPDATE audio_files SET duration = ?1 WHERE id = ?2",
+                            (duration, audio_id),
+                        ) {
+                            eprintln!("[DurationProbe] 保存时长失败: {}", e);
+                            failed.insert(audio_id);
+                            continue;
+                        }
+                        library.updated([audio_id]);
+                    }
+                    None => {
+                        eprintln!("[DurationProbe] 无法读取音频时长: {}", audio_id);
+                        failed.insert(audio_id);
+                    }
+                }
+            }
+        }
+    });
+
+    queue
+}
@@ -0,0 +1,338 @@
+use serde::Serialize;
+use std::fs::File;
+use std::io::BufReader;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use rusqlite::Connection;
+use rodio::{Decoder, Source};
+use tauri::State;
+use crate::i18n;
+
+// 分析帧长（秒）
+const FRAME_SECS: f64 = 0.02;
+// 计入停顿的最短静音时长（秒）
+const MIN_PAUSE_SECS: f64 = 0.25;
+// 节奏对比时重采样的点数
+const RHYTHM_POINTS: usize = 100;
+// 音高曲线重采样的点数
+const PITCH_POINTS: usize = 50;
+// 最多分析的音频时长（秒），避免误选长音频时耗时过久
+const MAX_ANALYZE_SECS: usize = 600;
+
+#[derive(Debug, Serialize)]
+pub struct PronunciationScore {
+    pub id: i64,
+    pub reference_id: i64,
+    pub recording_id: i64,
+    pub score: i64,
+    pub duration_score: i64,
+    pub rhythm_score: i64,
+    pub pitch_score: Option<i64>,
+    pub reference_duration: f64,
+    pub recording_duration: f64,
+    pub reference_pauses: usize,
+    pub recording_pauses: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PronunciationHistoryItem {
+    pub id: i64,
+    pub recording_id: i64,
+    pub score: i64,
+    pub created_date: String,
+}
+
+/// 单个音频的分析结果
+struct AudioProfile {
+    // 有声部分的时长（去除首尾静音）
+    duration: f64,
+    // 每帧是否有声
+    voiced: Vec<bool>,
+    // 有声帧的音高（半音），无声帧为None
+    pitch: Vec<Option<f64>>,
+    pauses: usize,
+}
+
+/// 解码为单声道采样
+fn decode_mono(file_path: &str) -> Result<(Vec<f32>, u32), String> {
+    let file = File::open(file_path).map_err(|e| i18n::trf("打开音频失败: {}", &[&e]))?;
+    let source = Decoder::new(BufReader::new(file)).map_err(|e| i18n::trf("解码音频失败: {}", &[&e]))?;
+
+    let channels = source.channels().max(1) as usize;
+    let sample_rate = source.sample_rate();
+    let max_samples = MAX_ANALYZE_SECS * sample_rate as usize * channels;
+
+    let mut mono = Vec::new();
+    let mut frame_sum = 0.0f32;
+    let mut frame_len = 0;
+
+    for sample in source.take(max_samples) {
+        frame_sum += sample as f32 / i16::MAX as f32;
+        frame_len += 1;
+        if frame_len == channels {
+            mono.push(frame_sum / channels as f32);
+            frame_sum = 0.0;
+            frame_len = 0;
+        }
+    }
+
+    Ok((mono, sample_rate))
+}
+
+/// 自相关法估计一帧的基频，返回半音值
+fn estimate_pitch(frame: &[f32], sample_rate: u32) -> Option<f64> {
+    let min_lag = (sample_rate / 400) as usize;
+    let max_lag = ((sample_rate / 80) as usize).min(frame.len().saturating_sub(1));
+    if min_lag >= max_lag {
+        return None;
+    }
+
+    let energy: f32 = frame.iter().map(|s| s * s).sum();
+    if energy <= f32::EPSILON {
+        return None;
+    }
+
+    let mut best_lag = 0;
+    let mut best_corr = 0.0f32;
+    for lag in min_lag..=max_lag {
+        let corr: f32 = frame.iter().zip(&frame[lag..]).map(|(a, b)| a * b).sum();
+        if corr > best_corr {
+            best_corr = corr;
+            best_lag = lag;
+        }
+    }
+
+    // 相关度太低视为无明确音高
+    if best_lag == 0 || best_corr / energy < 0.3 {
+        return None;
+    }
+
+    let freq = sample_rate as f64 / best_lag as f64;
+    Some(12.0 * (freq / 440.0).log2())
+}
+
+fn analyze(file_path: &str, with_pitch: bool) -> Result<AudioProfile, String> {
+    let (samples, sample_rate) = decode_mono(file_path)?;
+    let frame_len = ((sample_rate as f64 * FRAME_SECS) as usize).max(1);
+
+    let rms: Vec<f32> = samples
+        .chunks(frame_len)
+        .map(|frame| (frame.iter().map(|s| s * s).sum::<f32>() / frame.len() as f32).sqrt())
+        .collect();
+
+    let peak = rms.iter().cloned().fold(0.0f32, f32::max);
+    if peak <= f32::EPSILON {
+        return Err(i18n::tr("音频没有可分析的声音"));
+    }
+
+    // 以峰值的10%作为有声阈值
+    let threshold = peak * 0.1;
+    let voiced_all: Vec<bool> = rms.iter().map(|r| *r >= threshold).collect();
+
+    // 去除首尾静音
+    let first = voiced_all.iter().position(|v| *v).unwrap_or(0);
+    let last = voiced_all.iter().rposition(|v| *v).unwrap_or(0);
+    let voiced = voiced_all[first..=last].to_vec();
+
+    let min_pause_frames = (MIN_PAUSE_SECS / FRAME_SECS) as usize;
+    let mut pauses = 0;
+    let mut silent_run = 0;
+    for v in &voiced {
+        if *v {
+            if silent_run >= min_pause_frames {
+                pauses += 1;
+            }
+            silent_run = 0;
+        } else {
+            silent_run += 1;
+        }
+    }
+
+    let pitch = if with_pitch {
+        (first..=last)
+            .map(|i| {
+                if !voiced_all[i] {
+                    return None;
+                }
+                let start = i * frame_len;
+                let end = (start + frame_len * 2).min(samples.len());
+                estimate_pitch(&samples[start..end], sample_rate)
+            })
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    Ok(AudioProfile {
+        duration: voiced.len() as f64 * FRAME_SECS,
+        voiced,
+        pitch,
+        pauses,
+    })
+}
+
+/// 把序列按比例重采样到固定点数，便于对齐不同时长的两段音频
+fn resample<T: Clone>(values: &[T], points: usize) -> Vec<T> {
+    (0..points)
+        .map(|i| values[(i * values.len() / points).min(values.len() - 1)].clone())
+        .collect()
+}
+
+fn duration_score(reference: &AudioProfile, recording: &AudioProfile) -> f64 {
+    let (short, long) = if reference.duration < recording.duration {
+        (reference.duration, recording.duration)
+    } else {
+        (recording.duration, reference.duration)
+    };
+    if long <= 0.0 {
+        return 0.0;
+    }
+    short / long * 100.0
+}
+
+fn rhythm_score(reference: &AudioProfile, recording: &AudioProfile) -> f64 {
+    let a = resample(&reference.voiced, RHYTHM_POINTS);
+    let b = resample(&recording.voiced, RHYTHM_POINTS);
+    let matched = a.iter().zip(&b).filter(|(x, y)| x == y).count();
+    let pattern = matched as f64 / RHYTHM_POINTS as f64 * 100.0;
+
+    // 停顿次数差异扣分
+    let pause_diff = (reference.pauses as f64 - recording.pauses as f64).abs();
+    let pause_base = reference.pauses.max(recording.pauses).max(1) as f64;
+    let pauses = (1.0 - pause_diff / pause_base).max(0.0) * 100.0;
+
+    pattern * 0.7 + pauses * 0.3
+}
+
+/// 比较音高曲线走势（减去各自均值，消除男女声/童声的音域差异）
+fn pitch_score(reference: &AudioProfile, recording: &AudioProfile) -> Option<f64> {
+    let a = resample(&reference.pitch, PITCH_POINTS);
+    let b = resample(&recording.pitch, PITCH_POINTS);
+
+    let pairs: Vec<(f64, f64)> = a
+        .iter()
+        .zip(&b)
+        .filter_map(|(x, y)| Some(((*x)?, (*y)?)))
+        .collect();
+    if pairs.len() < 5 {
+        return None;
+    }
+
+    let mean_a = pairs.iter().map(|(x, _)| x).sum::<f64>() / pairs.len() as f64;
+    let mean_b = pairs.iter().map(|(_, y)| y).sum::<f64>() / pairs.len() as f64;
+    let avg_diff = pairs
+        .iter()
+        .map(|(x, y)| ((x - mean_a) - (y - mean_b)).abs())
+        .sum::<f64>()
+        / pairs.len() as f64;
+
+    // 平均相差6个半音及以上记0分
+    Some(((1.0 - avg_diff / 6.0).max(0.0)) * 100.0)
+}
+
+fn get_file_path(conn: &Connection, audio_id: i64) -> Result<String, String> {
+    conn.query_row(
+        "SELECT file_path FROM audio_files WHERE id = ?1",
+        [audio_id],
+        |row| row.get(0),
+    )
+    .map_err(|e| i18n::trf("音频不存在: {}", &[&e]))
+}
+
+/// 对比跟读录音与原音，给出相似度评分
+#[tauri::command]
+pub async fn compare_pronunciation(
+    reference_id: i64,
+    recording_id: i64,
+    include_pitch: Option<bool>,
+    conn: State<'_, Arc<Mutex<Connection>>>,
+) -> Result<PronunciationScore, String> {
+    let (reference_path, recording_path) = {
+        let conn = conn.lock().await;
+        (get_file_path(&conn, reference_id)?, get_file_path(&conn, recording_id)?)
+    };
+
+    let with_pitch = include_pitch.unwrap_or(true);
+    let (reference, recording) = tokio::task::spawn_blocking(move || {
+        Ok::<_, String>((
+            analyze(&reference_path, with_pitch)?,
+            analyze(&recording_path, with_pitch)?,
+        ))
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+
+    let duration = duration_score(&reference, &recording);
+    let rhythm = rhythm_score(&reference, &recording);
+    let pitch = if with_pitch {
+        pitch_score(&reference, &recording)
+    } else {
+        None
+    };
+
+    let score = match pitch {
+        Some(pitch) => duration * 0.3 + rhythm * 0.4 + pitch * 0.3,
+        None => duration * 0.4 + rhythm * 0.6,
+    };
+
+    let conn = conn.lock().await;
+    conn.execute(
+        "INSERT INTO pronunciation_scores (reference_id, recording_id, score, duration_score, rhythm_score, pitch_score)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        (
+            reference_id,
+            recording_id,
+            score.round() as i64,
+            duration.round() as i64,
+            rhythm.round() as i64,
+            pitch.map(|p| p.round() as i64),
+        ),
+    )
+    .map_err(|e| e.to_string())?;
+
+    Ok(PronunciationScore {
+        id: conn.last_insert_rowid(),
+        reference_id,
+        recording_id,
+        score: score.round() as i64,
+        duration_score: duration.round() as i64,
+        rhythm_score: rhythm.round() as i64,
+        pitch_score: pitch.map(|p| p.round() as i64),
+        reference_duration: reference.duration,
+        recording_duration: recording.duration,
+        reference_pauses: reference.pauses,
+        recording_pauses: recording.pauses,
+    })
+}
+
+/// 获取某段原音的跟读评分历史
+#[tauri::command]
+pub async fn get_pronunciation_history(
+    reference_id: i64,
+    conn: State<'_, Arc<Mutex<Connection>>>,
+) -> Result<Vec<PronunciationHistoryItem>, String> {
+    let conn = conn.lock().await;
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, recording_id, score, created_date
+             FROM pronunciation_scores
+             WHERE reference_id = ?1
+             ORDER BY created_date DESC"
+        )
+        .map_err(|e| e.to_string())?;
+
+    let items = stmt
+        .query_map([reference_id], |row| {
+            Ok(PronunciationHistoryItem {
+                id: row.get(0)?,
+                recording_id: row.get(1)?,
+                score: row.get(2)?,
+                created_date: row.get(3)?,
+            })
+        })
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    Ok(items)
+}
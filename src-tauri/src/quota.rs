@@ -0,0 +1,138 @@
+use serde::Serialize;
+use std::sync::Arc;
+use std::time::Instant;
+use tokio::sync::Mutex;
+use tokio::time::{interval, Duration};
+use rusqlite::Connection;
+use tauri::{AppHandle, Manager, State};
+use crate::i18n;
+use crate::kiosk;
+use crate::player::AudioPlayer;
+
+// 每日收听时长（分钟，0 为不限制）保存在 app_settings 中，后台服务进程也按此限制
+const QUOTA_SETTING_KEY: &str = "daily_listening_quota";
+// 统计实际播放时长的间隔（毫秒）
+const CHECK_INTERVAL_MILLIS: u64 = 1000;
+// 两次检查之间最多计入的秒数（系统休眠等造成的间隔不算收听）
+const MAX_TICK_SECS: f64 = 5.0;
+const MAX_QUOTA_MINUTES: i64 = 24 * 60;
+// 用完当天的收听时长、停止播放时发给前端的事件
+const QUOTA_REACHED_EVENT: &str = "listening-quota-reached";
+
+/// 今天的收听时长和限制
+#[derive(Debug, Clone, Serialize)]
+pub struct QuotaStatus {
+    /// 每日收听时长（分钟），0 为不限制
+    pub quota_minutes: i64,
+    /// 今天已经收听的秒数
+    pub used_secs: f64,
+    /// 今天剩余的秒数，不限制时为 None
+    pub remaining_secs: Option<f64>,
+}
+
+impl QuotaStatus {
+    pub fn is_exhausted(&self) -> bool {
+        self.remaining_secs.is_some_and(|remaining| remaining <= 0.0)
+    }
+}
+
+fn load_quota_minutes(conn: &Connection) -> i64 {
+    conn.query_row(
+        "SELECT value FROM app_settings WHERE key = ?1",
+        [QUOTA_SETTING_KEY],
+        |row| row.get::<_, String>(0),
+    )
+    .ok()
+    .and_then(|value| value.parse().ok())
+    .unwrap_or(0)
+}
+
+pub fn load_status(conn: &Connection) -> QuotaStatus {
+    let quota_minutes = load_quota_minutes(conn);
+    let used_secs: f64 = conn
+        .query_row(
+            "SELECT seconds FROM listening_time WHERE day = date('now', 'localtime')",
+            [],
+            |row| row.get(0),
+        )
+        .unwrap_or(0.0);
+    let remaining_secs = (quota_minutes > 0).then(|| (quota_minutes as f64 * 60.0 - used_secs).max(0.0));
+    QuotaStatus { quota_minutes, used_secs, remaining_secs }
+}
+
+/// 累加今天的收听时长
+fn add_listening_secs(conn: &Connection, secs: f64) -> rusqlite::Result<()> {
+    conn.execute(
+        "INSERT INTO listening_time (day, seconds) VALUES (date('now', 'localtime'), ?1)
+         ON CONFLICT(day) DO UPDATE SET seconds = seconds + ?1",
+        [secs],
+    )?;
+    Ok(())
+}
+
+/// 启动收听时长监视器：播放中时累计今天的实际收听时长（手动播放和定时任务都计入），
+/// 达到每日限制时停止播放并发出 listening-quota-reached 事件（后台服务进程没有窗口，只停止播放）；
+/// 重要任务的播放不受限制
+pub fn start_quota_monitor(app: Option<AppHandle>, db: Arc<Mutex<Connection>>, player: Arc<Mutex<AudioPlayer>>) {
+    tauri::async_runtime::spawn(async move {
+        let mut ticker = interval(Duration::from_millis(CHECK_INTERVAL_MILLIS));
+        let mut last_tick = Instant::now();
+
+        loop {
+            ticker.tick().await;
+            let elapsed = last_tick.elapsed().as_secs_f64().min(MAX_TICK_SECS);
+            last_tick = Instant::now();
+
+            let critical = {
+                let player = player.lock().await;
+                if !player.is_playing() {
+                    continue;
+                }
+                player.is_critical_playback()
+            };
+
+            let status = {
+                let conn = db.lock().await;
+                if let Err(e) = add_listening_secs(&conn, elapsed) {
+                    eprintln!("[Quota] 记录收听时长失败: {}", e);
+                }
+                load_status(&conn)
+            };
+            if status.is_exhausted() && !critical {
+                println!("⏹️ [Quota] 今天的收听时长已用完，停止播放");
+                player.lock().await.stop();
+                if let Some(app) = &app {
+                    let _ = app.emit_all(QUOTA_REACHED_EVENT, status);
+                }
+            }
+        }
+    });
+}
+
+#[tauri::command]
+pub async fn get_listening_quota(
+    conn: State<'_, Arc<Mutex<Connection>>>,
+) -> Result<QuotaStatus, String> {
+    Ok(load_status(&*conn.lock().await))
+}
+
+/// 设置每日收听时长（分钟，0 为不限制）；已设置家长密码时需要提供密码
+#[tauri::command]
+pub async fn set_listening_quota(
+    minutes: i64,
+    pin: Option<String>,
+    conn: State<'_, Arc<Mutex<Connection>>>,
+) -> Result<QuotaStatus, String> {
+    if !(0..=MAX_QUOTA_MINUTES).contains(&minutes) {
+        return Err(i18n::tr("每日收听时长需在0-1440分钟之间"));
+    }
+
+    let conn = conn.lock().await;
+    kiosk::check_parental_pin(&conn, pin.as_deref())?;
+    conn.execute(
+        "INSERT OR REPLACE INTO app_settings (key, value) VALUES (?1, ?2)",
+        (QUOTA_SETTING_KEY, minutes.to_string()),
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(load_status(&conn))
+}
@@ -1,22 +1,44 @@
 use hound::{WavSpec, WavWriter};
 use std::path::PathBuf;
 use std::sync::{Arc, Mutex as StdMutex};
-use tauri::State;
+use tauri::{AppHandle, Manager, State};
 use tokio::sync::Mutex;
 use rusqlite::Connection;
 use serde::Serialize;
 use std::io::BufReader;
+use std::time::Instant;
 use rodio::{Decoder, Source};
 use std::fs;
 use symphonia::core::io::MediaSourceStream;
 use symphonia::core::probe::Hint;
 use symphonia::core::meta::MetadataOptions;
 use symphonia::core::formats::FormatOptions;
+use crate::i18n;
+use crate::library_events::LibraryEvents;
+use crate::presets::{self, RecordingTemplate};
 
 #[derive(Debug, Serialize)]
 pub struct RecordingState {
     pub is_recording: bool,
     pub duration: f32,
+    pub markers: Vec<RecordingMarker>,
+}
+
+/// 录音中的时间点标记
+#[derive(Debug, Clone, Serialize)]
+pub struct RecordingMarker {
+    /// 距录音开始的秒数
+    pub position: f64,
+    pub label: String,
+}
+
+/// 保存在音频上的标记（章节/书签）
+#[derive(Debug, Serialize)]
+pub struct AudioMarker {
+    pub id: i64,
+    pub audio_id: i64,
+    pub position: f64,
+    pub label: String,
 }
 
 /// 获取音频文件的真实时长（秒）
@@ -92,6 +114,12 @@ fn get_audio_duration(file_path: &std::path::Path) -> i64 {
 pub struct AudioRecorder {
     is_recording: Arc<StdMutex<bool>>,
     output_path: Arc<StdMutex<Option<PathBuf>>>,
+    started_at: StdMutex<Option<Instant>>,
+    markers: StdMutex<Vec<RecordingMarker>>,
+    // 本次录音使用的模板，录音结束时按模板保存
+    template: StdMutex<Option<RecordingTemplate>>,
+    // 本次录音结束后加入的播放列表（优先于模板的播放列表）
+    playlist_id: StdMutex<Option<i64>>,
 }
 
 // 手动实现Send和Sync
@@ -103,6 +131,10 @@ impl AudioRecorder {
         AudioRecorder {
             is_recording: Arc::new(StdMutex::new(false)),
             output_path: Arc::new(StdMutex::new(None)),
+            started_at: StdMutex::new(None),
+            markers: StdMutex::new(Vec::new()),
+            template: StdMutex::new(None),
+            playlist_id: StdMutex::new(None),
         }
     }
 
@@ -121,28 +153,95 @@ impl AudioRecorder {
     pub fn set_output_path(&self, path: Option<PathBuf>) {
         *self.output_path.lock().unwrap() = path;
     }
+
+    /// 已录制的秒数
+    pub fn elapsed_secs(&self) -> f64 {
+        self.started_at.lock().unwrap().map(|t| t.elapsed().as_secs_f64()).unwrap_or(0.0)
+    }
+
+    /// 在当前录音位置添加标记
+    pub fn add_marker(&self, label: Option<String>) -> RecordingMarker {
+        let mut markers = self.markers.lock().unwrap();
+        let marker = RecordingMarker {
+            position: self.elapsed_secs(),
+            label: label
+                .map(|l| l.trim().to_string())
+                .filter(|l| !l.is_empty())
+                .unwrap_or_else(|| format!("标记 {}", markers.len() + 1)),
+        };
+        markers.push(marker.clone());
+        marker
+    }
+
+    pub fn markers(&self) -> Vec<RecordingMarker> {
+        self.markers.lock().unwrap().clone()
+    }
+
+    /// 开始新录音时重置计时和标记，结束时取出标记
+    fn begin_session(&self) {
+        *self.started_at.lock().unwrap() = Some(Instant::now());
+        self.markers.lock().unwrap().clear();
+    }
+
+    fn end_session(&self) -> Vec<RecordingMarker> {
+        *self.started_at.lock().unwrap() = None;
+        std::mem::take(&mut *self.markers.lock().unwrap())
+    }
 }
 
+/// 开始录音。filename 为空时按模板的文件名规则命名，都没有时按当前时间命名；
+/// 指定 playlist_id 时录音结束后自动加入该播放列表
 #[tauri::command]
 pub async fn start_recording(
-    filename: String,
+    filename: Option<String>,
+    template_id: Option<i64>,
+    playlist_id: Option<i64>,
     audio_dir: State<'_, PathBuf>,
     recorder: State<'_, Arc<Mutex<AudioRecorder>>>,
+    conn: State<'_, Arc<Mutex<Connection>>>,
 ) -> Result<String, String> {
     let recorder = recorder.lock().await;
 
     if recorder.is_recording() {
-        return Err("已经在录音中".to_string());
+        return Err(i18n::tr("已经在录音中"));
     }
 
+    let template = match template_id {
+        Some(id) => Some(presets::load_template(&*conn.lock().await, id)?),
+        None => None,
+    };
+    if let Some(playlist_id) = playlist_id {
+        let exists: bool = conn
+            .lock()
+            .await
+            .query_row(
+                "SELECT EXISTS(SELECT 1 FROM playlists WHERE id = ?1 AND deleted_at IS NULL)",
+                [playlist_id],
+                |row| row.get(0),
+            )
+            .map_err(|e| e.to_string())?;
+        if !exists {
+            return Err(i18n::tr("播放列表不存在"));
+        }
+    }
+    let filename = filename
+        .map(|f| f.trim().to_string())
+        .filter(|f| !f.is_empty())
+        .or_else(|| template.as_ref().map(presets::render_filename))
+        .filter(|f| !f.is_empty())
+        .unwrap_or_else(|| chrono::Local::now().format("录音_%Y%m%d_%H%M%S").to_string());
+    *recorder.template.lock().unwrap() = template;
+    *recorder.playlist_id.lock().unwrap() = playlist_id;
+
     // 创建rec子目录用于存放录音文件
-    let rec_dir = audio_dir.join("rec");
+    let rec_dir = audio_dir.join(crate::storage::RECORDING_DIR);
     std::fs::create_dir_all(&rec_dir)
-        .map_err(|e| format!("创建录音目录失败: {}", e))?;
+        .map_err(|e| i18n::trf("创建录音目录失败: {}", &[&e]))?;
 
     let output_path = rec_dir.join(format!("{}.wav", filename));
     recorder.set_output_path(Some(output_path.clone()));
     recorder.set_recording(true);
+    recorder.begin_session();
 
     // 在后台线程中进行录音
     let output_path_clone = output_path.clone();
@@ -285,8 +384,11 @@ pub async fn start_recording(
     Ok(output_path.to_string_lossy().to_string())
 }
 
+/// 结束录音并导入音频库；使用了模板时按模板的目录和格式保存；
+/// 加入开始录音时选择的播放列表，没有选择时加入模板的播放列表
 #[tauri::command]
 pub async fn stop_recording(
+    app: AppHandle,
     recorder: State<'_, Arc<Mutex<AudioRecorder>>>,
     conn: State<'_, Arc<Mutex<Connection>>>,
     audio_dir: State<'_, PathBuf>,
@@ -294,7 +396,7 @@ pub async fn stop_recording(
     let recorder = recorder.lock().await;
 
     if !recorder.is_recording() {
-        return Err("未在录音中".to_string());
+        return Err(i18n::tr("未在录音中"));
     }
 
     recorder.set_recording(false);
@@ -303,31 +405,66 @@ pub async fn stop_recording(
     std::thread::sleep(std::time::Duration::from_millis(500));
 
     let output_path = recorder.get_output_path()
-        .ok_or("录音文件路径丢失".to_string())?;
+        .ok_or_else(|| i18n::tr("录音文件路径丢失"))?;
 
     recorder.set_output_path(None);
+    let markers = recorder.end_session();
+    let template = recorder.template.lock().unwrap().take();
+    let playlist_id = recorder.playlist_id.lock().unwrap().take();
 
-    // 获取文件信息
-    let metadata = std::fs::metadata(&output_path)
-        .map_err(|e| format!("获取文件信息失败: {}", e))?;
-    let file_size = metadata.len() as i64;
-
-    let original_name = output_path
+    let mut original_name = output_path
         .file_name()
         .and_then(|n| n.to_str())
-        .ok_or("无效的文件名")?
+        .ok_or_else(|| i18n::tr("无效的文件名"))?
         .to_string();
 
-    let filename = format!(
-        "{}_{}.wav",
-        chrono::Local::now().format("%Y%m%d_%H%M%S"),
-        uuid::Uuid::new_v4().to_string().split('-').next().unwrap()
-    );
-
-    // 将文件重命名为标准格式（移动到主audio目录）
-    let dest_path = audio_dir.join(&filename);
-    std::fs::rename(&output_path, &dest_path)
-        .map_err(|e| format!("重命名文件失败: {}", e))?;
+    // 模板要求 MP3 时用 FFmpeg 转码，未安装 FFmpeg 时保留 WAV
+    let ffmpeg_path = match template.as_ref().filter(|t| t.format == "mp3") {
+        Some(_) => {
+            let ffmpeg_path = crate::audio::get_ffmpeg_executable_path(Some(&app)).await;
+            if ffmpeg_path.is_none() {
+                eprintln!("[Recorder] 未安装FFmpeg，录音保留为WAV");
+            }
+            ffmpeg_path
+        }
+        None => None,
+    };
+    let format = if ffmpeg_path.is_some() { "mp3" } else { "wav" };
+
+    // 将文件重命名为标准格式（模板指定了子目录时放到子目录，否则按存储布局存放）
+    let (filename, dest_path) = match template.as_ref().and_then(|t| t.folder.as_deref()) {
+        Some(folder) => {
+            let filename = format!(
+                "{}_{}.{}",
+                chrono::Local::now().format("%Y%m%d_%H%M%S"),
+                uuid::Uuid::new_v4().to_string().split('-').next().unwrap(),
+                format
+            );
+            let dest_dir = audio_dir.join(folder);
+            std::fs::create_dir_all(&dest_dir).map_err(|e| i18n::trf("创建录音目录失败: {}", &[&e]))?;
+            let dest_path = dest_dir.join(&filename);
+            (filename, dest_path)
+        }
+        None => {
+            let layout = crate::storage::load_layout(&*conn.lock().await);
+            let name = std::path::Path::new(&original_name).file_stem().and_then(|s| s.to_str()).unwrap_or(&original_name);
+            crate::storage::managed_file_path(&layout, &audio_dir, name, format, Some(crate::archive::RECORDING_SOURCE_TYPE))?
+        }
+    };
+    match &ffmpeg_path {
+        Some(ffmpeg_path) => {
+            crate::audio::run_ffmpeg_extract(ffmpeg_path, &output_path.to_string_lossy(), &dest_path, false)?;
+            let _ = std::fs::remove_file(&output_path);
+            original_name = std::path::Path::new(&original_name).with_extension("mp3").to_string_lossy().to_string();
+        }
+        None => {
+            std::fs::rename(&output_path, &dest_path)
+                .map_err(|e| i18n::trf("重命名文件失败: {}", &[&e]))?;
+        }
+    }
+    let metadata = std::fs::metadata(&dest_path)
+        .map_err(|e| i18n::trf("获取文件信息失败: {}", &[&e]))?;
+    let file_size = metadata.len() as i64;
 
     // 获取音频真实时长
     let duration = get_audio_duration(&dest_path);
@@ -335,20 +472,48 @@ pub async fn stop_recording(
     // 保存到数据库
     let conn = conn.lock().await;
     conn.execute(
-        "INSERT INTO audio_files (filename, original_name, file_path, file_size, duration, format)
-         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        "INSERT INTO audio_files (filename, original_name, file_path, file_size, duration, format, source_type, recording_template_id)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
         (
             &filename,
             &original_name,
             dest_path.to_str().unwrap(),
             file_size,
             duration,
-            "wav",
+            format,
+            crate::archive::RECORDING_SOURCE_TYPE,
+            template.as_ref().map(|t| t.id),
         ),
     )
     .map_err(|e| e.to_string())?;
 
     let id = conn.last_insert_rowid();
+
+    // 加入选择的播放列表，没有选择时加入模板指定的播放列表（播放列表已删除时跳过）
+    let playlist_id = playlist_id
+        .filter(|playlist_id| {
+            conn.query_row(
+                "SELECT EXISTS(SELECT 1 FROM playlists WHERE id = ?1 AND deleted_at IS NULL)",
+                [playlist_id],
+                |row| row.get::<_, bool>(0),
+            )
+            .unwrap_or(false)
+        })
+        .or_else(|| template.as_ref().filter(|t| t.playlist_name.is_some()).and_then(|t| t.playlist_id));
+    if let Some(playlist_id) = playlist_id {
+        crate::playlist::append_playlist_item(&conn, playlist_id, id).map_err(|e| e.to_string())?;
+    }
+
+    // 保存录音过程中打的标记
+    for marker in markers {
+        conn.execute(
+            "INSERT INTO audio_markers (audio_id, position, label) VALUES (?1, ?2, ?3)",
+            (id, marker.position.min(duration as f64), &marker.label),
+        )
+        .map_err(|e| e.to_string())?;
+    }
+
+    app.state::<LibraryEvents>().added([id]);
     Ok(id)
 }
 
@@ -359,6 +524,54 @@ pub async fn get_recording_state(
     let recorder = recorder.lock().await;
     Ok(RecordingState {
         is_recording: recorder.is_recording(),
-        duration: 0.0,
+        duration: recorder.elapsed_secs() as f32,
+        markers: recorder.markers(),
     })
 }
+
+/// 录音中在当前位置打一个标记（label 为空时自动编号），录音结束后随音频保存
+#[tauri::command]
+pub async fn add_recording_marker(
+    label: Option<String>,
+    recorder: State<'_, Arc<Mutex<AudioRecorder>>>,
+) -> Result<RecordingMarker, String> {
+    let recorder = recorder.lock().await;
+    if !recorder.is_recording() {
+        return Err(i18n::tr("未在录音中"));
+    }
+    Ok(recorder.add_marker(label))
+}
+
+/// 获取音频的标记（按时间排序），可用 play_audio 的 start_at 跳转播放
+#[tauri::command]
+pub async fn get_audio_markers(
+    audio_id: i64,
+    conn: State<'_, Arc<Mutex<Connection>>>,
+) -> Result<Vec<AudioMarker>, String> {
+    load_audio_markers(&*conn.lock().await, audio_id).map_err(|e| e.to_string())
+}
+
+/// 读取音频的标记（按时间排序）
+pub(crate) fn load_audio_markers(conn: &Connection, audio_id: i64) -> rusqlite::Result<Vec<AudioMarker>> {
+    conn.prepare("SELECT id, audio_id, position, label FROM audio_markers WHERE audio_id = ?1 ORDER BY position")?
+        .query_map([audio_id], |row| {
+            Ok(AudioMarker {
+                id: row.get(0)?,
+                audio_id: row.get(1)?,
+                position: row.get(2)?,
+                label: row.get(3)?,
+            })
+        })?
+        .collect()
+}
+
+#[tauri::command]
+pub async fn delete_audio_marker(
+    id: i64,
+    conn: State<'_, Arc<Mutex<Connection>>>,
+) -> Result<(), String> {
+    let conn = conn.lock().await;
+    conn.execute("DELETE FROM audio_markers WHERE id = ?1", [id])
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
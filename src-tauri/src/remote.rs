@@ -0,0 +1,213 @@
+use std::io::{self, Read, Seek, SeekFrom};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Condvar};
+use std::time::Duration;
+use tokio::sync::Mutex;
+use rodio::{Decoder, Source};
+use tauri::State;
+use crate::i18n;
+use crate::player::{AudioPlayer, DecodedSource, PlaybackState};
+
+// 连接和等待下一块数据的超时（秒），超时视为下载中断
+const STREAM_TIMEOUT_SECS: u64 = 20;
+// 边下边播最多缓存的字节数，超过后停止下载
+const MAX_STREAM_BYTES: usize = 300 * 1024 * 1024;
+
+/// 已下载的数据，播放线程从中读取
+#[derive(Default)]
+struct DownloadBuffer {
+    data: Vec<u8>,
+    // 服务器给出的总长度
+    total_len: Option<u64>,
+    finished: bool,
+    error: Option<String>,
+}
+
+#[derive(Default)]
+struct SharedDownload {
+    buffer: std::sync::Mutex<DownloadBuffer>,
+    changed: Condvar,
+    // 读取端已释放（停止播放或切歌），下载随之结束
+    cancelled: AtomicBool,
+}
+
+impl SharedDownload {
+    fn update(&self, update: impl FnOnce(&mut DownloadBuffer)) {
+        if let Ok(mut buffer) = self.buffer.lock() {
+            update(&mut buffer);
+        }
+        self.changed.notify_all();
+    }
+}
+
+/// 边下边播的读取端：读到尚未下载的位置时等待下载
+struct ProgressiveReader {
+    shared: Arc<SharedDownload>,
+    position: u64,
+}
+
+impl ProgressiveReader {
+    /// 等到 until 成立（或下载结束），返回当时的缓冲区
+    fn wait_for(&self, until: impl Fn(&DownloadBuffer) -> bool) -> io::Result<std::sync::MutexGuard<'_, DownloadBuffer>> {
+        let buffer = self.shared.buffer.lock().map_err(|_| io::Error::other("download poisoned"))?;
+        self.shared
+            .changed
+            .wait_while(buffer, |buffer| !buffer.finished && !until(buffer))
+            .map_err(|_| io::Error::other("download poisoned"))
+    }
+}
+
+impl Read for ProgressiveReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let position = self.position as usize;
+        let buffer = self.wait_for(|buffer| buffer.data.len() > position)?;
+        if position >= buffer.data.len() {
+            return match &buffer.error {
+                Some(e) => Err(io::Error::other(e.clone())),
+                None => Ok(0),
+            };
+        }
+        let len = buf.len().min(buffer.data.len() - position);
+        buf[..len].copy_from_slice(&buffer.data[position..position + len]);
+        drop(buffer);
+        self.position += len as u64;
+        Ok(len)
+    }
+}
+
+impl Seek for ProgressiveReader {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let target = match pos {
+            SeekFrom::Start(offset) => offset as i64,
+            SeekFrom::Current(offset) => self.position as i64 + offset,
+            SeekFrom::End(offset) => {
+                // 总长度未知时只能等下载完成
+                let buffer = self.wait_for(|buffer| buffer.total_len.is_some())?;
+                buffer.total_len.unwrap_or(buffer.data.len() as u64) as i64 + offset
+            }
+        };
+        if target < 0 {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "seek before start"));
+        }
+        self.position = target as u64;
+        Ok(self.position)
+    }
+}
+
+impl Drop for ProgressiveReader {
+    fn drop(&mut self) {
+        self.shared.cancelled.store(true, Ordering::Relaxed);
+    }
+}
+
+/// 在后台下载 response 的内容到共享缓冲区
+async fn download_into(mut response: reqwest::Response, shared: Arc<SharedDownload>) {
+    let result = loop {
+        if shared.cancelled.load(Ordering::Relaxed) {
+            break Ok(());
+        }
+        let chunk = match tokio::time::timeout(Duration::from_secs(STREAM_TIMEOUT_SECS), response.chunk()).await {
+            Ok(Ok(Some(chunk))) => chunk,
+            Ok(Ok(None)) => break Ok(()),
+            Ok(Err(e)) => break Err(e.to_string()),
+            Err(_) => break Err(i18n::tr("网络音频下载超时")),
+        };
+
+        let mut too_large = false;
+        shared.update(|buffer| {
+            buffer.data.extend_from_slice(&chunk);
+            too_large = buffer.data.len() > MAX_STREAM_BYTES;
+        });
+        if too_large {
+            break Err(i18n::tr("网络音频过大，请先导入音频库再播放"));
+        }
+    };
+
+    if let Err(e) = &result {
+        eprintln!("[Remote] 网络音频下载中断: {}", e);
+    }
+    shared.update(|buffer| {
+        buffer.finished = true;
+        buffer.error = result.err();
+    });
+}
+
+/// 解码 %E4%B8%AD 之类的转义，解码结果不是 UTF-8 时保留原样
+fn percent_decode(text: &str) -> String {
+    let mut bytes = Vec::new();
+    let mut rest = text.as_bytes();
+    while let Some((&byte, tail)) = rest.split_first() {
+        let escaped = tail
+            .get(..2)
+            .filter(|_| byte == b'%')
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match escaped {
+            Some(decoded) => {
+                bytes.push(decoded);
+                rest = &tail[2..];
+            }
+            None => {
+                bytes.push(byte);
+                rest = tail;
+            }
+        }
+    }
+    String::from_utf8(bytes).unwrap_or_else(|_| text.to_string())
+}
+
+/// 从地址中取出显示用的名称（最后一段路径，没有时用主机名）
+fn stream_name(url: &tauri::Url) -> String {
+    url.path_segments()
+        .and_then(|mut segments| segments.next_back())
+        .filter(|segment| !segment.is_empty())
+        .map(percent_decode)
+        .or_else(|| url.host_str().map(str::to_string))
+        .unwrap_or_else(|| url.to_string())
+}
+
+/// 直接播放 HTTP/HTTPS 地址上的音频（边下边播，不导入音频库），用于提取前试听在线素材
+#[tauri::command]
+pub async fn play_stream(
+    url: String,
+    player: State<'_, Arc<Mutex<AudioPlayer>>>,
+) -> Result<PlaybackState, String> {
+    let parsed = tauri::Url::parse(url.trim()).map_err(|_| i18n::tr("无效的网络地址"))?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return Err(i18n::tr("只支持 HTTP/HTTPS 地址"));
+    }
+    let name = stream_name(&parsed);
+
+    let client = reqwest::Client::builder()
+        .connect_timeout(Duration::from_secs(STREAM_TIMEOUT_SECS))
+        .build()
+        .map_err(|e| e.to_string())?;
+    let response = client
+        .get(parsed)
+        .send()
+        .await
+        .map_err(|e| i18n::trf("连接失败: {}", &[&e]))?;
+    if !response.status().is_success() {
+        return Err(i18n::trf("网络音频请求失败: HTTP {}", &[&response.status()]));
+    }
+
+    let shared = Arc::new(SharedDownload::default());
+    shared.update(|buffer| buffer.total_len = response.content_length());
+    tauri::async_runtime::spawn(download_into(response, shared.clone()));
+
+    // 探测格式需要读取开头的数据，会阻塞到下载足够的字节
+    let reader = ProgressiveReader { shared, position: 0 };
+    let (source, duration) = tauri::async_runtime::spawn_blocking(move || {
+        Decoder::new(reader).map(|decoder| {
+            let duration = decoder.total_duration().map(|duration| duration.as_secs_f64());
+            (Box::new(decoder) as DecodedSource, duration)
+        })
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| i18n::trf("无法解码网络音频: {}", &[&e]))?;
+
+    let mut player = player.lock().await;
+    player.play_stream(source, name, duration)?;
+    Ok(player.get_state())
+}
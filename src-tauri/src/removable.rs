@@ -0,0 +1,223 @@
+use serde::Serialize;
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use tokio::time::{interval, Duration};
+use rusqlite::Connection;
+use tauri::{AppHandle, Manager, State};
+use crate::audio::{self, ScanOptions, ScanResult};
+use crate::formats;
+use crate::probe::DurationProbeQueue;
+
+// 检查移动存储插拔的间隔（秒）
+const DRIVE_CHECK_SECS: u64 = 3;
+// 统计文件夹中音频数量时最多深入的层数
+const MAX_SCAN_DEPTH: usize = 4;
+// 插入含音频的移动存储时发给前端的事件
+const DRIVE_DETECTED_EVENT: &str = "removable-drive-detected";
+
+/// 移动存储上含音频的文件夹
+#[derive(Debug, Clone, Serialize)]
+pub struct DriveFolder {
+    pub path: String,
+    pub name: String,
+    /// 文件夹中（含子文件夹）支持的音频数量；根目录只统计直接放在根目录下的文件
+    pub audio_count: usize,
+    /// 是否为存储的根目录（导入时不包含子文件夹）
+    pub is_root: bool,
+}
+
+/// 已连接的移动存储（U盘、存储卡）
+#[derive(Debug, Clone, Serialize)]
+pub struct RemovableDrive {
+    pub path: String,
+    pub name: String,
+    pub folders: Vec<DriveFolder>,
+}
+
+/// 当前挂载的移动存储的根目录
+#[cfg(target_os = "windows")]
+fn removable_roots() -> Vec<PathBuf> {
+    use std::os::windows::ffi::OsStrExt;
+    use winapi::um::fileapi::{GetDriveTypeW, GetLogicalDrives};
+    use winapi::um::winbase::DRIVE_REMOVABLE;
+
+    let mask = unsafe { GetLogicalDrives() };
+    (0..26u8)
+        .filter(|index| mask & (1 << index) != 0)
+        .map(|index| format!("{}:\\", (b'A' + index) as char))
+        .filter(|root| {
+            let wide: Vec<u16> = std::ffi::OsStr::new(root).encode_wide().chain(Some(0)).collect();
+            unsafe { GetDriveTypeW(wide.as_ptr()) == DRIVE_REMOVABLE }
+        })
+        .map(PathBuf::from)
+        .collect()
+}
+
+/// 当前挂载的移动存储的根目录（/Volumes 下除系统盘以外的卷）
+#[cfg(target_os = "macos")]
+fn removable_roots() -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir("/Volumes") else { return Vec::new() };
+    entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.canonicalize().is_ok_and(|real| real != Path::new("/")))
+        .collect()
+}
+
+/// 当前挂载的移动存储的根目录（自动挂载到 /media 或 /run/media 下的分区）
+#[cfg(target_os = "linux")]
+fn removable_roots() -> Vec<PathBuf> {
+    let Ok(mounts) = fs::read_to_string("/proc/mounts") else { return Vec::new() };
+    mounts
+        .lines()
+        .filter_map(|line| line.split_whitespace().nth(1))
+        // /proc/mounts 中空格等字符转义为八进制
+        .map(|mount_point| mount_point.replace("\\040", " ").replace("\\011", "\t"))
+        .filter(|mount_point| mount_point.starts_with("/media/") || mount_point.starts_with("/run/media/"))
+        .map(PathBuf::from)
+        .collect()
+}
+
+fn is_hidden(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| name.starts_with('.') || name.starts_with('$') || name == "System Volume Information")
+}
+
+/// 统计目录中支持的音频数量，recursive 时包含子文件夹（最多 depth 层）
+fn count_audio_files(dir: &Path, recursive: bool, depth: usize) -> usize {
+    let Ok(entries) = fs::read_dir(dir) else { return 0 };
+    entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .map(|path| {
+            if path.is_file() {
+                usize::from(formats::is_supported_path(&path))
+            } else if recursive && depth > 0 && path.is_dir() && !is_hidden(&path) {
+                count_audio_files(&path, true, depth - 1)
+            } else {
+                0
+            }
+        })
+        .sum()
+}
+
+/// 读取移动存储上含音频的文件夹：根目录下的音频和各个一级文件夹
+fn scan_drive(root: &Path) -> RemovableDrive {
+    let name = root
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| root.to_string_lossy().trim_end_matches(['\\', '/']).to_string());
+
+    let mut folders = Vec::new();
+    let root_count = count_audio_files(root, false, 0);
+    if root_count > 0 {
+        folders.push(DriveFolder {
+            path: root.to_string_lossy().to_string(),
+            name: name.clone(),
+            audio_count: root_count,
+            is_root: true,
+        });
+    }
+
+    let mut subdirs: Vec<PathBuf> = fs::read_dir(root)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                .filter(|path| path.is_dir() && !is_hidden(path))
+                .collect()
+        })
+        .unwrap_or_default();
+    subdirs.sort();
+    for dir in subdirs {
+        let audio_count = count_audio_files(&dir, true, MAX_SCAN_DEPTH);
+        if audio_count > 0 {
+            folders.push(DriveFolder {
+                path: dir.to_string_lossy().to_string(),
+                name: dir.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default(),
+                audio_count,
+                is_root: false,
+            });
+        }
+    }
+
+    RemovableDrive { path: root.to_string_lossy().to_string(), name, folders }
+}
+
+/// 启动移动存储监视器：插入含音频文件夹的U盘或存储卡时发出 removable-drive-detected 事件，
+/// 由前端询问是否导入（启动时已连接的存储不提示）
+pub fn start_drive_monitor(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut ticker = interval(Duration::from_secs(DRIVE_CHECK_SECS));
+        let mut known: Option<HashSet<PathBuf>> = None;
+
+        loop {
+            ticker.tick().await;
+
+            let roots: HashSet<PathBuf> = removable_roots().into_iter().collect();
+            let inserted: Vec<PathBuf> = match &known {
+                Some(known) => roots.difference(known).cloned().collect(),
+                None => Vec::new(),
+            };
+            known = Some(roots);
+
+            for root in inserted {
+                let drive = match tauri::async_runtime::spawn_blocking(move || scan_drive(&root)).await {
+                    Ok(drive) => drive,
+                    Err(e) => {
+                        eprintln!("[Removable] 读取移动存储失败: {}", e);
+                        continue;
+                    }
+                };
+                if !drive.folders.is_empty() {
+                    println!("[Removable] 检测到移动存储: {} ({} 个文件夹含音频)", drive.name, drive.folders.len());
+                    let _ = app.emit_all(DRIVE_DETECTED_EVENT, drive);
+                }
+            }
+        }
+    });
+}
+
+/// 当前连接的移动存储及其中含音频的文件夹
+#[tauri::command]
+pub async fn get_removable_drives() -> Result<Vec<RemovableDrive>, String> {
+    tauri::async_runtime::spawn_blocking(|| removable_roots().iter().map(|root| scan_drive(root)).collect())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// 把移动存储上选中的文件夹复制进音频库（拔出后仍可播放），folder_playlists 时按文件夹生成播放列表
+#[tauri::command]
+pub async fn import_drive_folders(
+    folders: Vec<String>,
+    folder_playlists: Option<bool>,
+    app: AppHandle,
+    conn: State<'_, Arc<Mutex<Connection>>>,
+    audio_dir: State<'_, PathBuf>,
+    probe_queue: State<'_, DurationProbeQueue>,
+) -> Result<ScanResult, String> {
+    let mut total = ScanResult::default();
+    let roots = removable_roots();
+
+    for folder in folders {
+        let path = PathBuf::from(&folder);
+        let is_root = roots.iter().any(|root| root == &path);
+        // 一级文件夹以存储根目录为基准生成播放列表，播放列表名即文件夹名
+        let playlist_root = path.parent().filter(|_| !is_root).unwrap_or(&path).to_path_buf();
+        let options = ScanOptions {
+            copy_into_library: true,
+            recursive: !is_root,
+            folder_playlists: folder_playlists.unwrap_or(false),
+        };
+        let result = audio::import_directory(&path, &playlist_root, options, &app, &conn, &audio_dir, &probe_queue).await?;
+
+        total.found_files += result.found_files;
+        total.added_files += result.added_files;
+        total.skipped_files += result.skipped_files;
+        total.error_files += result.error_files;
+        total.playlists_created += result.playlists_created;
+    }
+
+    Ok(total)
+}
@@ -0,0 +1,213 @@
+use serde::Serialize;
+use std::collections::{BTreeMap, HashSet};
+use std::path::Path;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use rusqlite::Connection;
+use tauri::State;
+use crate::i18n;
+use crate::library_events::LibraryEvents;
+use crate::mediainfo;
+
+/// 批量重命名中一个音频的原名称和新名称
+#[derive(Debug, Clone, Serialize)]
+pub struct RenamePreview {
+    pub id: i64,
+    pub old_name: String,
+    pub new_name: String,
+}
+
+/// 生成名称时用到的单个音频信息
+struct RenameSource {
+    id: i64,
+    original_name: String,
+    file_path: String,
+    // 导入日期（本地时间 YYYYMMDD）
+    upload_date: String,
+}
+
+/// 按规则生成新名称（不含扩展名）：{n} 序号，{date} 导入日期，{original} 原名称，
+/// {tag} 内嵌标题，{tag:artist} 等其他内嵌标签；无法识别的 {…} 原样保留
+fn render_name(pattern: &str, number: &str, source: &RenameSource, stem: &str, tags: &BTreeMap<String, String>) -> String {
+    let mut name = String::new();
+    let mut rest = pattern;
+    while let Some(open) = rest.find('{') {
+        let Some(close) = rest[open..].find('}').map(|close| open + close) else { break };
+        name.push_str(&rest[..open]);
+        let token = &rest[open + 1..close];
+        match token {
+            "n" => name.push_str(number),
+            "date" => name.push_str(&source.upload_date),
+            "original" => name.push_str(stem),
+            "tag" => name.push_str(tags.get("title").map(String::as_str).unwrap_or_default()),
+            _ => match token.strip_prefix("tag:") {
+                Some(key) => name.push_str(tags.get(key.trim()).map(String::as_str).unwrap_or_default()),
+                None => name.push_str(&rest[open..=close]),
+            },
+        }
+        rest = &rest[close + 1..];
+    }
+    name.push_str(rest);
+    name.replace(['/', '\\'], "_").trim().to_string()
+}
+
+/// 按规则生成一批音频的新名称（保留扩展名）：序号从 start 开始，补零到相同位数（至少两位）；
+/// 生成的名称为空时保留原名称，同一批中重复的名称依次加上 (2)、(3)…
+fn preview_names(pattern: &str, start: i64, sources: &[RenameSource], tags: &[BTreeMap<String, String>]) -> Vec<RenamePreview> {
+    let width = (start + sources.len() as i64 - 1).max(1).to_string().len().max(2);
+    let no_tags = BTreeMap::new();
+    let mut used = HashSet::new();
+    sources
+        .iter()
+        .enumerate()
+        .map(|(index, source)| {
+            let path = Path::new(&source.original_name);
+            let stem = path.file_stem().map(|stem| stem.to_string_lossy().to_string()).unwrap_or_default();
+            let extension = path.extension().map(|ext| format!(".{}", ext.to_string_lossy())).unwrap_or_default();
+            let number = format!("{:0width$}", start + index as i64, width = width);
+
+            let rendered = render_name(pattern, &number, source, &stem, tags.get(index).unwrap_or(&no_tags));
+            let new_name = if rendered.is_empty() {
+                source.original_name.clone()
+            } else {
+                let mut new_name = format!("{}{}", rendered, extension);
+                let mut copy = 2;
+                while used.contains(&new_name.to_lowercase()) {
+                    new_name = format!("{} ({}){}", rendered, copy, extension);
+                    copy += 1;
+                }
+                new_name
+            };
+            used.insert(new_name.to_lowercase());
+            RenamePreview { id: source.id, old_name: source.original_name.clone(), new_name }
+        })
+        .collect()
+}
+
+/// 按规则批量重命名音频（只改显示名称，保留扩展名，不移动文件）。
+/// 默认只返回预览，apply 为 true 时才写入；序号按 ids 的顺序从 start（默认1）开始补零
+#[tauri::command]
+pub async fn batch_rename(
+    ids: Vec<i64>,
+    pattern: String,
+    start: Option<i64>,
+    apply: Option<bool>,
+    conn: State<'_, Arc<Mutex<Connection>>>,
+    library: State<'_, LibraryEvents>,
+) -> Result<Vec<RenamePreview>, String> {
+    if pattern.trim().is_empty() {
+        return Err(i18n::tr("请输入重命名规则"));
+    }
+
+    let sources: Vec<RenameSource> = {
+        let conn = conn.lock().await;
+        ids.iter()
+            .filter_map(|id| {
+                conn.query_row(
+                    "SELECT id, original_name, file_path, strftime('%Y%m%d', upload_date, 'localtime')
+                     FROM audio_files WHERE id = ?1",
+                    [id],
+                    |row| {
+                        Ok(RenameSource {
+                            id: row.get(0)?,
+                            original_name: row.get(1)?,
+                            file_path: row.get(2)?,
+                            upload_date: row.get::<_, Option<String>>(3)?.unwrap_or_default(),
+                        })
+                    },
+                )
+                .ok()
+            })
+            .collect()
+    };
+
+    let start = start.unwrap_or(1);
+    let read_tags = pattern.contains("{tag");
+
+    // 读取内嵌标签需要打开文件，放到后台线程
+    let previews = tauri::async_runtime::spawn_blocking(move || {
+        let tags: Vec<BTreeMap<String, String>> = match read_tags {
+            true => sources.iter().map(|source| mediainfo::read_standard_tags(Path::new(&source.file_path))).collect(),
+            false => Vec::new(),
+        };
+        preview_names(&pattern, start, &sources, &tags)
+    })
+    .await
+    .map_err(|e| e.to_string())?;
+
+    if apply.unwrap_or(false) {
+        let mut conn = conn.lock().await;
+        let tx = conn.transaction().map_err(|e| e.to_string())?;
+        let renamed: Vec<&RenamePreview> = previews.iter().filter(|preview| preview.new_name != preview.old_name).collect();
+        for preview in &renamed {
+            tx.execute(
+                "UPDATE audio_files SET original_name = ?1 WHERE id = ?2",
+                (&preview.new_name, preview.id),
+            )
+            .map_err(|e| e.to_string())?;
+        }
+        tx.commit().map_err(|e| e.to_string())?;
+        library.updated(renamed.iter().map(|preview| preview.id));
+    }
+
+    Ok(previews)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn source(id: i64, original_name: &str) -> RenameSource {
+        RenameSource {
+            id,
+            original_name: original_name.to_string(),
+            file_path: String::new(),
+            upload_date: "20240603".to_string(),
+        }
+    }
+
+    fn tags(pairs: &[(&str, &str)]) -> BTreeMap<String, String> {
+        pairs.iter().map(|(key, value)| (key.to_string(), value.to_string())).collect()
+    }
+
+    fn new_names(previews: &[RenamePreview]) -> Vec<&str> {
+        previews.iter().map(|preview| preview.new_name.as_str()).collect()
+    }
+
+    #[test]
+    fn placeholders() {
+        let audio = source(1, "unit3.mp3");
+        let tags = tags(&[("title", "Hello"), ("artist", "Super Simple")]);
+        assert_eq!(render_name("{n}-{original}", "07", &audio, "unit3", &tags), "07-unit3");
+        assert_eq!(render_name("{date} {tag}", "01", &audio, "unit3", &tags), "20240603 Hello");
+        assert_eq!(render_name("{tag:artist} - {tag: artist }", "01", &audio, "unit3", &tags), "Super Simple - Super Simple");
+        // 缺少的标签为空，无法识别和未闭合的 {…} 原样保留，路径分隔符替换为 _
+        assert_eq!(render_name("{tag:album}{x}/{n", "01", &audio, "unit3", &tags), "{x}_{n");
+        assert_eq!(render_name("  {tag:album}  ", "01", &audio, "unit3", &tags), "");
+    }
+
+    #[test]
+    fn numbering_is_zero_padded() {
+        let sources: Vec<RenameSource> = (1..=3).map(|id| source(id, &format!("{}.mp3", id))).collect();
+        assert_eq!(new_names(&preview_names("第{n}课", 1, &sources, &[])), ["第01课.mp3", "第02课.mp3", "第03课.mp3"]);
+        assert_eq!(new_names(&preview_names("{n}", 98, &sources, &[])), ["098.mp3", "099.mp3", "100.mp3"]);
+    }
+
+    #[test]
+    fn keeps_extension_or_original_name() {
+        let sources = [source(1, "a.song.flac"), source(2, "noext"), source(3, "b.mp3")];
+        let tags = [BTreeMap::new(), BTreeMap::new(), tags(&[("title", "B")])];
+        let previews = preview_names("{tag}", 1, &sources, &tags);
+        // 没有标题标签时保留原名称
+        assert_eq!(new_names(&previews), ["a.song.flac", "noext", "B.mp3"]);
+        assert_eq!(previews[2].old_name, "b.mp3");
+    }
+
+    #[test]
+    fn duplicate_names_get_a_suffix() {
+        let sources = [source(1, "1.mp3"), source(2, "2.mp3"), source(3, "3.MP3"), source(4, "4.wav")];
+        let tags = [tags(&[("title", "Song")]), tags(&[("title", "Song")]), tags(&[("title", "song")]), tags(&[("title", "Song")])];
+        let previews = preview_names("{tag}", 1, &sources, &tags);
+        assert_eq!(new_names(&previews), ["Song.mp3", "Song (2).mp3", "song (3).MP3", "Song.wav"]);
+    }
+}
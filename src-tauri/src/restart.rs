@@ -5,6 +5,7 @@ use std::thread;
 use std::time::Duration;
 use std::fs::OpenOptions;
 use std::io::Write;
+use crate::i18n;
 
 /// 写入日志文件
 fn write_log(message: &str) {
@@ -28,14 +29,14 @@ pub async fn restart_app(_app: AppHandle) -> Result<(), String> {
     let current_exe = env::current_exe()
         .map_err(|e| {
             write_log(&format!("获取当前可执行文件路径失败: {}", e));
-            format!("获取当前可执行文件路径失败: {}", e)
+            i18n::trf("获取当前可执行文件路径失败: {}", &[&e])
         })?;
 
     // 获取当前工作目录
     let current_dir = env::current_dir()
         .map_err(|e| {
             write_log(&format!("获取当前工作目录失败: {}", e));
-            format!("获取当前工作目录失败: {}", e)
+            i18n::trf("获取当前工作目录失败: {}", &[&e])
         })?;
 
     // 获取命令行参数
@@ -95,13 +96,13 @@ pub async fn restart_app(_app: AppHandle) -> Result<(), String> {
                         Err(_) => {
                             let e2 = "批处理文件执行失败";
                             write_log(e2);
-                            return Err(format!("重启失败: {} 和 {}", e, e2));
+                            return Err(i18n::trf("重启失败: {} 和 {}", &[&e, &i18n::tr(e2)]));
                         }
                     }
                 } else {
                     let e2 = "创建批处理文件失败";
                     write_log(e2);
-                    return Err(format!("重启失败: {} 和 {}", e, e2));
+                    return Err(i18n::trf("重启失败: {} 和 {}", &[&e, &i18n::tr(e2)]));
                 }
             }
         }
@@ -116,7 +117,7 @@ pub async fn restart_app(_app: AppHandle) -> Result<(), String> {
             .spawn()
             .map_err(|e| {
                 write_log(&format!("启动新进程失败: {}", e));
-                format!("启动新进程失败: {}", e)
+                i18n::trf("启动新进程失败: {}", &[&e])
             })?;
     }
 
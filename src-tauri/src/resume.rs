@@ -0,0 +1,147 @@
+use serde::Serialize;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use tokio::time::{interval, Duration};
+use rusqlite::Connection;
+use tauri::State;
+use crate::player::{self, AudioPlayer, ClipRange};
+
+// 保存播放位置的间隔（秒）
+const SAVE_INTERVAL_SECS: u64 = 5;
+// 只记住至少这么长的音频的播放位置（秒），短音频每次从头播放
+const MIN_RESUME_DURATION_SECS: f64 = 5.0 * 60.0;
+// 播放不到这么多秒时不记住位置
+const MIN_RESUME_POSITION_SECS: f64 = 10.0;
+// 离结尾不到这么多秒时视为已播完，下次从头播放
+const FINISHED_MARGIN_SECS: f64 = 10.0;
+
+/// 上次播放到的位置（秒，原文件时间），没有记录时为 None
+pub fn load_position(conn: &Connection, audio_id: i64) -> Option<f64> {
+    conn.query_row(
+        "SELECT position_secs FROM playback_positions WHERE audio_id = ?1",
+        [audio_id],
+        |row| row.get(0),
+    )
+    .ok()
+}
+
+fn save_position(conn: &Connection, audio_id: i64, position_secs: f64) -> rusqlite::Result<()> {
+    conn.execute(
+        "INSERT OR REPLACE INTO playback_positions (audio_id, position_secs, updated_at)
+         VALUES (?1, ?2, datetime('now'))",
+        (audio_id, position_secs),
+    )?;
+    Ok(())
+}
+
+fn clear_position(conn: &Connection, audio_id: i64) -> rusqlite::Result<()> {
+    conn.execute("DELETE FROM playback_positions WHERE audio_id = ?1", [audio_id])?;
+    Ok(())
+}
+
+/// 启动播放位置记录：定期保存长音频的播放位置，播完后清除
+pub fn start_position_saver(db: Arc<Mutex<Connection>>, player: Arc<Mutex<AudioPlayer>>) {
+    tauri::async_runtime::spawn(async move {
+        let mut ticker = interval(Duration::from_secs(SAVE_INTERVAL_SECS));
+        // 上次写入的音频和位置（整秒），暂停时位置不变就不再重复写入
+        let mut last_saved: Option<(i64, i64)> = None;
+
+        loop {
+            ticker.tick().await;
+
+            let snapshot = {
+                let mut player = player.lock().await;
+                player.sync_gapless();
+                player.current_audio_id().map(|audio_id| {
+                    (audio_id, player.has_pending_audio(), player.position_secs(), player.current_duration())
+                })
+            };
+            let Some((audio_id, pending, position, duration)) = snapshot else { continue };
+
+            let finished = !pending || duration.is_some_and(|d| position >= d - FINISHED_MARGIN_SECS);
+            if duration.is_some_and(|d| d < MIN_RESUME_DURATION_SECS)
+                || (!finished && position < MIN_RESUME_POSITION_SECS)
+            {
+                continue;
+            }
+
+            // 播完的记为 -1，只清除一次
+            let key = (audio_id, if finished { -1 } else { position as i64 });
+            if last_saved == Some(key) {
+                continue;
+            }
+
+            let conn = db.lock().await;
+            let result = match finished {
+                true => clear_position(&conn, audio_id),
+                false => save_position(&conn, audio_id, position),
+            };
+            match result {
+                Ok(()) => last_saved = Some(key),
+                Err(e) => eprintln!("[Resume] 保存播放位置失败: {}", e),
+            }
+        }
+    });
+}
+
+/// 启动时恢复上次未播完的曲目：加载到播放器并停在上次的位置，点播放即可继续
+pub async fn restore_last_track(db: &Arc<Mutex<Connection>>, player: &Arc<Mutex<AudioPlayer>>) {
+    let mut player = player.lock().await;
+    let loaded = {
+        let conn = db.lock().await;
+        let last: Option<(i64, f64)> = conn
+            .query_row(
+                "SELECT pp.audio_id, pp.position_secs FROM playback_positions pp
+                 JOIN audio_files af ON pp.audio_id = af.id
+                 ORDER BY pp.updated_at DESC LIMIT 1",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .ok();
+        last.and_then(|(audio_id, position)| {
+            player::load_audio(&conn, audio_id, player.speed())
+                .ok()
+                .map(|loaded| (audio_id, position, loaded))
+        })
+    };
+    let Some((audio_id, position, (file_path, audio_name, rendered_speed))) = loaded else { return };
+
+    player.set_rendered_speed(rendered_speed);
+    player.set_clip(ClipRange { start: Some(position), ..ClipRange::default() });
+    if let Err(e) = player.load_paused(&file_path, audio_id, audio_name) {
+        eprintln!("[Resume] 恢复上次播放的曲目失败: {}", e);
+    }
+}
+
+/// 音频上次播放到的位置
+#[derive(Debug, Serialize)]
+pub struct ResumePosition {
+    pub audio_id: i64,
+    pub position_secs: f64,
+}
+
+/// 所有记住了播放位置的音频（音频库用来显示“继续播放”）
+#[tauri::command]
+pub async fn get_resume_positions(
+    conn: State<'_, Arc<Mutex<Connection>>>,
+) -> Result<Vec<ResumePosition>, String> {
+    let conn = conn.lock().await;
+    let mut stmt = conn
+        .prepare("SELECT audio_id, position_secs FROM playback_positions")
+        .map_err(|e| e.to_string())?;
+    let positions = stmt
+        .query_map([], |row| Ok(ResumePosition { audio_id: row.get(0)?, position_secs: row.get(1)? }))
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+    Ok(positions)
+}
+
+/// 忘记音频的播放位置，下次从头播放
+#[tauri::command]
+pub async fn clear_resume_position(
+    audio_id: i64,
+    conn: State<'_, Arc<Mutex<Connection>>>,
+) -> Result<(), String> {
+    clear_position(&*conn.lock().await, audio_id).map_err(|e| e.to_string())
+}
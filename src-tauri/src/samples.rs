@@ -0,0 +1,257 @@
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use rusqlite::{Connection, OptionalExtension};
+use tauri::{AppHandle, Manager, State};
+use crate::download::{self, DownloadRequest};
+use crate::library_events::LibraryEvents;
+use crate::probe::DurationProbeQueue;
+use crate::i18n;
+
+// 示例内容包的下载地址，每个包一个子目录
+const SAMPLE_PACK_BASE_URL: &str = "https://github.com/leeyis/moerduo/releases/download/sample-packs";
+// 示例内容包导入的音频以此来源类型标记，source_url 为下载地址
+pub const SAMPLE_SOURCE_TYPE: &str = "sample_pack";
+// 安装进度事件
+const PROGRESS_EVENT: &str = "sample-pack-progress";
+
+struct SampleTrack {
+    title: &'static str,
+    file: &'static str,
+}
+
+struct SamplePlaylist {
+    name: &'static str,
+    tracks: &'static [SampleTrack],
+}
+
+struct SamplePack {
+    id: &'static str,
+    name: &'static str,
+    description: &'static str,
+    playlists: &'static [SamplePlaylist],
+}
+
+/// 精选的免费示例内容（均为公版作品）
+const SAMPLE_PACKS: &[SamplePack] = &[
+    SamplePack {
+        id: "nursery_rhymes",
+        name: "经典儿歌",
+        description: "中英文经典儿歌，适合睡前和起床时播放",
+        playlists: &[
+            SamplePlaylist {
+                name: "中文儿歌",
+                tracks: &[
+                    SampleTrack { title: "两只老虎", file: "liang-zhi-lao-hu.mp3" },
+                    SampleTrack { title: "小星星", file: "xiao-xing-xing.mp3" },
+                    SampleTrack { title: "找朋友", file: "zhao-peng-you.mp3" },
+                    SampleTrack { title: "数鸭子", file: "shu-ya-zi.mp3" },
+                ],
+            },
+            SamplePlaylist {
+                name: "英文儿歌",
+                tracks: &[
+                    SampleTrack { title: "Twinkle Twinkle Little Star", file: "twinkle-twinkle-little-star.mp3" },
+                    SampleTrack { title: "The ABC Song", file: "abc-song.mp3" },
+                    SampleTrack { title: "Old MacDonald Had a Farm", file: "old-macdonald.mp3" },
+                    SampleTrack { title: "Row, Row, Row Your Boat", file: "row-your-boat.mp3" },
+                ],
+            },
+        ],
+    },
+    SamplePack {
+        id: "classic_poems",
+        name: "古诗诵读",
+        description: "小学必背古诗朗读，适合晨读磨耳朵",
+        playlists: &[SamplePlaylist {
+            name: "古诗诵读",
+            tracks: &[
+                SampleTrack { title: "静夜思", file: "jing-ye-si.mp3" },
+                SampleTrack { title: "春晓", file: "chun-xiao.mp3" },
+                SampleTrack { title: "咏鹅", file: "yong-e.mp3" },
+                SampleTrack { title: "登鹳雀楼", file: "deng-guan-que-lou.mp3" },
+                SampleTrack { title: "悯农", file: "min-nong.mp3" },
+            ],
+        }],
+    },
+];
+
+/// 示例内容包信息
+#[derive(Debug, Serialize)]
+pub struct SamplePackInfo {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    pub track_count: usize,
+    pub playlist_names: Vec<String>,
+    /// 已导入音频库的曲目数
+    pub installed_count: usize,
+}
+
+/// 安装结果
+#[derive(Debug, Serialize)]
+pub struct SamplePackInstallResult {
+    pub pack_id: String,
+    /// 本次新下载导入的曲目数
+    pub imported: usize,
+    /// 已在音频库中、直接复用的曲目数
+    pub reused: usize,
+    pub playlist_ids: Vec<i64>,
+}
+
+fn track_url(pack: &SamplePack, track: &SampleTrack) -> String {
+    format!("{}/{}/{}", SAMPLE_PACK_BASE_URL, pack.id, track.file)
+}
+
+/// 已导入且文件仍存在的示例音频ID
+fn find_installed(conn: &Connection, url: &str) -> Option<i64> {
+    conn.query_row(
+        "SELECT id, file_path FROM audio_files WHERE source_type = ?1 AND source_url = ?2",
+        (SAMPLE_SOURCE_TYPE, url),
+        |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?)),
+    )
+    .optional()
+    .ok()
+    .flatten()
+    .filter(|(_, file_path)| Path::new(file_path).exists())
+    .map(|(id, _)| id)
+}
+
+/// 按名称查找未删除的播放列表，不存在时创建
+fn ensure_playlist(conn: &Connection, name: &str) -> rusqlite::Result<i64> {
+    let existing: Option<i64> = conn
+        .query_row(
+            "SELECT id FROM playlists WHERE name = ?1 AND deleted_at IS NULL ORDER BY id LIMIT 1",
+            [name],
+            |row| row.get(0),
+        )
+        .optional()?;
+    match existing {
+        Some(id) => Ok(id),
+        None => {
+            conn.execute("INSERT INTO playlists (name) VALUES (?1)", [name])?;
+            Ok(conn.last_insert_rowid())
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn get_sample_packs(
+    conn: State<'_, Arc<Mutex<Connection>>>,
+) -> Result<Vec<SamplePackInfo>, String> {
+    let conn = conn.lock().await;
+    Ok(SAMPLE_PACKS
+        .iter()
+        .map(|pack| {
+            let tracks: Vec<&SampleTrack> = pack.playlists.iter().flat_map(|p| p.tracks.iter()).collect();
+            SamplePackInfo {
+                id: pack.id.to_string(),
+                name: pack.name.to_string(),
+                description: pack.description.to_string(),
+                track_count: tracks.len(),
+                playlist_names: pack.playlists.iter().map(|p| p.name.to_string()).collect(),
+                installed_count: tracks
+                    .iter()
+                    .filter(|track| find_installed(&conn, &track_url(pack, track)).is_some())
+                    .count(),
+            }
+        })
+        .collect())
+}
+
+/// 下载并导入示例内容包，按包内分组建好播放列表（已导入的曲目直接复用，可重复执行以补全）
+#[tauri::command]
+pub async fn install_sample_pack(
+    pack_id: String,
+    app: AppHandle,
+    conn: State<'_, Arc<Mutex<Connection>>>,
+    audio_dir: State<'_, PathBuf>,
+    probe_queue: State<'_, DurationProbeQueue>,
+) -> Result<SamplePackInstallResult, String> {
+    let pack = SAMPLE_PACKS
+        .iter()
+        .find(|pack| pack.id == pack_id)
+        .ok_or_else(|| i18n::trf("示例内容包不存在: {}", &[&pack_id]))?;
+
+    let total = pack.playlists.iter().map(|p| p.tracks.len()).sum::<usize>().max(1);
+    let mut done = 0usize;
+    let mut imported = 0usize;
+    let mut reused = 0usize;
+    let mut playlist_ids = Vec::new();
+
+    for playlist in pack.playlists {
+        let mut audio_ids = Vec::new();
+
+        for track in playlist.tracks {
+            let url = track_url(pack, track);
+            let progress_range = ((done * 100 / total) as u8, ((done + 1) * 100 / total) as u8);
+            done += 1;
+
+            let installed = find_installed(&*conn.lock().await, &url);
+            if let Some(audio_id) = installed {
+                audio_ids.push(audio_id);
+                reused += 1;
+                continue;
+            }
+
+            let layout = crate::storage::load_layout(&*conn.lock().await);
+            let (filename, dest_path) =
+                crate::storage::managed_file_path(&layout, &audio_dir, track.title, "mp3", Some(SAMPLE_SOURCE_TYPE))?;
+            download::download_file(&app, &DownloadRequest {
+                url: &url,
+                dest: &dest_path,
+                progress_event: PROGRESS_EVENT,
+                progress_range,
+                expected_sha256: None,
+            })
+            .await
+            .map_err(|e| i18n::trf("下载“{}”失败: {}", &[&track.title, &e]))?;
+
+            let file_size = std::fs::metadata(&dest_path).map_err(|e| e.to_string())?.len() as i64;
+            let conn = conn.lock().await;
+            conn.execute(
+                "INSERT INTO audio_files (filename, original_name, file_path, file_size, duration, format, source_type, source_url)
+                 VALUES (?1, ?2, ?3, ?4, NULL, 'mp3', ?5, ?6)",
+                (
+                    &filename,
+                    format!("{}.mp3", track.title),
+                    dest_path.to_str().unwrap(),
+                    file_size,
+                    SAMPLE_SOURCE_TYPE,
+                    &url,
+                ),
+            )
+            .map_err(|e| e.to_string())?;
+            let audio_id = conn.last_insert_rowid();
+            app.state::<LibraryEvents>().added([audio_id]);
+            audio_ids.push(audio_id);
+            imported += 1;
+        }
+
+        // 建立播放列表，已在列表中的曲目不重复添加
+        let conn = conn.lock().await;
+        let playlist_id = ensure_playlist(&conn, playlist.name).map_err(|e| e.to_string())?;
+        for audio_id in audio_ids {
+            let exists: bool = conn
+                .query_row(
+                    "SELECT EXISTS(SELECT 1 FROM playlist_items WHERE playlist_id = ?1 AND audio_id = ?2)",
+                    (playlist_id, audio_id),
+                    |row| row.get(0),
+                )
+                .map_err(|e| e.to_string())?;
+            if !exists {
+                crate::playlist::append_playlist_item(&conn, playlist_id, audio_id).map_err(|e| e.to_string())?;
+            }
+        }
+        playlist_ids.push(playlist_id);
+    }
+
+    probe_queue.wake();
+    Ok(SamplePackInstallResult {
+        pack_id: pack.id.to_string(),
+        imported,
+        reused,
+        playlist_ids,
+    })
+}
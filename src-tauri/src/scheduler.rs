@@ -1,32 +1,507 @@
+use serde::Serialize;
 use std::sync::Arc;
-use tokio::sync::Mutex;
-use tokio::time::{sleep, Duration, interval};
-use rusqlite::Connection;
-use chrono::{Local, Timelike, Datelike};
-use crate::player::AudioPlayer;
+use std::collections::{HashMap, HashSet, VecDeque};
+use tokio::sync::{Mutex, Notify};
+use tokio::time::{sleep, sleep_until, Duration, Instant, interval};
+use rusqlite::{Connection, OptionalExtension};
+use chrono::{Local, NaiveDate, NaiveDateTime, Timelike};
+use crate::cron::CronSchedule;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use crate::player::{self, shuffle_tracks, AudioPlayer, ClipRange, PlayMode};
+use crate::handoff;
+use crate::holiday;
+use crate::kiosk;
+use crate::pause;
+use crate::playlist;
+use crate::quota;
+use crate::service;
+use crate::task::{self, VolumePoint};
+use crate::trace::{Decision, Tracer};
+use crate::weekday;
+use crate::i18n;
+
+// 音量曲线的调整间隔（秒）
+const VOLUME_CURVE_UPDATE_SECS: u64 = 5;
+// 等待曲目播完时检查播放器的间隔（毫秒）
+const TRACK_END_POLL_MILLIS: u64 = 200;
+// 最长的睡眠时间（秒）：系统睡眠唤醒、修改系统时间或其他进程修改任务后，最迟这么久按系统时间重新计算下一次检查的时刻
+const MAX_SLEEP_SECS: u64 = 60;
+// 触发时刻过后这么多秒内仍会执行（系统繁忙、睡眠唤醒或上一个任务刚播完时不至于错过）
+const FIRE_GRACE_SECS: i64 = 120;
+// 周末补播在周六的这段时间内执行（小时，含开始不含结束）
+const MAKEUP_START_HOUR: i64 = 9;
+const MAKEUP_END_HOUR: i64 = 12;
+
+/// 任务增删改时唤醒调度器（播放任务和停止、降低音量任务两个循环），立即检查并重新计算下一次触发时间
+#[derive(Clone, Default)]
+pub struct SchedulerWake {
+    play: Arc<Notify>,
+    silence: Arc<Notify>,
+}
+
+impl SchedulerWake {
+    pub fn notify(&self) {
+        self.play.notify_one();
+        self.silence.notify_one();
+    }
+}
+
+/// 下一次需要检查任务的时刻：最近一个播放任务（play_tasks 为 false 时为停止、降低音量任务）的触发时刻；
+/// 有触发时刻已到却还没处理的任务（暂停中跳过的非重要任务、后台服务运行中）时，睡眠一轮后再检查，
+/// 恢复后仍能在宽限时间内执行
+fn next_check_time(conn: &Connection, play_tasks: bool, handled: &HashMap<i64, NaiveDateTime>) -> Option<NaiveDateTime> {
+    let tasks = load_tasks(conn).unwrap_or_else(|e| {
+        eprintln!("[Scheduler] 查询任务失败: {}", e);
+        Vec::new()
+    });
+    let now = Local::now().naive_local();
+    tasks
+        .iter()
+        .filter(|task| task.is_play() == play_tasks)
+        .filter_map(|task| {
+            let unhandled = task
+                .last_slot(now)
+                .filter(|slot| (now - *slot).num_seconds() < FIRE_GRACE_SECS && handled.get(&task.id) != Some(slot));
+            match unhandled {
+                Some(_) => Some(now + chrono::Duration::seconds(MAX_SLEEP_SECS as i64)),
+                None => task.next_occurrence(now),
+            }
+        })
+        .min()
+}
+
+/// 在后台依次执行到期的播放任务：调度循环把任务放进队列后继续计时，不等待播放结束；
+/// 重要任务排在非重要任务之前，并中断正在播放的非重要任务
+#[derive(Clone, Default)]
+struct PlayQueue {
+    // 等待执行的任务，以及是否为周末补播
+    pending: Arc<Mutex<VecDeque<(TaskRow, bool)>>>,
+    ready: Arc<Notify>,
+    current: Arc<Mutex<Option<RunningTask>>>,
+}
+
+/// 执行队列中正在执行的任务
+struct RunningTask {
+    critical: bool,
+    // 设置后任务停止播放并结束
+    interrupt: Arc<AtomicBool>,
+}
+
+impl PlayQueue {
+    /// 加入队列，makeup 表示周末补播
+    async fn push(&self, task: TaskRow, makeup: bool) {
+        let critical = task.critical;
+        {
+            let mut pending = self.pending.lock().await;
+            let position = match critical {
+                true => pending.iter().take_while(|(queued, _)| queued.critical).count(),
+                false => pending.len(),
+            };
+            pending.insert(position, (task, makeup));
+        }
+        if critical {
+            if let Some(running) = self.current.lock().await.as_ref().filter(|running| !running.critical) {
+                running.interrupt.store(true, Ordering::Relaxed);
+            }
+        }
+        self.ready.notify_one();
+    }
+
+    /// 启动执行队列中任务的后台循环
+    fn spawn_worker(&self, db: Arc<Mutex<Connection>>, player: Arc<Mutex<AudioPlayer>>) {
+        let queue = self.clone();
+        tokio::spawn(async move {
+            loop {
+                let next = queue.pending.lock().await.pop_front();
+                let Some((task, makeup)) = next else {
+                    queue.ready.notified().await;
+                    continue;
+                };
+
+                let interrupt = Arc::new(AtomicBool::new(false));
+                *queue.current.lock().await = Some(RunningTask { critical: task.critical, interrupt: interrupt.clone() });
+                let tracer = Tracer::load(&*db.lock().await);
+                Scheduler::execute_task(db.clone(), player.clone(), &task, makeup, &interrupt, &tracer).await;
+                *queue.current.lock().await = None;
+            }
+        });
+    }
+}
+
+/// 立即执行一个启用的任务（托盘等手动触发，不受触发时间、重复日期和暂停限制），按今天的星期选择播放列表，
+/// 播放在后台进行
+pub async fn run_task_now(db: Arc<Mutex<Connection>>, player: Arc<Mutex<AudioPlayer>>, task_id: i64) -> Result<(), String> {
+    let (mut task, tracer) = {
+        let conn = db.lock().await;
+        let task = load_tasks(&conn)
+            .map_err(|e| e.to_string())?
+            .into_iter()
+            .find(|task| task.id == task_id)
+            .ok_or_else(|| i18n::tr("任务不存在"))?;
+        (task, Tracer::load(&conn))
+    };
+    task.playlist_id = weekday::playlist_for_weekday(
+        task.weekday_playlists.as_deref(),
+        weekday::weekday_index(&Local::now()),
+        task.playlist_id,
+    );
+
+    println!("✅ [Scheduler] 手动执行任务: {} (ID: {})", task.name, task.id);
+    tracer.record(&*db.lock().await, task.id, &task.name, Decision::Executed, &i18n::tr("手动执行"));
+    tokio::spawn(async move {
+        match task.is_play() {
+            true => Scheduler::execute_task(db, player, &task, false, &AtomicBool::new(false), &tracer).await,
+            false => Scheduler::execute_silence_task(db, player, &task, &tracer).await,
+        }
+    });
+    Ok(())
+}
+
+/// 周末补播：任务本周一到周五有应执行却没有执行记录的日子（未开机）时，返回补播用的播放列表
+/// （第一个错过的日子对应的播放列表）；周六本身要执行的任务、单次任务、指定日期任务、cron 任务和今天已补播过的任务不补播
+fn makeup_playlist(conn: &Connection, task: &TaskRow, today: NaiveDate) -> Option<i64> {
+    if !task.weekend_makeup
+        || matches!(task.repeat_mode.as_str(), "once" | "date" | "cron")
+        || weekday::runs_on_weekday(&task.repeat_mode, task.custom_days.as_deref(), 6)
+    {
+        return None;
+    }
+
+    let executed_on = |date: &str, makeup: bool| -> bool {
+        conn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM execution_history
+                           WHERE task_id = ?1 AND makeup = ?2 AND DATE(execution_time, 'localtime') = ?3)",
+            (task.id, makeup, date),
+            |row| row.get(0),
+        )
+        .unwrap_or(true)
+    };
+    if executed_on(&today.format("%Y-%m-%d").to_string(), true) {
+        return None;
+    }
+
+    (1..=5).rev().find_map(|offset| {
+        let day = today - chrono::Duration::days(offset);
+        let weekday = weekday::weekday_index(&day);
+        let scheduled = format!("{} {:02}:{:02}:00", day.format("%Y-%m-%d"), task.hour, task.minute);
+        let missed = task.runs_on(day)
+            && task.created_at < scheduled
+            && !executed_on(&day.format("%Y-%m-%d").to_string(), false);
+        missed.then(|| weekday::playlist_for_weekday(task.weekday_playlists.as_deref(), weekday, task.playlist_id))
+    })
+}
+
+/// 调度器读取的任务配置
+#[derive(Clone)]
+struct TaskRow {
+    id: i64,
+    name: String,
+    hour: i64,
+    minute: i64,
+    repeat_mode: String,
+    custom_days: Option<String>,
+    // 有效日期范围（YYYY-MM-DD，含两端）
+    start_date: Option<String>,
+    end_date: Option<String>,
+    playlist_id: i64,
+    volume: i64,
+    fade_in_duration: i64,
+    duration_minutes: Option<i64>,
+    // 达到时长限制时的渐弱时长（秒）
+    fade_out_duration: i64,
+    volume_curve: Option<String>,
+    min_plays_per_track: Option<i64>,
+    play_order: Option<String>,
+    weekday_playlists: Option<String>,
+    weekend_makeup: bool,
+    // 任务创建时间（本地时间 YYYY-MM-DD HH:MM:SS），创建之前的日子不算错过
+    created_at: String,
+    // 重要任务：暂停期间照常执行，播放时取消静音
+    critical: bool,
+    // 是否有过执行记录（单次任务执行过后不再触发）
+    has_run: bool,
+    // 任务类型：play / stop / lower_volume
+    action: String,
+    // 节假日不执行
+    skip_holidays: bool,
+    // 节假日日历（所有任务共用）
+    holidays: Arc<HashSet<NaiveDate>>,
+    // repeat_mode 为 cron 时解析的表达式（表达式无效时为 None，任务不会触发）
+    cron: Option<CronSchedule>,
+}
+
+impl TaskRow {
+    /// 播放播放列表的任务（其余为停止、降低音量任务）
+    fn is_play(&self) -> bool {
+        self.action == "play"
+    }
+
+    /// 指定日期是否因节假日跳过
+    fn skips_holiday(&self, date: NaiveDate) -> bool {
+        self.skip_holidays && self.holidays.contains(&date)
+    }
+
+    /// 指定日期是否执行（按重复日期、有效日期范围和节假日）
+    fn runs_on(&self, date: NaiveDate) -> bool {
+        weekday::runs_on_date(
+            &self.repeat_mode,
+            self.custom_days.as_deref(),
+            self.start_date.as_deref(),
+            self.end_date.as_deref(),
+            date,
+        ) && !self.skips_holiday(date)
+    }
+
+    /// 不晚于 now 的最近一个触发时刻（今天或昨天的 hour:minute，不考虑重复日期；
+    /// cron 任务为宽限时间内最近一个匹配的分钟）
+    fn last_slot(&self, now: NaiveDateTime) -> Option<NaiveDateTime> {
+        if let Some(cron) = &self.cron {
+            let minute = now.with_second(0)?.with_nanosecond(0)?;
+            return (0..=FIRE_GRACE_SECS / 60)
+                .map(|offset| minute - chrono::Duration::minutes(offset))
+                .find(|time| cron.matches(*time));
+        }
+        (0..=1).find_map(|offset| {
+            let date = now.date() - chrono::Duration::days(offset);
+            date.and_hms_opt(self.hour as u32, self.minute as u32, 0).filter(|time| *time <= now)
+        })
+    }
+
+    /// 晚于 now 的下一次触发时刻（按重复日期，有效日期范围尚未开始时从开始日期算起），
+    /// 一周内（加上跳过的节假日）不会触发、有效日期已过或单次任务已执行过时为 None
+    fn next_occurrence(&self, now: NaiveDateTime) -> Option<NaiveDateTime> {
+        if self.repeat_mode == "once" && self.has_run {
+            return None;
+        }
+        let first_date = self
+            .start_date
+            .as_deref()
+            .and_then(|start| NaiveDate::parse_from_str(start, "%Y-%m-%d").ok())
+            .map_or(now.date(), |start| start.max(now.date()));
+        if let Some(cron) = &self.cron {
+            let after = first_date.and_hms_opt(0, 0, 0)?.max(now);
+            return cron.next_after(after - chrono::Duration::seconds(1), |date| self.runs_on(date)).filter(|time| *time > now);
+        }
+        let holiday_days = if self.skip_holidays { self.holidays.len() as i64 } else { 0 };
+        (0..=7 + holiday_days).find_map(|offset| {
+            let date = first_date + chrono::Duration::days(offset);
+            let time = date.and_hms_opt(self.hour as u32, self.minute as u32, 0)?;
+            (time > now && self.runs_on(date)).then_some(time)
+        })
+    }
+}
+
+/// 所有启用的任务（同一时刻重要任务先执行，其次按优先级）
+fn load_tasks(conn: &Connection) -> rusqlite::Result<Vec<TaskRow>> {
+    let holidays = Arc::new(holiday::load_holidays(conn));
+    conn.prepare(
+        "SELECT id, name, hour, minute, repeat_mode, custom_days, playlist_id,
+                volume, fade_in_duration, duration_minutes, volume_curve,
+                min_plays_per_track, play_order, weekday_playlists, weekend_makeup,
+                datetime(created_date, 'localtime'), critical,
+                EXISTS(SELECT 1 FROM execution_history eh WHERE eh.task_id = st.id), fade_out_duration, action,
+                start_date, end_date, skip_holidays
+         FROM scheduled_tasks st
+         WHERE is_enabled = 1 AND deleted_at IS NULL
+           AND (action != 'play' OR playlist_id IN (SELECT id FROM playlists WHERE deleted_at IS NULL))
+         ORDER BY critical DESC, priority DESC, hour, minute",
+    )?
+    .query_map([], |row| {
+        let repeat_mode: String = row.get(4)?;
+        let custom_days: Option<String> = row.get(5)?;
+        let cron = match (repeat_mode.as_str(), custom_days.as_deref()) {
+            ("cron", Some(expression)) => CronSchedule::parse(expression).ok(),
+            _ => None,
+        };
+        Ok(TaskRow {
+            id: row.get(0)?,
+            name: row.get(1)?,
+            hour: row.get(2)?,
+            minute: row.get(3)?,
+            repeat_mode,
+            custom_days,
+            playlist_id: row.get(6)?,
+            volume: row.get(7)?,
+            fade_in_duration: row.get(8)?,
+            duration_minutes: row.get(9)?,
+            volume_curve: row.get(10)?,
+            min_plays_per_track: row.get(11)?,
+            play_order: row.get(12)?,
+            weekday_playlists: row.get(13)?,
+            weekend_makeup: row.get(14)?,
+            created_at: row.get(15)?,
+            critical: row.get(16)?,
+            has_run: row.get(17)?,
+            fade_out_duration: row.get(18)?,
+            action: row.get(19)?,
+            start_date: row.get(20)?,
+            end_date: row.get(21)?,
+            skip_holidays: row.get(22)?,
+            holidays: holidays.clone(),
+            cron,
+        })
+    })?
+    .collect()
+}
+
+/// 下一个将要触发的任务（界面顶部和托盘提示显示）
+#[derive(Debug, Clone, Serialize)]
+pub struct NextTask {
+    pub task_id: i64,
+    pub task_name: String,
+    /// 任务类型：play / stop / lower_volume
+    pub action: String,
+    /// 触发当天按星期对应的播放列表
+    pub playlist_id: i64,
+    /// 停止、降低音量任务不播放，为 None
+    pub playlist_name: Option<String>,
+    /// 触发时间（本地时间 YYYY-MM-DD HH:MM:SS）
+    pub trigger_time: String,
+    pub countdown_secs: i64,
+    /// 全局暂停期间非重要任务不会执行
+    pub paused: bool,
+}
+
+/// 所有启用任务中最早的下一次触发（按重复日期），同一时刻按执行顺序取第一个
+pub fn next_task(conn: &Connection) -> rusqlite::Result<Option<NextTask>> {
+    let now = Local::now().naive_local();
+    let next = load_tasks(conn)?
+        .into_iter()
+        .filter_map(|task| task.next_occurrence(now).map(|time| (time, task)))
+        .fold(None::<(NaiveDateTime, TaskRow)>, |earliest, (time, task)| match earliest {
+            Some((earliest_time, _)) if earliest_time <= time => earliest,
+            _ => Some((time, task)),
+        });
+    let Some((time, task)) = next else {
+        return Ok(None);
+    };
+
+    let playlist_id = weekday::playlist_for_weekday(
+        task.weekday_playlists.as_deref(),
+        weekday::weekday_index(&time.date()),
+        task.playlist_id,
+    );
+    let playlist_name = match task.is_play() {
+        true => conn
+            .query_row(
+                "SELECT name FROM playlists WHERE id = ?1 AND deleted_at IS NULL",
+                [playlist_id],
+                |row| row.get(0),
+            )
+            .optional()?,
+        false => None,
+    };
+    let paused = pause::load_pause_state(conn).paused && !task.critical;
+
+    Ok(Some(NextTask {
+        task_id: task.id,
+        task_name: task.name,
+        action: task.action,
+        playlist_id,
+        playlist_name,
+        trigger_time: time.format("%Y-%m-%d %H:%M:%S").to_string(),
+        countdown_secs: (time - now).num_seconds(),
+        paused,
+    }))
+}
+
+/// 除 task_id 以外，是否有会执行的播放任务（暂停期间只算重要任务）在 time 前后 window_secs 秒内触发
+fn play_task_triggers_near(tasks: &[TaskRow], task_id: i64, time: NaiveDateTime, window_secs: i64, paused: bool) -> bool {
+    let window = chrono::Duration::seconds(window_secs);
+    tasks
+        .iter()
+        .filter(|task| task.id != task_id && task.is_play() && (!paused || task.critical))
+        .filter_map(|task| task.next_occurrence(time - window - chrono::Duration::seconds(1)))
+        .any(|trigger| trigger <= time + window)
+}
+
+/// 是否有另一个播放任务紧接在 task_id 的结束时刻 end 前后 window_secs 秒内开始（用于任务交接）
+pub fn has_play_task_near(conn: &Connection, task_id: i64, end: NaiveDateTime, window_secs: i64) -> rusqlite::Result<bool> {
+    let paused = pause::load_pause_state(conn).paused;
+    Ok(play_task_triggers_near(&load_tasks(conn)?, task_id, end, window_secs, paused))
+}
+
+/// 离开作用域时自动结束的后台任务
+struct AbortOnDrop(tokio::task::JoinHandle<()>);
+
+impl Drop for AbortOnDrop {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
 
 pub struct Scheduler {
     db: Arc<Mutex<Connection>>,
     player: Arc<Mutex<AudioPlayer>>,
+    // 设置后，若该数据目录下的后台服务正在运行，则由服务负责执行任务
+    service_app_dir: Option<PathBuf>,
+    wake: SchedulerWake,
 }
 
 impl Scheduler {
     pub fn new(db: Arc<Mutex<Connection>>, player: Arc<Mutex<AudioPlayer>>) -> Self {
-        Self { db, player }
+        Self { db, player, service_app_dir: None, wake: SchedulerWake::default() }
+    }
+
+    /// 用于在任务变化时唤醒调度器
+    pub fn waker(&self) -> SchedulerWake {
+        self.wake.clone()
     }
 
+    /// 界面进程使用：后台服务运行时让出任务执行，避免重复播放
+    pub fn defer_to_service(mut self, app_dir: PathBuf) -> Self {
+        self.service_app_dir = Some(app_dir);
+        self
+    }
+
+    /// 启动调度循环：播放任务和停止、降低音量任务各有一个循环，在任务的触发时刻检查；
+    /// 播放任务交给执行队列在后台播放，播放期间也能按时触发其他任务
     pub async fn start(&self) {
+        let queue = PlayQueue::default();
+        queue.spawn_worker(self.db.clone(), self.player.clone());
+        self.spawn_loop(true, queue.clone());
+        self.spawn_loop(false, queue);
+    }
+
+    fn spawn_loop(&self, play_tasks: bool, queue: PlayQueue) {
         let db = self.db.clone();
         let player = self.player.clone();
+        let service_app_dir = self.service_app_dir.clone();
+        let wake = match play_tasks {
+            true => self.wake.play.clone(),
+            false => self.wake.silence.clone(),
+        };
 
         tokio::spawn(async move {
-            let mut interval = interval(Duration::from_secs(10)); // 每10秒检查一次，避免漏掉任务
+            // 每个任务已处理过的最近触发时刻，同一时刻只执行（或跳过）一次
+            let mut handled = HashMap::new();
 
             loop {
-                interval.tick().await;
+                let service_running = service_app_dir
+                    .as_ref()
+                    .is_some_and(|app_dir| service::is_service_alive(app_dir));
+                if !service_running {
+                    if let Err(e) = Self::check_and_execute_tasks(db.clone(), player.clone(), &queue, &mut handled, play_tasks).await {
+                        eprintln!("检查任务失败: {}", e);
+                    }
+                }
 
-                if let Err(e) = Self::check_and_execute_tasks(db.clone(), player.clone()).await {
-                    eprintln!("检查任务失败: {}", e);
+                // 睡到下一次检查的时刻，任务变化时提前唤醒重新计算；每次最多睡 MAX_SLEEP_SECS，
+                // 醒来时按系统时间还没到检查时刻则继续等待，不检查任务
+                loop {
+                    let check_at = next_check_time(&*db.lock().await, play_tasks, &handled);
+                    let max_sleep = Duration::from_secs(MAX_SLEEP_SECS);
+                    let delay = check_at
+                        .and_then(|at| (at - Local::now().naive_local()).to_std().ok())
+                        .map_or(max_sleep, |until| until.min(max_sleep));
+                    tokio::select! {
+                        _ = sleep_until(Instant::now() + delay) => {}
+                        _ = wake.notified() => break,
+                    }
+                    if check_at.is_some_and(|at| Local::now().naive_local() >= at) {
+                        break;
+                    }
                 }
             }
         });
@@ -35,212 +510,453 @@ impl Scheduler {
     async fn check_and_execute_tasks(
         db: Arc<Mutex<Connection>>,
         player: Arc<Mutex<AudioPlayer>>,
+        queue: &PlayQueue,
+        handled: &mut HashMap<i64, NaiveDateTime>,
+        play_tasks: bool,
     ) -> Result<(), String> {
         let now = Local::now();
+        let now_naive = now.naive_local();
         let current_hour = now.hour() as i64;
         let current_minute = now.minute() as i64;
-        let current_weekday = now.weekday().number_from_sunday() as i64; // 0=周日, 1-6=周一到周六
+        let current_weekday = weekday::weekday_index(&now); // 0=周日, 1-6=周一到周六
 
         println!("[Scheduler] 检查时间: {}:{:02}, 星期: {}", current_hour, current_minute, current_weekday);
 
-        // 查询所有启用的任务
-        let tasks = {
-            let conn = db.lock().await;
-            let mut stmt = conn
-                .prepare(
-                    "SELECT id, name, hour, minute, repeat_mode, custom_days, playlist_id,
-                            volume, fade_in_duration, duration_minutes, priority
-                     FROM scheduled_tasks
-                     WHERE is_enabled = 1
-                     ORDER BY priority DESC, hour, minute"
-                )
-                .map_err(|e| e.to_string())?;
+        let mut tasks = load_tasks(&*db.lock().await).map_err(|e| e.to_string())?;
+        tasks.retain(|task| task.is_play() == play_tasks);
 
-            let tasks: Vec<(i64, String, i64, i64, String, Option<String>, i64, i64, i64, Option<i64>, i64)> = stmt
-                .query_map([], |row| {
-                    Ok((
-                        row.get(0)?,
-                        row.get(1)?,
-                        row.get(2)?,
-                        row.get(3)?,
-                        row.get(4)?,
-                        row.get(5)?,
-                        row.get(6)?,
-                        row.get(7)?,
-                        row.get(8)?,
-                        row.get(9)?,
-                        row.get(10)?,
-                    ))
-                })
-                .map_err(|e| e.to_string())?
-                .collect::<Result<Vec<_>, _>>()
-                .map_err(|e| e.to_string())?;
-
-            tasks
+        // 全局暂停期间只执行重要任务
+        let (pause_state, tracer) = {
+            let conn = db.lock().await;
+            (pause::load_pause_state(&conn), Tracer::load(&conn))
         };
+        if pause_state.paused {
+            println!("[Scheduler] 定时任务已暂停 (自动恢复: {:?})", pause_state.resume_at);
+        }
 
-        for (task_id, name, hour, minute, repeat_mode, custom_days, playlist_id, volume, fade_in_duration, duration_minutes, _priority) in tasks {
-            // 检查时间是否匹配（允许当前分钟或前一分钟内执行，避免因检查间隔导致错过）
-            let time_matches = if current_minute == 0 {
-                // 如果当前是整点，需要检查上一小时的59分
-                (hour == current_hour && minute == 0) ||
-                (hour == if current_hour == 0 { 23 } else { current_hour - 1 } && minute == 59)
-            } else {
-                (hour == current_hour && minute == current_minute) ||
-                (hour == current_hour && minute == current_minute - 1)
+        for mut task in tasks.iter().cloned() {
+            // 最近的触发时刻已到且未超过宽限时间时执行，每个触发时刻只处理一次
+            let due_slot = task
+                .last_slot(now_naive)
+                .filter(|slot| (now_naive - *slot).num_seconds() < FIRE_GRACE_SECS);
+            let Some(slot) = due_slot else {
+                let current = format!("{}:{:02}", current_hour, current_minute);
+                let reason = match &task.cron {
+                    Some(_) => i18n::trf("cron 表达式 {}，当前 {}", &[&task.custom_days.as_deref().unwrap_or(""), &current]),
+                    None => i18n::trf(
+                        "触发时间 {}:{}，当前 {}:{}",
+                        &[&task.hour, &format!("{:02}", task.minute), &current_hour, &format!("{:02}", current_minute)],
+                    ),
+                };
+                tracer.record(&*db.lock().await, task.id, &task.name, Decision::NotDue, &reason);
+                continue;
             };
-
-            if !time_matches {
+            if handled.get(&task.id) == Some(&slot) {
                 continue;
             }
 
-            println!("[Scheduler] 发现匹配任务: {} ({}:{:02})", name, hour, minute);
+            println!("[Scheduler] 发现匹配任务: {} ({}:{:02})", task.name, task.hour, task.minute);
 
-            // 检查是否应该在今天执行
-            let should_execute = match repeat_mode.as_str() {
-                "daily" => true,
-                "weekday" => current_weekday >= 1 && current_weekday <= 5, // 周一到周五
-                "weekend" => current_weekday == 0 || current_weekday == 6, // 周六周日
-                "custom" => {
-                    if let Some(days_str) = custom_days {
-                        if let Ok(days) = serde_json::from_str::<Vec<i64>>(&days_str) {
-                            days.contains(&current_weekday)
-                        } else {
-                            false
-                        }
-                    } else {
-                        false
-                    }
-                }
-                "once" => {
-                    // 仅一次，检查是否已经执行过
-                    let conn = db.lock().await;
-                    let executed = conn
-                        .query_row(
-                            "SELECT COUNT(*) FROM execution_history WHERE task_id = ?1",
-                            [task_id],
-                            |row| row.get::<_, i64>(0),
-                        )
-                        .unwrap_or(0);
-                    executed == 0
-                }
-                _ => false,
-            };
+            // 暂停时不标记为已处理，宽限时间内恢复后仍会执行
+            if pause_state.paused && !task.critical {
+                tracer.record(&*db.lock().await, task.id, &task.name, Decision::Skipped, &i18n::tr("定时任务已暂停（非重要任务）"));
+                continue;
+            }
+            handled.insert(task.id, slot);
 
-            if !should_execute {
-                println!("[Scheduler] 任务 {} 今天不应该执行 (repeat_mode: {})", name, repeat_mode);
+            // 检查触发的那一天是否应该执行（跨午夜时为前一天）
+            let slot_weekday = weekday::weekday_index(&slot.date());
+            if task.skips_holiday(slot.date()) {
+                println!("[Scheduler] 任务 {} 节假日不执行", task.name);
+                tracer.record(&*db.lock().await, task.id, &task.name, Decision::Skipped, &i18n::tr("今天是节假日"));
+                continue;
+            }
+            if !task.runs_on(slot.date()) {
+                println!("[Scheduler] 任务 {} 今天不应该执行 (repeat_mode: {})", task.name, task.repeat_mode);
+                tracer.record(&*db.lock().await, task.id, &task.name, Decision::Skipped, &i18n::tr("今天不在任务的重复日期内"));
                 continue;
             }
 
-            // 检查今天是否已经执行过（避免重复执行）
-            let already_executed_today = {
+            if task.repeat_mode == "once" && task.has_run {
+                println!("[Scheduler] 单次任务 {} 已经执行过了", task.name);
+                tracer.record(&*db.lock().await, task.id, &task.name, Decision::Skipped, &i18n::tr("单次任务已经执行过"));
+                continue;
+            }
+
+            // 检查当天是否已经执行过（调度器重启或后台服务已执行时避免重复执行）；
+            // cron 任务一天可能触发多次，只检查这次触发时刻之后是否已执行
+            let already_executed = {
                 let conn = db.lock().await;
-                let today_start = format!("{} 00:00:00", now.format("%Y-%m-%d"));
-                let count: i64 = conn
-                    .query_row(
-                        "SELECT COUNT(*) FROM execution_history
-                         WHERE task_id = ?1 AND execution_time >= ?2",
-                        (&task_id, &today_start),
-                        |row| row.get(0),
-                    )
-                    .unwrap_or(0);
-                count > 0
+                let (sql, since) = match task.cron {
+                    Some(_) => (
+                        "SELECT EXISTS(SELECT 1 FROM execution_history
+                                       WHERE task_id = ?1 AND datetime(execution_time, 'localtime') >= ?2)",
+                        slot.format("%Y-%m-%d %H:%M:%S").to_string(),
+                    ),
+                    None => (
+                        "SELECT EXISTS(SELECT 1 FROM execution_history
+                                       WHERE task_id = ?1 AND DATE(execution_time, 'localtime') = ?2)",
+                        slot.date().format("%Y-%m-%d").to_string(),
+                    ),
+                };
+                conn.query_row(sql, (&task.id, since), |row| row.get(0)).unwrap_or(false)
             };
 
-            if already_executed_today {
-                println!("[Scheduler] 任务 {} 今天已经执行过了", name);
+            if already_executed {
+                println!("[Scheduler] 任务 {} 本次已经执行过了", task.name);
+                let reason = match task.cron {
+                    Some(_) => i18n::tr("这次触发已经执行过"),
+                    None => i18n::tr("今天已经执行过"),
+                };
+                tracer.record(&*db.lock().await, task.id, &task.name, Decision::Skipped, &reason);
                 continue;
             }
 
-            // 执行任务
-            println!("✅ [Scheduler] 执行定时任务: {} (ID: {})", name, task_id);
+            // 今天的收听时长已用完时不再开始非重要的播放任务
+            if task.is_play() && !task.critical && quota::load_status(&*db.lock().await).is_exhausted() {
+                println!("[Scheduler] 今天的收听时长已用完，跳过任务 {}", task.name);
+                tracer.record(&*db.lock().await, task.id, &task.name, Decision::Skipped, &i18n::tr("今天的收听时长已用完（非重要任务）"));
+                continue;
+            }
 
-            // 记录开始执行
-            {
-                let conn = db.lock().await;
-                let _ = conn.execute(
-                    "INSERT INTO execution_history (task_id, status, execution_time)
-                     VALUES (?1, 'started', datetime('now'))",
-                    [task_id],
-                );
+            // 按星期映射选择当天的播放列表
+            task.playlist_id = weekday::playlist_for_weekday(
+                task.weekday_playlists.as_deref(),
+                slot_weekday,
+                task.playlist_id,
+            );
+
+            // 执行任务
+            println!("✅ [Scheduler] 执行定时任务: {} (ID: {})", task.name, task.id);
+            let reason = match pause_state.paused {
+                true => i18n::tr("到达触发时间（重要任务，忽略暂停）"),
+                false => i18n::tr("到达触发时间"),
+            };
+            tracer.record(&*db.lock().await, task.id, &task.name, Decision::Executed, &reason);
+            if task.is_play() {
+                queue.push(task, false).await;
+            } else {
+                let (db, player) = (db.clone(), player.clone());
+                tokio::spawn(async move {
+                    let tracer = Tracer::load(&*db.lock().await);
+                    Self::execute_silence_task(db, player, &task, &tracer).await;
+                });
             }
+        }
 
-            // 播放播放列表
-            if let Err(e) = Self::play_playlist(
-                db.clone(),
-                player.clone(),
-                playlist_id,
-                volume,
-                fade_in_duration,
-                duration_minutes,
-            )
-            .await
-            {
-                eprintln!("播放失败: {}", e);
+        // 周六上午补播本周工作日错过的任务
+        if play_tasks && current_weekday == 6 && (MAKEUP_START_HOUR..MAKEUP_END_HOUR).contains(&current_hour) {
+            for mut task in tasks.into_iter().filter(|task| !pause_state.paused || task.critical) {
+                let playlist_id = makeup_playlist(&*db.lock().await, &task, now.date_naive());
+                let Some(playlist_id) = playlist_id else { continue };
+                if !task.critical && quota::load_status(&*db.lock().await).is_exhausted() {
+                    tracer.record(&*db.lock().await, task.id, &task.name, Decision::Skipped, &i18n::tr("今天的收听时长已用完（非重要任务）"));
+                    continue;
+                }
+                task.playlist_id = playlist_id;
 
-                // 记录失败
-                let conn = db.lock().await;
-                let _ = conn.execute(
-                    "UPDATE execution_history SET status = 'failed'
-                     WHERE task_id = ?1 AND execution_time = (
-                         SELECT MAX(execution_time) FROM execution_history WHERE task_id = ?1
-                     )",
-                    [task_id],
-                );
+                println!("✅ [Scheduler] 周末补播任务: {} (ID: {})", task.name, task.id);
+                tracer.record(&*db.lock().await, task.id, &task.name, Decision::Executed, &i18n::tr("周末补播工作日错过的执行"));
+                queue.push(task, true).await;
             }
         }
 
         Ok(())
     }
 
+    /// 执行任务并记录执行历史，makeup 表示周末补播，interrupt 被设置时（重要任务到期）停止播放并结束任务
+    async fn execute_task(
+        db: Arc<Mutex<Connection>>,
+        player: Arc<Mutex<AudioPlayer>>,
+        task: &TaskRow,
+        makeup: bool,
+        interrupt: &AtomicBool,
+        tracer: &Tracer,
+    ) {
+        // 记录开始执行（附带播放列表信息，便于统计区分定时播放与手动播放）
+        let (execution_id, playlist_name) = {
+            let conn = db.lock().await;
+            let playlist_name: Option<String> = conn
+                .query_row(
+                    "SELECT name FROM playlists WHERE id = ?1",
+                    [task.playlist_id],
+                    |row| row.get(0),
+                )
+                .ok();
+            let _ = conn.execute(
+                "INSERT INTO execution_history (task_id, status, execution_time, playlist_id, playlist_name, makeup)
+                 VALUES (?1, 'started', datetime('now'), ?2, ?3, ?4)",
+                (task.id, task.playlist_id, &playlist_name, makeup),
+            );
+            (conn.last_insert_rowid(), playlist_name)
+        };
+
+        // 解析音量曲线（格式错误时忽略，按固定音量播放）
+        let volume_curve = task.volume_curve.as_deref().and_then(|curve| match task::parse_volume_curve(curve) {
+            Ok(points) => Some(points),
+            Err(e) => {
+                eprintln!("[Scheduler] 任务 {} 的音量曲线无效: {}", task.name, e);
+                None
+            }
+        });
+
+        // 重要任务播放时取消静音，结束后恢复
+        let unmuted = task.critical && {
+            let mut player = player.lock().await;
+            let muted = player.is_muted();
+            if muted {
+                println!("🔔 [Scheduler] 重要任务 {}，取消静音", task.name);
+                player.set_muted(false);
+            }
+            muted
+        };
+
+        // 重要任务播放期间不受每日收听时长限制
+        if task.critical {
+            player.lock().await.set_critical_playback(true);
+        }
+
+        // 播放播放列表
+        let result = Self::play_playlist(
+            db.clone(),
+            player.clone(),
+            task,
+            playlist_name,
+            volume_curve,
+            interrupt,
+        )
+        .await;
+
+        if task.critical {
+            player.lock().await.set_critical_playback(false);
+        }
+        if unmuted {
+            player.lock().await.set_muted(true);
+        }
+
+        let status = match result {
+            Ok(()) => "completed",
+            Err(e) => {
+                eprintln!("播放失败: {}", e);
+                "failed"
+            }
+        };
+
+        // 记录执行结果
+        let conn = db.lock().await;
+        let _ = conn.execute(
+            "UPDATE execution_history SET status = ?1 WHERE id = ?2",
+            (status, execution_id),
+        );
+        let reason = match status {
+            "completed" if interrupt.load(Ordering::Relaxed) => i18n::tr("被重要任务中断"),
+            "completed" => i18n::tr("播放完成"),
+            _ => i18n::tr("播放失败"),
+        };
+        tracer.record(&conn, task.id, &task.name, Decision::Finished, &reason);
+    }
+
+    /// 执行停止或降低音量任务并记录执行历史，fade_out_duration 为渐变时长
+    async fn execute_silence_task(
+        db: Arc<Mutex<Connection>>,
+        player: Arc<Mutex<AudioPlayer>>,
+        task: &TaskRow,
+        tracer: &Tracer,
+    ) {
+        let fade_secs = task.fade_out_duration.max(0) as u64;
+        let reason = if task.action == "stop" {
+            if player.lock().await.has_pending_audio() {
+                println!("⏹️ [Scheduler] 任务 {} 停止播放", task.name);
+                let end = std::time::Instant::now() + Duration::from_secs(fade_secs);
+                fade_out_until(&player, end, fade_secs).await;
+            }
+            i18n::tr("已停止播放")
+        } else {
+            println!("🔉 [Scheduler] 任务 {} 将音量降至 {}%", task.name, task.volume);
+            lower_volume_to(&player, task.volume.clamp(0, 100) as f32 / 100.0, fade_secs).await;
+            i18n::trf("已将音量降至{}%", &[&task.volume])
+        };
+
+        let conn = db.lock().await;
+        let _ = conn.execute(
+            "INSERT INTO execution_history (task_id, status, execution_time) VALUES (?1, 'completed', datetime('now'))",
+            [task.id],
+        );
+        tracer.record(&conn, task.id, &task.name, Decision::Finished, &reason);
+    }
+
     async fn play_playlist(
         db: Arc<Mutex<Connection>>,
         player: Arc<Mutex<AudioPlayer>>,
-        playlist_id: i64,
-        volume: i64,
-        fade_in_duration: i64,
-        duration_minutes: Option<i64>,
+        task: &TaskRow,
+        playlist_name: Option<String>,
+        volume_curve: Option<Vec<VolumePoint>>,
+        interrupt: &AtomicBool,
     ) -> Result<(), String> {
-        // 获取播放列表中的所有音频
-        let audio_files = {
+        let TaskRow { playlist_id, volume, fade_in_duration, duration_minutes, fade_out_duration, min_plays_per_track, .. } = *task;
+
+        // 任务音量和音量曲线都不超过家长设置的最大音量（后台服务进程也在这里读取）
+        let max_volume = kiosk::load_max_volume(&*db.lock().await);
+        player.lock().await.set_max_volume(max_volume as f32 / 100.0);
+
+        // 获取播放列表中的所有音频（任务设置了播放顺序时覆盖播放列表的播放模式）
+        let last_played = player.lock().await.last_played_audio();
+        let (audio_files, gapless, play_mode) = {
             let conn = db.lock().await;
+            let (play_mode, gapless): (Option<String>, bool) = conn
+                .query_row(
+                    "SELECT play_mode, gapless FROM playlists WHERE id = ?1",
+                    [playlist_id],
+                    |row| Ok((row.get(0)?, row.get(1)?)),
+                )
+                .unwrap_or((None, false));
+            let play_mode = PlayMode::parse(play_mode.as_deref());
+            let play_order = match &task.play_order {
+                Some(order) => order.clone(),
+                None => if play_mode == PlayMode::Random { "shuffle" } else { "sequential" }.to_string(),
+            };
+            let order_by = match play_order.as_str() {
+                "reverse" => "pi.sort_order DESC",
+                "newest_first" => "af.upload_date DESC, af.id DESC",
+                _ => "pi.sort_order",
+            };
             let mut stmt = conn
-                .prepare(
-                    "SELECT af.id, af.file_path, af.duration, af.original_name
+                .prepare(&format!(
+                    "SELECT af.id, af.file_path, af.original_name, pi.start_offset, pi.end_offset, pi.gain_db
                      FROM playlist_items pi
                      JOIN audio_files af ON pi.audio_id = af.id
-                     WHERE pi.playlist_id = ?1
-                     ORDER BY pi.sort_order"
-                )
+                     WHERE pi.playlist_id = ?1 AND {}
+                     ORDER BY {}",
+                    playlist::PLAYABLE_ITEM_CONDITION, order_by
+                ))
                 .map_err(|e| e.to_string())?;
 
-            let files: Vec<(i64, String, i64, String)> = stmt
+            let mut files: Vec<(i64, String, String, ClipRange)> = stmt
                 .query_map([playlist_id], |row| {
-                    Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))
+                    let clip = ClipRange { start: row.get(3)?, end: row.get(4)?, gain_db: row.get(5)? };
+                    Ok((row.get(0)?, row.get(1)?, row.get(2)?, clip))
                 })
                 .map_err(|e| e.to_string())?
                 .collect::<Result<Vec<_>, _>>()
                 .map_err(|e| e.to_string())?;
 
-            files
+            if play_order == "shuffle" {
+                shuffle_tracks(&mut files, |(id, _, _, _)| *id, last_played);
+            }
+
+            (files, gapless, play_mode)
+        };
+
+        if audio_files.is_empty() {
+            return Err(i18n::tr("播放列表为空"));
+        }
+
+        // 设置了每曲最少播放次数时，扣除今天已播放的次数后展开为多轮播放
+        let audio_files = match min_plays_per_track {
+            Some(min_plays) if min_plays > 0 => {
+                let played_today = {
+                    let conn = db.lock().await;
+                    audio_files
+                        .iter()
+                        .map(|(id, _, _, _)| {
+                            conn.query_row(
+                                "SELECT COUNT(*) FROM playback_history
+                                 WHERE audio_id = ?1 AND DATE(play_time, 'localtime') = DATE('now', 'localtime')",
+                                [id],
+                                |row| row.get::<_, i64>(0),
+                            )
+                            .unwrap_or(0)
+                        })
+                        .collect::<Vec<_>>()
+                };
+                expand_to_min_plays(audio_files, &played_today, min_plays)
+            }
+            _ => audio_files,
         };
 
         if audio_files.is_empty() {
-            return Err("播放列表为空".to_string());
+            println!("[Scheduler] 今天每首音频的播放次数均已达标");
+            return Ok(());
         }
 
         // 设置播放队列
         let audio_ids: Vec<i64> = audio_files.iter().map(|(id, _, _, _)| *id).collect();
+        let clips: Vec<ClipRange> = audio_files.iter().map(|(_, _, _, clip)| *clip).collect();
         let mut player_guard = player.lock().await;
         player_guard.set_playlist_queue(audio_ids, true); // 标记为自动播放
+        player_guard.set_queue_clips(clips);
+        player_guard.set_play_mode(play_mode, false);
         drop(player_guard);
 
+        // 单曲循环/列表循环只在任务有时长限制时循环，否则播放一遍，避免无休止地播放
+        let repeating = play_mode.is_repeating() && duration_minutes.is_some();
+        let track_at = |position: usize| -> Option<usize> {
+            match (repeating, play_mode) {
+                (true, PlayMode::Single) => Some(0),
+                (true, _) => Some(position % audio_files.len()),
+                (false, _) => (position < audio_files.len()).then_some(position),
+            }
+        };
+
         // 记录开始时间（用于时长控制）
         let start_time = std::time::Instant::now();
         let max_duration_secs = duration_minutes.map(|mins| mins as u64 * 60);
+        // 设置了渐弱时提前开始渐弱，在任务结束时刻（时长限制或最后一首播完）正好停止
+        let fade_out_secs = fade_out_duration.max(0) as u64;
+        let handoff_settings = handoff::load_settings(&*db.lock().await);
+
+        // 按音量曲线定期调整音量，渐强期间暂停调整
+        let fading = Arc::new(AtomicBool::new(false));
+        let _curve_updater = volume_curve.clone().map(|curve| {
+            let player = player.clone();
+            let fading = fading.clone();
+            AbortOnDrop(tokio::spawn(async move {
+                let mut ticker = interval(Duration::from_secs(VOLUME_CURVE_UPDATE_SECS));
+                loop {
+                    ticker.tick().await;
+                    if fading.load(Ordering::Relaxed) {
+                        continue;
+                    }
+                    let minutes = start_time.elapsed().as_secs_f64() / 60.0;
+                    let target = task::volume_at(&curve, minutes).min(max_volume);
+                    player.lock().await.set_volume(target as f32 / 100.0);
+                }
+            }))
+        });
+
+        // 当前时刻应有的音量（有曲线时按曲线，否则为任务音量）
+        let target_volume_now = |elapsed: std::time::Duration| -> i64 {
+            match &volume_curve {
+                Some(curve) => task::volume_at(curve, elapsed.as_secs_f64() / 60.0),
+                None => volume,
+            }
+            .min(max_volume)
+        };
+
+        // 播放每个音频文件（无缝播放时，下一首已提前追加到 Sink，当前曲目播完立即接上）
+        let mut appended_next = false;
+        let mut position = 0;
+        while let Some(index) = track_at(position) {
+            let (audio_id, file_path, audio_name, clip) = audio_files[index].clone();
+
+            // 定时停止要求播完当前曲目（或倒计时已结束）时不再播放下一首
+            if position > 0 {
+                let mut player_guard = player.lock().await;
+                if player_guard.take_sleep_stop(index == 0) {
+                    println!("⏹️ [Scheduler] 定时停止，不再播放下一首");
+                    player_guard.stop();
+                    break;
+                }
+            }
+            position += 1;
+
+            // 曲目之间被重要任务中断时不再播放下一首
+            if interrupt.load(Ordering::Relaxed) {
+                println!("⏹️ [Scheduler] 重要任务到期，中断当前任务");
+                player.lock().await.stop();
+                break;
+            }
 
-        // 播放每个音频文件
-        for (audio_id, file_path, duration, audio_name) in audio_files {
             // 检查是否超过时长限制
             if let Some(max_secs) = max_duration_secs {
                 let elapsed_secs = start_time.elapsed().as_secs();
@@ -258,81 +974,356 @@ impl Scheduler {
 
             let mut player_guard = player.lock().await;
 
-            // 如果配置了渐强，先设置较低音量
-            if fade_in_duration > 0 {
-                player_guard.set_volume(0.0);
+            if appended_next {
+                // 已无缝切换到这一首，渐强只作用于第一首
+                drop(player_guard);
+            } else if player_guard.handoff_pending() {
+                // 上一个任务交接过来：与其交叉淡入淡出（代替渐强），开始播放后再设置音量，避免上一个任务的音量跳变
+                fading.store(true, Ordering::Relaxed);
+                player_guard.accept_handoff();
+                player_guard.set_clip(clip);
+                player_guard.play_with_info(&file_path, audio_id, audio_name.clone())?;
+                player_guard.set_volume(target_volume_now(start_time.elapsed()) as f32 / 100.0);
+                let levels = player_guard.crossfade_levels();
+                drop(player_guard);
+
+                if let Some((from, to)) = levels {
+                    println!("🔀 [Scheduler] 与上一个任务交叉淡入淡出 {} 秒", handoff_settings.crossfade_secs);
+                    let match_factor = handoff::loudness_match_factor(&*db.lock().await, from, to);
+                    player.lock().await.set_crossfade_match(match_factor);
+                    crossfade_for(&player, handoff_settings.crossfade_secs as u64).await;
+                }
+                fading.store(false, Ordering::Relaxed);
             } else {
-                player_guard.set_volume(volume as f32 / 100.0);
-            }
+                // 如果配置了渐强，先设置较低音量
+                if fade_in_duration > 0 {
+                    fading.store(true, Ordering::Relaxed);
+                    player_guard.set_volume(0.0);
+                } else {
+                    player_guard.set_volume(target_volume_now(start_time.elapsed()) as f32 / 100.0);
+                }
 
-            // 开始播放
-            player_guard.play_with_info(&file_path, audio_id, audio_name)?;
+                // 开始播放（仅播放片段范围内的部分，并应用该项的增益）
+                player_guard.set_clip(clip);
+                player_guard.play_with_info(&file_path, audio_id, audio_name.clone())?;
 
-            // 实现渐强效果
-            if fade_in_duration > 0 {
-                let target_volume = volume as f32 / 100.0;
-                let steps = fade_in_duration as u64;
-                let volume_step = target_volume / steps as f32;
+                // 实现渐强效果
+                if fade_in_duration > 0 {
+                    let target_volume = target_volume_now(start_time.elapsed()) as f32 / 100.0;
+                    let steps = fade_in_duration as u64;
+                    let volume_step = target_volume / steps as f32;
 
-                drop(player_guard); // 释放锁，以便渐强过程中不阻塞
+                    drop(player_guard); // 释放锁，以便渐强过程中不阻塞
 
-                for i in 0..=steps {
-                    let current_volume = volume_step * i as f32;
-                    let mut player_guard = player.lock().await;
-                    player_guard.set_volume(current_volume.min(target_volume));
+                    for i in 0..=steps {
+                        let current_volume = volume_step * i as f32;
+                        let mut player_guard = player.lock().await;
+                        player_guard.set_volume(current_volume.min(target_volume));
+                        drop(player_guard);
+                        sleep(Duration::from_secs(1)).await;
+                    }
+                    fading.store(false, Ordering::Relaxed);
+                } else {
                     drop(player_guard);
-                    sleep(Duration::from_secs(1)).await;
                 }
-            } else {
-                drop(player_guard);
             }
 
-            // 等待播放完成，但要考虑时长限制
-            let audio_duration_secs = duration as u64;
+            // 以所属播放列表记录播放历史
+            {
+                let conn = db.lock().await;
+                let _ = conn.execute(
+                    "INSERT INTO playback_history (audio_id, audio_name, playlist_id, playlist_name)
+                     VALUES (?1, ?2, ?3, ?4)",
+                    (audio_id, &audio_name, playlist_id, &playlist_name),
+                );
+            }
 
-            if let Some(max_secs) = max_duration_secs {
-                let elapsed_secs = start_time.elapsed().as_secs();
-                let remaining_secs = if max_secs > elapsed_secs {
-                    max_secs - elapsed_secs
-                } else {
-                    0
+            // 任务的结束时刻：达到时长限制时，或设置了渐弱时最后一首播完时
+            let mut end = max_duration_secs.map(|max_secs| start_time + Duration::from_secs(max_secs));
+            if fade_out_secs > 0 && track_at(position).is_none() {
+                let track_end = player
+                    .lock()
+                    .await
+                    .track_remaining_secs()
+                    .map(|secs| std::time::Instant::now() + Duration::from_secs_f64(secs));
+                end = end.into_iter().chain(track_end).min();
+            }
+            // 紧接着有下一个播放任务时不渐弱，到结束时刻把音频交接给下一个任务
+            let handing_off = match end {
+                Some(end) => {
+                    let end_time = Local::now().naive_local()
+                        + chrono::Duration::from_std(end.saturating_duration_since(std::time::Instant::now())).unwrap_or_default();
+                    handoff::is_back_to_back(&*db.lock().await, task.id, end_time)
+                }
+                None => false,
+            };
+            let lead_secs = if handing_off { 0 } else { fade_out_secs };
+            // 距离开始渐弱（或结束）的秒数，没有结束时刻时为 None
+            let remaining_secs = end.map(|end| {
+                end.saturating_duration_since(std::time::Instant::now())
+                    .as_secs()
+                    .saturating_sub(lead_secs)
+            });
+
+            let mut player_guard = player.lock().await;
+            let serial = player_guard.track_serial();
+            if gapless {
+                // 把下一首追加到同一个 Sink，当前曲目播完立即接上
+                appended_next = match track_at(position).map(|next| (next, &audio_files[next])) {
+                    Some((next, (next_id, next_path, next_name, next_clip))) => {
+                        player_guard.set_clip(*next_clip);
+                        match player_guard.append_with_info(next_path, *next_id, next_name.clone(), Some(next)) {
+                            Ok(()) => true,
+                            Err(e) => {
+                                eprintln!("[Scheduler] 预加载下一首失败，将在当前曲目结束后重新播放: {}", e);
+                                false
+                            }
+                        }
+                    }
+                    None => false,
                 };
+            }
+            drop(player_guard);
 
-                // 只等待剩余时长或音频时长，取较小值
-                let wait_secs = audio_duration_secs.min(remaining_secs);
-                sleep(Duration::from_secs(wait_secs)).await;
+            // 非无缝播放时预先打开并解码下一首的开头，切换时不再停顿
+            if !gapless {
+                if let Some(next) = track_at(position) {
+                    player::preload_file(&player, audio_files[next].1.clone()).await;
+                }
+            }
 
-                // 如果音频还没播完但达到时长限制，停止播放
-                if wait_secs < audio_duration_secs {
-                    println!("⏹️ [Scheduler] 达到时长限制，停止当前音频");
-                    let mut player_guard = player.lock().await;
-                    player_guard.stop();
-                    drop(player_guard);
+            // 由播放器检测曲目播完后再播放下一首，同时考虑时长限制
+            match wait_for_track_end(&player, serial, remaining_secs, interrupt).await {
+                TrackWait::Finished => {}
+                TrackWait::TimeLimit if handing_off => {
+                    println!("🔀 [Scheduler] 任务结束，当前音频交接给下一个任务");
+                    player.lock().await.begin_handoff();
+                    // 下一个任务没有接手时渐弱停止（接手或停止播放后不再有待交接的音频）
+                    let player = player.clone();
+                    tokio::spawn(async move {
+                        sleep(Duration::from_secs(handoff::FALLBACK_SECS)).await;
+                        if player.lock().await.handoff_pending() {
+                            let end = std::time::Instant::now() + Duration::from_secs(fade_out_secs);
+                            fade_out_until(&player, end, fade_out_secs).await;
+                        }
+                    });
+                    break;
+                }
+                TrackWait::TimeLimit => {
+                    println!("⏹️ [Scheduler] 任务即将结束，停止当前音频");
+                    fading.store(true, Ordering::Relaxed);
+                    let end = end.unwrap_or_else(std::time::Instant::now);
+                    fade_out_until(&player, end, fade_out_secs).await;
+                    break;
+                }
+                TrackWait::Stopped => {
+                    println!("⏹️ [Scheduler] 播放已被停止，结束任务");
+                    break;
+                }
+                TrackWait::Interrupted => {
+                    println!("⏹️ [Scheduler] 重要任务到期，中断当前任务");
+                    player.lock().await.stop();
                     break;
                 }
-            } else {
-                // 没有时长限制，等待音频播放完成
-                sleep(Duration::from_secs(audio_duration_secs)).await;
             }
+        }
 
-            // 更新播放计数
-            let conn = db.lock().await;
-            let _ = conn.execute(
-                "UPDATE audio_files SET play_count = play_count + 1, last_played = datetime('now') WHERE id = ?1",
-                [audio_id],
-            );
+        Ok(())
+    }
+}
+
+/// 等待当前曲目的结果
+enum TrackWait {
+    /// 播完了（或切换到了其他曲目）
+    Finished,
+    /// 达到任务的时长限制时还没播完
+    TimeLimit,
+    /// 播放被停止（手动停止或定时停止）
+    Stopped,
+    /// 重要任务到期，需要中断当前任务
+    Interrupted,
+}
+
+/// 等待当前曲目播完（Sink 切换到下一首或全部播完），在 remaining_secs 秒内没播完时返回 TimeLimit，
+/// interrupt 被设置时返回 Interrupted
+async fn wait_for_track_end(
+    player: &Arc<Mutex<AudioPlayer>>,
+    serial: u64,
+    remaining_secs: Option<u64>,
+    interrupt: &AtomicBool,
+) -> TrackWait {
+    let deadline = remaining_secs.map(|secs| std::time::Instant::now() + Duration::from_secs(secs));
+    loop {
+        if interrupt.load(Ordering::Relaxed) {
+            return TrackWait::Interrupted;
+        }
+        {
+            let mut player_guard = player.lock().await;
+            player_guard.sync_gapless();
+            if player_guard.current_audio_id().is_none() {
+                return TrackWait::Stopped;
+            }
+            if player_guard.track_serial() != serial || !player_guard.has_pending_audio() {
+                return TrackWait::Finished;
+            }
+        }
+        if deadline.is_some_and(|deadline| std::time::Instant::now() >= deadline) {
+            return TrackWait::TimeLimit;
+        }
+        sleep(Duration::from_millis(TRACK_END_POLL_MILLIS)).await;
+    }
+}
+
+/// 在结束时刻 end 之前的 fade_secs 秒内逐渐减小音量，到时停止播放并恢复原音量（播放被停止时提前结束）
+async fn fade_out_until(player: &Arc<Mutex<AudioPlayer>>, end: std::time::Instant, fade_secs: u64) {
+    let volume = player.lock().await.volume();
+    loop {
+        {
+            let mut player_guard = player.lock().await;
+            let remaining = end.saturating_duration_since(std::time::Instant::now()).as_secs_f64();
+            if remaining <= 0.0 || player_guard.current_audio_id().is_none() {
+                player_guard.stop_after_fade(volume);
+                return;
+            }
+            player_guard.set_volume(volume * (remaining / fade_secs as f64).min(1.0) as f32);
         }
+        sleep(Duration::from_millis(TRACK_END_POLL_MILLIS)).await;
+    }
+}
 
-        // 记录完成
-        let conn = db.lock().await;
-        let _ = conn.execute(
-            "UPDATE execution_history SET status = 'completed'
-             WHERE execution_time = (
-                 SELECT MAX(execution_time) FROM execution_history
-             )",
-            [],
-        );
+/// 在 secs 秒内完成任务交接的交叉淡入淡出（暂停、停止等中断交叉淡入淡出时提前结束）
+async fn crossfade_for(player: &Arc<Mutex<AudioPlayer>>, secs: u64) {
+    let start = std::time::Instant::now();
+    loop {
+        let progress = (start.elapsed().as_secs_f32() / secs.max(1) as f32).min(1.0);
+        if !player.lock().await.set_crossfade_progress(progress) || progress >= 1.0 {
+            return;
+        }
+        sleep(Duration::from_millis(TRACK_END_POLL_MILLIS)).await;
+    }
+}
 
-        Ok(())
+/// 在 fade_secs 秒内把音量逐渐降到 target（当前音量不高于 target 时不变）
+async fn lower_volume_to(player: &Arc<Mutex<AudioPlayer>>, target: f32, fade_secs: u64) {
+    let volume = player.lock().await.volume();
+    if volume <= target {
+        return;
+    }
+    let start = std::time::Instant::now();
+    loop {
+        let progress = match fade_secs {
+            0 => 1.0,
+            _ => (start.elapsed().as_secs_f64() / fade_secs as f64).min(1.0),
+        };
+        player.lock().await.set_volume(volume + (target - volume) * progress as f32);
+        if progress >= 1.0 {
+            return;
+        }
+        sleep(Duration::from_millis(TRACK_END_POLL_MILLIS)).await;
+    }
+}
+
+/// 按轮次展开播放序列：每轮只保留尚未达到 min_plays 次的音频，保持列表顺序
+fn expand_to_min_plays<T: Clone>(files: Vec<T>, played_today: &[i64], min_plays: i64) -> Vec<T> {
+    let mut sequence = Vec::new();
+    for round in 0..min_plays {
+        for (file, played) in files.iter().zip(played_today) {
+            if played + round < min_plays {
+                sequence.push(file.clone());
+            }
+        }
+    }
+    sequence
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn min_plays_expands_in_rounds() {
+        // a 今天已播放 1 次，b、c 还没有播放过
+        let sequence = expand_to_min_plays(vec!["a", "b", "c"], &[1, 0, 0], 2);
+        assert_eq!(sequence, ["a", "b", "c", "b", "c"]);
+        assert_eq!(expand_to_min_plays(vec!["a", "b"], &[0, 0], 1), ["a", "b"]);
+    }
+
+    fn workday_task(skip_holidays: bool, holidays: &[&str]) -> TaskRow {
+        TaskRow {
+            id: 1,
+            name: "起床".to_string(),
+            hour: 7,
+            minute: 0,
+            repeat_mode: "weekday".to_string(),
+            custom_days: None,
+            start_date: None,
+            end_date: None,
+            playlist_id: 1,
+            volume: 50,
+            fade_in_duration: 0,
+            duration_minutes: None,
+            fade_out_duration: 0,
+            volume_curve: None,
+            min_plays_per_track: None,
+            play_order: None,
+            weekday_playlists: None,
+            weekend_makeup: false,
+            created_at: "2024-01-01 00:00:00".to_string(),
+            critical: false,
+            has_run: false,
+            action: "play".to_string(),
+            skip_holidays,
+            holidays: Arc::new(holidays.iter().map(|day| NaiveDate::parse_from_str(day, "%Y-%m-%d").unwrap()).collect()),
+            cron: None,
+        }
+    }
+
+    #[test]
+    fn skip_holidays_moves_to_the_next_workday() {
+        // 2024-10-01 至 10-03 为周二至周四
+        let holidays = ["2024-10-01", "2024-10-02", "2024-10-03"];
+        let at = |day: &str, hour: u32| NaiveDate::parse_from_str(day, "%Y-%m-%d").unwrap().and_hms_opt(hour, 0, 0).unwrap();
+        let task = workday_task(true, &holidays);
+        assert!(task.skips_holiday(at("2024-10-02", 0).date()));
+        assert!(!task.runs_on(at("2024-10-02", 0).date()));
+        assert!(task.runs_on(at("2024-10-04", 0).date()));
+        assert_eq!(task.next_occurrence(at("2024-09-30", 8)), Some(at("2024-10-04", 7)));
+
+        // 没有设置节假日不执行时照常触发
+        let task = workday_task(false, &holidays);
+        assert!(!task.skips_holiday(at("2024-10-02", 0).date()));
+        assert_eq!(task.next_occurrence(at("2024-09-30", 8)), Some(at("2024-10-01", 7)));
+    }
+
+    #[test]
+    fn back_to_back_successor_behind_other_tasks() {
+        let at = |day: &str, hour: u32, minute: u32| {
+            NaiveDate::parse_from_str(day, "%Y-%m-%d").unwrap().and_hms_opt(hour, minute, 0).unwrap()
+        };
+        let task = |id: i64, hour: i64, minute: i64, action: &str| TaskRow {
+            id,
+            hour,
+            minute,
+            repeat_mode: "daily".to_string(),
+            action: action.to_string(),
+            ..workday_task(false, &[])
+        };
+        // 7:30 结束的任务之前 7:20 有一个降低音量任务，紧接着的是 7:30 的播放任务
+        let tasks = [task(1, 7, 0, "play"), task(2, 7, 20, "lower_volume"), task(3, 7, 30, "play")];
+        let end = at("2024-06-03", 7, 30);
+        assert!(play_task_triggers_near(&tasks, 1, end, 60, false));
+        // 暂停时非重要任务不会执行，不交接
+        assert!(!play_task_triggers_near(&tasks, 1, end, 60, true));
+        // 下一个播放任务不在结束时刻附近
+        assert!(!play_task_triggers_near(&tasks, 1, at("2024-06-03", 7, 25), 60, false));
+        // 不算自己
+        assert!(!play_task_triggers_near(&tasks[..2], 1, at("2024-06-04", 7, 0), 60, false));
+    }
+
+    #[test]
+    fn min_plays_already_reached() {
+        assert!(expand_to_min_plays(vec!["a", "b"], &[3, 2], 2).is_empty());
+        assert!(expand_to_min_plays(vec!["a", "b"], &[0, 0], 0).is_empty());
+        assert!(expand_to_min_plays(Vec::<&str>::new(), &[], 3).is_empty());
     }
 }
@@ -0,0 +1,138 @@
+use auto_launch::AutoLaunchBuilder;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::sync::Mutex;
+use tokio::time::{interval, Duration};
+use crate::{db, i18n, interrupt, loudness, playcount, player, quota, scheduler};
+
+// 以后台服务模式启动时的命令行参数
+pub const SERVICE_ARG: &str = "--service";
+
+// 与 tauri.conf.json 中的 bundle.identifier 保持一致，用于定位应用数据目录
+const APP_IDENTIFIER: &str = "com.moerduo.app";
+const SERVICE_APP_NAME: &str = "磨耳朵后台服务";
+const HEARTBEAT_FILE: &str = "service.heartbeat";
+// 心跳写入间隔与判定存活的超时时间（秒）
+const HEARTBEAT_INTERVAL_SECS: u64 = 30;
+const HEARTBEAT_TIMEOUT_SECS: u64 = 90;
+
+#[derive(Debug, Serialize)]
+pub struct BackgroundServiceStatus {
+    pub enabled: bool,
+    pub running: bool,
+}
+
+pub fn is_service_mode() -> bool {
+    std::env::args().any(|arg| arg == SERVICE_ARG)
+}
+
+/// 不依赖窗口进程时的应用数据目录（与Tauri的 app_data_dir 相同）
+fn service_app_dir() -> Option<PathBuf> {
+    dirs::data_dir().map(|dir| dir.join(APP_IDENTIFIER))
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// 后台服务是否正在运行（根据心跳文件判断）
+pub fn is_service_alive(app_dir: &Path) -> bool {
+    std::fs::read_to_string(app_dir.join(HEARTBEAT_FILE))
+        .ok()
+        .and_then(|content| content.trim().parse::<u64>().ok())
+        .map(|beat| now_secs().saturating_sub(beat) < HEARTBEAT_TIMEOUT_SECS)
+        .unwrap_or(false)
+}
+
+/// 以无界面的后台服务模式运行调度器和播放器
+pub fn run_service() {
+    let app_dir = service_app_dir().expect("Failed to get app data dir");
+    std::fs::create_dir_all(&app_dir).expect("Failed to create app data dir");
+
+    let conn = db::init_database(&app_dir.join("moerduo.db")).expect("Failed to initialize database");
+    let db_conn = Arc::new(Mutex::new(conn));
+    let audio_player = Arc::new(Mutex::new(player::AudioPlayer::new()));
+
+    println!("[Service] 后台服务已启动，数据目录: {}", app_dir.display());
+
+    tauri::async_runtime::block_on(async move {
+        playcount::start_play_count_monitor(db_conn.clone(), audio_player.clone());
+        quota::start_quota_monitor(None, db_conn.clone(), audio_player.clone());
+        interrupt::start_capture_monitor(None, db_conn.clone(), audio_player.clone());
+        loudness::start_loudness_analyzer(db_conn.clone(), audio_player.clone());
+        let scheduler = scheduler::Scheduler::new(db_conn, audio_player);
+        scheduler.start().await;
+
+        let heartbeat_path = app_dir.join(HEARTBEAT_FILE);
+        let mut ticker = interval(Duration::from_secs(HEARTBEAT_INTERVAL_SECS));
+
+        loop {
+            tokio::select! {
+                _ = ticker.tick() => {
+                    if let Err(e) = std::fs::write(&heartbeat_path, now_secs().to_string()) {
+                        eprintln!("[Service] 写入心跳失败: {}", e);
+                    }
+                }
+                _ = tokio::signal::ctrl_c() => {
+                    let _ = std::fs::remove_file(&heartbeat_path);
+                    println!("[Service] 后台服务已退出");
+                    break;
+                }
+            }
+        }
+    });
+}
+
+fn build_service_launcher() -> Result<auto_launch::AutoLaunch, String> {
+    let app_path = crate::autostart::get_app_path()?;
+
+    AutoLaunchBuilder::new()
+        .set_app_name(SERVICE_APP_NAME)
+        .set_app_path(&app_path.to_string_lossy())
+        .set_args(&[SERVICE_ARG])
+        .set_use_launch_agent(true)
+        .build()
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_background_service_status(
+    app: tauri::AppHandle,
+) -> Result<BackgroundServiceStatus, String> {
+    let enabled = build_service_launcher()
+        .and_then(|launcher| launcher.is_enabled().map_err(|e| e.to_string()))
+        .unwrap_or(false);
+
+    let running = app.path_resolver()
+        .app_data_dir()
+        .map(|dir| is_service_alive(&dir))
+        .unwrap_or(false);
+
+    Ok(BackgroundServiceStatus { enabled, running })
+}
+
+/// 注册/取消登录时自动启动的后台服务
+#[tauri::command]
+pub async fn set_background_service(enable: bool) -> Result<(), String> {
+    let launcher = build_service_launcher()?;
+
+    if enable {
+        launcher.enable().map_err(|e| e.to_string())?;
+
+        // 立即启动一个服务进程，无需等到下次登录
+        let app_path = crate::autostart::get_app_path()?;
+        std::process::Command::new(app_path)
+            .arg(SERVICE_ARG)
+            .spawn()
+            .map_err(|e| i18n::trf("启动后台服务失败: {}", &[&e]))?;
+    } else {
+        launcher.disable().map_err(|e| e.to_string())?;
+    }
+
+    Ok(())
+}
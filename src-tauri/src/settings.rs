@@ -1,31 +1,109 @@
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
-use tokio::sync::Mutex;
+use tokio::sync::{broadcast, Mutex};
 use rusqlite::Connection;
-use tauri::State;
+use tauri::{AppHandle, Manager, State};
 use serde_json;
+use crate::i18n;
+use crate::pause;
 
-#[derive(Debug, Serialize, Deserialize)]
+// 导入播放器无法解码的文件时是否自动用FFmpeg转码为MP3（默认开启）
+const AUTO_TRANSCODE_KEY: &str = "auto_transcode";
+// 从视频或在线视频提取音频时是否同时做响度归一化（默认关闭）
+const EXTRACT_LOUDNORM_KEY: &str = "extract_loudnorm";
+// 设置变更广播的缓冲条数，订阅者落后时只需要最新的设置
+const SETTINGS_BUS_CAPACITY: usize = 8;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AppSettings {
     pub auto_start: bool,
     pub minimize_to_tray: bool,
     pub default_volume: i64,
     pub theme: String,
     pub audio_path: Option<String>,
+    #[serde(default = "default_auto_transcode")]
+    pub auto_transcode: bool,
+    /// 提取音频时用 FFmpeg loudnorm 统一响度
+    #[serde(default)]
+    pub extract_loudnorm: bool,
+    /// 后端返回的错误、托盘菜单和通知使用的语言（zh / en）
+    #[serde(default = "default_language")]
+    pub language: String,
+}
+
+fn default_language() -> String {
+    "zh".to_string()
+}
+
+fn default_auto_transcode() -> bool {
+    true
+}
+
+/// 读取“导入时自动转码”设置
+pub(crate) fn auto_transcode_enabled(conn: &Connection) -> bool {
+    conn.query_row(
+        "SELECT value FROM app_settings WHERE key = ?1",
+        [AUTO_TRANSCODE_KEY],
+        |row| row.get::<_, String>(0),
+    )
+    .ok()
+    .and_then(|value| serde_json::from_str(&value).ok())
+    .unwrap_or_else(default_auto_transcode)
+}
+
+/// 读取“提取时统一响度”设置
+pub(crate) fn extract_loudnorm_enabled(conn: &Connection) -> bool {
+    conn.query_row(
+        "SELECT value FROM app_settings WHERE key = ?1",
+        [EXTRACT_LOUDNORM_KEY],
+        |row| row.get::<_, String>(0),
+    )
+    .ok()
+    .and_then(|value| serde_json::from_str(&value).ok())
+    .unwrap_or(false)
+}
+
+/// 设置变更广播：保存设置后发送最新的设置，各模块订阅后立即应用，无需重启
+#[derive(Clone)]
+pub struct SettingsBus(broadcast::Sender<AppSettings>);
+
+impl Default for SettingsBus {
+    fn default() -> Self {
+        Self(broadcast::channel(SETTINGS_BUS_CAPACITY).0)
+    }
+}
+
+impl SettingsBus {
+    pub fn subscribe(&self) -> broadcast::Receiver<AppSettings> {
+        self.0.subscribe()
+    }
+}
+
+/// 通知后端各模块和前端设置已变更
+fn publish_settings(app: &AppHandle, bus: &SettingsBus, settings: AppSettings) {
+    let _ = app.emit_all("settings-changed", &settings);
+    // 没有订阅者时发送失败，忽略即可
+    let _ = bus.0.send(settings);
 }
 
 #[tauri::command]
 pub async fn get_settings(
     conn: State<'_, Arc<Mutex<Connection>>>,
 ) -> Result<AppSettings, String> {
-    let conn = conn.lock().await;
+    load_settings(&*conn.lock().await)
+}
 
+/// 读取全部应用设置，未保存的项使用默认值
+pub(crate) fn load_settings(conn: &Connection) -> Result<AppSettings, String> {
     let mut settings = AppSettings {
         auto_start: false,
         minimize_to_tray: true,
         default_volume: 50,
         theme: "light".to_string(),
         audio_path: None,
+        auto_transcode: default_auto_transcode(),
+        extract_loudnorm: false,
+        language: default_language(),
     };
 
     // 从数据库读取设置
@@ -64,6 +142,19 @@ pub async fn get_settings(
             "audio_path" => {
                 settings.audio_path = Some(value);
             }
+            AUTO_TRANSCODE_KEY => {
+                if let Ok(val) = serde_json::from_str(&value) {
+                    settings.auto_transcode = val;
+                }
+            }
+            EXTRACT_LOUDNORM_KEY => {
+                if let Ok(val) = serde_json::from_str(&value) {
+                    settings.extract_loudnorm = val;
+                }
+            }
+            i18n::LANGUAGE_KEY => {
+                settings.language = value;
+            }
             _ => {}
         }
     }
@@ -76,7 +167,9 @@ pub async fn get_settings(
 pub async fn save_setting(
     key: String,
     value: String,
+    app: AppHandle,
     conn: State<'_, Arc<Mutex<Connection>>>,
+    bus: State<'_, SettingsBus>,
 ) -> Result<(), String> {
     let conn = conn.lock().await;
 
@@ -86,14 +179,18 @@ pub async fn save_setting(
     )
     .map_err(|e| e.to_string())?;
 
+    publish_settings(&app, &bus, load_settings(&conn)?);
     Ok(())
 }
 
 #[tauri::command]
 pub async fn save_settings(
     settings: AppSettings,
+    app: AppHandle,
     conn: State<'_, Arc<Mutex<Connection>>>,
+    bus: State<'_, SettingsBus>,
 ) -> Result<(), String> {
+    let language = i18n::normalize_language(&settings.language)?;
     let conn = conn.lock().await;
 
     // 保存所有设置
@@ -121,14 +218,37 @@ pub async fn save_settings(
     )
     .map_err(|e| e.to_string())?;
 
-    if let Some(audio_path) = settings.audio_path {
+    conn.execute(
+        "INSERT OR REPLACE INTO app_settings (key, value) VALUES (?1, ?2)",
+        (AUTO_TRANSCODE_KEY, serde_json::to_string(&settings.auto_transcode).unwrap_or_default()),
+    )
+    .map_err(|e| e.to_string())?;
+
+    conn.execute(
+        "INSERT OR REPLACE INTO app_settings (key, value) VALUES (?1, ?2)",
+        (EXTRACT_LOUDNORM_KEY, serde_json::to_string(&settings.extract_loudnorm).unwrap_or_default()),
+    )
+    .map_err(|e| e.to_string())?;
+
+    conn.execute(
+        "INSERT OR REPLACE INTO app_settings (key, value) VALUES (?1, ?2)",
+        (i18n::LANGUAGE_KEY, language),
+    )
+    .map_err(|e| e.to_string())?;
+
+    if let Some(audio_path) = &settings.audio_path {
         conn.execute(
             "INSERT OR REPLACE INTO app_settings (key, value) VALUES (?1, ?2)",
-            ("audio_path", &audio_path),
+            ("audio_path", audio_path),
         )
         .map_err(|e| e.to_string())?;
     }
 
+    // 语言立即生效：刷新托盘菜单
+    i18n::set_language(language);
+    i18n::refresh_tray(&app, pause::load_pause_state(&conn).paused);
+
+    publish_settings(&app, &bus, AppSettings { language: language.to_string(), ..settings });
     Ok(())
 }
 
@@ -162,7 +282,7 @@ pub async fn export_config(
     _conn: State<'_, Arc<Mutex<Connection>>>,
 ) -> Result<String, String> {
     // TODO: 实现配置导出功能
-    Err("功能暂未实现".to_string())
+    Err(i18n::tr("功能暂未实现"))
 }
 
 #[tauri::command]
@@ -170,5 +290,5 @@ pub async fn import_config(
     _conn: State<'_, Arc<Mutex<Connection>>>,
 ) -> Result<String, String> {
     // TODO: 实现配置导入功能
-    Err("功能暂未实现".to_string())
+    Err(i18n::tr("功能暂未实现"))
 }
\ No newline at end of file
@@ -0,0 +1,434 @@
+use serde::Serialize;
+use std::collections::hash_map::{Entry, HashMap};
+use std::collections::HashSet;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use rusqlite::Connection;
+use chrono::{Duration, Local, NaiveDate, NaiveDateTime, NaiveTime, Timelike};
+use tauri::State;
+use crate::audio::FALLBACK_DURATION_SECS;
+use crate::holiday;
+use crate::pause;
+use crate::playlist;
+use crate::player::ClipRange;
+use crate::weekday;
+use crate::i18n;
+
+// 默认模拟天数，以及允许的最大天数
+const DEFAULT_SIMULATION_DAYS: i64 = 7;
+const MAX_SIMULATION_DAYS: i64 = 31;
+const DATE_FORMAT: &str = "%Y-%m-%d";
+const TIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+/// 模拟的一次任务执行
+#[derive(Debug, Serialize)]
+pub struct SimulatedExecution {
+    pub task_id: i64,
+    pub task_name: String,
+    /// 计划触发时间（YYYY-MM-DD HH:MM:SS）
+    pub scheduled_time: String,
+    /// 实际开始、结束时间，未执行时为空
+    pub start_time: Option<String>,
+    pub end_time: Option<String>,
+    pub playlist_id: i64,
+    pub playlist_name: Option<String>,
+    /// 预计播放时长（秒）
+    pub duration_secs: i64,
+    /// run（准时）、delayed（排队等待前一个任务）、missed（被前一个任务占用而错过）、
+    /// paused（全局暂停中）、skipped（今天已执行过）、failed（播放列表为空或已删除）
+    pub status: String,
+    /// 造成延迟或错过的任务名称
+    pub blocked_by: Option<String>,
+}
+
+struct SimTask {
+    id: i64,
+    name: String,
+    time: NaiveTime,
+    repeat_mode: String,
+    custom_days: Option<String>,
+    start_date: Option<String>,
+    end_date: Option<String>,
+    playlist_id: i64,
+    fade_in_duration: i64,
+    duration_minutes: Option<i64>,
+    min_plays_per_track: Option<i64>,
+    weekday_playlists: Option<String>,
+    /// 最近一次执行的日期（YYYY-MM-DD）
+    last_executed: Option<String>,
+    /// 重要任务，暂停期间照常执行
+    critical: bool,
+    /// 节假日不执行
+    skip_holidays: bool,
+}
+
+/// 播放列表名称及各曲目的播放时长（秒），播放列表不存在或已删除时为 None
+type PlaylistInfo = Option<(String, Vec<i64>)>;
+
+fn load_tasks(conn: &Connection) -> rusqlite::Result<Vec<SimTask>> {
+    // 与调度器的执行顺序一致：同一分钟内重要任务先执行，其次优先级高的先执行
+    let mut stmt = conn.prepare(
+        "SELECT id, name, hour, minute, repeat_mode, custom_days, playlist_id, fade_in_duration,
+                duration_minutes, min_plays_per_track, weekday_playlists,
+                (SELECT DATE(MAX(execution_time), 'localtime') FROM execution_history eh WHERE eh.task_id = st.id),
+                critical, start_date, end_date, skip_holidays
+         FROM scheduled_tasks st
+         WHERE is_enabled = 1 AND deleted_at IS NULL AND action = 'play'
+           AND playlist_id IN (SELECT id FROM playlists WHERE deleted_at IS NULL)
+         ORDER BY critical DESC, priority DESC, hour, minute",
+    )?;
+    let tasks = stmt
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, u32>(2)?,
+                row.get::<_, u32>(3)?,
+                SimTask {
+                    id: row.get(0)?,
+                    name: row.get(1)?,
+                    time: NaiveTime::MIN,
+                    repeat_mode: row.get(4)?,
+                    custom_days: row.get(5)?,
+                    playlist_id: row.get(6)?,
+                    fade_in_duration: row.get(7)?,
+                    duration_minutes: row.get(8)?,
+                    min_plays_per_track: row.get(9)?,
+                    weekday_playlists: row.get(10)?,
+                    last_executed: row.get(11)?,
+                    critical: row.get(12)?,
+                    start_date: row.get(13)?,
+                    end_date: row.get(14)?,
+                    skip_holidays: row.get(15)?,
+                },
+            ))
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    Ok(tasks
+        .into_iter()
+        .filter_map(|(hour, minute, task)| {
+            NaiveTime::from_hms_opt(hour, minute, 0).map(|time| SimTask { time, ..task })
+        })
+        .collect())
+}
+
+fn load_playlist(conn: &Connection, playlist_id: i64) -> rusqlite::Result<PlaylistInfo> {
+    let name: Option<String> = match conn.query_row(
+        "SELECT name FROM playlists WHERE id = ?1 AND deleted_at IS NULL",
+        [playlist_id],
+        |row| row.get(0),
+    ) {
+        Ok(name) => Some(name),
+        Err(rusqlite::Error::QueryReturnedNoRows) => None,
+        Err(e) => return Err(e),
+    };
+    let Some(name) = name else { return Ok(None) };
+
+    let mut stmt = conn.prepare(&format!(
+        "SELECT af.duration, pi.start_offset, pi.end_offset
+         FROM playlist_items pi
+         JOIN audio_files af ON pi.audio_id = af.id
+         WHERE pi.playlist_id = ?1 AND {}",
+        playlist::PLAYABLE_ITEM_CONDITION
+    ))?;
+    // 与调度器相同：时长未知时按默认时长等待
+    let tracks = stmt
+        .query_map([playlist_id], |row| {
+            let duration: Option<i64> = row.get(0)?;
+            let clip = ClipRange { start: row.get(1)?, end: row.get(2)?, gain_db: None };
+            Ok(clip
+                .effective_secs(duration.filter(|secs| *secs > 0))
+                .unwrap_or(FALLBACK_DURATION_SECS))
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    Ok(Some((name, tracks)))
+}
+
+/// 按调度器的播放方式估算任务的播放时长（秒）：渐强每首额外占用 fade_in_duration + 1 秒，
+/// 每曲最少播放次数按整轮重复估算，最后受时长限制截断
+fn estimate_run_secs(task: &SimTask, tracks: &[i64]) -> i64 {
+    let mut per_round: i64 = tracks.iter().sum();
+    if task.fade_in_duration > 0 {
+        per_round += tracks.len() as i64 * (task.fade_in_duration + 1);
+    }
+    let total = per_round * task.min_plays_per_track.filter(|n| *n > 0).unwrap_or(1);
+    match task.duration_minutes {
+        Some(minutes) => total.min(minutes * 60),
+        None => total,
+    }
+}
+
+fn floor_minute(time: NaiveDateTime) -> NaiveDateTime {
+    time.with_second(0).and_then(|t| t.with_nanosecond(0)).unwrap_or(time)
+}
+
+/// 时间段内晚于 now 的全部触发点（触发时间、任务、当天的播放列表），按时间排序（同一时间保持优先级顺序）
+fn expand_triggers<'a>(
+    tasks: &'a [SimTask],
+    holidays: &HashSet<NaiveDate>,
+    start: NaiveDate,
+    end: NaiveDate,
+    now: NaiveDateTime,
+) -> Vec<(NaiveDateTime, &'a SimTask, i64)> {
+    let mut triggers = Vec::new();
+    let mut date = start;
+    while date <= end {
+        let day = weekday::weekday_index(&date);
+        let is_holiday = holidays.contains(&date);
+        for task in tasks {
+            let runs = weekday::runs_on_date(
+                &task.repeat_mode,
+                task.custom_days.as_deref(),
+                task.start_date.as_deref(),
+                task.end_date.as_deref(),
+                date,
+            ) && !(task.skip_holidays && is_holiday);
+            if !runs {
+                continue;
+            }
+            let playlist_id = weekday::playlist_for_weekday(task.weekday_playlists.as_deref(), day, task.playlist_id);
+            // cron 任务一天可能触发多次
+            for time in weekday::trigger_times(&task.repeat_mode, task.custom_days.as_deref(), task.time) {
+                let trigger = date.and_time(time);
+                if trigger > now {
+                    triggers.push((trigger, task, playlist_id));
+                }
+            }
+        }
+        date += Duration::days(1);
+    }
+    triggers.sort_by_key(|(trigger, _, _)| *trigger);
+    triggers
+}
+
+/// 正在播放的任务：结束时间、开始它的那次检查所在的分钟、任务名称
+struct Busy {
+    end: NaiveDateTime,
+    check_minute: NaiveDateTime,
+    task_name: String,
+}
+
+/// 模拟执行时间段内的任务，不实际播放。
+/// 与调度器的行为一致：同一次检查中匹配的任务依次排队执行；播放期间到点的任务，
+/// 只有在前一个任务结束后的下一次检查中仍处于匹配窗口（当前或前一分钟）时才会执行，否则错过
+fn simulate(
+    tasks: &[SimTask],
+    playlists: &mut HashMap<i64, PlaylistInfo>,
+    conn: &Connection,
+    start: NaiveDate,
+    end: NaiveDate,
+    now: NaiveDateTime,
+    pause_state: &pause::SchedulerPauseState,
+) -> rusqlite::Result<Vec<SimulatedExecution>> {
+    let triggers = expand_triggers(tasks, &holiday::load_holidays(conn), start, end, now);
+
+    let resume_at = pause_state
+        .resume_at
+        .as_deref()
+        .and_then(|value| NaiveDateTime::parse_from_str(value, TIME_FORMAT).ok());
+    let today = now.format(DATE_FORMAT).to_string();
+
+    let mut executions = Vec::new();
+    let mut once_done: Vec<i64> = Vec::new();
+    let mut busy: Option<Busy> = None;
+
+    for (trigger, task, playlist_id) in triggers {
+        if task.repeat_mode == "once" {
+            if task.last_executed.is_some() || once_done.contains(&task.id) {
+                continue;
+            }
+            once_done.push(task.id);
+        }
+
+        let playlist: &PlaylistInfo = match playlists.entry(playlist_id) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(load_playlist(conn, playlist_id)?),
+        };
+
+        let mut execution = SimulatedExecution {
+            task_id: task.id,
+            task_name: task.name.clone(),
+            scheduled_time: trigger.format(TIME_FORMAT).to_string(),
+            start_time: None,
+            end_time: None,
+            playlist_id,
+            playlist_name: playlist.as_ref().map(|(name, _)| name.clone()),
+            duration_secs: 0,
+            status: String::new(),
+            blocked_by: None,
+        };
+
+        let paused = !task.critical && pause_state.paused && resume_at.is_none_or(|resume_at| trigger < resume_at);
+        // cron 任务每次触发都会执行，不受当天已执行的限制
+        let ran_today = trigger.format(DATE_FORMAT).to_string() == today
+            && task.last_executed.as_deref() == Some(today.as_str())
+            && task.repeat_mode != "cron";
+
+        // 确定开始时间：空闲时准时开始，忙碌时排队或错过
+        let start_time = match &busy {
+            Some(current) if current.end > trigger => {
+                execution.blocked_by = Some(current.task_name.clone());
+                if trigger <= current.check_minute || trigger >= floor_minute(current.end) - Duration::minutes(1) {
+                    Some((current.end, true))
+                } else {
+                    None
+                }
+            }
+            _ => Some((trigger, false)),
+        };
+
+        execution.status = if paused {
+            "paused"
+        } else if ran_today {
+            "skipped"
+        } else {
+            match (start_time, playlist) {
+                (None, _) => "missed",
+                (Some(_), None) => "failed",
+                (Some(_), Some((_, tracks))) if tracks.is_empty() => "failed",
+                (Some((start, delayed)), Some((_, tracks))) => {
+                    let duration_secs = estimate_run_secs(task, tracks);
+                    let end_time = start + Duration::seconds(duration_secs);
+                    let check_minute = match &busy {
+                        Some(current) if delayed && trigger > current.check_minute => floor_minute(current.end),
+                        Some(current) if delayed => current.check_minute,
+                        _ => trigger,
+                    };
+                    execution.duration_secs = duration_secs;
+                    execution.start_time = Some(start.format(TIME_FORMAT).to_string());
+                    execution.end_time = Some(end_time.format(TIME_FORMAT).to_string());
+                    busy = Some(Busy { end: end_time, check_minute, task_name: task.name.clone() });
+                    if delayed { "delayed" } else { "run" }
+                }
+            }
+        }
+        .to_string();
+
+        executions.push(execution);
+    }
+
+    Ok(executions)
+}
+
+/// 模拟时间段内（默认从今天起 7 天）将发生的任务执行序列，包括排队延迟和被占用而错过的任务，不实际播放
+#[tauri::command]
+pub async fn simulate_schedule(
+    start_date: Option<String>,
+    end_date: Option<String>,
+    conn: State<'_, Arc<Mutex<Connection>>>,
+) -> Result<Vec<SimulatedExecution>, String> {
+    let now = Local::now().naive_local();
+    let parse_date = |value: &str| {
+        NaiveDate::parse_from_str(value, DATE_FORMAT).map_err(|_| i18n::trf("无效的日期: {}", &[&value]))
+    };
+    let start = match start_date.as_deref() {
+        Some(value) => parse_date(value)?,
+        None => now.date(),
+    };
+    let end = match end_date.as_deref() {
+        Some(value) => parse_date(value)?,
+        None => start + Duration::days(DEFAULT_SIMULATION_DAYS - 1),
+    };
+    if end < start {
+        return Err(i18n::tr("结束日期不能早于开始日期"));
+    }
+    if (end - start).num_days() >= MAX_SIMULATION_DAYS {
+        return Err(i18n::trf("模拟范围不能超过{}天", &[&MAX_SIMULATION_DAYS]));
+    }
+
+    let conn = conn.lock().await;
+    let pause_state = pause::load_pause_state(&conn);
+    let tasks = load_tasks(&conn).map_err(|e| e.to_string())?;
+    let mut playlists = HashMap::new();
+    simulate(&tasks, &mut playlists, &conn, start, end, now, &pause_state).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn task(id: i64, time: &str, repeat_mode: &str, custom_days: Option<&str>) -> SimTask {
+        SimTask {
+            id,
+            name: format!("任务{}", id),
+            time: NaiveTime::parse_from_str(time, "%H:%M").unwrap(),
+            repeat_mode: repeat_mode.to_string(),
+            custom_days: custom_days.map(str::to_string),
+            start_date: None,
+            end_date: None,
+            playlist_id: 1,
+            fade_in_duration: 0,
+            duration_minutes: None,
+            min_plays_per_track: None,
+            weekday_playlists: None,
+            last_executed: None,
+            critical: false,
+            skip_holidays: false,
+        }
+    }
+
+    fn date(value: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(value, DATE_FORMAT).unwrap()
+    }
+
+    fn time(value: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    fn expand(tasks: &[SimTask], holidays: &[&str], now: &str, days: i64) -> Vec<(String, i64, i64)> {
+        let holidays = holidays.iter().map(|day| date(day)).collect();
+        let now = time(now);
+        expand_triggers(tasks, &holidays, now.date(), now.date() + Duration::days(days - 1), now)
+            .into_iter()
+            .map(|(trigger, task, playlist_id)| (trigger.format("%m-%d %H:%M").to_string(), task.id, playlist_id))
+            .collect()
+    }
+
+    #[test]
+    fn triggers_follow_repeat_rules() {
+        // 2024-06-01 是周六：工作日任务从周一开始触发，不晚于当前时间的触发点不算
+        let tasks = [task(1, "07:30", "weekday", None), task(2, "08:00", "daily", None)];
+        let triggers = expand(&tasks, &[], "2024-06-01 08:00", 3);
+        assert_eq!(
+            triggers,
+            [("06-02 08:00".to_string(), 2, 1), ("06-03 07:30".to_string(), 1, 1), ("06-03 08:00".to_string(), 2, 1)]
+        );
+    }
+
+    #[test]
+    fn triggers_respect_date_range_holidays_and_weekday_playlists() {
+        let mut summer = task(1, "07:30", "daily", None);
+        summer.start_date = Some("2024-06-03".to_string());
+        summer.end_date = Some("2024-06-04".to_string());
+        let mut workday = task(2, "09:00", "weekday", None);
+        workday.skip_holidays = true;
+        workday.weekday_playlists = Some(r#"{"3":7}"#.to_string());
+        let triggers = expand(&[summer, workday], &["2024-06-04"], "2024-06-02 00:00", 4);
+        assert_eq!(
+            triggers,
+            [
+                ("06-03 07:30".to_string(), 1, 1),
+                ("06-03 09:00".to_string(), 2, 1),
+                ("06-04 07:30".to_string(), 1, 1),
+                ("06-05 09:00".to_string(), 2, 7),
+            ]
+        );
+    }
+
+    #[test]
+    fn cron_tasks_trigger_several_times_a_day() {
+        let tasks = [task(1, "00:00", "cron", Some("0 9-13/2 * * *")), task(2, "10:00", "daily", None)];
+        let triggers: Vec<String> = expand(&tasks, &[], "2024-06-03 09:00", 1).into_iter().map(|(t, _, _)| t).collect();
+        assert_eq!(triggers, ["06-03 10:00", "06-03 11:00", "06-03 13:00"]);
+    }
+
+    #[test]
+    fn run_length_estimate() {
+        let mut sample = task(1, "07:00", "daily", None);
+        assert_eq!(estimate_run_secs(&sample, &[60, 120]), 180);
+        sample.fade_in_duration = 4;
+        sample.min_plays_per_track = Some(2);
+        assert_eq!(estimate_run_secs(&sample, &[60, 120]), 2 * (180 + 2 * 5));
+        sample.duration_minutes = Some(5);
+        assert_eq!(estimate_run_secs(&sample, &[60, 120]), 300);
+    }
+}
@@ -0,0 +1,164 @@
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::Instant;
+use tokio::sync::Mutex;
+use tokio::time::{interval, sleep, Duration};
+use tauri::State;
+use crate::i18n;
+use crate::player::{AudioPlayer, PlaybackState};
+
+// 定时停止的检查间隔（毫秒）
+const CHECK_INTERVAL_MILLIS: u64 = 500;
+// 倒计时最后这么多秒逐渐减小音量，结束时停止
+const FADE_OUT_SECS: f64 = 10.0;
+// 倒计时最长时长（分钟）
+const MAX_MINUTES: f64 = 24.0 * 60.0;
+
+/// 定时停止的方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SleepMode {
+    /// 倒计时结束时渐弱停止
+    Countdown,
+    /// 播完当前曲目后停止
+    Track,
+    /// 播完当前播放列表后停止（循环模式下不再从头播放）
+    Playlist,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct SleepTimer {
+    pub mode: SleepMode,
+    // 倒计时结束的时刻，仅 Countdown 模式
+    ends_at: Option<Instant>,
+}
+
+impl SleepTimer {
+    fn countdown(minutes: f64) -> Self {
+        Self {
+            mode: SleepMode::Countdown,
+            ends_at: Some(Instant::now() + Duration::from_secs_f64(minutes * 60.0)),
+        }
+    }
+
+    /// 倒计时剩余的秒数，其他模式为 None
+    fn remaining_secs(&self) -> Option<f64> {
+        self.ends_at.map(|ends_at| ends_at.saturating_duration_since(Instant::now()).as_secs_f64())
+    }
+
+    /// 倒计时是否已经结束
+    pub fn is_expired(&self) -> bool {
+        self.ends_at.is_some_and(|ends_at| ends_at <= Instant::now())
+    }
+
+    /// 曲目播完时是否应停止，不再播放下一首；wraps 表示下一首要从播放列表开头重新播放（含单曲循环）
+    pub fn stops_at_track_end(&self, wraps: bool) -> bool {
+        match self.mode {
+            SleepMode::Countdown => self.is_expired(),
+            SleepMode::Track => true,
+            SleepMode::Playlist => wraps,
+        }
+    }
+
+    pub fn state(&self) -> SleepTimerState {
+        SleepTimerState { mode: self.mode, remaining_secs: self.remaining_secs() }
+    }
+}
+
+/// 返回给前端的定时停止状态
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SleepTimerState {
+    pub mode: SleepMode,
+    /// 倒计时剩余的秒数，其他模式为 None
+    pub remaining_secs: Option<f64>,
+}
+
+/// 启动定时停止监视器：倒计时进入最后几秒时逐渐减小音量，结束时停止播放并恢复原音量
+pub fn start_sleep_monitor(player: Arc<Mutex<AudioPlayer>>) {
+    tauri::async_runtime::spawn(async move {
+        let mut ticker = interval(Duration::from_millis(CHECK_INTERVAL_MILLIS));
+
+        loop {
+            ticker.tick().await;
+
+            let fading = {
+                let player = player.lock().await;
+                countdown_remaining(&player).is_some_and(|remaining| remaining <= FADE_OUT_SECS)
+            };
+            if fading {
+                fade_out_and_stop(&player).await;
+            }
+        }
+    });
+}
+
+/// 尚未结束的倒计时剩余的秒数
+fn countdown_remaining(player: &AudioPlayer) -> Option<f64> {
+    player
+        .sleep_timer()
+        .filter(|timer| timer.mode == SleepMode::Countdown && !timer.is_expired())
+        .and_then(|timer| timer.remaining_secs())
+}
+
+/// 按剩余时间逐渐减小音量，倒计时结束时停止；期间取消或延长定时则恢复原音量
+async fn fade_out_and_stop(player: &Arc<Mutex<AudioPlayer>>) {
+    let volume = player.lock().await.volume();
+
+    loop {
+        {
+            let mut player = player.lock().await;
+            let expired = player.sleep_timer().is_some_and(|timer| timer.mode == SleepMode::Countdown && timer.is_expired());
+            if expired {
+                println!("⏹️ [SleepTimer] 定时停止，停止播放");
+                player.stop_after_fade(volume);
+                return;
+            }
+            match countdown_remaining(&player).filter(|remaining| *remaining <= FADE_OUT_SECS) {
+                Some(remaining) => player.set_volume(volume * (remaining / FADE_OUT_SECS) as f32),
+                None => {
+                    player.set_volume(volume);
+                    return;
+                }
+            }
+        }
+        sleep(Duration::from_millis(CHECK_INTERVAL_MILLIS)).await;
+    }
+}
+
+/// 设置定时停止：countdown 为 minutes 分钟后渐弱停止（默认），
+/// track / playlist 为播完当前曲目 / 当前播放列表后停止
+#[tauri::command]
+pub async fn set_sleep_timer(
+    minutes: Option<f64>,
+    mode: Option<SleepMode>,
+    player: State<'_, Arc<Mutex<AudioPlayer>>>,
+) -> Result<PlaybackState, String> {
+    let mut player = player.lock().await;
+    let timer = match mode.unwrap_or(SleepMode::Countdown) {
+        SleepMode::Countdown => {
+            let minutes = minutes.unwrap_or_default();
+            if !(minutes > 0.0 && minutes <= MAX_MINUTES) {
+                return Err(i18n::tr("定时停止需在0-1440分钟之间"));
+            }
+            SleepTimer::countdown(minutes)
+        }
+        mode => {
+            if player.current_audio_id().is_none() {
+                return Err(i18n::tr("当前没有正在播放的音频"));
+            }
+            SleepTimer { mode, ends_at: None }
+        }
+    };
+
+    player.set_sleep_timer(Some(timer));
+    Ok(player.get_state())
+}
+
+#[tauri::command]
+pub async fn cancel_sleep_timer(
+    player: State<'_, Arc<Mutex<AudioPlayer>>>,
+) -> Result<PlaybackState, String> {
+    let mut player = player.lock().await;
+    player.set_sleep_timer(None);
+    Ok(player.get_state())
+}
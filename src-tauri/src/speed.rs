@@ -0,0 +1,125 @@
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use rusqlite::Connection;
+use tauri::State;
+use crate::i18n;
+use crate::player::{self, AudioPlayer, PlaybackState};
+
+// 加速/减速的步长保存在 app_settings 中
+const SPEED_STEP_KEY: &str = "speed_step";
+const DEFAULT_SPEED_STEP: f32 = 0.25;
+const MIN_SPEED_STEP: f32 = 0.05;
+const MAX_SPEED_STEP: f32 = 1.0;
+
+/// 常用倍速
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SpeedPreset {
+    /// 0.75 倍
+    Slow,
+    /// 1.0 倍
+    Normal,
+    /// 1.25 倍
+    Fast,
+    /// 1.5 倍
+    Faster,
+}
+
+const PRESETS: [SpeedPreset; 4] = [SpeedPreset::Slow, SpeedPreset::Normal, SpeedPreset::Fast, SpeedPreset::Faster];
+
+impl SpeedPreset {
+    pub fn speed(self) -> f32 {
+        match self {
+            SpeedPreset::Slow => 0.75,
+            SpeedPreset::Normal => 1.0,
+            SpeedPreset::Fast => 1.25,
+            SpeedPreset::Faster => 1.5,
+        }
+    }
+
+    /// 与倍速相同的预设，不是预设倍速时为 None
+    pub fn matching(speed: f32) -> Option<Self> {
+        PRESETS.into_iter().find(|preset| (preset.speed() - speed).abs() < 0.005)
+    }
+}
+
+pub fn load_speed_step(conn: &Connection) -> f32 {
+    conn.query_row(
+        "SELECT value FROM app_settings WHERE key = ?1",
+        [SPEED_STEP_KEY],
+        |row| row.get::<_, String>(0),
+    )
+    .ok()
+    .and_then(|value| value.parse().ok())
+    .unwrap_or(DEFAULT_SPEED_STEP)
+    .clamp(MIN_SPEED_STEP, MAX_SPEED_STEP)
+}
+
+/// 按步长调整倍速（取两位小数，避免累积误差），正在播放时立即应用
+async fn step_speed(
+    direction: f32,
+    player: &Arc<Mutex<AudioPlayer>>,
+    conn: &Arc<Mutex<Connection>>,
+) -> Result<PlaybackState, String> {
+    let step = load_speed_step(&*conn.lock().await);
+    let mut player = player.lock().await;
+    let speed = ((player.speed() + direction * step) * 100.0).round() / 100.0;
+    player::apply_speed(&mut player, conn, speed).await?;
+    Ok(player.get_state())
+}
+
+/// 按设置的步长加快倍速（不超过最大倍速）
+#[tauri::command]
+pub async fn speed_up(
+    player: State<'_, Arc<Mutex<AudioPlayer>>>,
+    conn: State<'_, Arc<Mutex<Connection>>>,
+) -> Result<PlaybackState, String> {
+    step_speed(1.0, &player, &conn).await
+}
+
+/// 按设置的步长放慢倍速（不低于最小倍速）
+#[tauri::command]
+pub async fn speed_down(
+    player: State<'_, Arc<Mutex<AudioPlayer>>>,
+    conn: State<'_, Arc<Mutex<Connection>>>,
+) -> Result<PlaybackState, String> {
+    step_speed(-1.0, &player, &conn).await
+}
+
+/// 切换到常用倍速（slow / normal / fast / faster）
+#[tauri::command]
+pub async fn set_speed_preset(
+    preset: SpeedPreset,
+    player: State<'_, Arc<Mutex<AudioPlayer>>>,
+    conn: State<'_, Arc<Mutex<Connection>>>,
+) -> Result<PlaybackState, String> {
+    let mut player = player.lock().await;
+    player::apply_speed(&mut player, &conn, preset.speed()).await?;
+    Ok(player.get_state())
+}
+
+#[tauri::command]
+pub async fn get_speed_step(
+    conn: State<'_, Arc<Mutex<Connection>>>,
+) -> Result<f32, String> {
+    Ok(load_speed_step(&*conn.lock().await))
+}
+
+#[tauri::command]
+pub async fn set_speed_step(
+    step: f32,
+    conn: State<'_, Arc<Mutex<Connection>>>,
+) -> Result<(), String> {
+    if !(MIN_SPEED_STEP..=MAX_SPEED_STEP).contains(&step) {
+        return Err(i18n::tr("倍速步长需在0.05-1.0之间"));
+    }
+    conn.lock()
+        .await
+        .execute(
+            "INSERT OR REPLACE INTO app_settings (key, value) VALUES (?1, ?2)",
+            (SPEED_STEP_KEY, step.to_string()),
+        )
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
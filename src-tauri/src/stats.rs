@@ -3,6 +3,15 @@ use tauri::State;
 use tokio::sync::Mutex;
 use rusqlite::Connection;
 use serde::Serialize;
+use chrono::{Duration, Local, NaiveDate, NaiveDateTime};
+use crate::holiday;
+use crate::i18n;
+use crate::playlist;
+use crate::weekday;
+
+// 相邻两次播放间隔超过该分钟数时，算作新的收听时段
+const SESSION_GAP_MINUTES: i64 = 30;
+const SESSION_TIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
 
 #[derive(Serialize)]
 pub struct Statistics {
@@ -11,6 +20,40 @@ pub struct Statistics {
     pub total_play_duration: i64,
     pub this_week_play_count: i64,
     pub this_month_play_count: i64,
+    /// 设置了每周目标的播放列表本周的完成情况
+    pub weekly_targets: Vec<WeeklyTargetProgress>,
+}
+
+#[derive(Serialize)]
+pub struct WeeklyTargetProgress {
+    pub playlist_id: i64,
+    pub playlist_name: String,
+    /// 每周目标遍数
+    pub target: i64,
+    /// 本周已完成的遍数
+    pub completions: i64,
+}
+
+fn load_weekly_targets(conn: &Connection) -> rusqlite::Result<Vec<WeeklyTargetProgress>> {
+    let targets = conn
+        .prepare(
+            "SELECT id, name, weekly_target FROM playlists
+             WHERE weekly_target IS NOT NULL AND deleted_at IS NULL
+             ORDER BY name",
+        )?
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?
+        .collect::<rusqlite::Result<Vec<(i64, String, i64)>>>()?;
+    targets
+        .into_iter()
+        .map(|(playlist_id, playlist_name, target)| {
+            Ok(WeeklyTargetProgress {
+                completions: playlist::week_completions(conn, playlist_id)?,
+                playlist_id,
+                playlist_name,
+                target,
+            })
+        })
+        .collect()
 }
 
 #[derive(Serialize)]
@@ -18,7 +61,7 @@ pub struct TopAudio {
     pub id: i64,
     pub name: String,
     pub play_count: i64,
-    pub duration: i64,
+    pub duration: Option<i64>,
 }
 
 #[tauri::command]
@@ -66,12 +109,15 @@ pub async fn get_statistics(
         )
         .unwrap_or(0);
 
+    let weekly_targets = load_weekly_targets(&conn).map_err(|e| e.to_string())?;
+
     Ok(Statistics {
         total_audio_count,
         total_play_count,
         total_play_duration,
         this_week_play_count,
         this_month_play_count,
+        weekly_targets,
     })
 }
 
@@ -236,3 +282,333 @@ pub async fn get_monthly_playback(
 
     Ok(result)
 }
+
+/// 收听时段中的一次播放
+#[derive(Debug, Serialize)]
+pub struct SessionTrack {
+    pub audio_id: i64,
+    pub audio_name: String,
+    pub playlist_name: Option<String>,
+    /// 开始播放时间（本地时间）
+    pub play_time: String,
+    /// 估算的收听时长（秒）：曲目时长，被下一次播放打断时截至下一次开始
+    pub listened_secs: i64,
+}
+
+/// 连续收听的一个时段（相邻播放间隔不超过 SESSION_GAP_MINUTES 分钟）
+#[derive(Debug, Serialize)]
+pub struct ListeningSession {
+    pub start_time: String,
+    pub end_time: String,
+    pub duration_secs: i64,
+    pub playlist_names: Vec<String>,
+    pub tracks: Vec<SessionTrack>,
+}
+
+struct PlayRecord {
+    audio_id: i64,
+    audio_name: String,
+    playlist_name: Option<String>,
+    start: NaiveDateTime,
+    duration_secs: i64,
+}
+
+/// 按播放间隔把播放记录（按时间升序）分组为收听时段
+fn group_sessions(plays: Vec<PlayRecord>, gap: Duration) -> Vec<ListeningSession> {
+    let next_starts: Vec<Option<NaiveDateTime>> = plays.iter().skip(1).map(|p| Some(p.start)).chain([None]).collect();
+    let mut sessions: Vec<ListeningSession> = Vec::new();
+    let mut session_start = None;
+    let mut session_end = None;
+
+    for (play, next_start) in plays.into_iter().zip(next_starts) {
+        let mut end = play.start + Duration::seconds(play.duration_secs.max(0));
+        if let Some(next_start) = next_start {
+            end = end.min(next_start.max(play.start));
+        }
+
+        let continues = session_end.is_some_and(|last_end: NaiveDateTime| play.start - last_end <= gap);
+        if !continues {
+            session_start = Some(play.start);
+            sessions.push(ListeningSession {
+                start_time: play.start.format(SESSION_TIME_FORMAT).to_string(),
+                end_time: String::new(),
+                duration_secs: 0,
+                playlist_names: Vec::new(),
+                tracks: Vec::new(),
+            });
+        }
+        session_end = Some(session_end.filter(|_| continues).map_or(end, |last: NaiveDateTime| last.max(end)));
+
+        let Some(session) = sessions.last_mut() else { continue };
+        if let Some(name) = &play.playlist_name {
+            if !session.playlist_names.contains(name) {
+                session.playlist_names.push(name.clone());
+            }
+        }
+        if let (Some(start), Some(end)) = (session_start, session_end) {
+            session.end_time = end.format(SESSION_TIME_FORMAT).to_string();
+            session.duration_secs = (end - start).num_seconds();
+        }
+        session.tracks.push(SessionTrack {
+            audio_id: play.audio_id,
+            audio_name: play.audio_name,
+            playlist_name: play.playlist_name,
+            play_time: play.start.format(SESSION_TIME_FORMAT).to_string(),
+            listened_secs: (end - play.start).num_seconds(),
+        });
+    }
+
+    sessions
+}
+
+/// 解析本地日期范围（YYYY-MM-DD），开始日期默认今天，结束日期默认与开始日期相同
+pub(crate) fn parse_date_range(start_date: Option<&str>, end_date: Option<&str>) -> Result<(NaiveDate, NaiveDate), String> {
+    let parse_date = |value: &str| {
+        NaiveDate::parse_from_str(value, "%Y-%m-%d").map_err(|_| i18n::trf("无效的日期: {}", &[&value]))
+    };
+    let start = match start_date {
+        Some(value) => parse_date(value)?,
+        None => Local::now().date_naive(),
+    };
+    let end = match end_date {
+        Some(value) => parse_date(value)?,
+        None => start,
+    };
+    if end < start {
+        return Err(i18n::tr("结束日期不能早于开始日期"));
+    }
+    Ok((start, end))
+}
+
+/// 本地日期范围（YYYY-MM-DD，含两端）内的播放记录，按收听时段分组，时间正序
+pub(crate) fn load_sessions(conn: &Connection, start: &str, end: &str) -> rusqlite::Result<Vec<ListeningSession>> {
+    let plays = conn
+        .prepare(
+            "SELECT ph.audio_id, ph.audio_name, ph.playlist_name,
+                    datetime(ph.play_time, 'localtime') AS local_time, COALESCE(af.duration, 0)
+             FROM playback_history ph
+             LEFT JOIN audio_files af ON ph.audio_id = af.id
+             WHERE DATE(ph.play_time, 'localtime') >= ?1 AND DATE(ph.play_time, 'localtime') <= ?2
+             ORDER BY local_time, ph.id",
+        )?
+        .query_map([start, end], |row| {
+            let local_time: String = row.get(3)?;
+            Ok((row.get(0)?, row.get(1)?, row.get(2)?, local_time, row.get(4)?))
+        })?
+        .collect::<rusqlite::Result<Vec<(i64, String, Option<String>, String, i64)>>>()?
+        .into_iter()
+        .filter_map(|(audio_id, audio_name, playlist_name, local_time, duration_secs)| {
+            let start = NaiveDateTime::parse_from_str(&local_time, SESSION_TIME_FORMAT).ok()?;
+            Some(PlayRecord { audio_id, audio_name, playlist_name, start, duration_secs })
+        })
+        .collect();
+
+    Ok(group_sessions(plays, Duration::minutes(SESSION_GAP_MINUTES)))
+}
+
+/// 按收听时段分组的播放记录（本地日期范围，默认今天），最近的时段在前
+#[tauri::command]
+pub async fn get_sessions(
+    start_date: Option<String>,
+    end_date: Option<String>,
+    conn: State<'_, Arc<Mutex<Connection>>>,
+) -> Result<Vec<ListeningSession>, String> {
+    let (start, end) = parse_date_range(start_date.as_deref(), end_date.as_deref())?;
+    let mut sessions = load_sessions(
+        &*conn.lock().await,
+        &start.format("%Y-%m-%d").to_string(),
+        &end.format("%Y-%m-%d").to_string(),
+    )
+    .map_err(|e| e.to_string())?;
+    sessions.reverse();
+    Ok(sessions)
+}
+
+/// 一条任务执行记录
+#[derive(Debug, Serialize)]
+pub struct ExecutionRecord {
+    pub id: i64,
+    pub task_id: i64,
+    pub task_name: Option<String>,
+    /// 执行时间（本地时间）
+    pub execution_time: String,
+    pub status: String,
+    pub playlist_name: Option<String>,
+}
+
+/// 某天计划执行的任务及其实际执行情况
+#[derive(Debug, Serialize)]
+pub struct PlannedTask {
+    pub task_id: i64,
+    pub task_name: String,
+    /// 计划时间（HH:MM）
+    pub scheduled_time: String,
+    /// 按星期映射选出的播放列表
+    pub playlist_id: i64,
+    pub playlist_name: Option<String>,
+    /// 已执行时为执行记录的状态，否则为 pending（尚未到时间）或 missed（已过时间未执行）
+    pub status: String,
+    pub execution: Option<ExecutionRecord>,
+}
+
+/// 某天的计划与实际：计划任务、计划外的执行记录和播放记录
+#[derive(Debug, Serialize)]
+pub struct DaySchedule {
+    pub date: String,
+    pub planned: Vec<PlannedTask>,
+    /// 不在当天计划中的执行记录（任务之后被修改、停用或删除）
+    pub unplanned_executions: Vec<ExecutionRecord>,
+    pub playback: Vec<SessionTrack>,
+}
+
+struct DayTask {
+    id: i64,
+    name: String,
+    hour: u32,
+    minute: u32,
+    repeat_mode: String,
+    custom_days: Option<String>,
+    start_date: Option<String>,
+    end_date: Option<String>,
+    playlist_id: i64,
+    weekday_playlists: Option<String>,
+    /// 是否在这一天之前执行过（单次任务只执行一次）
+    executed_before: bool,
+    /// 节假日不执行
+    skip_holidays: bool,
+}
+
+fn load_day_tasks(conn: &Connection, date: &str) -> rusqlite::Result<Vec<DayTask>> {
+    conn.prepare(
+        "SELECT st.id, st.name, st.hour, st.minute, st.repeat_mode, st.custom_days, st.playlist_id,
+                st.weekday_playlists,
+                EXISTS(SELECT 1 FROM execution_history eh
+                       WHERE eh.task_id = st.id AND DATE(eh.execution_time, 'localtime') < ?1),
+                st.start_date, st.end_date, st.skip_holidays
+         FROM scheduled_tasks st
+         WHERE st.is_enabled = 1 AND st.deleted_at IS NULL
+           AND DATE(st.created_date, 'localtime') <= ?1
+         ORDER BY st.hour, st.minute, st.priority DESC",
+    )?
+    .query_map([date], |row| {
+        Ok(DayTask {
+            id: row.get(0)?,
+            name: row.get(1)?,
+            hour: row.get(2)?,
+            minute: row.get(3)?,
+            repeat_mode: row.get(4)?,
+            custom_days: row.get(5)?,
+            playlist_id: row.get(6)?,
+            weekday_playlists: row.get(7)?,
+            executed_before: row.get(8)?,
+            start_date: row.get(9)?,
+            end_date: row.get(10)?,
+            skip_holidays: row.get(11)?,
+        })
+    })?
+    .collect()
+}
+
+/// 本地日期范围（YYYY-MM-DD，含两端）内的任务执行记录，时间正序
+pub(crate) fn load_executions(conn: &Connection, start: &str, end: &str) -> rusqlite::Result<Vec<ExecutionRecord>> {
+    conn.prepare(
+        "SELECT eh.id, eh.task_id, st.name, datetime(eh.execution_time, 'localtime') AS local_time,
+                eh.status, eh.playlist_name
+         FROM execution_history eh
+         LEFT JOIN scheduled_tasks st ON eh.task_id = st.id
+         WHERE DATE(eh.execution_time, 'localtime') >= ?1 AND DATE(eh.execution_time, 'localtime') <= ?2
+         ORDER BY local_time, eh.id",
+    )?
+    .query_map([start, end], |row| {
+        Ok(ExecutionRecord {
+            id: row.get(0)?,
+            task_id: row.get(1)?,
+            task_name: row.get(2)?,
+            execution_time: row.get(3)?,
+            status: row.get(4)?,
+            playlist_name: row.get(5)?,
+        })
+    })?
+    .collect()
+}
+
+/// 某天的计划与实际（本地日期，默认今天）：当天应执行的任务与执行记录逐一对应，
+/// 并附上当天的播放记录，供日历一次取得
+#[tauri::command]
+pub async fn get_day_schedule(
+    date: Option<String>,
+    conn: State<'_, Arc<Mutex<Connection>>>,
+) -> Result<DaySchedule, String> {
+    let day = match date.as_deref() {
+        Some(value) => {
+            NaiveDate::parse_from_str(value, "%Y-%m-%d").map_err(|_| i18n::trf("无效的日期: {}", &[&value]))?
+        }
+        None => Local::now().date_naive(),
+    };
+    let date = day.format("%Y-%m-%d").to_string();
+    let weekday = weekday::weekday_index(&day);
+    let now = Local::now().naive_local();
+
+    let conn = conn.lock().await;
+    let tasks = load_day_tasks(&conn, &date).map_err(|e| e.to_string())?;
+    let mut executions = load_executions(&conn, &date, &date).map_err(|e| e.to_string())?;
+    let sessions = load_sessions(&conn, &date, &date).map_err(|e| e.to_string())?;
+    let is_holiday = holiday::is_holiday(&conn, day);
+
+    let mut planned = Vec::new();
+    for task in tasks {
+        // 与调度器一致：每个任务每天最多执行一次，取当天的第一条执行记录
+        let execution = executions
+            .iter()
+            .position(|e| e.task_id == task.id)
+            .map(|index| executions.remove(index));
+        let runs_today = weekday::runs_on_date(
+            &task.repeat_mode,
+            task.custom_days.as_deref(),
+            task.start_date.as_deref(),
+            task.end_date.as_deref(),
+            day,
+        ) && !(task.skip_holidays && is_holiday);
+        // 单次任务：执行过的那天，或者尚未执行时从今天起的每一天（直到执行）
+        let is_planned = match task.repeat_mode.as_str() {
+            "once" => execution.is_some() || (!task.executed_before && day >= now.date()),
+            _ => runs_today,
+        };
+        if !is_planned {
+            if let Some(execution) = execution {
+                executions.push(execution);
+            }
+            continue;
+        }
+
+        let scheduled = day.and_hms_opt(task.hour, task.minute, 0);
+        let status = match &execution {
+            Some(execution) => execution.status.clone(),
+            None if scheduled.is_some_and(|time| time > now) => "pending".to_string(),
+            None => "missed".to_string(),
+        };
+        let playlist_id = weekday::playlist_for_weekday(task.weekday_playlists.as_deref(), weekday, task.playlist_id);
+        let playlist_name = conn
+            .query_row(
+                "SELECT name FROM playlists WHERE id = ?1 AND deleted_at IS NULL",
+                [playlist_id],
+                |row| row.get(0),
+            )
+            .ok();
+
+        planned.push(PlannedTask {
+            task_id: task.id,
+            task_name: task.name,
+            scheduled_time: format!("{:02}:{:02}", task.hour, task.minute),
+            playlist_id,
+            playlist_name,
+            status,
+            execution,
+        });
+    }
+    executions.sort_by(|a, b| a.execution_time.cmp(&b.execution_time).then(a.id.cmp(&b.id)));
+
+    let playback = sessions.into_iter().flat_map(|session| session.tracks).collect();
+
+    Ok(DaySchedule { date, planned, unplanned_executions: executions, playback })
+}
@@ -0,0 +1,450 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use rusqlite::Connection;
+use tauri::State;
+use crate::formats;
+use crate::player::AudioPlayer;
+use crate::library_events::LibraryEvents;
+use crate::probe::DurationProbeQueue;
+use crate::i18n;
+
+// 存储布局保存在 app_settings 中
+const LAYOUT_SETTING_KEY: &str = "storage_layout";
+// 可读文件名的最大字符数（不含扩展名和序号）
+const MAX_NAME_CHARS: usize = 80;
+// 最近这么多分钟内修改过的文件不算遗留文件（可能正在导入、转码或录音）
+const ORPHAN_MIN_AGE_MINUTES: u64 = 10;
+// 录音保存的子目录
+pub(crate) const RECORDING_DIR: &str = "rec";
+
+/// 音频目录中文件夹的划分方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FolderLayout {
+    /// 全部放在音频目录下
+    #[default]
+    Flat,
+    /// 按导入的年份/月份，如 2026/05
+    YearMonth,
+    /// 按来源：imported、recordings、video、online、samples
+    Source,
+}
+
+/// 导入音频库的文件的存放方式，只影响之后导入的文件，已有文件可用 reorganize_library 整理
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StorageLayout {
+    pub folders: FolderLayout,
+    /// 使用音频名称作为文件名（重名时加序号），否则使用时间戳加随机串
+    pub readable_names: bool,
+}
+
+pub fn load_layout(conn: &Connection) -> StorageLayout {
+    conn.query_row(
+        "SELECT value FROM app_settings WHERE key = ?1",
+        [LAYOUT_SETTING_KEY],
+        |row| row.get::<_, String>(0),
+    )
+    .ok()
+    .and_then(|value| serde_json::from_str(&value).ok())
+    .unwrap_or_default()
+}
+
+/// 按来源类型划分的文件夹名
+fn source_folder(source_type: Option<&str>) -> &'static str {
+    match source_type {
+        Some(crate::archive::RECORDING_SOURCE_TYPE) => "recordings",
+        Some("video") => "video",
+        Some("online") => "online",
+        Some(crate::samples::SAMPLE_SOURCE_TYPE) => "samples",
+        _ => "imported",
+    }
+}
+
+/// 去掉文件名中不允许的字符，过长时截断
+fn sanitize_name(name: &str) -> String {
+    let cleaned: String = name
+        .chars()
+        .map(|c| match c {
+            '<' | '>' | ':' | '"' | '/' | '\\' | '|' | '?' | '*' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .take(MAX_NAME_CHARS)
+        .collect();
+    cleaned.trim().trim_matches('.').trim().to_string()
+}
+
+fn generated_stem() -> String {
+    format!(
+        "{}_{}",
+        chrono::Local::now().format("%Y%m%d_%H%M%S"),
+        uuid::Uuid::new_v4().to_string().split('-').next().unwrap()
+    )
+}
+
+/// 目录中不存在的文件名：重名时在名称后加 _2、_3……（skip 为文件自身的当前路径，整理时不算重名）
+fn unique_filename(dir: &Path, stem: &str, extension: &str, skip: Option<&Path>) -> String {
+    let mut filename = format!("{}.{}", stem, extension);
+    let mut index = 2;
+    while dir.join(&filename).exists() && skip != Some(dir.join(&filename).as_path()) {
+        filename = format!("{}_{}.{}", stem, index, extension);
+        index += 1;
+    }
+    filename
+}
+
+/// 按布局计算文件所在的子目录（相对音频目录），upload_date 为导入时间（YYYY-MM-DD HH:MM:SS，None 表示现在）
+fn relative_dir(layout: &StorageLayout, source_type: Option<&str>, upload_date: Option<&str>) -> PathBuf {
+    match layout.folders {
+        FolderLayout::Flat => PathBuf::new(),
+        FolderLayout::YearMonth => {
+            let (year, month) = upload_date
+                .and_then(|date| Some((date.get(0..4)?.to_string(), date.get(5..7)?.to_string())))
+                .unwrap_or_else(|| {
+                    let now = chrono::Local::now();
+                    (now.format("%Y").to_string(), now.format("%m").to_string())
+                });
+            PathBuf::from(year).join(month)
+        }
+        FolderLayout::Source => PathBuf::from(source_folder(source_type)),
+    }
+}
+
+/// 按当前布局为新导入的文件分配文件名和路径（会创建所需的子目录）。
+/// name 为音频名称（可带扩展名），extension 为保存的格式
+pub(crate) fn managed_file_path(
+    layout: &StorageLayout,
+    audio_dir: &Path,
+    name: &str,
+    extension: &str,
+    source_type: Option<&str>,
+) -> Result<(String, PathBuf), String> {
+    let dir = audio_dir.join(relative_dir(layout, source_type, None));
+    std::fs::create_dir_all(&dir).map_err(|e| i18n::trf("创建音频目录失败: {}", &[&e]))?;
+    let filename = unique_filename(&dir, &file_stem(layout, name, extension), extension, None);
+    let path = dir.join(&filename);
+    Ok((filename, path))
+}
+
+/// 文件名（不含扩展名）：可读名称去掉与保存格式相同的扩展名，名称为空时使用生成的名称
+fn file_stem(layout: &StorageLayout, name: &str, extension: &str) -> String {
+    if !layout.readable_names {
+        return generated_stem();
+    }
+    let name = match Path::new(name).extension().and_then(|ext| ext.to_str()) {
+        Some(ext) if ext.eq_ignore_ascii_case(extension) => Path::new(name).file_stem().and_then(|s| s.to_str()).unwrap_or(name),
+        _ => name,
+    };
+    Some(sanitize_name(name)).filter(|stem| !stem.is_empty()).unwrap_or_else(generated_stem)
+}
+
+struct ManagedFile {
+    id: i64,
+    original_name: String,
+    file_path: String,
+    format: String,
+    source_type: Option<String>,
+    upload_date: Option<String>,
+}
+
+/// 可以整理的文件：由应用管理、未归档、不是按录音模板指定的目录保存的文件
+fn load_managed_files(conn: &Connection) -> rusqlite::Result<Vec<ManagedFile>> {
+    conn.prepare(
+        "SELECT af.id, af.original_name, af.file_path, af.format, af.source_type, datetime(af.upload_date, 'localtime')
+         FROM audio_files af
+         LEFT JOIN recording_templates rt ON af.recording_template_id = rt.id
+         WHERE af.is_managed = 1 AND af.archived_at IS NULL AND rt.folder IS NULL
+         ORDER BY af.id",
+    )?
+    .query_map([], |row| {
+        Ok(ManagedFile {
+            id: row.get(0)?,
+            original_name: row.get(1)?,
+            file_path: row.get(2)?,
+            format: row.get(3)?,
+            source_type: row.get(4)?,
+            upload_date: row.get(5)?,
+        })
+    })?
+    .collect()
+}
+
+/// 整理结果
+#[derive(Debug, Default, Serialize)]
+pub struct ReorganizeResult {
+    /// 已移动（预览时为需要移动）的文件数
+    pub moved: usize,
+    /// 已符合布局的文件数
+    pub unchanged: usize,
+    /// 正在播放、文件丢失、不在音频目录中或移动失败而跳过的文件数
+    pub skipped: usize,
+}
+
+/// 把已有的文件按当前布局移动和重命名，apply 不为 true 时只统计需要移动的文件
+#[tauri::command]
+pub async fn reorganize_library(
+    apply: Option<bool>,
+    conn: State<'_, Arc<Mutex<Connection>>>,
+    player: State<'_, Arc<Mutex<AudioPlayer>>>,
+    audio_dir: State<'_, PathBuf>,
+) -> Result<ReorganizeResult, String> {
+    let apply = apply.unwrap_or(false);
+    let (layout, files) = {
+        let conn = conn.lock().await;
+        (load_layout(&conn), load_managed_files(&conn).map_err(|e| e.to_string())?)
+    };
+    let mut result = ReorganizeResult::default();
+
+    for file in files {
+        let current = PathBuf::from(&file.file_path);
+        if !current.starts_with(audio_dir.as_path()) || !current.exists() {
+            result.skipped += 1;
+            continue;
+        }
+
+        let dir = audio_dir.join(relative_dir(&layout, file.source_type.as_deref(), file.upload_date.as_deref()));
+        // 使用生成的文件名时保留原有的文件名，只调整目录
+        let stem = match layout.readable_names {
+            true => file_stem(&layout, &file.original_name, &file.format),
+            false => current.file_stem().and_then(|s| s.to_str()).map(str::to_string).unwrap_or_else(generated_stem),
+        };
+        let extension = current.extension().and_then(|e| e.to_str()).unwrap_or(&file.format).to_string();
+        let filename = unique_filename(&dir, &stem, &extension, Some(&current));
+        let target = dir.join(&filename);
+        if target == current {
+            result.unchanged += 1;
+            continue;
+        }
+        if !apply {
+            result.moved += 1;
+            continue;
+        }
+
+        let in_use = {
+            let player = player.lock().await;
+            player.current_audio_id() == Some(file.id) || player.is_queued(file.id)
+        };
+        if in_use {
+            result.skipped += 1;
+            continue;
+        }
+
+        let moved = std::fs::create_dir_all(&dir).and_then(|_| std::fs::rename(&current, &target));
+        if let Err(e) = moved {
+            eprintln!("[Storage] 移动文件失败 {}: {}", file.file_path, e);
+            result.skipped += 1;
+            continue;
+        }
+        let updated = conn.lock().await.execute(
+            "UPDATE audio_files SET filename = ?1, file_path = ?2 WHERE id = ?3",
+            (&filename, target.to_string_lossy().to_string(), file.id),
+        );
+        if let Err(e) = updated {
+            // 数据库更新失败时把文件移回原处
+            eprintln!("[Storage] 更新文件路径失败 {}: {}", file.id, e);
+            let _ = std::fs::rename(&target, &current);
+            result.skipped += 1;
+            continue;
+        }
+        result.moved += 1;
+    }
+
+    Ok(result)
+}
+
+#[tauri::command]
+pub async fn get_storage_layout(
+    conn: State<'_, Arc<Mutex<Connection>>>,
+) -> Result<StorageLayout, String> {
+    Ok(load_layout(&*conn.lock().await))
+}
+
+/// 保存存储布局，之后导入的文件按新布局存放
+#[tauri::command]
+pub async fn set_storage_layout(
+    layout: StorageLayout,
+    conn: State<'_, Arc<Mutex<Connection>>>,
+) -> Result<(), String> {
+    let value = serde_json::to_string(&layout).map_err(|e| e.to_string())?;
+    conn.lock()
+        .await
+        .execute(
+            "INSERT OR REPLACE INTO app_settings (key, value) VALUES (?1, ?2)",
+            (LAYOUT_SETTING_KEY, &value),
+        )
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// 音频目录中没有被音频库引用的文件（导入中途崩溃、取消的录音等留下的）
+#[derive(Debug, Clone, Serialize)]
+pub struct OrphanedFile {
+    pub path: String,
+    pub size: u64,
+    /// 最后修改时间（本地时间 YYYY-MM-DD HH:MM:SS）
+    pub modified: Option<String>,
+    /// 是否可以重新导入音频库（播放器能直接播放的格式）
+    pub importable: bool,
+}
+
+/// 数据库中引用的所有文件：音频库（含已归档、已删除待清理的）和倍速预渲染版本
+fn referenced_paths(conn: &Connection) -> rusqlite::Result<HashSet<PathBuf>> {
+    conn.prepare("SELECT file_path FROM audio_files UNION SELECT file_path FROM audio_speed_variants")?
+        .query_map([], |row| row.get::<_, String>(0).map(PathBuf::from))?
+        .collect()
+}
+
+/// 递归收集目录中的文件（跳过隐藏文件）
+fn collect_files(dir: &Path, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if entry.file_name().to_string_lossy().starts_with('.') {
+            continue;
+        }
+        match entry.file_type() {
+            Ok(file_type) if file_type.is_dir() => collect_files(&path, files),
+            Ok(file_type) if file_type.is_file() => files.push(path),
+            _ => {}
+        }
+    }
+}
+
+fn orphaned_files(conn: &Connection, audio_dir: &Path) -> Result<Vec<OrphanedFile>, String> {
+    let referenced = referenced_paths(conn).map_err(|e| e.to_string())?;
+    let mut files = Vec::new();
+    collect_files(audio_dir, &mut files);
+    files.sort();
+
+    let min_age = std::time::Duration::from_secs(ORPHAN_MIN_AGE_MINUTES * 60);
+    Ok(files
+        .into_iter()
+        .filter(|path| !referenced.contains(path))
+        .filter_map(|path| {
+            let metadata = std::fs::metadata(&path).ok()?;
+            let modified = metadata.modified().ok();
+            if modified.and_then(|time| time.elapsed().ok()).is_some_and(|age| age < min_age) {
+                return None;
+            }
+            let importable = path.extension().and_then(|ext| ext.to_str()).is_some_and(formats::is_native);
+            Some(OrphanedFile {
+                path: path.to_string_lossy().to_string(),
+                size: metadata.len(),
+                modified: modified.map(|time| {
+                    chrono::DateTime::<chrono::Local>::from(time).format("%Y-%m-%d %H:%M:%S").to_string()
+                }),
+                importable,
+            })
+        })
+        .collect())
+}
+
+/// 列出音频目录（含录音、归档等子目录）中没有被音频库引用的文件
+#[tauri::command]
+pub async fn find_orphaned_files(
+    conn: State<'_, Arc<Mutex<Connection>>>,
+    audio_dir: State<'_, PathBuf>,
+) -> Result<Vec<OrphanedFile>, String> {
+    let conn = conn.lock().await;
+    orphaned_files(&conn, &audio_dir)
+}
+
+/// 批量处理遗留文件的结果
+#[derive(Debug, Default, Serialize)]
+pub struct OrphanActionResult {
+    /// 已删除（或已导入）的文件数
+    pub processed: usize,
+    /// 已不是遗留文件、无法导入或处理失败而跳过的文件数
+    pub skipped: usize,
+}
+
+/// 删除遗留文件：只删除当前仍未被引用的文件，避免误删
+#[tauri::command]
+pub async fn delete_orphaned_files(
+    paths: Vec<String>,
+    conn: State<'_, Arc<Mutex<Connection>>>,
+    audio_dir: State<'_, PathBuf>,
+) -> Result<OrphanActionResult, String> {
+    let conn = conn.lock().await;
+    let orphans: HashSet<String> = orphaned_files(&conn, &audio_dir)?.into_iter().map(|file| file.path).collect();
+    let mut result = OrphanActionResult::default();
+
+    for path in paths {
+        if !orphans.contains(&path) {
+            result.skipped += 1;
+            continue;
+        }
+        match std::fs::remove_file(&path) {
+            Ok(()) => result.processed += 1,
+            Err(e) => {
+                eprintln!("[Storage] 删除遗留文件失败 {}: {}", path, e);
+                result.skipped += 1;
+            }
+        }
+    }
+    Ok(result)
+}
+
+/// 把遗留文件重新导入音频库（文件保留在原处，录音目录中的文件记为录音），时长由后台检测队列补全
+#[tauri::command]
+pub async fn import_orphaned_files(
+    paths: Vec<String>,
+    conn: State<'_, Arc<Mutex<Connection>>>,
+    audio_dir: State<'_, PathBuf>,
+    probe_queue: State<'_, DurationProbeQueue>,
+    library: State<'_, LibraryEvents>,
+) -> Result<OrphanActionResult, String> {
+    let conn = conn.lock().await;
+    let orphans: HashSet<String> = orphaned_files(&conn, &audio_dir)?
+        .into_iter()
+        .filter(|file| file.importable)
+        .map(|file| file.path)
+        .collect();
+    let recording_dir = audio_dir.join(RECORDING_DIR);
+    let mut result = OrphanActionResult::default();
+
+    for path in paths {
+        if !orphans.contains(&path) {
+            result.skipped += 1;
+            continue;
+        }
+        let file = PathBuf::from(&path);
+        let filename = file.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+        let format = file.extension().map(|ext| ext.to_string_lossy().to_lowercase()).unwrap_or_default();
+        let file_size = std::fs::metadata(&file).map(|metadata| metadata.len() as i64).unwrap_or(0);
+        let source_type = file.starts_with(&recording_dir).then_some(crate::archive::RECORDING_SOURCE_TYPE);
+
+        match conn.execute(
+            "INSERT INTO audio_files (filename, original_name, file_path, file_size, duration, format, upload_date, is_managed, source_type)
+             VALUES (?1, ?2, ?3, ?4, NULL, ?5, ?6, 1, ?7)",
+            (
+                &filename,
+                &filename,
+                &path,
+                file_size,
+                &format,
+                chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+                source_type,
+            ),
+        ) {
+            Ok(_) => {
+                result.processed += 1;
+                library.added([conn.last_insert_rowid()]);
+            }
+            Err(e) => {
+                eprintln!("[Storage] 导入遗留文件失败 {}: {}", path, e);
+                result.skipped += 1;
+            }
+        }
+    }
+
+    if result.processed > 0 {
+        probe_queue.wake();
+    }
+    Ok(result)
+}
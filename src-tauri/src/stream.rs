@@ -0,0 +1,88 @@
+use std::error::Error;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use rusqlite::Connection;
+use tauri::http::header::{ACCEPT_RANGES, ACCESS_CONTROL_ALLOW_ORIGIN, CONTENT_LENGTH, CONTENT_RANGE, CONTENT_TYPE};
+use tauri::http::status::StatusCode;
+use tauri::http::{HttpRange, Request, Response, ResponseBuilder};
+use tauri::{AppHandle, Manager};
+use crate::formats;
+
+/// 音频流协议名。前端用 convertFileSrc(String(audioId), STREAM_SCHEME) 得到地址，
+/// 直接交给 <audio> 元素播放
+pub const STREAM_SCHEME: &str = "stream";
+
+// 单个范围请求最多返回的字节数，播放器会按需继续请求后面的部分
+const MAX_RANGE_BYTES: u64 = 1024 * 1024;
+
+fn empty_response(status: StatusCode) -> Result<Response, Box<dyn Error>> {
+    ResponseBuilder::new().status(status).body(Vec::new())
+}
+
+/// 从请求地址中取出音频ID（stream://localhost/<id>，Windows 上为 https://stream.localhost/<id>）
+fn parse_audio_id(uri: &str) -> Option<i64> {
+    let url = tauri::Url::parse(uri).ok()?;
+    url.path_segments()?.next_back()?.parse().ok()
+}
+
+/// 音频流协议处理：只按ID提供音频库中的文件（不接受任意路径），支持 Range 请求以便拖动进度
+pub fn handle_stream_request(app: &AppHandle, request: &Request) -> Result<Response, Box<dyn Error>> {
+    let Some(audio_id) = parse_audio_id(request.uri()) else {
+        return empty_response(StatusCode::BAD_REQUEST);
+    };
+    let Some(db) = app.try_state::<Arc<Mutex<Connection>>>() else {
+        return empty_response(StatusCode::SERVICE_UNAVAILABLE);
+    };
+    // 协议处理运行在窗口事件线程上，不在异步运行时中，可以阻塞等待锁
+    let file_path: Option<String> = db
+        .blocking_lock()
+        .query_row("SELECT file_path FROM audio_files WHERE id = ?1", [audio_id], |row| row.get(0))
+        .ok();
+    let Some(file_path) = file_path else {
+        return empty_response(StatusCode::NOT_FOUND);
+    };
+    let Ok(mut file) = File::open(&file_path) else {
+        return empty_response(StatusCode::NOT_FOUND);
+    };
+    let len = file.metadata()?.len();
+
+    let response = ResponseBuilder::new()
+        .header(ACCESS_CONTROL_ALLOW_ORIGIN, "*")
+        .header(ACCEPT_RANGES, "bytes")
+        .header(CONTENT_TYPE, formats::mime_type_for_path(Path::new(&file_path)));
+
+    let range_header = request
+        .headers()
+        .get("range")
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.to_string());
+    let Some(range_header) = range_header else {
+        let mut buf = Vec::with_capacity(len as usize);
+        file.read_to_end(&mut buf)?;
+        return response.header(CONTENT_LENGTH, len).body(buf);
+    };
+
+    // 只处理第一个范围，多段范围请求对音频播放没有意义
+    let range = HttpRange::parse(&range_header, len).ok().and_then(|ranges| ranges.first().copied());
+    let Some(range) = range.filter(|range| range.length > 0 && range.start < len) else {
+        return ResponseBuilder::new()
+            .status(StatusCode::RANGE_NOT_SATISFIABLE)
+            .header(CONTENT_RANGE, format!("bytes */{}", len))
+            .body(Vec::new());
+    };
+    let nbytes = range.length.min(MAX_RANGE_BYTES).min(len - range.start);
+    let end = range.start + nbytes - 1;
+
+    let mut buf = Vec::with_capacity(nbytes as usize);
+    file.seek(SeekFrom::Start(range.start))?;
+    file.take(nbytes).read_to_end(&mut buf)?;
+
+    response
+        .status(StatusCode::PARTIAL_CONTENT)
+        .header(CONTENT_RANGE, format!("bytes {}-{}/{}", range.start, end, len))
+        .header(CONTENT_LENGTH, nbytes)
+        .body(buf)
+}
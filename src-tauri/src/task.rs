@@ -2,7 +2,13 @@ use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use tokio::sync::Mutex;
 use rusqlite::Connection;
+use chrono::{NaiveDate, Timelike};
 use tauri::State;
+use crate::playlist::PLAYLIST_DURATION_SQL;
+use crate::scheduler::{self, NextTask, SchedulerWake};
+use crate::cron::CronSchedule;
+use crate::weekday;
+use crate::i18n;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ScheduledTask {
@@ -17,26 +23,198 @@ pub struct ScheduledTask {
     pub volume: i64,
     pub fade_in_duration: i64,
     pub duration_minutes: Option<i64>,
+    /// 达到时长限制时的渐弱时长（秒），渐弱在结束时刻完成
+    pub fade_out_duration: i64,
+    pub volume_curve: Option<String>,
+    pub min_plays_per_track: Option<i64>,
+    /// 覆盖播放列表的播放顺序：sequential/shuffle/reverse/newest_first，为空时沿用播放列表
+    pub play_order: Option<String>,
+    /// 按星期指定的播放列表（JSON，如 {"1":3,"2":5}），未指定的星期使用 playlist_id
+    pub weekday_playlists: Option<String>,
+    /// 工作日错过（未开机）时，周六上午自动补播一次
+    pub weekend_makeup: bool,
+    /// 重要任务（如吃药提醒）：暂停定时任务期间照常执行，播放时取消静音
+    pub critical: bool,
+    /// 任务类型：play 播放播放列表，stop 停止当前播放，lower_volume 把音量降到 volume；
+    /// 后两种不播放 playlist_id，fade_out_duration 为停止或降低音量的渐变时长
+    pub action: String,
+    /// 有效日期范围（YYYY-MM-DD，含两端，None 表示不限）；repeat_mode 为 date 时两者相同，即执行的那一天
+    pub start_date: Option<String>,
+    pub end_date: Option<String>,
+    /// 节假日日历中的日子不执行（如工作日的起床播放）
+    pub skip_holidays: bool,
     pub is_enabled: bool,
     pub priority: i64,
     pub created_date: String,
 }
 
-#[tauri::command]
-pub async fn get_scheduled_tasks(
-    conn: State<'_, Arc<Mutex<Connection>>>,
-) -> Result<Vec<ScheduledTask>, String> {
-    let conn = conn.lock().await;
-    let mut stmt = conn
-        .prepare(
-            "SELECT st.id, st.name, st.hour, st.minute, st.repeat_mode, st.custom_days,
-                    st.playlist_id, p.name as playlist_name, st.volume, st.fade_in_duration,
-                    st.duration_minutes, st.is_enabled, st.priority, st.created_date
-             FROM scheduled_tasks st
-             JOIN playlists p ON st.playlist_id = p.id
-             ORDER BY st.hour, st.minute"
-        )
-        .map_err(|e| e.to_string())?;
+/// 音量曲线上的一个点：任务开始后第 minute 分钟的音量（0-100）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VolumePoint {
+    pub minute: f64,
+    pub volume: i64,
+}
+
+/// 解析并校验音量曲线JSON，例如 [{"minute":0,"volume":60},{"minute":30,"volume":20}]
+pub fn parse_volume_curve(curve: &str) -> Result<Vec<VolumePoint>, String> {
+    let points: Vec<VolumePoint> = serde_json::from_str(curve)
+        .map_err(|e| i18n::trf("音量曲线格式错误: {}", &[&e]))?;
+
+    if points.is_empty() {
+        return Err(i18n::tr("音量曲线至少需要一个点"));
+    }
+    if points.iter().any(|p| p.minute < 0.0 || !(0..=100).contains(&p.volume)) {
+        return Err(i18n::tr("音量曲线的时间不能为负，音量必须在0-100之间"));
+    }
+    if points.windows(2).any(|w| w[1].minute <= w[0].minute) {
+        return Err(i18n::tr("音量曲线的时间点必须递增"));
+    }
+
+    Ok(points)
+}
+
+/// 按线性插值计算任务开始后 elapsed_minutes 分钟时的音量（0-100）
+pub fn volume_at(curve: &[VolumePoint], elapsed_minutes: f64) -> i64 {
+    let first = &curve[0];
+    let last = &curve[curve.len() - 1];
+
+    if elapsed_minutes <= first.minute {
+        return first.volume;
+    }
+    if elapsed_minutes >= last.minute {
+        return last.volume;
+    }
+
+    for w in curve.windows(2) {
+        let (a, b) = (&w[0], &w[1]);
+        if elapsed_minutes <= b.minute {
+            let ratio = (elapsed_minutes - a.minute) / (b.minute - a.minute);
+            return (a.volume as f64 + (b.volume - a.volume) as f64 * ratio).round() as i64;
+        }
+    }
+
+    last.volume
+}
+
+/// 规范化 custom_days（星期约定见 weekday 模块：0=周日 … 6=周六）；cron 模式下为 cron 表达式
+fn normalize_custom_days(repeat_mode: &str, custom_days: Option<String>) -> Result<Option<String>, String> {
+    if repeat_mode == "cron" {
+        let expression = custom_days
+            .map(|expression| expression.split_whitespace().collect::<Vec<_>>().join(" "))
+            .filter(|expression| !expression.is_empty())
+            .ok_or_else(|| i18n::tr("请输入 cron 表达式"))?;
+        CronSchedule::parse(&expression)?;
+        return Ok(Some(expression));
+    }
+    custom_days
+        .map(|days| weekday::normalize_custom_days(&days).ok_or_else(|| i18n::trf("无效的自定义星期: {}", &[&days])))
+        .transpose()
+}
+
+/// cron 任务的 hour/minute 记为一天中第一次触发的时刻（用于排序和显示），其余任务不变
+fn cron_first_time(repeat_mode: &str, custom_days: Option<&str>, hour: i64, minute: i64) -> (i64, i64) {
+    let first = (repeat_mode == "cron")
+        .then(|| CronSchedule::parse(custom_days?).ok()?.times().next())
+        .flatten();
+    first.map_or((hour, minute), |time| (time.hour() as i64, time.minute() as i64))
+}
+
+/// 校验并规范化有效日期范围：date 模式必须指定日期，结束日期与开始日期相同
+fn normalize_date_range(
+    repeat_mode: &str,
+    start_date: Option<String>,
+    end_date: Option<String>,
+) -> Result<(Option<String>, Option<String>), String> {
+    let parse = |date: Option<String>| -> Result<Option<NaiveDate>, String> {
+        date.filter(|date| !date.trim().is_empty())
+            .map(|date| NaiveDate::parse_from_str(date.trim(), "%Y-%m-%d").map_err(|_| i18n::trf("无效的日期: {}", &[&date])))
+            .transpose()
+    };
+    let start = parse(start_date)?;
+    let end = match repeat_mode {
+        "date" => Some(start.ok_or_else(|| i18n::tr("请选择任务执行的日期"))?),
+        _ => parse(end_date)?,
+    };
+    if let (Some(start), Some(end)) = (start, end) {
+        if end < start {
+            return Err(i18n::tr("结束日期不能早于开始日期"));
+        }
+    }
+
+    let format = |date: Option<NaiveDate>| date.map(|date| date.format("%Y-%m-%d").to_string());
+    Ok((format(start), format(end)))
+}
+
+/// 任务可选的播放顺序
+pub const PLAY_ORDERS: [&str; 4] = ["sequential", "shuffle", "reverse", "newest_first"];
+
+fn validate_play_order(play_order: Option<&str>) -> Result<(), String> {
+    match play_order {
+        Some(order) if !PLAY_ORDERS.contains(&order) => Err(i18n::trf("无效的播放顺序: {}", &[&order])),
+        _ => Ok(()),
+    }
+}
+
+/// 任务类型
+pub const TASK_ACTIONS: [&str; 3] = ["play", "stop", "lower_volume"];
+
+/// 校验任务类型，未指定时为 play
+fn normalize_action(action: Option<String>) -> Result<String, String> {
+    let action = action.unwrap_or_else(|| "play".to_string());
+    if !TASK_ACTIONS.contains(&action.as_str()) {
+        return Err(i18n::trf("无效的任务类型: {}", &[&action]));
+    }
+    Ok(action)
+}
+
+/// 校验并规范化按星期指定的播放列表映射，空映射视为未设置
+fn normalize_weekday_playlists(conn: &Connection, mapping: Option<String>) -> Result<Option<String>, String> {
+    let Some(mapping) = mapping.filter(|m| !m.trim().is_empty()) else { return Ok(None) };
+    let parsed = weekday::parse_weekday_playlists(&mapping)
+        .ok_or_else(|| i18n::trf("无效的星期播放列表映射: {}", &[&mapping]))?;
+    if parsed.is_empty() {
+        return Ok(None);
+    }
+
+    for playlist_id in parsed.values() {
+        let exists: bool = conn
+            .query_row(
+                "SELECT EXISTS(SELECT 1 FROM playlists WHERE id = ?1 AND deleted_at IS NULL)",
+                [playlist_id],
+                |row| row.get(0),
+            )
+            .map_err(|e| e.to_string())?;
+        if !exists {
+            return Err(i18n::trf("星期映射中的播放列表不存在: {}", &[&playlist_id]));
+        }
+    }
+
+    serde_json::to_string(&parsed).map(Some).map_err(|e| e.to_string())
+}
+
+/// 校验每曲最少播放次数（1-20）
+fn validate_min_plays(min_plays_per_track: Option<i64>) -> Result<(), String> {
+    match min_plays_per_track {
+        Some(n) if !(1..=20).contains(&n) => Err(i18n::tr("每首最少播放次数需在1-20之间")),
+        _ => Ok(()),
+    }
+}
+
+/// 所有未删除的任务；停止、降低音量任务不播放播放列表，不受其播放列表被删除的影响
+fn load_scheduled_tasks(conn: &Connection) -> rusqlite::Result<Vec<ScheduledTask>> {
+    let mut stmt = conn.prepare(
+        "SELECT st.id, st.name, st.hour, st.minute, st.repeat_mode, st.custom_days,
+                st.playlist_id, COALESCE(p.name, '') as playlist_name, st.volume, st.fade_in_duration,
+                st.duration_minutes, st.is_enabled, st.priority, st.created_date, st.volume_curve,
+                st.min_plays_per_track, st.play_order, st.weekday_playlists, st.weekend_makeup,
+                st.critical, st.fade_out_duration, st.action, st.start_date, st.end_date,
+                st.skip_holidays
+         FROM scheduled_tasks st
+         LEFT JOIN playlists p ON st.playlist_id = p.id
+         WHERE st.deleted_at IS NULL
+           AND (st.action != 'play' OR (p.id IS NOT NULL AND p.deleted_at IS NULL))
+         ORDER BY st.hour, st.minute",
+    )?;
 
     let tasks = stmt
         .query_map([], |row| {
@@ -55,15 +233,31 @@ pub async fn get_scheduled_tasks(
                 is_enabled: row.get(11)?,
                 priority: row.get(12)?,
                 created_date: row.get(13)?,
+                volume_curve: row.get(14)?,
+                min_plays_per_track: row.get(15)?,
+                play_order: row.get(16)?,
+                weekday_playlists: row.get(17)?,
+                weekend_makeup: row.get(18)?,
+                critical: row.get(19)?,
+                fade_out_duration: row.get(20)?,
+                action: row.get(21)?,
+                start_date: row.get(22)?,
+                end_date: row.get(23)?,
+                skip_holidays: row.get(24)?,
             })
-        })
-        .map_err(|e| e.to_string())?
-        .collect::<std::result::Result<Vec<_>, _>>()
-        .map_err(|e| e.to_string())?;
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
 
     Ok(tasks)
 }
 
+#[tauri::command]
+pub async fn get_scheduled_tasks(
+    conn: State<'_, Arc<Mutex<Connection>>>,
+) -> Result<Vec<ScheduledTask>, String> {
+    load_scheduled_tasks(&*conn.lock().await).map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 pub async fn create_scheduled_task(
     name: String,
@@ -76,13 +270,36 @@ pub async fn create_scheduled_task(
     fade_in_duration: i64,
     duration_minutes: Option<i64>,
     priority: i64,
+    volume_curve: Option<String>,
+    min_plays_per_track: Option<i64>,
+    play_order: Option<String>,
+    weekday_playlists: Option<String>,
+    weekend_makeup: Option<bool>,
+    critical: Option<bool>,
+    fade_out_duration: Option<i64>,
+    action: Option<String>,
+    start_date: Option<String>,
+    end_date: Option<String>,
+    skip_holidays: Option<bool>,
     conn: State<'_, Arc<Mutex<Connection>>>,
+    wake: State<'_, SchedulerWake>,
 ) -> Result<i64, String> {
+    if let Some(curve) = &volume_curve {
+        parse_volume_curve(curve)?;
+    }
+    validate_min_plays(min_plays_per_track)?;
+    validate_play_order(play_order.as_deref())?;
+    let custom_days = normalize_custom_days(&repeat_mode, custom_days)?;
+    let (hour, minute) = cron_first_time(&repeat_mode, custom_days.as_deref(), hour, minute);
+    let action = normalize_action(action)?;
+    let (start_date, end_date) = normalize_date_range(&repeat_mode, start_date, end_date)?;
+
     let conn = conn.lock().await;
+    let weekday_playlists = normalize_weekday_playlists(&conn, weekday_playlists)?;
     conn.execute(
-        "INSERT INTO scheduled_tasks (name, hour, minute, repeat_mode, custom_days, playlist_id, volume, fade_in_duration, duration_minutes, priority)
-         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
-        (
+        "INSERT INTO scheduled_tasks (name, hour, minute, repeat_mode, custom_days, playlist_id, volume, fade_in_duration, duration_minutes, priority, volume_curve, min_plays_per_track, play_order, weekday_playlists, weekend_makeup, critical, fade_out_duration, action, start_date, end_date, skip_holidays)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21)",
+        rusqlite::params![
             &name,
             hour,
             minute,
@@ -93,11 +310,23 @@ pub async fn create_scheduled_task(
             fade_in_duration,
             duration_minutes,
             priority,
-        ),
+            &volume_curve,
+            min_plays_per_track,
+            &play_order,
+            &weekday_playlists,
+            weekend_makeup.unwrap_or(false),
+            critical.unwrap_or(false),
+            fade_out_duration.unwrap_or(0).max(0),
+            &action,
+            &start_date,
+            &end_date,
+            skip_holidays.unwrap_or(false),
+        ],
     )
     .map_err(|e| e.to_string())?;
 
     let id = conn.last_insert_rowid();
+    wake.notify();
     Ok(id)
 }
 
@@ -114,14 +343,40 @@ pub async fn update_scheduled_task(
     fade_in_duration: i64,
     duration_minutes: Option<i64>,
     priority: i64,
+    volume_curve: Option<String>,
+    min_plays_per_track: Option<i64>,
+    play_order: Option<String>,
+    weekday_playlists: Option<String>,
+    weekend_makeup: Option<bool>,
+    critical: Option<bool>,
+    fade_out_duration: Option<i64>,
+    action: Option<String>,
+    start_date: Option<String>,
+    end_date: Option<String>,
+    skip_holidays: Option<bool>,
     conn: State<'_, Arc<Mutex<Connection>>>,
+    wake: State<'_, SchedulerWake>,
 ) -> Result<(), String> {
+    if let Some(curve) = &volume_curve {
+        parse_volume_curve(curve)?;
+    }
+    validate_min_plays(min_plays_per_track)?;
+    validate_play_order(play_order.as_deref())?;
+    let custom_days = normalize_custom_days(&repeat_mode, custom_days)?;
+    let (hour, minute) = cron_first_time(&repeat_mode, custom_days.as_deref(), hour, minute);
+    let action = normalize_action(action)?;
+    let (start_date, end_date) = normalize_date_range(&repeat_mode, start_date, end_date)?;
+
     let conn = conn.lock().await;
+    let weekday_playlists = normalize_weekday_playlists(&conn, weekday_playlists)?;
     conn.execute(
         "UPDATE scheduled_tasks SET name = ?1, hour = ?2, minute = ?3, repeat_mode = ?4,
-         custom_days = ?5, playlist_id = ?6, volume = ?7, fade_in_duration = ?8, duration_minutes = ?9, priority = ?10
-         WHERE id = ?11",
-        (
+         custom_days = ?5, playlist_id = ?6, volume = ?7, fade_in_duration = ?8, duration_minutes = ?9, priority = ?10,
+         volume_curve = ?11, min_plays_per_track = ?12, play_order = ?13, weekday_playlists = ?14,
+         weekend_makeup = ?15, critical = ?16, fade_out_duration = ?17, action = ?18,
+         start_date = ?19, end_date = ?20, skip_holidays = ?21
+         WHERE id = ?22",
+        rusqlite::params![
             &name,
             hour,
             minute,
@@ -132,22 +387,197 @@ pub async fn update_scheduled_task(
             fade_in_duration,
             duration_minutes,
             priority,
+            &volume_curve,
+            min_plays_per_track,
+            &play_order,
+            &weekday_playlists,
+            weekend_makeup.unwrap_or(false),
+            critical.unwrap_or(false),
+            fade_out_duration.unwrap_or(0).max(0),
+            &action,
+            &start_date,
+            &end_date,
+            skip_holidays.unwrap_or(false),
             id,
-        ),
+        ],
     )
     .map_err(|e| e.to_string())?;
 
+    wake.notify();
     Ok(())
 }
 
+/// 批量修改的内容，未设置的字段保持不变
+#[derive(Debug, Default, Deserialize)]
+pub struct TaskBulkChanges {
+    /// 执行时间整体平移的分钟数（可为负数，跨过午夜时星期和有效日期随之平移）
+    pub shift_minutes: Option<i64>,
+    pub volume: Option<i64>,
+    pub playlist_id: Option<i64>,
+}
+
+/// 批量修改多个任务（平移时间、调整音量、替换播放列表），返回修改的任务数
+#[tauri::command]
+pub async fn bulk_update_tasks(
+    ids: Vec<i64>,
+    changes: TaskBulkChanges,
+    conn: State<'_, Arc<Mutex<Connection>>>,
+    wake: State<'_, SchedulerWake>,
+) -> Result<usize, String> {
+    if let Some(volume) = changes.volume {
+        if !(0..=100).contains(&volume) {
+            return Err(i18n::tr("音量必须在0-100之间"));
+        }
+    }
+
+    let mut conn = conn.lock().await;
+
+    if let Some(playlist_id) = changes.playlist_id {
+        let exists: bool = conn
+            .query_row(
+                "SELECT EXISTS(SELECT 1 FROM playlists WHERE id = ?1 AND deleted_at IS NULL)",
+                [playlist_id],
+                |row| row.get(0),
+            )
+            .map_err(|e| e.to_string())?;
+        if !exists {
+            return Err(i18n::tr("播放列表不存在"));
+        }
+    }
+
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    let mut updated = 0;
+
+    for id in ids {
+        type TaskTiming = (i64, i64, String, Option<String>, Option<String>, Option<String>);
+        let task: Option<TaskTiming> = tx
+            .query_row(
+                "SELECT hour, minute, repeat_mode, custom_days, start_date, end_date FROM scheduled_tasks
+                 WHERE id = ?1 AND deleted_at IS NULL",
+                [id],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?, row.get(5)?)),
+            )
+            .ok();
+        let Some((hour, minute, repeat_mode, custom_days, start_date, end_date)) = task else { continue };
+
+        // cron 任务的触发时刻由表达式决定，不参与平移
+        if let Some(shift) = changes.shift_minutes.filter(|shift| *shift != 0 && repeat_mode != "cron") {
+            let total = hour * 60 + minute + shift;
+            let offset = total.div_euclid(24 * 60);
+            let (repeat_mode, custom_days) = weekday::shift_repeat_rule(&repeat_mode, custom_days.as_deref(), offset);
+            let shift_date = |date: Option<String>| {
+                date.map(|date| match NaiveDate::parse_from_str(&date, "%Y-%m-%d") {
+                    Ok(parsed) => (parsed + chrono::Duration::days(offset)).format("%Y-%m-%d").to_string(),
+                    Err(_) => date,
+                })
+            };
+            let total = total.rem_euclid(24 * 60);
+            tx.execute(
+                "UPDATE scheduled_tasks SET hour = ?1, minute = ?2, repeat_mode = ?3, custom_days = ?4,
+                 start_date = ?5, end_date = ?6 WHERE id = ?7",
+                (total / 60, total % 60, &repeat_mode, &custom_days, shift_date(start_date), shift_date(end_date), id),
+            )
+            .map_err(|e| e.to_string())?;
+        }
+
+        if let Some(volume) = changes.volume {
+            tx.execute("UPDATE scheduled_tasks SET volume = ?1 WHERE id = ?2", (volume, id))
+                .map_err(|e| e.to_string())?;
+        }
+
+        if let Some(playlist_id) = changes.playlist_id {
+            tx.execute("UPDATE scheduled_tasks SET playlist_id = ?1 WHERE id = ?2", (playlist_id, id))
+                .map_err(|e| e.to_string())?;
+        }
+
+        updated += 1;
+    }
+
+    tx.commit().map_err(|e| e.to_string())?;
+    wake.notify();
+    Ok(updated)
+}
+
 #[tauri::command]
 pub async fn delete_scheduled_task(
     id: i64,
     conn: State<'_, Arc<Mutex<Connection>>>,
+    wake: State<'_, SchedulerWake>,
 ) -> Result<(), String> {
     let conn = conn.lock().await;
-    conn.execute("DELETE FROM scheduled_tasks WHERE id = ?1", [id])
+    conn.execute(
+        "UPDATE scheduled_tasks SET deleted_at = datetime('now') WHERE id = ?1",
+        [id],
+    )
+    .map_err(|e| e.to_string())?;
+    wake.notify();
+    Ok(())
+}
+
+/// 最近删除的任务或播放列表
+#[derive(Debug, Serialize)]
+pub struct DeletedEntry {
+    pub id: i64,
+    pub name: String,
+    pub deleted_at: String,
+}
+
+#[tauri::command]
+pub async fn get_deleted_tasks(
+    conn: State<'_, Arc<Mutex<Connection>>>,
+) -> Result<Vec<DeletedEntry>, String> {
+    let conn = conn.lock().await;
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, name, deleted_at FROM scheduled_tasks
+             WHERE deleted_at IS NOT NULL
+             ORDER BY deleted_at DESC"
+        )
         .map_err(|e| e.to_string())?;
+
+    let tasks = stmt
+        .query_map([], |row| {
+            Ok(DeletedEntry {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                deleted_at: row.get(2)?,
+            })
+        })
+        .map_err(|e| e.to_string())?
+        .collect::<std::result::Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    Ok(tasks)
+}
+
+/// 从“最近删除”中恢复任务
+#[tauri::command]
+pub async fn restore_task(
+    id: i64,
+    conn: State<'_, Arc<Mutex<Connection>>>,
+    wake: State<'_, SchedulerWake>,
+) -> Result<(), String> {
+    let conn = conn.lock().await;
+    let playlist_deleted: bool = conn
+        .query_row(
+            "SELECT st.action = 'play' AND (p.id IS NULL OR p.deleted_at IS NOT NULL) FROM scheduled_tasks st
+             LEFT JOIN playlists p ON st.playlist_id = p.id
+             WHERE st.id = ?1",
+            [id],
+            |row| row.get(0),
+        )
+        .map_err(|_| i18n::tr("任务或其播放列表不存在"))?;
+
+    if playlist_deleted {
+        return Err(i18n::tr("任务使用的播放列表已被删除，请先恢复播放列表"));
+    }
+
+    conn.execute(
+        "UPDATE scheduled_tasks SET deleted_at = NULL WHERE id = ?1",
+        [id],
+    )
+    .map_err(|e| e.to_string())?;
+    wake.notify();
     Ok(())
 }
 
@@ -156,6 +586,7 @@ pub async fn toggle_scheduled_task(
     id: i64,
     enabled: bool,
     conn: State<'_, Arc<Mutex<Connection>>>,
+    wake: State<'_, SchedulerWake>,
 ) -> Result<(), String> {
     let conn = conn.lock().await;
     conn.execute(
@@ -163,6 +594,7 @@ pub async fn toggle_scheduled_task(
         (enabled, id),
     )
     .map_err(|e| e.to_string())?;
+    wake.notify();
     Ok(())
 }
 
@@ -174,130 +606,57 @@ pub struct TaskConflict {
     pub minute: i64,
 }
 
-// 检查两个任务的重复模式是否可能在同一天执行
-fn check_repeat_conflict(mode1: &str, days1: &Option<String>, mode2: &str, days2: &Option<String>) -> bool {
-    // 如果任一任务是 "once"，则不考虑重复冲突（同一天的时间冲突仍需检查）
-    if mode1 == "once" || mode2 == "once" {
-        return true;
-    }
-
-    // daily 与任何模式都冲突
-    if mode1 == "daily" || mode2 == "daily" {
-        return true;
-    }
-
-    // weekday 与 weekday 或 custom(包含工作日) 冲突
-    if mode1 == "weekday" {
-        if mode2 == "weekday" {
-            return true;
-        }
-        if mode2 == "custom" {
-            if let Some(days) = days2 {
-                if let Ok(custom_days) = serde_json::from_str::<Vec<i32>>(days) {
-                    // 检查是否包含1-5（周一到周五）
-                    if custom_days.iter().any(|d| *d >= 1 && *d <= 5) {
-                        return true;
-                    }
-                }
-            }
-        }
-        return false;
-    }
-
-    // weekend 与 weekend 或 custom(包含周末) 冲突
-    if mode1 == "weekend" {
-        if mode2 == "weekend" {
-            return true;
-        }
-        if mode2 == "custom" {
-            if let Some(days) = days2 {
-                if let Ok(custom_days) = serde_json::from_str::<Vec<i32>>(days) {
-                    // 检查是否包含0或6（周日或周六）
-                    if custom_days.iter().any(|d| *d == 0 || *d == 6) {
-                        return true;
-                    }
-                }
-            }
-        }
-        return false;
-    }
-
-    // custom 模式的冲突检查
-    if mode1 == "custom" && mode2 == "custom" {
-        if let (Some(days1_str), Some(days2_str)) = (days1, days2) {
-            if let (Ok(days1_vec), Ok(days2_vec)) = (
-                serde_json::from_str::<Vec<i32>>(days1_str),
-                serde_json::from_str::<Vec<i32>>(days2_str),
-            ) {
-                // 检查是否有交集
-                return days1_vec.iter().any(|d| days2_vec.contains(d));
-            }
-        }
-    }
-
-    if mode1 == "custom" {
-        if let Some(days1_str) = days1 {
-            if let Ok(days1_vec) = serde_json::from_str::<Vec<i32>>(days1_str) {
-                if mode2 == "weekday" {
-                    return days1_vec.iter().any(|d| *d >= 1 && *d <= 5);
-                }
-                if mode2 == "weekend" {
-                    return days1_vec.iter().any(|d| *d == 0 || *d == 6);
-                }
-            }
-        }
-    }
-
-    false
+/// 冲突的解决建议（kind: move_later / move_earlier / trim）
+#[derive(Debug, Serialize)]
+pub struct ConflictSuggestion {
+    pub kind: String,
+    pub hour: i64,
+    pub minute: i64,
+    pub duration_minutes: i64,
+    pub description: String,
 }
 
-// 检查任务时间冲突
-#[tauri::command]
-pub async fn check_task_conflicts(
-    task_id: Option<i64>, // 如果是更新任务，传入任务ID；如果是新建任务，传入None
-    hour: i64,
-    minute: i64,
-    repeat_mode: String,
-    custom_days: Option<String>,
-    duration_minutes: Option<i64>,
-    playlist_id: i64,
-    conn: State<'_, Arc<Mutex<Connection>>>,
-) -> Result<Vec<TaskConflict>, String> {
-    let conn = conn.lock().await;
+#[derive(Debug, Serialize)]
+pub struct ConflictCheckResult {
+    pub conflicts: Vec<TaskConflict>,
+    pub suggestions: Vec<ConflictSuggestion>,
+}
 
-    // 获取播放列表的总时长（如果没有设置 duration_minutes）
-    let estimated_duration = if let Some(dur) = duration_minutes {
-        dur
-    } else {
-        // 计算播放列表的总时长（秒转分钟）
+/// 任务的预计播放时长（分钟）：未设置时长时按播放列表总时长向上取整
+pub(crate) fn estimated_minutes(conn: &Connection, duration_minutes: Option<i64>, playlist_id: i64) -> i64 {
+    duration_minutes.unwrap_or_else(|| {
         let total_seconds: i64 = conn
-            .query_row(
-                "SELECT COALESCE(SUM(af.duration), 0) FROM playlist_items pi
-                 JOIN audio_files af ON pi.audio_id = af.id
-                 WHERE pi.playlist_id = ?1",
-                [playlist_id],
-                |row| row.get(0),
-            )
+            .query_row(PLAYLIST_DURATION_SQL, [playlist_id], |row| row.get(0))
             .unwrap_or(0);
+        (total_seconds + 59) / 60
+    })
+}
 
-        (total_seconds + 59) / 60 // 向上取整到分钟
-    };
-
-    // 计算任务的开始和结束时间（分钟）
-    let start_time = hour * 60 + minute;
-    let end_time = start_time + estimated_duration;
+/// 查找与 start_time（分钟）开始、持续 duration 分钟的任务冲突的已启用播放任务（停止、降低音量任务不占用时间），
+/// exclude_id 为任务自身，date_range 为任务的有效日期范围
+fn find_conflicts(
+    conn: &Connection,
+    exclude_id: Option<i64>,
+    start_time: i64,
+    duration: i64,
+    repeat_mode: &str,
+    custom_days: Option<&str>,
+    date_range: (Option<&str>, Option<&str>),
+) -> Result<ConflictCheckResult, String> {
+    let end_time = start_time + duration;
 
     // 查询所有启用的任务
     let mut stmt = conn
         .prepare(
             "SELECT st.id, st.name, st.hour, st.minute, st.repeat_mode, st.custom_days,
-                    st.duration_minutes, st.playlist_id
+                    st.duration_minutes, st.playlist_id, st.start_date, st.end_date
              FROM scheduled_tasks st
-             WHERE st.is_enabled = 1"
+             WHERE st.is_enabled = 1 AND st.deleted_at IS NULL AND st.action = 'play'"
         )
         .map_err(|e| e.to_string())?;
 
-    let existing_tasks: Vec<(i64, String, i64, i64, String, Option<String>, Option<i64>, i64)> = stmt
+    type ExistingTask = (i64, String, i64, i64, String, Option<String>, Option<i64>, i64, Option<String>, Option<String>);
+    let existing_tasks: Vec<ExistingTask> = stmt
         .query_map([], |row| {
             Ok((
                 row.get(0)?,
@@ -308,6 +667,8 @@ pub async fn check_task_conflicts(
                 row.get(5)?,
                 row.get(6)?,
                 row.get(7)?,
+                row.get(8)?,
+                row.get(9)?,
             ))
         })
         .map_err(|e| e.to_string())?
@@ -315,38 +676,24 @@ pub async fn check_task_conflicts(
         .map_err(|e| e.to_string())?;
 
     let mut conflicts = Vec::new();
+    // 与新任务可能在同一天执行的任务时间段（分钟）
+    let mut busy_ranges = Vec::new();
 
-    for (id, name, h, m, mode, days, dur_min, pl_id) in existing_tasks {
+    for (id, name, h, m, mode, days, dur_min, pl_id, start, end) in existing_tasks {
         // 跳过自己（更新任务时）
-        if let Some(current_id) = task_id {
-            if id == current_id {
-                continue;
-            }
+        if exclude_id == Some(id) {
+            continue;
         }
 
-        // 检查重复模式是否可能冲突
-        if !check_repeat_conflict(&repeat_mode, &custom_days, &mode, &days) {
+        // 检查重复模式和有效日期范围是否可能冲突
+        let existing_range = (start.as_deref(), end.as_deref());
+        if !weekday::schedules_overlap((repeat_mode, custom_days), date_range, (&mode, days.as_deref()), existing_range) {
             continue;
         }
 
-        // 计算现有任务的时长
-        let existing_duration = if let Some(dur) = dur_min {
-            dur
-        } else {
-            let total_seconds: i64 = conn
-                .query_row(
-                    "SELECT COALESCE(SUM(af.duration), 0) FROM playlist_items pi
-                     JOIN audio_files af ON pi.audio_id = af.id
-                     WHERE pi.playlist_id = ?1",
-                    [pl_id],
-                    |row| row.get(0),
-                )
-                .unwrap_or(0);
-            (total_seconds + 59) / 60
-        };
-
         let existing_start = h * 60 + m;
-        let existing_end = existing_start + existing_duration;
+        let existing_end = existing_start + estimated_minutes(conn, dur_min, pl_id);
+        busy_ranges.push((existing_start, existing_end));
 
         // 检查时间段是否重叠
         // 两个时间段重叠的条件：start1 < end2 && start2 < end1
@@ -360,5 +707,274 @@ pub async fn check_task_conflicts(
         }
     }
 
-    Ok(conflicts)
+    let suggestions = if conflicts.is_empty() {
+        Vec::new()
+    } else {
+        suggest_resolutions(start_time, duration, &busy_ranges)
+    };
+
+    Ok(ConflictCheckResult {
+        conflicts,
+        suggestions,
+    })
+}
+
+/// 要检查冲突的任务（新建或编辑中的任务）
+#[derive(Debug, Deserialize)]
+pub struct ConflictCheckParams {
+    /// 更新任务时为任务ID，新建任务时为空
+    pub task_id: Option<i64>,
+    pub hour: i64,
+    pub minute: i64,
+    pub repeat_mode: String,
+    pub custom_days: Option<String>,
+    pub duration_minutes: Option<i64>,
+    pub playlist_id: i64,
+    pub start_date: Option<String>,
+    pub end_date: Option<String>,
+}
+
+// 检查任务时间冲突
+#[tauri::command]
+pub async fn check_task_conflicts(
+    params: ConflictCheckParams,
+    conn: State<'_, Arc<Mutex<Connection>>>,
+) -> Result<ConflictCheckResult, String> {
+    let ConflictCheckParams { task_id, hour, minute, repeat_mode, custom_days, duration_minutes, playlist_id, start_date, end_date } =
+        params;
+    let (start_date, end_date) = normalize_date_range(&repeat_mode, start_date, end_date)?;
+    let (hour, minute) = cron_first_time(&repeat_mode, custom_days.as_deref(), hour, minute);
+    let conn = conn.lock().await;
+    let duration = estimated_minutes(&conn, duration_minutes, playlist_id);
+    let date_range = (start_date.as_deref(), end_date.as_deref());
+    find_conflicts(&conn, task_id, hour * 60 + minute, duration, &repeat_mode, custom_days.as_deref(), date_range)
+}
+
+/// 复制任务的结果：新任务ID，以及新时间与其他任务的冲突检查结果
+#[derive(Debug, Serialize)]
+pub struct ClonedTask {
+    pub id: i64,
+    #[serde(flatten)]
+    pub check: ConflictCheckResult,
+}
+
+/// 把任务连同全部设置复制到另一个时间（如同一播放列表的早/中/晚任务），
+/// 未指定名称时沿用原名称；有冲突时照常创建，并返回冲突和解决建议
+#[tauri::command]
+pub async fn clone_task(
+    id: i64,
+    hour: i64,
+    minute: i64,
+    name: Option<String>,
+    conn: State<'_, Arc<Mutex<Connection>>>,
+    wake: State<'_, SchedulerWake>,
+) -> Result<ClonedTask, String> {
+    if !(0..24).contains(&hour) || !(0..60).contains(&minute) {
+        return Err(i18n::trf("无效的时间: {}", &[&format!("{:02}:{:02}", hour, minute)]));
+    }
+
+    let conn = conn.lock().await;
+    type TaskSchedule = (String, Option<String>, Option<i64>, i64, Option<String>, Option<String>);
+    let (repeat_mode, custom_days, duration_minutes, playlist_id, start_date, end_date): TaskSchedule = conn
+        .query_row(
+            "SELECT repeat_mode, custom_days, duration_minutes, playlist_id, start_date, end_date FROM scheduled_tasks
+             WHERE id = ?1 AND deleted_at IS NULL",
+            [id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?, row.get(5)?)),
+        )
+        .map_err(|_| i18n::tr("任务不存在"))?;
+
+    conn.execute(
+        "INSERT INTO scheduled_tasks (name, hour, minute, repeat_mode, custom_days, playlist_id, volume, fade_in_duration,
+                                      is_enabled, priority, duration_minutes, volume_curve, min_plays_per_track, play_order,
+                                      weekday_playlists, weekend_makeup, critical, fade_out_duration, action,
+                                      start_date, end_date, skip_holidays)
+         SELECT COALESCE(?1, name), ?2, ?3, repeat_mode, custom_days, playlist_id, volume, fade_in_duration,
+                is_enabled, priority, duration_minutes, volume_curve, min_plays_per_track, play_order,
+                weekday_playlists, weekend_makeup, critical, fade_out_duration, action, start_date, end_date,
+                skip_holidays
+         FROM scheduled_tasks WHERE id = ?4",
+        (name.as_deref().map(str::trim).filter(|name| !name.is_empty()), hour, minute, id),
+    )
+    .map_err(|e| e.to_string())?;
+    let new_id = conn.last_insert_rowid();
+
+    let duration = estimated_minutes(&conn, duration_minutes, playlist_id);
+    let date_range = (start_date.as_deref(), end_date.as_deref());
+    let check = find_conflicts(&conn, Some(new_id), hour * 60 + minute, duration, &repeat_mode, custom_days.as_deref(), date_range)?;
+    wake.notify();
+    Ok(ClonedTask { id: new_id, check })
+}
+
+/// 所有启用任务中下一个将要触发的任务，没有任务会在一周内触发时为 None
+#[tauri::command]
+pub async fn get_next_task(
+    conn: State<'_, Arc<Mutex<Connection>>>,
+) -> Result<Option<NextTask>, String> {
+    scheduler::next_task(&*conn.lock().await).map_err(|e| e.to_string())
+}
+
+// 一天的分钟数
+const MINUTES_PER_DAY: i64 = 24 * 60;
+
+/// 根据已有任务的时间段，计算冲突的解决建议：前后最近的空闲时间，或缩短时长
+fn suggest_resolutions(start: i64, duration: i64, busy_ranges: &[(i64, i64)]) -> Vec<ConflictSuggestion> {
+    let is_free = |s: i64| {
+        let e = s + duration.max(1);
+        busy_ranges.iter().all(|(bs, be)| !(s < *be && *bs < e))
+    };
+
+    let mut suggestions = Vec::new();
+
+    // 向后查找最近的空闲开始时间
+    if let Some(s) = (start + 1..MINUTES_PER_DAY).find(|s| is_free(*s)) {
+        suggestions.push(ConflictSuggestion {
+            kind: "move_later".to_string(),
+            hour: s / 60,
+            minute: s % 60,
+            duration_minutes: duration,
+            description: i18n::trf("推迟到 {} 开始", &[&format!("{:02}:{:02}", s / 60, s % 60)]),
+        });
+    }
+
+    // 向前查找最近的空闲开始时间
+    if let Some(s) = (0..start).rev().find(|s| is_free(*s)) {
+        suggestions.push(ConflictSuggestion {
+            kind: "move_earlier".to_string(),
+            hour: s / 60,
+            minute: s % 60,
+            duration_minutes: duration,
+            description: i18n::trf("提前到 {} 开始", &[&format!("{:02}:{:02}", s / 60, s % 60)]),
+        });
+    }
+
+    // 开始时间空闲时，缩短时长到下一个任务开始之前
+    let start_is_free = busy_ranges.iter().all(|(bs, be)| !(start >= *bs && start < *be));
+    if start_is_free {
+        if let Some(next_start) = busy_ranges
+            .iter()
+            .map(|(bs, _)| *bs)
+            .filter(|bs| *bs > start)
+            .min()
+        {
+            let trimmed = next_start - start;
+            if trimmed > 0 && trimmed < duration {
+                suggestions.push(ConflictSuggestion {
+                    kind: "trim".to_string(),
+                    hour: start / 60,
+                    minute: start % 60,
+                    duration_minutes: trimmed,
+                    description: i18n::trf("将播放时长缩短为 {} 分钟", &[&trimmed]),
+                });
+            }
+        }
+    }
+
+    // 按与原时间的接近程度排序
+    suggestions.sort_by_key(|s| {
+        if s.kind == "trim" {
+            0
+        } else {
+            (s.hour * 60 + s.minute - start).abs()
+        }
+    });
+
+    suggestions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn silence_tasks_survive_playlist_deletion() {
+        let conn = crate::db::init_database(std::path::Path::new(":memory:")).unwrap();
+        conn.execute_batch(
+            "INSERT INTO playlists (id, name) VALUES (1, '睡前');
+             INSERT INTO scheduled_tasks (name, hour, minute, repeat_mode, playlist_id, action)
+             VALUES ('睡前故事', 20, 0, 'daily', 1, 'play');
+             INSERT INTO scheduled_tasks (name, hour, minute, repeat_mode, playlist_id, volume, action)
+             VALUES ('调低音量', 20, 30, 'daily', 1, 20, 'lower_volume');
+             UPDATE playlists SET deleted_at = datetime('now') WHERE id = 1;",
+        )
+        .unwrap();
+        let names = |conn: &Connection| -> Vec<String> { load_scheduled_tasks(conn).unwrap().into_iter().map(|task| task.name).collect() };
+        assert_eq!(names(&conn), ["调低音量"]);
+
+        // 超过保留期清除已删除的播放列表时不连带删除停止、降低音量任务
+        conn.execute("UPDATE playlists SET deleted_at = datetime('now', '-60 days') WHERE id = 1", []).unwrap();
+        crate::db::purge_deleted_items(&conn).unwrap();
+        assert_eq!(names(&conn), ["调低音量"]);
+    }
+
+    fn curve(points: &[(f64, i64)]) -> Vec<VolumePoint> {
+        points.iter().map(|&(minute, volume)| VolumePoint { minute, volume }).collect()
+    }
+
+    fn kinds(suggestions: &[ConflictSuggestion]) -> Vec<(&str, i64, i64)> {
+        suggestions
+            .iter()
+            .map(|s| (s.kind.as_str(), s.hour * 60 + s.minute, s.duration_minutes))
+            .collect()
+    }
+
+    #[test]
+    fn suggestions_move_to_nearest_free_time() {
+        // 08:00 开始 30 分钟，与 07:50-08:20 的任务冲突
+        let suggestions = suggest_resolutions(480, 30, &[(470, 500)]);
+        assert_eq!(kinds(&suggestions), [("move_later", 500, 30), ("move_earlier", 440, 30)]);
+        assert_eq!(suggestions[0].description, "推迟到 08:20 开始");
+        assert_eq!(suggestions[1].description, "提前到 07:20 开始");
+    }
+
+    #[test]
+    fn suggestions_trim_before_next_task() {
+        // 08:00 开始 60 分钟，08:30 有另一个任务：开始时间空闲，可以缩短为 30 分钟
+        let suggestions = suggest_resolutions(480, 60, &[(510, 540)]);
+        assert_eq!(kinds(&suggestions), [("trim", 480, 30), ("move_earlier", 450, 60), ("move_later", 540, 60)]);
+        assert_eq!(suggestions[0].description, "将播放时长缩短为 30 分钟");
+    }
+
+    #[test]
+    fn suggestions_at_day_boundaries() {
+        // 23:00 开始的任务播放到午夜之后，当天已没有更晚的空闲时间
+        assert_eq!(kinds(&suggest_resolutions(1410, 60, &[(1380, 1470)])), [("move_earlier", 1320, 60)]);
+        // 00:00 开始时没有更早的时间
+        assert_eq!(kinds(&suggest_resolutions(0, 30, &[(0, 30)])), [("move_later", 30, 30)]);
+    }
+
+    #[test]
+    fn volume_curve_holds_endpoints() {
+        let points = curve(&[(5.0, 60), (35.0, 20)]);
+        // 第一个点之前和最后一个点之后保持端点音量
+        assert_eq!(volume_at(&points, 0.0), 60);
+        assert_eq!(volume_at(&points, 5.0), 60);
+        assert_eq!(volume_at(&points, 35.0), 20);
+        assert_eq!(volume_at(&points, 120.0), 20);
+        assert_eq!(volume_at(&curve(&[(0.0, 40)]), 10.0), 40);
+    }
+
+    #[test]
+    fn volume_curve_interpolates_between_points() {
+        let points = curve(&[(0.0, 60), (30.0, 20), (40.0, 80)]);
+        assert_eq!(volume_at(&points, 15.0), 40);
+        assert_eq!(volume_at(&points, 30.0), 20);
+        assert_eq!(volume_at(&points, 35.0), 50);
+        // 四舍五入：60 → 20 的 1/8 处为 55
+        assert_eq!(volume_at(&points, 3.75), 55);
+    }
+
+    #[test]
+    fn volume_curve_validation() {
+        assert_eq!(parse_volume_curve(r#"[{"minute":0,"volume":60},{"minute":30,"volume":20}]"#).unwrap().len(), 2);
+        for invalid in [
+            "[]",
+            "not json",
+            r#"[{"minute":-1,"volume":60}]"#,
+            r#"[{"minute":0,"volume":101}]"#,
+            r#"[{"minute":10,"volume":60},{"minute":10,"volume":20}]"#,
+        ] {
+            assert!(parse_volume_curve(invalid).is_err(), "{}", invalid);
+        }
+    }
 }
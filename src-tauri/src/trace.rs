@@ -0,0 +1,203 @@
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use rusqlite::Connection;
+use chrono::{Duration, Local, NaiveDate, NaiveDateTime};
+use tauri::State;
+use crate::i18n;
+
+// 调度日志级别保存在 app_settings 中，后台服务进程也按此记录
+const TRACE_LEVEL_KEY: &str = "scheduler_trace_level";
+// 调度日志保留的天数
+const TRACE_RETENTION_DAYS: i64 = 7;
+// 同一任务相同的判断在这么多分钟内只记录一次（调度器在触发窗口内会反复检查）
+const TRACE_DEDUP_MINUTES: i64 = 2;
+// 一次最多返回的日志条数
+const MAX_TRACE_ENTRIES: i64 = 2000;
+const TIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+/// 调度日志级别
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TraceLevel {
+    /// 不记录
+    Off,
+    /// 记录到点任务的判断（执行、跳过及原因）和执行结果
+    Decisions,
+    /// 另外记录每次检查中未到时间的任务
+    Verbose,
+}
+
+pub fn load_level(conn: &Connection) -> TraceLevel {
+    conn.query_row(
+        "SELECT value FROM app_settings WHERE key = ?1",
+        [TRACE_LEVEL_KEY],
+        |row| row.get::<_, String>(0),
+    )
+    .ok()
+    .and_then(|value| serde_json::from_str(&value).ok())
+    .unwrap_or(TraceLevel::Decisions)
+}
+
+/// 调度器的判断结果
+#[derive(Debug, Clone, Copy)]
+pub enum Decision {
+    /// 执行任务
+    Executed,
+    /// 到点但没有执行
+    Skipped,
+    /// 还没到时间（仅 Verbose）
+    NotDue,
+    /// 执行结束
+    Finished,
+}
+
+impl Decision {
+    fn as_str(self) -> &'static str {
+        match self {
+            Decision::Executed => "executed",
+            Decision::Skipped => "skipped",
+            Decision::NotDue => "not_due",
+            Decision::Finished => "finished",
+        }
+    }
+
+    fn level(self) -> TraceLevel {
+        match self {
+            Decision::NotDue => TraceLevel::Verbose,
+            _ => TraceLevel::Decisions,
+        }
+    }
+}
+
+/// 一次检查中使用的调度日志记录器（检查开始时读取一次级别）
+pub struct Tracer {
+    level: TraceLevel,
+}
+
+impl Tracer {
+    pub fn load(conn: &Connection) -> Self {
+        let level = load_level(conn);
+        if level != TraceLevel::Off {
+            let _ = conn.execute(
+                "DELETE FROM scheduler_trace WHERE trace_time < datetime('now', ?1)",
+                [format!("-{} days", TRACE_RETENTION_DAYS)],
+            );
+        }
+        Self { level }
+    }
+
+    /// 记录对任务的判断和原因，级别不够时忽略
+    pub fn record(&self, conn: &Connection, task_id: i64, task_name: &str, decision: Decision, reason: &str) {
+        if decision.level() > self.level {
+            return;
+        }
+        if let Err(e) = conn.execute(
+            "INSERT INTO scheduler_trace (task_id, task_name, decision, reason)
+             SELECT ?1, ?2, ?3, ?4
+             WHERE NOT EXISTS (SELECT 1 FROM scheduler_trace
+                               WHERE task_id = ?1 AND decision = ?3 AND reason = ?4
+                                 AND trace_time >= datetime('now', ?5))",
+            (task_id, task_name, decision.as_str(), reason, format!("-{} minutes", TRACE_DEDUP_MINUTES)),
+        ) {
+            eprintln!("[Scheduler] 写入调度日志失败: {}", e);
+        }
+    }
+}
+
+/// 一条调度日志
+#[derive(Debug, Serialize)]
+pub struct TraceEntry {
+    pub id: i64,
+    /// 记录时间（本地时间 YYYY-MM-DD HH:MM:SS）
+    pub trace_time: String,
+    pub task_id: i64,
+    pub task_name: String,
+    /// executed / skipped / not_due / finished
+    pub decision: String,
+    pub reason: String,
+}
+
+/// 解析本地时间：YYYY-MM-DD HH:MM:SS，或 YYYY-MM-DD（end 为当天结束）
+fn parse_bound(value: &str, end_of_day: bool) -> Result<NaiveDateTime, String> {
+    NaiveDateTime::parse_from_str(value, TIME_FORMAT)
+        .or_else(|_| {
+            NaiveDate::parse_from_str(value, "%Y-%m-%d").map(|date| match end_of_day {
+                true => date.and_hms_opt(23, 59, 59).unwrap_or_default(),
+                false => date.and_hms_opt(0, 0, 0).unwrap_or_default(),
+            })
+        })
+        .map_err(|_| i18n::trf("无效的时间: {}", &[&value]))
+}
+
+/// 查询时间范围内（本地时间，默认最近 24 小时）的调度日志，可只看某个任务，最新的在前
+#[tauri::command]
+pub async fn get_scheduler_trace(
+    start: Option<String>,
+    end: Option<String>,
+    task_id: Option<i64>,
+    conn: State<'_, Arc<Mutex<Connection>>>,
+) -> Result<Vec<TraceEntry>, String> {
+    let now = Local::now().naive_local();
+    let start = match start.as_deref() {
+        Some(value) => parse_bound(value, false)?,
+        None => now - Duration::hours(24),
+    };
+    let end = match end.as_deref() {
+        Some(value) => parse_bound(value, true)?,
+        None => now,
+    };
+
+    let conn = conn.lock().await;
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, datetime(trace_time, 'localtime'), task_id, task_name, decision, reason
+             FROM scheduler_trace
+             WHERE datetime(trace_time, 'localtime') BETWEEN ?1 AND ?2
+               AND (?3 IS NULL OR task_id = ?3)
+             ORDER BY id DESC
+             LIMIT ?4",
+        )
+        .map_err(|e| e.to_string())?;
+    let entries = stmt
+        .query_map(
+            (start.format(TIME_FORMAT).to_string(), end.format(TIME_FORMAT).to_string(), task_id, MAX_TRACE_ENTRIES),
+            |row| {
+                Ok(TraceEntry {
+                    id: row.get(0)?,
+                    trace_time: row.get(1)?,
+                    task_id: row.get(2)?,
+                    task_name: row.get(3)?,
+                    decision: row.get(4)?,
+                    reason: row.get(5)?,
+                })
+            },
+        )
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+    Ok(entries)
+}
+
+#[tauri::command]
+pub async fn get_scheduler_trace_level(
+    conn: State<'_, Arc<Mutex<Connection>>>,
+) -> Result<TraceLevel, String> {
+    Ok(load_level(&*conn.lock().await))
+}
+
+#[tauri::command]
+pub async fn set_scheduler_trace_level(
+    level: TraceLevel,
+    conn: State<'_, Arc<Mutex<Connection>>>,
+) -> Result<(), String> {
+    let value = serde_json::to_string(&level).map_err(|e| e.to_string())?;
+    conn.lock()
+        .await
+        .execute(
+            "INSERT OR REPLACE INTO app_settings (key, value) VALUES (?1, ?2)",
+            (TRACE_LEVEL_KEY, &value),
+        )
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
@@ -0,0 +1,166 @@
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use tokio::time::{interval, Duration};
+use rusqlite::Connection;
+use tauri::{AppHandle, Manager, State};
+use crate::i18n;
+use crate::player::{self, AudioPlayer};
+use crate::scheduler;
+
+// 托盘图标的点击动作保存在 app_settings 中
+const ACTIONS_SETTING_KEY: &str = "tray_actions";
+// 空闲时刷新托盘提示中下一个任务的间隔（秒）
+const TOOLTIP_REFRESH_SECS: u64 = 30;
+
+/// 点击托盘图标时执行的动作
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TrayAction {
+    /// 不做任何事
+    None,
+    /// 显示主窗口
+    ShowWindow,
+    /// 播放/暂停
+    PlayPause,
+    /// 立即执行默认任务
+    RunDefaultTask,
+}
+
+/// 托盘图标的点击动作（Tauri 只区分单击和双击，双击时会先触发一次单击；双击仅 Windows 支持）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TrayActions {
+    pub click: TrayAction,
+    pub double_click: TrayAction,
+    /// run_default_task 执行的任务
+    pub default_task_id: Option<i64>,
+}
+
+impl Default for TrayActions {
+    fn default() -> Self {
+        Self {
+            click: TrayAction::ShowWindow,
+            double_click: TrayAction::None,
+            default_task_id: None,
+        }
+    }
+}
+
+pub fn load_actions(conn: &Connection) -> TrayActions {
+    conn.query_row(
+        "SELECT value FROM app_settings WHERE key = ?1",
+        [ACTIONS_SETTING_KEY],
+        |row| row.get::<_, String>(0),
+    )
+    .ok()
+    .and_then(|value| serde_json::from_str(&value).ok())
+    .unwrap_or_default()
+}
+
+pub fn show_main_window(app: &AppHandle) {
+    if let Some(window) = app.get_window("main") {
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
+}
+
+/// 没有播放时的托盘提示：应用名称和下一个任务的触发时间
+pub async fn idle_tooltip(app: &AppHandle) -> String {
+    let app_name = app.package_info().name.clone();
+    let next = {
+        let conn = app.state::<Arc<Mutex<Connection>>>();
+        let conn = conn.lock().await;
+        scheduler::next_task(&conn).ok().flatten()
+    };
+    match next {
+        // 触发时间只显示 HH:MM，不是今天时带上日期
+        Some(next) => {
+            let today = chrono::Local::now().format("%Y-%m-%d").to_string();
+            let time = if next.trigger_time.starts_with(&today) {
+                &next.trigger_time[11..16]
+            } else {
+                &next.trigger_time[5..16]
+            };
+            format!("{}\n{}", app_name, i18n::trf("下一个任务：{} {}", &[&time, &next.task_name]))
+        }
+        None => app_name,
+    }
+}
+
+/// 定时刷新空闲时的托盘提示（任务到点或增删后下一个任务会变化）；播放中的提示由播放状态事件更新
+pub fn start_tooltip_refresher(app: AppHandle, player: Arc<Mutex<AudioPlayer>>) {
+    tauri::async_runtime::spawn(async move {
+        let mut ticker = interval(Duration::from_secs(TOOLTIP_REFRESH_SECS));
+        loop {
+            ticker.tick().await;
+            if player.lock().await.has_pending_audio() {
+                continue;
+            }
+            let tooltip = idle_tooltip(&app).await;
+            let _ = app.tray_handle().set_tooltip(&tooltip);
+        }
+    });
+}
+
+/// 单击或双击托盘图标：按设置执行对应的动作
+pub async fn handle_click(app: &AppHandle, double_click: bool) {
+    let actions = {
+        let conn = app.state::<Arc<Mutex<Connection>>>();
+        let conn = conn.lock().await;
+        load_actions(&conn)
+    };
+    let action = if double_click { actions.double_click } else { actions.click };
+
+    match action {
+        TrayAction::None => {}
+        TrayAction::ShowWindow => show_main_window(app),
+        TrayAction::PlayPause => player::toggle_from_tray(app).await,
+        TrayAction::RunDefaultTask => {
+            let Some(task_id) = actions.default_task_id else {
+                return;
+            };
+            let db = app.state::<Arc<Mutex<Connection>>>().inner().clone();
+            let player = app.state::<Arc<Mutex<AudioPlayer>>>().inner().clone();
+            if let Err(e) = scheduler::run_task_now(db, player, task_id).await {
+                eprintln!("[Tray] 执行默认任务失败: {}", e);
+            }
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn get_tray_actions(
+    conn: State<'_, Arc<Mutex<Connection>>>,
+) -> Result<TrayActions, String> {
+    Ok(load_actions(&*conn.lock().await))
+}
+
+/// 保存托盘图标的点击动作；使用“执行默认任务”时需要指定存在的任务
+#[tauri::command]
+pub async fn set_tray_actions(
+    actions: TrayActions,
+    conn: State<'_, Arc<Mutex<Connection>>>,
+) -> Result<(), String> {
+    let conn = conn.lock().await;
+    let runs_task = [actions.click, actions.double_click].contains(&TrayAction::RunDefaultTask);
+    if runs_task {
+        let exists: bool = conn
+            .query_row(
+                "SELECT EXISTS(SELECT 1 FROM scheduled_tasks WHERE id = ?1 AND deleted_at IS NULL)",
+                [actions.default_task_id],
+                |row| row.get(0),
+            )
+            .map_err(|e| e.to_string())?;
+        if !exists {
+            return Err(i18n::tr("任务不存在"));
+        }
+    }
+
+    let value = serde_json::to_string(&actions).map_err(|e| e.to_string())?;
+    conn.execute(
+        "INSERT OR REPLACE INTO app_settings (key, value) VALUES (?1, ?2)",
+        (ACTIONS_SETTING_KEY, &value),
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
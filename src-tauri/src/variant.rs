@@ -0,0 +1,227 @@
+use serde::Serialize;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use rusqlite::Connection;
+use tauri::{State, AppHandle, Manager};
+use crate::audio::{create_command_from_path, get_ffmpeg_executable_path};
+use crate::i18n;
+
+// 默认预渲染的倍速
+const DEFAULT_VARIANT_SPEEDS: [f32; 2] = [0.75, 0.9];
+
+#[derive(Debug, Serialize)]
+pub struct SpeedVariant {
+    pub id: i64,
+    pub audio_id: i64,
+    pub speed: f32,
+    pub file_path: String,
+    pub file_size: i64,
+    pub created_date: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RenderVariantsResult {
+    pub rendered: i32,
+    pub skipped: i32,
+    pub failed: Vec<String>,
+}
+
+/// 查找与指定倍速匹配的预渲染文件路径
+pub fn find_variant_path(conn: &Connection, audio_id: i64, speed: f32) -> Option<String> {
+    conn.query_row(
+        "SELECT file_path FROM audio_speed_variants
+         WHERE audio_id = ?1 AND ABS(speed - ?2) < 0.01
+         ORDER BY id DESC LIMIT 1",
+        (audio_id, speed as f64),
+        |row| row.get::<_, String>(0),
+    )
+    .ok()
+    .filter(|path| std::path::Path::new(path).exists())
+}
+
+/// 删除某个音频的所有预渲染文件及记录
+pub fn remove_variants(conn: &Connection, audio_id: i64) -> Result<(), String> {
+    let mut stmt = conn
+        .prepare("SELECT file_path FROM audio_speed_variants WHERE audio_id = ?1")
+        .map_err(|e| e.to_string())?;
+
+    let paths: Vec<String> = stmt
+        .query_map([audio_id], |row| row.get(0))
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    for path in paths {
+        if let Err(e) = std::fs::remove_file(&path) {
+            eprintln!("删除倍速文件失败: {}", e);
+        }
+    }
+
+    conn.execute("DELETE FROM audio_speed_variants WHERE audio_id = ?1", [audio_id])
+        .map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+/// 批量生成倍速版本（使用FFmpeg atempo滤镜，保持音调不变）
+#[tauri::command]
+pub async fn render_speed_variants(
+    audio_ids: Vec<i64>,
+    speeds: Option<Vec<f32>>,
+    app: AppHandle,
+    conn: State<'_, Arc<Mutex<Connection>>>,
+    audio_dir: State<'_, PathBuf>,
+) -> Result<RenderVariantsResult, String> {
+    let ffmpeg_path = get_ffmpeg_executable_path(Some(&app)).await
+        .ok_or_else(|| i18n::tr("FFmpeg未安装。请将ffmpeg.exe放入tools目录，或点击\"一键安装FFmpeg\"按钮进行安装"))?;
+
+    let speeds = speeds.unwrap_or_else(|| DEFAULT_VARIANT_SPEEDS.to_vec());
+    if speeds.iter().any(|s| !(0.5..=2.0).contains(s)) {
+        return Err(i18n::tr("倍速必须在0.5到2.0之间"));
+    }
+
+    let variant_dir = audio_dir.join("variants");
+    std::fs::create_dir_all(&variant_dir)
+        .map_err(|e| i18n::trf("创建倍速目录失败: {}", &[&e]))?;
+
+    let mut result = RenderVariantsResult {
+        rendered: 0,
+        skipped: 0,
+        failed: Vec::new(),
+    };
+
+    let total_jobs = (audio_ids.len() * speeds.len()).max(1);
+    let mut finished_jobs = 0;
+
+    for audio_id in audio_ids {
+        let source: Option<(String, String)> = {
+            let conn = conn.lock().await;
+            conn.query_row(
+                "SELECT file_path, original_name FROM audio_files WHERE id = ?1",
+                [audio_id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .ok()
+        };
+
+        let (file_path, original_name) = match source {
+            Some(source) => source,
+            None => {
+                result.failed.push(i18n::trf("音频不存在: {}", &[&audio_id]));
+                finished_jobs += speeds.len();
+                continue;
+            }
+        };
+
+        for &speed in &speeds {
+            finished_jobs += 1;
+
+            // 已存在相同倍速的版本则跳过
+            let exists = {
+                let conn = conn.lock().await;
+                find_variant_path(&conn, audio_id, speed).is_some()
+            };
+            if exists {
+                result.skipped += 1;
+                continue;
+            }
+
+            let output_path = variant_dir.join(format!(
+                "{}_{}x_{}.mp3",
+                audio_id,
+                (speed * 100.0).round() as i64,
+                uuid::Uuid::new_v4().to_string().split('-').next().unwrap()
+            ));
+
+            let output = create_command_from_path(&ffmpeg_path)
+                .arg("-i")
+                .arg(&file_path)
+                .arg("-vn")
+                .arg("-filter:a")
+                .arg(format!("atempo={}", speed))
+                .arg("-acodec")
+                .arg("libmp3lame")
+                .arg("-ab")
+                .arg("128k")
+                .arg("-y")
+                .arg(output_path.to_str().unwrap())
+                .output();
+
+            match output {
+                Ok(output) if output.status.success() && output_path.exists() => {
+                    let file_size = std::fs::metadata(&output_path)
+                        .map(|m| m.len() as i64)
+                        .unwrap_or(0);
+
+                    let conn = conn.lock().await;
+                    conn.execute(
+                        "INSERT INTO audio_speed_variants (audio_id, speed, file_path, file_size)
+                         VALUES (?1, ?2, ?3, ?4)",
+                        (audio_id, speed as f64, output_path.to_str().unwrap(), file_size),
+                    )
+                    .map_err(|e| e.to_string())?;
+
+                    result.rendered += 1;
+                }
+                Ok(output) => {
+                    let error_msg = String::from_utf8_lossy(&output.stderr);
+                    eprintln!("生成倍速版本失败: {}", error_msg);
+                    let _ = std::fs::remove_file(&output_path);
+                    result.failed.push(format!("{} ({}x)", original_name, speed));
+                }
+                Err(e) => {
+                    eprintln!("执行FFmpeg命令失败: {}", e);
+                    result.failed.push(format!("{} ({}x)", original_name, speed));
+                }
+            }
+
+            let progress = (finished_jobs * 100 / total_jobs) as u8;
+            app.emit_all("speed-variant-progress", progress).map_err(|e| e.to_string())?;
+        }
+    }
+
+    Ok(result)
+}
+
+#[tauri::command]
+pub async fn get_speed_variants(
+    audio_id: i64,
+    conn: State<'_, Arc<Mutex<Connection>>>,
+) -> Result<Vec<SpeedVariant>, String> {
+    let conn = conn.lock().await;
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, audio_id, speed, file_path, file_size, created_date
+             FROM audio_speed_variants
+             WHERE audio_id = ?1
+             ORDER BY speed"
+        )
+        .map_err(|e| e.to_string())?;
+
+    let variants = stmt
+        .query_map([audio_id], |row| {
+            Ok(SpeedVariant {
+                id: row.get(0)?,
+                audio_id: row.get(1)?,
+                speed: row.get::<_, f64>(2)? as f32,
+                file_path: row.get(3)?,
+                file_size: row.get(4)?,
+                created_date: row.get(5)?,
+            })
+        })
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    Ok(variants)
+}
+
+#[tauri::command]
+pub async fn delete_speed_variants(
+    audio_id: i64,
+    conn: State<'_, Arc<Mutex<Connection>>>,
+) -> Result<(), String> {
+    let conn = conn.lock().await;
+    remove_variants(&conn, audio_id)
+}
@@ -0,0 +1,292 @@
+//! 任务重复规则的星期匹配
+//!
+//! 星期约定：0=周日，1=周一 … 6=周六（与 JS `Date.getDay()` 及前端 dayNames 一致）。
+//! `custom_days` 以 JSON 数组保存，例如 `[1,3,5]` 表示周一、周三、周五。
+//! 任务还可以限定有效日期范围 `start_date`/`end_date`（YYYY-MM-DD，含两端，未设置的一端不限）；
+//! `date` 模式只在 `start_date` 当天执行（保存时 `end_date` 与其相同）。
+//! `cron` 模式的 `custom_days` 为 cron 表达式（见 cron 模块），按表达式的日、月、星期匹配日期。
+
+use chrono::{Datelike, NaiveDate, NaiveTime};
+use std::collections::BTreeMap;
+use crate::cron::CronSchedule;
+
+/// 一周全部七天的掩码
+const ALL_DAYS: u8 = 0b111_1111;
+/// 工作日（周一到周五）的掩码
+const WEEKDAYS: u8 = 0b011_1110;
+/// 周末（周六、周日）的掩码
+const WEEKEND: u8 = 0b100_0001;
+
+/// 日期对应的星期（0=周日 … 6=周六）
+pub fn weekday_index<D: Datelike>(date: &D) -> u32 {
+    date.weekday().num_days_from_sunday()
+}
+
+/// 日期所在的周的周一（每周从周一开始统计）
+pub fn week_start(date: NaiveDate) -> NaiveDate {
+    date - chrono::Duration::days(date.weekday().num_days_from_monday() as i64)
+}
+
+/// 解析 custom_days JSON，忽略超出 0-6 的值
+pub fn parse_custom_days(custom_days: &str) -> Option<Vec<u32>> {
+    let days: Vec<i64> = serde_json::from_str(custom_days).ok()?;
+    Some(
+        days.into_iter()
+            .filter(|d| (0..=6).contains(d))
+            .map(|d| d as u32)
+            .collect(),
+    )
+}
+
+/// 重复规则覆盖的星期掩码（bit n 表示星期 n；cron 规则为可能执行的星期）
+fn days_mask(repeat_mode: &str, custom_days: Option<&str>) -> u8 {
+    match repeat_mode {
+        "daily" | "once" | "date" => ALL_DAYS,
+        "weekday" => WEEKDAYS,
+        "weekend" => WEEKEND,
+        "custom" => custom_days
+            .and_then(parse_custom_days)
+            .map(|days| days.iter().fold(0, |mask, d| mask | (1 << d)))
+            .unwrap_or(0),
+        "cron" => custom_days
+            .and_then(|expression| CronSchedule::parse(expression).ok())
+            .map_or(0, |cron| cron.weekday_mask()),
+        _ => 0,
+    }
+}
+
+/// 重复规则是否在指定星期执行（"once" 不受星期限制，是否已执行由调用方判断）
+pub fn runs_on_weekday(repeat_mode: &str, custom_days: Option<&str>, weekday: u32) -> bool {
+    weekday <= 6 && days_mask(repeat_mode, custom_days) & (1 << weekday) != 0
+}
+
+/// 日期是否在有效日期范围内
+pub fn in_date_range(start_date: Option<&str>, end_date: Option<&str>, date: NaiveDate) -> bool {
+    let date = date.format("%Y-%m-%d").to_string();
+    start_date.is_none_or(|start| start <= date.as_str()) && end_date.is_none_or(|end| date.as_str() <= end)
+}
+
+/// 重复规则在指定日期是否执行（星期和有效日期范围都要满足；cron 规则按表达式的日、月、星期）
+pub fn runs_on_date(
+    repeat_mode: &str,
+    custom_days: Option<&str>,
+    start_date: Option<&str>,
+    end_date: Option<&str>,
+    date: NaiveDate,
+) -> bool {
+    let runs = match repeat_mode {
+        "cron" => custom_days.and_then(|expression| CronSchedule::parse(expression).ok()).is_some_and(|cron| cron.runs_on(date)),
+        _ => runs_on_weekday(repeat_mode, custom_days, weekday_index(&date)),
+    };
+    in_date_range(start_date, end_date, date) && runs
+}
+
+/// 一天中的触发时刻：cron 规则为表达式的全部时刻，其余为任务的 hour:minute
+pub fn trigger_times(repeat_mode: &str, custom_days: Option<&str>, time: NaiveTime) -> Vec<NaiveTime> {
+    match repeat_mode {
+        "cron" => custom_days
+            .and_then(|expression| CronSchedule::parse(expression).ok())
+            .map(|cron| cron.times().collect())
+            .unwrap_or_default(),
+        _ => vec![time],
+    }
+}
+
+/// 两条重复规则是否可能在同一天执行
+pub fn repeat_rules_overlap(
+    mode1: &str,
+    days1: Option<&str>,
+    mode2: &str,
+    days2: Option<&str>,
+) -> bool {
+    days_mask(mode1, days1) & days_mask(mode2, days2) != 0
+}
+
+/// 两个带有效日期范围的规则是否可能在同一天执行：范围有交集，且交集内有两条规则都执行的星期
+/// （交集不足一周时逐天检查）
+pub fn schedules_overlap(
+    (mode1, days1): (&str, Option<&str>),
+    range1: (Option<&str>, Option<&str>),
+    (mode2, days2): (&str, Option<&str>),
+    range2: (Option<&str>, Option<&str>),
+) -> bool {
+    let parse = |date: Option<&str>| date.and_then(|date| NaiveDate::parse_from_str(date, "%Y-%m-%d").ok());
+    let start = parse(range1.0).max(parse(range2.0));
+    let end = match (parse(range1.1), parse(range2.1)) {
+        (Some(end1), Some(end2)) => Some(end1.min(end2)),
+        (end1, end2) => end1.or(end2),
+    };
+
+    match (start, end) {
+        (Some(start), Some(end)) if (end - start).num_days() < 7 => start
+            .iter_days()
+            .take_while(|date| *date <= end)
+            .any(|date| {
+                let day = weekday_index(&date);
+                runs_on_weekday(mode1, days1, day) && runs_on_weekday(mode2, days2, day)
+            }),
+        _ => repeat_rules_overlap(mode1, days1, mode2, days2),
+    }
+}
+
+/// 将重复规则整体平移 offset 天（执行时间跨过午夜时使用）；
+/// 每天执行的规则不变，其余规则改写为等价的 custom 规则
+pub fn shift_repeat_rule(repeat_mode: &str, custom_days: Option<&str>, offset: i64) -> (String, Option<String>) {
+    if matches!(repeat_mode, "daily" | "once" | "date" | "cron") || offset.rem_euclid(7) == 0 {
+        return (repeat_mode.to_string(), custom_days.map(str::to_string));
+    }
+
+    let mask = days_mask(repeat_mode, custom_days);
+    let days: Vec<i64> = (0..7)
+        .filter(|d| mask & (1 << ((d - offset).rem_euclid(7))) != 0)
+        .collect();
+    ("custom".to_string(), serde_json::to_string(&days).ok())
+}
+
+/// 解析按星期指定播放列表的映射 JSON，例如 `{"1":3,"2":5}`（键为星期，值为播放列表ID）
+pub fn parse_weekday_playlists(mapping: &str) -> Option<BTreeMap<u32, i64>> {
+    let raw: BTreeMap<String, i64> = serde_json::from_str(mapping).ok()?;
+    raw.into_iter()
+        .map(|(day, playlist_id)| {
+            let day: u32 = day.trim().parse().ok()?;
+            (day <= 6).then_some((day, playlist_id))
+        })
+        .collect()
+}
+
+/// 指定星期应播放的播放列表，未映射（或映射无效）时使用默认播放列表
+pub fn playlist_for_weekday(mapping: Option<&str>, weekday: u32, default_playlist: i64) -> i64 {
+    mapping
+        .and_then(parse_weekday_playlists)
+        .and_then(|mapping| mapping.get(&weekday).copied())
+        .unwrap_or(default_playlist)
+}
+
+/// 规范化旧数据中的 custom_days：旧版本可能以 7 表示周日，同时去重排序；无法解析时返回 None
+pub fn normalize_custom_days(custom_days: &str) -> Option<String> {
+    let days: Vec<i64> = serde_json::from_str(custom_days).ok()?;
+    let mut days: Vec<i64> = days
+        .into_iter()
+        .map(|d| if d == 7 { 0 } else { d })
+        .filter(|d| (0..=6).contains(d))
+        .collect();
+    days.sort_unstable();
+    days.dedup();
+    serde_json::to_string(&days).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    #[test]
+    fn weekday_index_starts_from_sunday() {
+        // 2024-06-02 是周日，2024-06-08 是周六
+        let sunday = NaiveDate::from_ymd_opt(2024, 6, 2).unwrap();
+        let monday = NaiveDate::from_ymd_opt(2024, 6, 3).unwrap();
+        let saturday = NaiveDate::from_ymd_opt(2024, 6, 8).unwrap();
+        assert_eq!(weekday_index(&sunday), 0);
+        assert_eq!(weekday_index(&monday), 1);
+        assert_eq!(weekday_index(&saturday), 6);
+    }
+
+    #[test]
+    fn weekday_mode_matches_monday_to_friday() {
+        assert!(!runs_on_weekday("weekday", None, 0));
+        for day in 1..=5 {
+            assert!(runs_on_weekday("weekday", None, day));
+        }
+        assert!(!runs_on_weekday("weekday", None, 6));
+    }
+
+    #[test]
+    fn weekend_mode_matches_saturday_and_sunday() {
+        assert!(runs_on_weekday("weekend", None, 0));
+        assert!(runs_on_weekday("weekend", None, 6));
+        for day in 1..=5 {
+            assert!(!runs_on_weekday("weekend", None, day));
+        }
+    }
+
+    #[test]
+    fn custom_mode_matches_listed_days() {
+        let days = Some("[0,3]");
+        assert!(runs_on_weekday("custom", days, 0));
+        assert!(runs_on_weekday("custom", days, 3));
+        assert!(!runs_on_weekday("custom", days, 1));
+        assert!(!runs_on_weekday("custom", None, 3));
+        assert!(!runs_on_weekday("custom", Some("not json"), 3));
+    }
+
+    #[test]
+    fn daily_and_once_match_every_day() {
+        for day in 0..=6 {
+            assert!(runs_on_weekday("daily", None, day));
+            assert!(runs_on_weekday("once", None, day));
+        }
+        assert!(!runs_on_weekday("daily", None, 7));
+        assert!(!runs_on_weekday("unknown", None, 1));
+    }
+
+    #[test]
+    fn overlap_between_rules() {
+        assert!(repeat_rules_overlap("weekday", None, "daily", None));
+        assert!(!repeat_rules_overlap("weekday", None, "weekend", None));
+        assert!(repeat_rules_overlap("weekend", None, "custom", Some("[0]")));
+        assert!(!repeat_rules_overlap("weekend", None, "custom", Some("[1,2]")));
+        assert!(repeat_rules_overlap("custom", Some("[2,4]"), "custom", Some("[4]")));
+        assert!(!repeat_rules_overlap("custom", Some("[2,4]"), "custom", Some("[3]")));
+        assert!(repeat_rules_overlap("once", None, "custom", Some("[3]")));
+    }
+
+    #[test]
+    fn date_range_limits_matching_days() {
+        // 2024-06-03 是周一
+        let monday = NaiveDate::from_ymd_opt(2024, 6, 3).unwrap();
+        assert!(runs_on_date("weekday", None, Some("2024-06-01"), Some("2024-08-31"), monday));
+        assert!(!runs_on_date("weekday", None, Some("2024-06-04"), None, monday));
+        assert!(!runs_on_date("weekday", None, None, Some("2024-06-02"), monday));
+        assert!(runs_on_date("date", None, Some("2024-06-03"), Some("2024-06-03"), monday));
+        assert!(!runs_on_date("date", None, Some("2024-06-04"), Some("2024-06-04"), monday));
+    }
+
+    #[test]
+    fn overlap_considers_date_ranges() {
+        let summer = (Some("2024-07-01"), Some("2024-08-31"));
+        let june = (Some("2024-06-01"), Some("2024-06-30"));
+        assert!(schedules_overlap(("daily", None), summer, ("weekday", None), (None, None)));
+        assert!(!schedules_overlap(("daily", None), summer, ("daily", None), june));
+        // 2024-06-01 是周六，只与周末规则重叠
+        let june_first = (Some("2024-06-01"), Some("2024-06-01"));
+        assert!(schedules_overlap(("date", None), june_first, ("weekend", None), (None, None)));
+        assert!(!schedules_overlap(("date", None), june_first, ("weekday", None), june));
+    }
+
+    #[test]
+    fn normalize_maps_seven_to_sunday_and_sorts() {
+        assert_eq!(normalize_custom_days("[7,3,1,3]").as_deref(), Some("[0,1,3]"));
+        assert_eq!(normalize_custom_days("[0,6,9,-1]").as_deref(), Some("[0,6]"));
+        assert_eq!(normalize_custom_days("oops"), None);
+    }
+
+    #[test]
+    fn weekday_playlist_mapping_falls_back_to_default() {
+        let mapping = Some(r#"{"1":3,"2":5}"#);
+        assert_eq!(playlist_for_weekday(mapping, 1, 9), 3);
+        assert_eq!(playlist_for_weekday(mapping, 2, 9), 5);
+        assert_eq!(playlist_for_weekday(mapping, 3, 9), 9);
+        assert_eq!(playlist_for_weekday(None, 1, 9), 9);
+        assert_eq!(parse_weekday_playlists(r#"{"7":1}"#), None);
+        assert_eq!(parse_weekday_playlists("[1,2]"), None);
+    }
+
+    #[test]
+    fn shift_rotates_days_across_week_boundary() {
+        assert_eq!(shift_repeat_rule("weekday", None, -1), ("custom".to_string(), Some("[0,1,2,3,4]".to_string())));
+        assert_eq!(shift_repeat_rule("weekend", None, 1), ("custom".to_string(), Some("[0,1]".to_string())));
+        assert_eq!(shift_repeat_rule("custom", Some("[6]"), 1).1.as_deref(), Some("[0]"));
+        assert_eq!(shift_repeat_rule("daily", None, 1), ("daily".to_string(), None));
+        assert_eq!(shift_repeat_rule("weekday", None, 0), ("weekday".to_string(), None));
+    }
+}
@@ -0,0 +1,301 @@
+//! 最简单的 XLSX 写入：按 Office Open XML 格式直接生成 zip 包，
+//! 只支持文本和数字单元格、加粗行和列宽，足够导出报表
+
+use std::fs::File;
+use std::io::Write;
+use zip::write::FileOptions;
+use zip::ZipWriter;
+use crate::i18n;
+
+const CONTENT_TYPES_NS: &str = "http://schemas.openxmlformats.org/package/2006/content-types";
+const RELATIONSHIPS_NS: &str = "http://schemas.openxmlformats.org/package/2006/relationships";
+const SPREADSHEET_NS: &str = "http://schemas.openxmlformats.org/spreadsheetml/2006/main";
+const DOC_RELATIONSHIPS_NS: &str = "http://schemas.openxmlformats.org/officeDocument/2006/relationships";
+const XML_HEADER: &str = "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n";
+
+// 样式表：0 为默认样式，1 为加粗
+const STYLES_XML: &str = "<fonts count=\"2\"><font><sz val=\"11\"/><name val=\"Calibri\"/></font>\
+    <font><b/><sz val=\"11\"/><name val=\"Calibri\"/></font></fonts>\
+    <fills count=\"2\"><fill><patternFill patternType=\"none\"/></fill><fill><patternFill patternType=\"gray125\"/></fill></fills>\
+    <borders count=\"1\"><border><left/><right/><top/><bottom/><diagonal/></border></borders>\
+    <cellStyleXfs count=\"1\"><xf numFmtId=\"0\" fontId=\"0\" fillId=\"0\" borderId=\"0\"/></cellStyleXfs>\
+    <cellXfs count=\"2\"><xf numFmtId=\"0\" fontId=\"0\" fillId=\"0\" borderId=\"0\" xfId=\"0\"/>\
+    <xf numFmtId=\"0\" fontId=\"1\" fillId=\"0\" borderId=\"0\" xfId=\"0\" applyFont=\"1\"/></cellXfs>\
+    <cellStyles count=\"1\"><cellStyle name=\"Normal\" xfId=\"0\" builtinId=\"0\"/></cellStyles>";
+
+pub enum Cell {
+    Text(String),
+    Number(f64),
+    Empty,
+}
+
+impl From<&str> for Cell {
+    fn from(value: &str) -> Self {
+        Cell::Text(value.to_string())
+    }
+}
+
+impl From<String> for Cell {
+    fn from(value: String) -> Self {
+        Cell::Text(value)
+    }
+}
+
+impl From<i64> for Cell {
+    fn from(value: i64) -> Self {
+        Cell::Number(value as f64)
+    }
+}
+
+impl From<f64> for Cell {
+    fn from(value: f64) -> Self {
+        Cell::Number(value)
+    }
+}
+
+impl From<Option<String>> for Cell {
+    fn from(value: Option<String>) -> Self {
+        value.map_or(Cell::Empty, Cell::Text)
+    }
+}
+
+struct Row {
+    cells: Vec<Cell>,
+    bold: bool,
+}
+
+/// 一个工作表
+pub struct Sheet {
+    name: String,
+    widths: Vec<f64>,
+    rows: Vec<Row>,
+}
+
+impl Sheet {
+    /// 工作表名最长 31 个字符，且不能包含 []:*?/\
+    pub fn new(name: &str) -> Self {
+        let name: String = name
+            .chars()
+            .map(|c| if "[]:*?/\\".contains(c) { '_' } else { c })
+            .take(31)
+            .collect();
+        Self { name, widths: Vec::new(), rows: Vec::new() }
+    }
+
+    /// 设置各列的宽度（字符数）
+    pub fn widths(mut self, widths: &[f64]) -> Self {
+        self.widths = widths.to_vec();
+        self
+    }
+
+    pub fn row(&mut self, cells: Vec<Cell>) {
+        self.rows.push(Row { cells, bold: false });
+    }
+
+    /// 加粗的行（表头、合计）
+    pub fn bold_row(&mut self, cells: Vec<Cell>) {
+        self.rows.push(Row { cells, bold: true });
+    }
+
+    pub fn blank_row(&mut self) {
+        self.rows.push(Row { cells: Vec::new(), bold: false });
+    }
+
+    fn to_xml(&self) -> String {
+        let mut xml = format!("{}<worksheet xmlns=\"{}\">", XML_HEADER, SPREADSHEET_NS);
+        if !self.widths.is_empty() {
+            xml.push_str("<cols>");
+            for (index, width) in self.widths.iter().enumerate() {
+                xml.push_str(&format!(
+                    "<col min=\"{0}\" max=\"{0}\" width=\"{1}\" customWidth=\"1\"/>",
+                    index + 1,
+                    width
+                ));
+            }
+            xml.push_str("</cols>");
+        }
+
+        xml.push_str("<sheetData>");
+        for (row_index, row) in self.rows.iter().enumerate() {
+            let row_number = row_index + 1;
+            xml.push_str(&format!("<row r=\"{}\">", row_number));
+            let style = if row.bold { " s=\"1\"" } else { "" };
+            for (col_index, cell) in row.cells.iter().enumerate() {
+                let reference = format!("{}{}", column_name(col_index), row_number);
+                match cell {
+                    Cell::Text(text) => xml.push_str(&format!(
+                        "<c r=\"{}\" t=\"inlineStr\"{}><is><t xml:space=\"preserve\">{}</t></is></c>",
+                        reference,
+                        style,
+                        escape(text)
+                    )),
+                    Cell::Number(value) => {
+                        xml.push_str(&format!("<c r=\"{}\"{}><v>{}</v></c>", reference, style, value))
+                    }
+                    Cell::Empty => {}
+                }
+            }
+            xml.push_str("</row>");
+        }
+        xml.push_str("</sheetData></worksheet>");
+        xml
+    }
+}
+
+/// 列序号（从 0 开始）转为 A、B … Z、AA …
+fn column_name(mut index: usize) -> String {
+    let mut name = Vec::new();
+    loop {
+        name.push(b'A' + (index % 26) as u8);
+        if index < 26 {
+            break;
+        }
+        index = index / 26 - 1;
+    }
+    name.reverse();
+    String::from_utf8(name).unwrap_or_default()
+}
+
+/// XML 转义，并去掉 XML 中不允许出现的控制字符
+fn escape(value: &str) -> String {
+    value
+        .chars()
+        .filter(|c| !c.is_control() || matches!(c, '\t' | '\n' | '\r'))
+        .collect::<String>()
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// 把工作表写成 xlsx 文件
+pub fn write_workbook(path: &str, sheets: &[Sheet]) -> Result<(), String> {
+    let file = File::create(path).map_err(|e| i18n::trf("写入文件失败: {}", &[&e]))?;
+    let mut zip = ZipWriter::new(file);
+    let mut add = |name: &str, content: String| -> Result<(), String> {
+        zip.start_file(name, FileOptions::default()).map_err(|e| e.to_string())?;
+        zip.write_all(content.as_bytes()).map_err(|e| e.to_string())
+    };
+
+    let mut content_types = format!(
+        "{}<Types xmlns=\"{}\">\
+         <Default Extension=\"rels\" ContentType=\"application/vnd.openxmlformats-package.relationships+xml\"/>\
+         <Default Extension=\"xml\" ContentType=\"application/xml\"/>\
+         <Override PartName=\"/xl/workbook.xml\" ContentType=\"application/vnd.openxmlformats-officedocument.spreadsheetml.sheet.main+xml\"/>\
+         <Override PartName=\"/xl/styles.xml\" ContentType=\"application/vnd.openxmlformats-officedocument.spreadsheetml.styles+xml\"/>",
+        XML_HEADER, CONTENT_TYPES_NS
+    );
+    let mut workbook = format!(
+        "{}<workbook xmlns=\"{}\" xmlns:r=\"{}\"><sheets>",
+        XML_HEADER, SPREADSHEET_NS, DOC_RELATIONSHIPS_NS
+    );
+    let mut workbook_rels = format!("{}<Relationships xmlns=\"{}\">", XML_HEADER, RELATIONSHIPS_NS);
+
+    for (index, sheet) in sheets.iter().enumerate() {
+        let id = index + 1;
+        content_types.push_str(&format!(
+            "<Override PartName=\"/xl/worksheets/sheet{}.xml\" \
+             ContentType=\"application/vnd.openxmlformats-officedocument.spreadsheetml.worksheet+xml\"/>",
+            id
+        ));
+        workbook.push_str(&format!("<sheet name=\"{}\" sheetId=\"{}\" r:id=\"rId{}\"/>", escape(&sheet.name), id, id));
+        workbook_rels.push_str(&format!(
+            "<Relationship Id=\"rId{}\" Type=\"{}/worksheet\" Target=\"worksheets/sheet{}.xml\"/>",
+            id, DOC_RELATIONSHIPS_NS, id
+        ));
+        add(&format!("xl/worksheets/sheet{}.xml", id), sheet.to_xml())?;
+    }
+    content_types.push_str("</Types>");
+    workbook.push_str("</sheets></workbook>");
+    workbook_rels.push_str(&format!(
+        "<Relationship Id=\"rId{}\" Type=\"{}/styles\" Target=\"styles.xml\"/></Relationships>",
+        sheets.len() + 1,
+        DOC_RELATIONSHIPS_NS
+    ));
+
+    add("[Content_Types].xml", content_types)?;
+    add(
+        "_rels/.rels",
+        format!(
+            "{}<Relationships xmlns=\"{}\"><Relationship Id=\"rId1\" Type=\"{}/officeDocument\" Target=\"xl/workbook.xml\"/></Relationships>",
+            XML_HEADER, RELATIONSHIPS_NS, DOC_RELATIONSHIPS_NS
+        ),
+    )?;
+    add("xl/workbook.xml", workbook)?;
+    add("xl/_rels/workbook.xml.rels", workbook_rels)?;
+    add("xl/styles.xml", format!("{}<styleSheet xmlns=\"{}\">{}</styleSheet>", XML_HEADER, SPREADSHEET_NS, STYLES_XML))?;
+
+    zip.finish().map_err(|e| i18n::trf("写入文件失败: {}", &[&e]))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    #[test]
+    fn column_names_past_z() {
+        let names: Vec<String> = [0, 1, 25, 26, 27, 51, 52, 701, 702].into_iter().map(column_name).collect();
+        assert_eq!(names, ["A", "B", "Z", "AA", "AB", "AZ", "BA", "ZZ", "AAA"]);
+    }
+
+    #[test]
+    fn escapes_cell_text() {
+        assert_eq!(escape("<a href=\"x\">R&D</a>"), "&lt;a href=&quot;x&quot;&gt;R&amp;D&lt;/a&gt;");
+        // 保留制表符和换行，去掉其他控制字符
+        assert_eq!(escape("第一行\n\t第二行\u{1}\u{b}"), "第一行\n\t第二行");
+    }
+
+    #[test]
+    fn sheet_xml_structure() {
+        let mut sheet = Sheet::new("统计: 2024/06").widths(&[12.0, 8.5]);
+        sheet.bold_row(vec!["名称".into(), "次数".into()]);
+        sheet.blank_row();
+        let mut cells: Vec<Cell> = (0..27).map(|_| Cell::Empty).collect();
+        cells[0] = "a < b".into();
+        cells[1] = 3i64.into();
+        cells[26] = 1.5.into();
+        sheet.row(cells);
+
+        assert_eq!(sheet.name, "统计_ 2024_06");
+        let xml = sheet.to_xml();
+        assert!(xml.contains("<cols><col min=\"1\" max=\"1\" width=\"12\" customWidth=\"1\"/><col min=\"2\" max=\"2\" width=\"8.5\" customWidth=\"1\"/></cols>"));
+        assert!(xml.contains("<row r=\"1\"><c r=\"A1\" t=\"inlineStr\" s=\"1\"><is><t xml:space=\"preserve\">名称</t></is></c>"));
+        assert!(xml.contains("<row r=\"2\"></row>"));
+        assert!(xml.contains("<c r=\"A3\" t=\"inlineStr\"><is><t xml:space=\"preserve\">a &lt; b</t></is></c><c r=\"B3\"><v>3</v></c><c r=\"AA3\"><v>1.5</v></c></row>"));
+        // 空单元格不写出
+        assert!(!xml.contains("r=\"C3\""));
+    }
+
+    #[test]
+    fn workbook_package_parts() {
+        let path = std::env::temp_dir().join(format!("moerduo-xlsx-test-{}.xlsx", std::process::id()));
+        let mut first = Sheet::new("执行记录");
+        first.row(vec!["x".into()]);
+        write_workbook(&path.to_string_lossy(), &[first, Sheet::new("R&D")]).unwrap();
+
+        let mut archive = zip::ZipArchive::new(File::open(&path).unwrap()).unwrap();
+        let mut names: Vec<String> = archive.file_names().map(str::to_string).collect();
+        names.sort();
+        assert_eq!(
+            names,
+            [
+                "[Content_Types].xml",
+                "_rels/.rels",
+                "xl/_rels/workbook.xml.rels",
+                "xl/styles.xml",
+                "xl/workbook.xml",
+                "xl/worksheets/sheet1.xml",
+                "xl/worksheets/sheet2.xml",
+            ]
+        );
+        let mut workbook = String::new();
+        archive.by_name("xl/workbook.xml").unwrap().read_to_string(&mut workbook).unwrap();
+        assert!(workbook.contains("<sheet name=\"执行记录\" sheetId=\"1\" r:id=\"rId1\"/><sheet name=\"R&amp;D\" sheetId=\"2\" r:id=\"rId2\"/>"));
+        let mut rels = String::new();
+        archive.by_name("xl/_rels/workbook.xml.rels").unwrap().read_to_string(&mut rels).unwrap();
+        assert!(rels.contains("Id=\"rId3\"") && rels.contains("Target=\"styles.xml\""));
+        let _ = std::fs::remove_file(&path);
+    }
+}
@@ -1,6 +1,7 @@
-import { useState } from 'react'
+import { useState, useEffect } from 'react'
 import { BrowserRouter as Router, Routes, Route, Link } from 'react-router-dom'
 import { Music, List, Clock, BarChart3, Settings, HelpCircle } from 'lucide-react'
+import { invoke } from '@tauri-apps/api/tauri'
 import AudioLibrary from './pages/AudioLibrary'
 import Playlists from './pages/Playlists'
 import Tasks from './pages/Tasks'
@@ -8,15 +9,35 @@ import Statistics from './pages/Statistics'
 import SettingsPage from './pages/Settings'
 import Help from './pages/Help'
 import PlayController from './components/PlayController'
+import CountdownTimer from './components/CountdownTimer'
+import NextTaskInfo from './components/NextTaskInfo'
+import PreflightBanner from './components/PreflightBanner'
+import RemovableDriveImport from './components/RemovableDriveImport'
+import KioskView, { KioskStatus } from './components/KioskView'
 import { PlayerProvider } from './contexts/PlayerContext'
 import { useTheme } from './hooks/useTheme'
 
 function App() {
   const [activeTab, setActiveTab] = useState('audio')
 
+  const [kioskStatus, setKioskStatus] = useState<KioskStatus | null>(null)
+
   // 在应用启动时加载并应用主题
   useTheme()
 
+  // 儿童模式状态（设置页进入儿童模式后通过 kiosk-changed 事件刷新）
+  const loadKioskStatus = () => {
+    invoke<KioskStatus>('get_kiosk_status')
+      .then(setKioskStatus)
+      .catch((error) => console.error('加载儿童模式状态失败:', error))
+  }
+
+  useEffect(() => {
+    loadKioskStatus()
+    window.addEventListener('kiosk-changed', loadKioskStatus)
+    return () => window.removeEventListener('kiosk-changed', loadKioskStatus)
+  }, [])
+
   const menuItems = [
     { id: 'audio', label: '音频库', icon: Music, path: '/' },
     { id: 'playlists', label: '播放列表', icon: List, path: '/playlists' },
@@ -26,16 +47,27 @@ function App() {
     { id: 'help', label: '帮助', icon: HelpCircle, path: '/help' },
   ]
 
+  if (kioskStatus?.enabled) {
+    return (
+      <PlayerProvider>
+        <KioskView status={kioskStatus} onExit={loadKioskStatus} />
+      </PlayerProvider>
+    )
+  }
+
   return (
     <PlayerProvider>
       <Router>
         <div className="flex flex-col h-screen bg-gray-50">
+          <PreflightBanner />
+          <RemovableDriveImport />
           <div className="flex flex-1 overflow-hidden">
             {/* 侧边栏 */}
             <aside className="w-56 bg-white border-r border-gray-200 flex flex-col">
               <div className="p-4 border-b border-gray-200">
                 <h1 className="text-xl font-bold text-gray-800">磨耳朵</h1>
                 <p className="text-xs text-gray-500 mt-1">定时音频播放软件</p>
+                <NextTaskInfo />
               </div>
 
               <nav className="flex-1 p-3">
@@ -55,6 +87,10 @@ function App() {
                   </Link>
                 ))}
               </nav>
+
+              <div className="p-3 border-t border-gray-200">
+                <CountdownTimer />
+              </div>
             </aside>
 
             {/* 主内容区 */}
@@ -0,0 +1,137 @@
+import { useState, useEffect } from 'react'
+import { PenLine, X } from 'lucide-react'
+import { invoke } from '@tauri-apps/api/tauri'
+
+interface RenamePreview {
+  id: number
+  old_name: string
+  new_name: string
+}
+
+interface BatchRenameDialogProps {
+  ids: number[]
+  onClose: () => void
+  onRenamed: () => void
+}
+
+// 按规则批量重命名选中的音频：先预览，确认后再写入
+export default function BatchRenameDialog({ ids, onClose, onRenamed }: BatchRenameDialogProps) {
+  const [pattern, setPattern] = useState('{original}')
+  const [start, setStart] = useState(1)
+  const [previews, setPreviews] = useState<RenamePreview[]>([])
+  const [error, setError] = useState<string | null>(null)
+  const [applying, setApplying] = useState(false)
+
+  // 规则变化后重新预览
+  useEffect(() => {
+    if (ids.length === 0) return
+    const timer = setTimeout(() => {
+      invoke<RenamePreview[]>('batch_rename', { ids, pattern, start })
+        .then((result) => {
+          setPreviews(result)
+          setError(null)
+        })
+        .catch((e) => {
+          setPreviews([])
+          setError(String(e))
+        })
+    }, 300)
+    return () => clearTimeout(timer)
+  }, [ids, pattern, start])
+
+  if (ids.length === 0) return null
+
+  const changedCount = previews.filter((p) => p.new_name !== p.old_name).length
+
+  const handleApply = async () => {
+    setApplying(true)
+    try {
+      await invoke('batch_rename', { ids, pattern, start, apply: true })
+      onRenamed()
+      onClose()
+    } catch (e) {
+      alert('重命名失败: ' + e)
+    } finally {
+      setApplying(false)
+    }
+  }
+
+  return (
+    <div className="fixed inset-0 bg-black bg-opacity-50 flex items-center justify-center z-50">
+      <div className="bg-white rounded-lg shadow-xl max-w-2xl w-full mx-4">
+        <div className="flex items-center justify-between p-6 border-b border-gray-200">
+          <h3 className="text-lg font-semibold text-gray-800 flex items-center gap-2">
+            <PenLine size={20} />
+            批量重命名（{ids.length} 个）
+          </h3>
+          <button onClick={onClose} className="text-gray-400 hover:text-gray-600 transition-colors">
+            <X size={20} />
+          </button>
+        </div>
+
+        <div className="p-6">
+          <div className="flex gap-2 mb-2">
+            <input
+              type="text"
+              value={pattern}
+              onChange={(e) => setPattern(e.target.value)}
+              className="flex-1 px-3 py-2 border border-gray-300 rounded-lg focus:outline-none focus:ring-2 focus:ring-blue-500"
+              placeholder="如：第{n}课 {tag}"
+            />
+            <label className="flex items-center gap-1 text-sm text-gray-600">
+              起始序号
+              <input
+                type="number"
+                min="0"
+                value={start}
+                onChange={(e) => setStart(parseInt(e.target.value) || 0)}
+                className="w-16 px-2 py-2 border border-gray-300 rounded-lg"
+              />
+            </label>
+          </div>
+          <p className="text-xs text-gray-500 mb-4">
+            {'{n}'} 序号（按选择顺序）、{'{date}'} 导入日期、{'{original}'} 原名称、{'{tag}'} 内嵌标题、
+            {'{tag:artist}'} / {'{tag:album}'} / {'{tag:track}'} 其他标签；扩展名保持不变
+          </p>
+
+          <div className="max-h-80 overflow-y-auto border border-gray-200 rounded-lg">
+            {error ? (
+              <p className="p-3 text-sm text-red-600">{error}</p>
+            ) : (
+              <table className="w-full text-sm">
+                <tbody>
+                  {previews.map((preview) => (
+                    <tr key={preview.id} className="border-b border-gray-100">
+                      <td className="px-3 py-1.5 text-gray-500 break-all">{preview.old_name}</td>
+                      <td className="px-1 text-gray-400">→</td>
+                      <td
+                        className={`px-3 py-1.5 break-all ${
+                          preview.new_name === preview.old_name ? 'text-gray-400' : 'text-gray-800'
+                        }`}
+                      >
+                        {preview.new_name}
+                      </td>
+                    </tr>
+                  ))}
+                </tbody>
+              </table>
+            )}
+          </div>
+        </div>
+
+        <div className="flex justify-end gap-2 px-6 pb-6">
+          <button onClick={onClose} className="px-4 py-2 text-gray-700 hover:bg-gray-100 rounded-lg">
+            取消
+          </button>
+          <button
+            onClick={handleApply}
+            disabled={applying || changedCount === 0}
+            className="px-4 py-2 bg-blue-600 text-white rounded-lg hover:bg-blue-700 disabled:opacity-50"
+          >
+            {applying ? '重命名中...' : `重命名 ${changedCount} 个`}
+          </button>
+        </div>
+      </div>
+    </div>
+  )
+}
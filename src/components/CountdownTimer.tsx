@@ -0,0 +1,130 @@
+import { useState, useEffect } from 'react'
+import { Timer, X } from 'lucide-react'
+import { invoke } from '@tauri-apps/api/tauri'
+import { listen } from '@tauri-apps/api/event'
+
+interface CountdownInfo {
+  minutes: number
+  ends_at: string
+  remaining_secs: number
+  chime_audio_id: number | null
+}
+
+interface AudioFile {
+  id: number
+  original_name: string
+}
+
+export default function CountdownTimer() {
+  const [countdown, setCountdown] = useState<CountdownInfo | null>(null)
+  const [remaining, setRemaining] = useState(0)
+  const [minutes, setMinutes] = useState(25)
+  const [chimeId, setChimeId] = useState<number | ''>('')
+  const [audioFiles, setAudioFiles] = useState<AudioFile[]>([])
+  const [expanded, setExpanded] = useState(false)
+
+  useEffect(() => {
+    invoke<CountdownInfo | null>('get_countdown').then(setCountdown).catch(console.error)
+
+    const unlistenChanged = listen<CountdownInfo | null>('countdown-changed', (event) => {
+      setCountdown(event.payload)
+    })
+    const unlistenFinished = listen<number>('countdown-finished', (event) => {
+      alert(`${event.payload} 分钟倒计时结束！`)
+    })
+    return () => {
+      unlistenChanged.then(fn => fn())
+      unlistenFinished.then(fn => fn())
+    }
+  }, [])
+
+  // 按结束时间每秒刷新剩余时间
+  useEffect(() => {
+    if (!countdown) return
+    const endsAt = Date.now() + countdown.remaining_secs * 1000
+    const update = () => setRemaining(Math.max(0, Math.round((endsAt - Date.now()) / 1000)))
+    update()
+    const timer = setInterval(update, 1000)
+    return () => clearInterval(timer)
+  }, [countdown])
+
+  const handleExpand = async () => {
+    setExpanded(true)
+    try {
+      setAudioFiles(await invoke<AudioFile[]>('get_audio_files'))
+    } catch (error) {
+      console.error('加载音频列表失败:', error)
+    }
+  }
+
+  const handleStart = async () => {
+    try {
+      await invoke('start_countdown', { minutes, chimeAudioId: chimeId === '' ? null : chimeId })
+      setExpanded(false)
+    } catch (error) {
+      alert('开始倒计时失败: ' + error)
+    }
+  }
+
+  const formatRemaining = (secs: number) =>
+    `${Math.floor(secs / 60)}:${(secs % 60).toString().padStart(2, '0')}`
+
+  if (countdown) {
+    return (
+      <div className="flex items-center justify-between px-3 py-2 bg-orange-50 text-orange-700 rounded-lg text-sm">
+        <span className="flex items-center gap-2">
+          <Timer size={16} />
+          {formatRemaining(remaining)}
+        </span>
+        <button onClick={() => invoke('cancel_countdown')} className="p-1 hover:bg-orange-100 rounded" title="取消倒计时">
+          <X size={14} />
+        </button>
+      </div>
+    )
+  }
+
+  if (!expanded) {
+    return (
+      <button
+        onClick={handleExpand}
+        className="w-full flex items-center gap-3 px-3 py-2.5 rounded-lg text-gray-700 hover:bg-gray-100 transition-colors"
+      >
+        <Timer size={20} />
+        <span className="text-sm font-medium">倒计时</span>
+      </button>
+    )
+  }
+
+  return (
+    <div className="space-y-2 p-2 border border-gray-200 rounded-lg text-sm">
+      <div className="flex items-center gap-2">
+        <input
+          type="number"
+          min="1"
+          value={minutes}
+          onChange={(e) => setMinutes(parseInt(e.target.value) || 1)}
+          className="w-16 px-2 py-1 border border-gray-300 rounded"
+        />
+        <span className="text-gray-600">分钟</span>
+      </div>
+      <select
+        value={chimeId}
+        onChange={(e) => setChimeId(e.target.value ? parseInt(e.target.value) : '')}
+        className="w-full px-2 py-1 border border-gray-300 rounded"
+      >
+        <option value="">上次的提示音</option>
+        {audioFiles.map((audio) => (
+          <option key={audio.id} value={audio.id}>{audio.original_name}</option>
+        ))}
+      </select>
+      <div className="flex gap-2">
+        <button onClick={handleStart} className="flex-1 px-2 py-1 bg-blue-600 text-white rounded hover:bg-blue-700">
+          开始
+        </button>
+        <button onClick={() => setExpanded(false)} className="px-2 py-1 text-gray-600 hover:bg-gray-100 rounded">
+          取消
+        </button>
+      </div>
+    </div>
+  )
+}
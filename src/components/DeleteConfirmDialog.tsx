@@ -65,7 +65,7 @@ export default function DeleteConfirmDialog({
               <p className="font-medium text-gray-800">同时删除硬盘上的文件</p>
               <p className="text-sm text-gray-600 mt-1">
                 {deletePhysicalFile ? (
-                  <span className="text-red-600">⚠️ 警告：文件将被永久删除，无法恢复！</span>
+                  <span className="text-red-600">⚠️ 警告：文件将被永久删除，无法恢复！（引用的外部文件会保留）</span>
                 ) : (
                   <span>仅从音频库中移除，保留硬盘文件</span>
                 )}
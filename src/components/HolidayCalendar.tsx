@@ -0,0 +1,147 @@
+import { useState, useEffect } from 'react'
+import { Plus, Trash2, Upload } from 'lucide-react'
+import { invoke } from '@tauri-apps/api/tauri'
+import { open } from '@tauri-apps/api/dialog'
+
+interface Holiday {
+  date: string
+  name: string
+  source: 'manual' | 'import'
+}
+
+interface HolidayImportResult {
+  imported: number
+  skipped: number
+}
+
+const emptyForm = { date: '', name: '' }
+
+// 本地日期 YYYY-MM-DD
+const formatDate = (date: Date) => {
+  const pad = (value: number) => String(value).padStart(2, '0')
+  return `${date.getFullYear()}-${pad(date.getMonth() + 1)}-${pad(date.getDate())}`
+}
+
+export default function HolidayCalendar() {
+  const [holidays, setHolidays] = useState<Holiday[]>([])
+  const [form, setForm] = useState(emptyForm)
+  const [showPast, setShowPast] = useState(false)
+
+  useEffect(() => {
+    loadHolidays()
+  }, [])
+
+  const loadHolidays = async () => {
+    try {
+      setHolidays(await invoke<Holiday[]>('get_holidays'))
+    } catch (error) {
+      console.error('加载节假日失败:', error)
+    }
+  }
+
+  const handleAdd = async () => {
+    if (!form.date) {
+      alert('请选择日期')
+      return
+    }
+    try {
+      await invoke('add_holiday', { date: form.date, name: form.name })
+      setForm(emptyForm)
+      await loadHolidays()
+    } catch (error) {
+      alert('添加节假日失败: ' + error)
+    }
+  }
+
+  const handleDelete = async (holiday: Holiday) => {
+    try {
+      await invoke('delete_holiday', { date: holiday.date })
+      await loadHolidays()
+    } catch (error) {
+      alert('删除节假日失败: ' + error)
+    }
+  }
+
+  const handleImport = async () => {
+    const selected = await open({
+      multiple: false,
+      filters: [{ name: '假日列表', extensions: ['ics', 'csv', 'txt'] }],
+    })
+    if (!selected || Array.isArray(selected)) return
+    try {
+      const result = await invoke<HolidayImportResult>('import_holidays', { path: selected })
+      const skipped = result.skipped > 0 ? `，跳过 ${result.skipped} 条无法识别的内容` : ''
+      alert(`已导入 ${result.imported} 个节假日${skipped}`)
+      await loadHolidays()
+    } catch (error) {
+      alert('导入节假日失败: ' + error)
+    }
+  }
+
+  const today = formatDate(new Date())
+  const visible = showPast ? holidays : holidays.filter((holiday) => holiday.date >= today)
+
+  return (
+    <div className="space-y-3">
+      <p className="text-sm text-gray-500">
+        勾选了“节假日不执行”的任务在这些日子跳过；可导入公共假日的 .ics 日历，或每行“YYYY-MM-DD,名称”的文本/CSV
+      </p>
+
+      <div className="max-h-60 overflow-y-auto space-y-1">
+        {visible.length === 0 && <p className="text-sm text-gray-400">暂无节假日</p>}
+        {visible.map((holiday) => (
+          <div key={holiday.date} className="flex items-center justify-between px-3 py-1.5 border border-gray-200 rounded-lg">
+            <p className="text-sm text-gray-800">
+              {holiday.date}
+              {holiday.name && <span className="ml-2 text-gray-600">{holiday.name}</span>}
+              {holiday.source === 'import' && <span className="ml-2 text-xs text-gray-400">导入</span>}
+            </p>
+            <button
+              onClick={() => handleDelete(holiday)}
+              className="p-1 text-red-600 hover:bg-red-50 rounded-lg transition-colors"
+              title="删除"
+            >
+              <Trash2 size={14} />
+            </button>
+          </div>
+        ))}
+      </div>
+
+      <div className="flex items-center gap-2">
+        <input
+          type="date"
+          value={form.date}
+          onChange={(e) => setForm({ ...form, date: e.target.value })}
+          className="px-3 py-2 border border-gray-300 rounded-lg text-sm"
+        />
+        <input
+          type="text"
+          value={form.name}
+          onChange={(e) => setForm({ ...form, name: e.target.value })}
+          placeholder="名称（可选），如：国庆节"
+          className="flex-1 px-3 py-2 border border-gray-300 rounded-lg text-sm"
+        />
+        <button
+          onClick={handleAdd}
+          className="flex items-center gap-1 px-3 py-2 bg-purple-600 text-white rounded-lg hover:bg-purple-700 transition-colors text-sm"
+        >
+          <Plus size={16} />
+          <span>添加</span>
+        </button>
+      </div>
+      <div className="flex items-center justify-between">
+        <label className="flex items-center gap-2 text-sm text-gray-700">
+          <input type="checkbox" checked={showPast} onChange={(e) => setShowPast(e.target.checked)} />
+          显示已过去的节假日
+        </label>
+        <button
+          onClick={handleImport}
+          className="flex items-center gap-1 px-3 py-2 border border-gray-300 text-gray-700 rounded-lg hover:bg-gray-50 transition-colors text-sm"
+        >
+          <Upload size={16} />
+          <span>导入假日列表</span>
+        </button>
+      </div>
+    </div>
+  )
+}
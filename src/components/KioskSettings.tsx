@@ -0,0 +1,219 @@
+import { useState, useEffect } from 'react'
+import { Lock } from 'lucide-react'
+import { invoke } from '@tauri-apps/api/tauri'
+import { KioskStatus, QuotaStatus } from './KioskView'
+
+interface Playlist {
+  id: number
+  name: string
+}
+
+export default function KioskSettings() {
+  const [status, setStatus] = useState<KioskStatus | null>(null)
+  const [playlists, setPlaylists] = useState<Playlist[]>([])
+  const [selected, setSelected] = useState<number[]>([])
+  const [oldPin, setOldPin] = useState('')
+  const [newPin, setNewPin] = useState('')
+  const [maxVolume, setMaxVolume] = useState(100)
+  const [volumePin, setVolumePin] = useState('')
+  const [quota, setQuota] = useState<QuotaStatus | null>(null)
+  const [quotaMinutes, setQuotaMinutes] = useState(0)
+  const [quotaPin, setQuotaPin] = useState('')
+
+  useEffect(() => {
+    invoke<KioskStatus>('get_kiosk_status')
+      .then((data) => {
+        setStatus(data)
+        setSelected(data.playlists.map((playlist) => playlist.id))
+        setMaxVolume(data.max_volume)
+      })
+      .catch((error) => console.error('加载儿童模式设置失败:', error))
+    invoke<QuotaStatus>('get_listening_quota')
+      .then((data) => {
+        setQuota(data)
+        setQuotaMinutes(data.quota_minutes)
+      })
+      .catch((error) => console.error('加载收听时长失败:', error))
+    invoke<Playlist[]>('get_playlists')
+      .then(setPlaylists)
+      .catch((error) => console.error('加载播放列表失败:', error))
+  }, [])
+
+  const handleSavePin = async () => {
+    try {
+      await invoke('set_parental_pin', { oldPin: oldPin || null, newPin })
+      setOldPin('')
+      setNewPin('')
+      setStatus(status && { ...status, has_pin: true })
+      alert('家长密码已保存')
+    } catch (error) {
+      alert('保存家长密码失败: ' + error)
+    }
+  }
+
+  const handleSaveMaxVolume = async () => {
+    try {
+      await invoke('set_max_volume', { maxVolume, pin: volumePin || null })
+      setVolumePin('')
+      setStatus(status && { ...status, max_volume: maxVolume })
+      alert('最大音量已保存')
+    } catch (error) {
+      alert('保存最大音量失败: ' + error)
+    }
+  }
+
+  const handleSaveQuota = async () => {
+    try {
+      setQuota(await invoke<QuotaStatus>('set_listening_quota', { minutes: quotaMinutes, pin: quotaPin || null }))
+      setQuotaPin('')
+      alert('每日收听时长已保存')
+    } catch (error) {
+      alert('保存每日收听时长失败: ' + error)
+    }
+  }
+
+  const toggleSelected = (id: number) => {
+    setSelected(selected.includes(id) ? selected.filter((s) => s !== id) : [...selected, id])
+  }
+
+  const handleEnter = async () => {
+    if (!confirm('进入儿童模式后窗口将锁定全屏，只能播放选中的播放列表，退出需要家长密码。确定进入吗？')) return
+    try {
+      await invoke('enter_kiosk_mode', { playlistIds: selected })
+      window.dispatchEvent(new Event('kiosk-changed'))
+    } catch (error) {
+      alert('进入儿童模式失败: ' + error)
+    }
+  }
+
+  return (
+    <div className="space-y-4">
+      <div>
+        <label className="block text-sm font-medium text-gray-700 mb-2">
+          家长密码（4-8 位数字）{status?.has_pin && '：已设置'}
+        </label>
+        <div className="flex gap-2">
+          {status?.has_pin && (
+            <input
+              type="password"
+              inputMode="numeric"
+              value={oldPin}
+              onChange={(e) => setOldPin(e.target.value)}
+              placeholder="原密码"
+              className="w-32 px-3 py-2 border border-gray-300 rounded-lg text-sm"
+            />
+          )}
+          <input
+            type="password"
+            inputMode="numeric"
+            value={newPin}
+            onChange={(e) => setNewPin(e.target.value)}
+            placeholder="新密码"
+            className="w-32 px-3 py-2 border border-gray-300 rounded-lg text-sm"
+          />
+          <button
+            onClick={handleSavePin}
+            className="px-3 py-2 bg-gray-100 text-gray-700 rounded-lg hover:bg-gray-200 transition-colors text-sm"
+          >
+            保存密码
+          </button>
+        </div>
+      </div>
+
+      <div>
+        <label className="block text-sm font-medium text-gray-700 mb-2">
+          最大音量（保护听力，手动调节和定时任务都不会超过）：{maxVolume}%
+        </label>
+        <div className="flex items-center gap-2">
+          <input
+            type="range"
+            min="1"
+            max="100"
+            value={maxVolume}
+            onChange={(e) => setMaxVolume(parseInt(e.target.value))}
+            className="flex-1"
+          />
+          {status?.has_pin && (
+            <input
+              type="password"
+              inputMode="numeric"
+              value={volumePin}
+              onChange={(e) => setVolumePin(e.target.value)}
+              placeholder="家长密码"
+              className="w-28 px-3 py-2 border border-gray-300 rounded-lg text-sm"
+            />
+          )}
+          <button
+            onClick={handleSaveMaxVolume}
+            disabled={maxVolume === status?.max_volume}
+            className="px-3 py-2 bg-gray-100 text-gray-700 rounded-lg hover:bg-gray-200 transition-colors text-sm disabled:opacity-50"
+          >
+            保存
+          </button>
+        </div>
+      </div>
+
+      <div>
+        <label className="block text-sm font-medium text-gray-700 mb-2">
+          每日收听时长（分钟，0 为不限制，用完后自动停止播放）
+          {quota && `：今天已听 ${Math.floor(quota.used_secs / 60)} 分钟`}
+        </label>
+        <div className="flex items-center gap-2">
+          <input
+            type="number"
+            min="0"
+            max="1440"
+            value={quotaMinutes}
+            onChange={(e) => setQuotaMinutes(parseInt(e.target.value) || 0)}
+            className="w-24 px-3 py-2 border border-gray-300 rounded-lg text-sm"
+          />
+          {status?.has_pin && (
+            <input
+              type="password"
+              inputMode="numeric"
+              value={quotaPin}
+              onChange={(e) => setQuotaPin(e.target.value)}
+              placeholder="家长密码"
+              className="w-28 px-3 py-2 border border-gray-300 rounded-lg text-sm"
+            />
+          )}
+          <button
+            onClick={handleSaveQuota}
+            disabled={quotaMinutes === quota?.quota_minutes}
+            className="px-3 py-2 bg-gray-100 text-gray-700 rounded-lg hover:bg-gray-200 transition-colors text-sm disabled:opacity-50"
+          >
+            保存
+          </button>
+        </div>
+      </div>
+
+      <div>
+        <label className="block text-sm font-medium text-gray-700 mb-2">儿童模式中可以播放的播放列表</label>
+        <div className="flex flex-wrap gap-3">
+          {playlists.map((playlist) => (
+            <label key={playlist.id} className="flex items-center gap-1 text-sm text-gray-700">
+              <input
+                type="checkbox"
+                checked={selected.includes(playlist.id)}
+                onChange={() => toggleSelected(playlist.id)}
+              />
+              {playlist.name}
+            </label>
+          ))}
+        </div>
+      </div>
+
+      <div className="flex items-center justify-between">
+        <p className="text-xs text-gray-500">适合把旧平板或电脑专门留给孩子使用，重启后仍保持儿童模式</p>
+        <button
+          onClick={handleEnter}
+          disabled={!status?.has_pin || selected.length === 0}
+          className="flex items-center gap-1 px-3 py-2 bg-purple-600 text-white rounded-lg hover:bg-purple-700 transition-colors text-sm disabled:opacity-50"
+        >
+          <Lock size={16} />
+          <span>进入儿童模式</span>
+        </button>
+      </div>
+    </div>
+  )
+}
@@ -0,0 +1,135 @@
+import { useState, useEffect } from 'react'
+import { Play, Pause, SkipForward, SkipBack, Lock } from 'lucide-react'
+import { invoke } from '@tauri-apps/api/tauri'
+import { listen } from '@tauri-apps/api/event'
+import { usePlayer } from '../contexts/PlayerContext'
+
+export interface QuotaStatus {
+  quota_minutes: number
+  used_secs: number
+  remaining_secs: number | null
+}
+
+export interface KioskStatus {
+  enabled: boolean
+  has_pin: boolean
+  playlists: { id: number; name: string }[]
+  max_volume: number
+}
+
+// 儿童模式界面：只有白名单播放列表的大按钮和播放控制，退出需要家长密码
+export default function KioskView({ status, onExit }: { status: KioskStatus; onExit: () => void }) {
+  const { isPlaying, currentAudio } = usePlayer()
+  const [showExit, setShowExit] = useState(false)
+  const [pin, setPin] = useState('')
+  const [error, setError] = useState('')
+  const [quota, setQuota] = useState<QuotaStatus | null>(null)
+
+  // 每日收听时长：定期刷新剩余时间，用完时后台会停止播放并发出事件
+  useEffect(() => {
+    const loadQuota = () => {
+      invoke<QuotaStatus>('get_listening_quota')
+        .then(setQuota)
+        .catch((error) => console.error('加载收听时长失败:', error))
+    }
+    loadQuota()
+    const interval = setInterval(loadQuota, 30000)
+    const unlisten = listen<QuotaStatus>('listening-quota-reached', (event) => setQuota(event.payload))
+    return () => {
+      clearInterval(interval)
+      unlisten.then(fn => fn())
+    }
+  }, [])
+
+  const run = async (command: string, args?: Record<string, unknown>) => {
+    try {
+      await invoke(command, args)
+    } catch (error) {
+      console.error(`${command} 失败:`, error)
+    }
+  }
+
+  const handleExit = async () => {
+    try {
+      await invoke('exit_kiosk_mode', { pin })
+      setPin('')
+      setShowExit(false)
+      onExit()
+    } catch (error) {
+      setError(String(error))
+      setPin('')
+    }
+  }
+
+  return (
+    <div className="h-screen flex flex-col bg-yellow-50 p-8 select-none">
+      <div className="flex justify-end">
+        <button
+          onClick={() => { setShowExit(true); setError('') }}
+          className="p-2 text-gray-400 hover:text-gray-600"
+          title="退出儿童模式"
+        >
+          <Lock size={20} />
+        </button>
+      </div>
+
+      <div className="flex-1 grid grid-cols-2 gap-6 content-center">
+        {status.playlists.map((playlist) => (
+          <button
+            key={playlist.id}
+            onClick={() => run('play_playlist', { playlistId: playlist.id, isAutoPlay: true })}
+            className="py-12 bg-white rounded-3xl shadow-md text-3xl font-bold text-gray-800 hover:bg-blue-50 active:scale-95 transition"
+          >
+            {playlist.name}
+          </button>
+        ))}
+      </div>
+
+      <div className="text-center text-xl text-gray-700 mb-4 h-8 truncate">
+        {quota?.remaining_secs === 0 ? '今天的收听时间用完了，明天再听吧' : currentAudio?.name || ''}
+      </div>
+      {quota?.remaining_secs != null && quota.remaining_secs > 0 && (
+        <div className="text-center text-sm text-gray-500 mb-2">
+          今天还可以听 {Math.ceil(quota.remaining_secs / 60)} 分钟
+        </div>
+      )}
+      <div className="flex items-center justify-center gap-8">
+        <button onClick={() => run('play_previous')} className="p-6 bg-white rounded-full shadow-md">
+          <SkipBack size={40} />
+        </button>
+        <button onClick={() => run('toggle_play_pause')} className="p-8 bg-blue-600 text-white rounded-full shadow-md">
+          {isPlaying ? <Pause size={56} /> : <Play size={56} />}
+        </button>
+        <button onClick={() => run('play_next')} className="p-6 bg-white rounded-full shadow-md">
+          <SkipForward size={40} />
+        </button>
+      </div>
+
+      {showExit && (
+        <div className="fixed inset-0 bg-black bg-opacity-50 flex items-center justify-center">
+          <div className="bg-white rounded-lg p-6 w-80">
+            <h3 className="text-lg font-semibold text-gray-800 mb-4">输入家长密码</h3>
+            <input
+              type="password"
+              inputMode="numeric"
+              value={pin}
+              onChange={(e) => setPin(e.target.value)}
+              onKeyDown={(e) => e.key === 'Enter' && handleExit()}
+              className="w-full px-3 py-2 border border-gray-300 rounded-lg mb-2"
+              autoFocus
+            />
+            {error && <p className="text-sm text-red-600 mb-2">{error}</p>}
+            <div className="flex justify-end gap-2 mt-2">
+              <button onClick={() => setShowExit(false)} className="px-4 py-2 text-gray-700 hover:bg-gray-100 rounded-lg">
+                取消
+              </button>
+              <button onClick={handleExit} className="px-4 py-2 bg-blue-600 text-white rounded-lg hover:bg-blue-700">
+                退出
+              </button>
+            </div>
+          </div>
+        </div>
+      )}
+    </div>
+  )
+}
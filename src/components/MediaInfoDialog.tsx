@@ -0,0 +1,114 @@
+import { useState, useEffect } from 'react'
+import { Info, X, Loader2 } from 'lucide-react'
+import { invoke } from '@tauri-apps/api/tauri'
+
+interface MediaInfo {
+  source: string
+  container: string | null
+  codec: string | null
+  codec_long_name: string | null
+  bitrate: number | null
+  sample_rate: number | null
+  channels: number | null
+  channel_layout: string | null
+  bits_per_sample: number | null
+  duration_secs: number | null
+  file_size: number | null
+  tags: Record<string, string>
+}
+
+interface MediaInfoDialogProps {
+  audioId: number | null
+  audioName: string
+  onClose: () => void
+}
+
+// 音频文件的编码信息：排查音质差或无法播放的原因
+export default function MediaInfoDialog({ audioId, audioName, onClose }: MediaInfoDialogProps) {
+  const [info, setInfo] = useState<MediaInfo | null>(null)
+  const [error, setError] = useState<string | null>(null)
+
+  useEffect(() => {
+    if (audioId === null) return
+    setInfo(null)
+    setError(null)
+    invoke<MediaInfo>('get_media_info', { id: audioId })
+      .then(setInfo)
+      .catch((e) => setError(String(e)))
+  }, [audioId])
+
+  if (audioId === null) return null
+
+  const rows: [string, string | null][] = info
+    ? [
+        ['容器格式', info.container],
+        ['编码', info.codec && (info.codec_long_name ? `${info.codec}（${info.codec_long_name}）` : info.codec)],
+        ['码率', info.bitrate !== null ? `${Math.round(info.bitrate / 1000)} kbps${info.source === 'symphonia' ? '（估算）' : ''}` : null],
+        ['采样率', info.sample_rate !== null ? `${info.sample_rate} Hz` : null],
+        ['声道', info.channels !== null ? `${info.channels}${info.channel_layout ? `（${info.channel_layout}）` : ''}` : null],
+        ['位深', info.bits_per_sample !== null ? `${info.bits_per_sample} bit` : null],
+        ['时长', info.duration_secs !== null ? `${info.duration_secs.toFixed(2)} 秒` : null],
+        ['文件大小', info.file_size !== null ? `${(info.file_size / (1024 * 1024)).toFixed(2)} MB` : null],
+      ]
+    : []
+
+  return (
+    <div className="fixed inset-0 bg-black bg-opacity-50 flex items-center justify-center z-50">
+      <div className="bg-white rounded-lg shadow-xl max-w-lg w-full mx-4">
+        <div className="flex items-center justify-between p-6 border-b border-gray-200">
+          <h3 className="text-lg font-semibold text-gray-800 flex items-center gap-2 min-w-0">
+            <Info size={20} className="flex-shrink-0" />
+            <span className="truncate">{audioName}</span>
+          </h3>
+          <button onClick={onClose} className="text-gray-400 hover:text-gray-600 transition-colors">
+            <X size={20} />
+          </button>
+        </div>
+
+        <div className="p-6 max-h-[70vh] overflow-y-auto">
+          {error ? (
+            <p className="text-sm text-red-600">读取失败: {error}</p>
+          ) : !info ? (
+            <div className="flex items-center gap-2 text-sm text-gray-500">
+              <Loader2 size={16} className="animate-spin" />
+              读取中...
+            </div>
+          ) : (
+            <>
+              <table className="w-full text-sm mb-4">
+                <tbody>
+                  {rows.map(([label, value]) => (
+                    <tr key={label} className="border-b border-gray-100">
+                      <td className="py-1.5 pr-4 text-gray-500 whitespace-nowrap">{label}</td>
+                      <td className="py-1.5 text-gray-800 break-all">{value ?? '--'}</td>
+                    </tr>
+                  ))}
+                </tbody>
+              </table>
+
+              <h4 className="text-sm font-medium text-gray-700 mb-2">内嵌标签</h4>
+              {Object.keys(info.tags).length === 0 ? (
+                <p className="text-sm text-gray-400">无</p>
+              ) : (
+                <table className="w-full text-sm">
+                  <tbody>
+                    {Object.entries(info.tags).map(([key, value]) => (
+                      <tr key={key} className="border-b border-gray-100">
+                        <td className="py-1.5 pr-4 text-gray-500 whitespace-nowrap">{key}</td>
+                        <td className="py-1.5 text-gray-800 break-all">{value}</td>
+                      </tr>
+                    ))}
+                  </tbody>
+                </table>
+              )}
+
+              <p className="text-xs text-gray-400 mt-4">
+                {info.source === 'ffprobe' ? '由 ffprobe 读取' : '未找到 FFmpeg，由内置解码器读取（信息可能不全）'}
+              </p>
+            </>
+          )}
+        </div>
+      </div>
+    </div>
+  )
+}
@@ -0,0 +1,69 @@
+import { useState, useEffect } from 'react'
+import { AlarmClock } from 'lucide-react'
+import { invoke } from '@tauri-apps/api/tauri'
+
+interface NextTask {
+  task_id: number
+  task_name: string
+  action: string
+  playlist_id: number
+  playlist_name: string | null
+  trigger_time: string
+  countdown_secs: number
+  paused: boolean
+}
+
+// 重新查询下一个任务的间隔（毫秒），任务增删改后最多延迟这么久显示
+const REFRESH_MILLIS = 30000
+
+function formatCountdown(secs: number) {
+  const days = Math.floor(secs / 86400)
+  const hours = Math.floor((secs % 86400) / 3600)
+  const minutes = Math.floor((secs % 3600) / 60)
+  if (days > 0) return `${days}天${hours}小时后`
+  if (hours > 0) return `${hours}小时${minutes}分钟后`
+  if (minutes > 0) return `${minutes}分钟后`
+  return '即将开始'
+}
+
+export default function NextTaskInfo() {
+  const [next, setNext] = useState<NextTask | null>(null)
+  const [remaining, setRemaining] = useState(0)
+
+  useEffect(() => {
+    const load = () => invoke<NextTask | null>('get_next_task').then(setNext).catch(console.error)
+    load()
+    const timer = setInterval(load, REFRESH_MILLIS)
+    return () => clearInterval(timer)
+  }, [])
+
+  // 按触发时间每秒刷新倒计时
+  useEffect(() => {
+    if (!next) return
+    const triggerAt = Date.now() + next.countdown_secs * 1000
+    const update = () => setRemaining(Math.max(0, Math.round((triggerAt - Date.now()) / 1000)))
+    update()
+    const timer = setInterval(update, 1000)
+    return () => clearInterval(timer)
+  }, [next])
+
+  if (!next) return null
+
+  return (
+    <div className="mt-3 flex items-start gap-2 text-xs text-gray-600" title={next.trigger_time}>
+      <AlarmClock size={14} className="mt-0.5 flex-shrink-0 text-blue-500" />
+      <div className="min-w-0">
+        <div className="truncate font-medium text-gray-700">{next.task_name}</div>
+        {next.action === 'play' ? (
+          next.playlist_name && <div className="truncate text-gray-500">{next.playlist_name}</div>
+        ) : (
+          <div className="text-gray-500">{next.action === 'stop' ? '停止播放' : '降低音量'}</div>
+        )}
+        <div className={next.paused ? 'text-orange-500' : 'text-blue-600'}>
+          {next.trigger_time.slice(11, 16)} · {formatCountdown(remaining)}
+          {next.paused && '（已暂停）'}
+        </div>
+      </div>
+    </div>
+  )
+}
@@ -1,23 +1,59 @@
 import { useState, useEffect } from 'react'
-import { Play, Pause, Square, SkipForward, SkipBack, Volume2, VolumeX } from 'lucide-react'
+import { Play, Pause, Square, SkipForward, SkipBack, Shuffle, Volume2, VolumeX, Moon, Pin } from 'lucide-react'
 import { invoke } from '@tauri-apps/api/tauri'
+import { listen } from '@tauri-apps/api/event'
+
+const PLAY_MODE_NAMES: Record<string, string> = {
+  sequential: '顺序播放',
+  random: '随机播放',
+  single: '单曲循环',
+  loop: '列表循环',
+}
 
 interface PlaybackState {
   is_playing: boolean
   current_audio_id: number | null
   current_audio_name: string | null
   volume: number
+  is_muted: boolean
+  max_volume: number
   speed: number
+  speed_preset: SpeedPreset | null
   playlist_queue: number[]
   current_index: number
   is_auto_play: boolean
+  position_secs: number
+  duration_secs: number | null
+  ab_loop: [number, number] | null
+  compare_pair: [number, number] | null
+  play_mode: 'sequential' | 'random' | 'single' | 'loop' | null
+  sleep_timer: { mode: 'countdown' | 'track' | 'playlist'; remaining_secs: number | null } | null
 }
 
+type SpeedPreset = 'slow' | 'normal' | 'fast' | 'faster'
+
+// 常用倍速（与后端的 SpeedPreset 对应）
+const SPEED_PRESETS: { preset: SpeedPreset; speed: number }[] = [
+  { preset: 'slow', speed: 0.75 },
+  { preset: 'normal', speed: 1.0 },
+  { preset: 'fast', speed: 1.25 },
+  { preset: 'faster', speed: 1.5 },
+]
+
+// 定时停止选项：数字为倒计时分钟数
+const SLEEP_OPTIONS: { value: string; label: string }[] = [
+  { value: '15', label: '15 分钟' },
+  { value: '30', label: '30 分钟' },
+  { value: '45', label: '45 分钟' },
+  { value: '60', label: '60 分钟' },
+  { value: 'track', label: '播完本首' },
+  { value: 'playlist', label: '播完列表' },
+]
+
 export default function PlayController() {
   const [playbackState, setPlaybackState] = useState<PlaybackState | null>(null)
-  const [isMuted, setIsMuted] = useState(false)
-
-  const SPEED_OPTIONS = [0.8, 1.0, 1.2, 1.5, 2.0, 3.0]
+  // A-B 循环：已标记的 A 点（秒）
+  const [loopStart, setLoopStart] = useState<number | null>(null)
 
   // 定期获取播放状态
   useEffect(() => {
@@ -32,20 +68,28 @@ export default function PlayController() {
 
     updateState()
     const interval = setInterval(updateState, 1000) // 每秒更新一次
+    // 家长设置的每日收听时长用完时提示
+    const unlisten = listen('listening-quota-reached', () => {
+      alert('今天的收听时间已用完，播放已停止')
+    })
+
+    // 其他应用占用麦克风时自动暂停/恢复，立即刷新播放状态
+    const unlistenCapture = Promise.all([
+      listen('capture-auto-paused', updateState),
+      listen('capture-auto-resumed', updateState),
+    ])
 
-    return () => clearInterval(interval)
+    return () => {
+      clearInterval(interval)
+      unlisten.then(fn => fn())
+      unlistenCapture.then(fns => fns.forEach(fn => fn()))
+    }
   }, [])
 
   const handlePlayPause = async () => {
     try {
-      if (playbackState?.is_playing) {
-        await invoke('pause_audio')
-      } else {
-        // 如果有当前音频，恢复播放需要重新调用 play_audio
-        if (playbackState?.current_audio_id) {
-          await invoke('play_audio', { id: playbackState.current_audio_id })
-        }
-      }
+      const state = await invoke<PlaybackState>('toggle_play_pause')
+      setPlaybackState(state)
     } catch (error) {
       console.error('播放控制失败:', error)
     }
@@ -83,30 +127,114 @@ export default function PlayController() {
     }
   }
 
+  // 静音时后端保留原音量，取消静音后恢复
   const toggleMute = async () => {
-    const newMuted = !isMuted
-    setIsMuted(newMuted)
+    if (!playbackState) return
     try {
-      const currentVolume = playbackState?.volume || 0.5
-      await invoke('set_volume', { volume: newMuted ? 0 : currentVolume })
+      setPlaybackState(await invoke<PlaybackState>(playbackState.is_muted ? 'unmute_audio' : 'mute_audio'))
     } catch (error) {
       console.error('静音切换失败:', error)
     }
   }
 
-  const handleSpeedChange = async (speed: number) => {
+  // 切换常用倍速，或按设置的步长加快 / 放慢
+  const handleSpeedCommand = async (command: string, args?: Record<string, unknown>) => {
     try {
-      await invoke('set_speed', { speed })
+      setPlaybackState(await invoke<PlaybackState>(command, args))
     } catch (error) {
       console.error('倍速设置失败:', error)
     }
   }
 
+  // 记住当前音频的倍速，之后播放该音频时自动使用
+  const rememberSpeed = async () => {
+    if (!playbackState || playbackState.current_audio_id === null) return
+    try {
+      await invoke('set_audio_preferred_speed', { id: playbackState.current_audio_id, speed: playbackState.speed })
+      alert(`已记住此音频的倍速：${playbackState.speed}x`)
+    } catch (error) {
+      alert('记住倍速失败: ' + error)
+    }
+  }
+
+  // 依次点击：标记 A 点 → 标记 B 点并开始循环 → 取消循环
+  const handleAbLoop = async () => {
+    if (!playbackState) return
+    try {
+      if (playbackState.ab_loop) {
+        setPlaybackState(await invoke<PlaybackState>('clear_ab_loop'))
+        setLoopStart(null)
+      } else if (loopStart === null) {
+        setLoopStart(playbackState.position_secs)
+      } else {
+        const [start, end] = [loopStart, playbackState.position_secs].sort((a, b) => a - b)
+        setPlaybackState(await invoke<PlaybackState>('set_ab_loop', { startSecs: start, endSecs: end }))
+        setLoopStart(null)
+      }
+    } catch (error) {
+      console.error('A-B 循环设置失败:', error)
+      setLoopStart(null)
+    }
+  }
+
+  // 对比播放时切换到另一个音频的相同位置
+  const handleSwitchCompare = async () => {
+    try {
+      setPlaybackState(await invoke<PlaybackState>('switch_ab_compare'))
+    } catch (error) {
+      console.error('切换对比音频失败:', error)
+    }
+  }
+
+  // 重新打乱尚未播放的曲目，当前曲目继续播放
+  const handleReshuffle = async () => {
+    try {
+      setPlaybackState(await invoke<PlaybackState>('reshuffle_queue'))
+    } catch (error) {
+      console.error('重新打乱队列失败:', error)
+    }
+  }
+
+  // 设置或取消定时停止
+  const handleSleepTimer = async (value: string) => {
+    try {
+      if (value === 'cancel') {
+        setPlaybackState(await invoke<PlaybackState>('cancel_sleep_timer'))
+      } else if (value === 'track' || value === 'playlist') {
+        setPlaybackState(await invoke<PlaybackState>('set_sleep_timer', { mode: value }))
+      } else {
+        setPlaybackState(await invoke<PlaybackState>('set_sleep_timer', { mode: 'countdown', minutes: Number(value) }))
+      }
+    } catch (error) {
+      console.error('设置定时停止失败:', error)
+    }
+  }
+
   if (!playbackState || !playbackState.current_audio_id) {
     return null // 没有播放内容时不显示
   }
 
+  const formatTime = (secs: number) => {
+    const total = Math.max(0, Math.floor(secs))
+    const m = Math.floor(total / 60)
+    const s = (total % 60).toString().padStart(2, '0')
+    return `${m}:${s}`
+  }
+
+  const sleepTimer = playbackState.sleep_timer
+  const sleepLabel = !sleepTimer
+    ? null
+    : sleepTimer.mode === 'track'
+      ? '播完本首后停止'
+      : sleepTimer.mode === 'playlist'
+        ? '播完列表后停止'
+        : `${formatTime(sleepTimer.remaining_secs ?? 0)} 后停止`
+
   const volume = Math.round(playbackState.volume * 100)
+  const maxVolume = Math.round(playbackState.max_volume * 100)
+  const progress = playbackState.duration_secs
+    ? Math.min(100, (playbackState.position_secs / playbackState.duration_secs) * 100)
+    : 0
   const hasPlaylist = playbackState.playlist_queue.length > 0
   const canGoBack = hasPlaylist && playbackState.current_index > 0
   const canGoForward = hasPlaylist && playbackState.current_index < playbackState.playlist_queue.length - 1
@@ -130,11 +258,30 @@ export default function PlayController() {
                 {playbackState.current_index + 1} / {playbackState.playlist_queue.length}
               </span>
             )}
+            {playbackState.play_mode && (
+              <span className="px-2 py-0.5 bg-gray-100 text-gray-600 rounded text-xs">
+                {PLAY_MODE_NAMES[playbackState.play_mode]}
+              </span>
+            )}
+            {sleepLabel && (
+              <span className="px-2 py-0.5 bg-indigo-100 text-indigo-700 rounded text-xs">
+                {sleepLabel}
+              </span>
+            )}
             <div className="flex-1" />
             <span className="text-gray-600">
               倍速: {playbackState.speed.toFixed(1)}x
             </span>
           </div>
+          <div className="flex items-center gap-3 text-xs text-gray-500">
+            <span className="w-10 text-right">{formatTime(playbackState.position_secs)}</span>
+            <div className="flex-1 h-1 bg-gray-200 rounded">
+              <div className="h-1 bg-blue-600 rounded" style={{ width: `${progress}%` }} />
+            </div>
+            <span className="w-10">
+              {playbackState.duration_secs ? formatTime(playbackState.duration_secs) : '--:--'}
+            </span>
+          </div>
         </div>
 
         {/* 控制按钮 */}
@@ -185,17 +332,85 @@ export default function PlayController() {
             >
               <SkipForward size={20} />
             </button>
+
+            {/* 重新随机 */}
+            {playbackState.play_mode === 'random' && canGoForward && (
+              <button
+                onClick={handleReshuffle}
+                className="p-2 hover:bg-gray-100 text-gray-700 rounded-lg transition-colors"
+                title="重新打乱后面的曲目"
+              >
+                <Shuffle size={20} />
+              </button>
+            )}
           </div>
 
           <div className="flex items-center gap-4">
+            {/* A-B 循环 */}
+            <button
+              onClick={handleAbLoop}
+              className={`px-2 py-1 text-xs rounded transition-colors ${
+                playbackState.ab_loop || loopStart !== null
+                  ? 'bg-blue-600 text-white'
+                  : 'bg-gray-100 text-gray-700 hover:bg-gray-200'
+              }`}
+              title={
+                playbackState.ab_loop
+                  ? `正在循环 ${formatTime(playbackState.ab_loop[0])} - ${formatTime(playbackState.ab_loop[1])}，点击取消`
+                  : loopStart !== null
+                    ? '点击标记 B 点并开始循环'
+                    : '点击标记 A 点'
+              }
+            >
+              {playbackState.ab_loop ? 'A-B ✓' : loopStart !== null ? 'A-' : 'A-B'}
+            </button>
+
+            {/* 对比播放切换 */}
+            {playbackState.compare_pair && (
+              <button
+                onClick={handleSwitchCompare}
+                className="px-2 py-1 text-xs rounded bg-purple-600 text-white hover:bg-purple-700 transition-colors"
+                title="切换到另一个音频的相同位置"
+              >
+                {playbackState.compare_pair[0] === playbackState.current_audio_id ? 'A ⇄ B' : 'B ⇄ A'}
+              </button>
+            )}
+
+            {/* 定时停止 */}
+            <div className="flex items-center gap-1" title="定时停止">
+              <Moon size={16} className="text-gray-600" />
+              <select
+                value=""
+                onChange={(e) => handleSleepTimer(e.target.value)}
+                className="px-1 py-1 text-xs border border-gray-300 rounded"
+              >
+                <option value="" disabled hidden>
+                  {sleepTimer ? '已定时' : '定时停止'}
+                </option>
+                {SLEEP_OPTIONS.map((option) => (
+                  <option key={option.value} value={option.value}>
+                    {option.label}
+                  </option>
+                ))}
+                {sleepTimer && <option value="cancel">取消定时</option>}
+              </select>
+            </div>
+
             {/* 倍速控制 */}
             <div className="flex items-center gap-1">
-              {SPEED_OPTIONS.map((speed) => (
+              <button
+                onClick={() => handleSpeedCommand('speed_down')}
+                className="px-2 py-1 text-xs rounded bg-gray-100 text-gray-700 hover:bg-gray-200"
+                title="放慢"
+              >
+                −
+              </button>
+              {SPEED_PRESETS.map(({ preset, speed }) => (
                 <button
-                  key={speed}
-                  onClick={() => handleSpeedChange(speed)}
+                  key={preset}
+                  onClick={() => handleSpeedCommand('set_speed_preset', { preset })}
                   className={`px-2 py-1 text-xs rounded transition-colors ${
-                    Math.abs(playbackState.speed - speed) < 0.01
+                    playbackState.speed_preset === preset
                       ? 'bg-blue-600 text-white'
                       : 'bg-gray-100 text-gray-700 hover:bg-gray-200'
                   }`}
@@ -204,6 +419,21 @@ export default function PlayController() {
                   {speed}x
                 </button>
               ))}
+              <button
+                onClick={() => handleSpeedCommand('speed_up')}
+                className="px-2 py-1 text-xs rounded bg-gray-100 text-gray-700 hover:bg-gray-200"
+                title="加快"
+              >
+                +
+              </button>
+              <button
+                onClick={rememberSpeed}
+                disabled={playbackState.current_audio_id === null}
+                className="p-1 text-gray-600 hover:bg-gray-100 rounded disabled:opacity-50"
+                title="记住此音频的倍速"
+              >
+                <Pin size={14} />
+              </button>
             </div>
 
             {/* 音量控制 */}
@@ -211,18 +441,19 @@ export default function PlayController() {
               <button
                 onClick={toggleMute}
                 className="p-2 hover:bg-gray-100 rounded-lg transition-colors"
-                title={isMuted ? '取消静音' : '静音'}
+                title={playbackState.is_muted ? '取消静音' : '静音'}
               >
-                {isMuted ? <VolumeX size={20} /> : <Volume2 size={20} />}
+                {playbackState.is_muted ? <VolumeX size={20} /> : <Volume2 size={20} />}
               </button>
 
               <input
                 type="range"
                 min="0"
-                max="100"
+                max={maxVolume}
                 value={volume}
                 onChange={(e) => handleVolumeChange(parseInt(e.target.value))}
                 className="w-24"
+                title={maxVolume < 100 ? `最大音量 ${maxVolume}%` : undefined}
               />
 
               <span className="text-sm text-gray-600 w-10">{volume}%</span>
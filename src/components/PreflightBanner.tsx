@@ -0,0 +1,48 @@
+import { useState, useEffect } from 'react'
+import { AlertTriangle, X } from 'lucide-react'
+import { listen } from '@tauri-apps/api/event'
+
+interface PreflightReport {
+  task_id: number
+  task_name: string
+  trigger_time: string
+  playlist_id: number
+  problems: string[]
+}
+
+export default function PreflightBanner() {
+  const [reports, setReports] = useState<PreflightReport[]>([])
+
+  useEffect(() => {
+    const unlisten = listen<PreflightReport>('preflight-warning', (event) => {
+      setReports((prev) => [...prev.filter(r => r.task_id !== event.payload.task_id), event.payload])
+    })
+    return () => {
+      unlisten.then(fn => fn())
+    }
+  }, [])
+
+  if (reports.length === 0) return null
+
+  return (
+    <div className="border-b border-orange-200 bg-orange-50">
+      {reports.map((report) => (
+        <div key={report.task_id} className="flex items-start gap-3 px-4 py-2 text-sm text-orange-800">
+          <AlertTriangle size={16} className="mt-0.5 flex-shrink-0" />
+          <div className="flex-1">
+            <span className="font-medium">{report.task_name}</span>
+            <span className="text-orange-600">（{report.trigger_time}）</span>
+            ：{report.problems.join('；')}
+          </div>
+          <button
+            onClick={() => setReports((prev) => prev.filter(r => r.task_id !== report.task_id))}
+            className="p-1 hover:bg-orange-100 rounded"
+            title="忽略"
+          >
+            <X size={14} />
+          </button>
+        </div>
+      ))}
+    </div>
+  )
+}
@@ -0,0 +1,147 @@
+import { useState, useEffect } from 'react'
+import { Plus, Trash2 } from 'lucide-react'
+import { invoke } from '@tauri-apps/api/tauri'
+
+interface RecordingTemplate {
+  id: number
+  name: string
+  filename_pattern: string
+  folder: string | null
+  format: string
+  playlist_id: number | null
+  playlist_name: string | null
+  recording_count: number
+}
+
+interface Playlist {
+  id: number
+  name: string
+}
+
+const emptyForm = { name: '', filename_pattern: '{name}_{date}', folder: '', format: 'wav', playlist_id: '' }
+
+export default function RecordingTemplates() {
+  const [templates, setTemplates] = useState<RecordingTemplate[]>([])
+  const [playlists, setPlaylists] = useState<Playlist[]>([])
+  const [form, setForm] = useState(emptyForm)
+
+  useEffect(() => {
+    loadTemplates()
+    invoke<Playlist[]>('get_playlists')
+      .then(setPlaylists)
+      .catch((error) => console.error('加载播放列表失败:', error))
+  }, [])
+
+  const loadTemplates = async () => {
+    try {
+      setTemplates(await invoke<RecordingTemplate[]>('get_recording_templates'))
+    } catch (error) {
+      console.error('加载录音模板失败:', error)
+    }
+  }
+
+  const handleCreate = async () => {
+    try {
+      await invoke('create_recording_template', {
+        name: form.name,
+        filenamePattern: form.filename_pattern,
+        folder: form.folder || null,
+        format: form.format,
+        playlistId: form.playlist_id ? Number(form.playlist_id) : null,
+      })
+      setForm(emptyForm)
+      await loadTemplates()
+    } catch (error) {
+      alert('保存录音模板失败: ' + error)
+    }
+  }
+
+  const handleDelete = async (template: RecordingTemplate) => {
+    if (!confirm(`确定删除录音模板“${template.name}”吗？已录制的音频会保留。`)) return
+    try {
+      await invoke('delete_recording_template', { id: template.id })
+      await loadTemplates()
+    } catch (error) {
+      alert('删除录音模板失败: ' + error)
+    }
+  }
+
+  return (
+    <div className="space-y-3">
+      {templates.map((template) => (
+        <div key={template.id} className="flex items-center justify-between p-3 border border-gray-200 rounded-lg">
+          <div>
+            <p className="font-medium text-gray-800">{template.name}</p>
+            <p className="text-sm text-gray-500">
+              {template.filename_pattern}.{template.format}
+              {template.folder && ` · 目录：${template.folder}`}
+              {template.playlist_name && ` · 加入：${template.playlist_name}`}
+              {` · 已录制 ${template.recording_count} 次`}
+            </p>
+          </div>
+          <button
+            onClick={() => handleDelete(template)}
+            className="p-2 text-red-600 hover:bg-red-50 rounded-lg transition-colors"
+            title="删除"
+          >
+            <Trash2 size={16} />
+          </button>
+        </div>
+      ))}
+
+      <div className="grid grid-cols-2 gap-2">
+        <input
+          type="text"
+          value={form.name}
+          onChange={(e) => setForm({ ...form, name: e.target.value })}
+          placeholder="模板名称，如：朗读打卡"
+          className="px-3 py-2 border border-gray-300 rounded-lg text-sm"
+        />
+        <input
+          type="text"
+          value={form.filename_pattern}
+          onChange={(e) => setForm({ ...form, filename_pattern: e.target.value })}
+          placeholder="文件名规则"
+          className="px-3 py-2 border border-gray-300 rounded-lg text-sm"
+        />
+        <input
+          type="text"
+          value={form.folder}
+          onChange={(e) => setForm({ ...form, folder: e.target.value })}
+          placeholder="保存子目录（可选）"
+          className="px-3 py-2 border border-gray-300 rounded-lg text-sm"
+        />
+        <div className="flex gap-2">
+          <select
+            value={form.format}
+            onChange={(e) => setForm({ ...form, format: e.target.value })}
+            className="px-3 py-2 border border-gray-300 rounded-lg text-sm"
+          >
+            <option value="wav">WAV</option>
+            <option value="mp3">MP3</option>
+          </select>
+          <select
+            value={form.playlist_id}
+            onChange={(e) => setForm({ ...form, playlist_id: e.target.value })}
+            className="flex-1 px-3 py-2 border border-gray-300 rounded-lg text-sm"
+          >
+            <option value="">不加入播放列表</option>
+            {playlists.map((playlist) => (
+              <option key={playlist.id} value={playlist.id}>{playlist.name}</option>
+            ))}
+          </select>
+        </div>
+      </div>
+      <div className="flex items-center justify-between">
+        <p className="text-xs text-gray-500">文件名可用 {'{name}'}、{'{date}'}、{'{time}'}、{'{n}'}（第几次录音）；MP3 需要 FFmpeg</p>
+        <button
+          onClick={handleCreate}
+          className="flex items-center gap-1 px-3 py-2 bg-purple-600 text-white rounded-lg hover:bg-purple-700 transition-colors text-sm"
+        >
+          <Plus size={16} />
+          <span>添加模板</span>
+        </button>
+      </div>
+    </div>
+  )
+}
@@ -0,0 +1,121 @@
+import { useState, useEffect } from 'react'
+import { HardDrive } from 'lucide-react'
+import { invoke } from '@tauri-apps/api/tauri'
+import { listen } from '@tauri-apps/api/event'
+
+interface DriveFolder {
+  path: string
+  name: string
+  audio_count: number
+  is_root: boolean
+}
+
+interface RemovableDrive {
+  path: string
+  name: string
+  folders: DriveFolder[]
+}
+
+interface ScanResult {
+  found_files: number
+  added_files: number
+  skipped_files: number
+  error_files: number
+  playlists_created: number
+}
+
+// 插入含音频的U盘时弹出：选择要导入的文件夹，复制进音频库
+export default function RemovableDriveImport() {
+  const [drive, setDrive] = useState<RemovableDrive | null>(null)
+  const [selected, setSelected] = useState<string[]>([])
+  const [folderPlaylists, setFolderPlaylists] = useState(true)
+  const [importing, setImporting] = useState(false)
+
+  useEffect(() => {
+    const unlisten = listen<RemovableDrive>('removable-drive-detected', (event) => {
+      setDrive(event.payload)
+      setSelected(event.payload.folders.map((folder) => folder.path))
+    })
+    return () => {
+      unlisten.then(fn => fn())
+    }
+  }, [])
+
+  if (!drive) return null
+
+  const toggleSelected = (path: string) => {
+    setSelected(selected.includes(path) ? selected.filter((p) => p !== path) : [...selected, path])
+  }
+
+  const handleImport = async () => {
+    setImporting(true)
+    try {
+      const result = await invoke<ScanResult>('import_drive_folders', { folders: selected, folderPlaylists })
+      let message = `导入完成！\n新增文件: ${result.added_files} 个\n跳过文件: ${result.skipped_files} 个（已存在）`
+      if (result.error_files > 0) {
+        message += `\n错误文件: ${result.error_files} 个`
+      }
+      if (result.playlists_created > 0) {
+        message += `\n新建播放列表: ${result.playlists_created} 个`
+      }
+      alert(message)
+      setDrive(null)
+    } catch (error) {
+      alert('导入失败: ' + error)
+    } finally {
+      setImporting(false)
+    }
+  }
+
+  return (
+    <div className="fixed inset-0 bg-black bg-opacity-50 flex items-center justify-center z-50">
+      <div className="bg-white rounded-lg p-6 w-96">
+        <h3 className="text-lg font-semibold text-gray-800 mb-1 flex items-center gap-2">
+          <HardDrive size={20} />
+          检测到U盘：{drive.name}
+        </h3>
+        <p className="text-sm text-gray-500 mb-4">选择要导入音频库的文件夹（会复制到本机，拔出U盘后仍可播放）</p>
+
+        <div className="max-h-64 overflow-y-auto space-y-2 mb-4">
+          {drive.folders.map((folder) => (
+            <label key={folder.path} className="flex items-center gap-2 text-sm text-gray-700">
+              <input
+                type="checkbox"
+                checked={selected.includes(folder.path)}
+                onChange={() => toggleSelected(folder.path)}
+              />
+              <span className="flex-1 truncate">{folder.is_root ? '根目录下的音频' : folder.name}</span>
+              <span className="text-gray-400">{folder.audio_count} 个</span>
+            </label>
+          ))}
+        </div>
+
+        <label className="flex items-center gap-2 text-sm text-gray-700 mb-4">
+          <input
+            type="checkbox"
+            checked={folderPlaylists}
+            onChange={(e) => setFolderPlaylists(e.target.checked)}
+          />
+          按文件夹生成播放列表
+        </label>
+
+        <div className="flex justify-end gap-2">
+          <button
+            onClick={() => setDrive(null)}
+            disabled={importing}
+            className="px-4 py-2 text-gray-700 hover:bg-gray-100 rounded-lg"
+          >
+            忽略
+          </button>
+          <button
+            onClick={handleImport}
+            disabled={importing || selected.length === 0}
+            className="px-4 py-2 bg-blue-600 text-white rounded-lg hover:bg-blue-700 disabled:opacity-50"
+          >
+            {importing ? '导入中...' : '导入'}
+          </button>
+        </div>
+      </div>
+    </div>
+  )
+}
@@ -0,0 +1,116 @@
+import { useState, useEffect } from 'react'
+import { ListChecks, X } from 'lucide-react'
+import { invoke } from '@tauri-apps/api/tauri'
+
+interface TraceEntry {
+  id: number
+  trace_time: string
+  task_id: number
+  task_name: string
+  decision: 'executed' | 'skipped' | 'not_due' | 'finished'
+  reason: string
+}
+
+interface SchedulerTraceDialogProps {
+  onClose: () => void
+}
+
+const DECISION_LABELS: Record<TraceEntry['decision'], { label: string; className: string }> = {
+  executed: { label: '执行', className: 'bg-green-100 text-green-700' },
+  skipped: { label: '跳过', className: 'bg-orange-100 text-orange-700' },
+  not_due: { label: '未到时间', className: 'bg-gray-100 text-gray-500' },
+  finished: { label: '结束', className: 'bg-blue-100 text-blue-700' },
+}
+
+// 日期输入框的值：YYYY-MM-DD
+const formatDate = (date: Date) => {
+  const pad = (value: number) => String(value).padStart(2, '0')
+  return `${date.getFullYear()}-${pad(date.getMonth() + 1)}-${pad(date.getDate())}`
+}
+
+// 调度日志：调度器对每个任务的判断（执行、跳过及原因）
+export default function SchedulerTraceDialog({ onClose }: SchedulerTraceDialogProps) {
+  const [date, setDate] = useState(formatDate(new Date()))
+  const [taskFilter, setTaskFilter] = useState<number | null>(null)
+  const [entries, setEntries] = useState<TraceEntry[]>([])
+  const [error, setError] = useState<string | null>(null)
+
+  useEffect(() => {
+    invoke<TraceEntry[]>('get_scheduler_trace', { start: date, end: date, taskId: taskFilter })
+      .then((result) => {
+        setEntries(result)
+        setError(null)
+      })
+      .catch((e) => setError(String(e)))
+  }, [date, taskFilter])
+
+  // 下拉框中的任务取自当天的日志
+  const tasks = Array.from(new Map(entries.map((entry) => [entry.task_id, entry.task_name])).entries())
+
+  return (
+    <div className="fixed inset-0 bg-black bg-opacity-50 flex items-center justify-center z-50">
+      <div className="bg-white rounded-lg shadow-xl max-w-3xl w-full mx-4">
+        <div className="flex items-center justify-between p-6 border-b border-gray-200">
+          <h3 className="text-lg font-semibold text-gray-800 flex items-center gap-2">
+            <ListChecks size={20} />
+            调度日志
+          </h3>
+          <button onClick={onClose} className="text-gray-400 hover:text-gray-600 transition-colors">
+            <X size={20} />
+          </button>
+        </div>
+
+        <div className="p-6">
+          <div className="flex gap-2 mb-4">
+            <input
+              type="date"
+              value={date}
+              onChange={(e) => setDate(e.target.value)}
+              className="px-3 py-2 border border-gray-300 rounded-lg text-sm"
+            />
+            <select
+              value={taskFilter ?? ''}
+              onChange={(e) => setTaskFilter(e.target.value ? Number(e.target.value) : null)}
+              className="px-3 py-2 border border-gray-300 rounded-lg text-sm"
+            >
+              <option value="">全部任务</option>
+              {tasks.map(([id, name]) => (
+                <option key={id} value={id}>
+                  {name}
+                </option>
+              ))}
+            </select>
+          </div>
+
+          <div className="max-h-96 overflow-y-auto border border-gray-200 rounded-lg">
+            {error ? (
+              <p className="p-3 text-sm text-red-600">{error}</p>
+            ) : entries.length === 0 ? (
+              <p className="p-3 text-sm text-gray-500">没有调度日志</p>
+            ) : (
+              <table className="w-full text-sm">
+                <tbody>
+                  {entries.map((entry) => {
+                    const decision = DECISION_LABELS[entry.decision]
+                    return (
+                      <tr key={entry.id} className="border-b border-gray-100">
+                        <td className="px-3 py-1.5 text-gray-500 whitespace-nowrap">{entry.trace_time.slice(11)}</td>
+                        <td className="px-3 py-1.5 text-gray-800">{entry.task_name}</td>
+                        <td className="px-3 py-1.5">
+                          <span className={`px-2 py-0.5 rounded text-xs ${decision?.className ?? ''}`}>
+                            {decision?.label ?? entry.decision}
+                          </span>
+                        </td>
+                        <td className="px-3 py-1.5 text-gray-600">{entry.reason}</td>
+                      </tr>
+                    )
+                  })}
+                </tbody>
+              </table>
+            )}
+          </div>
+        </div>
+      </div>
+    </div>
+  )
+}
@@ -1,5 +1,6 @@
 import { createContext, useContext, useState, useEffect, ReactNode } from 'react'
 import { invoke } from '@tauri-apps/api/tauri'
+import { listen } from '@tauri-apps/api/event'
 
 interface PlaybackState {
   is_playing: boolean
@@ -10,6 +11,8 @@ interface PlaybackState {
   playlist_queue: number[]
   current_index: number
   is_auto_play: boolean
+  position_secs: number
+  duration_secs: number | null
 }
 
 interface PlayerContextType {
@@ -36,44 +39,45 @@ export function PlayerProvider({ children }: { children: ReactNode }) {
   const [audioList, setAudioList] = useState<Array<{id: number, name: string}>>([])
   const [currentIndex, setCurrentIndex] = useState(-1)
 
-  // 定期同步播放状态
+  // 同步播放状态：后端在播放状态变化时发出事件，另外低频轮询兜底
   useEffect(() => {
-    const syncState = async () => {
-      try {
-        const state = await invoke<PlaybackState>('get_playback_state')
+    const applyState = (state: PlaybackState) => {
+      setIsPlaying(state.is_playing)
 
-        // 只在状态真正改变时更新，避免不必要的重渲染
-        if (state.is_playing !== isPlaying) {
-          setIsPlaying(state.is_playing)
-        }
+      // 如果后端没有当前音频（播放完成或停止），清除前端状态
+      if (!state.current_audio_id || !state.current_audio_name) {
+        if (!state.current_audio_id) setCurrentAudio(null)
+        return
+      }
+      const audio = { id: state.current_audio_id, name: state.current_audio_name }
+      // 只在ID变化时更新，避免不必要的重渲染
+      setCurrentAudio((current) => (current && current.id === audio.id ? current : audio))
+    }
 
-        // 如果后端没有当前音频（播放完成或停止），清除前端状态
-        if (!state.current_audio_id && currentAudio) {
-          setCurrentAudio(null)
-          setIsPlaying(false)
-        } else if (state.current_audio_id && state.current_audio_name) {
-          // 如果后端有音频但前端没有，或者ID不匹配，更新前端状态
-          if (!currentAudio || currentAudio.id !== state.current_audio_id) {
-            setCurrentAudio({
-              id: state.current_audio_id,
-              name: state.current_audio_name
-            })
-          }
-        }
+    const syncState = async () => {
+      try {
+        applyState(await invoke<PlaybackState>('get_playback_state'))
       } catch (error) {
         console.error('同步播放状态失败:', error)
       }
     }
 
     syncState()
-    const interval = setInterval(syncState, 500) // 每0.5秒同步一次
-
-    return () => clearInterval(interval)
-  }, [isPlaying, currentAudio])
+    const interval = setInterval(syncState, 5000)
+    const events = ['playback-started', 'playback-paused', 'playback-stopped', 'track-changed', 'volume-changed']
+    const unlistens = events.map((event) => listen<PlaybackState>(event, (e) => applyState(e.payload)))
+    // 曲目播完时立即同步
+    unlistens.push(listen('track-ended', syncState))
+
+    return () => {
+      clearInterval(interval)
+      unlistens.forEach((unlisten) => unlisten.then(fn => fn()))
+    }
+  }, [])
 
   const playAudio = async (id: number, name: string, newAudioList?: Array<{id: number, name: string}>) => {
     try {
-      await invoke('play_audio', { id })
+      await invoke('play_audio', { id, resume: true })
       setCurrentAudio({ id, name })
       setIsPlaying(true)
 
@@ -125,8 +129,8 @@ export function PlayerProvider({ children }: { children: ReactNode }) {
       await pauseAudio()
     } else {
       if (currentAudio) {
-        // 如果有当前音频，恢复播放
-        await invoke('play_audio', { id: currentAudio.id })
+        // 如果有当前音频，从暂停处恢复播放
+        await invoke('resume_audio')
         setIsPlaying(true)
       }
     }
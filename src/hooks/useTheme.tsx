@@ -1,5 +1,6 @@
 import { useState, useEffect } from 'react'
 import { invoke } from '@tauri-apps/api/tauri'
+import { listen } from '@tauri-apps/api/event'
 
 export interface ThemeSettings {
   auto_start: boolean
@@ -7,6 +8,9 @@ export interface ThemeSettings {
   default_volume: number
   theme: string
   audio_path: string | null
+  auto_transcode: boolean
+  extract_loudnorm: boolean
+  language: string
 }
 
 export function useTheme() {
@@ -16,12 +20,22 @@ export function useTheme() {
     default_volume: 50,
     theme: 'light',
     audio_path: null,
+    auto_transcode: true,
+    extract_loudnorm: false,
+    language: 'zh',
   })
 
   const [isLoading, setIsLoading] = useState(true)
 
   useEffect(() => {
     loadSettings()
+    // 其他窗口或页面保存设置后同步过来
+    const unlisten = listen<ThemeSettings>('settings-changed', (event) => {
+      setSettings(event.payload)
+    })
+    return () => {
+      unlisten.then((fn) => fn())
+    }
   }, [])
 
   const loadSettings = async () => {
@@ -1,10 +1,12 @@
-import { useState, useEffect } from 'react'
-import { Upload, Trash2, Play, Pause, Square, Search, Music, RefreshCw, Mic, SkipBack, SkipForward, Film, Loader2 } from 'lucide-react'
-import { invoke } from '@tauri-apps/api/tauri'
+import { useState, useEffect, useRef } from 'react'
+import { Upload, Trash2, Play, Pause, Square, Search, Music, RefreshCw, Mic, SkipBack, SkipForward, Film, Loader2, Bookmark, Headphones, Info, PenLine, GraduationCap } from 'lucide-react'
+import { invoke, convertFileSrc } from '@tauri-apps/api/tauri'
 import { open } from '@tauri-apps/api/dialog'
 import { listen } from '@tauri-apps/api/event'
 import { usePlayer } from '../contexts/PlayerContext'
 import DeleteConfirmDialog from '../components/DeleteConfirmDialog'
+import MediaInfoDialog from '../components/MediaInfoDialog'
+import BatchRenameDialog from '../components/BatchRenameDialog'
 import { useTheme } from '../hooks/useTheme'
 
 interface AudioFile {
@@ -12,15 +14,55 @@ interface AudioFile {
   filename: string
   original_name: string
   file_size: number
-  duration: number
+  duration: number | null
   format: string
   upload_date: string
+  is_managed: boolean
+  source_type: string | null
+  source_url: string | null
+  archived_at: string | null
+  preferred_speed: number | null
+  learned_at: string | null
+}
+
+interface RecordingTemplate {
+  id: number
+  name: string
+  filename_pattern: string
+  format: string
+  folder: string | null
+  playlist_name: string | null
+}
+
+interface Playlist {
+  id: number
+  name: string
+}
+
+interface AudioMarker {
+  id: number
+  audio_id: number
+  position: number
+  label: string
+}
+
+interface AudioUsage {
+  playing: boolean
+  queued: boolean
+  tasks: string[]
+}
+
+interface SupportedFormat {
+  extension: string
+  label: string
+  native: boolean
 }
 
 export default function AudioLibrary() {
   const { settings } = useTheme()
   const theme = settings.theme
   const [audioFiles, setAudioFiles] = useState<AudioFile[]>([])
+  const [resumePositions, setResumePositions] = useState<Map<number, number>>(new Map())
   const [selectedFiles, setSelectedFiles] = useState<Set<number>>(new Set())
   const [searchTerm, setSearchTerm] = useState('')
   const { isPlaying, currentAudio, playAudio, pauseAudio, stopAudio, playNext, playPrevious, currentIndex, totalCount } = usePlayer()
@@ -29,7 +71,21 @@ export default function AudioLibrary() {
   const [showDeleteDialog, setShowDeleteDialog] = useState(false)
   const [showRecordDialog, setShowRecordDialog] = useState(false)
   const [isRecording, setIsRecording] = useState(false)
+  const [recordingMarkers, setRecordingMarkers] = useState<{ position: number, label: string }[]>([])
+  const [markerAudio, setMarkerAudio] = useState<AudioFile | null>(null)
+  const [audioMarkers, setAudioMarkers] = useState<AudioMarker[]>([])
+  const [previewId, setPreviewId] = useState<number | null>(null)
+  // 查看编码信息的音频
+  const [mediaInfoFile, setMediaInfoFile] = useState<AudioFile | null>(null)
+  // 批量重命名的音频（按列表中的顺序）
+  const [renameIds, setRenameIds] = useState<number[]>([])
+  const previewAudio = useRef<HTMLAudioElement | null>(null)
   const [recordingFilename, setRecordingFilename] = useState('')
+  const [recordingTemplates, setRecordingTemplates] = useState<RecordingTemplate[]>([])
+  const [recordingTemplateId, setRecordingTemplateId] = useState<number | null>(null)
+  // 录音结束后直接加入的播放列表
+  const [recordingPlaylists, setRecordingPlaylists] = useState<Playlist[]>([])
+  const [recordingPlaylistId, setRecordingPlaylistId] = useState<number | null>(null)
   const [showExtractDialog, setShowExtractDialog] = useState(false)
   const [extractType, setExtractType] = useState<'local' | 'online'>('local')
   const [videoUrl, setVideoUrl] = useState('')
@@ -43,11 +99,12 @@ export default function AudioLibrary() {
 
   const handleUpload = async () => {
     try {
+      const formats = await invoke<SupportedFormat[]>('get_supported_formats')
       const selected = await open({
         multiple: true,
         filters: [{
           name: 'Audio',
-          extensions: ['mp3', 'wav', 'ogg', 'flac', 'm4a']
+          extensions: formats.map(format => format.extension)
         }]
       })
 
@@ -65,6 +122,10 @@ export default function AudioLibrary() {
   const handleScan = async () => {
     if (isScanning) return
 
+    // 包含子文件夹时可按文件夹层级生成播放列表
+    const recursive = confirm('是否扫描子文件夹？')
+    const folderPlaylists = recursive && confirm('是否按文件夹生成播放列表？（如“英语/一年级/Unit 3”）')
+
     setIsScanning(true)
     try {
       const result = await invoke<{
@@ -72,7 +133,8 @@ export default function AudioLibrary() {
         added_files: number
         skipped_files: number
         error_files: number
-      }>('scan_audio_directory')
+        playlists_created: number
+      }>('scan_audio_directory', { recursive, folderPlaylists })
 
       // 显示扫描结果
       let message = `扫描完成！\n`
@@ -82,6 +144,9 @@ export default function AudioLibrary() {
       if (result.error_files > 0) {
         message += `错误文件: ${result.error_files} 个`
       }
+      if (result.playlists_created > 0) {
+        message += `\n新建播放列表: ${result.playlists_created} 个`
+      }
 
       if (result.added_files > 0) {
         message += '\n\n音频库已更新！'
@@ -99,6 +164,18 @@ export default function AudioLibrary() {
     }
   }
 
+  // 选中的音频都已学会时取消标记，否则全部标记为已学会
+  const handleToggleLearned = async () => {
+    const selected = audioFiles.filter((file) => selectedFiles.has(file.id))
+    const learned = !selected.every((file) => file.learned_at)
+    try {
+      await invoke('set_audio_learned', { ids: selected.map((file) => file.id), learned })
+      await loadAudioFiles()
+    } catch (error) {
+      alert('标记已学会失败: ' + error)
+    }
+  }
+
   const loadAudioFiles = async () => {
     try {
       const files = await invoke<AudioFile[]>('get_audio_files')
@@ -106,6 +183,24 @@ export default function AudioLibrary() {
     } catch (error) {
       console.error('加载失败:', error)
     }
+    try {
+      const positions = await invoke<{ audio_id: number, position_secs: number }[]>('get_resume_positions')
+      setResumePositions(new Map(positions.map(p => [p.audio_id, p.position_secs])))
+    } catch (error) {
+      console.error('加载播放位置失败:', error)
+    }
+  }
+
+  // 忘记播放位置，下次从头播放
+  const handleClearResume = async (audioId: number) => {
+    try {
+      await invoke('clear_resume_position', { audioId })
+      const next = new Map(resumePositions)
+      next.delete(audioId)
+      setResumePositions(next)
+    } catch (error) {
+      console.error('清除播放位置失败:', error)
+    }
   }
 
   const handleDelete = () => {
@@ -117,8 +212,26 @@ export default function AudioLibrary() {
     setShowDeleteDialog(false)
 
     try {
+      // 正在播放、排队或被定时任务引用的音频需要确认后强制删除
+      const inUse: string[] = []
       for (const id of selectedFiles) {
-        await invoke('delete_audio_file', { id, deletePhysicalFile })
+        const usage = await invoke<AudioUsage>('get_audio_usage', { id })
+        if (usage.playing || usage.queued || usage.tasks.length > 0) {
+          const name = audioFiles.find(f => f.id === id)?.original_name ?? `#${id}`
+          const reasons = [
+            usage.playing ? '正在播放' : '',
+            usage.queued ? '在播放队列中' : '',
+            usage.tasks.length > 0 ? `被任务引用：${usage.tasks.join('、')}` : '',
+          ].filter(Boolean).join('；')
+          inUse.push(`${name}（${reasons}）`)
+        }
+      }
+      if (inUse.length > 0 && !confirm(`以下音频正在使用中：\n${inUse.join('\n')}\n\n仍要删除吗？删除后将停止播放并从播放列表中移除。`)) {
+        return
+      }
+
+      for (const id of selectedFiles) {
+        await invoke('delete_audio_file', { id, deletePhysicalFile, force: inUse.length > 0 })
       }
       setSelectedFiles(new Set())
       await loadAudioFiles()
@@ -132,22 +245,52 @@ export default function AudioLibrary() {
     setShowDeleteDialog(false)
   }
 
+  const handleRefreshFromSource = async (file: AudioFile) => {
+    if (!confirm(`从来源重新提取「${file.original_name}」？\n来源: ${file.source_url}`)) return
+
+    try {
+      await invoke('refresh_audio_from_source', { id: file.id })
+      await loadAudioFiles()
+    } catch (error) {
+      console.error('刷新失败:', error)
+      alert('刷新失败: ' + error)
+    }
+  }
+
   const handleOpenRecordDialog = () => {
     const now = new Date()
     const defaultFilename = now.toISOString().replace(/[:.]/g, '-').split('T')[0] + '_' +
                            now.toTimeString().split(' ')[0].replace(/:/g, '')
     setRecordingFilename(defaultFilename)
+    setRecordingTemplateId(null)
     setShowRecordDialog(true)
+    invoke<RecordingTemplate[]>('get_recording_templates')
+      .then(setRecordingTemplates)
+      .catch((error) => console.error('加载录音模板失败:', error))
+    invoke<Playlist[]>('get_playlists')
+      .then(setRecordingPlaylists)
+      .catch((error) => console.error('加载播放列表失败:', error))
+  }
+
+  const handleSelectRecordingTemplate = (id: number | null) => {
+    setRecordingTemplateId(id)
+    // 使用模板时默认按模板规则命名
+    setRecordingFilename('')
   }
 
   const handleStartRecording = async () => {
-    if (!recordingFilename.trim()) {
+    if (!recordingFilename.trim() && recordingTemplateId === null) {
       alert('请输入文件名')
       return
     }
 
     try {
-      await invoke('start_recording', { filename: recordingFilename })
+      await invoke('start_recording', {
+        filename: recordingFilename,
+        templateId: recordingTemplateId,
+        playlistId: recordingPlaylistId,
+      })
+      setRecordingMarkers([])
       setIsRecording(true)
     } catch (error) {
       console.error('开始录音失败:', error)
@@ -168,6 +311,81 @@ export default function AudioLibrary() {
     }
   }
 
+  const handleAddMarker = async () => {
+    try {
+      const marker = await invoke<{ position: number, label: string }>('add_recording_marker', {})
+      setRecordingMarkers((prev) => [...prev, marker])
+    } catch (error) {
+      console.error('添加标记失败:', error)
+    }
+  }
+
+  // 对比播放选中的两个音频（如示范音频和自己的录音）
+  const handleCompare = async () => {
+    const [idA, idB] = audioFiles.filter((file) => selectedFiles.has(file.id)).map((file) => file.id)
+    try {
+      await invoke('play_ab_compare', { idA, idB })
+    } catch (error) {
+      console.error('对比播放失败:', error)
+      alert('对比播放失败: ' + error)
+    }
+  }
+
+  // 在界面内试听（通过 stream 协议直接读取音频库文件，不影响播放器）
+  const handlePreview = (file: AudioFile) => {
+    previewAudio.current?.pause()
+    if (previewId === file.id) {
+      setPreviewId(null)
+      return
+    }
+    const audio = new Audio(convertFileSrc(String(file.id), 'stream'))
+    audio.onended = () => setPreviewId(null)
+    audio.play().catch((error) => {
+      console.error('试听失败:', error)
+      setPreviewId(null)
+    })
+    previewAudio.current = audio
+    setPreviewId(file.id)
+  }
+
+  useEffect(() => {
+    return () => previewAudio.current?.pause()
+  }, [])
+
+  const handleShowMarkers = async (file: AudioFile) => {
+    try {
+      setAudioMarkers(await invoke<AudioMarker[]>('get_audio_markers', { audioId: file.id }))
+      setMarkerAudio(file)
+    } catch (error) {
+      alert('加载标记失败: ' + error)
+    }
+  }
+
+  const handlePlayFromMarker = async (marker: AudioMarker) => {
+    try {
+      await invoke('play_audio', { id: marker.audio_id, startAt: marker.position })
+    } catch (error) {
+      alert('播放失败: ' + error)
+    }
+  }
+
+  const handleDeleteMarker = async (marker: AudioMarker) => {
+    try {
+      await invoke('delete_audio_marker', { id: marker.id })
+      setAudioMarkers((prev) => prev.filter(m => m.id !== marker.id))
+    } catch (error) {
+      alert('删除标记失败: ' + error)
+    }
+  }
+
+  const formatMarkerTime = (secs: number) => {
+    const total = Math.floor(secs)
+    const h = Math.floor(total / 3600)
+    const m = Math.floor((total % 3600) / 60).toString().padStart(2, '0')
+    const s = (total % 60).toString().padStart(2, '0')
+    return h > 0 ? `${h}:${m}:${s}` : `${m}:${s}`
+  }
+
   const handleOpenExtractDialog = async () => {
     setExtractType('local')
     setVideoUrl('')
@@ -234,6 +452,15 @@ export default function AudioLibrary() {
     }
   }
 
+  // 边下边播音频链接，不导入音频库
+  const handleStreamPreview = async () => {
+    try {
+      await invoke('play_stream', { url: videoUrl.trim() })
+    } catch (error) {
+      alert('试听失败: ' + error)
+    }
+  }
+
   const handleExtractAudio = async () => {
     if (extractType === 'online') {
       // 在线视频提取
@@ -355,7 +582,8 @@ export default function AudioLibrary() {
     return (bytes / (1024 * 1024)).toFixed(1) + ' MB'
   }
 
-  const formatDuration = (seconds: number) => {
+  const formatDuration = (seconds: number | null) => {
+    if (seconds === null) return '--'
     const hours = Math.floor(seconds / 3600)
     const mins = Math.floor((seconds % 3600) / 60)
     const secs = seconds % 60
@@ -399,7 +627,8 @@ export default function AudioLibrary() {
       setIsDragging(false)
 
       const filePaths = event.payload
-      const supportedFormats = ['mp3', 'wav', 'ogg', 'flac', 'm4a']
+      const formats = await invoke<SupportedFormat[]>('get_supported_formats')
+      const supportedFormats = formats.map(format => format.extension)
 
       // 过滤支持的音频文件
       const audioFiles = filePaths.filter(path => {
@@ -408,7 +637,7 @@ export default function AudioLibrary() {
       })
 
       if (audioFiles.length === 0) {
-        alert('没有检测到支持的音频文件！\n支持格式：MP3, WAV, OGG, FLAC, M4A')
+        alert(`没有检测到支持的音频文件！\n支持格式：${formats.map(format => format.label).join(', ')}`)
         return
       }
 
@@ -426,6 +655,11 @@ export default function AudioLibrary() {
       }
     })
 
+    // 监听音库变化（导入、删除、后台时长检测等，后端已合并成批）
+    const unlistenLibrary = listen('library-changed', () => {
+      loadAudioFiles()
+    })
+
     // 监听拖放悬停事件
     const unlistenHover = listen('tauri://file-drop-hover', () => {
       setIsDragging(true)
@@ -439,6 +673,7 @@ export default function AudioLibrary() {
     return () => {
       unlisten.then(fn => fn())
       unlistenHover.then(fn => fn())
+      unlistenLibrary.then(fn => fn())
       unlistenCancelled.then(fn => fn())
     }
   }, [])
@@ -453,6 +688,18 @@ export default function AudioLibrary() {
         onCancel={handleDeleteCancel}
       />
 
+      <MediaInfoDialog
+        audioId={mediaInfoFile?.id ?? null}
+        audioName={mediaInfoFile?.original_name ?? ''}
+        onClose={() => setMediaInfoFile(null)}
+      />
+
+      <BatchRenameDialog
+        ids={renameIds}
+        onClose={() => setRenameIds([])}
+        onRenamed={loadAudioFiles}
+      />
+
       {/* 拖放遮罩层 */}
       {isDragging && (
         <div className="absolute inset-0 bg-blue-500 bg-opacity-20 border-4 border-dashed border-blue-500 z-50 flex items-center justify-center">
@@ -501,6 +748,36 @@ export default function AudioLibrary() {
             <span>提取音频</span>
           </button>
 
+          {selectedFiles.size === 2 && (
+            <button
+              onClick={handleCompare}
+              className="flex items-center gap-2 px-4 py-2 bg-purple-600 text-white rounded-lg hover:bg-purple-700 transition-colors"
+              title="先播放第一个，在播放栏中可切换到另一个的相同位置"
+            >
+              <Headphones size={18} />
+              <span>对比播放</span>
+            </button>
+          )}
+
+          <button
+            onClick={() => setRenameIds(audioFiles.filter((file) => selectedFiles.has(file.id)).map((file) => file.id))}
+            disabled={selectedFiles.size === 0}
+            className="flex items-center gap-2 px-4 py-2 bg-gray-600 text-white rounded-lg hover:bg-gray-700 transition-colors disabled:opacity-50 disabled:cursor-not-allowed"
+          >
+            <PenLine size={18} />
+            <span>批量重命名</span>
+          </button>
+
+          <button
+            onClick={handleToggleLearned}
+            disabled={selectedFiles.size === 0}
+            className="flex items-center gap-2 px-4 py-2 bg-green-600 text-white rounded-lg hover:bg-green-700 transition-colors disabled:opacity-50 disabled:cursor-not-allowed"
+            title="开启了“跳过已学会”的播放列表不再播放已学会的音频"
+          >
+            <GraduationCap size={18} />
+            <span>已学会</span>
+          </button>
+
           <button
             onClick={handleDelete}
             disabled={selectedFiles.size === 0}
@@ -631,14 +908,52 @@ export default function AudioLibrary() {
                           )}
                           <span
                             className={`truncate ${isCurrentlyPlaying ? 'text-blue-700 font-medium' : 'text-gray-800'}`}
-                            title={file.original_name}
+                            title={file.source_url ? `${file.original_name}\n来源: ${file.source_url}` : file.original_name}
                           >
                             {file.original_name}
                           </span>
+                          {!file.is_managed && (
+                            <span
+                              className="flex-shrink-0 px-1.5 py-0.5 text-xs text-gray-500 bg-gray-100 rounded"
+                              title="引用外部文件，删除时默认保留硬盘上的原文件"
+                            >
+                              外部
+                            </span>
+                          )}
+                          {resumePositions.has(file.id) && (
+                            <span
+                              onClick={(e) => { e.stopPropagation(); handleClearResume(file.id) }}
+                              className="flex-shrink-0 px-1.5 py-0.5 text-xs text-green-700 bg-green-50 rounded cursor-pointer"
+                              title="播放时从上次的位置继续，点击改为从头播放"
+                            >
+                              听到 {formatDuration(Math.floor(resumePositions.get(file.id)!))}
+                            </span>
+                          )}
+                          {file.archived_at && (
+                            <span
+                              className="flex-shrink-0 px-1.5 py-0.5 text-xs text-amber-700 bg-amber-50 rounded"
+                              title={`已于 ${formatDateTime(file.archived_at)} 归档`}
+                            >
+                              已归档
+                            </span>
+                          )}
+                          {file.learned_at && (
+                            <span
+                              className="flex-shrink-0 px-1.5 py-0.5 text-xs text-green-700 bg-green-50 rounded"
+                              title={`已于 ${formatDateTime(file.learned_at)} 标记为已学会`}
+                            >
+                              已学会
+                            </span>
+                          )}
                         </div>
                       </td>
                       <td className="py-3 text-gray-600">{formatFileSize(file.file_size)}</td>
-                      <td className="py-3 text-gray-600">{formatDuration(file.duration)}</td>
+                      <td className="py-3 text-gray-600">
+                        {formatDuration(file.duration)}
+                        {file.preferred_speed !== null && (
+                          <span className="ml-1 text-xs text-blue-600" title="记住的倍速">{file.preferred_speed}x</span>
+                        )}
+                      </td>
                       <td className="py-3 text-gray-600 uppercase">{file.format}</td>
                       <td className="py-3 text-gray-600">
                         {formatDateTime(file.upload_date)}
@@ -691,6 +1006,38 @@ export default function AudioLibrary() {
                               <Play size={16} />
                             </button>
                           )}
+                          <button
+                            onClick={() => handlePreview(file)}
+                            className={`p-2 rounded-lg transition-colors ${
+                              previewId === file.id ? 'text-green-600 bg-green-50' : 'text-gray-500 hover:bg-gray-100'
+                            }`}
+                            title={previewId === file.id ? '停止试听' : '试听'}
+                          >
+                            <Headphones size={16} />
+                          </button>
+                          <button
+                            onClick={() => handleShowMarkers(file)}
+                            className="p-2 text-gray-500 hover:bg-gray-100 rounded-lg transition-colors"
+                            title="标记（章节/书签）"
+                          >
+                            <Bookmark size={16} />
+                          </button>
+                          <button
+                            onClick={() => setMediaInfoFile(file)}
+                            className="p-2 text-gray-500 hover:bg-gray-100 rounded-lg transition-colors"
+                            title="编码信息"
+                          >
+                            <Info size={16} />
+                          </button>
+                          {file.source_url && (
+                            <button
+                              onClick={() => handleRefreshFromSource(file)}
+                              className="p-2 text-gray-500 hover:bg-gray-100 rounded-lg transition-colors"
+                              title={`从来源重新提取: ${file.source_url}`}
+                            >
+                              <RefreshCw size={16} />
+                            </button>
+                          )}
                         </div>
                       </td>
                     </tr>
@@ -707,6 +1054,24 @@ export default function AudioLibrary() {
         <div className="fixed inset-0 bg-black bg-opacity-50 flex items-center justify-center z-50">
           <div className="bg-white rounded-lg p-6 w-96">
             <h3 className="text-xl font-bold mb-4">录制音频</h3>
+            {recordingTemplates.length > 0 && (
+              <div className="mb-4">
+                <label className="block text-sm font-medium text-gray-700 mb-2">
+                  录音模板
+                </label>
+                <select
+                  value={recordingTemplateId ?? ''}
+                  onChange={(e) => handleSelectRecordingTemplate(e.target.value ? Number(e.target.value) : null)}
+                  className="w-full px-4 py-2 border border-gray-300 rounded-lg focus:outline-none focus:ring-2 focus:ring-purple-500"
+                  disabled={isRecording}
+                >
+                  <option value="">不使用模板</option>
+                  {recordingTemplates.map((template) => (
+                    <option key={template.id} value={template.id}>{template.name}</option>
+                  ))}
+                </select>
+              </div>
+            )}
             <div className="mb-4">
               <label className="block text-sm font-medium text-gray-700 mb-2">
                 文件名
@@ -715,11 +1080,35 @@ export default function AudioLibrary() {
                 type="text"
                 value={recordingFilename}
                 onChange={(e) => setRecordingFilename(e.target.value)}
-                placeholder="请输入文件名"
+                placeholder={recordingTemplateId === null ? '请输入文件名' : '留空则按模板规则命名'}
                 className="w-full px-4 py-2 border border-gray-300 rounded-lg focus:outline-none focus:ring-2 focus:ring-purple-500"
                 disabled={isRecording}
               />
-              <p className="text-xs text-gray-500 mt-1">文件将保存为 WAV 格式</p>
+              <p className="text-xs text-gray-500 mt-1">
+                {(() => {
+                  const template = recordingTemplates.find((t) => t.id === recordingTemplateId)
+                  if (!template) return '文件将保存为 WAV 格式'
+                  return `文件将保存为 ${template.format.toUpperCase()} 格式` +
+                    (template.folder ? `，存放在“${template.folder}”目录` : '') +
+                    (template.playlist_name && recordingPlaylistId === null ? `，并加入播放列表“${template.playlist_name}”` : '')
+                })()}
+              </p>
+            </div>
+            <div className="mb-4">
+              <label className="block text-sm font-medium text-gray-700 mb-2">
+                加入播放列表
+              </label>
+              <select
+                value={recordingPlaylistId ?? ''}
+                onChange={(e) => setRecordingPlaylistId(e.target.value ? Number(e.target.value) : null)}
+                className="w-full px-4 py-2 border border-gray-300 rounded-lg focus:outline-none focus:ring-2 focus:ring-purple-500"
+                disabled={isRecording}
+              >
+                <option value="">不加入（或按模板设置）</option>
+                {recordingPlaylists.map((playlist) => (
+                  <option key={playlist.id} value={playlist.id}>{playlist.name}</option>
+                ))}
+              </select>
             </div>
             {isRecording && (
               <div className="mb-4 p-3 bg-red-50 border border-red-200 rounded-lg">
@@ -727,6 +1116,13 @@ export default function AudioLibrary() {
                   <div className="w-3 h-3 bg-red-600 rounded-full animate-pulse" />
                   <span className="font-medium">正在录音中...</span>
                 </div>
+                {recordingMarkers.length > 0 && (
+                  <ul className="mt-2 text-sm text-gray-700 max-h-32 overflow-y-auto">
+                    {recordingMarkers.map((marker, index) => (
+                      <li key={index}>{formatMarkerTime(marker.position)} {marker.label}</li>
+                    ))}
+                  </ul>
+                )}
               </div>
             )}
             <div className="flex justify-end gap-2">
@@ -747,19 +1143,71 @@ export default function AudioLibrary() {
                   </button>
                 </>
               ) : (
-                <button
-                  onClick={handleStopRecording}
-                  className="flex items-center gap-2 px-4 py-2 bg-red-600 text-white rounded-lg hover:bg-red-700 transition-colors"
-                >
-                  <Square size={16} />
-                  <span>结束录音</span>
-                </button>
+                <>
+                  <button
+                    onClick={handleAddMarker}
+                    className="flex items-center gap-2 px-4 py-2 text-purple-700 bg-purple-50 rounded-lg hover:bg-purple-100 transition-colors"
+                  >
+                    <Bookmark size={16} />
+                    <span>打标记</span>
+                  </button>
+                  <button
+                    onClick={handleStopRecording}
+                    className="flex items-center gap-2 px-4 py-2 bg-red-600 text-white rounded-lg hover:bg-red-700 transition-colors"
+                  >
+                    <Square size={16} />
+                    <span>结束录音</span>
+                  </button>
+                </>
               )}
             </div>
           </div>
         </div>
       )}
 
+      {/* 音频标记对话框 */}
+      {markerAudio && (
+        <div className="fixed inset-0 bg-black bg-opacity-50 flex items-center justify-center z-50">
+          <div className="bg-white rounded-lg p-6 w-96">
+            <h3 className="text-xl font-bold mb-4">标记 - {markerAudio.original_name}</h3>
+            {audioMarkers.length === 0 ? (
+              <p className="text-gray-500 mb-4">没有标记（录音时点击“打标记”添加）</p>
+            ) : (
+              <ul className="mb-4 max-h-80 overflow-y-auto divide-y divide-gray-100">
+                {audioMarkers.map((marker) => (
+                  <li key={marker.id} className="flex items-center gap-2 py-2">
+                    <span className="font-mono text-sm text-gray-500">{formatMarkerTime(marker.position)}</span>
+                    <span className="flex-1 text-gray-800">{marker.label}</span>
+                    <button
+                      onClick={() => handlePlayFromMarker(marker)}
+                      className="p-1 text-blue-600 hover:bg-blue-50 rounded"
+                      title="从此处播放"
+                    >
+                      <Play size={14} />
+                    </button>
+                    <button
+                      onClick={() => handleDeleteMarker(marker)}
+                      className="p-1 text-red-600 hover:bg-red-50 rounded"
+                      title="删除标记"
+                    >
+                      <Trash2 size={14} />
+                    </button>
+                  </li>
+                ))}
+              </ul>
+            )}
+            <div className="flex justify-end">
+              <button
+                onClick={() => setMarkerAudio(null)}
+                className="px-4 py-2 text-gray-600 hover:bg-gray-100 rounded-lg transition-colors"
+              >
+                关闭
+              </button>
+            </div>
+          </div>
+        </div>
+      )}
+
       {/* 提取音频对话框 */}
       {showExtractDialog && (
         <div className="fixed inset-0 bg-black bg-opacity-50 flex items-center justify-center z-50">
@@ -852,15 +1300,26 @@ export default function AudioLibrary() {
                 <label className="block text-sm font-medium text-gray-700 mb-2">
                   视频地址 *
                 </label>
-                <input
-                  type="text"
-                  value={videoUrl}
-                  onChange={(e) => setVideoUrl(e.target.value)}
-                  placeholder="输入Bilibili、抖音等视频链接"
-                  className="w-full px-4 py-2 border border-gray-300 rounded-lg focus:outline-none focus:ring-2 focus:ring-orange-500"
-                  disabled={isExtracting || isInstallingFFmpeg || isRestarting}
-                />
-                <p className="text-xs text-gray-500 mt-1">支持B站、抖音、YouTube等主流平台</p>
+                <div className="flex gap-2">
+                  <input
+                    type="text"
+                    value={videoUrl}
+                    onChange={(e) => setVideoUrl(e.target.value)}
+                    placeholder="输入Bilibili、抖音等视频链接"
+                    className="flex-1 px-4 py-2 border border-gray-300 rounded-lg focus:outline-none focus:ring-2 focus:ring-orange-500"
+                    disabled={isExtracting || isInstallingFFmpeg || isRestarting}
+                  />
+                  <button
+                    onClick={handleStreamPreview}
+                    disabled={!videoUrl.trim() || isExtracting}
+                    className="flex items-center gap-1 px-3 py-2 text-gray-700 border border-gray-300 rounded-lg hover:bg-gray-50 disabled:opacity-50"
+                    title="直接播放音频链接，不导入音频库"
+                  >
+                    <Headphones size={16} />
+                    试听
+                  </button>
+                </div>
+                <p className="text-xs text-gray-500 mt-1">支持B站、抖音、YouTube等主流平台；直接的音频链接（如 .mp3）可先试听</p>
               </div>
             )}
 
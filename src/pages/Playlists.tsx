@@ -1,5 +1,5 @@
 import { useState, useEffect } from 'react'
-import { Plus, Trash2, List as ListIcon, Shuffle, Repeat, Repeat1, Music, Play, PlayCircle, SkipBack, SkipForward, Pause } from 'lucide-react'
+import { Plus, Trash2, List as ListIcon, Shuffle, Repeat, Repeat1, Music, Play, PlayCircle, SkipBack, SkipForward, Pause, Scissors, RotateCcw, SlidersHorizontal, StickyNote } from 'lucide-react'
 import { invoke } from '@tauri-apps/api/tauri'
 import { usePlayer } from '../contexts/PlayerContext'
 
@@ -7,17 +7,39 @@ interface Playlist {
   id: number
   name: string
   play_mode: string
+  gapless: boolean
+  exclude_learned: boolean
+  weekly_target: number | null
+  week_completions: number
   created_date: string
   updated_date: string
 }
 
+interface DeletedEntry {
+  id: number
+  name: string
+  deleted_at: string
+}
+
 interface PlaylistItem {
   id: number
   playlist_id: number
   audio_id: number
   sort_order: number
   audio_name: string
-  duration: number
+  duration: number | null
+  start_offset: number | null
+  end_offset: number | null
+  gain_db: number | null
+  note: string | null
+}
+
+interface PlaylistDeleteImpact {
+  playlist_id: number
+  playlist_name: string
+  tasks: Array<{ id: number; name: string; is_enabled: boolean; via_weekday: boolean }>
+  execution_count: number
+  playback_count: number
 }
 
 interface AudioFile {
@@ -25,12 +47,13 @@ interface AudioFile {
   filename: string
   original_name: string
   file_size: number
-  duration: number
+  duration: number | null
   format: string
 }
 
 export default function Playlists() {
   const [playlists, setPlaylists] = useState<Playlist[]>([])
+  const [deletedPlaylists, setDeletedPlaylists] = useState<DeletedEntry[]>([])
   const [selectedPlaylist, setSelectedPlaylist] = useState<number | null>(null)
   const [playlistItems, setPlaylistItems] = useState<PlaylistItem[]>([])
   const [showNewDialog, setShowNewDialog] = useState(false)
@@ -40,7 +63,8 @@ export default function Playlists() {
   const [selectedAudios, setSelectedAudios] = useState<Set<number>>(new Set())
   const [showDeleteConfirm, setShowDeleteConfirm] = useState(false)
   const [playlistToDelete, setPlaylistToDelete] = useState<number | null>(null)
-  const [tasksUsingPlaylist, setTasksUsingPlaylist] = useState<string[]>([])
+  const [deleteImpact, setDeleteImpact] = useState<PlaylistDeleteImpact | null>(null)
+  const [retargetPlaylistId, setRetargetPlaylistId] = useState<number | null>(null)
   const { playAudio, isPlaying, currentAudio, playNext, playPrevious, currentIndex, totalCount } = usePlayer()
 
   useEffect(() => {
@@ -57,11 +81,22 @@ export default function Playlists() {
     try {
       const lists = await invoke<Playlist[]>('get_playlists')
       setPlaylists(lists)
+      setDeletedPlaylists(await invoke<DeletedEntry[]>('get_deleted_playlists'))
     } catch (error) {
       console.error('加载播放列表失败:', error)
     }
   }
 
+  const handleRestorePlaylist = async (id: number) => {
+    try {
+      await invoke('restore_playlist', { id })
+      loadPlaylists()
+    } catch (error) {
+      console.error('恢复播放列表失败:', error)
+      alert('恢复失败: ' + error)
+    }
+  }
+
   const loadPlaylistItems = async (playlistId: number) => {
     try {
       const items = await invoke<PlaylistItem[]>('get_playlist_items', { playlistId })
@@ -86,11 +121,12 @@ export default function Playlists() {
 
   const handleDeletePlaylist = async (id: number) => {
     try {
-      // 检查是否有定时任务引用此播放列表
-      const taskNames = await invoke<string[]>('check_playlist_tasks', { playlistId: id })
+      // 检查哪些定时任务和历史记录引用此播放列表
+      const impact = await invoke<PlaylistDeleteImpact>('get_playlist_delete_impact', { id })
 
       setPlaylistToDelete(id)
-      setTasksUsingPlaylist(taskNames)
+      setDeleteImpact(impact)
+      setRetargetPlaylistId(null)
       setShowDeleteConfirm(true)
     } catch (error) {
       console.error('检查任务失败:', error)
@@ -102,7 +138,11 @@ export default function Playlists() {
     if (playlistToDelete === null) return
 
     try {
-      await invoke('delete_playlist', { id: playlistToDelete })
+      await invoke('delete_playlist', {
+        id: playlistToDelete,
+        confirm: true,
+        retargetPlaylistId: retargetPlaylistId ?? undefined,
+      })
       if (selectedPlaylist === playlistToDelete) {
         setSelectedPlaylist(null)
         setPlaylistItems([])
@@ -110,6 +150,7 @@ export default function Playlists() {
       loadPlaylists()
       setShowDeleteConfirm(false)
       setPlaylistToDelete(null)
+      setDeleteImpact(null)
     } catch (error) {
       console.error('删除播放列表失败:', error)
       alert('删除失败: ' + error)
@@ -119,7 +160,7 @@ export default function Playlists() {
   const cancelDelete = () => {
     setShowDeleteConfirm(false)
     setPlaylistToDelete(null)
-    setTasksUsingPlaylist([])
+    setDeleteImpact(null)
   }
 
   const handleSetPlayMode = async (mode: string) => {
@@ -133,6 +174,40 @@ export default function Playlists() {
     }
   }
 
+  const handleToggleGapless = async (gapless: boolean) => {
+    if (!selectedPlaylist) return
+
+    try {
+      await invoke('set_playlist_gapless', { playlistId: selectedPlaylist, gapless })
+      loadPlaylists()
+    } catch (error) {
+      console.error('设置无缝播放失败:', error)
+    }
+  }
+
+  const handleSetWeeklyTarget = async (value: string) => {
+    if (!selectedPlaylist) return
+
+    try {
+      await invoke('set_playlist_weekly_target', { playlistId: selectedPlaylist, target: value ? parseInt(value) : null })
+      loadPlaylists()
+    } catch (error) {
+      console.error('设置每周目标失败:', error)
+      alert('设置每周目标失败: ' + error)
+    }
+  }
+
+  const handleToggleExcludeLearned = async (excludeLearned: boolean) => {
+    if (!selectedPlaylist) return
+
+    try {
+      await invoke('set_playlist_exclude_learned', { playlistId: selectedPlaylist, excludeLearned })
+      loadPlaylists()
+    } catch (error) {
+      console.error('设置跳过已学会失败:', error)
+    }
+  }
+
   const loadAudioFiles = async () => {
     try {
       const files = await invoke<AudioFile[]>('get_audio_files')
@@ -175,6 +250,71 @@ export default function Playlists() {
     }
   }
 
+  // 解析 "m:ss" 或秒数，空白表示不限制
+  const parseClipTime = (value: string): number | null => {
+    const text = value.trim()
+    if (!text) return null
+    const parts = text.split(':').map(Number)
+    if (parts.some(isNaN)) throw new Error(`无效的时间: ${text}`)
+    return parts.reduce((total, part) => total * 60 + part, 0)
+  }
+
+  const handleEditClip = async (item: PlaylistItem) => {
+    if (!selectedPlaylist) return
+
+    const start = prompt('片段开始位置（如 3:00，留空表示从头播放）', item.start_offset !== null ? formatDuration(Math.round(item.start_offset)) : '')
+    if (start === null) return
+    const end = prompt('片段结束位置（如 5:00，留空表示播放到结尾）', item.end_offset !== null ? formatDuration(Math.round(item.end_offset)) : '')
+    if (end === null) return
+
+    try {
+      await invoke('set_playlist_item_clip', {
+        id: item.id,
+        startOffset: parseClipTime(start),
+        endOffset: parseClipTime(end),
+      })
+      loadPlaylistItems(selectedPlaylist)
+    } catch (error) {
+      console.error('设置片段失败:', error)
+      alert('设置片段失败: ' + error)
+    }
+  }
+
+  const handleEditGain = async (item: PlaylistItem) => {
+    if (!selectedPlaylist) return
+
+    const input = prompt('音量增益（dB，-20 到 +20，留空或 0 表示不调整）', item.gain_db !== null ? String(item.gain_db) : '')
+    if (input === null) return
+    const gainDb = input.trim() ? Number(input) : null
+    if (gainDb !== null && isNaN(gainDb)) {
+      alert(`无效的增益: ${input}`)
+      return
+    }
+
+    try {
+      await invoke('set_playlist_item_gain', { id: item.id, gainDb })
+      loadPlaylistItems(selectedPlaylist)
+    } catch (error) {
+      console.error('设置增益失败:', error)
+      alert('设置增益失败: ' + error)
+    }
+  }
+
+  const handleEditNote = async (item: PlaylistItem) => {
+    if (!selectedPlaylist) return
+
+    const input = prompt('备注（学习要点，如“注意过去时”，留空表示清除）', item.note ?? '')
+    if (input === null) return
+
+    try {
+      await invoke('set_playlist_item_note', { id: item.id, note: input.trim() || null })
+      loadPlaylistItems(selectedPlaylist)
+    } catch (error) {
+      console.error('设置备注失败:', error)
+      alert('设置备注失败: ' + error)
+    }
+  }
+
   const handlePlayAll = async () => {
     if (!selectedPlaylist || playlistItems.length === 0) return
 
@@ -238,7 +378,8 @@ export default function Playlists() {
     }
   }
 
-  const formatDuration = (seconds: number) => {
+  const formatDuration = (seconds: number | null) => {
+    if (seconds === null) return '--'
     const hours = Math.floor(seconds / 3600)
     const mins = Math.floor((seconds % 3600) / 60)
     const secs = seconds % 60
@@ -281,6 +422,7 @@ export default function Playlists() {
                   <p className="font-medium text-gray-800 truncate">{playlist.name}</p>
                   <p className="text-xs text-gray-500 mt-1">
                     {getPlayModeName(playlist.play_mode)}
+                    {playlist.weekly_target && ` · 本周 ${playlist.week_completions}/${playlist.weekly_target} 遍`}
                   </p>
                 </div>
                 <button
@@ -301,6 +443,24 @@ export default function Playlists() {
               <p className="text-sm">还没有播放列表</p>
             </div>
           )}
+
+          {deletedPlaylists.length > 0 && (
+            <div className="mt-4 pt-3 border-t border-gray-200">
+              <p className="text-xs text-gray-500 mb-2">最近删除</p>
+              {deletedPlaylists.map((playlist) => (
+                <div key={playlist.id} className="flex items-center justify-between px-3 py-1 text-sm text-gray-500">
+                  <span className="truncate" title={`删除于 ${playlist.deleted_at}`}>{playlist.name}</span>
+                  <button
+                    onClick={() => handleRestorePlaylist(playlist.id)}
+                    className="p-1 text-blue-600 hover:bg-blue-50 rounded transition-colors"
+                    title="恢复"
+                  >
+                    <RotateCcw size={14} />
+                  </button>
+                </div>
+              ))}
+            </div>
+          )}
         </div>
       </div>
 
@@ -341,6 +501,45 @@ export default function Playlists() {
                       {getPlayModeIcon(mode)}
                     </button>
                   ))}
+                  <label
+                    className="flex items-center gap-2 px-3 py-2 bg-gray-100 text-gray-700 rounded-lg cursor-pointer"
+                    title="播放时相邻曲目之间没有停顿（适合分段的有声书）"
+                  >
+                    <input
+                      type="checkbox"
+                      checked={currentPlaylist.gapless}
+                      onChange={(e) => handleToggleGapless(e.target.checked)}
+                    />
+                    <span>无缝播放</span>
+                  </label>
+                  <label
+                    className="flex items-center gap-2 px-3 py-2 bg-gray-100 text-gray-700 rounded-lg cursor-pointer"
+                    title="播放和定时任务跳过音频库中标记为已学会的音频"
+                  >
+                    <input
+                      type="checkbox"
+                      checked={currentPlaylist.exclude_learned}
+                      onChange={(e) => handleToggleExcludeLearned(e.target.checked)}
+                    />
+                    <span>跳过已学会</span>
+                  </label>
+                  <label
+                    className="flex items-center gap-2 px-3 py-2 bg-gray-100 text-gray-700 rounded-lg"
+                    title="每周要完整听完几遍（每首都播放过一次算一遍），进度显示在播放记录中"
+                  >
+                    <span>每周</span>
+                    <input
+                      key={currentPlaylist.id}
+                      type="number"
+                      min="1"
+                      max="50"
+                      defaultValue={currentPlaylist.weekly_target ?? ''}
+                      onBlur={(e) => handleSetWeeklyTarget(e.target.value)}
+                      placeholder="不限"
+                      className="w-14 px-1 border border-gray-300 rounded text-sm"
+                    />
+                    <span>遍</span>
+                  </label>
                 </div>
               </div>
 
@@ -390,8 +589,23 @@ export default function Playlists() {
                                 {item.audio_name}
                               </span>
                             </div>
+                            {item.note && (
+                              <div className="text-xs text-amber-700 mt-1 whitespace-pre-wrap">{item.note}</div>
+                            )}
+                          </td>
+                          <td className="py-3 text-gray-600">
+                            {formatDuration(item.duration)}
+                            {(item.start_offset !== null || item.end_offset !== null) && (
+                              <div className="text-xs text-purple-600">
+                                片段 {formatDuration(Math.round(item.start_offset ?? 0))}-{item.end_offset !== null ? formatDuration(Math.round(item.end_offset)) : '结尾'}
+                              </div>
+                            )}
+                            {item.gain_db !== null && (
+                              <div className="text-xs text-teal-600">
+                                增益 {item.gain_db > 0 ? '+' : ''}{item.gain_db} dB
+                              </div>
+                            )}
                           </td>
-                          <td className="py-3 text-gray-600">{formatDuration(item.duration)}</td>
                           <td className="py-3">
                             <div className="flex gap-1">
                               {currentAudio && currentAudio.id === item.audio_id && isPlaying ? (
@@ -433,6 +647,27 @@ export default function Playlists() {
                                   <Play size={16} />
                                 </button>
                               )}
+                              <button
+                                onClick={() => handleEditClip(item)}
+                                className="p-2 text-purple-600 hover:bg-purple-50 rounded-lg transition-colors"
+                                title="设置播放片段"
+                              >
+                                <Scissors size={16} />
+                              </button>
+                              <button
+                                onClick={() => handleEditGain(item)}
+                                className="p-2 text-teal-600 hover:bg-teal-50 rounded-lg transition-colors"
+                                title="调整该项音量增益"
+                              >
+                                <SlidersHorizontal size={16} />
+                              </button>
+                              <button
+                                onClick={() => handleEditNote(item)}
+                                className="p-2 text-amber-600 hover:bg-amber-50 rounded-lg transition-colors"
+                                title="编辑备注"
+                              >
+                                <StickyNote size={16} />
+                              </button>
                               <button
                                 onClick={() => handleRemoveFromPlaylist(item.id)}
                                 className="p-2 text-red-600 hover:bg-red-50 rounded-lg transition-colors"
@@ -466,22 +701,46 @@ export default function Playlists() {
           <div className="bg-white rounded-lg p-6 w-[480px]">
             <h3 className="text-xl font-bold mb-4 text-gray-800">确认删除</h3>
 
-            {tasksUsingPlaylist.length > 0 ? (
+            {deleteImpact && deleteImpact.tasks.length > 0 ? (
               <>
                 <div className="mb-4 p-3 bg-red-50 border border-red-200 rounded-lg">
                   <p className="text-red-700 font-medium mb-2">⚠️ 警告：以下定时任务正在使用此播放列表</p>
                   <ul className="list-disc ml-6 text-red-600">
-                    {tasksUsingPlaylist.map((taskName, idx) => (
-                      <li key={idx}>{taskName}</li>
+                    {deleteImpact.tasks.map((task) => (
+                      <li key={task.id}>
+                        {task.name}
+                        {!task.is_enabled && <span className="text-gray-500">（已禁用）</span>}
+                        {task.via_weekday && <span className="text-gray-500">（星期映射）</span>}
+                      </li>
                     ))}
                   </ul>
                 </div>
+                <div className="mb-4">
+                  <label className="block text-sm text-gray-700 mb-1">将这些任务改为使用</label>
+                  <select
+                    value={retargetPlaylistId ?? ''}
+                    onChange={(e) => setRetargetPlaylistId(e.target.value ? parseInt(e.target.value) : null)}
+                    className="w-full px-3 py-2 border border-gray-300 rounded-lg"
+                  >
+                    <option value="">不修改（任务将无法执行）</option>
+                    {playlists.filter(p => p.id !== playlistToDelete).map((p) => (
+                      <option key={p.id} value={p.id}>{p.name}</option>
+                    ))}
+                  </select>
+                </div>
                 <p className="text-gray-600 mb-6">
-                  删除此播放列表将影响这些定时任务的执行。确定要继续删除吗？
+                  {retargetPlaylistId === null
+                    ? '删除此播放列表将影响这些定时任务的执行。确定要继续删除吗？'
+                    : '这些任务将改为使用所选播放列表。确定要继续删除吗？'}
                 </p>
               </>
             ) : (
-              <p className="text-gray-600 mb-6">确定要删除该播放列表吗？此操作无法撤销。</p>
+              <p className="text-gray-600 mb-6">确定要删除该播放列表吗？删除后 30 天内可在“最近删除”中恢复。</p>
+            )}
+            {deleteImpact && (deleteImpact.execution_count > 0 || deleteImpact.playback_count > 0) && (
+              <p className="text-sm text-gray-500 mb-4">
+                历史记录中有 {deleteImpact.execution_count} 条任务执行记录和 {deleteImpact.playback_count} 条播放记录引用此播放列表，删除后仍会保留。
+              </p>
             )}
 
             <div className="flex justify-end gap-2">
@@ -494,12 +753,12 @@ export default function Playlists() {
               <button
                 onClick={confirmDelete}
                 className={`px-4 py-2 text-white rounded-lg transition-colors ${
-                  tasksUsingPlaylist.length > 0
+                  deleteImpact && deleteImpact.tasks.length > 0
                     ? 'bg-red-600 hover:bg-red-700'
                     : 'bg-blue-600 hover:bg-blue-700'
                 }`}
               >
-                {tasksUsingPlaylist.length > 0 ? '确认删除' : '删除'}
+                {deleteImpact && deleteImpact.tasks.length > 0 ? '确认删除' : '删除'}
               </button>
             </div>
           </div>
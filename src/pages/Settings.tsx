@@ -1,12 +1,370 @@
-import { useState } from 'react'
+import { useState, useEffect } from 'react'
 import { Save, FolderOpen, Moon, Sun, Volume2, Download, Upload } from 'lucide-react'
 import { invoke } from '@tauri-apps/api/tauri'
 import { open } from '@tauri-apps/api/dialog'
+import { listen } from '@tauri-apps/api/event'
 import { useTheme } from '../hooks/useTheme'
+import RecordingTemplates from '../components/RecordingTemplates'
+import KioskSettings from '../components/KioskSettings'
+import HolidayCalendar from '../components/HolidayCalendar'
+import SchedulerTraceDialog from '../components/SchedulerTraceDialog'
+
+interface PlayCountRule {
+  min_percent: number
+  min_secs: number
+}
+
+interface ArchivePolicy {
+  enabled: boolean
+  months: number
+  compress: boolean
+}
+
+interface ChannelMix {
+  balance: number
+  force_mono: boolean
+}
+
+interface CapturePausePolicy {
+  enabled: boolean
+  resume: boolean
+}
+
+interface TaskHandoff {
+  enabled: boolean
+  crossfade_secs: number
+}
+
+type TraceLevel = 'off' | 'decisions' | 'verbose'
+
+interface StorageLayout {
+  folders: 'flat' | 'year_month' | 'source'
+  readable_names: boolean
+}
+
+interface ReorganizeResult {
+  moved: number
+  unchanged: number
+  skipped: number
+}
+
+type TrayAction = 'none' | 'show_window' | 'play_pause' | 'run_default_task'
+
+interface TrayActions {
+  click: TrayAction
+  double_click: TrayAction
+  default_task_id: number | null
+}
+
+const TRAY_ACTION_OPTIONS: Array<{ value: TrayAction; label: string }> = [
+  { value: 'show_window', label: '显示窗口' },
+  { value: 'play_pause', label: '播放/暂停' },
+  { value: 'run_default_task', label: '执行默认任务' },
+  { value: 'none', label: '无操作' },
+]
+
+interface OrphanedFile {
+  path: string
+  size: number
+  modified: string | null
+  importable: boolean
+}
+
+interface OrphanActionResult {
+  processed: number
+  skipped: number
+}
+
+interface SamplePackInfo {
+  id: string
+  name: string
+  description: string
+  track_count: number
+  playlist_names: string[]
+  installed_count: number
+}
 
 export default function SettingsPage() {
   const { settings, setSettings, saveSettings } = useTheme()
   const [saved, setSaved] = useState(false)
+  const [samplePacks, setSamplePacks] = useState<SamplePackInfo[]>([])
+  const [installingPack, setInstallingPack] = useState<string | null>(null)
+  const [packProgress, setPackProgress] = useState(0)
+  const [playCountRule, setPlayCountRule] = useState<PlayCountRule>({ min_percent: 50, min_secs: 30 })
+  const [loudnessNormalization, setLoudnessNormalization] = useState(true)
+  const [skipSilence, setSkipSilence] = useState(false)
+  const [speedStep, setSpeedStep] = useState(0.25)
+  const [channelMix, setChannelMix] = useState<ChannelMix>({ balance: 0, force_mono: false })
+  const [capturePause, setCapturePause] = useState<CapturePausePolicy>({ enabled: false, resume: true })
+  const [taskHandoff, setTaskHandoff] = useState<TaskHandoff>({ enabled: false, crossfade_secs: 8 })
+  const [archivePolicy, setArchivePolicy] = useState<ArchivePolicy>({ enabled: false, months: 6, compress: true })
+  const [archiving, setArchiving] = useState(false)
+  const [traceLevel, setTraceLevel] = useState<TraceLevel>('decisions')
+  const [showTrace, setShowTrace] = useState(false)
+  const [storageLayout, setStorageLayout] = useState<StorageLayout>({ folders: 'flat', readable_names: false })
+  const [reorganizing, setReorganizing] = useState(false)
+  const [trayActions, setTrayActions] = useState<TrayActions>({ click: 'show_window', double_click: 'none', default_task_id: null })
+  const [taskOptions, setTaskOptions] = useState<Array<{ id: number; name: string }>>([])
+  const [orphans, setOrphans] = useState<OrphanedFile[] | null>(null)
+  const [sweeping, setSweeping] = useState(false)
+
+  useEffect(() => {
+    loadSamplePacks()
+    invoke<PlayCountRule>('get_play_count_rule')
+      .then(setPlayCountRule)
+      .catch((error) => console.error('加载播放计数规则失败:', error))
+    invoke<ArchivePolicy>('get_archive_policy')
+      .then(setArchivePolicy)
+      .catch((error) => console.error('加载录音归档策略失败:', error))
+    invoke<TraceLevel>('get_scheduler_trace_level')
+      .then(setTraceLevel)
+      .catch((error) => console.error('加载调度日志级别失败:', error))
+    invoke<StorageLayout>('get_storage_layout')
+      .then(setStorageLayout)
+      .catch((error) => console.error('加载存储方式失败:', error))
+    invoke<ChannelMix>('get_channel_mix')
+      .then(setChannelMix)
+      .catch((error) => console.error('加载声道设置失败:', error))
+    invoke<TrayActions>('get_tray_actions')
+      .then(setTrayActions)
+      .catch((error) => console.error('加载托盘点击动作失败:', error))
+    invoke<Array<{ id: number; name: string }>>('get_scheduled_tasks')
+      .then(setTaskOptions)
+      .catch((error) => console.error('加载任务列表失败:', error))
+    invoke<CapturePausePolicy>('get_capture_pause_policy')
+      .then(setCapturePause)
+      .catch((error) => console.error('加载通话自动暂停设置失败:', error))
+    invoke<TaskHandoff>('get_task_handoff')
+      .then(setTaskHandoff)
+      .catch((error) => console.error('加载任务交接设置失败:', error))
+    invoke<boolean>('get_loudness_normalization')
+      .then(setLoudnessNormalization)
+      .catch((error) => console.error('加载响度归一化设置失败:', error))
+    invoke<number>('get_speed_step')
+      .then(setSpeedStep)
+      .catch((error) => console.error('加载倍速步长失败:', error))
+    invoke<boolean>('get_skip_silence')
+      .then(setSkipSilence)
+      .catch((error) => console.error('加载跳过静音设置失败:', error))
+    const unlisten = listen<number>('sample-pack-progress', (event) => {
+      setPackProgress(event.payload)
+    })
+    return () => {
+      unlisten.then((fn) => fn())
+    }
+  }, [])
+
+  const savePlayCountRule = async (rule: PlayCountRule) => {
+    try {
+      await invoke('set_play_count_rule', { rule })
+    } catch (error) {
+      console.error('保存播放计数规则失败:', error)
+      alert('保存播放计数规则失败: ' + error)
+    }
+  }
+
+  const toggleLoudnessNormalization = async (enabled: boolean) => {
+    try {
+      await invoke('set_loudness_normalization', { enabled })
+      setLoudnessNormalization(enabled)
+    } catch (error) {
+      console.error('保存响度归一化设置失败:', error)
+      alert('保存响度归一化设置失败: ' + error)
+    }
+  }
+
+  const toggleSkipSilence = async (enabled: boolean) => {
+    try {
+      await invoke('set_skip_silence', { enabled })
+      setSkipSilence(enabled)
+    } catch (error) {
+      console.error('保存跳过静音设置失败:', error)
+      alert('保存跳过静音设置失败: ' + error)
+    }
+  }
+
+  const saveSpeedStep = async (step: number) => {
+    try {
+      await invoke('set_speed_step', { step })
+      setSpeedStep(step)
+    } catch (error) {
+      console.error('保存倍速步长失败:', error)
+      alert('保存倍速步长失败: ' + error)
+    }
+  }
+
+  const saveTraceLevel = async (level: TraceLevel) => {
+    try {
+      await invoke('set_scheduler_trace_level', { level })
+      setTraceLevel(level)
+    } catch (error) {
+      console.error('保存调度日志级别失败:', error)
+      alert('保存调度日志级别失败: ' + error)
+    }
+  }
+
+  const saveChannelMix = async (mix: ChannelMix) => {
+    try {
+      await invoke('set_channel_mix', { mix })
+      setChannelMix(mix)
+    } catch (error) {
+      console.error('保存声道设置失败:', error)
+      alert('保存声道设置失败: ' + error)
+    }
+  }
+
+  const saveTrayActions = async (actions: TrayActions) => {
+    // 选择“执行默认任务”但还没有指定任务时，默认使用第一个任务
+    const needsTask = actions.click === 'run_default_task' || actions.double_click === 'run_default_task'
+    const next = needsTask && actions.default_task_id === null && taskOptions.length > 0
+      ? { ...actions, default_task_id: taskOptions[0].id }
+      : actions
+    try {
+      await invoke('set_tray_actions', { actions: next })
+      setTrayActions(next)
+    } catch (error) {
+      console.error('保存托盘点击动作失败:', error)
+      alert('保存托盘点击动作失败: ' + error)
+    }
+  }
+
+  const saveCapturePause = async (policy: CapturePausePolicy) => {
+    try {
+      await invoke('set_capture_pause_policy', { policy })
+      setCapturePause(policy)
+    } catch (error) {
+      console.error('保存通话自动暂停设置失败:', error)
+      alert('保存通话自动暂停设置失败: ' + error)
+    }
+  }
+
+  const saveTaskHandoff = async (settings: TaskHandoff) => {
+    try {
+      await invoke('set_task_handoff', { settings })
+      setTaskHandoff(settings)
+    } catch (error) {
+      console.error('保存任务交接设置失败:', error)
+      alert('保存任务交接设置失败: ' + error)
+    }
+  }
+
+  const saveArchivePolicy = async (policy: ArchivePolicy) => {
+    try {
+      await invoke('set_archive_policy', { policy })
+      setArchivePolicy(policy)
+    } catch (error) {
+      console.error('保存录音归档策略失败:', error)
+      alert('保存录音归档策略失败: ' + error)
+    }
+  }
+
+  const handleArchiveNow = async () => {
+    setArchiving(true)
+    try {
+      const result = await invoke<{ archived: number, compressed: number, skipped: number, saved_bytes: number }>('archive_recordings_now')
+      alert(`已归档 ${result.archived} 个录音（压缩 ${result.compressed} 个，跳过 ${result.skipped} 个），节省 ${(result.saved_bytes / 1024 / 1024).toFixed(1)} MB`)
+    } catch (error) {
+      alert('归档失败: ' + error)
+    } finally {
+      setArchiving(false)
+    }
+  }
+
+  const saveStorageLayout = async (layout: StorageLayout) => {
+    try {
+      await invoke('set_storage_layout', { layout })
+      setStorageLayout(layout)
+    } catch (error) {
+      console.error('保存存储方式失败:', error)
+      alert('保存存储方式失败: ' + error)
+    }
+  }
+
+  // 先预览需要移动的文件数，确认后再整理
+  const handleReorganize = async () => {
+    setReorganizing(true)
+    try {
+      const preview = await invoke<ReorganizeResult>('reorganize_library')
+      if (preview.moved === 0) {
+        alert('已有文件都符合当前存储方式')
+        return
+      }
+      if (!confirm(`将移动或重命名 ${preview.moved} 个文件，是否继续？`)) return
+      const result = await invoke<ReorganizeResult>('reorganize_library', { apply: true })
+      alert(`已整理 ${result.moved} 个文件（跳过 ${result.skipped} 个）`)
+    } catch (error) {
+      alert('整理失败: ' + error)
+    } finally {
+      setReorganizing(false)
+    }
+  }
+
+  const handleFindOrphans = async () => {
+    setSweeping(true)
+    try {
+      setOrphans(await invoke<OrphanedFile[]>('find_orphaned_files'))
+    } catch (error) {
+      alert('查找遗留文件失败: ' + error)
+    } finally {
+      setSweeping(false)
+    }
+  }
+
+  // 导入可播放的遗留文件，或删除全部遗留文件，完成后重新查找
+  const handleOrphanAction = async (action: 'import' | 'delete') => {
+    if (!orphans) return
+    const targets = action === 'import' ? orphans.filter((file) => file.importable) : orphans
+    if (action === 'delete' && !confirm(`将永久删除 ${targets.length} 个文件，是否继续？`)) return
+
+    setSweeping(true)
+    try {
+      const result = await invoke<OrphanActionResult>(
+        action === 'import' ? 'import_orphaned_files' : 'delete_orphaned_files',
+        { paths: targets.map((file) => file.path) }
+      )
+      alert(`已${action === 'import' ? '导入' : '删除'} ${result.processed} 个文件（跳过 ${result.skipped} 个）`)
+      setOrphans(await invoke<OrphanedFile[]>('find_orphaned_files'))
+    } catch (error) {
+      alert('处理遗留文件失败: ' + error)
+    } finally {
+      setSweeping(false)
+    }
+  }
+
+  // 问题反馈时附上的诊断信息（版本、数据目录、FFmpeg、音频设备、磁盘空间等）
+  const handleCopyDiagnostics = async () => {
+    try {
+      const diagnostics = await invoke<Record<string, unknown>>('get_diagnostics')
+      await navigator.clipboard.writeText(JSON.stringify(diagnostics, null, 2))
+      alert('诊断信息已复制，可粘贴到问题反馈中')
+    } catch (error) {
+      alert('获取诊断信息失败: ' + error)
+    }
+  }
+
+  const loadSamplePacks = async () => {
+    try {
+      const packs = await invoke<SamplePackInfo[]>('get_sample_packs')
+      setSamplePacks(packs)
+    } catch (error) {
+      console.error('加载示例内容失败:', error)
+    }
+  }
+
+  const handleInstallPack = async (packId: string) => {
+    setInstallingPack(packId)
+    setPackProgress(0)
+    try {
+      const result = await invoke<{ imported: number; reused: number }>('install_sample_pack', { packId })
+      alert(`安装完成：新导入 ${result.imported} 首，复用 ${result.reused} 首，已创建播放列表`)
+      loadSamplePacks()
+    } catch (error) {
+      console.error('安装示例内容失败:', error)
+      alert('安装示例内容失败: ' + error)
+    } finally {
+      setInstallingPack(null)
+    }
+  }
 
   const handleSave = async () => {
     try {
@@ -91,6 +449,41 @@ export default function SettingsPage() {
               </label>
             </div>
 
+            <div className="flex items-center justify-between">
+              <div>
+                <p className="font-medium text-gray-800">托盘图标点击</p>
+                <p className="text-sm text-gray-500">单击、双击托盘图标时的动作（双击仅 Windows 支持，双击时也会触发一次单击）</p>
+              </div>
+              <div className="flex items-center gap-2 text-sm">
+                {(['click', 'double_click'] as const).map((key) => (
+                  <label key={key} className="flex items-center gap-1">
+                    <span>{key === 'click' ? '单击' : '双击'}</span>
+                    <select
+                      value={trayActions[key]}
+                      onChange={(e) => saveTrayActions({ ...trayActions, [key]: e.target.value as TrayAction })}
+                      className="px-2 py-1 border border-gray-300 rounded-lg"
+                    >
+                      {TRAY_ACTION_OPTIONS.map((option) => (
+                        <option key={option.value} value={option.value}>{option.label}</option>
+                      ))}
+                    </select>
+                  </label>
+                ))}
+                {(trayActions.click === 'run_default_task' || trayActions.double_click === 'run_default_task') && (
+                  <select
+                    value={trayActions.default_task_id ?? ''}
+                    onChange={(e) => saveTrayActions({ ...trayActions, default_task_id: parseInt(e.target.value) })}
+                    className="px-2 py-1 border border-gray-300 rounded-lg"
+                    title="默认任务"
+                  >
+                    {taskOptions.map((task) => (
+                      <option key={task.id} value={task.id}>{task.name}</option>
+                    ))}
+                  </select>
+                )}
+              </div>
+            </div>
+
             <div className="flex items-center justify-between">
               <div>
                 <p className="font-medium text-gray-800">最小化到系统托盘</p>
@@ -108,6 +501,30 @@ export default function SettingsPage() {
                 <div className="w-11 h-6 bg-gray-200 peer-focus:outline-none peer-focus:ring-4 peer-focus:ring-blue-300 rounded-full peer peer-checked:after:translate-x-full peer-checked:after:border-white after:content-[''] after:absolute after:top-[2px] after:left-[2px] after:bg-white after:border-gray-300 after:border after:rounded-full after:h-5 after:w-5 after:transition-all peer-checked:bg-blue-600"></div>
               </label>
             </div>
+
+            <div className="flex items-center justify-between">
+              <div>
+                <p className="font-medium text-gray-800">调度日志</p>
+                <p className="text-sm text-gray-500">记录每个任务执行或跳过的原因，用于排查任务为什么没有按时执行</p>
+              </div>
+              <div className="flex items-center gap-3">
+                <select
+                  value={traceLevel}
+                  onChange={(e) => saveTraceLevel(e.target.value as TraceLevel)}
+                  className="px-3 py-1 border border-gray-300 rounded-lg text-sm"
+                >
+                  <option value="off">关闭</option>
+                  <option value="decisions">到点的任务</option>
+                  <option value="verbose">详细（含未到时间）</option>
+                </select>
+                <button
+                  onClick={() => setShowTrace(true)}
+                  className="px-3 py-1 text-sm text-blue-600 border border-blue-200 rounded-lg hover:bg-blue-50"
+                >
+                  查看日志
+                </button>
+              </div>
+            </div>
           </div>
         </div>
 
@@ -158,6 +575,320 @@ export default function SettingsPage() {
                 当前存储位置：{settings.audio_path || '应用数据目录/audio'}
               </p>
             </div>
+
+            <div className="flex items-center justify-between">
+              <div>
+                <p className="font-medium text-gray-800">存储方式</p>
+                <p className="text-sm text-gray-500">导入、录音、提取的音频在存储路径下的存放方式，只影响之后导入的文件</p>
+              </div>
+              <div className="flex items-center gap-3 text-sm">
+                <select
+                  value={storageLayout.folders}
+                  onChange={(e) => saveStorageLayout({ ...storageLayout, folders: e.target.value as StorageLayout['folders'] })}
+                  className="px-3 py-1 border border-gray-300 rounded-lg"
+                >
+                  <option value="flat">不分文件夹</option>
+                  <option value="year_month">按年/月</option>
+                  <option value="source">按来源</option>
+                </select>
+                <label className="flex items-center gap-1">
+                  <input
+                    type="checkbox"
+                    checked={storageLayout.readable_names}
+                    onChange={(e) => saveStorageLayout({ ...storageLayout, readable_names: e.target.checked })}
+                  />
+                  <span>用名称作文件名</span>
+                </label>
+                <button
+                  onClick={handleReorganize}
+                  disabled={reorganizing}
+                  className="px-2 py-1 bg-gray-100 rounded hover:bg-gray-200 disabled:opacity-50"
+                >
+                  {reorganizing ? '整理中...' : '整理已有文件'}
+                </button>
+              </div>
+            </div>
+
+            <div>
+              <div className="flex items-center justify-between">
+                <div>
+                  <p className="font-medium text-gray-800">遗留文件</p>
+                  <p className="text-sm text-gray-500">存储路径中没有出现在音频库里的文件（导入中断、取消的录音等留下的）</p>
+                </div>
+                <div className="flex items-center gap-2 text-sm">
+                  <button
+                    onClick={handleFindOrphans}
+                    disabled={sweeping}
+                    className="px-2 py-1 bg-gray-100 rounded hover:bg-gray-200 disabled:opacity-50"
+                  >
+                    {sweeping ? '处理中...' : '查找'}
+                  </button>
+                  {orphans && orphans.some((file) => file.importable) && (
+                    <button
+                      onClick={() => handleOrphanAction('import')}
+                      disabled={sweeping}
+                      className="px-2 py-1 bg-blue-50 text-blue-700 rounded hover:bg-blue-100 disabled:opacity-50"
+                    >
+                      导入音频库
+                    </button>
+                  )}
+                  {orphans && orphans.length > 0 && (
+                    <button
+                      onClick={() => handleOrphanAction('delete')}
+                      disabled={sweeping}
+                      className="px-2 py-1 bg-red-50 text-red-700 rounded hover:bg-red-100 disabled:opacity-50"
+                    >
+                      全部删除
+                    </button>
+                  )}
+                </div>
+              </div>
+              {orphans && (
+                orphans.length === 0 ? (
+                  <p className="text-sm text-gray-500 mt-2">没有遗留文件</p>
+                ) : (
+                  <ul className="mt-2 max-h-40 overflow-auto text-xs text-gray-600 border border-gray-200 rounded-lg divide-y">
+                    {orphans.map((file) => (
+                      <li key={file.path} className="flex justify-between gap-2 px-2 py-1">
+                        <span className="truncate" title={file.path}>{file.path}</span>
+                        <span className="shrink-0 text-gray-400">
+                          {(file.size / 1024 / 1024).toFixed(1)} MB{file.importable ? '' : ' · 不可导入'}
+                        </span>
+                      </li>
+                    ))}
+                  </ul>
+                )
+              )}
+            </div>
+
+            <div className="flex items-center justify-between">
+              <div>
+                <p className="font-medium text-gray-800">导入时自动转码</p>
+                <p className="text-sm text-gray-500">无法直接播放的音频（如 OPUS、WMA、APE）导入时用 FFmpeg 转为 MP3</p>
+              </div>
+              <label className="relative inline-flex items-center cursor-pointer">
+                <input
+                  type="checkbox"
+                  checked={settings.auto_transcode}
+                  onChange={(e) =>
+                    setSettings({ ...settings, auto_transcode: e.target.checked })
+                  }
+                  className="sr-only peer"
+                />
+                <div className="w-11 h-6 bg-gray-200 peer-focus:outline-none peer-focus:ring-4 peer-focus:ring-blue-300 rounded-full peer peer-checked:after:translate-x-full peer-checked:after:border-white after:content-[''] after:absolute after:top-[2px] after:left-[2px] after:bg-white after:border-gray-300 after:border after:rounded-full after:h-5 after:w-5 after:transition-all peer-checked:bg-blue-600"></div>
+              </label>
+            </div>
+
+            <div className="flex items-center justify-between">
+              <div>
+                <p className="font-medium text-gray-800">提取时统一响度</p>
+                <p className="text-sm text-gray-500">从视频或在线视频提取音频时用 FFmpeg loudnorm 调整到一致的响度</p>
+              </div>
+              <label className="relative inline-flex items-center cursor-pointer">
+                <input
+                  type="checkbox"
+                  checked={settings.extract_loudnorm}
+                  onChange={(e) =>
+                    setSettings({ ...settings, extract_loudnorm: e.target.checked })
+                  }
+                  className="sr-only peer"
+                />
+                <div className="w-11 h-6 bg-gray-200 peer-focus:outline-none peer-focus:ring-4 peer-focus:ring-blue-300 rounded-full peer peer-checked:after:translate-x-full peer-checked:after:border-white after:content-[''] after:absolute after:top-[2px] after:left-[2px] after:bg-white after:border-gray-300 after:border after:rounded-full after:h-5 after:w-5 after:transition-all peer-checked:bg-blue-600"></div>
+              </label>
+            </div>
+
+            <div>
+              <div className="flex items-center justify-between mb-2">
+                <label className="font-medium text-gray-800">
+                  声道平衡: {channelMix.balance === 0 ? '居中' : channelMix.balance < 0 ? `偏左 ${Math.round(-channelMix.balance * 100)}%` : `偏右 ${Math.round(channelMix.balance * 100)}%`}
+                </label>
+                <label className="flex items-center gap-2 text-sm text-gray-700">
+                  <input
+                    type="checkbox"
+                    checked={channelMix.force_mono}
+                    onChange={(e) => saveChannelMix({ ...channelMix, force_mono: e.target.checked })}
+                  />
+                  强制单声道（只录到一个声道的录音也能从两侧播放）
+                </label>
+              </div>
+              <input
+                type="range"
+                min="-100"
+                max="100"
+                value={Math.round(channelMix.balance * 100)}
+                onChange={(e) => setChannelMix({ ...channelMix, balance: parseInt(e.target.value) / 100 })}
+                onMouseUp={() => saveChannelMix(channelMix)}
+                onKeyUp={() => saveChannelMix(channelMix)}
+                onDoubleClick={() => saveChannelMix({ ...channelMix, balance: 0 })}
+                className="w-full"
+                title="双击恢复居中"
+              />
+            </div>
+
+            <div className="flex items-center justify-between">
+              <div>
+                <p className="font-medium text-gray-800">音量均衡</p>
+                <p className="text-sm text-gray-500">后台分析每个音频的响度，播放时自动调整增益，让不同来源的音频音量一致</p>
+              </div>
+              <label className="relative inline-flex items-center cursor-pointer">
+                <input
+                  type="checkbox"
+                  checked={loudnessNormalization}
+                  onChange={(e) => toggleLoudnessNormalization(e.target.checked)}
+                  className="sr-only peer"
+                />
+                <div className="w-11 h-6 bg-gray-200 peer-focus:outline-none peer-focus:ring-4 peer-focus:ring-blue-300 rounded-full peer peer-checked:after:translate-x-full peer-checked:after:border-white after:content-[''] after:absolute after:top-[2px] after:left-[2px] after:bg-white after:border-gray-300 after:border after:rounded-full after:h-5 after:w-5 after:transition-all peer-checked:bg-blue-600"></div>
+              </label>
+            </div>
+
+            <div className="flex items-center justify-between">
+              <div>
+                <p className="font-medium text-gray-800">跳过首尾静音</p>
+                <p className="text-sm text-gray-500">后台分析每个音频开头和结尾的静音，播放时直接跳过，让播放列表衔接更紧凑</p>
+              </div>
+              <label className="relative inline-flex items-center cursor-pointer">
+                <input
+                  type="checkbox"
+                  checked={skipSilence}
+                  onChange={(e) => toggleSkipSilence(e.target.checked)}
+                  className="sr-only peer"
+                />
+                <div className="w-11 h-6 bg-gray-200 peer-focus:outline-none peer-focus:ring-4 peer-focus:ring-blue-300 rounded-full peer peer-checked:after:translate-x-full peer-checked:after:border-white after:content-[''] after:absolute after:top-[2px] after:left-[2px] after:bg-white after:border-gray-300 after:border after:rounded-full after:h-5 after:w-5 after:transition-all peer-checked:bg-blue-600"></div>
+              </label>
+            </div>
+
+            <div className="flex items-center justify-between">
+              <div>
+                <p className="font-medium text-gray-800">倍速步长</p>
+                <p className="text-sm text-gray-500">播放栏中“加快 / 放慢”每次调整的倍速</p>
+              </div>
+              <select
+                value={speedStep}
+                onChange={(e) => saveSpeedStep(parseFloat(e.target.value))}
+                className="px-3 py-1 border border-gray-300 rounded-lg text-sm"
+              >
+                {[0.05, 0.1, 0.25, 0.5].map((step) => (
+                  <option key={step} value={step}>{step}x</option>
+                ))}
+              </select>
+            </div>
+
+            <div className="flex items-center justify-between">
+              <div>
+                <p className="font-medium text-gray-800">通话时自动暂停</p>
+                <p className="text-sm text-gray-500">其他应用开始使用麦克风（网课、视频通话、会议）时暂停播放（不支持 macOS）</p>
+              </div>
+              <div className="flex items-center gap-4 text-sm text-gray-700">
+                <label className="flex items-center gap-2">
+                  <input
+                    type="checkbox"
+                    checked={capturePause.enabled}
+                    onChange={(e) => saveCapturePause({ ...capturePause, enabled: e.target.checked })}
+                  />
+                  自动暂停
+                </label>
+                <label className="flex items-center gap-2">
+                  <input
+                    type="checkbox"
+                    checked={capturePause.resume}
+                    disabled={!capturePause.enabled}
+                    onChange={(e) => saveCapturePause({ ...capturePause, resume: e.target.checked })}
+                  />
+                  结束后继续播放
+                </label>
+              </div>
+            </div>
+
+            <div className="flex items-center justify-between">
+              <div>
+                <p className="font-medium text-gray-800">任务交接时交叉淡入淡出</p>
+                <p className="text-sm text-gray-500">一个任务结束时紧接着开始另一个播放任务，两者按匹配的响度交叉淡入淡出，不再先停止再开始</p>
+              </div>
+              <div className="flex items-center gap-2 text-sm text-gray-700">
+                <input
+                  type="checkbox"
+                  checked={taskHandoff.enabled}
+                  onChange={(e) => saveTaskHandoff({ ...taskHandoff, enabled: e.target.checked })}
+                />
+                <input
+                  type="number"
+                  min="1"
+                  max="30"
+                  value={taskHandoff.crossfade_secs}
+                  disabled={!taskHandoff.enabled}
+                  onChange={(e) => setTaskHandoff({ ...taskHandoff, crossfade_secs: Number(e.target.value) })}
+                  onBlur={() => saveTaskHandoff(taskHandoff)}
+                  className="w-16 px-2 py-1 border border-gray-300 rounded"
+                />
+                <span>秒</span>
+              </div>
+            </div>
+
+            <div className="flex items-center justify-between">
+              <div>
+                <p className="font-medium text-gray-800">自动归档旧录音</p>
+                <p className="text-sm text-gray-500">超过保留期的录音移到归档目录（仍可播放），可选转为 MP3 节省空间</p>
+              </div>
+              <div className="flex items-center gap-2 text-sm text-gray-700">
+                <input
+                  type="checkbox"
+                  checked={archivePolicy.enabled}
+                  onChange={(e) => saveArchivePolicy({ ...archivePolicy, enabled: e.target.checked })}
+                />
+                <input
+                  type="number"
+                  min="1"
+                  value={archivePolicy.months}
+                  onChange={(e) => setArchivePolicy({ ...archivePolicy, months: Number(e.target.value) })}
+                  onBlur={() => saveArchivePolicy(archivePolicy)}
+                  className="w-16 px-2 py-1 border border-gray-300 rounded"
+                />
+                <span>个月</span>
+                <label className="flex items-center gap-1">
+                  <input
+                    type="checkbox"
+                    checked={archivePolicy.compress}
+                    onChange={(e) => saveArchivePolicy({ ...archivePolicy, compress: e.target.checked })}
+                  />
+                  <span>压缩</span>
+                </label>
+                <button
+                  onClick={handleArchiveNow}
+                  disabled={archiving}
+                  className="px-2 py-1 bg-gray-100 rounded hover:bg-gray-200 disabled:opacity-50"
+                >
+                  {archiving ? '归档中...' : '立即归档'}
+                </button>
+              </div>
+            </div>
+
+            <div className="flex items-center justify-between">
+              <div>
+                <p className="font-medium text-gray-800">播放次数统计规则</p>
+                <p className="text-sm text-gray-500">实际播放达到任一条件才计一次播放，两项都为 0 时开始播放即计数</p>
+              </div>
+              <div className="flex items-center gap-2 text-sm text-gray-700">
+                <input
+                  type="number"
+                  min="0"
+                  max="100"
+                  value={playCountRule.min_percent}
+                  onChange={(e) => setPlayCountRule({ ...playCountRule, min_percent: Number(e.target.value) })}
+                  onBlur={() => savePlayCountRule(playCountRule)}
+                  className="w-16 px-2 py-1 border border-gray-300 rounded"
+                />
+                <span>% 或</span>
+                <input
+                  type="number"
+                  min="0"
+                  value={playCountRule.min_secs}
+                  onChange={(e) => setPlayCountRule({ ...playCountRule, min_secs: Number(e.target.value) })}
+                  onBlur={() => savePlayCountRule(playCountRule)}
+                  className="w-16 px-2 py-1 border border-gray-300 rounded"
+                />
+                <span>秒</span>
+              </div>
+            </div>
           </div>
         </div>
 
@@ -206,6 +937,68 @@ export default function SettingsPage() {
               </button>
             </div>
           </div>
+
+          <div className="mt-6">
+            <label className="font-medium text-gray-800 mb-3 block">语言 / Language</label>
+            <select
+              value={settings.language}
+              onChange={(e) => saveSettings({ ...settings, language: e.target.value })}
+              className="px-4 py-2 border border-gray-300 rounded-lg focus:outline-none focus:ring-2 focus:ring-blue-500"
+            >
+              <option value="zh">中文</option>
+              <option value="en">English</option>
+            </select>
+            <p className="text-sm text-gray-500 mt-1">用于托盘菜单、系统通知和后端返回的提示信息</p>
+          </div>
+        </div>
+
+        {/* 录音模板 */}
+        <div className="bg-white rounded-lg p-6 shadow-sm border border-gray-200 mb-6">
+          <h3 className="text-lg font-semibold text-gray-800 mb-4">录音模板</h3>
+          <RecordingTemplates />
+        </div>
+
+        {/* 节假日日历 */}
+        <div className="bg-white rounded-lg p-6 shadow-sm border border-gray-200 mb-6">
+          <h3 className="text-lg font-semibold text-gray-800 mb-4">节假日日历</h3>
+          <HolidayCalendar />
+        </div>
+
+                {/* 儿童模式 */}
+        <div className="bg-white rounded-lg p-6 shadow-sm border border-gray-200 mb-6">
+          <h3 className="text-lg font-semibold text-gray-800 mb-4">儿童模式</h3>
+          <KioskSettings />
+        </div>
+
+        {/* 示例内容 */}
+        <div className="bg-white rounded-lg p-6 shadow-sm border border-gray-200 mb-6">
+          <h3 className="text-lg font-semibold text-gray-800 mb-4">示例内容</h3>
+
+          <div className="space-y-3">
+            {samplePacks.map((pack) => (
+              <div key={pack.id} className="flex items-center justify-between p-3 bg-gray-50 rounded-lg">
+                <div>
+                  <p className="font-medium text-gray-800">{pack.name}</p>
+                  <p className="text-sm text-gray-500">
+                    {pack.description}（{pack.track_count} 首，播放列表：{pack.playlist_names.join('、')}）
+                  </p>
+                </div>
+                <button
+                  onClick={() => handleInstallPack(pack.id)}
+                  disabled={installingPack !== null}
+                  className="px-4 py-2 bg-blue-600 text-white rounded-lg hover:bg-blue-700 transition-colors disabled:opacity-50 disabled:cursor-not-allowed whitespace-nowrap"
+                >
+                  {installingPack === pack.id
+                    ? `安装中 ${packProgress}%`
+                    : pack.installed_count >= pack.track_count
+                      ? '重新安装'
+                      : pack.installed_count > 0
+                        ? `补全（${pack.installed_count}/${pack.track_count}）`
+                        : '安装'}
+                </button>
+              </div>
+            ))}
+          </div>
         </div>
 
         {/* 数据管理 */}
@@ -259,10 +1052,18 @@ export default function SettingsPage() {
           </button>
         </div>
 
+        {showTrace && <SchedulerTraceDialog onClose={() => setShowTrace(false)} />}
+
         {/* 关于信息 */}
         <div className="mt-6 pt-6 border-t border-gray-200 text-center text-sm text-gray-500">
           <p>磨耳朵 v0.1.0</p>
           <p className="mt-1">开源免费跨平台定时音频播放软件</p>
+          <button
+            onClick={handleCopyDiagnostics}
+            className="mt-2 text-blue-600 hover:underline"
+          >
+            复制诊断信息
+          </button>
         </div>
       </div>
     </div>
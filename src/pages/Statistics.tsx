@@ -1,6 +1,7 @@
 import { useState, useEffect } from 'react'
-import { ChevronLeft, ChevronRight, Calendar as CalendarIcon } from 'lucide-react'
+import { ChevronLeft, ChevronRight, Calendar as CalendarIcon, Download } from 'lucide-react'
 import { invoke } from '@tauri-apps/api/tauri'
+import { save } from '@tauri-apps/api/dialog'
 
 interface MonthlyPlayback {
   date: string
@@ -13,15 +14,102 @@ interface PlaylistPlayInfo {
   audio_count: number
 }
 
+interface SessionTrack {
+  audio_id: number
+  audio_name: string
+  playlist_name: string | null
+  play_time: string
+  listened_secs: number
+}
+
+interface ListeningSession {
+  start_time: string
+  end_time: string
+  duration_secs: number
+  playlist_names: string[]
+  tracks: SessionTrack[]
+}
+
+interface PlannedTask {
+  task_id: number
+  task_name: string
+  scheduled_time: string
+  playlist_name: string | null
+  status: string
+  execution: { execution_time: string } | null
+}
+
+interface WeeklyTargetProgress {
+  playlist_id: number
+  playlist_name: string
+  target: number
+  completions: number
+}
+
+interface DaySchedule {
+  date: string
+  planned: PlannedTask[]
+}
+
+const TASK_STATUS_NAMES: Record<string, string> = {
+  pending: '待执行',
+  missed: '未执行',
+  started: '已执行',
+  completed: '已完成',
+  failed: '失败',
+}
+
 export default function Statistics() {
   const [currentDate, setCurrentDate] = useState(new Date())
   const [monthlyData, setMonthlyData] = useState<MonthlyPlayback[]>([])
   const [selectedDate, setSelectedDate] = useState<MonthlyPlayback | null>(null)
+  const [sessions, setSessions] = useState<ListeningSession[]>([])
+  const [daySchedule, setDaySchedule] = useState<DaySchedule | null>(null)
+  const [weeklyTargets, setWeeklyTargets] = useState<WeeklyTargetProgress[]>([])
+
+  useEffect(() => {
+    invoke<{ weekly_targets: WeeklyTargetProgress[] }>('get_statistics')
+      .then((stats) => setWeeklyTargets(stats.weekly_targets))
+      .catch((error) => console.error('加载每周目标失败:', error))
+  }, [])
 
   useEffect(() => {
     loadMonthlyData()
   }, [currentDate])
 
+  useEffect(() => {
+    if (selectedDate) {
+      loadSessions(selectedDate.date)
+      loadDaySchedule(selectedDate.date)
+    } else {
+      setSessions([])
+      setDaySchedule(null)
+    }
+  }, [selectedDate])
+
+  const loadSessions = async (date: string) => {
+    try {
+      const data = await invoke<ListeningSession[]>('get_sessions', { startDate: date, endDate: date })
+      setSessions(data)
+    } catch (error) {
+      console.error('加载收听时段失败:', error)
+      setSessions([])
+    }
+  }
+
+  const loadDaySchedule = async (date: string) => {
+    try {
+      setDaySchedule(await invoke<DaySchedule>('get_day_schedule', { date }))
+    } catch (error) {
+      console.error('加载当天计划失败:', error)
+      setDaySchedule(null)
+    }
+  }
+
+  const formatClock = (time: string) => time.slice(11, 16)
+
+  const formatMinutes = (secs: number) => `${Math.max(1, Math.round(secs / 60))} 分钟`
+
   const loadMonthlyData = async () => {
     try {
       const year = currentDate.getFullYear()
@@ -34,6 +122,29 @@ export default function Statistics() {
     }
   }
 
+  // 导出当前月份的执行记录和播放记录（Excel）
+  const handleExportMonth = async () => {
+    const year = currentDate.getFullYear()
+    const month = String(currentDate.getMonth() + 1).padStart(2, '0')
+    const lastDay = String(getDaysInMonth(currentDate)).padStart(2, '0')
+    try {
+      const path = await save({
+        defaultPath: `收听记录_${year}-${month}.xlsx`,
+        filters: [{ name: 'Excel', extensions: ['xlsx'] }],
+      })
+      if (!path) return
+      const savedPath = await invoke<string>('export_history_xlsx', {
+        startDate: `${year}-${month}-01`,
+        endDate: `${year}-${month}-${lastDay}`,
+        path,
+      })
+      alert(`收听记录已导出到: ${savedPath}`)
+    } catch (error) {
+      console.error('导出收听记录失败:', error)
+      alert('导出收听记录失败: ' + error)
+    }
+  }
+
   const getDaysInMonth = (date: Date) => {
     return new Date(date.getFullYear(), date.getMonth() + 1, 0).getDate()
   }
@@ -140,9 +251,42 @@ export default function Statistics() {
             >
               <ChevronRight size={20} />
             </button>
+            <button
+              onClick={handleExportMonth}
+              className="flex items-center gap-2 px-3 py-2 bg-white border border-gray-200 rounded-lg hover:bg-gray-100 transition-colors text-sm"
+              title="导出本月收听记录（Excel）"
+            >
+              <Download size={16} />
+              <span>导出 Excel</span>
+            </button>
           </div>
         </div>
 
+        {/* 本周目标：播放列表设置的每周完成遍数 */}
+        {weeklyTargets.length > 0 && (
+          <div className="mb-6 bg-white rounded-lg p-6 shadow-sm border border-gray-200">
+            <h3 className="text-lg font-bold text-gray-800 mb-4">本周目标</h3>
+            <div className="space-y-3">
+              {weeklyTargets.map((goal) => (
+                <div key={goal.playlist_id}>
+                  <div className="flex items-center justify-between text-sm mb-1">
+                    <span className="font-medium text-gray-800">{goal.playlist_name}</span>
+                    <span className={goal.completions >= goal.target ? 'text-green-600' : 'text-gray-600'}>
+                      {goal.completions >= goal.target ? '已完成 ' : ''}{goal.completions} / {goal.target} 遍
+                    </span>
+                  </div>
+                  <div className="h-2 bg-gray-100 rounded-full overflow-hidden">
+                    <div
+                      className={`h-full ${goal.completions >= goal.target ? 'bg-green-500' : 'bg-blue-500'}`}
+                      style={{ width: `${Math.min(100, (goal.completions / goal.target) * 100)}%` }}
+                    />
+                  </div>
+                </div>
+              ))}
+            </div>
+          </div>
+        )}
+
         <div className="bg-white rounded-lg p-6 shadow-sm border border-gray-200">
           {/* 星期标题 */}
           <div className="grid grid-cols-7 gap-2 mb-2">
@@ -192,6 +336,47 @@ export default function Statistics() {
                   </div>
                 ))}
               </div>
+
+              {daySchedule && daySchedule.planned.length > 0 && (
+                <div className="space-y-2">
+                  <h4 className="font-medium text-gray-700">计划任务：</h4>
+                  {daySchedule.planned.map((task) => (
+                    <div key={task.task_id} className="flex items-center justify-between p-3 bg-gray-50 rounded-lg">
+                      <div>
+                        <div className="font-medium text-gray-800">
+                          {task.scheduled_time} {task.task_name}
+                        </div>
+                        <div className="text-sm text-gray-500">{task.playlist_name || '播放列表已删除'}</div>
+                      </div>
+                      <span className={`text-sm ${task.status === 'missed' || task.status === 'failed' ? 'text-red-600' : 'text-gray-600'}`}>
+                        {TASK_STATUS_NAMES[task.status] || task.status}
+                        {task.execution && ` ${formatClock(task.execution.execution_time)}`}
+                      </span>
+                    </div>
+                  ))}
+                </div>
+              )}
+
+              {sessions.length > 0 && (
+                <div className="space-y-2">
+                  <h4 className="font-medium text-gray-700">收听时段：</h4>
+                  {sessions.map((session, idx) => (
+                    <div key={idx} className="p-3 bg-gray-50 rounded-lg">
+                      <div className="flex items-center justify-between mb-1">
+                        <span className="font-medium text-gray-800">
+                          {formatClock(session.start_time)} - {formatClock(session.end_time)}
+                        </span>
+                        <span className="text-sm text-gray-600">
+                          {formatMinutes(session.duration_secs)} · {session.tracks.length} 首
+                        </span>
+                      </div>
+                      <div className="text-sm text-gray-600 truncate">
+                        {session.tracks.map(track => track.audio_name).join('、')}
+                      </div>
+                    </div>
+                  ))}
+                </div>
+              )}
             </div>
           </div>
         )}
@@ -1,6 +1,8 @@
 import { useState, useEffect } from 'react'
-import { Plus, Trash2, Edit2, Clock, Power } from 'lucide-react'
+import { Plus, Trash2, Edit2, Clock, Power, RotateCcw, ShieldCheck, Copy } from 'lucide-react'
 import { invoke } from '@tauri-apps/api/tauri'
+import { listen } from '@tauri-apps/api/event'
+import { open, save } from '@tauri-apps/api/dialog'
 
 interface Task {
   id: number
@@ -14,11 +16,33 @@ interface Task {
   volume: number
   fade_in_duration: number
   duration_minutes: number | null
+  fade_out_duration: number
+  volume_curve: string | null
+  min_plays_per_track: number | null
+  play_order: string | null
+  weekday_playlists: string | null
+  weekend_makeup: boolean
+  critical: boolean
+  action: string
+  start_date: string | null
+  end_date: string | null
+  skip_holidays: boolean
   is_enabled: boolean
   priority: number
   created_date: string
 }
 
+interface DeletedEntry {
+  id: number
+  name: string
+  deleted_at: string
+}
+
+interface SchedulerPauseState {
+  paused: boolean
+  resume_at: string | null
+}
+
 interface Playlist {
   id: number
   name: string
@@ -31,15 +55,68 @@ interface TaskConflict {
   minute: number
 }
 
+interface ConflictSuggestion {
+  kind: 'move_later' | 'move_earlier' | 'trim'
+  hour: number
+  minute: number
+  duration_minutes: number
+  description: string
+}
+
+interface SimulatedExecution {
+  task_id: number
+  task_name: string
+  scheduled_time: string
+  start_time: string | null
+  end_time: string | null
+  playlist_id: number
+  playlist_name: string | null
+  duration_secs: number
+  status: 'run' | 'delayed' | 'missed' | 'paused' | 'skipped' | 'failed'
+  blocked_by: string | null
+}
+
+interface IcsImportResult {
+  imported: number
+  skipped: string[]
+}
+
+interface ConflictCheckResult {
+  conflicts: TaskConflict[]
+  suggestions: ConflictSuggestion[]
+}
+
+interface ClonedTask extends ConflictCheckResult {
+  id: number
+}
+
 export default function Tasks() {
   const [tasks, setTasks] = useState<Task[]>([])
   const [playlists, setPlaylists] = useState<Playlist[]>([])
   const [showDialog, setShowDialog] = useState(false)
   const [editingTask, setEditingTask] = useState<Task | null>(null)
   const [conflicts, setConflicts] = useState<TaskConflict[]>([])
+  const [suggestions, setSuggestions] = useState<ConflictSuggestion[]>([])
   const [showConflictDialog, setShowConflictDialog] = useState(false)
   const [showDeleteConfirm, setShowDeleteConfirm] = useState(false)
   const [taskToDelete, setTaskToDelete] = useState<number | null>(null)
+  const [pauseState, setPauseState] = useState<SchedulerPauseState>({ paused: false, resume_at: null })
+  const [deletedTasks, setDeletedTasks] = useState<DeletedEntry[]>([])
+  const [selectedTaskIds, setSelectedTaskIds] = useState<Set<number>>(new Set())
+  const [bulkChanges, setBulkChanges] = useState({ shiftMinutes: '', volume: '', playlistId: '' })
+  const [showBellDialog, setShowBellDialog] = useState(false)
+  const [icsImport, setIcsImport] = useState<{ path: string; playlistId: number } | null>(null)
+  const [simulation, setSimulation] = useState<SimulatedExecution[] | null>(null)
+  const [bellForm, setBellForm] = useState({
+    start_time: '08:00',
+    lesson_minutes: 40,
+    break_minutes: 10,
+    periods: 4,
+    start_playlist_id: 0,
+    end_playlist_id: 0,
+    lunch_after_period: 0,
+    lunch_minutes: 90,
+  })
 
   const [formData, setFormData] = useState({
     name: '',
@@ -47,27 +124,190 @@ export default function Tasks() {
     minute: 0,
     repeat_mode: 'weekday',
     custom_days: [] as number[],
+    cron_expression: '',
     playlist_id: 0,
     volume: 50,
     fade_in_duration: 30,
     duration_minutes: null as number | null,
+    fade_out_duration: 0,
+    volume_curve: null as string | null,
+    min_plays_per_track: null as number | null,
+    play_order: null as string | null,
+    weekday_playlists: {} as Record<string, number>,
+    weekend_makeup: false,
+    critical: false,
+    action: 'play',
+    start_date: '',
+    end_date: '',
+    skip_holidays: false,
     priority: 0,
   })
 
   useEffect(() => {
     loadTasks()
     loadPlaylists()
+    invoke<SchedulerPauseState>('get_scheduler_pause').then(setPauseState).catch(console.error)
+
+    const unlisten = listen<SchedulerPauseState>('scheduler-pause-changed', (event) => {
+      setPauseState(event.payload)
+    })
+    return () => {
+      unlisten.then(fn => fn())
+    }
   }, [])
 
+  const handlePauseScheduler = async (hours: number | null) => {
+    try {
+      await invoke('pause_scheduler', { hours })
+    } catch (error) {
+      console.error('暂停定时任务失败:', error)
+      alert('暂停定时任务失败: ' + error)
+    }
+  }
+
+  const handleResumeScheduler = async () => {
+    try {
+      await invoke('resume_scheduler')
+    } catch (error) {
+      console.error('恢复定时任务失败:', error)
+    }
+  }
+
   const loadTasks = async () => {
     try {
       const taskList = await invoke<Task[]>('get_scheduled_tasks')
       setTasks(taskList)
+      setDeletedTasks(await invoke<DeletedEntry[]>('get_deleted_tasks'))
     } catch (error) {
       console.error('加载定时任务失败:', error)
     }
   }
 
+  const toggleTaskSelection = (id: number) => {
+    const next = new Set(selectedTaskIds)
+    if (next.has(id)) {
+      next.delete(id)
+    } else {
+      next.add(id)
+    }
+    setSelectedTaskIds(next)
+  }
+
+  const handleBulkUpdate = async () => {
+    const changes = {
+      shift_minutes: bulkChanges.shiftMinutes ? parseInt(bulkChanges.shiftMinutes) : null,
+      volume: bulkChanges.volume ? parseInt(bulkChanges.volume) : null,
+      playlist_id: bulkChanges.playlistId ? parseInt(bulkChanges.playlistId) : null,
+    }
+    if (changes.shift_minutes === null && changes.volume === null && changes.playlist_id === null) {
+      alert('请至少填写一项要修改的内容')
+      return
+    }
+
+    try {
+      const count = await invoke<number>('bulk_update_tasks', { ids: Array.from(selectedTaskIds), changes })
+      alert(`已修改 ${count} 个任务`)
+      setSelectedTaskIds(new Set())
+      setBulkChanges({ shiftMinutes: '', volume: '', playlistId: '' })
+      loadTasks()
+    } catch (error) {
+      console.error('批量修改失败:', error)
+      alert('批量修改失败: ' + error)
+    }
+  }
+
+  const handleExportSchedule = async (format: 'csv' | 'html' | 'ics') => {
+    try {
+      const path = await save({
+        defaultPath: `播放时间表.${format}`,
+        filters: [{ name: format.toUpperCase(), extensions: [format] }],
+      })
+      if (!path) return
+      const savedPath = format === 'ics'
+        ? await invoke<string>('export_schedule_ics', { path })
+        : await invoke<string>('export_schedule', { format, path })
+      alert(`时间表已导出到: ${savedPath}`)
+    } catch (error) {
+      console.error('导出时间表失败:', error)
+      alert('导出时间表失败: ' + error)
+    }
+  }
+
+  const handleChooseIcs = async () => {
+    if (playlists.length === 0) {
+      alert('请先创建播放列表')
+      return
+    }
+    const selected = await open({
+      multiple: false,
+      filters: [{ name: '日历', extensions: ['ics'] }],
+    })
+    if (typeof selected === 'string') {
+      setIcsImport({ path: selected, playlistId: playlists[0].id })
+    }
+  }
+
+  const handleImportIcs = async () => {
+    if (!icsImport) return
+    try {
+      const result = await invoke<IcsImportResult>('import_schedule_ics', {
+        path: icsImport.path,
+        playlistId: icsImport.playlistId,
+      })
+      const skipped = result.skipped.length > 0
+        ? `\n跳过 ${result.skipped.length} 个无法转换的事件：${result.skipped.join('、')}`
+        : ''
+      alert(`已导入 ${result.imported} 个任务${skipped}`)
+      setIcsImport(null)
+      loadTasks()
+    } catch (error) {
+      console.error('导入日历失败:', error)
+      alert('导入日历失败: ' + error)
+    }
+  }
+
+  const handleGenerateBells = async () => {
+    if (!bellForm.start_playlist_id) {
+      alert('请选择上课铃播放列表')
+      return
+    }
+
+    try {
+      const created = await invoke<unknown[]>('generate_bell_schedule', {
+        timetable: {
+          ...bellForm,
+          end_playlist_id: bellForm.end_playlist_id || null,
+          lunch_after_period: bellForm.lunch_after_period || null,
+          lunch_minutes: bellForm.lunch_after_period ? bellForm.lunch_minutes : null,
+        },
+      })
+      alert(`已生成 ${created.length} 个铃声任务`)
+      setShowBellDialog(false)
+      loadTasks()
+    } catch (error) {
+      console.error('生成铃声任务失败:', error)
+      alert('生成铃声任务失败: ' + error)
+    }
+  }
+
+  const handleSimulate = async () => {
+    try {
+      setSimulation(await invoke<SimulatedExecution[]>('simulate_schedule'))
+    } catch (error) {
+      alert('模拟失败: ' + error)
+    }
+  }
+
+  const handleRestoreTask = async (id: number) => {
+    try {
+      await invoke('restore_task', { id })
+      loadTasks()
+    } catch (error) {
+      console.error('恢复任务失败:', error)
+      alert('恢复任务失败: ' + error)
+    }
+  }
+
   const loadPlaylists = async () => {
     try {
       const lists = await invoke<Playlist[]>('get_playlists')
@@ -80,6 +320,16 @@ export default function Tasks() {
     }
   }
 
+  const applySuggestion = (suggestion: ConflictSuggestion) => {
+    setFormData({
+      ...formData,
+      hour: suggestion.hour,
+      minute: suggestion.minute,
+      duration_minutes: suggestion.kind === 'trim' ? suggestion.duration_minutes : formData.duration_minutes,
+    })
+    setShowConflictDialog(false)
+  }
+
   const handleSaveTask = async () => {
     if (!formData.name.trim()) {
       alert('请输入任务名称')
@@ -94,23 +344,40 @@ export default function Tasks() {
     try {
       const customDaysStr = formData.repeat_mode === 'custom'
         ? JSON.stringify(formData.custom_days)
+        : formData.repeat_mode === 'cron'
+          ? formData.cron_expression
+          : null
+      const weekdayPlaylistsStr = Object.keys(formData.weekday_playlists).length > 0
+        ? JSON.stringify(formData.weekday_playlists)
+        : null
+      // 指定日期的任务只用开始日期；单次任务不限日期
+      const startDate = formData.repeat_mode !== 'once' && formData.start_date ? formData.start_date : null
+      const endDate = formData.repeat_mode !== 'once' && formData.repeat_mode !== 'date' && formData.end_date
+        ? formData.end_date
         : null
 
-      // 检查任务冲突
-      const conflictList = await invoke<TaskConflict[]>('check_task_conflicts', {
-        taskId: editingTask?.id || null,
-        hour: formData.hour,
-        minute: formData.minute,
-        repeatMode: formData.repeat_mode,
-        customDays: customDaysStr,
-        durationMinutes: formData.duration_minutes,
-        playlistId: formData.playlist_id,
-      })
+      // 检查任务冲突（停止、降低音量任务不占用时间，不检查）
+      if (formData.action === 'play') {
+        const conflictResult = await invoke<ConflictCheckResult>('check_task_conflicts', {
+          params: {
+            task_id: editingTask?.id || null,
+            hour: formData.hour,
+            minute: formData.minute,
+            repeat_mode: formData.repeat_mode,
+            custom_days: customDaysStr,
+            duration_minutes: formData.duration_minutes,
+            playlist_id: formData.playlist_id,
+            start_date: startDate,
+            end_date: endDate,
+          },
+        })
 
-      if (conflictList.length > 0) {
-        setConflicts(conflictList)
-        setShowConflictDialog(true)
-        return
+        if (conflictResult.conflicts.length > 0) {
+          setConflicts(conflictResult.conflicts)
+          setSuggestions(conflictResult.suggestions)
+          setShowConflictDialog(true)
+          return
+        }
       }
 
       // 没有冲突，保存任务
@@ -127,6 +394,17 @@ export default function Tasks() {
           fadeInDuration: formData.fade_in_duration,
           durationMinutes: formData.duration_minutes,
           priority: formData.priority,
+          volumeCurve: formData.volume_curve,
+          minPlaysPerTrack: formData.min_plays_per_track,
+          playOrder: formData.play_order,
+          weekdayPlaylists: weekdayPlaylistsStr,
+          weekendMakeup: formData.weekend_makeup,
+          critical: formData.critical,
+          fadeOutDuration: formData.fade_out_duration,
+          action: formData.action,
+          startDate,
+          endDate,
+          skipHolidays: formData.skip_holidays,
         })
       } else {
         await invoke('create_scheduled_task', {
@@ -140,6 +418,17 @@ export default function Tasks() {
           fadeInDuration: formData.fade_in_duration,
           durationMinutes: formData.duration_minutes,
           priority: formData.priority,
+          volumeCurve: formData.volume_curve,
+          minPlaysPerTrack: formData.min_plays_per_track,
+          playOrder: formData.play_order,
+          weekdayPlaylists: weekdayPlaylistsStr,
+          weekendMakeup: formData.weekend_makeup,
+          critical: formData.critical,
+          fadeOutDuration: formData.fade_out_duration,
+          action: formData.action,
+          startDate,
+          endDate,
+          skipHolidays: formData.skip_holidays,
         })
       }
 
@@ -160,6 +449,39 @@ export default function Tasks() {
     }
   }
 
+  const handlePreflight = async (id: number) => {
+    try {
+      const report = await invoke<{ problems: string[] }>('preflight_check_task', { taskId: id })
+      alert(report.problems.length === 0 ? '预检通过：播放列表、音频文件和输出设备均正常' : '预检发现问题：\n' + report.problems.join('\n'))
+    } catch (error) {
+      alert('预检失败: ' + error)
+    }
+  }
+
+  // 复制任务到另一个时间，复制后提示与其他任务的冲突
+  const handleCloneTask = async (task: Task) => {
+    const input = prompt(`将“${task.name}”复制到哪个时间？（如 12:30）`, '')
+    if (input === null) return
+    const match = input.trim().match(/^(\d{1,2})[:：](\d{1,2})$/)
+    if (!match) {
+      alert('请输入有效的时间，如 12:30')
+      return
+    }
+    try {
+      const result = await invoke<ClonedTask>('clone_task', { id: task.id, hour: parseInt(match[1]), minute: parseInt(match[2]) })
+      loadTasks()
+      if (result.conflicts.length > 0) {
+        const names = result.conflicts
+          .map((c) => `${c.task_name}（${c.hour.toString().padStart(2, '0')}:${c.minute.toString().padStart(2, '0')}）`)
+          .join('、')
+        const tips = result.suggestions.map((s) => s.description).join('；')
+        alert(`已复制，但与以下任务时间重叠：${names}${tips ? `\n建议：${tips}` : ''}`)
+      }
+    } catch (error) {
+      alert('复制任务失败: ' + error)
+    }
+  }
+
   const handleDeleteTask = (id: number) => {
     setTaskToDelete(id)
     setShowDeleteConfirm(true)
@@ -191,11 +513,23 @@ export default function Tasks() {
       hour: task.hour,
       minute: task.minute,
       repeat_mode: task.repeat_mode,
-      custom_days: task.custom_days ? JSON.parse(task.custom_days) : [],
+      custom_days: task.repeat_mode === 'custom' && task.custom_days ? JSON.parse(task.custom_days) : [],
+      cron_expression: task.repeat_mode === 'cron' ? task.custom_days || '' : '',
       playlist_id: task.playlist_id,
       volume: task.volume,
       fade_in_duration: task.fade_in_duration,
       duration_minutes: task.duration_minutes,
+      fade_out_duration: task.fade_out_duration,
+      volume_curve: task.volume_curve,
+      min_plays_per_track: task.min_plays_per_track,
+      play_order: task.play_order,
+      weekday_playlists: task.weekday_playlists ? JSON.parse(task.weekday_playlists) : {},
+      weekend_makeup: task.weekend_makeup,
+      critical: task.critical,
+      action: task.action,
+      start_date: task.start_date || '',
+      end_date: task.end_date || '',
+      skip_holidays: task.skip_holidays,
       priority: task.priority,
     })
     setShowDialog(true)
@@ -208,10 +542,22 @@ export default function Tasks() {
       minute: 0,
       repeat_mode: 'weekday',
       custom_days: [],
+      cron_expression: '',
       playlist_id: playlists.length > 0 ? playlists[0].id : 0,
       volume: 50,
       fade_in_duration: 30,
       duration_minutes: null,
+      fade_out_duration: 0,
+      volume_curve: null,
+      min_plays_per_track: null,
+      play_order: null,
+      weekday_playlists: {},
+      weekend_makeup: false,
+      critical: false,
+      action: 'play',
+      start_date: '',
+      end_date: '',
+      skip_holidays: false,
       priority: 0,
     })
     setEditingTask(null)
@@ -228,6 +574,10 @@ export default function Tasks() {
         return '周末'
       case 'once':
         return '仅一次'
+      case 'date':
+        return '指定日期'
+      case 'cron':
+        return `cron ${customDays || ''}`
       case 'custom':
         if (!customDays) return '自定义'
         const days = JSON.parse(customDays)
@@ -238,6 +588,15 @@ export default function Tasks() {
     }
   }
 
+  // 有效日期范围的说明，指定日期的任务显示那一天
+  const getDateRangeName = (task: Task) => {
+    if (task.repeat_mode === 'date') return task.start_date
+    if (task.start_date && task.end_date) return `${task.start_date} 至 ${task.end_date}`
+    if (task.start_date) return `${task.start_date} 起`
+    if (task.end_date) return `至 ${task.end_date}`
+    return null
+  }
+
   const formatTime = (hour: number, minute: number) => {
     return `${hour.toString().padStart(2, '0')}:${minute.toString().padStart(2, '0')}`
   }
@@ -249,16 +608,137 @@ export default function Tasks() {
       <div className="border-b border-gray-200 p-6">
         <div className="flex items-center justify-between mb-4">
           <h2 className="text-2xl font-bold text-gray-800">定时任务</h2>
+          <div className="flex items-center gap-2">
+            {pauseState.paused ? (
+              <button
+                onClick={handleResumeScheduler}
+                className="px-4 py-2 text-orange-700 bg-orange-100 rounded-lg hover:bg-orange-200 transition-colors"
+              >
+                恢复定时任务
+              </button>
+            ) : (
+              <select
+                value=""
+                onChange={(e) => {
+                  if (e.target.value) {
+                    handlePauseScheduler(e.target.value === 'manual' ? null : parseFloat(e.target.value))
+                  }
+                }}
+                className="px-3 py-2 border border-gray-300 rounded-lg text-gray-700"
+              >
+                <option value="">暂停全部…</option>
+                <option value="1">暂停 1 小时</option>
+                <option value="2">暂停 2 小时</option>
+                <option value="4">暂停 4 小时</option>
+                <option value="manual">暂停直到手动恢复</option>
+              </select>
+            )}
+            <select
+              value=""
+              onChange={(e) => {
+                if (e.target.value) handleExportSchedule(e.target.value as 'csv' | 'html' | 'ics')
+              }}
+              className="px-3 py-2 border border-gray-300 rounded-lg text-gray-700"
+            >
+              <option value="">导出时间表…</option>
+              <option value="html">打印版 (HTML)</option>
+              <option value="csv">表格 (CSV)</option>
+              <option value="ics">日历 (ICS)</option>
+            </select>
+            <button
+              onClick={handleChooseIcs}
+              className="px-4 py-2 border border-gray-300 rounded-lg text-gray-700 hover:bg-gray-50 transition-colors"
+            >
+              从日历导入
+            </button>
+            <button
+              onClick={handleSimulate}
+              className="px-4 py-2 border border-gray-300 rounded-lg text-gray-700 hover:bg-gray-50 transition-colors"
+            >
+              模拟一周
+            </button>
+            <button
+              onClick={() => setShowBellDialog(true)}
+              className="px-4 py-2 border border-gray-300 rounded-lg text-gray-700 hover:bg-gray-50 transition-colors"
+            >
+              生成作息铃声
+            </button>
+            <button
+              onClick={() => setShowDialog(true)}
+              className="flex items-center gap-2 px-4 py-2 bg-blue-600 text-white rounded-lg hover:bg-blue-700 transition-colors"
+            >
+              <Plus size={18} />
+              <span>新建任务</span>
+            </button>
+          </div>
+        </div>
+        {pauseState.paused && (
+          <p className="mb-2 text-orange-700">
+            ⏸ 所有定时任务已暂停{pauseState.resume_at ? `，将于 ${pauseState.resume_at} 自动恢复` : '，需手动恢复'}
+          </p>
+        )}
+        <p className="text-gray-600">共 {tasks.length} 个任务，{tasks.filter(t => t.is_enabled).length} 个已启用</p>
+        {deletedTasks.length > 0 && (
+          <div className="mt-2 flex flex-wrap items-center gap-2 text-sm text-gray-500">
+            <span>最近删除：</span>
+            {deletedTasks.map((task) => (
+              <button
+                key={task.id}
+                onClick={() => handleRestoreTask(task.id)}
+                className="flex items-center gap-1 px-2 py-0.5 bg-gray-100 hover:bg-gray-200 rounded transition-colors"
+                title={`删除于 ${task.deleted_at}，点击恢复`}
+              >
+                <RotateCcw size={12} />
+                <span>{task.name}</span>
+              </button>
+            ))}
+          </div>
+        )}
+      </div>
+
+      {selectedTaskIds.size > 0 && (
+        <div className="border-b border-gray-200 px-6 py-3 bg-gray-50 flex flex-wrap items-center gap-3 text-sm">
+          <span className="font-medium text-gray-700">已选 {selectedTaskIds.size} 个任务</span>
+          <input
+            type="number"
+            value={bulkChanges.shiftMinutes}
+            onChange={(e) => setBulkChanges({ ...bulkChanges, shiftMinutes: e.target.value })}
+            placeholder="时间平移(分钟，可为负)"
+            className="w-44 px-2 py-1 border border-gray-300 rounded"
+          />
+          <input
+            type="number"
+            min="0"
+            max="100"
+            value={bulkChanges.volume}
+            onChange={(e) => setBulkChanges({ ...bulkChanges, volume: e.target.value })}
+            placeholder="音量(0-100)"
+            className="w-28 px-2 py-1 border border-gray-300 rounded"
+          />
+          <select
+            value={bulkChanges.playlistId}
+            onChange={(e) => setBulkChanges({ ...bulkChanges, playlistId: e.target.value })}
+            className="px-2 py-1 border border-gray-300 rounded"
+          >
+            <option value="">播放列表不变</option>
+            {playlists.map((playlist) => (
+              <option key={playlist.id} value={playlist.id}>{playlist.name}</option>
+            ))}
+          </select>
+          <button
+            onClick={handleBulkUpdate}
+            className="px-3 py-1 bg-blue-600 text-white rounded hover:bg-blue-700 transition-colors"
+          >
+            应用
+          </button>
           <button
-            onClick={() => setShowDialog(true)}
-            className="flex items-center gap-2 px-4 py-2 bg-blue-600 text-white rounded-lg hover:bg-blue-700 transition-colors"
+            onClick={() => setSelectedTaskIds(new Set())}
+            className="px-3 py-1 text-gray-600 hover:bg-gray-200 rounded transition-colors"
           >
-            <Plus size={18} />
-            <span>新建任务</span>
+            取消选择
           </button>
         </div>
-        <p className="text-gray-600">共 {tasks.length} 个任务，{tasks.filter(t => t.is_enabled).length} 个已启用</p>
-      </div>
+      )}
 
       <div className="flex-1 overflow-auto p-6">
         {tasks.length === 0 ? (
@@ -280,8 +760,24 @@ export default function Tasks() {
               >
                 <div className="flex items-start justify-between mb-3">
                   <div className="flex-1">
-                    <h3 className="text-lg font-semibold text-gray-800 mb-1">
+                    <h3 className="text-lg font-semibold text-gray-800 mb-1 flex items-center gap-2">
+                      <input
+                        type="checkbox"
+                        checked={selectedTaskIds.has(task.id)}
+                        onChange={() => toggleTaskSelection(task.id)}
+                        title="选择以批量修改"
+                      />
                       {task.name}
+                      {task.critical && (
+                        <span className="px-2 py-0.5 text-xs bg-red-100 text-red-700 rounded" title="暂停期间照常执行">
+                          重要
+                        </span>
+                      )}
+                      {task.skip_holidays && (
+                        <span className="px-2 py-0.5 text-xs bg-green-100 text-green-700 rounded" title="节假日日历中的日子不执行">
+                          节假日跳过
+                        </span>
+                      )}
                     </h3>
                     <p className="text-3xl font-bold text-blue-600">
                       {formatTime(task.hour, task.minute)}
@@ -306,23 +802,49 @@ export default function Tasks() {
                   <div className="flex items-center gap-2">
                     <Clock size={14} />
                     <span>{getRepeatModeName(task.repeat_mode, task.custom_days)}</span>
+                    {getDateRangeName(task) && (
+                      <span className="text-gray-500">（{getDateRangeName(task)}）</span>
+                    )}
                   </div>
-                  <div>
-                    <span className="text-gray-500">播放列表:</span>{' '}
-                    <span className="font-medium">{task.playlist_name}</span>
-                  </div>
+                  {task.action === 'play' ? (
+                    <div>
+                      <span className="text-gray-500">播放列表:</span>{' '}
+                      <span className="font-medium">{task.playlist_name}</span>
+                    </div>
+                  ) : (
+                    <div>
+                      <span className="text-gray-500">动作:</span>{' '}
+                      <span className="font-medium">
+                        {task.action === 'stop' ? '停止播放' : `音量降至 ${task.volume}%`}
+                      </span>
+                    </div>
+                  )}
                   <div className="flex gap-4">
-                    <span>
-                      <span className="text-gray-500">音量:</span> {task.volume}%
-                    </span>
-                    <span>
-                      <span className="text-gray-500">渐强:</span> {task.fade_in_duration}秒
-                    </span>
+                    {task.action === 'play' && (
+                      <>
+                        <span>
+                          <span className="text-gray-500">音量:</span> {task.volume}%
+                        </span>
+                        <span>
+                          <span className="text-gray-500">渐强:</span> {task.fade_in_duration}秒
+                        </span>
+                      </>
+                    )}
+                    {task.fade_out_duration > 0 && (
+                      <span>
+                        <span className="text-gray-500">渐弱:</span> {task.fade_out_duration}秒
+                      </span>
+                    )}
                     {task.duration_minutes && (
                       <span>
                         <span className="text-gray-500">时长:</span> {task.duration_minutes}分钟
                       </span>
                     )}
+                    {task.min_plays_per_track && (
+                      <span>
+                        <span className="text-gray-500">每首:</span> {task.min_plays_per_track}遍
+                      </span>
+                    )}
                   </div>
                 </div>
 
@@ -334,6 +856,22 @@ export default function Tasks() {
                     <Edit2 size={14} />
                     <span>编辑</span>
                   </button>
+                  <button
+                    onClick={() => handlePreflight(task.id)}
+                    className="flex items-center gap-2 px-3 py-2 text-gray-700 bg-gray-100 rounded-lg hover:bg-gray-200 transition-colors"
+                    title="检查播放列表、音频文件和输出设备"
+                  >
+                    <ShieldCheck size={14} />
+                    <span>预检</span>
+                  </button>
+                  <button
+                    onClick={() => handleCloneTask(task)}
+                    className="flex items-center gap-2 px-3 py-2 text-gray-700 bg-gray-100 rounded-lg hover:bg-gray-200 transition-colors"
+                    title="以相同设置复制到另一个时间"
+                  >
+                    <Copy size={14} />
+                    <span>复制</span>
+                  </button>
                   <button
                     onClick={() => handleDeleteTask(task.id)}
                     className="flex items-center gap-2 px-3 py-2 text-red-600 bg-red-50 rounded-lg hover:bg-red-100 transition-colors"
@@ -370,6 +908,27 @@ export default function Tasks() {
                 />
               </div>
 
+              <div>
+                <label className="block text-sm font-medium text-gray-700 mb-1">
+                  任务类型
+                </label>
+                <select
+                  value={formData.action}
+                  onChange={(e) => setFormData({ ...formData, action: e.target.value })}
+                  className="w-full px-4 py-2 border border-gray-300 rounded-lg focus:outline-none focus:ring-2 focus:ring-blue-500"
+                >
+                  <option value="play">播放播放列表</option>
+                  <option value="stop">停止播放</option>
+                  <option value="lower_volume">降低音量</option>
+                </select>
+                {formData.action !== 'play' && (
+                  <p className="text-xs text-gray-500 mt-1">
+                    到时停止或调低正在进行的任何播放（包括其他定时任务），例如每晚 21:30 停止所有播放
+                  </p>
+                )}
+              </div>
+
+              {formData.repeat_mode !== 'cron' && (
               <div className="grid grid-cols-2 gap-4">
                 <div>
                   <label className="block text-sm font-medium text-gray-700 mb-1">
@@ -402,6 +961,7 @@ export default function Tasks() {
                   />
                 </div>
               </div>
+              )}
 
               <div>
                 <label className="block text-sm font-medium text-gray-700 mb-1">
@@ -419,9 +979,67 @@ export default function Tasks() {
                   <option value="weekend">周末（周六、周日）</option>
                   <option value="custom">自定义</option>
                   <option value="once">仅一次</option>
+                  <option value="date">指定日期</option>
+                  <option value="cron">cron 表达式（高级）</option>
                 </select>
               </div>
 
+              {formData.repeat_mode === 'cron' && (
+                <div>
+                  <label className="block text-sm font-medium text-gray-700 mb-1">
+                    cron 表达式
+                  </label>
+                  <input
+                    type="text"
+                    value={formData.cron_expression}
+                    onChange={(e) => setFormData({ ...formData, cron_expression: e.target.value })}
+                    placeholder="0 9-18/2 * * *"
+                    className="w-full px-4 py-2 border border-gray-300 rounded-lg font-mono focus:outline-none focus:ring-2 focus:ring-blue-500"
+                  />
+                  <p className="text-xs text-gray-500 mt-1">
+                    分 时 日 月 星期（0 和 7 为周日），支持 *、范围 a-b、步长 */n 和逗号列表；
+                    例如 0 9-18/2 * * * 表示每天 9:00 到 18:00 每两小时一次
+                  </p>
+                </div>
+              )}
+
+              {formData.repeat_mode === 'date' && (
+                <div>
+                  <label className="block text-sm font-medium text-gray-700 mb-1">
+                    执行日期
+                  </label>
+                  <input
+                    type="date"
+                    value={formData.start_date}
+                    onChange={(e) => setFormData({ ...formData, start_date: e.target.value })}
+                    className="w-full px-4 py-2 border border-gray-300 rounded-lg focus:outline-none focus:ring-2 focus:ring-blue-500"
+                  />
+                </div>
+              )}
+
+              {formData.repeat_mode !== 'date' && formData.repeat_mode !== 'once' && (
+                <div>
+                  <label className="block text-sm font-medium text-gray-700 mb-1">
+                    有效日期（可选，如只在暑假期间执行）
+                  </label>
+                  <div className="flex items-center gap-2">
+                    <input
+                      type="date"
+                      value={formData.start_date}
+                      onChange={(e) => setFormData({ ...formData, start_date: e.target.value })}
+                      className="flex-1 px-4 py-2 border border-gray-300 rounded-lg focus:outline-none focus:ring-2 focus:ring-blue-500"
+                    />
+                    <span className="text-gray-500">至</span>
+                    <input
+                      type="date"
+                      value={formData.end_date}
+                      onChange={(e) => setFormData({ ...formData, end_date: e.target.value })}
+                      className="flex-1 px-4 py-2 border border-gray-300 rounded-lg focus:outline-none focus:ring-2 focus:ring-blue-500"
+                    />
+                  </div>
+                </div>
+              )}
+
               {formData.repeat_mode === 'custom' && (
                 <div>
                   <label className="block text-sm font-medium text-gray-700 mb-2">
@@ -454,81 +1072,231 @@ export default function Tasks() {
                 </div>
               )}
 
-              <div>
-                <label className="block text-sm font-medium text-gray-700 mb-1">
-                  播放列表
-                </label>
-                <select
-                  value={formData.playlist_id}
-                  onChange={(e) =>
-                    setFormData({ ...formData, playlist_id: parseInt(e.target.value) })
-                  }
-                  className="w-full px-4 py-2 border border-gray-300 rounded-lg focus:outline-none focus:ring-2 focus:ring-blue-500"
-                >
-                  {playlists.map((playlist) => (
-                    <option key={playlist.id} value={playlist.id}>
-                      {playlist.name}
-                    </option>
-                  ))}
-                </select>
-              </div>
+              {formData.action === 'play' && (
+                <div>
+                  <label className="block text-sm font-medium text-gray-700 mb-1">
+                    播放列表
+                  </label>
+                  <select
+                    value={formData.playlist_id}
+                    onChange={(e) =>
+                      setFormData({ ...formData, playlist_id: parseInt(e.target.value) })
+                    }
+                    className="w-full px-4 py-2 border border-gray-300 rounded-lg focus:outline-none focus:ring-2 focus:ring-blue-500"
+                  >
+                    {playlists.map((playlist) => (
+                      <option key={playlist.id} value={playlist.id}>
+                        {playlist.name}
+                      </option>
+                    ))}
+                  </select>
+                </div>
+              )}
 
-              <div>
-                <label className="block text-sm font-medium text-gray-700 mb-1">
-                  音量: {formData.volume}%
-                </label>
-                <input
-                  type="range"
-                  min="0"
-                  max="100"
-                  value={formData.volume}
-                  onChange={(e) =>
-                    setFormData({ ...formData, volume: parseInt(e.target.value) })
-                  }
-                  className="w-full"
-                />
-              </div>
+              {formData.action !== 'stop' && (
+                <div>
+                  <label className="block text-sm font-medium text-gray-700 mb-1">
+                    {formData.action === 'play' ? '音量' : '降至音量'}: {formData.volume}%
+                  </label>
+                  <input
+                    type="range"
+                    min="0"
+                    max="100"
+                    value={formData.volume}
+                    onChange={(e) =>
+                      setFormData({ ...formData, volume: parseInt(e.target.value) })
+                    }
+                    className="w-full"
+                  />
+                </div>
+              )}
+
+              {formData.action === 'play' && (
+                <div>
+                  <label className="block text-sm font-medium text-gray-700 mb-1">
+                    渐强时长: {formData.fade_in_duration}秒
+                  </label>
+                  <input
+                    type="range"
+                    min="0"
+                    max="300"
+                    step="10"
+                    value={formData.fade_in_duration}
+                    onChange={(e) =>
+                      setFormData({
+                        ...formData,
+                        fade_in_duration: parseInt(e.target.value),
+                      })
+                    }
+                    className="w-full"
+                  />
+                </div>
+              )}
 
               <div>
                 <label className="block text-sm font-medium text-gray-700 mb-1">
-                  渐强时长: {formData.fade_in_duration}秒
+                  {formData.action === 'play' ? '结束前渐弱' : '渐变时长'}: {formData.fade_out_duration ? `${formData.fade_out_duration}秒` : '不渐变'}
                 </label>
                 <input
                   type="range"
                   min="0"
-                  max="300"
-                  step="10"
-                  value={formData.fade_in_duration}
+                  max="120"
+                  step="5"
+                  value={formData.fade_out_duration}
                   onChange={(e) =>
                     setFormData({
                       ...formData,
-                      fade_in_duration: parseInt(e.target.value),
+                      fade_out_duration: parseInt(e.target.value),
                     })
                   }
                   className="w-full"
                 />
+                <p className="text-xs text-gray-500 mt-1">
+                  {formData.action === 'play'
+                    ? '播放时长用完或最后一首快播完时逐渐减小音量，避免突然中断'
+                    : '在这段时间内逐渐减小音量，而不是立即停止或调低'}
+                </p>
               </div>
 
+              {formData.action === 'play' && (
+                <>
+                  <div>
+                    <label className="block text-sm font-medium text-gray-700 mb-1">
+                      播放时长（留空表示播放全部）: {formData.duration_minutes ? `${formData.duration_minutes}分钟` : '播放全部'}
+                    </label>
+                    <input
+                      type="number"
+                      min="1"
+                      max="480"
+                      value={formData.duration_minutes || ''}
+                      onChange={(e) =>
+                        setFormData({
+                          ...formData,
+                          duration_minutes: e.target.value ? parseInt(e.target.value) : null,
+                        })
+                      }
+                      placeholder="留空表示播放列表全部音频"
+                      className="w-full px-4 py-2 border border-gray-300 rounded-lg focus:outline-none focus:ring-2 focus:ring-blue-500"
+                    />
+                    <p className="text-xs text-gray-500 mt-1">
+                      设置播放时长可以避免长时间播放影响下一个任务
+                    </p>
+                  </div>
+
+                  <div>
+                    <label className="block text-sm font-medium text-gray-700 mb-1">
+                      按星期使用不同播放列表（可选）
+                    </label>
+                    <div className="grid grid-cols-2 gap-2">
+                      {dayNames.map((dayName, day) => (
+                        <div key={day} className="flex items-center gap-2 text-sm">
+                          <span className="w-10 text-gray-600">{dayName}</span>
+                          <select
+                            value={formData.weekday_playlists[day] ?? ''}
+                            onChange={(e) => {
+                              const mapping = { ...formData.weekday_playlists }
+                              if (e.target.value) {
+                                mapping[day] = parseInt(e.target.value)
+                              } else {
+                                delete mapping[day]
+                              }
+                              setFormData({ ...formData, weekday_playlists: mapping })
+                            }}
+                            className="flex-1 px-2 py-1 border border-gray-300 rounded"
+                          >
+                            <option value="">默认播放列表</option>
+                            {playlists.map((playlist) => (
+                              <option key={playlist.id} value={playlist.id}>{playlist.name}</option>
+                            ))}
+                          </select>
+                        </div>
+                      ))}
+                    </div>
+                  </div>
+
+                  <div>
+                    <label className="block text-sm font-medium text-gray-700 mb-1">
+                      播放顺序
+                    </label>
+                    <select
+                      value={formData.play_order || ''}
+                      onChange={(e) =>
+                        setFormData({ ...formData, play_order: e.target.value || null })
+                      }
+                      className="w-full px-4 py-2 border border-gray-300 rounded-lg focus:outline-none focus:ring-2 focus:ring-blue-500"
+                    >
+                      <option value="">沿用播放列表设置</option>
+                      <option value="sequential">顺序播放</option>
+                      <option value="shuffle">随机播放</option>
+                      <option value="reverse">倒序播放</option>
+                      <option value="newest_first">最新添加优先</option>
+                    </select>
+                  </div>
+
+                  <div>
+                    <label className="block text-sm font-medium text-gray-700 mb-1">
+                      每首最少播放次数（留空表示播放一遍）: {formData.min_plays_per_track ? `${formData.min_plays_per_track}遍` : '一遍'}
+                    </label>
+                    <input
+                      type="number"
+                      min="1"
+                      max="20"
+                      value={formData.min_plays_per_track || ''}
+                      onChange={(e) =>
+                        setFormData({
+                          ...formData,
+                          min_plays_per_track: e.target.value ? parseInt(e.target.value) : null,
+                        })
+                      }
+                      placeholder="留空表示播放列表只播放一遍"
+                      className="w-full px-4 py-2 border border-gray-300 rounded-lg focus:outline-none focus:ring-2 focus:ring-blue-500"
+                    />
+                    <p className="text-xs text-gray-500 mt-1">
+                      会扣除今天已播放的次数，循环播放直到每首音频都达到设定次数
+                    </p>
+                  </div>
+
+                  <div>
+                    <label className="flex items-center gap-2 text-sm font-medium text-gray-700">
+                      <input
+                        type="checkbox"
+                        checked={formData.weekend_makeup}
+                        onChange={(e) => setFormData({ ...formData, weekend_makeup: e.target.checked })}
+                      />
+                      周末补播
+                    </label>
+                    <p className="text-xs text-gray-500 mt-1">
+                      本周工作日因未开机错过时，周六上午（9-12点）自动补播一次
+                    </p>
+                  </div>
+                </>
+              )}
+
               <div>
-                <label className="block text-sm font-medium text-gray-700 mb-1">
-                  播放时长（留空表示播放全部）: {formData.duration_minutes ? `${formData.duration_minutes}分钟` : '播放全部'}
+                <label className="flex items-center gap-2 text-sm font-medium text-gray-700">
+                  <input
+                    type="checkbox"
+                    checked={formData.critical}
+                    onChange={(e) => setFormData({ ...formData, critical: e.target.checked })}
+                  />
+                  重要任务
                 </label>
-                <input
-                  type="number"
-                  min="1"
-                  max="480"
-                  value={formData.duration_minutes || ''}
-                  onChange={(e) =>
-                    setFormData({
-                      ...formData,
-                      duration_minutes: e.target.value ? parseInt(e.target.value) : null,
-                    })
-                  }
-                  placeholder="留空表示播放列表全部音频"
-                  className="w-full px-4 py-2 border border-gray-300 rounded-lg focus:outline-none focus:ring-2 focus:ring-blue-500"
-                />
                 <p className="text-xs text-gray-500 mt-1">
-                  设置播放时长可以避免长时间播放影响下一个任务
+                  如吃药提醒：暂停定时任务期间照常执行，播放时自动取消静音；同一时间先于普通任务执行
+                </p>
+              </div>
+
+              <div>
+                <label className="flex items-center gap-2 text-sm font-medium text-gray-700">
+                  <input
+                    type="checkbox"
+                    checked={formData.skip_holidays}
+                    onChange={(e) => setFormData({ ...formData, skip_holidays: e.target.checked })}
+                  />
+                  节假日不执行
+                </label>
+                <p className="text-xs text-gray-500 mt-1">
+                  如工作日的起床播放：设置中节假日日历里的日子跳过
                 </p>
               </div>
             </div>
@@ -570,6 +1338,22 @@ export default function Tasks() {
                   </li>
                 ))}
               </ul>
+              {suggestions.length > 0 && (
+                <div className="mt-4">
+                  <p className="text-gray-700 mb-2">建议的调整方式：</p>
+                  <div className="flex flex-wrap gap-2">
+                    {suggestions.map((suggestion) => (
+                      <button
+                        key={suggestion.kind}
+                        onClick={() => applySuggestion(suggestion)}
+                        className="px-3 py-1.5 bg-blue-50 text-blue-700 border border-blue-200 rounded-lg hover:bg-blue-100 transition-colors text-sm"
+                      >
+                        {suggestion.description}
+                      </button>
+                    ))}
+                  </div>
+                </div>
+              )}
               <p className="text-gray-600 mt-4 text-sm">
                 请修改当前任务的时间或播放时长，或者调整冲突任务的时间设置。
               </p>
@@ -586,12 +1370,228 @@ export default function Tasks() {
         </div>
       )}
 
+      {/* 模拟执行结果 */}
+      {simulation && (
+        <div className="fixed inset-0 bg-black bg-opacity-50 flex items-center justify-center z-50">
+          <div className="bg-white rounded-lg p-6 w-[760px] max-h-[85vh] flex flex-col">
+            <h3 className="text-xl font-bold mb-1 text-gray-800">未来一周的执行模拟</h3>
+            <p className="text-sm text-gray-500 mb-4">按调度器规则推算，不会实际播放</p>
+            <div className="flex-1 overflow-y-auto">
+              {simulation.length === 0 ? (
+                <p className="text-gray-500">未来一周没有要执行的任务</p>
+              ) : (
+                <table className="w-full text-sm">
+                  <thead>
+                    <tr className="text-left text-gray-500 border-b">
+                      <th className="py-2">计划时间</th>
+                      <th>任务</th>
+                      <th>播放列表</th>
+                      <th>实际时间</th>
+                      <th>状态</th>
+                    </tr>
+                  </thead>
+                  <tbody>
+                    {simulation.map((item, index) => (
+                      <tr key={index} className="border-b border-gray-100">
+                        <td className="py-2 whitespace-nowrap">{item.scheduled_time.slice(0, 16)}</td>
+                        <td>{item.task_name}</td>
+                        <td>{item.playlist_name ?? '-'}</td>
+                        <td className="whitespace-nowrap">
+                          {item.start_time ? `${item.start_time.slice(11, 16)} - ${item.end_time?.slice(11, 16)}` : '-'}
+                        </td>
+                        <td className={{
+                          run: 'text-green-600',
+                          delayed: 'text-orange-600',
+                          missed: 'text-red-600',
+                          paused: 'text-gray-500',
+                          skipped: 'text-gray-500',
+                          failed: 'text-red-600',
+                        }[item.status]}>
+                          {{
+                            run: '准时执行',
+                            delayed: `延迟（等待 ${item.blocked_by}）`,
+                            missed: `错过（${item.blocked_by} 仍在播放）`,
+                            paused: '已暂停',
+                            skipped: '今天已执行',
+                            failed: '播放列表为空或已删除',
+                          }[item.status]}
+                        </td>
+                      </tr>
+                    ))}
+                  </tbody>
+                </table>
+              )}
+            </div>
+            <div className="flex justify-end mt-4">
+              <button
+                onClick={() => setSimulation(null)}
+                className="px-4 py-2 bg-gray-100 text-gray-700 rounded-lg hover:bg-gray-200 transition-colors"
+              >
+                关闭
+              </button>
+            </div>
+          </div>
+        </div>
+      )}
+
+      {/* 作息铃声生成对话框 */}
+      {showBellDialog && (
+        <div className="fixed inset-0 bg-black bg-opacity-50 flex items-center justify-center z-50">
+          <div className="bg-white rounded-lg p-6 w-[480px]">
+            <h3 className="text-xl font-bold mb-4 text-gray-800">生成作息铃声</h3>
+            <div className="grid grid-cols-2 gap-3 text-sm">
+              <label className="text-gray-700">
+                第一节上课时间
+                <input
+                  type="time"
+                  value={bellForm.start_time}
+                  onChange={(e) => setBellForm({ ...bellForm, start_time: e.target.value })}
+                  className="w-full mt-1 px-3 py-2 border border-gray-300 rounded-lg"
+                />
+              </label>
+              <label className="text-gray-700">
+                节数
+                <input
+                  type="number"
+                  min="1"
+                  max="20"
+                  value={bellForm.periods}
+                  onChange={(e) => setBellForm({ ...bellForm, periods: parseInt(e.target.value) || 1 })}
+                  className="w-full mt-1 px-3 py-2 border border-gray-300 rounded-lg"
+                />
+              </label>
+              <label className="text-gray-700">
+                每节时长(分钟)
+                <input
+                  type="number"
+                  min="1"
+                  value={bellForm.lesson_minutes}
+                  onChange={(e) => setBellForm({ ...bellForm, lesson_minutes: parseInt(e.target.value) || 1 })}
+                  className="w-full mt-1 px-3 py-2 border border-gray-300 rounded-lg"
+                />
+              </label>
+              <label className="text-gray-700">
+                课间(分钟)
+                <input
+                  type="number"
+                  min="0"
+                  value={bellForm.break_minutes}
+                  onChange={(e) => setBellForm({ ...bellForm, break_minutes: parseInt(e.target.value) || 0 })}
+                  className="w-full mt-1 px-3 py-2 border border-gray-300 rounded-lg"
+                />
+              </label>
+              <label className="text-gray-700">
+                午休在第几节后(0=无)
+                <input
+                  type="number"
+                  min="0"
+                  value={bellForm.lunch_after_period}
+                  onChange={(e) => setBellForm({ ...bellForm, lunch_after_period: parseInt(e.target.value) || 0 })}
+                  className="w-full mt-1 px-3 py-2 border border-gray-300 rounded-lg"
+                />
+              </label>
+              <label className="text-gray-700">
+                午休(分钟)
+                <input
+                  type="number"
+                  min="0"
+                  value={bellForm.lunch_minutes}
+                  onChange={(e) => setBellForm({ ...bellForm, lunch_minutes: parseInt(e.target.value) || 0 })}
+                  className="w-full mt-1 px-3 py-2 border border-gray-300 rounded-lg"
+                />
+              </label>
+              <label className="text-gray-700">
+                上课铃
+                <select
+                  value={bellForm.start_playlist_id}
+                  onChange={(e) => setBellForm({ ...bellForm, start_playlist_id: parseInt(e.target.value) })}
+                  className="w-full mt-1 px-3 py-2 border border-gray-300 rounded-lg"
+                >
+                  <option value={0}>请选择播放列表</option>
+                  {playlists.map((playlist) => (
+                    <option key={playlist.id} value={playlist.id}>{playlist.name}</option>
+                  ))}
+                </select>
+              </label>
+              <label className="text-gray-700">
+                下课铃
+                <select
+                  value={bellForm.end_playlist_id}
+                  onChange={(e) => setBellForm({ ...bellForm, end_playlist_id: parseInt(e.target.value) })}
+                  className="w-full mt-1 px-3 py-2 border border-gray-300 rounded-lg"
+                >
+                  <option value={0}>与上课铃相同</option>
+                  {playlists.map((playlist) => (
+                    <option key={playlist.id} value={playlist.id}>{playlist.name}</option>
+                  ))}
+                </select>
+              </label>
+            </div>
+            <p className="text-xs text-gray-500 mt-3">将按工作日重复生成每节课的上课铃和下课铃任务。</p>
+            <div className="flex justify-end gap-2 mt-6">
+              <button
+                onClick={() => setShowBellDialog(false)}
+                className="px-4 py-2 text-gray-600 hover:bg-gray-100 rounded-lg transition-colors"
+              >
+                取消
+              </button>
+              <button
+                onClick={handleGenerateBells}
+                className="px-4 py-2 bg-blue-600 text-white rounded-lg hover:bg-blue-700 transition-colors"
+              >
+                生成
+              </button>
+            </div>
+          </div>
+        </div>
+      )}
+
+      {/* 日历导入对话框 */}
+      {icsImport && (
+        <div className="fixed inset-0 bg-black bg-opacity-50 flex items-center justify-center z-50">
+          <div className="bg-white rounded-lg p-6 w-[480px]">
+            <h3 className="text-xl font-bold mb-4 text-gray-800">从日历导入</h3>
+            <p className="text-sm text-gray-600 mb-3 break-all">{icsImport.path}</p>
+            <label className="block text-sm text-gray-700">
+              导入的任务播放
+              <select
+                value={icsImport.playlistId}
+                onChange={(e) => setIcsImport({ ...icsImport, playlistId: parseInt(e.target.value) })}
+                className="w-full mt-1 px-3 py-2 border border-gray-300 rounded-lg"
+              >
+                {playlists.map((playlist) => (
+                  <option key={playlist.id} value={playlist.id}>{playlist.name}</option>
+                ))}
+              </select>
+            </label>
+            <p className="text-xs text-gray-500 mt-3">
+              每天或每周按星期重复的事件导入为重复任务，24 小时内的单次事件导入为单次任务，其余事件跳过。
+              从本软件导出的日历会还原原来的播放列表。
+            </p>
+            <div className="flex justify-end gap-2 mt-6">
+              <button
+                onClick={() => setIcsImport(null)}
+                className="px-4 py-2 text-gray-600 hover:bg-gray-100 rounded-lg transition-colors"
+              >
+                取消
+              </button>
+              <button
+                onClick={handleImportIcs}
+                className="px-4 py-2 bg-blue-600 text-white rounded-lg hover:bg-blue-700 transition-colors"
+              >
+                导入
+              </button>
+            </div>
+          </div>
+        </div>
+      )}
+
       {/* 删除确认对话框 */}
       {showDeleteConfirm && (
         <div className="fixed inset-0 bg-black bg-opacity-50 flex items-center justify-center z-50">
           <div className="bg-white rounded-lg p-6 w-[480px]">
             <h3 className="text-xl font-bold mb-4 text-gray-800">确认删除</h3>
-            <p className="text-gray-600 mb-6">确定要删除该定时任务吗？此操作无法撤销。</p>
+            <p className="text-gray-600 mb-6">确定要删除该定时任务吗？删除后 30 天内可在“最近删除”中恢复。</p>
             <div className="flex justify-end gap-2">
               <button
                 onClick={cancelDeleteTask}